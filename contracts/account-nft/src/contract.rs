@@ -4,15 +4,20 @@ use cosmwasm_std::{
     to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
 };
 use cw2::set_contract_version;
-use cw721_base::Cw721Contract;
+use cw721_base::{Cw721Contract, ExecuteMsg as ParentExecuteMsg};
 use mars_types::account_nft::{ExecuteMsg, InstantiateMsg, NftConfig, QueryMsg};
 
 use crate::{
     error::ContractError,
     execute::{burn, mint, update_config},
+    metadata::{query_all_nft_info, query_nft_info, set_label},
     migrations,
     query::{query_config, query_next_id},
-    state::{CONFIG, NEXT_ID},
+    recovery::{
+        cancel_recovery, clear_recovery, complete_recovery, initiate_recovery, query_recovery,
+        remove_backup_owner, set_backup_owner,
+    },
+    state::{CONFIG, LABELS, NEXT_ID},
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -68,6 +73,53 @@ pub fn execute(
         ExecuteMsg::Burn {
             token_id,
         } => burn(deps, env, info, token_id),
+        ExecuteMsg::SetBackupOwner {
+            token_id,
+            backup_owner,
+            recovery_delay,
+        } => set_backup_owner(deps, info, token_id, backup_owner, recovery_delay),
+        ExecuteMsg::RemoveBackupOwner {
+            token_id,
+        } => remove_backup_owner(deps, info, token_id),
+        ExecuteMsg::InitiateRecovery {
+            token_id,
+        } => initiate_recovery(deps, env, info, token_id),
+        ExecuteMsg::CancelRecovery {
+            token_id,
+        } => cancel_recovery(deps, info, token_id),
+        ExecuteMsg::CompleteRecovery {
+            token_id,
+        } => complete_recovery(deps, env, info, token_id),
+        ExecuteMsg::SetLabel {
+            token_id,
+            label,
+        } => set_label(deps, info, token_id, label),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => {
+            clear_recovery(deps.storage, &token_id);
+            LABELS.remove(deps.storage, &token_id);
+            let msg = ParentExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            };
+            Parent::default().execute(deps, env, info, msg).map_err(Into::into)
+        }
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => {
+            clear_recovery(deps.storage, &token_id);
+            LABELS.remove(deps.storage, &token_id);
+            let msg = ParentExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            };
+            Parent::default().execute(deps, env, info, msg).map_err(Into::into)
+        }
         _ => Parent::default().execute(deps, env, info, msg.try_into()?).map_err(Into::into),
     }
 }
@@ -77,6 +129,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::NextId {} => to_json_binary(&query_next_id(deps)?),
+        QueryMsg::Recovery {
+            token_id,
+        } => to_json_binary(&query_recovery(deps, token_id)?),
+        QueryMsg::NftInfo {
+            token_id,
+        } => to_json_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_json_binary(&query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
         _ => Parent::default().query(deps, env, msg.try_into()?),
     }
 }
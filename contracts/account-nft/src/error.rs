@@ -26,4 +26,51 @@ pub enum ContractError {
 
     #[error("{0}")]
     Version(#[from] cw2::VersionError),
+
+    #[error("Token {token_id} has no backup owner")]
+    NoBackupOwner {
+        token_id: String,
+    },
+
+    #[error("{user} is not the backup owner of token {token_id}")]
+    NotBackupOwner {
+        user: String,
+        token_id: String,
+    },
+
+    #[error("Backup owner cannot be the token owner")]
+    BackupOwnerIsOwner,
+
+    #[error("Recovery delay must be at least {min} seconds")]
+    RecoveryDelayTooShort {
+        min: u64,
+    },
+
+    #[error("Recovery delay must be at most {max} seconds")]
+    RecoveryDelayTooLong {
+        max: u64,
+    },
+
+    #[error("Recovery of token {token_id} already initiated")]
+    RecoveryAlreadyInitiated {
+        token_id: String,
+    },
+
+    #[error("Token {token_id} has no pending recovery")]
+    NoPendingRecovery {
+        token_id: String,
+    },
+
+    #[error("Recovery can be completed from {completable_at}")]
+    RecoveryDelayNotPassed {
+        completable_at: u64,
+    },
+
+    #[error("Label cannot be empty")]
+    EmptyLabel,
+
+    #[error("Label cannot be longer than {max} characters")]
+    LabelTooLong {
+        max: usize,
+    },
 }
@@ -1,24 +1,19 @@
-use cosmwasm_std::{
-    to_json_binary, DepsMut, Empty, Env, MessageInfo, QueryRequest, Response, WasmQuery,
-};
+use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Response};
 use cw721::Cw721Execute;
 use cw721_base::{
     ContractError::Ownership,
     OwnershipError::{NoOwner, NotOwner},
 };
-use mars_types::{
-    account_nft::NftConfigUpdates,
-    credit_manager::QueryMsg,
-    health::{AccountKind, HealthValuesResponse, QueryMsg::HealthValues},
-    oracle::ActionKind,
-};
+use mars_types::account_nft::NftConfigUpdates;
 
 use crate::{
     contract::Parent,
     error::ContractError::{
         self, BaseError, BurnNotAllowed, CreditManagerContractNotSet, HealthContractNotSet,
     },
-    state::{CONFIG, NEXT_ID},
+    metadata::{query_account_kind, query_health_values},
+    recovery::clear_recovery,
+    state::{CONFIG, LABELS, NEXT_ID},
 };
 
 pub fn mint(deps: DepsMut, info: MessageInfo, user: &str) -> Result<Response, ContractError> {
@@ -46,22 +41,9 @@ pub fn burn(
         return Err(CreditManagerContractNotSet);
     };
 
-    let acc_kind: AccountKind = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: cm_contract_addr.into(),
-        msg: to_json_binary(&QueryMsg::AccountKind {
-            account_id: token_id.clone(),
-        })?,
-    }))?;
-
-    let response: HealthValuesResponse =
-        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: health_contract_addr.into(),
-            msg: to_json_binary(&HealthValues {
-                account_id: token_id.clone(),
-                kind: acc_kind,
-                action: ActionKind::Default,
-            })?,
-        }))?;
+    let acc_kind = query_account_kind(deps.as_ref(), cm_contract_addr.as_str(), &token_id)?;
+    let response =
+        query_health_values(deps.as_ref(), health_contract_addr.as_str(), &token_id, &acc_kind)?;
 
     if !response.total_debt_value.is_zero() {
         return Err(BurnNotAllowed {
@@ -78,6 +60,9 @@ pub fn burn(
         });
     }
 
+    clear_recovery(deps.storage, &token_id);
+    LABELS.remove(deps.storage, &token_id);
+
     Parent::default().burn(deps, env, info, token_id).map_err(Into::into)
 }
 
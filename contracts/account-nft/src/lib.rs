@@ -1,6 +1,8 @@
 pub mod contract;
 pub mod error;
 pub mod execute;
+pub mod metadata;
 pub mod migrations;
 pub mod query;
+pub mod recovery;
 pub mod state;
@@ -0,0 +1,141 @@
+use cosmwasm_std::{
+    to_json_binary, Deps, DepsMut, Env, Int128, Int256, MessageInfo, QueryRequest, Response,
+    StdResult, WasmQuery,
+};
+use cw721::{AllNftInfoResponse, Cw721Query, NftInfoResponse};
+use mars_types::{
+    account_nft::AccountMetadata,
+    credit_manager::QueryMsg as CmQueryMsg,
+    health::{AccountKind, HealthValuesResponse, QueryMsg::HealthValues},
+    oracle::ActionKind,
+};
+
+use crate::{
+    contract::Parent,
+    error::ContractError::{self, EmptyLabel, LabelTooLong},
+    recovery::assert_token_owner,
+    state::{CONFIG, LABELS},
+};
+
+/// Keeps labels short enough to be displayed in wallets next to the token id
+pub const MAX_LABEL_LENGTH: usize = 32;
+
+pub fn set_label(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    label: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_token_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    let response = Response::new()
+        .add_attribute("action", "set_label")
+        .add_attribute("token_id", token_id.clone());
+
+    let Some(label) = label else {
+        LABELS.remove(deps.storage, &token_id);
+        return Ok(response);
+    };
+
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(EmptyLabel);
+    }
+    // Counted in chars rather than bytes so emojis take a single slot
+    if label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(LabelTooLong {
+            max: MAX_LABEL_LENGTH,
+        });
+    }
+    LABELS.save(deps.storage, &token_id, &label.to_string())?;
+
+    Ok(response.add_attribute("label", label))
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse<AccountMetadata>> {
+    let info = Parent::default().nft_info(deps, token_id.clone())?;
+    Ok(NftInfoResponse {
+        token_uri: info.token_uri,
+        extension: query_account_metadata(deps, &token_id)?,
+    })
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse<AccountMetadata>> {
+    let res = Parent::default().all_nft_info(deps, env, token_id.clone(), include_expired)?;
+    Ok(AllNftInfoResponse {
+        access: res.access,
+        info: NftInfoResponse {
+            token_uri: res.info.token_uri,
+            extension: query_account_metadata(deps, &token_id)?,
+        },
+    })
+}
+
+/// Wallets query the metadata of every token they display, so a missing contract or a failing
+/// query leaves the related fields empty instead of failing the whole query
+fn query_account_metadata(deps: Deps, token_id: &str) -> StdResult<AccountMetadata> {
+    let config = CONFIG.load(deps.storage)?;
+    let label = LABELS.may_load(deps.storage, token_id)?;
+
+    let account_kind = config
+        .credit_manager_contract_addr
+        .and_then(|addr| query_account_kind(deps, addr.as_str(), token_id).ok());
+
+    let health = match (config.health_contract_addr, &account_kind) {
+        (Some(addr), Some(kind)) => query_health_values(deps, addr.as_str(), token_id, kind).ok(),
+        _ => None,
+    };
+
+    let Some(health) = health else {
+        return Ok(AccountMetadata {
+            label,
+            account_kind,
+            ..Default::default()
+        });
+    };
+
+    let net_value =
+        Int256::from(health.total_collateral_value) - Int256::from(health.total_debt_value);
+
+    Ok(AccountMetadata {
+        label,
+        account_kind,
+        net_value: Int128::try_from(net_value).ok(),
+        max_ltv_health_factor: health.max_ltv_health_factor,
+        liquidation_health_factor: health.liquidation_health_factor,
+    })
+}
+
+pub fn query_account_kind(
+    deps: Deps,
+    cm_contract_addr: &str,
+    token_id: &str,
+) -> StdResult<AccountKind> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: cm_contract_addr.to_string(),
+        msg: to_json_binary(&CmQueryMsg::AccountKind {
+            account_id: token_id.to_string(),
+        })?,
+    }))
+}
+
+pub fn query_health_values(
+    deps: Deps,
+    health_contract_addr: &str,
+    token_id: &str,
+    kind: &AccountKind,
+) -> StdResult<HealthValuesResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: health_contract_addr.to_string(),
+        msg: to_json_binary(&HealthValues {
+            account_id: token_id.to_string(),
+            kind: kind.clone(),
+            action: ActionKind::Default,
+        })?,
+    }))
+}
@@ -0,0 +1,208 @@
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation, Response, StdResult,
+    Storage,
+};
+use cw721_base::{ContractError::Ownership, OwnershipError::NotOwner};
+use mars_types::account_nft::{BackupOwner, PendingRecovery, RecoveryResponse};
+
+use crate::{
+    contract::Parent,
+    error::ContractError::{
+        self, BackupOwnerIsOwner, BaseError, NoBackupOwner, NoPendingRecovery, NotBackupOwner,
+        RecoveryAlreadyInitiated, RecoveryDelayNotPassed, RecoveryDelayTooLong,
+        RecoveryDelayTooShort,
+    },
+    state::{BACKUP_OWNERS, PENDING_RECOVERIES},
+};
+
+/// Gives the owner at least a day to notice and cancel an unexpected recovery
+pub const MIN_RECOVERY_DELAY: u64 = 24 * 60 * 60;
+
+/// A year is plenty for any recovery, a longer delay would effectively disable it
+pub const MAX_RECOVERY_DELAY: u64 = 365 * 24 * 60 * 60;
+
+pub fn set_backup_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    backup_owner: String,
+    recovery_delay: u64,
+) -> Result<Response, ContractError> {
+    assert_token_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    let address = deps.api.addr_validate(&backup_owner)?;
+    if address == info.sender {
+        return Err(BackupOwnerIsOwner);
+    }
+
+    if recovery_delay < MIN_RECOVERY_DELAY {
+        return Err(RecoveryDelayTooShort {
+            min: MIN_RECOVERY_DELAY,
+        });
+    }
+    if recovery_delay > MAX_RECOVERY_DELAY {
+        return Err(RecoveryDelayTooLong {
+            max: MAX_RECOVERY_DELAY,
+        });
+    }
+
+    BACKUP_OWNERS.save(
+        deps.storage,
+        &token_id,
+        &BackupOwner {
+            address: address.clone(),
+            recovery_delay,
+        },
+    )?;
+    PENDING_RECOVERIES.remove(deps.storage, &token_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "set_backup_owner")
+        .add_attribute("token_id", token_id)
+        .add_attribute("backup_owner", address)
+        .add_attribute("recovery_delay", recovery_delay.to_string()))
+}
+
+pub fn remove_backup_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    assert_token_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    clear_recovery(deps.storage, &token_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_backup_owner")
+        .add_attribute("token_id", token_id))
+}
+
+pub fn initiate_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let backup_owner = load_backup_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    if PENDING_RECOVERIES.has(deps.storage, &token_id) {
+        return Err(RecoveryAlreadyInitiated {
+            token_id,
+        });
+    }
+
+    let now = env.block.time.seconds();
+    let completable_at = now.checked_add(backup_owner.recovery_delay).ok_or_else(|| {
+        OverflowError::new(OverflowOperation::Add, now, backup_owner.recovery_delay)
+    })?;
+    PENDING_RECOVERIES.save(
+        deps.storage,
+        &token_id,
+        &PendingRecovery {
+            initiated_at: now,
+            completable_at,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "initiate_recovery")
+        .add_attribute("token_id", token_id)
+        .add_attribute("backup_owner", backup_owner.address)
+        .add_attribute("completable_at", completable_at.to_string()))
+}
+
+pub fn cancel_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    assert_token_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    if !PENDING_RECOVERIES.has(deps.storage, &token_id) {
+        return Err(NoPendingRecovery {
+            token_id,
+        });
+    }
+    PENDING_RECOVERIES.remove(deps.storage, &token_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_recovery")
+        .add_attribute("token_id", token_id))
+}
+
+pub fn complete_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let backup_owner = load_backup_owner(deps.as_ref(), &info.sender, &token_id)?;
+
+    let Some(pending) = PENDING_RECOVERIES.may_load(deps.storage, &token_id)? else {
+        return Err(NoPendingRecovery {
+            token_id,
+        });
+    };
+    if env.block.time.seconds() < pending.completable_at {
+        return Err(RecoveryDelayNotPassed {
+            completable_at: pending.completable_at,
+        });
+    }
+
+    // Moved without the cw721 approval checks, previous approvals are dropped like on a regular transfer
+    let parent = Parent::default();
+    let mut token = parent.tokens.load(deps.storage, &token_id)?;
+    let previous_owner = token.owner;
+    token.owner = backup_owner.address.clone();
+    token.approvals = vec![];
+    parent.tokens.save(deps.storage, &token_id, &token)?;
+
+    clear_recovery(deps.storage, &token_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "complete_recovery")
+        .add_attribute("token_id", token_id)
+        .add_attribute("previous_owner", previous_owner)
+        .add_attribute("owner", backup_owner.address))
+}
+
+/// Backup owner and pending recovery are bound to the current owner and don't survive a transfer or burn
+pub fn clear_recovery(storage: &mut dyn Storage, token_id: &str) {
+    BACKUP_OWNERS.remove(storage, token_id);
+    PENDING_RECOVERIES.remove(storage, token_id);
+}
+
+pub fn query_recovery(deps: Deps, token_id: String) -> StdResult<RecoveryResponse> {
+    Ok(RecoveryResponse {
+        backup_owner: BACKUP_OWNERS.may_load(deps.storage, &token_id)?,
+        pending_recovery: PENDING_RECOVERIES.may_load(deps.storage, &token_id)?,
+        token_id,
+    })
+}
+
+pub fn assert_token_owner(deps: Deps, sender: &Addr, token_id: &str) -> Result<(), ContractError> {
+    let token = Parent::default().tokens.load(deps.storage, token_id)?;
+    if token.owner != *sender {
+        return Err(BaseError(Ownership(NotOwner)));
+    }
+    Ok(())
+}
+
+fn load_backup_owner(
+    deps: Deps,
+    sender: &Addr,
+    token_id: &str,
+) -> Result<BackupOwner, ContractError> {
+    let Some(backup_owner) = BACKUP_OWNERS.may_load(deps.storage, token_id)? else {
+        return Err(NoBackupOwner {
+            token_id: token_id.to_string(),
+        });
+    };
+    if backup_owner.address != *sender {
+        return Err(NotBackupOwner {
+            user: sender.to_string(),
+            token_id: token_id.to_string(),
+        });
+    }
+    Ok(backup_owner)
+}
@@ -1,10 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cw_storage_plus::Item;
-use mars_types::account_nft::NftConfig;
+use cw_storage_plus::{Item, Map};
+use mars_types::account_nft::{BackupOwner, NftConfig, PendingRecovery};
 
 pub const CONFIG: Item<NftConfig> = Item::new("config");
 pub const NEXT_ID: Item<u64> = Item::new("next_id");
 
+pub const BACKUP_OWNERS: Map<&str, BackupOwner> = Map::new("backup_owners");
+pub const PENDING_RECOVERIES: Map<&str, PendingRecovery> = Map::new("pending_recoveries");
+
+pub const LABELS: Map<&str, String> = Map::new("labels");
+
 /// Helper marker used during burning empty accounts. Used only for v1 -> v2 migration.
 #[cw_serde]
 pub enum BurningMarker {
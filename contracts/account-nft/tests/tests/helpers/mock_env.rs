@@ -1,6 +1,6 @@
 use anyhow::Result as AnyResult;
 use cosmwasm_std::{Addr, Empty};
-use cw721::OwnerOfResponse;
+use cw721::{AllNftInfoResponse, NftInfoResponse, OwnerOfResponse};
 use cw721_base::{
     Action::{AcceptOwnership, TransferOwnership},
     ExecuteMsg::UpdateOwnership,
@@ -11,7 +11,8 @@ use mars_mock_credit_manager::msg::ExecuteMsg::SetAccountKindResponse;
 use mars_mock_rover_health::msg::ExecuteMsg::SetHealthResponse;
 use mars_types::{
     account_nft::{
-        ExecuteMsg, ExecuteMsg::UpdateConfig, NftConfigUpdates, QueryMsg, UncheckedNftConfig,
+        AccountMetadata, ExecuteMsg, ExecuteMsg::UpdateConfig, NftConfigUpdates, QueryMsg,
+        RecoveryResponse, UncheckedNftConfig,
     },
     health::{AccountKind, HealthValuesResponse},
 };
@@ -153,6 +154,147 @@ impl MockEnv {
         )
     }
 
+    pub fn transfer_nft(
+        &mut self,
+        sender: &Addr,
+        recipient: &Addr,
+        token_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn set_backup_owner(
+        &mut self,
+        sender: &Addr,
+        token_id: &str,
+        backup_owner: &Addr,
+        recovery_delay: u64,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::SetBackupOwner {
+                token_id: token_id.to_string(),
+                backup_owner: backup_owner.to_string(),
+                recovery_delay,
+            },
+            &[],
+        )
+    }
+
+    pub fn remove_backup_owner(&mut self, sender: &Addr, token_id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::RemoveBackupOwner {
+                token_id: token_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn initiate_recovery(&mut self, sender: &Addr, token_id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::InitiateRecovery {
+                token_id: token_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn cancel_recovery(&mut self, sender: &Addr, token_id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::CancelRecovery {
+                token_id: token_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn complete_recovery(&mut self, sender: &Addr, token_id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::CompleteRecovery {
+                token_id: token_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_recovery(&mut self, token_id: &str) -> RecoveryResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.nft_contract.clone(),
+                &QueryMsg::Recovery {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn set_label(
+        &mut self,
+        sender: &Addr,
+        token_id: &str,
+        label: Option<&str>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.nft_contract.clone(),
+            &ExecuteMsg::SetLabel {
+                token_id: token_id.to_string(),
+                label: label.map(Into::into),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_nft_info(&mut self, token_id: &str) -> NftInfoResponse<AccountMetadata> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.nft_contract.clone(),
+                &QueryMsg::NftInfo {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_all_nft_info(&mut self, token_id: &str) -> AllNftInfoResponse<AccountMetadata> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.nft_contract.clone(),
+                &QueryMsg::AllNftInfo {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn increment_by_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += 1;
+        });
+    }
+
     pub fn propose_new_minter(
         &mut self,
         sender: &Addr,
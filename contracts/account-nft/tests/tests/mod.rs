@@ -2,7 +2,9 @@ mod helpers;
 
 mod test_burn_allowance;
 mod test_instantiate;
+mod test_labels;
 mod test_migration_v2;
 mod test_mint;
 mod test_proposed_minter;
+mod test_recovery;
 mod test_update_config;
@@ -1,13 +1,16 @@
 use std::ops::Add;
 
-use cosmwasm_std::{Addr, Empty, StdResult, Uint128};
+use cosmwasm_std::{Addr, StdResult, Uint128};
 use cw721::NftInfoResponse;
 use cw721_base::{ContractError::Ownership, OwnershipError::NotOwner};
 use mars_account_nft::error::{
     ContractError,
     ContractError::{BaseError, BurnNotAllowed, HealthContractNotSet},
 };
-use mars_types::{account_nft::QueryMsg::NftInfo, health::AccountKind};
+use mars_types::{
+    account_nft::{AccountMetadata, QueryMsg::NftInfo},
+    health::AccountKind,
+};
 
 use super::helpers::{below_max_for_burn, generate_health_response, MockEnv, MAX_VALUE_FOR_BURN};
 
@@ -113,7 +116,7 @@ fn burn_allowance_when_under_max() {
     );
 
     // Assert no errors on calling for NftInfo
-    let _: NftInfoResponse<Empty> = mock
+    let _: NftInfoResponse<AccountMetadata> = mock
         .app
         .wrap()
         .query_wasm_smart(
@@ -127,7 +130,7 @@ fn burn_allowance_when_under_max() {
     mock.set_health_response(&user, &token_id, AccountKind::Default, &below_max_for_burn());
     mock.burn(&user, &token_id).unwrap();
 
-    let res: StdResult<NftInfoResponse<Empty>> = mock.app.wrap().query_wasm_smart(
+    let res: StdResult<NftInfoResponse<AccountMetadata>> = mock.app.wrap().query_wasm_smart(
         mock.nft_contract,
         &NftInfo {
             token_id,
@@ -0,0 +1,135 @@
+use cosmwasm_std::{Addr, Decimal, Int128};
+use cw721_base::{ContractError::Ownership, OwnershipError::NotOwner};
+use mars_account_nft::{
+    error::{
+        ContractError,
+        ContractError::{BaseError, EmptyLabel, LabelTooLong},
+    },
+    metadata::MAX_LABEL_LENGTH,
+};
+use mars_types::{account_nft::AccountMetadata, health::AccountKind};
+
+use super::helpers::{generate_health_response, MockEnv};
+
+#[test]
+fn only_token_owner_can_set_label() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let token_id = mock.mint(&user).unwrap();
+
+    let bad_guy = Addr::unchecked("bad_guy");
+    let res = mock.set_label(&bad_guy, &token_id, Some("mine"));
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, BaseError(Ownership(NotOwner)));
+
+    mock.set_label(&user, &token_id, Some(" 🚀 leverage ")).unwrap();
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(res.extension.label, Some("🚀 leverage".to_string()));
+
+    mock.set_label(&user, &token_id, None).unwrap();
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(res.extension.label, None);
+}
+
+#[test]
+fn label_length_is_validated() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let token_id = mock.mint(&user).unwrap();
+
+    let res = mock.set_label(&user, &token_id, Some("  "));
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, EmptyLabel);
+
+    let too_long = "a".repeat(MAX_LABEL_LENGTH + 1);
+    let res = mock.set_label(&user, &token_id, Some(&too_long));
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        LabelTooLong {
+            max: MAX_LABEL_LENGTH
+        }
+    );
+
+    // Multi-byte characters count once
+    let emojis = "🌕".repeat(MAX_LABEL_LENGTH);
+    mock.set_label(&user, &token_id, Some(&emojis)).unwrap();
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(res.extension.label, Some(emojis));
+}
+
+#[test]
+fn label_cleared_on_transfer() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let token_id = mock.mint(&user).unwrap();
+
+    mock.set_label(&user, &token_id, Some("savings")).unwrap();
+
+    let buyer = Addr::unchecked("buyer");
+    mock.transfer_nft(&user, &buyer, &token_id).unwrap();
+
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(res.extension.label, None);
+}
+
+#[test]
+fn nft_info_returns_account_metadata() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let token_id = mock.mint(&user).unwrap();
+
+    mock.set_label(&user, &token_id, Some("degen")).unwrap();
+    mock.set_account_kind_response(&user, &token_id, AccountKind::HighLeveredStrategy);
+
+    let mut health = generate_health_response(2_500, 1_000);
+    health.max_ltv_health_factor = Some(Decimal::percent(30));
+    health.liquidation_health_factor = Some(Decimal::percent(35));
+    mock.set_health_response(&user, &token_id, AccountKind::HighLeveredStrategy, &health);
+
+    let expected = AccountMetadata {
+        label: Some("degen".to_string()),
+        account_kind: Some(AccountKind::HighLeveredStrategy),
+        net_value: Some(Int128::new(-1_500)),
+        max_ltv_health_factor: Some(Decimal::percent(30)),
+        liquidation_health_factor: Some(Decimal::percent(35)),
+    };
+
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(res.extension, expected);
+
+    let res = mock.query_all_nft_info(&token_id);
+    assert_eq!(res.access.owner, user.to_string());
+    assert_eq!(res.info.extension, expected);
+}
+
+#[test]
+fn nft_info_without_health_values() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let token_id = mock.mint(&user).unwrap();
+
+    // Health contract has no response for the account
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(
+        res.extension,
+        AccountMetadata {
+            account_kind: Some(AccountKind::Default),
+            ..Default::default()
+        }
+    );
+
+    let mut mock = MockEnv::new().instantiate_with_health_contract(false).build().unwrap();
+    let token_id = mock.mint(&user).unwrap();
+    mock.set_label(&user, &token_id, Some("cold")).unwrap();
+
+    let res = mock.query_nft_info(&token_id);
+    assert_eq!(
+        res.extension,
+        AccountMetadata {
+            label: Some("cold".to_string()),
+            account_kind: Some(AccountKind::Default),
+            ..Default::default()
+        }
+    );
+}
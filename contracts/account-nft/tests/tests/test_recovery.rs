@@ -0,0 +1,219 @@
+use cosmwasm_std::Addr;
+use cw721_base::{ContractError::Ownership, OwnershipError::NotOwner};
+use mars_account_nft::{
+    error::{
+        ContractError,
+        ContractError::{
+            BackupOwnerIsOwner, BaseError, NoBackupOwner, NoPendingRecovery, NotBackupOwner,
+            RecoveryAlreadyInitiated, RecoveryDelayNotPassed, RecoveryDelayTooLong,
+            RecoveryDelayTooShort,
+        },
+    },
+    recovery::{MAX_RECOVERY_DELAY, MIN_RECOVERY_DELAY},
+};
+use mars_types::account_nft::{BackupOwner, PendingRecovery};
+
+use super::helpers::MockEnv;
+
+#[test]
+fn only_token_owner_can_set_backup_owner() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let backup = Addr::unchecked("backup");
+    let token_id = mock.mint(&user).unwrap();
+
+    let res = mock.set_backup_owner(&backup, &token_id, &backup, MIN_RECOVERY_DELAY);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, BaseError(Ownership(NotOwner)));
+
+    let res = mock.set_backup_owner(&user, &token_id, &user, MIN_RECOVERY_DELAY);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, BackupOwnerIsOwner);
+
+    let res = mock.set_backup_owner(&user, &token_id, &backup, MIN_RECOVERY_DELAY - 1);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        RecoveryDelayTooShort {
+            min: MIN_RECOVERY_DELAY
+        }
+    );
+
+    let res = mock.set_backup_owner(&user, &token_id, &backup, MAX_RECOVERY_DELAY + 1);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        RecoveryDelayTooLong {
+            max: MAX_RECOVERY_DELAY
+        }
+    );
+    let res = mock.set_backup_owner(&user, &token_id, &backup, u64::MAX);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        RecoveryDelayTooLong {
+            max: MAX_RECOVERY_DELAY
+        }
+    );
+
+    mock.set_backup_owner(&user, &token_id, &backup, MIN_RECOVERY_DELAY).unwrap();
+    let res = mock.query_recovery(&token_id);
+    assert_eq!(
+        res.backup_owner,
+        Some(BackupOwner {
+            address: backup.clone(),
+            recovery_delay: MIN_RECOVERY_DELAY,
+        })
+    );
+    assert_eq!(res.pending_recovery, None);
+
+    let res = mock.remove_backup_owner(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, BaseError(Ownership(NotOwner)));
+
+    mock.remove_backup_owner(&user, &token_id).unwrap();
+    let res = mock.query_recovery(&token_id);
+    assert_eq!(res.backup_owner, None);
+}
+
+#[test]
+fn recovery_can_be_canceled_during_delay() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let backup = Addr::unchecked("backup");
+    let bad_guy = Addr::unchecked("bad_guy");
+    let token_id = mock.mint(&user).unwrap();
+
+    let res = mock.initiate_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        NoBackupOwner {
+            token_id: token_id.clone()
+        }
+    );
+
+    let recovery_delay = MIN_RECOVERY_DELAY * 2;
+    mock.set_backup_owner(&user, &token_id, &backup, recovery_delay).unwrap();
+
+    let res = mock.initiate_recovery(&bad_guy, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        NotBackupOwner {
+            user: bad_guy.to_string(),
+            token_id: token_id.clone()
+        }
+    );
+
+    let res = mock.complete_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        NoPendingRecovery {
+            token_id: token_id.clone()
+        }
+    );
+
+    mock.initiate_recovery(&backup, &token_id).unwrap();
+    let initiated_at = mock.app.block_info().time.seconds();
+    assert_eq!(
+        mock.query_recovery(&token_id).pending_recovery,
+        Some(PendingRecovery {
+            initiated_at,
+            completable_at: initiated_at + recovery_delay,
+        })
+    );
+
+    let res = mock.initiate_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        RecoveryAlreadyInitiated {
+            token_id: token_id.clone()
+        }
+    );
+
+    mock.increment_by_time(recovery_delay - 1);
+    let res = mock.complete_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        RecoveryDelayNotPassed {
+            completable_at: initiated_at + recovery_delay
+        }
+    );
+
+    let res = mock.cancel_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(err, BaseError(Ownership(NotOwner)));
+
+    mock.cancel_recovery(&user, &token_id).unwrap();
+    let res = mock.query_recovery(&token_id);
+    assert!(res.backup_owner.is_some());
+    assert_eq!(res.pending_recovery, None);
+
+    mock.increment_by_time(1);
+    let res = mock.complete_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        NoPendingRecovery {
+            token_id: token_id.clone()
+        }
+    );
+    mock.assert_owner_is_correct(&user, &token_id);
+}
+
+#[test]
+fn backup_owner_recovers_token_after_delay() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let backup = Addr::unchecked("backup");
+    let token_id = mock.mint(&user).unwrap();
+
+    mock.set_backup_owner(&user, &token_id, &backup, MIN_RECOVERY_DELAY).unwrap();
+    mock.initiate_recovery(&backup, &token_id).unwrap();
+    mock.increment_by_time(MIN_RECOVERY_DELAY);
+    mock.complete_recovery(&backup, &token_id).unwrap();
+
+    mock.assert_owner_is_correct(&backup, &token_id);
+    let res = mock.query_recovery(&token_id);
+    assert_eq!(res.backup_owner, None);
+    assert_eq!(res.pending_recovery, None);
+
+    // previous owner lost access
+    let res = mock.transfer_nft(&user, &user, &token_id);
+    assert!(res.is_err());
+    mock.transfer_nft(&backup, &user, &token_id).unwrap();
+    mock.assert_owner_is_correct(&user, &token_id);
+}
+
+#[test]
+fn transfer_clears_backup_owner() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let user = Addr::unchecked("user");
+    let backup = Addr::unchecked("backup");
+    let buyer = Addr::unchecked("buyer");
+    let token_id = mock.mint(&user).unwrap();
+
+    mock.set_backup_owner(&user, &token_id, &backup, MIN_RECOVERY_DELAY).unwrap();
+    mock.initiate_recovery(&backup, &token_id).unwrap();
+
+    mock.transfer_nft(&user, &buyer, &token_id).unwrap();
+    let res = mock.query_recovery(&token_id);
+    assert_eq!(res.backup_owner, None);
+    assert_eq!(res.pending_recovery, None);
+
+    // backup owner of the previous owner can't take over the token
+    mock.increment_by_time(MIN_RECOVERY_DELAY);
+    let res = mock.complete_recovery(&backup, &token_id);
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert_eq!(
+        err,
+        NoBackupOwner {
+            token_id: token_id.clone()
+        }
+    );
+    mock.assert_owner_is_correct(&buyer, &token_id);
+}
@@ -0,0 +1,154 @@
+use cosmwasm_std::{Coin, Decimal, Deps, DepsMut, Response, Uint128};
+use mars_types::{health::HealthValuesResponse, oracle::ActionKind};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    health::query_health_values,
+    state::{COIN_BALANCES, DEBT_SHARES, VALUE_CHANGE_REFERENCE},
+    utils::debt_shares_to_amount,
+};
+
+pub fn assert_health_factor_above(
+    deps: Deps,
+    account_id: &str,
+    min_health_factor: Decimal,
+) -> ContractResult<Response> {
+    let health = query_health_values(deps, account_id, ActionKind::Default)?;
+    check_health_factor_above(account_id, &health, min_health_factor)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_health_factor_above")
+        .add_attribute("account_id", account_id)
+        .add_attribute("min_health_factor", min_health_factor.to_string()))
+}
+
+/// Accounts without debt (no max LTV health factor) always pass
+pub fn check_health_factor_above(
+    account_id: &str,
+    health: &HealthValuesResponse,
+    min_health_factor: Decimal,
+) -> ContractResult<()> {
+    match health.max_ltv_health_factor {
+        Some(hf) if hf < min_health_factor => Err(ContractError::HealthFactorBelowMin {
+            account_id: account_id.to_string(),
+            max_ltv_health_factor: hf.to_string(),
+            min_health_factor,
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn assert_coin_balance_at_least(
+    deps: Deps,
+    account_id: &str,
+    coin: &Coin,
+) -> ContractResult<Response> {
+    let balance =
+        COIN_BALANCES.may_load(deps.storage, (account_id, &coin.denom))?.unwrap_or_default();
+    check_coin_balance_at_least(balance, coin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_coin_balance_at_least")
+        .add_attribute("account_id", account_id)
+        .add_attribute("coin", coin.to_string()))
+}
+
+pub fn check_coin_balance_at_least(balance: Uint128, coin: &Coin) -> ContractResult<()> {
+    if balance < coin.amount {
+        return Err(ContractError::CoinBalanceBelowMin {
+            denom: coin.denom.clone(),
+            balance,
+            min: coin.amount,
+        });
+    }
+    Ok(())
+}
+
+pub fn assert_debt_at_most(deps: Deps, account_id: &str, coin: &Coin) -> ContractResult<Response> {
+    let debt = match DEBT_SHARES.may_load(deps.storage, (account_id, &coin.denom))? {
+        Some(shares) => debt_shares_to_amount(deps, &coin.denom, shares)?.amount,
+        None => Uint128::zero(),
+    };
+    check_debt_at_most(debt, coin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_debt_at_most")
+        .add_attribute("account_id", account_id)
+        .add_attribute("coin", coin.to_string()))
+}
+
+pub fn check_debt_at_most(debt: Uint128, coin: &Coin) -> ContractResult<()> {
+    if debt > coin.amount {
+        return Err(ContractError::DebtAboveMax {
+            denom: coin.denom.clone(),
+            debt,
+            max: coin.amount,
+        });
+    }
+    Ok(())
+}
+
+pub fn assert_value_change(
+    deps: Deps,
+    account_id: &str,
+    max_loss_pct: Decimal,
+) -> ContractResult<Response> {
+    let (_, prev_net_value) = VALUE_CHANGE_REFERENCE.load(deps.storage)?;
+    let new_net_value = query_net_value(deps, account_id)?;
+    check_value_change(prev_net_value, new_net_value, max_loss_pct)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_value_change")
+        .add_attribute("account_id", account_id)
+        .add_attribute("prev_net_value", prev_net_value)
+        .add_attribute("new_net_value", new_net_value))
+}
+
+pub fn check_value_change(
+    prev_net_value: Uint128,
+    new_net_value: Uint128,
+    max_loss_pct: Decimal,
+) -> ContractResult<()> {
+    let loss = prev_net_value.saturating_sub(new_net_value);
+    let max_loss = prev_net_value.checked_mul_floor(max_loss_pct)?;
+    if loss > max_loss {
+        return Err(ContractError::ValueLossExceeded {
+            prev_net_value,
+            new_net_value,
+            max_loss_pct,
+        });
+    }
+    Ok(())
+}
+
+/// Withdrawals aren't losses: the net value they take out of the account is taken out of the
+/// reference of its value change assertions as well
+pub fn withdraw_excluded_from_value_change<T>(
+    deps: &mut DepsMut,
+    account_id: &str,
+    withdraw: impl FnOnce(&mut DepsMut) -> ContractResult<T>,
+) -> ContractResult<T> {
+    let reference = match VALUE_CHANGE_REFERENCE.may_load(deps.storage)? {
+        Some((reference_account_id, reference)) if reference_account_id == account_id => reference,
+        _ => return withdraw(deps),
+    };
+
+    let prev_net_value = query_net_value(deps.as_ref(), account_id)?;
+    let res = withdraw(deps)?;
+    let withdrawn_value =
+        prev_net_value.saturating_sub(query_net_value(deps.as_ref(), account_id)?);
+    VALUE_CHANGE_REFERENCE
+        .save(deps.storage, &(account_id.to_string(), reference.saturating_sub(withdrawn_value)))?;
+
+    Ok(res)
+}
+
+/// Collateral value minus debt value, zero if the account is underwater
+pub fn net_value(health: &HealthValuesResponse) -> Uint128 {
+    health.total_collateral_value.saturating_sub(health.total_debt_value)
+}
+
+pub fn query_net_value(deps: Deps, account_id: &str) -> ContractResult<Uint128> {
+    let health = query_health_values(deps, account_id, ActionKind::Default)?;
+    Ok(net_value(&health))
+}
@@ -2,6 +2,7 @@ use cosmwasm_std::{Coin, DepsMut, Response, Uint128};
 
 use crate::{
     error::{ContractError, ContractResult},
+    fees::{charge_fee, load_fee_config},
     state::{DEBT_SHARES, RED_BANK, TOTAL_DEBT_SHARES},
     utils::{assert_coin_is_whitelisted, increment_coin_balance},
 };
@@ -12,7 +13,8 @@ pub static DEFAULT_DEBT_SHARES_PER_COIN_BORROWED: Uint128 = Uint128::new(1_000_0
 /// if total debt is zero, then we define 1 unit of coin borrowed = 1,000,000 debt unit
 /// else, get debt ownership % and multiply by total existing shares
 ///
-/// increment total debt shares, token debt shares, and asset amount
+/// increment total debt shares, token debt shares, and asset amount.
+/// The borrow origination fee (if any) is moved to the rewards-collector account.
 pub fn borrow(mut deps: DepsMut, account_id: &str, coin: Coin) -> ContractResult<Response> {
     if coin.amount.is_zero() {
         return Err(ContractError::NoAmount);
@@ -20,6 +22,23 @@ pub fn borrow(mut deps: DepsMut, account_id: &str, coin: Coin) -> ContractResult
 
     assert_coin_is_whitelisted(&mut deps, &coin.denom)?;
 
+    let debt_shares_to_add = add_debt(&mut deps, account_id, &coin)?;
+
+    let fee = load_fee_config(deps.storage)?.borrow_fee(&coin.denom);
+    let fee_paid = charge_fee(deps.storage, account_id, &coin, fee)?;
+
+    Ok(Response::new()
+        .add_message(RED_BANK.load(deps.storage)?.borrow_msg(&coin)?)
+        .add_attribute("action", "borrow")
+        .add_attribute("account_id", account_id)
+        .add_attribute("debt_shares_added", debt_shares_to_add)
+        .add_attribute("coin_borrowed", coin.to_string())
+        .add_attribute("borrow_fee", fee_paid.to_string()))
+}
+
+/// Adds the debt shares of the borrowed coin to the account and credits the coin to its balance.
+/// The coin still has to be borrowed from the Red Bank by the caller.
+pub fn add_debt(deps: &mut DepsMut, account_id: &str, coin: &Coin) -> ContractResult<Uint128> {
     let red_bank = RED_BANK.load(deps.storage)?;
     let total_debt_amount = red_bank.query_debt(&deps.querier, &coin.denom)?;
 
@@ -50,12 +69,7 @@ pub fn borrow(mut deps: DepsMut, account_id: &str, coin: Coin) -> ContractResult
             .map_err(ContractError::Overflow)
     })?;
 
-    increment_coin_balance(deps.storage, account_id, &coin)?;
+    increment_coin_balance(deps.storage, account_id, coin)?;
 
-    Ok(Response::new()
-        .add_message(red_bank.borrow_msg(&coin)?)
-        .add_attribute("action", "borrow")
-        .add_attribute("account_id", account_id)
-        .add_attribute("debt_shares_added", debt_shares_to_add)
-        .add_attribute("coin_borrowed", coin.to_string()))
+    Ok(debt_shares_to_add)
 }
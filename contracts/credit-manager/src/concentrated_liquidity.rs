@@ -0,0 +1,541 @@
+use std::str::FromStr;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Empty, Env, Order, QuerierWrapper, Reply,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128,
+};
+use mars_osmosis::helpers::{query_cl_position, query_pool, Pool};
+use mars_types::{
+    credit_manager::{ActionAmount, ActionCoin, ClPosition},
+    traits::Stringify,
+};
+use osmosis_std::types::{
+    cosmos::base::v1beta1::Coin as OsmoCoin,
+    osmosis::concentratedliquidity::v1beta1::{
+        MsgAddToPosition, MsgAddToPositionResponse, MsgCollectIncentives, MsgCollectSpreadRewards,
+        MsgCreatePosition, MsgCreatePositionResponse, MsgWithdrawPosition,
+        MsgWithdrawPositionResponse,
+    },
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{
+        CL_POSITIONS, CL_POSITION_TEMP_STORAGE, COIN_BALANCES, MAX_CL_POSITIONS, REWARDS_COLLECTOR,
+    },
+    utils::{assert_coins_are_whitelisted, decrement_coin_balance, increment_coin_balance},
+};
+
+pub const CL_POSITION_REPLY_ID: u64 = 10_002;
+
+/// Request sent to the concentrated liquidity module, completed on reply
+#[cw_serde]
+pub enum ClPositionRequest {
+    /// Tokens not used by the pool are returned to the account
+    Create {
+        account_id: String,
+        token0: Coin,
+        token1: Coin,
+    },
+    /// The pool replaces `position_id` with a new position.
+    /// Tokens not used by the pool are returned to the account.
+    AddTo {
+        account_id: String,
+        position_id: u64,
+        token0: Coin,
+        token1: Coin,
+    },
+    /// Withdrawn tokens are added to the account, except for the `protocol_fee` share which is
+    /// transferred to the rewards-collector account (liquidations)
+    Withdraw {
+        account_id: String,
+        denom0: String,
+        denom1: String,
+        protocol_fee: Decimal,
+    },
+}
+
+/// Spread rewards and incentives a position can claim
+pub struct ClaimableRewards {
+    pub spread_rewards: Vec<Coin>,
+    pub incentives: Vec<Coin>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_cl_position(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    pool_id: u64,
+    lower_tick: i64,
+    upper_tick: i64,
+    coins_in: Vec<ActionCoin>,
+    token_min_amount0: Uint128,
+    token_min_amount1: Uint128,
+) -> ContractResult<Response> {
+    let Pool::ConcentratedLiquidity(pool) = query_pool(&deps.querier, pool_id)? else {
+        return Err(ContractError::NotConcentratedLiquidityPool(pool_id));
+    };
+
+    // Both pool tokens count as collateral
+    assert_coins_are_whitelisted(&mut deps, vec![&pool.token0, &pool.token1])?;
+    assert_under_max_cl_positions(deps.storage, account_id)?;
+
+    let (token0, token1) =
+        take_pool_tokens(deps.storage, account_id, pool_id, &pool.token0, &pool.token1, coins_in)?;
+
+    // Provided tokens have to be sorted by denom
+    let mut tokens_provided = [&token0, &token1]
+        .into_iter()
+        .filter(|token| !token.amount.is_zero())
+        .map(to_osmo_coin)
+        .collect::<Vec<_>>();
+    tokens_provided.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    let create_msg = MsgCreatePosition {
+        pool_id,
+        sender: env.contract.address.to_string(),
+        lower_tick,
+        upper_tick,
+        tokens_provided,
+        token_min_amount0: token_min_amount0.to_string(),
+        token_min_amount1: token_min_amount1.to_string(),
+    };
+
+    let coins_in = [token0.clone(), token1.clone()];
+    CL_POSITION_TEMP_STORAGE.save(
+        deps.storage,
+        &ClPositionRequest::Create {
+            account_id: account_id.to_string(),
+            token0,
+            token1,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(create_msg, CL_POSITION_REPLY_ID))
+        .add_attribute("action", "create_cl_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("pool_id", pool_id.to_string())
+        .add_attribute("coins_in", coins_in.as_slice().to_string()))
+}
+
+pub fn add_to_cl_position(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    position_id: u64,
+    coins_in: Vec<ActionCoin>,
+    token_min_amount0: Uint128,
+    token_min_amount1: Uint128,
+) -> ContractResult<Response> {
+    assert_cl_position_owner(deps.storage, account_id, position_id)?;
+    let (position, rewards) = query_cl_position_with_rewards(&deps.querier, position_id)?;
+
+    let denom0 = position.asset0.denom;
+    let denom1 = position.asset1.denom;
+    assert_coins_are_whitelisted(&mut deps, vec![&denom0, &denom1])?;
+
+    let (token0, token1) =
+        take_pool_tokens(deps.storage, account_id, position.pool_id, &denom0, &denom1, coins_in)?;
+
+    // The pool withdraws the replaced position which also collects its rewards
+    let (claim_msgs, _) = claim_rewards_msgs(deps.storage, &env, account_id, position_id, rewards)?;
+
+    let add_msg = MsgAddToPosition {
+        position_id,
+        sender: env.contract.address.to_string(),
+        amount0: token0.amount.to_string(),
+        amount1: token1.amount.to_string(),
+        token_min_amount0: token_min_amount0.to_string(),
+        token_min_amount1: token_min_amount1.to_string(),
+    };
+
+    let coins_in = [token0.clone(), token1.clone()];
+    CL_POSITION_TEMP_STORAGE.save(
+        deps.storage,
+        &ClPositionRequest::AddTo {
+            account_id: account_id.to_string(),
+            position_id,
+            token0,
+            token1,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(claim_msgs)
+        .add_submessage(SubMsg::reply_on_success(add_msg, CL_POSITION_REPLY_ID))
+        .add_attribute("action", "add_to_cl_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("coins_in", coins_in.as_slice().to_string()))
+}
+
+pub fn withdraw_cl_position(
+    deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    position_id: u64,
+    liquidity: Option<Decimal256>,
+) -> ContractResult<Response> {
+    assert_cl_position_owner(deps.storage, account_id, position_id)?;
+    let (position, rewards) = query_cl_position_with_rewards(&deps.querier, position_id)?;
+
+    let liquidity = liquidity.unwrap_or(position.liquidity);
+    if liquidity.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+    if liquidity > position.liquidity {
+        return Err(ContractError::InsufficientClLiquidity {
+            position_id,
+            requested: liquidity,
+            available: position.liquidity,
+        });
+    }
+
+    let (claim_msgs, claimed) =
+        claim_rewards_msgs(deps.storage, &env, account_id, position_id, rewards)?;
+    let withdraw_msg = withdraw_liquidity_msg(
+        deps.storage,
+        &env,
+        account_id,
+        account_id,
+        &position,
+        liquidity,
+        Decimal::zero(),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(claim_msgs)
+        .add_submessage(withdraw_msg)
+        .add_attribute("action", "withdraw_cl_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("liquidity", liquidity.to_string())
+        .add_attribute("rewards", claimed.as_slice().to_string()))
+}
+
+pub fn claim_cl_position_rewards(
+    deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    position_id: u64,
+) -> ContractResult<Response> {
+    assert_cl_position_owner(deps.storage, account_id, position_id)?;
+    let (_, rewards) = query_cl_position_with_rewards(&deps.querier, position_id)?;
+
+    let (claim_msgs, claimed) =
+        claim_rewards_msgs(deps.storage, &env, account_id, position_id, rewards)?;
+    if claimed.is_empty() {
+        return Err(ContractError::NoAmount);
+    }
+
+    Ok(Response::new()
+        .add_messages(claim_msgs)
+        .add_attribute("action", "claim_cl_position_rewards")
+        .add_attribute("account_id", account_id)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("rewards", claimed.as_slice().to_string()))
+}
+
+pub fn handle_cl_position_reply(deps: DepsMut, reply: Reply) -> ContractResult<Response> {
+    let request = CL_POSITION_TEMP_STORAGE.load(deps.storage)?;
+    CL_POSITION_TEMP_STORAGE.remove(deps.storage);
+
+    match request {
+        ClPositionRequest::Create {
+            account_id,
+            token0,
+            token1,
+        } => {
+            let res = MsgCreatePositionResponse::try_from(reply.result)?;
+            CL_POSITIONS.save(deps.storage, (&account_id, res.position_id), &Empty {})?;
+            let refunded = refund_unused(
+                deps.storage,
+                &account_id,
+                [(token0, &res.amount0), (token1, &res.amount1)],
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "create_cl_position/handle_reply")
+                .add_attribute("account_id", account_id)
+                .add_attribute("position_id", res.position_id.to_string())
+                .add_attribute("liquidity", res.liquidity_created)
+                .add_attribute("coins_refunded", refunded.as_slice().to_string()))
+        }
+        ClPositionRequest::AddTo {
+            account_id,
+            position_id,
+            token0,
+            token1,
+        } => {
+            let res = MsgAddToPositionResponse::try_from(reply.result)?;
+            CL_POSITIONS.remove(deps.storage, (&account_id, position_id));
+            CL_POSITIONS.save(deps.storage, (&account_id, res.position_id), &Empty {})?;
+            let refunded = refund_unused(
+                deps.storage,
+                &account_id,
+                [(token0, &res.amount0), (token1, &res.amount1)],
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "add_to_cl_position/handle_reply")
+                .add_attribute("account_id", account_id)
+                .add_attribute("prev_position_id", position_id.to_string())
+                .add_attribute("position_id", res.position_id.to_string())
+                .add_attribute("coins_refunded", refunded.as_slice().to_string()))
+        }
+        ClPositionRequest::Withdraw {
+            account_id,
+            denom0,
+            denom1,
+            protocol_fee,
+        } => {
+            let res = MsgWithdrawPositionResponse::try_from(reply.result)?;
+            let rewards_collector_account = REWARDS_COLLECTOR.load(deps.storage)?.account_id;
+
+            let mut received = vec![];
+            for (denom, amount) in [(denom0, &res.amount0), (denom1, &res.amount1)] {
+                let mut amount = Uint128::from_str(amount)?;
+                if amount.is_zero() {
+                    continue;
+                }
+
+                let protocol_fee_amt = amount.checked_mul_ceil(protocol_fee)?;
+                if !protocol_fee_amt.is_zero() {
+                    increment_coin_balance(
+                        deps.storage,
+                        &rewards_collector_account,
+                        &Coin::new(protocol_fee_amt.u128(), &denom),
+                    )?;
+                    amount = amount.checked_sub(protocol_fee_amt)?;
+                }
+
+                let coin = Coin::new(amount.u128(), denom);
+                increment_coin_balance(deps.storage, &account_id, &coin)?;
+                received.push(coin);
+            }
+
+            Ok(Response::new()
+                .add_attribute("action", "withdraw_cl_position/handle_reply")
+                .add_attribute("account_id", account_id)
+                .add_attribute("coins_received", received.as_slice().to_string()))
+        }
+    }
+}
+
+/// Each position is queried for health checks, so their number per account is limited
+pub fn assert_under_max_cl_positions(
+    storage: &dyn Storage,
+    account_id: &str,
+) -> ContractResult<()> {
+    let maximum = MAX_CL_POSITIONS.load(storage)?;
+    let new_amount = Uint128::from(
+        CL_POSITIONS.prefix(account_id).keys(storage, None, None, Order::Ascending).count() as u128,
+    )
+    .checked_add(Uint128::one())?;
+
+    if new_amount > maximum {
+        return Err(ContractError::ExceedsMaxClPositions {
+            new_amount,
+            maximum,
+        });
+    }
+    Ok(())
+}
+
+pub fn query_cl_positions(deps: Deps, account_id: &str) -> ContractResult<Vec<ClPosition>> {
+    CL_POSITIONS
+        .prefix(account_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|position_id| Ok(query_cl_position_with_rewards(&deps.querier, position_id?)?.0))
+        .collect()
+}
+
+pub fn query_cl_position_with_rewards(
+    querier: &QuerierWrapper,
+    position_id: u64,
+) -> ContractResult<(ClPosition, ClaimableRewards)> {
+    let breakdown = query_cl_position(querier, position_id)?;
+    let position = breakdown.position.ok_or_else(|| {
+        StdError::not_found(format!("concentrated liquidity position {position_id}"))
+    })?;
+
+    Ok((
+        ClPosition {
+            position_id,
+            pool_id: position.pool_id,
+            lower_tick: position.lower_tick,
+            upper_tick: position.upper_tick,
+            liquidity: Decimal256::from_str(&position.liquidity)?,
+            asset0: Pool::unwrap_coin(&breakdown.asset0)?,
+            asset1: Pool::unwrap_coin(&breakdown.asset1)?,
+        },
+        ClaimableRewards {
+            spread_rewards: from_osmo_coins(&breakdown.claimable_spread_rewards)?,
+            incentives: from_osmo_coins(&breakdown.claimable_incentives)?,
+        },
+    ))
+}
+
+pub fn assert_cl_position_owner(
+    storage: &dyn Storage,
+    account_id: &str,
+    position_id: u64,
+) -> ContractResult<()> {
+    if !CL_POSITIONS.has(storage, (account_id, position_id)) {
+        return Err(ContractError::NoClPosition {
+            account_id: account_id.to_string(),
+            position_id,
+        });
+    }
+    Ok(())
+}
+
+/// Adds the claimable rewards to the account's coin balance and returns the messages collecting
+/// them from the pool, together with the claimed coins
+pub fn claim_rewards_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    account_id: &str,
+    position_id: u64,
+    rewards: ClaimableRewards,
+) -> ContractResult<(Vec<CosmosMsg>, Vec<Coin>)> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !rewards.spread_rewards.is_empty() {
+        msgs.push(
+            MsgCollectSpreadRewards {
+                position_ids: vec![position_id],
+                sender: env.contract.address.to_string(),
+            }
+            .into(),
+        );
+    }
+    if !rewards.incentives.is_empty() {
+        msgs.push(
+            MsgCollectIncentives {
+                position_ids: vec![position_id],
+                sender: env.contract.address.to_string(),
+            }
+            .into(),
+        );
+    }
+
+    let claimed = rewards.spread_rewards.into_iter().chain(rewards.incentives).collect::<Vec<_>>();
+    for coin in claimed.iter() {
+        increment_coin_balance(storage, account_id, coin)?;
+    }
+
+    Ok((msgs, claimed))
+}
+
+/// Withdraws liquidity of `account_id`'s position for `recipient_account_id`.
+/// The position is removed from the account if all of its liquidity is withdrawn.
+pub fn withdraw_liquidity_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    account_id: &str,
+    recipient_account_id: &str,
+    position: &ClPosition,
+    liquidity: Decimal256,
+    protocol_fee: Decimal,
+) -> ContractResult<SubMsg> {
+    if liquidity == position.liquidity {
+        CL_POSITIONS.remove(storage, (account_id, position.position_id));
+    }
+
+    CL_POSITION_TEMP_STORAGE.save(
+        storage,
+        &ClPositionRequest::Withdraw {
+            account_id: recipient_account_id.to_string(),
+            denom0: position.asset0.denom.clone(),
+            denom1: position.asset1.denom.clone(),
+            protocol_fee,
+        },
+    )?;
+
+    let withdraw_msg = MsgWithdrawPosition {
+        position_id: position.position_id,
+        sender: env.contract.address.to_string(),
+        liquidity_amount: liquidity.to_string(),
+    };
+    Ok(SubMsg::reply_on_success(withdraw_msg, CL_POSITION_REPLY_ID))
+}
+
+/// Resolves the amounts of the pool tokens to provide and deducts them from the account's balance
+fn take_pool_tokens(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    pool_id: u64,
+    denom0: &str,
+    denom1: &str,
+    coins_in: Vec<ActionCoin>,
+) -> ContractResult<(Coin, Coin)> {
+    let mut token0 = Coin::new(0, denom0);
+    let mut token1 = Coin::new(0, denom1);
+    for coin_in in coins_in {
+        let token = if coin_in.denom == denom0 {
+            &mut token0
+        } else if coin_in.denom == denom1 {
+            &mut token1
+        } else {
+            return Err(ContractError::NotClPoolToken {
+                denom: coin_in.denom,
+                pool_id,
+            });
+        };
+        let amount = match coin_in.amount {
+            ActionAmount::Exact(amount) => amount,
+            ActionAmount::AccountBalance => {
+                COIN_BALANCES.may_load(storage, (account_id, &coin_in.denom))?.unwrap_or_default()
+            }
+        };
+        token.amount = token.amount.checked_add(amount)?;
+    }
+
+    if token0.amount.is_zero() && token1.amount.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+
+    for token in [&token0, &token1] {
+        if !token.amount.is_zero() {
+            decrement_coin_balance(storage, account_id, token)?;
+        }
+    }
+
+    Ok((token0, token1))
+}
+
+/// Returns the provided tokens not used by the pool to the account's balance
+fn refund_unused(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    tokens: [(Coin, &String); 2],
+) -> ContractResult<Vec<Coin>> {
+    let mut refunded = vec![];
+    for (provided, used) in tokens {
+        let unused = provided.amount.checked_sub(Uint128::from_str(used)?)?;
+        if !unused.is_zero() {
+            let coin = Coin::new(unused.u128(), provided.denom);
+            increment_coin_balance(storage, account_id, &coin)?;
+            refunded.push(coin);
+        }
+    }
+    Ok(refunded)
+}
+
+fn to_osmo_coin(coin: &Coin) -> OsmoCoin {
+    OsmoCoin {
+        denom: coin.denom.clone(),
+        amount: coin.amount.to_string(),
+    }
+}
+
+fn from_osmo_coins(coins: &[OsmoCoin]) -> StdResult<Vec<Coin>> {
+    coins
+        .iter()
+        .map(|coin| Ok(Coin::new(Uint128::from_str(&coin.amount)?.u128(), &coin.denom)))
+        .filter(|coin| !matches!(coin, Ok(c) if c.amount.is_zero()))
+        .collect()
+}
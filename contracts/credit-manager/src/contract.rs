@@ -8,17 +8,23 @@ use mars_types::{
 };
 
 use crate::{
+    concentrated_liquidity::{handle_cl_position_reply, CL_POSITION_REPLY_ID},
+    deleverage::{deleverage, update_deleverage_opt_in},
+    dust::sweep_dust,
     error::{ContractError, ContractResult},
     execute::{create_credit_account, dispatch_actions, execute_callback},
     instantiate::store_config,
     migrations,
+    price_updates::update_prices_and_dispatch,
     query::{
         query_accounts, query_all_coin_balances, query_all_debt_shares,
         query_all_total_debt_shares, query_all_vault_positions, query_all_vault_utilizations,
-        query_config, query_positions, query_total_debt_shares, query_vault_bindings,
-        query_vault_position_value, query_vault_utilization,
+        query_config, query_deleverage_opt_in, query_positions, query_total_debt_shares,
+        query_vault_bindings, query_vault_position_value, query_vault_utilization,
     },
     repay::repay_from_wallet,
+    simulate::simulate_actions,
+    staking::send_staking_batch,
     update_config::{update_config, update_nft_config, update_owner},
     utils::get_account_kind,
     vault::handle_unlock_request_reply,
@@ -64,10 +70,40 @@ pub fn execute(
             account_id,
             account_kind,
             actions,
-        } => dispatch_actions(deps, env, info, account_id, account_kind, actions),
+            price_updates,
+        } => match price_updates {
+            Some(price_updates) if !price_updates.is_empty() => update_prices_and_dispatch(
+                deps,
+                env,
+                info,
+                price_updates,
+                account_id,
+                account_kind,
+                actions,
+            ),
+            _ => dispatch_actions(deps, env, info, account_id, account_kind, actions),
+        },
         ExecuteMsg::RepayFromWallet {
             account_id,
         } => repay_from_wallet(deps, env, info, account_id),
+        ExecuteMsg::UpdateDeleverageOptIn {
+            account_id,
+            enabled,
+        } => update_deleverage_opt_in(deps, info, account_id, enabled),
+        ExecuteMsg::Deleverage {
+            account_id,
+            coin_in,
+            debt_denom,
+            route,
+        } => deleverage(deps, env, info, account_id, coin_in, debt_denom, route),
+        ExecuteMsg::SweepDust {
+            account_id,
+            debt_denom,
+            collateral_denom,
+        } => sweep_dust(deps, env, info, account_id, debt_denom, collateral_denom),
+        ExecuteMsg::SendStakingBatch {
+            validator,
+        } => send_staking_batch(deps, env, info, validator),
     }
 }
 
@@ -75,6 +111,7 @@ pub fn execute(
 pub fn reply(deps: DepsMut, _: Env, reply: Reply) -> ContractResult<Response> {
     match reply.id {
         VAULT_REQUEST_REPLY_ID => handle_unlock_request_reply(deps, reply),
+        CL_POSITION_REPLY_ID => handle_cl_position_reply(deps, reply),
         id => Err(ContractError::ReplyIdError(id)),
     }
 }
@@ -100,7 +137,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
         } => to_json_binary(&query_all_vault_utilizations(deps, env, start_after, limit)?),
         QueryMsg::Positions {
             account_id,
-        } => to_json_binary(&query_positions(deps, &account_id)?),
+        } => to_json_binary(&query_positions(deps, &env, &account_id)?),
         QueryMsg::AllCoinBalances {
             start_after,
             limit,
@@ -132,6 +169,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_after,
             limit,
         } => to_json_binary(&query_vault_bindings(deps, start_after, limit)?),
+        QueryMsg::DeleverageOptIn {
+            account_id,
+        } => to_json_binary(&query_deleverage_opt_in(deps, &account_id)?),
+        QueryMsg::SimulateActions {
+            account_id,
+            actions,
+        } => to_json_binary(&simulate_actions(deps, &env, &account_id, actions)?),
     };
     res.map_err(Into::into)
 }
@@ -0,0 +1,216 @@
+use cosmwasm_std::{
+    BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use mars_types::{
+    credit_manager::{ActionAmount, ActionCoin, CallbackMsg, DeleverageConfig},
+    health::HealthState,
+    oracle::ActionKind,
+    swapper::SwapperRoute,
+    traits::Stringify,
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    fees::{fee_amount, load_fee_config},
+    health::{query_health_state, query_health_values},
+    repay::current_debt_for_denom,
+    staking::sync_unbonding_releases,
+    state::{
+        COIN_BALANCES, DELEVERAGE_CONFIG, DELEVERAGE_OPT_INS, MAX_SLIPPAGE, ORACLE,
+        REENTRANCY_GUARD,
+    },
+    utils::{assert_is_token_owner, decrement_coin_balance},
+};
+
+pub fn assert_deleverage_config(config: &DeleverageConfig) -> ContractResult<()> {
+    if config.bonus >= Decimal::one() {
+        return Err(ContractError::InvalidConfig {
+            reason: "Deleverage bonus must be less than 1".to_string(),
+        });
+    }
+    if config.max_repay_ratio.is_zero() || config.max_repay_ratio > Decimal::one() {
+        return Err(ContractError::InvalidConfig {
+            reason: "Deleverage max repay ratio must be greater than 0 and less than or equal to 1"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn update_deleverage_opt_in(
+    deps: DepsMut,
+    info: MessageInfo,
+    account_id: String,
+    enabled: bool,
+) -> ContractResult<Response> {
+    assert_is_token_owner(&deps, &info.sender, &account_id)?;
+
+    if enabled {
+        DELEVERAGE_OPT_INS.save(deps.storage, &account_id, &true)?;
+    } else {
+        DELEVERAGE_OPT_INS.remove(deps.storage, &account_id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_deleverage_opt_in")
+        .add_attribute("account_id", account_id)
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Sells a slice of the account's collateral for the debt denom and repays debt with the proceeds.
+/// Only allowed within the soft-liquidation band, i.e. the account is above max LTV but not yet
+/// liquidatable. The keeper is paid a bonus (taken from `coin_in`) to its wallet.
+pub fn deleverage(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    coin_in: Coin,
+    debt_denom: String,
+    route: Option<SwapperRoute>,
+) -> ContractResult<Response> {
+    let config =
+        DELEVERAGE_CONFIG.may_load(deps.storage)?.ok_or(ContractError::DeleverageDisabled)?;
+
+    if !DELEVERAGE_OPT_INS.has(deps.storage, &account_id) {
+        return Err(ContractError::DeleverageNotOptedIn {
+            account_id,
+        });
+    }
+
+    if coin_in.amount.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+
+    if coin_in.denom == debt_denom {
+        return Err(ContractError::InvalidConfig {
+            reason: "Deleverage coin_in denom must differ from debt denom".to_string(),
+        });
+    }
+
+    // Prevents the account from being modified by other dispatches until the deleverage completes
+    REENTRANCY_GUARD.try_lock(deps.storage)?;
+    sync_unbonding_releases(&mut deps, &env, &info.funds)?;
+
+    // Assert the account is within the soft-liquidation band
+    let health = query_health_values(deps.as_ref(), &account_id, ActionKind::Default)?;
+    let liquidation_health =
+        query_health_values(deps.as_ref(), &account_id, ActionKind::Liquidation)?;
+    if !health.above_max_ltv || liquidation_health.liquidatable {
+        return Err(ContractError::NotDeleveragable {
+            account_id,
+            max_ltv_health_factor: health.max_ltv_health_factor.to_string(),
+            liquidation_health_factor: liquidation_health.liquidation_health_factor.to_string(),
+        });
+    }
+
+    // Account must have debt to repay in the requested denom
+    current_debt_for_denom(deps.as_ref(), &account_id, &debt_denom)?;
+
+    let balance =
+        COIN_BALANCES.may_load(deps.storage, (&account_id, &coin_in.denom))?.unwrap_or_default();
+    if coin_in.amount > balance {
+        return Err(ContractError::InsufficientFunds {
+            requested: coin_in.amount,
+            available: balance,
+        });
+    }
+
+    // Cap the slice of collateral that can be sold in a single deleverage
+    let oracle = ORACLE.load(deps.storage)?;
+    let coin_in_value = oracle.query_value(&deps.querier, &coin_in, ActionKind::Default)?;
+    let max_value = health.total_debt_value.checked_mul_floor(config.max_repay_ratio)?;
+    if coin_in_value > max_value {
+        return Err(ContractError::DeleverageAmountTooLarge {
+            value: coin_in_value,
+            max_value,
+        });
+    }
+
+    // Keeper bonus is taken from the collateral sold, the rest is swapped for the debt denom
+    let bonus_amount = coin_in.amount.checked_mul_floor(config.bonus)?;
+    let swap_amount = coin_in.amount.checked_sub(bonus_amount)?;
+    let bonus = Coin::new(bonus_amount.u128(), &coin_in.denom);
+
+    // Minimum amount of debt denom to receive, based on oracle prices and max slippage.
+    // Swap fee is taken from the swapped amount.
+    let max_slippage = MAX_SLIPPAGE.load(deps.storage)?;
+    let swap_coin = Coin::new(swap_amount.u128(), &coin_in.denom);
+    let swap_fee = fee_amount(&swap_coin, load_fee_config(deps.storage)?.swap_fee)?;
+    let swap_value = oracle.query_value(
+        &deps.querier,
+        &Coin::new(swap_amount.checked_sub(swap_fee.amount)?.u128(), &coin_in.denom),
+        ActionKind::Default,
+    )?;
+    let debt_price = oracle.query_price(&deps.querier, &debt_denom, ActionKind::Default)?.price;
+    let min_receive = swap_value
+        .checked_div_floor(debt_price)?
+        .checked_mul_floor(Decimal::one() - max_slippage)?;
+
+    let callbacks = [
+        CallbackMsg::SwapExactIn {
+            account_id: account_id.clone(),
+            coin_in: ActionCoin {
+                denom: coin_in.denom.clone(),
+                amount: ActionAmount::Exact(swap_amount),
+            },
+            denom_out: debt_denom.clone(),
+            min_receive,
+            route,
+        },
+        // Repays as much debt as the account's balance of the debt denom allows
+        CallbackMsg::Repay {
+            account_id: account_id.clone(),
+            coin: ActionCoin {
+                denom: debt_denom.clone(),
+                amount: ActionAmount::AccountBalance,
+            },
+        },
+        CallbackMsg::AssertDeleveraged {
+            account_id: account_id.clone(),
+        },
+        CallbackMsg::RemoveReentrancyGuard {},
+    ];
+    let callback_msgs = callbacks
+        .iter()
+        .map(|callback| callback.into_cosmos_msg(&env.contract.address))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    let mut response = Response::new();
+    if !bonus.amount.is_zero() {
+        decrement_coin_balance(deps.storage, &account_id, &bonus)?;
+        response = response.add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![bonus.clone()],
+        }));
+    }
+
+    Ok(response
+        .add_messages(callback_msgs)
+        .add_attribute("action", "deleverage")
+        .add_attribute("account_id", account_id)
+        .add_attribute("keeper", info.sender)
+        .add_attribute("coin_in", coin_in.to_string())
+        .add_attribute("debt_denom", debt_denom)
+        .add_attribute("min_receive", min_receive)
+        .add_attribute("keeper_bonus", bonus.to_string()))
+}
+
+pub fn assert_deleveraged(deps: Deps, account_id: &str) -> ContractResult<Response> {
+    let health = query_health_state(deps, account_id, ActionKind::Default)?;
+
+    if let HealthState::Unhealthy {
+        max_ltv_health_factor,
+        ..
+    } = health
+    {
+        return Err(ContractError::DeleverageInsufficient {
+            account_id: account_id.to_string(),
+            max_ltv_health_factor: max_ltv_health_factor.to_string(),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_deleveraged")
+        .add_attribute("account_id", account_id))
+}
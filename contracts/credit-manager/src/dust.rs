@@ -0,0 +1,142 @@
+use std::{cmp::min, collections::BTreeSet};
+
+use cosmwasm_std::{
+    Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use mars_types::{
+    credit_manager::{ActionCoin, CallbackMsg},
+    oracle::ActionKind,
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    repay::current_debt_for_denom,
+    staking::sync_unbonding_releases,
+    state::{COIN_BALANCES, DEBT_SHARES, ORACLE, PARAMS, REENTRANCY_GUARD, REWARDS_COLLECTOR},
+    utils::{debt_shares_to_amount, decrement_coin_balance, increment_coin_balance},
+};
+
+/// Debts of the given denoms must be either zero or worth at least the asset's `min_debt_value`.
+/// Small debts are uneconomic to liquidate.
+pub fn assert_min_debt_values(
+    deps: Deps,
+    account_id: &str,
+    denoms: BTreeSet<String>,
+) -> ContractResult<Response> {
+    for denom in denoms.iter() {
+        let Some(debt_shares) = DEBT_SHARES.may_load(deps.storage, (account_id, denom))? else {
+            continue;
+        };
+        let debt = debt_shares_to_amount(deps, denom, debt_shares)?;
+        assert_min_debt_value(deps, account_id, &debt)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "callback/assert_min_debt_values")
+        .add_attribute("account_id", account_id))
+}
+
+pub fn assert_min_debt_value(deps: Deps, account_id: &str, debt: &Coin) -> ContractResult<()> {
+    if debt.amount.is_zero() {
+        return Ok(());
+    }
+
+    let (value, min_value) = debt_value_and_min(deps, debt)?;
+    if value < min_value {
+        return Err(ContractError::DebtBelowMinValue {
+            account_id: account_id.to_string(),
+            denom: debt.denom.clone(),
+            value,
+            min_value,
+        });
+    }
+    Ok(())
+}
+
+/// The rewards-collector account repays the dust debt of the account in full. In exchange it
+/// receives the account's collateral of the same value, capped at the account's balance.
+pub fn sweep_dust(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    debt_denom: String,
+    collateral_denom: String,
+) -> ContractResult<Response> {
+    let rewards_collector = REWARDS_COLLECTOR.load(deps.storage)?;
+    if info.sender != rewards_collector.address || account_id == rewards_collector.account_id {
+        return Err(ContractError::Unauthorized {
+            user: info.sender.to_string(),
+            action: "sweep dust".to_string(),
+        });
+    }
+
+    let (debt_amount, _) = current_debt_for_denom(deps.as_ref(), &account_id, &debt_denom)?;
+    let debt = Coin::new(debt_amount.u128(), &debt_denom);
+    let (debt_value, min_value) = debt_value_and_min(deps.as_ref(), &debt)?;
+    if debt_value >= min_value {
+        return Err(ContractError::NotDust {
+            denom: debt_denom,
+            value: debt_value,
+            min_value,
+        });
+    }
+
+    // Prevents the account from being modified by other dispatches until the repayment completes
+    REENTRANCY_GUARD.try_lock(deps.storage)?;
+    sync_unbonding_releases(&mut deps, &env, &info.funds)?;
+
+    let collateral_price = ORACLE
+        .load(deps.storage)?
+        .query_price(&deps.querier, &collateral_denom, ActionKind::Default)?
+        .price;
+    let collateral_balance =
+        COIN_BALANCES.may_load(deps.storage, (&account_id, &collateral_denom))?.unwrap_or_default();
+    let collateral = Coin {
+        amount: min(collateral_balance, debt_value.checked_div_floor(collateral_price)?),
+        denom: collateral_denom,
+    };
+
+    // Debt is paid from the rewards-collector account balance
+    decrement_coin_balance(deps.storage, &rewards_collector.account_id, &debt)?;
+    increment_coin_balance(deps.storage, &account_id, &debt)?;
+    if !collateral.amount.is_zero() {
+        decrement_coin_balance(deps.storage, &account_id, &collateral)?;
+        increment_coin_balance(deps.storage, &rewards_collector.account_id, &collateral)?;
+    }
+
+    let callbacks = [
+        CallbackMsg::Repay {
+            account_id: account_id.clone(),
+            coin: ActionCoin::from(&debt),
+        },
+        CallbackMsg::RemoveReentrancyGuard {},
+    ];
+    let callback_msgs = callbacks
+        .iter()
+        .map(|callback| callback.into_cosmos_msg(&env.contract.address))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new()
+        .add_messages(callback_msgs)
+        .add_attribute("action", "sweep_dust")
+        .add_attribute("account_id", account_id)
+        .add_attribute("rewards_collector_account_id", rewards_collector.account_id)
+        .add_attribute("coin_debt_repaid", debt.to_string())
+        .add_attribute("coin_collateral_swept", collateral.to_string()))
+}
+
+/// Returns (debt value, min debt value). Assets without params have no minimum.
+fn debt_value_and_min(deps: Deps, debt: &Coin) -> ContractResult<(Uint128, Uint128)> {
+    let min_value = PARAMS
+        .load(deps.storage)?
+        .query_asset_params(&deps.querier, &debt.denom)?
+        .map(|params| params.min_debt_value)
+        .unwrap_or_default();
+    if min_value.is_zero() {
+        return Ok((Uint128::zero(), min_value));
+    }
+
+    let value = ORACLE.load(deps.storage)?.query_value(&deps.querier, debt, ActionKind::Default)?;
+    Ok((value, min_value))
+}
@@ -1,6 +1,6 @@
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError, Coin, Coins,
-    CoinsError, Decimal, DecimalRangeExceeded, OverflowError, StdError, Uint128,
+    CoinsError, Decimal, Decimal256, DecimalRangeExceeded, OverflowError, StdError, Uint128,
 };
 use cw2::VersionError;
 use cw_utils::PaymentError;
@@ -47,6 +47,13 @@ pub enum ContractError {
     #[error("{0} is not an available coin to request")]
     CoinNotAvailable(String),
 
+    #[error("Coin balance of {denom} is {balance}, asserted at least {min}")]
+    CoinBalanceBelowMin {
+        denom: String,
+        balance: Uint128,
+        min: Uint128,
+    },
+
     #[error("{0}")]
     CheckedFromRatioError(#[from] CheckedFromRatioError),
 
@@ -65,6 +72,47 @@ pub enum ContractError {
         maximum: Uint128,
     },
 
+    #[error("New concentrated liquidity positions: {new_amount:?}. Maximum: {maximum:?}.")]
+    ExceedsMaxClPositions {
+        new_amount: Uint128,
+        maximum: Uint128,
+    },
+
+    #[error("Deleverage would sell {value} of collateral value, maximum: {max_value}")]
+    DeleverageAmountTooLarge {
+        value: Uint128,
+        max_value: Uint128,
+    },
+
+    #[error("Deleveraging is not enabled")]
+    DeleverageDisabled,
+
+    #[error("Deleverage did not bring {account_id:?} back below max LTV. Max LTV health factor: {max_ltv_health_factor:?}")]
+    DeleverageInsufficient {
+        account_id: String,
+        max_ltv_health_factor: String,
+    },
+
+    #[error("Debt of {denom} is {debt}, asserted at most {max}")]
+    DebtAboveMax {
+        denom: String,
+        debt: Uint128,
+        max: Uint128,
+    },
+
+    #[error("Debt of {denom:?} in account {account_id:?} worth {value} is below the minimum debt value {min_value}, repay it in full instead")]
+    DebtBelowMinValue {
+        account_id: String,
+        denom: String,
+        value: Uint128,
+        min_value: Uint128,
+    },
+
+    #[error("{account_id:?} has not opted in to deleveraging")]
+    DeleverageNotOptedIn {
+        account_id: String,
+    },
+
     #[error("Callbacks cannot be invoked externally")]
     ExternalInvocation,
 
@@ -85,6 +133,13 @@ pub enum ContractError {
         new_hf: String,
     },
 
+    #[error("{account_id:?} max LTV health factor {max_ltv_health_factor} is below asserted minimum {min_health_factor}")]
+    HealthFactorBelowMin {
+        account_id: String,
+        max_ltv_health_factor: String,
+        min_health_factor: Decimal,
+    },
+
     #[error("{reason:?}")]
     HLS {
         reason: String,
@@ -107,6 +162,12 @@ pub enum ContractError {
         request_coin: Coin,
     },
 
+    #[error("Estimated amount {estimated} is less than min receive {min_receive}")]
+    MinReceiveNotMet {
+        estimated: Uint128,
+        min_receive: Uint128,
+    },
+
     #[error("No coin amount set for action")]
     NoAmount,
 
@@ -127,6 +188,20 @@ pub enum ContractError {
         lqdt_health_factor: String,
     },
 
+    #[error("{account_id:?} is not in the soft-liquidation band. Max LTV health factor: {max_ltv_health_factor:?}, liquidation health factor: {liquidation_health_factor:?}")]
+    NotDeleveragable {
+        account_id: String,
+        max_ltv_health_factor: String,
+        liquidation_health_factor: String,
+    },
+
+    #[error("Debt of {denom:?} worth {value} is not below the minimum debt value {min_value}")]
+    NotDust {
+        denom: String,
+        value: Uint128,
+        min_value: Uint128,
+    },
+
     #[error("{user:?} is not the owner of {account_id:?}")]
     NotTokenOwner {
         user: String,
@@ -148,6 +223,17 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("Perps contract is not configured")]
+    PerpsNotConfigured,
+
+    #[error("Account {account_id} has no perp positions")]
+    NoPerpPositions {
+        account_id: String,
+    },
+
+    #[error("Pyth contract is not configured")]
+    PythNotConfigured,
+
     #[error("{0}")]
     ReentrancyGuard(String),
 
@@ -160,9 +246,17 @@ pub enum ContractError {
     #[error("Cannot request liquidation on own credit account")]
     SelfLiquidation,
 
+    #[error("{0} action can not be simulated")]
+    SimulationNotSupported(String),
+
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("Transfer not allowed: {reason}")]
+    TransferNotAllowed {
+        reason: String,
+    },
+
     #[error("{user:?} is not authorized to {action:?}")]
     Unauthorized {
         user: String,
@@ -172,6 +266,13 @@ pub enum ContractError {
     #[error("There is more time left on the lock period")]
     UnlockNotReady,
 
+    #[error("Net value dropped from {prev_net_value} to {new_net_value}, exceeding max loss of {max_loss_pct}")]
+    ValueLossExceeded {
+        prev_net_value: Uint128,
+        new_net_value: Uint128,
+        max_loss_pct: Decimal,
+    },
+
     #[error("{0}")]
     Version(#[from] VersionError),
 
@@ -201,4 +302,56 @@ pub enum ContractError {
 
     #[error("{0} asset params not found")]
     AssetParamsNotFound(String),
+
+    #[error("{0} can not be staked from credit accounts")]
+    StakingNotEnabled(String),
+
+    #[error("No staking position with validator {validator}")]
+    NoStakingPosition {
+        validator: String,
+    },
+
+    #[error("Delegation cannot be represented by zero delegation shares")]
+    ZeroDelegationShares,
+
+    #[error("Amount {amount} is below the minimum of {min} which can be undelegated or redelegated, move the whole delegation instead")]
+    StakingAmountBelowMin {
+        amount: Uint128,
+        min: Uint128,
+    },
+
+    #[error("Can not redelegate from validator {0} to itself")]
+    RedelegationToSameValidator(String),
+
+    #[error("No staking batch of validator {validator} is due")]
+    NoStakingBatchDue {
+        validator: String,
+    },
+
+    #[error("Pool {0} is not a concentrated liquidity pool")]
+    NotConcentratedLiquidityPool(u64),
+
+    #[error("{denom} is not a token of concentrated liquidity pool {pool_id}")]
+    NotClPoolToken {
+        denom: String,
+        pool_id: u64,
+    },
+
+    #[error("Account {account_id} has no concentrated liquidity position {position_id}")]
+    NoClPosition {
+        account_id: String,
+        position_id: u64,
+    },
+
+    #[error("Insufficient liquidity in position {position_id}. Requested {requested}, available {available}")]
+    InsufficientClLiquidity {
+        position_id: u64,
+        requested: Decimal256,
+        available: Decimal256,
+    },
+
+    #[error("Action is paused: {action}")]
+    ActionPaused {
+        action: String,
+    },
 }
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use cosmwasm_std::{
     to_json_binary, Addr, Coins, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult,
@@ -6,31 +6,49 @@ use cosmwasm_std::{
 };
 use mars_types::{
     account_nft::ExecuteMsg as NftExecuteMsg,
-    credit_manager::{Action, CallbackMsg, LiquidateRequest},
-    health::AccountKind,
+    credit_manager::{Action, CallbackMsg, LiquidateRequest, TransferPosition},
+    health::{AccountKind, HealthState},
     oracle::ActionKind,
 };
 use mars_vault::msg::{ExecuteMsg, ExtensionExecuteMsg};
 
 use crate::{
+    assertions::{
+        assert_coin_balance_at_least, assert_debt_at_most, assert_health_factor_above,
+        assert_value_change, query_net_value, withdraw_excluded_from_value_change,
+    },
     borrow::borrow,
     claim_astro_lp_rewards::claim_lp_rewards,
     claim_rewards::claim_rewards,
+    concentrated_liquidity::{
+        add_to_cl_position, claim_cl_position_rewards, create_cl_position, withdraw_cl_position,
+    },
+    deleverage::assert_deleveraged,
     deposit::{assert_deposit_caps, deposit, update_or_reset_denom_deposits},
+    dust::assert_min_debt_values,
     error::{ContractError, ContractResult},
     health::{assert_max_ltv, query_health_state},
     hls::assert_hls_rules,
     lend::lend,
     liquidate::assert_not_self_liquidation,
     liquidate_astro_lp::liquidate_astro_lp,
+    liquidate_cl_position::liquidate_cl_position,
     liquidate_deposit::liquidate_deposit,
     liquidate_lend::liquidate_lend,
+    liquidate_staking::liquidate_staking,
+    pause::assert_actions_not_paused,
+    perp::{close_perp_position, close_perp_positions_msgs, execute_perp_order, liquidate_perps},
     reclaim::reclaim,
     refund::refund_coin_balances,
     repay::{repay, repay_for_recipient},
     stake_astro_lp::stake_lp,
-    state::{ACCOUNT_KINDS, ACCOUNT_NFT, REENTRANCY_GUARD, VAULTS},
+    staking::{
+        claim_staking_rewards, delegate, redelegate, settle_unbondings, snapshot_unbonding_balance,
+        sync_unbonding_releases, undelegate,
+    },
+    state::{ACCOUNT_KINDS, ACCOUNT_NFT, REENTRANCY_GUARD, VALUE_CHANGE_REFERENCE, VAULTS},
     swap::swap_exact_in,
+    transfer::{assert_transfer_allowed, merge_accounts, transfer_to_account},
     unstake_astro_lp::unstake_lp,
     update_coin_balances::{update_coin_balance, update_coin_balance_after_vault_liquidation},
     utils::{assert_is_token_owner, get_account_kind},
@@ -119,8 +137,14 @@ pub fn dispatch_actions(
     };
     let account_id = &account_id;
 
+    assert_actions_not_paused(deps.as_ref(), account_id, &actions)?;
+
     REENTRANCY_GUARD.try_lock(deps.storage)?;
 
+    // Unbonding completed since the last interaction is released to the coin balance
+    sync_unbonding_releases(&mut deps, &env, &info.funds)?;
+    settle_unbondings(&mut deps, account_id)?;
+
     let mut callbacks: Vec<CallbackMsg> = vec![];
     let mut received_coins = Coins::try_from(info.funds)?;
 
@@ -134,6 +158,10 @@ pub fn dispatch_actions(
                     recipient_account_id: None,
                     ..
                 }
+                | Action::AssertHealthFactorAbove(..)
+                | Action::AssertCoinBalanceAtLeast(..)
+                | Action::AssertDebtAtMost(..)
+                | Action::AssertValueChange { .. }
         )
     });
 
@@ -167,6 +195,12 @@ pub fn dispatch_actions(
     // otherwise it should compare deposit amount before and after the TX.
     let mut denoms_for_cap_check: BTreeMap<String, Option<Uint128>> = BTreeMap::new();
 
+    // Debts which may be left below the min debt value: borrowed or partially repaid denoms per account
+    let mut denoms_for_min_debt_check: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    // Accounts receiving positions from this account, with their health state before the actions
+    let mut transfer_recipients: BTreeMap<String, HealthState> = BTreeMap::new();
+
     for action in actions {
         match action {
             Action::Deposit(coin) => {
@@ -193,14 +227,24 @@ pub fn dispatch_actions(
                 coin,
                 recipient: deps.api.addr_validate(&recipient)?,
             }),
-            Action::Borrow(coin) => callbacks.push(CallbackMsg::Borrow {
-                account_id: account_id.to_string(),
-                coin,
-            }),
+            Action::Borrow(coin) => {
+                denoms_for_min_debt_check
+                    .entry(account_id.to_string())
+                    .or_default()
+                    .insert(coin.denom.clone());
+                callbacks.push(CallbackMsg::Borrow {
+                    account_id: account_id.to_string(),
+                    coin,
+                })
+            }
             Action::Repay {
                 recipient_account_id,
                 coin,
             } => {
+                denoms_for_min_debt_check
+                    .entry(recipient_account_id.clone().unwrap_or_else(|| account_id.to_string()))
+                    .or_default()
+                    .insert(coin.denom.clone());
                 if let Some(recipient) = recipient_account_id {
                     callbacks.push(CallbackMsg::RepayForRecipient {
                         benefactor_account_id: account_id.to_string(),
@@ -270,6 +314,32 @@ pub fn dispatch_actions(
                         request: LiquidateRequest::StakedAstroLp(lp_denom),
                     })
                 }
+                LiquidateRequest::Staking {
+                    validator,
+                    position_type,
+                } => callbacks.push(CallbackMsg::Liquidate {
+                    liquidator_account_id: account_id.to_string(),
+                    liquidatee_account_id: liquidatee_account_id.to_string(),
+                    debt_coin,
+                    request: LiquidateRequest::Staking {
+                        validator,
+                        position_type,
+                    },
+                }),
+                LiquidateRequest::ClPosition(position_id) => {
+                    callbacks.push(CallbackMsg::Liquidate {
+                        liquidator_account_id: account_id.to_string(),
+                        liquidatee_account_id: liquidatee_account_id.to_string(),
+                        debt_coin,
+                        request: LiquidateRequest::ClPosition(position_id),
+                    })
+                }
+                LiquidateRequest::Perps => callbacks.push(CallbackMsg::Liquidate {
+                    liquidator_account_id: account_id.to_string(),
+                    liquidatee_account_id: liquidatee_account_id.to_string(),
+                    debt_coin,
+                    request: LiquidateRequest::Perps,
+                }),
             },
             Action::SwapExactIn {
                 coin_in,
@@ -369,6 +439,153 @@ pub fn dispatch_actions(
                     account_id: account_id.to_string(),
                 })
             }
+            Action::AssertHealthFactorAbove(min_health_factor) => {
+                callbacks.push(CallbackMsg::AssertHealthFactorAbove {
+                    account_id: account_id.to_string(),
+                    min_health_factor,
+                })
+            }
+            Action::AssertCoinBalanceAtLeast(coin) => {
+                callbacks.push(CallbackMsg::AssertCoinBalanceAtLeast {
+                    account_id: account_id.to_string(),
+                    coin,
+                })
+            }
+            Action::AssertDebtAtMost(coin) => callbacks.push(CallbackMsg::AssertDebtAtMost {
+                account_id: account_id.to_string(),
+                coin,
+            }),
+            Action::AssertValueChange {
+                max_loss_pct,
+            } => callbacks.push(CallbackMsg::AssertValueChange {
+                account_id: account_id.to_string(),
+                max_loss_pct,
+            }),
+            Action::TransferToAccount {
+                recipient_account_id,
+                positions,
+            } => {
+                assert_transfer_allowed(&deps, &info.sender, account_id, &recipient_account_id)?;
+                add_transfer_recipient(&deps, &mut transfer_recipients, &recipient_account_id)?;
+                let positions = positions
+                    .into_iter()
+                    .map(|position| {
+                        Ok(match position {
+                            TransferPosition::Deposit(coin) => TransferPosition::Deposit(coin),
+                            TransferPosition::Debt(coin) => TransferPosition::Debt(coin),
+                            TransferPosition::Lend(coin) => TransferPosition::Lend(coin),
+                            TransferPosition::Vault(vault) => {
+                                TransferPosition::Vault(vault.check(deps.api)?)
+                            }
+                            TransferPosition::StakedAstroLp(coin) => {
+                                TransferPosition::StakedAstroLp(coin)
+                            }
+                            TransferPosition::Staking(validator) => {
+                                TransferPosition::Staking(validator)
+                            }
+                            TransferPosition::ClPosition(position_id) => {
+                                TransferPosition::ClPosition(position_id)
+                            }
+                        })
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+                callbacks.push(CallbackMsg::TransferToAccount {
+                    account_id: account_id.to_string(),
+                    recipient_account_id,
+                    positions,
+                })
+            }
+            Action::Delegate {
+                validator,
+                coin,
+            } => callbacks.push(CallbackMsg::Delegate {
+                account_id: account_id.to_string(),
+                validator,
+                coin,
+            }),
+            Action::Undelegate {
+                validator,
+                coin,
+            } => callbacks.push(CallbackMsg::Undelegate {
+                account_id: account_id.to_string(),
+                validator,
+                coin,
+            }),
+            Action::Redelegate {
+                src_validator,
+                dst_validator,
+                coin,
+            } => callbacks.push(CallbackMsg::Redelegate {
+                account_id: account_id.to_string(),
+                src_validator,
+                dst_validator,
+                coin,
+            }),
+            Action::ClaimStakingRewards {} => callbacks.push(CallbackMsg::ClaimStakingRewards {
+                account_id: account_id.to_string(),
+            }),
+            Action::CreateClPosition {
+                pool_id,
+                lower_tick,
+                upper_tick,
+                coins_in,
+                token_min_amount0,
+                token_min_amount1,
+            } => callbacks.push(CallbackMsg::CreateClPosition {
+                account_id: account_id.to_string(),
+                pool_id,
+                lower_tick,
+                upper_tick,
+                coins_in,
+                token_min_amount0,
+                token_min_amount1,
+            }),
+            Action::AddToClPosition {
+                position_id,
+                coins_in,
+                token_min_amount0,
+                token_min_amount1,
+            } => callbacks.push(CallbackMsg::AddToClPosition {
+                account_id: account_id.to_string(),
+                position_id,
+                coins_in,
+                token_min_amount0,
+                token_min_amount1,
+            }),
+            Action::WithdrawClPosition {
+                position_id,
+                liquidity,
+            } => callbacks.push(CallbackMsg::WithdrawClPosition {
+                account_id: account_id.to_string(),
+                position_id,
+                liquidity,
+            }),
+            Action::ClaimClPositionRewards {
+                position_id,
+            } => callbacks.push(CallbackMsg::ClaimClPositionRewards {
+                account_id: account_id.to_string(),
+                position_id,
+            }),
+            Action::ExecutePerpOrder {
+                denom,
+                size,
+                reduce_only,
+            } => callbacks.push(CallbackMsg::ExecutePerpOrder {
+                account_id: account_id.to_string(),
+                denom,
+                size,
+                reduce_only,
+            }),
+            Action::MergeAccounts {
+                recipient_account_id,
+            } => {
+                assert_transfer_allowed(&deps, &info.sender, account_id, &recipient_account_id)?;
+                add_transfer_recipient(&deps, &mut transfer_recipients, &recipient_account_id)?;
+                callbacks.push(CallbackMsg::MergeAccounts {
+                    account_id: account_id.to_string(),
+                    recipient_account_id,
+                })
+            }
         }
     }
 
@@ -378,6 +595,13 @@ pub fn dispatch_actions(
         return Err(ContractError::ExtraFundsReceived(received_coins));
     }
 
+    // Deposits are applied right away, all other actions in callbacks. The net value at this point
+    // is the reference for value change assertions, so that deposits don't hide losses.
+    if callbacks.iter().any(|callback| matches!(callback, CallbackMsg::AssertValueChange { .. })) {
+        let net_value = query_net_value(deps.as_ref(), account_id)?;
+        VALUE_CHANGE_REFERENCE.save(deps.storage, &(account_id.to_string(), net_value))?;
+    }
+
     // Ensures the account state abides by the rules of the HLS account kind
     let kind = get_account_kind(deps.storage, account_id)?;
     if kind == AccountKind::HighLeveredStrategy {
@@ -397,6 +621,28 @@ pub fn dispatch_actions(
         });
     }
 
+    for (account_id, denoms) in denoms_for_min_debt_check {
+        callbacks.push(CallbackMsg::AssertMinDebtValues {
+            account_id,
+            denoms,
+        });
+    }
+
+    // Recipients of transferred positions are held to the same rules as the account itself
+    for (recipient_account_id, prev_health_state) in transfer_recipients {
+        if get_account_kind(deps.storage, &recipient_account_id)?
+            == AccountKind::HighLeveredStrategy
+        {
+            callbacks.push(CallbackMsg::AssertHlsRules {
+                account_id: recipient_account_id.clone(),
+            });
+        }
+        callbacks.push(CallbackMsg::AssertMaxLTV {
+            account_id: recipient_account_id,
+            prev_health_state,
+        });
+    }
+
     callbacks.extend([
         // After user selected actions, we assert that the relevant deposit caps
         // are not exceeded.
@@ -418,6 +664,19 @@ pub fn dispatch_actions(
         .add_attribute("account_id", account_id.to_string()))
 }
 
+fn add_transfer_recipient(
+    deps: &DepsMut,
+    recipients: &mut BTreeMap<String, HealthState>,
+    recipient_account_id: &str,
+) -> ContractResult<()> {
+    if !recipients.contains_key(recipient_account_id) {
+        let health_state =
+            query_health_state(deps.as_ref(), recipient_account_id, ActionKind::Default)?;
+        recipients.insert(recipient_account_id.to_string(), health_state);
+    }
+    Ok(())
+}
+
 fn validate_account(
     deps: &DepsMut,
     info: &MessageInfo,
@@ -464,7 +723,7 @@ fn validate_account(
 }
 
 pub fn execute_callback(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     env: Env,
     callback: CallbackMsg,
@@ -477,7 +736,9 @@ pub fn execute_callback(
             account_id,
             coin,
             recipient,
-        } => withdraw(deps, &account_id, &coin, recipient),
+        } => withdraw_excluded_from_value_change(&mut deps, &account_id, |deps| {
+            withdraw(deps.branch(), &account_id, &coin, recipient)
+        }),
         CallbackMsg::Borrow {
             coin,
             account_id,
@@ -506,6 +767,13 @@ pub fn execute_callback(
             account_id,
             prev_health_state,
         } => assert_max_ltv(deps.as_ref(), &account_id, prev_health_state),
+        CallbackMsg::AssertDeleveraged {
+            account_id,
+        } => assert_deleveraged(deps.as_ref(), &account_id),
+        CallbackMsg::AssertMinDebtValues {
+            account_id,
+            denoms,
+        } => assert_min_debt_values(deps.as_ref(), &account_id, denoms),
         CallbackMsg::AssertDepositCaps {
             denoms,
         } => assert_deposit_caps(deps.as_ref(), denoms),
@@ -532,7 +800,17 @@ pub fn execute_callback(
             request,
         } => {
             assert_not_self_liquidation(&liquidator_account_id, &liquidatee_account_id)?;
-            match request {
+
+            // Perp positions are closed after the liquidation, so that the liquidated amounts are
+            // based on the health of the account with its perp positions still open. A position
+            // is only closed if the account is still liquidatable by then.
+            let close_perps_msgs = close_perp_positions_msgs(
+                deps.as_ref(),
+                &env.contract.address,
+                &liquidator_account_id,
+                &liquidatee_account_id,
+            )?;
+            let response = match request {
                 LiquidateRequest::Deposit(request_coin_denom) => liquidate_deposit(
                     deps,
                     env,
@@ -569,7 +847,30 @@ pub fn execute_callback(
                     debt_coin,
                     &request_coin_denom,
                 ),
-            }
+                LiquidateRequest::Staking {
+                    validator,
+                    position_type,
+                } => liquidate_staking(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    &validator,
+                    position_type,
+                ),
+                LiquidateRequest::ClPosition(position_id) => liquidate_cl_position(
+                    deps,
+                    env,
+                    &liquidator_account_id,
+                    &liquidatee_account_id,
+                    debt_coin,
+                    position_id,
+                ),
+                LiquidateRequest::Perps => liquidate_perps(deps.as_ref(), &liquidatee_account_id),
+            }?;
+
+            Ok(response.add_messages(close_perps_msgs))
         }
         CallbackMsg::SwapExactIn {
             account_id,
@@ -625,9 +926,36 @@ pub fn execute_callback(
         } => refund_coin_balances(deps, env, &account_id),
         CallbackMsg::AssertHlsRules {
             account_id,
-        } => assert_hls_rules(deps.as_ref(), &account_id),
+        } => assert_hls_rules(deps.as_ref(), &env, &account_id),
+        CallbackMsg::AssertHealthFactorAbove {
+            account_id,
+            min_health_factor,
+        } => assert_health_factor_above(deps.as_ref(), &account_id, min_health_factor),
+        CallbackMsg::AssertCoinBalanceAtLeast {
+            account_id,
+            coin,
+        } => assert_coin_balance_at_least(deps.as_ref(), &account_id, &coin),
+        CallbackMsg::AssertDebtAtMost {
+            account_id,
+            coin,
+        } => assert_debt_at_most(deps.as_ref(), &account_id, &coin),
+        CallbackMsg::AssertValueChange {
+            account_id,
+            max_loss_pct,
+        } => assert_value_change(deps.as_ref(), &account_id, max_loss_pct),
+        CallbackMsg::TransferToAccount {
+            account_id,
+            recipient_account_id,
+            positions,
+        } => transfer_to_account(deps, env, &account_id, &recipient_account_id, positions),
+        CallbackMsg::MergeAccounts {
+            account_id,
+            recipient_account_id,
+        } => merge_accounts(deps, env, &account_id, &recipient_account_id),
         CallbackMsg::RemoveReentrancyGuard {} => {
             REENTRANCY_GUARD.try_unlock(deps.storage)?;
+            VALUE_CHANGE_REFERENCE.remove(deps.storage);
+            snapshot_unbonding_balance(deps, &env)?;
             Ok(Response::new().add_attribute("action", "remove_reentrancy_guard"))
         }
         CallbackMsg::StakeAstroLp {
@@ -642,5 +970,95 @@ pub fn execute_callback(
             account_id,
             lp_denom,
         } => claim_lp_rewards(deps, &account_id, &lp_denom),
+        CallbackMsg::Delegate {
+            account_id,
+            validator,
+            coin,
+        } => delegate(deps, env, &account_id, &validator, coin),
+        CallbackMsg::Undelegate {
+            account_id,
+            validator,
+            coin,
+        } => undelegate(deps, env, &account_id, &validator, coin),
+        CallbackMsg::Redelegate {
+            account_id,
+            src_validator,
+            dst_validator,
+            coin,
+        } => redelegate(deps, env, &account_id, &src_validator, &dst_validator, coin),
+        CallbackMsg::ClaimStakingRewards {
+            account_id,
+        } => claim_staking_rewards(deps, env, &account_id),
+        CallbackMsg::CreateClPosition {
+            account_id,
+            pool_id,
+            lower_tick,
+            upper_tick,
+            coins_in,
+            token_min_amount0,
+            token_min_amount1,
+        } => create_cl_position(
+            deps,
+            env,
+            &account_id,
+            pool_id,
+            lower_tick,
+            upper_tick,
+            coins_in,
+            token_min_amount0,
+            token_min_amount1,
+        ),
+        CallbackMsg::AddToClPosition {
+            account_id,
+            position_id,
+            coins_in,
+            token_min_amount0,
+            token_min_amount1,
+        } => add_to_cl_position(
+            deps,
+            env,
+            &account_id,
+            position_id,
+            coins_in,
+            token_min_amount0,
+            token_min_amount1,
+        ),
+        CallbackMsg::WithdrawClPosition {
+            account_id,
+            position_id,
+            liquidity,
+        } => withdraw_cl_position(deps, env, &account_id, position_id, liquidity),
+        CallbackMsg::ClaimClPositionRewards {
+            account_id,
+            position_id,
+        } => claim_cl_position_rewards(deps, env, &account_id, position_id),
+        CallbackMsg::ExecutePerpOrder {
+            account_id,
+            denom,
+            size,
+            reduce_only,
+        } => execute_perp_order(deps, &account_id, &denom, size, reduce_only),
+        CallbackMsg::ClosePerpPosition {
+            liquidator_account_id,
+            account_id,
+            denom,
+        } => close_perp_position(deps, &env, &liquidator_account_id, &account_id, &denom),
+        CallbackMsg::DispatchActions {
+            sender,
+            account_id,
+            account_kind,
+            actions,
+            funds,
+        } => dispatch_actions(
+            deps,
+            env,
+            MessageInfo {
+                sender,
+                funds,
+            },
+            account_id,
+            account_kind,
+            actions,
+        ),
     }
 }
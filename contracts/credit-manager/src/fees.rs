@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+
+use cosmwasm_std::{Coin, Decimal, StdResult, Storage};
+use mars_types::credit_manager::FeeConfig;
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{FEE_CONFIG, REWARDS_COLLECTOR},
+    utils::{decrement_coin_balance, increment_coin_balance},
+};
+
+pub fn assert_fee_config(config: &FeeConfig) -> ContractResult<()> {
+    if config.swap_fee >= Decimal::one() {
+        return Err(ContractError::InvalidConfig {
+            reason: "Swap fee must be less than 1".to_string(),
+        });
+    }
+
+    let mut denoms = HashSet::new();
+    for borrow_fee in config.borrow_fees.iter() {
+        if borrow_fee.fee >= Decimal::one() {
+            return Err(ContractError::InvalidConfig {
+                reason: format!("Borrow fee for {} must be less than 1", borrow_fee.denom),
+            });
+        }
+        if !denoms.insert(&borrow_fee.denom) {
+            return Err(ContractError::InvalidConfig {
+                reason: format!("Duplicate borrow fee for {}", borrow_fee.denom),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Fees are disabled until the owner sets the config
+pub fn load_fee_config(storage: &dyn Storage) -> StdResult<FeeConfig> {
+    Ok(FEE_CONFIG.may_load(storage)?.unwrap_or_default())
+}
+
+/// Fee share of the coin, rounded down
+pub fn fee_amount(coin: &Coin, fee: Decimal) -> ContractResult<Coin> {
+    Ok(Coin {
+        denom: coin.denom.clone(),
+        amount: coin.amount.checked_mul_floor(fee)?,
+    })
+}
+
+/// Moves the fee share of the coin from the account's balance to the rewards-collector account.
+/// Returns the fee paid.
+pub fn charge_fee(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    coin: &Coin,
+    fee: Decimal,
+) -> ContractResult<Coin> {
+    let fee_coin = fee_amount(coin, fee)?;
+    if fee_coin.amount.is_zero() {
+        return Ok(fee_coin);
+    }
+
+    let rewards_collector_account = REWARDS_COLLECTOR.load(storage)?.account_id;
+    decrement_coin_balance(storage, account_id, &fee_coin)?;
+    increment_coin_balance(storage, &rewards_collector_account, &fee_coin)?;
+    Ok(fee_coin)
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Deps, Response};
+use cosmwasm_std::{Deps, Env, Response};
 use mars_types::{credit_manager::Positions, health::AccountKind, params::HlsAssetType};
 
 use crate::{
@@ -7,7 +7,7 @@ use crate::{
     state::PARAMS,
 };
 
-pub fn assert_hls_rules(deps: Deps, account_id: &str) -> ContractResult<Response> {
+pub fn assert_hls_rules(deps: Deps, env: &Env, account_id: &str) -> ContractResult<Response> {
     // Rule #1 - There can only be 0 or 1 debt denom in the account
     let Positions {
         // destruct Positions so whenever we add new positions we don't forget to add them here
@@ -18,7 +18,17 @@ pub fn assert_hls_rules(deps: Deps, account_id: &str) -> ContractResult<Response
         lends,
         vaults,
         staked_astro_lps,
-    } = query_positions(deps, account_id)?;
+        staking,
+        cl_positions,
+        perps,
+    } = query_positions(deps, env, account_id)?;
+
+    // Perps are leveraged by themselves and not correlated to any debt
+    if !perps.is_empty() {
+        return Err(ContractError::HLS {
+            reason: "Account has perp positions".to_string(),
+        });
+    }
 
     if debts.len() > 1 {
         return Err(ContractError::HLS {
@@ -97,6 +107,44 @@ pub fn assert_hls_rules(deps: Deps, account_id: &str) -> ContractResult<Response
                     ),
                 })?;
         }
+
+        // === Native staking positions ===
+        for position in staking.iter() {
+            hls.correlations
+                .iter()
+                .find(|h| match h {
+                    HlsAssetType::Coin {
+                        denom,
+                    } => &position.delegated.denom == denom,
+                    _ => false,
+                })
+                .ok_or_else(|| ContractError::HLS {
+                    reason: format!(
+                        "{} staking position is not a correlated asset to debt {}",
+                        position.delegated.denom, debt.denom
+                    ),
+                })?;
+        }
+
+        // === Concentrated liquidity positions ===
+        for position in cl_positions.iter() {
+            for asset in [&position.asset0, &position.asset1] {
+                hls.correlations
+                    .iter()
+                    .find(|h| match h {
+                        HlsAssetType::Coin {
+                            denom,
+                        } => &asset.denom == denom,
+                        _ => false,
+                    })
+                    .ok_or_else(|| ContractError::HLS {
+                        reason: format!(
+                            "{} of concentrated liquidity position {} is not a correlated asset to debt {}",
+                            asset.denom, position.position_id, debt.denom
+                        ),
+                    })?;
+            }
+        }
     }
 
     Ok(Response::new()
@@ -5,8 +5,8 @@ use mars_types::credit_manager::InstantiateMsg;
 use crate::{
     error::ContractResult,
     state::{
-        HEALTH_CONTRACT, INCENTIVES, MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE, OWNER, PARAMS,
-        RED_BANK, SWAPPER, ZAPPER,
+        HEALTH_CONTRACT, INCENTIVES, MAX_CL_POSITIONS, MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS,
+        ORACLE, OWNER, PARAMS, RED_BANK, SWAPPER, ZAPPER,
     },
     utils::assert_max_slippage,
 };
@@ -25,6 +25,7 @@ pub fn store_config(deps: DepsMut, env: Env, msg: &InstantiateMsg) -> ContractRe
     SWAPPER.save(deps.storage, &msg.swapper.check(deps.api)?)?;
     ZAPPER.save(deps.storage, &msg.zapper.check(deps.api)?)?;
     MAX_UNLOCKING_POSITIONS.save(deps.storage, &msg.max_unlocking_positions)?;
+    MAX_CL_POSITIONS.save(deps.storage, &msg.max_cl_positions)?;
 
     assert_max_slippage(msg.max_slippage)?;
     MAX_SLIPPAGE.save(deps.storage, &msg.max_slippage)?;
@@ -1,26 +1,39 @@
+pub mod assertions;
 pub mod borrow;
 pub mod claim_astro_lp_rewards;
 pub mod claim_rewards;
+pub mod concentrated_liquidity;
 pub mod contract;
+pub mod deleverage;
 pub mod deposit;
+pub mod dust;
 pub mod error;
 pub mod execute;
+pub mod fees;
 pub mod health;
 pub mod hls;
 pub mod instantiate;
 pub mod lend;
 pub mod liquidate;
 pub mod liquidate_astro_lp;
+pub mod liquidate_cl_position;
 pub mod liquidate_deposit;
 pub mod liquidate_lend;
+pub mod liquidate_staking;
 pub mod migrations;
+pub mod pause;
+pub mod perp;
+pub mod price_updates;
 pub mod query;
 pub mod reclaim;
 pub mod refund;
 pub mod repay;
+pub mod simulate;
 pub mod stake_astro_lp;
+pub mod staking;
 pub mod state;
 pub mod swap;
+pub mod transfer;
 pub mod unstake_astro_lp;
 pub mod update_coin_balances;
 pub mod update_config;
@@ -0,0 +1,96 @@
+use cosmwasm_std::{Coin, Decimal, Decimal256, DepsMut, Env, Response};
+use mars_types::oracle::ActionKind;
+
+use crate::{
+    concentrated_liquidity::{
+        assert_cl_position_owner, claim_rewards_msgs, query_cl_position_with_rewards,
+        withdraw_liquidity_msg,
+    },
+    error::{ContractError, ContractResult},
+    liquidate::calculate_liquidation,
+    liquidate_deposit::repay_debt,
+    state::ORACLE,
+};
+
+pub fn liquidate_cl_position(
+    deps: DepsMut,
+    env: Env,
+    liquidator_account_id: &str,
+    liquidatee_account_id: &str,
+    debt_coin: Coin,
+    position_id: u64,
+) -> ContractResult<Response> {
+    assert_cl_position_owner(deps.storage, liquidatee_account_id, position_id)?;
+    let (position, rewards) = query_cl_position_with_rewards(&deps.querier, position_id)?;
+
+    // The position is valued the same way as in the health computer (underlying tokens at
+    // oracle prices) and denominated in token0 so it can be liquidated like a single coin
+    let oracle = ORACLE.load(deps.storage)?;
+    let price0 =
+        oracle.query_price(&deps.querier, &position.asset0.denom, ActionKind::Liquidation)?.price;
+    let price1 =
+        oracle.query_price(&deps.querier, &position.asset1.denom, ActionKind::Liquidation)?.price;
+    let (amount0, amount1) = position.underlying_at_prices(price0, price1)?;
+    let total_amount0 = amount0
+        .amount
+        .checked_add(amount1.amount.checked_multiply_ratio(price1.atomics(), price0.atomics())?)?;
+
+    if total_amount0.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+
+    let (debt, liquidator_request, liquidatee_request) = calculate_liquidation(
+        &deps,
+        liquidatee_account_id,
+        &debt_coin,
+        &position.asset0.denom,
+        total_amount0,
+    )?;
+
+    // Liquidator pays down debt on behalf of liquidatee
+    let repay_msg =
+        repay_debt(deps.storage, &env, liquidator_account_id, liquidatee_account_id, &debt)?;
+
+    // Rewards are not accounted for in the liquidation calculation, they belong to the liquidatee
+    let (claim_msgs, _) =
+        claim_rewards_msgs(deps.storage, &env, liquidatee_account_id, position_id, rewards)?;
+
+    // Share of the liquidity matching the liquidated amount is withdrawn from the pool. Both
+    // underlying tokens go to the liquidator, protocol fee to rewards-collector account.
+    let liquidity = if liquidatee_request.amount == total_amount0 {
+        position.liquidity
+    } else {
+        position
+            .liquidity
+            .checked_mul(Decimal256::from_ratio(liquidatee_request.amount, total_amount0))?
+    };
+    if liquidity.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+    let protocol_fee = Decimal::checked_from_ratio(
+        liquidatee_request.amount.checked_sub(liquidator_request.amount)?,
+        liquidatee_request.amount,
+    )?;
+    let withdraw_msg = withdraw_liquidity_msg(
+        deps.storage,
+        &env,
+        liquidatee_account_id,
+        liquidator_account_id,
+        &position,
+        liquidity,
+        protocol_fee,
+    )?;
+
+    Ok(Response::new()
+        .add_message(repay_msg)
+        .add_messages(claim_msgs)
+        .add_submessage(withdraw_msg)
+        .add_attribute("action", "liquidate_cl_position")
+        .add_attribute("account_id", liquidator_account_id)
+        .add_attribute("liquidatee_account_id", liquidatee_account_id)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("coin_debt_repaid", debt.to_string())
+        .add_attribute("coin_liquidated", liquidatee_request.to_string())
+        .add_attribute("liquidity_liquidated", liquidity.to_string())
+        .add_attribute("protocol_fee", protocol_fee.to_string()))
+}
@@ -0,0 +1,145 @@
+use cosmwasm_std::{Coin, DepsMut, Env, Response, Uint128};
+use mars_types::credit_manager::{ActionAmount, StakingPositionType};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    liquidate::calculate_liquidation,
+    liquidate_deposit::repay_debt,
+    staking::{
+        accrue_account_rewards, add_delegation_shares, add_unbonding_entries,
+        cancel_staking_requests, delegation_to_move, query_delegated_amount,
+        remove_delegation_shares, save_unbonding_entries, take_unbonding_entries,
+        update_reward_indices,
+    },
+    state::{REWARDS_COLLECTOR, UNBONDINGS},
+};
+
+pub fn liquidate_staking(
+    mut deps: DepsMut,
+    env: Env,
+    liquidator_account_id: &str,
+    liquidatee_account_id: &str,
+    debt_coin: Coin,
+    validator: &str,
+    position_type: StakingPositionType,
+) -> ContractResult<Response> {
+    let request_coin_denom = deps.querier.query_bonded_denom()?;
+
+    // Check how much is available in the requested bucket
+    let mut unbonding_entries =
+        UNBONDINGS.may_load(deps.storage, (liquidatee_account_id, validator))?.unwrap_or_default();
+    let total_staked_amount = match position_type {
+        StakingPositionType::Delegated => {
+            query_delegated_amount(deps.as_ref(), &env, liquidatee_account_id, validator)?
+        }
+        StakingPositionType::Unbonding => unbonding_entries
+            .iter()
+            .try_fold(Uint128::zero(), |acc, e| acc.checked_add(e.amount))?,
+    };
+
+    if total_staked_amount.is_zero() {
+        return Err(ContractError::NoStakingPosition {
+            validator: validator.to_string(),
+        });
+    }
+
+    let (debt, liquidator_request, liquidatee_request) = calculate_liquidation(
+        &deps,
+        liquidatee_account_id,
+        &debt_coin,
+        &request_coin_denom,
+        total_staked_amount,
+    )?;
+
+    // Liquidator pays down debt on behalf of liquidatee
+    let repay_msg =
+        repay_debt(deps.storage, &env, liquidator_account_id, liquidatee_account_id, &debt)?;
+
+    // The staking position is moved between the accounts without unbonding (Rover stays the
+    // delegator). Protocol fee is transferred to rewards-collector account in the same form.
+    let rewards_collector_account = REWARDS_COLLECTOR.load(deps.storage)?.account_id;
+    let protocol_fee_coin = Coin {
+        denom: request_coin_denom,
+        amount: liquidatee_request.amount.checked_sub(liquidator_request.amount)?,
+    };
+
+    let mut res = Response::new().add_message(repay_msg);
+
+    match position_type {
+        StakingPositionType::Delegated => {
+            // Queued undelegations and redelegations don't protect the delegation from liquidation
+            cancel_staking_requests(deps.storage, liquidatee_account_id, validator);
+
+            res = res.add_messages(update_reward_indices(&mut deps, &env, validator)?);
+            for account_id in
+                [liquidatee_account_id, liquidator_account_id, &rewards_collector_account]
+            {
+                accrue_account_rewards(deps.storage, account_id, validator)?;
+            }
+
+            let (_, shares) = delegation_to_move(
+                deps.as_ref(),
+                &env,
+                liquidatee_account_id,
+                validator,
+                &ActionAmount::Exact(liquidatee_request.amount),
+            )?;
+            let liquidator_shares = shares
+                .checked_multiply_ratio(liquidator_request.amount, liquidatee_request.amount)?;
+            let protocol_fee_shares = shares.checked_sub(liquidator_shares)?;
+
+            remove_delegation_shares(deps.storage, liquidatee_account_id, validator, shares)?;
+            add_delegation_shares(
+                deps.storage,
+                liquidator_account_id,
+                validator,
+                liquidator_shares,
+            )?;
+            if !protocol_fee_shares.is_zero() {
+                add_delegation_shares(
+                    deps.storage,
+                    &rewards_collector_account,
+                    validator,
+                    protocol_fee_shares,
+                )?;
+            }
+        }
+        StakingPositionType::Unbonding => {
+            // Entries closest to completion are liquidated first
+            let mut liquidated_entries =
+                take_unbonding_entries(&mut unbonding_entries, liquidatee_request.amount)?;
+            save_unbonding_entries(
+                deps.storage,
+                liquidatee_account_id,
+                validator,
+                unbonding_entries,
+            )?;
+
+            let liquidator_entries =
+                take_unbonding_entries(&mut liquidated_entries, liquidator_request.amount)?;
+            add_unbonding_entries(
+                deps.storage,
+                liquidator_account_id,
+                validator,
+                liquidator_entries,
+            )?;
+            if !liquidated_entries.is_empty() {
+                add_unbonding_entries(
+                    deps.storage,
+                    &rewards_collector_account,
+                    validator,
+                    liquidated_entries,
+                )?;
+            }
+        }
+    }
+
+    Ok(res
+        .add_attribute("action", "liquidate_staking")
+        .add_attribute("account_id", liquidator_account_id)
+        .add_attribute("liquidatee_account_id", liquidatee_account_id)
+        .add_attribute("validator", validator)
+        .add_attribute("coin_debt_repaid", debt.to_string())
+        .add_attribute("coin_liquidated", liquidatee_request.to_string())
+        .add_attribute("protocol_fee_coin", protocol_fee_coin.to_string()))
+}
@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_std::{Deps, Uint128};
+use mars_types::{
+    credit_manager::{Action, ActionAmount},
+    params::{PausableAction, PausableContract},
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{DEBT_SHARES, PARAMS},
+    utils::debt_shares_to_amount,
+};
+
+/// Actions which can be paused via the params contract. Repayments, withdrawals and liquidations
+/// are never paused, so accounts can always be de-risked (see `deposits_exceed_repayments`).
+pub fn pausable_action(action: &Action) -> Option<PausableAction> {
+    match action {
+        Action::Deposit(..) => Some(PausableAction::Deposits),
+        Action::Borrow(..) => Some(PausableAction::Borrows),
+        Action::EnterVault {
+            ..
+        } => Some(PausableAction::VaultEntries),
+        Action::SwapExactIn {
+            ..
+        }
+        | Action::ProvideLiquidity {
+            ..
+        }
+        | Action::CreateClPosition {
+            ..
+        }
+        | Action::AddToClPosition {
+            ..
+        } => Some(PausableAction::Swaps),
+        _ => None,
+    }
+}
+
+/// Deposits only funding repayments and liquidations of the same actions aren't paused either.
+/// Per denom, deposits are exempt up to the amount repaid (at most the debt) and the debt coins of
+/// liquidations.
+fn deposits_exceed_repayments(
+    deps: Deps,
+    account_id: &str,
+    actions: &[Action],
+) -> ContractResult<bool> {
+    let mut deposits: BTreeMap<&str, Uint128> = BTreeMap::new();
+    let mut repayments: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for action in actions {
+        match action {
+            Action::Deposit(coin) => {
+                let total = deposits.entry(&coin.denom).or_default();
+                *total = total.checked_add(coin.amount)?;
+            }
+            Action::Repay {
+                recipient_account_id,
+                coin,
+            } => {
+                let debt_account_id = recipient_account_id.as_deref().unwrap_or(account_id);
+                let debt =
+                    match DEBT_SHARES.may_load(deps.storage, (debt_account_id, &coin.denom))? {
+                        Some(shares) => debt_shares_to_amount(deps, &coin.denom, shares)?.amount,
+                        None => Uint128::zero(),
+                    };
+                let amount = match coin.amount {
+                    ActionAmount::Exact(amount) => amount.min(debt),
+                    ActionAmount::AccountBalance => debt,
+                };
+                let total = repayments.entry(&coin.denom).or_default();
+                *total = total.checked_add(amount)?;
+            }
+            Action::Liquidate {
+                debt_coin,
+                ..
+            } => {
+                let total = repayments.entry(&debt_coin.denom).or_default();
+                *total = total.checked_add(debt_coin.amount)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(deposits
+        .into_iter()
+        .any(|(denom, amount)| amount > repayments.get(denom).copied().unwrap_or_default()))
+}
+
+pub fn assert_actions_not_paused(
+    deps: Deps,
+    account_id: &str,
+    actions: &[Action],
+) -> ContractResult<()> {
+    match paused_action(deps, account_id, actions)? {
+        Some(action) => Err(ContractError::ActionPaused {
+            action: action.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// One of the given actions which is currently paused, if any
+pub fn paused_action(
+    deps: Deps,
+    account_id: &str,
+    actions: &[Action],
+) -> ContractResult<Option<PausableAction>> {
+    let mut pausable_actions: BTreeSet<_> = actions.iter().filter_map(pausable_action).collect();
+    if pausable_actions.contains(&PausableAction::Deposits)
+        && !deposits_exceed_repayments(deps, account_id, actions)?
+    {
+        pausable_actions.remove(&PausableAction::Deposits);
+    }
+    if pausable_actions.is_empty() {
+        return Ok(None);
+    }
+
+    let params = PARAMS.load(deps.storage)?;
+    for action in pausable_actions {
+        if params.query_is_paused(&deps.querier, PausableContract::CreditManager, action)? {
+            return Ok(Some(action));
+        }
+    }
+
+    Ok(None)
+}
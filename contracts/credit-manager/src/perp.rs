@@ -0,0 +1,221 @@
+use cosmwasm_std::{coin, Addr, Coin, CosmosMsg, Deps, DepsMut, Env, Int128, Response, Uint128};
+use mars_types::{
+    adapters::perps::Perps, credit_manager::CallbackMsg, oracle::ActionKind, perps::PerpPosition,
+};
+
+use crate::{
+    borrow::add_debt,
+    error::{ContractError, ContractResult},
+    health::query_health_values,
+    query::query_positions,
+    state::{COIN_BALANCES, ORACLE, PERPS, RED_BANK},
+    utils::{decrement_coin_balance, increment_coin_balance},
+};
+
+pub fn execute_perp_order(
+    deps: DepsMut,
+    account_id: &str,
+    denom: &str,
+    size: Int128,
+    reduce_only: Option<bool>,
+) -> ContractResult<Response> {
+    let perps = load_perps(deps.as_ref())?;
+
+    // Losses, fees and funding are paid from the account's base denom balance.
+    // Profits are sent back by the perps contract and credited to the account.
+    let settlement =
+        perps.query_order_settlement(&deps.querier, account_id, denom, size, reduce_only)?;
+    let settlement_coin = Coin {
+        denom: settlement.base_denom,
+        amount: settlement.amount.unsigned_abs(),
+    };
+
+    let mut funds = vec![];
+    if settlement.amount < Int128::zero() {
+        decrement_coin_balance(deps.storage, account_id, &settlement_coin)?;
+        funds.push(settlement_coin.clone());
+    } else if !settlement_coin.amount.is_zero() {
+        increment_coin_balance(deps.storage, account_id, &settlement_coin)?;
+    }
+
+    let msg = perps.execute_order_msg(account_id, denom, size, reduce_only, funds)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "execute_perp_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("size", size.to_string())
+        .add_attribute("settlement", settlement.amount.to_string()))
+}
+
+/// Liquidation without repaying debt, only the perp positions of the account are closed
+/// (by the callbacks of `close_perp_positions_msgs`)
+pub fn liquidate_perps(deps: Deps, liquidatee_account_id: &str) -> ContractResult<Response> {
+    let health = query_health_values(deps, liquidatee_account_id, ActionKind::Liquidation)?;
+    if !health.liquidatable {
+        return Err(ContractError::NotLiquidatable {
+            account_id: liquidatee_account_id.to_string(),
+            lqdt_health_factor: health.liquidation_health_factor.to_string(),
+        });
+    }
+
+    if load_perps(deps)?.query_positions(&deps.querier, liquidatee_account_id)?.is_empty() {
+        return Err(ContractError::NoPerpPositions {
+            account_id: liquidatee_account_id.to_string(),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "liquidate_perps")
+        .add_attribute("account_id", liquidatee_account_id))
+}
+
+/// Callbacks closing the perp positions of a liquidated account one at a time. Every callback
+/// checks the health of the account again, so positions are only closed while it's liquidatable.
+pub fn close_perp_positions_msgs(
+    deps: Deps,
+    contract_addr: &Addr,
+    liquidator_account_id: &str,
+    account_id: &str,
+) -> ContractResult<Vec<CosmosMsg>> {
+    query_perp_positions(deps, account_id)?
+        .into_iter()
+        .map(|perp| {
+            Ok(CallbackMsg::ClosePerpPosition {
+                liquidator_account_id: liquidator_account_id.to_string(),
+                account_id: account_id.to_string(),
+                denom: perp.denom,
+            }
+            .into_cosmos_msg(contract_addr)?)
+        })
+        .collect()
+}
+
+/// Closes the perp position of a liquidated account if the account is still liquidatable.
+/// Losses and the liquidation fee are paid from the account's base denom balance. A shortfall is
+/// borrowed against the account's other collateral, so it can be liquidated like any other debt.
+/// An account without any collateral left pays the liquidator first, the perps vault absorbs the
+/// rest of its loss.
+pub fn close_perp_position(
+    mut deps: DepsMut,
+    env: &Env,
+    liquidator_account_id: &str,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<Response> {
+    let perps = load_perps(deps.as_ref())?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "close_perp_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom);
+
+    let health = query_health_values(deps.as_ref(), account_id, ActionKind::Liquidation)?;
+    if !health.liquidatable {
+        return Ok(response.add_attribute("closed", "false"));
+    }
+
+    let settlement = perps.query_close_position_settlement(&deps.querier, account_id, denom)?;
+    let base_denom = settlement.base_denom;
+    let mut fee = liquidation_fee(deps.as_ref(), account_id, denom, &base_denom)?;
+
+    let (mut loss, profit) = if settlement.amount < Int128::zero() {
+        (settlement.amount.unsigned_abs(), Uint128::zero())
+    } else {
+        (Uint128::zero(), settlement.amount.unsigned_abs())
+    };
+    if !profit.is_zero() {
+        increment_coin_balance(deps.storage, account_id, &coin(profit.u128(), &base_denom))?;
+    }
+
+    let balance =
+        COIN_BALANCES.may_load(deps.storage, (account_id, &base_denom))?.unwrap_or_default();
+    let shortfall = loss.checked_add(fee)?.saturating_sub(balance);
+    let mut debt_added = Uint128::zero();
+    if !shortfall.is_zero() {
+        if has_collateral(deps.as_ref(), env, account_id, &base_denom)? {
+            let borrowed = coin(shortfall.u128(), &base_denom);
+            add_debt(&mut deps, account_id, &borrowed)?;
+            response = response.add_message(RED_BANK.load(deps.storage)?.borrow_msg(&borrowed)?);
+            debt_added = shortfall;
+        } else {
+            fee = fee.min(balance);
+            loss = balance - fee;
+        }
+    }
+
+    if !fee.is_zero() {
+        let fee_coin = coin(fee.u128(), &base_denom);
+        decrement_coin_balance(deps.storage, account_id, &fee_coin)?;
+        increment_coin_balance(deps.storage, liquidator_account_id, &fee_coin)?;
+    }
+
+    let mut funds = vec![];
+    if !loss.is_zero() {
+        let loss_coin = coin(loss.u128(), &base_denom);
+        decrement_coin_balance(deps.storage, account_id, &loss_coin)?;
+        funds.push(loss_coin);
+    }
+
+    let msg = perps.close_position_msg(account_id, denom, funds)?;
+
+    Ok(response
+        .add_message(msg)
+        .add_attribute("closed", "true")
+        .add_attribute("settlement", settlement.amount.to_string())
+        .add_attribute("paid", loss)
+        .add_attribute("debt_added", debt_added)
+        .add_attribute("liquidator_account_id", liquidator_account_id)
+        .add_attribute("liquidation_fee", fee))
+}
+
+/// Fee on the notional value of the position (at liquidation prices), in base denom
+fn liquidation_fee(
+    deps: Deps,
+    account_id: &str,
+    denom: &str,
+    base_denom: &str,
+) -> ContractResult<Uint128> {
+    let Some(perp) =
+        query_perp_positions(deps, account_id)?.into_iter().find(|perp| perp.denom == denom)
+    else {
+        return Ok(Uint128::zero());
+    };
+    let oracle = ORACLE.load(deps.storage)?;
+    let price = oracle.query_price(&deps.querier, denom, ActionKind::Liquidation)?.price;
+    let base_price = oracle.query_price(&deps.querier, base_denom, ActionKind::Liquidation)?.price;
+    Ok(perp
+        .size
+        .unsigned_abs()
+        .checked_mul_floor(price)?
+        .checked_mul_floor(perp.liquidation_fee_rate)?
+        .checked_div_floor(base_price)?)
+}
+
+/// Whether the account holds any collateral besides its base denom balance
+fn has_collateral(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    base_denom: &str,
+) -> ContractResult<bool> {
+    let positions = query_positions(deps, env, account_id)?;
+    Ok(positions.deposits.iter().any(|coin| coin.denom != base_denom)
+        || !positions.lends.is_empty()
+        || !positions.vaults.is_empty()
+        || !positions.staked_astro_lps.is_empty()
+        || !positions.staking.is_empty()
+        || !positions.cl_positions.is_empty())
+}
+
+pub fn query_perp_positions(deps: Deps, account_id: &str) -> ContractResult<Vec<PerpPosition>> {
+    match PERPS.may_load(deps.storage)? {
+        Some(perps) => Ok(perps.query_positions(&deps.querier, account_id)?),
+        None => Ok(vec![]),
+    }
+}
+
+fn load_perps(deps: Deps) -> ContractResult<Perps> {
+    PERPS.may_load(deps.storage)?.ok_or(ContractError::PerpsNotConfigured)
+}
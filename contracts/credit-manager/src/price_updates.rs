@@ -0,0 +1,68 @@
+use cosmwasm_std::{Binary, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+use mars_types::{
+    credit_manager::{Action, CallbackMsg},
+    health::AccountKind,
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::PYTH,
+};
+
+/// Pushes the Pyth price updates before dispatching the actions.
+///
+/// Health of the account is queried at the start of the dispatch, so the actions are deferred to
+/// a callback which is executed after the Pyth contract has stored the new prices.
+/// The update fee is paid from the sent funds, the remaining funds are passed on to the actions.
+pub fn update_prices_and_dispatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_updates: Vec<Binary>,
+    account_id: Option<String>,
+    account_kind: Option<AccountKind>,
+    actions: Vec<Action>,
+) -> ContractResult<Response> {
+    let pyth = PYTH.may_load(deps.storage)?.ok_or(ContractError::PythNotConfigured)?;
+
+    let fee = pyth.query_update_fee(&deps.querier, &price_updates)?;
+    let funds = deduct_fee(info.funds, &fee)?;
+
+    let update_msg = pyth.update_price_feeds_msg(price_updates.clone(), fee.clone())?;
+    let dispatch_msg = CallbackMsg::DispatchActions {
+        sender: info.sender,
+        account_id,
+        account_kind,
+        actions,
+        funds,
+    }
+    .into_cosmos_msg(&env.contract.address)?;
+
+    Ok(Response::new()
+        .add_message(update_msg)
+        .add_message(dispatch_msg)
+        .add_attribute("action", "update_prices")
+        .add_attribute("price_updates", price_updates.len().to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+fn deduct_fee(mut funds: Vec<Coin>, fee: &Coin) -> ContractResult<Vec<Coin>> {
+    if fee.amount.is_zero() {
+        return Ok(funds);
+    }
+
+    let available =
+        funds.iter().find(|c| c.denom == fee.denom).map(|c| c.amount).unwrap_or_default();
+    if available < fee.amount {
+        return Err(ContractError::InsufficientFunds {
+            requested: fee.amount,
+            available,
+        });
+    }
+
+    for coin in funds.iter_mut().filter(|c| c.denom == fee.denom) {
+        coin.amount -= fee.amount;
+    }
+    funds.retain(|c| c.amount != Uint128::zero());
+    Ok(funds)
+}
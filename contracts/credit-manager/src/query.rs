@@ -12,11 +12,15 @@ use mars_types::{
 };
 
 use crate::{
+    concentrated_liquidity::query_cl_positions,
     error::ContractResult,
+    perp::query_perp_positions,
+    staking::query_staking_positions,
     state::{
-        ACCOUNT_KINDS, ACCOUNT_NFT, COIN_BALANCES, DEBT_SHARES, HEALTH_CONTRACT, INCENTIVES,
-        MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE, OWNER, PARAMS, RED_BANK, REWARDS_COLLECTOR,
-        SWAPPER, TOTAL_DEBT_SHARES, VAULTS, VAULT_POSITIONS, ZAPPER,
+        ACCOUNT_KINDS, ACCOUNT_NFT, COIN_BALANCES, DEBT_SHARES, DELEVERAGE_CONFIG,
+        DELEVERAGE_OPT_INS, FEE_CONFIG, HEALTH_CONTRACT, INCENTIVES, MAX_CL_POSITIONS,
+        MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE, OWNER, PARAMS, PERPS, PYTH, RED_BANK,
+        REWARDS_COLLECTOR, SWAPPER, TOTAL_DEBT_SHARES, VAULTS, VAULT_POSITIONS, ZAPPER,
     },
     utils::debt_shares_to_amount,
     vault::vault_utilization_in_deposit_cap_denom,
@@ -54,15 +58,24 @@ pub fn query_config(deps: Deps) -> ContractResult<ConfigResponse> {
         oracle: ORACLE.load(deps.storage)?.address().into(),
         params: PARAMS.load(deps.storage)?.address().into(),
         max_unlocking_positions: MAX_UNLOCKING_POSITIONS.load(deps.storage)?,
+        max_cl_positions: MAX_CL_POSITIONS.load(deps.storage)?,
         max_slippage: MAX_SLIPPAGE.load(deps.storage)?,
         swapper: SWAPPER.load(deps.storage)?.address().into(),
         zapper: ZAPPER.load(deps.storage)?.address().into(),
         health_contract: HEALTH_CONTRACT.load(deps.storage)?.address().into(),
         rewards_collector: REWARDS_COLLECTOR.may_load(deps.storage)?,
+        deleverage_config: DELEVERAGE_CONFIG.may_load(deps.storage)?,
+        fee_config: FEE_CONFIG.may_load(deps.storage)?,
+        perps: PERPS.may_load(deps.storage)?.map(|p| p.address().into()),
+        pyth: PYTH.may_load(deps.storage)?.map(|p| p.address().into()),
     })
 }
 
-pub fn query_positions(deps: Deps, account_id: &str) -> ContractResult<Positions> {
+pub fn query_deleverage_opt_in(deps: Deps, account_id: &str) -> ContractResult<bool> {
+    Ok(DELEVERAGE_OPT_INS.has(deps.storage, account_id))
+}
+
+pub fn query_positions(deps: Deps, env: &Env, account_id: &str) -> ContractResult<Positions> {
     Ok(Positions {
         account_id: account_id.to_string(),
         account_kind: ACCOUNT_KINDS.load(deps.storage, account_id).unwrap_or(AccountKind::Default),
@@ -73,6 +86,9 @@ pub fn query_positions(deps: Deps, account_id: &str) -> ContractResult<Positions
         staked_astro_lps: INCENTIVES
             .load(deps.storage)?
             .query_all_staked_astro_lp_coins(&deps.querier, account_id)?,
+        staking: query_staking_positions(deps, env, account_id)?,
+        cl_positions: query_cl_positions(deps, account_id)?,
+        perps: query_perp_positions(deps, account_id)?,
     })
 }
 
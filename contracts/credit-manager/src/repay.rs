@@ -58,7 +58,7 @@ pub fn repay(deps: DepsMut, account_id: &str, coin: &ActionCoin) -> ContractResu
         .add_attribute("coin_repaid", coin_to_repay.to_string()))
 }
 
-fn debt_amount_to_shares(deps: Deps, coin: &Coin) -> ContractResult<Uint128> {
+pub fn debt_amount_to_shares(deps: Deps, coin: &Coin) -> ContractResult<Uint128> {
     let red_bank = RED_BANK.load(deps.storage)?;
     let total_debt_shares = TOTAL_DEBT_SHARES.load(deps.storage, &coin.denom)?;
     let total_debt_amount = red_bank.query_debt(&deps.querier, &coin.denom)?;
@@ -0,0 +1,1238 @@
+use std::{
+    cmp::min,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+};
+
+use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Env, Uint128};
+use mars_types::{
+    adapters::vault::{VaultPosition, VaultUnchecked},
+    credit_manager::{
+        Action, ActionAmount, ActionCoin, ClPosition, DebtAmount, DepositCapExceeded, FailedAction,
+        Positions, SimulateActionsResponse, StakingPosition, TransferPosition, UnbondingEntry,
+    },
+    health::{AccountKind, HealthValuesResponse},
+    oracle::ActionKind,
+    params::TotalDepositResponse,
+    perps::PerpPosition,
+    swapper::SwapperRoute,
+};
+
+use crate::{
+    assertions::{
+        check_coin_balance_at_least, check_debt_at_most, check_health_factor_above,
+        check_value_change, net_value, query_net_value,
+    },
+    borrow::DEFAULT_DEBT_SHARES_PER_COIN_BORROWED,
+    concentrated_liquidity::{assert_under_max_cl_positions, query_cl_position_with_rewards},
+    dust::assert_min_debt_value,
+    error::{ContractError, ContractResult},
+    fees::{fee_amount, load_fee_config},
+    pause::{pausable_action, paused_action},
+    query::query_positions,
+    repay::current_debt_for_denom,
+    staking::{
+        amount_to_shares, assert_staking_enabled, check_min_staking_amount,
+        query_pending_staking_rewards, query_staking_params, redelegation_allowed,
+        staking_batch_due,
+    },
+    state::{
+        HEALTH_CONTRACT, INCENTIVES, PARAMS, RED_BANK, SWAPPER, TOTAL_DEBT_SHARES, VAULT_POSITIONS,
+        ZAPPER,
+    },
+    transfer::{all_of, assert_no_fund_manager},
+    utils::assert_slippage,
+};
+
+/// Dry-run of `UpdateCreditAccount` actions.
+///
+/// Mirrors `dispatch_actions` and the callbacks it dispatches, but instead of sending messages the
+/// actions are applied to a copy of the account's positions. Swaps and zaps use the estimates of
+/// the swapper/zapper and debts use Red Bank debt share math. Simulation stops at the first
+/// failing action.
+///
+/// Paused actions and debts left below the min debt value are checked with the same helpers as
+/// dispatch. Positions transferred to another account are removed, the recipient's side isn't
+/// simulated (and neither is the ownership of the recipient account, which needs the sender).
+pub fn simulate_actions(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    actions: Vec<Action>,
+) -> ContractResult<SimulateActionsResponse> {
+    let mut simulation = Simulation::new(deps, env, account_id)?;
+    if actions.iter().any(|action| matches!(action, Action::AssertValueChange { .. })) {
+        simulation.value_change_reference = Some(query_net_value(deps, account_id)?);
+    }
+
+    // Dispatch rejects all actions if one of them is paused
+    let mut failed_action = None;
+    if let Some(paused) = paused_action(deps, account_id, &actions)? {
+        let index = actions
+            .iter()
+            .position(|action| pausable_action(action) == Some(paused))
+            .unwrap_or_default();
+        failed_action = Some(FailedAction {
+            index: index as u32,
+            error: ContractError::ActionPaused {
+                action: paused.to_string(),
+            }
+            .to_string(),
+        });
+    }
+
+    // Debts which may be left below the min debt value, with the last action changing them
+    let mut min_debt_checks: BTreeMap<(String, String), usize> = BTreeMap::new();
+
+    for (index, action) in actions.iter().enumerate() {
+        if failed_action.is_some() {
+            break;
+        }
+
+        // Positions are left untouched by a failing action
+        let snapshot = simulation.clone();
+        if let Err(err) = simulation.apply(deps, env, action) {
+            simulation = snapshot;
+            failed_action = Some(FailedAction {
+                index: index as u32,
+                error: err.to_string(),
+            });
+            break;
+        }
+
+        match action {
+            Action::Borrow(coin) => {
+                min_debt_checks.insert((account_id.to_string(), coin.denom.clone()), index);
+            }
+            Action::Repay {
+                recipient_account_id,
+                coin,
+            } => {
+                let debt_account_id = recipient_account_id.as_deref().unwrap_or(account_id);
+                min_debt_checks.insert((debt_account_id.to_string(), coin.denom.clone()), index);
+            }
+            _ => {}
+        }
+    }
+
+    if failed_action.is_none() {
+        failed_action = simulation.min_debt_failure(deps, min_debt_checks)?;
+    }
+
+    let deposit_caps_exceeded = simulation.deposit_caps_exceeded(deps)?;
+    let fees = to_coins(simulation.fees.clone());
+    let health = simulation.health(deps)?;
+    let positions = simulation.into_positions(deps)?;
+
+    Ok(SimulateActionsResponse {
+        positions,
+        health,
+        deposit_caps_exceeded,
+        fees,
+        failed_action,
+    })
+}
+
+#[derive(Clone)]
+struct TotalDebt {
+    amount: Uint128,
+    shares: Uint128,
+}
+
+/// Projected positions of a credit account
+#[derive(Clone)]
+struct Simulation {
+    account_id: String,
+    account_kind: AccountKind,
+    deposits: BTreeMap<String, Uint128>,
+    debt_shares: BTreeMap<String, Uint128>,
+    lends: BTreeMap<String, Uint128>,
+    vaults: Vec<VaultPosition>,
+    staked_astro_lps: BTreeMap<String, Uint128>,
+    staking: Vec<StakingPosition>,
+    cl_positions: Vec<ClPosition>,
+    perps: Vec<PerpPosition>,
+    /// Total debt of Rover in Red Bank per denom, loaded on first use
+    total_debts: BTreeMap<String, TotalDebt>,
+    /// Amount per denom added to Rover, checked against deposit caps
+    deposit_increases: BTreeMap<String, Uint128>,
+    /// Protocol fees paid to the rewards-collector account
+    fees: BTreeMap<String, Uint128>,
+    rewards_claimed: bool,
+    astro_lp_rewards_claimed: BTreeSet<String>,
+    /// Net value compared against by value change assertions, moved by deposits and withdrawals
+    value_change_reference: Option<Uint128>,
+    /// Remaining debt per (account id, denom) of other accounts repaid by this one
+    recipient_debts: BTreeMap<(String, String), Uint128>,
+    staking_rewards_accrued: BTreeSet<String>,
+    /// Validators whose batch was sent by the simulated actions
+    staking_batches_sent: BTreeSet<String>,
+    /// Validators with a redelegation into them maturing from the simulated actions on
+    staking_redelegations_into: BTreeSet<String>,
+    cl_rewards_claimed: BTreeSet<u64>,
+}
+
+impl Simulation {
+    fn new(deps: Deps, env: &Env, account_id: &str) -> ContractResult<Self> {
+        let positions = query_positions(deps, env, account_id)?;
+        Ok(Self {
+            account_id: positions.account_id,
+            account_kind: positions.account_kind,
+            deposits: to_map(positions.deposits),
+            debt_shares: positions
+                .debts
+                .into_iter()
+                .map(|debt| (debt.denom, debt.shares))
+                .collect(),
+            lends: to_map(positions.lends),
+            vaults: positions.vaults,
+            staked_astro_lps: to_map(positions.staked_astro_lps),
+            staking: positions.staking,
+            cl_positions: positions.cl_positions,
+            perps: positions.perps,
+            total_debts: BTreeMap::new(),
+            deposit_increases: BTreeMap::new(),
+            fees: BTreeMap::new(),
+            rewards_claimed: false,
+            astro_lp_rewards_claimed: BTreeSet::new(),
+            value_change_reference: None,
+            recipient_debts: BTreeMap::new(),
+            staking_rewards_accrued: BTreeSet::new(),
+            staking_batches_sent: BTreeSet::new(),
+            staking_redelegations_into: BTreeSet::new(),
+            cl_rewards_claimed: BTreeSet::new(),
+        })
+    }
+
+    fn apply(&mut self, deps: Deps, env: &Env, action: &Action) -> ContractResult<()> {
+        match action {
+            Action::Deposit(coin) => {
+                if coin.amount.is_zero() {
+                    return Ok(());
+                }
+                self.excluded_from_value_change(deps, |simulation| simulation.deposit(coin))
+            }
+            Action::Withdraw(coin) => {
+                self.excluded_from_value_change(deps, |simulation| simulation.withdraw(coin))
+            }
+            Action::WithdrawToWallet {
+                coin,
+                recipient,
+            } => {
+                deps.api.addr_validate(recipient)?;
+                self.excluded_from_value_change(deps, |simulation| simulation.withdraw(coin))
+            }
+            Action::Borrow(coin) => self.borrow(deps, coin),
+            Action::Lend(coin) => self.lend(deps, coin),
+            Action::Reclaim(coin) => self.reclaim(coin),
+            Action::ClaimRewards {} => self.claim_rewards(deps),
+            Action::Repay {
+                recipient_account_id: None,
+                coin,
+            } => self.repay(deps, coin),
+            Action::Repay {
+                recipient_account_id: Some(recipient_account_id),
+                coin,
+            } => self.repay_for_recipient(deps, recipient_account_id, coin),
+            Action::SwapExactIn {
+                coin_in,
+                denom_out,
+                min_receive,
+                route,
+            } => self.swap_exact_in(deps, coin_in, denom_out, *min_receive, route),
+            Action::ProvideLiquidity {
+                coins_in,
+                lp_token_out,
+                slippage,
+            } => self.provide_liquidity(deps, coins_in, lp_token_out, *slippage),
+            Action::WithdrawLiquidity {
+                lp_token,
+                slippage,
+            } => self.withdraw_liquidity(deps, lp_token, *slippage),
+            Action::StakeAstroLp {
+                lp_token,
+            } => self.stake_astro_lp(deps, lp_token),
+            Action::UnstakeAstroLp {
+                lp_token,
+            } => self.unstake_astro_lp(deps, lp_token),
+            Action::ClaimAstroLpRewards {
+                lp_denom,
+            } => self.claim_astro_lp_rewards(deps, lp_denom),
+            Action::RefundAllCoinBalances {} => {
+                self.excluded_from_value_change(deps, |simulation| {
+                    simulation.deposits.clear();
+                    Ok(())
+                })
+            }
+            Action::EnterVault {
+                ..
+            } => Err(ContractError::SimulationNotSupported("enter_vault".to_string())),
+            Action::ExitVault {
+                ..
+            } => Err(ContractError::SimulationNotSupported("exit_vault".to_string())),
+            Action::RequestVaultUnlock {
+                ..
+            } => Err(ContractError::SimulationNotSupported("request_vault_unlock".to_string())),
+            Action::ExitVaultUnlocked {
+                ..
+            } => Err(ContractError::SimulationNotSupported("exit_vault_unlocked".to_string())),
+            Action::Liquidate {
+                ..
+            } => Err(ContractError::SimulationNotSupported("liquidate".to_string())),
+            Action::TransferToAccount {
+                recipient_account_id,
+                positions,
+            } => {
+                self.assert_transfer_allowed(deps, recipient_account_id)?;
+                positions.iter().try_for_each(|position| {
+                    self.transfer_position(deps, env, recipient_account_id, position)
+                })
+            }
+            Action::MergeAccounts {
+                recipient_account_id,
+            } => self.merge_accounts(deps, env, recipient_account_id),
+            Action::Delegate {
+                validator,
+                coin,
+            } => self.delegate(deps, env, validator, coin),
+            Action::Undelegate {
+                validator,
+                coin,
+            } => self.queue_staking_move(deps, env, validator, coin, None),
+            Action::Redelegate {
+                src_validator,
+                dst_validator,
+                coin,
+            } => {
+                assert_staking_enabled(deps, &coin.denom)?;
+                if src_validator == dst_validator {
+                    return Err(ContractError::RedelegationToSameValidator(src_validator.clone()));
+                }
+                self.queue_staking_move(deps, env, src_validator, coin, Some(dst_validator))
+            }
+            Action::ClaimStakingRewards {} => self.claim_staking_rewards(deps, env),
+            // The amounts taken by the pool depend on its liquidity math which can't be estimated
+            Action::CreateClPosition {
+                ..
+            } => Err(ContractError::SimulationNotSupported("create_cl_position".to_string())),
+            Action::AddToClPosition {
+                ..
+            } => Err(ContractError::SimulationNotSupported("add_to_cl_position".to_string())),
+            Action::WithdrawClPosition {
+                position_id,
+                liquidity,
+            } => self.withdraw_cl_position(deps, *position_id, *liquidity),
+            Action::ClaimClPositionRewards {
+                position_id,
+            } => {
+                self.cl_position_index(*position_id)?;
+                if self.claim_cl_position_rewards(deps, *position_id)?.is_empty() {
+                    return Err(ContractError::NoAmount);
+                }
+                Ok(())
+            }
+            Action::ExecutePerpOrder {
+                ..
+            } => Err(ContractError::SimulationNotSupported("execute_perp_order".to_string())),
+            Action::AssertHealthFactorAbove(min_health_factor) => {
+                let health = self.health(deps)?;
+                check_health_factor_above(&self.account_id, &health, *min_health_factor)
+            }
+            Action::AssertCoinBalanceAtLeast(coin) => {
+                check_coin_balance_at_least(balance(&self.deposits, &coin.denom), coin)
+            }
+            Action::AssertDebtAtMost(coin) => {
+                let debt = self.debt_amount(deps, &coin.denom)?;
+                check_debt_at_most(debt, coin)
+            }
+            Action::AssertValueChange {
+                max_loss_pct,
+            } => {
+                let prev_net_value = self.value_change_reference.unwrap_or_default();
+                let new_net_value = net_value(&self.health(deps)?);
+                check_value_change(prev_net_value, new_net_value, *max_loss_pct)
+            }
+        }
+    }
+
+    /// Moves the reference of value change assertions by the net value the deposit or withdrawal
+    /// adds or takes out, like dispatch does
+    fn excluded_from_value_change(
+        &mut self,
+        deps: Deps,
+        apply: impl FnOnce(&mut Self) -> ContractResult<()>,
+    ) -> ContractResult<()> {
+        let Some(reference) = self.value_change_reference else {
+            return apply(self);
+        };
+
+        let prev_net_value = net_value(&self.health(deps)?);
+        apply(self)?;
+        let new_net_value = net_value(&self.health(deps)?);
+        self.value_change_reference = Some(if new_net_value > prev_net_value {
+            reference.checked_add(new_net_value - prev_net_value)?
+        } else {
+            reference.saturating_sub(prev_net_value - new_net_value)
+        });
+        Ok(())
+    }
+
+    fn deposit(&mut self, coin: &Coin) -> ContractResult<()> {
+        increment(&mut self.deposits, coin)?;
+        increment(&mut self.deposit_increases, coin)
+    }
+
+    fn withdraw(&mut self, coin: &ActionCoin) -> ContractResult<()> {
+        let amount = coin.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin.denom));
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+        decrement(&mut self.deposits, &Coin::new(amount.u128(), &coin.denom))
+    }
+
+    fn borrow(&mut self, deps: Deps, coin: &Coin) -> ContractResult<()> {
+        if coin.amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+
+        assert_whitelisted(deps, &coin.denom)?;
+
+        let total_debt = self.total_debt_mut(deps, &coin.denom)?;
+        let debt_shares_to_add = if total_debt.amount.is_zero() {
+            coin.amount.checked_mul(DEFAULT_DEBT_SHARES_PER_COIN_BORROWED)?
+        } else {
+            total_debt.shares.checked_multiply_ratio(coin.amount, total_debt.amount)?
+        };
+
+        if debt_shares_to_add.is_zero() {
+            return Err(ContractError::ZeroDebtShares);
+        }
+
+        total_debt.amount = total_debt.amount.checked_add(coin.amount)?;
+        total_debt.shares = total_debt.shares.checked_add(debt_shares_to_add)?;
+
+        let shares = self.debt_shares.entry(coin.denom.clone()).or_default();
+        *shares = shares.checked_add(debt_shares_to_add)?;
+
+        increment(&mut self.deposits, coin)?;
+
+        let fee = load_fee_config(deps.storage)?.borrow_fee(&coin.denom);
+        self.charge_fee(coin, fee)?;
+        Ok(())
+    }
+
+    fn repay(&mut self, deps: Deps, coin: &ActionCoin) -> ContractResult<()> {
+        let debt_shares = *self.debt_shares.get(&coin.denom).ok_or(ContractError::NoDebt)?;
+        let coin_balance = balance(&self.deposits, &coin.denom);
+
+        let total_debt = self.total_debt_mut(deps, &coin.denom)?;
+        let debt_amount = total_debt.amount.checked_mul_ceil((debt_shares, total_debt.shares))?;
+        let amount_to_repay = min(debt_amount, coin.amount.value().unwrap_or(coin_balance));
+        let shares_to_repay =
+            total_debt.shares.checked_multiply_ratio(amount_to_repay, total_debt.amount)?;
+
+        total_debt.amount = total_debt.amount.checked_sub(amount_to_repay)?;
+        total_debt.shares = total_debt.shares.checked_sub(shares_to_repay)?;
+
+        if amount_to_repay == debt_amount {
+            self.debt_shares.remove(&coin.denom);
+        } else {
+            self.debt_shares.insert(coin.denom.clone(), debt_shares.checked_sub(shares_to_repay)?);
+        }
+
+        decrement(&mut self.deposits, &Coin::new(amount_to_repay.u128(), &coin.denom))
+    }
+
+    fn repay_for_recipient(
+        &mut self,
+        deps: Deps,
+        recipient_account_id: &str,
+        coin: &ActionCoin,
+    ) -> ContractResult<()> {
+        let key = (recipient_account_id.to_string(), coin.denom.clone());
+        let debt_amount = match self.recipient_debts.get(&key) {
+            Some(debt_amount) => *debt_amount,
+            None => current_debt_for_denom(deps, recipient_account_id, &coin.denom)?.0,
+        };
+        let coin_balance = balance(&self.deposits, &coin.denom);
+        let amount_to_repay = min(debt_amount, coin.amount.value().unwrap_or(coin_balance));
+        self.recipient_debts.insert(key, debt_amount.checked_sub(amount_to_repay)?);
+
+        let total_debt = self.total_debt_mut(deps, &coin.denom)?;
+        let shares_to_repay =
+            total_debt.shares.checked_multiply_ratio(amount_to_repay, total_debt.amount)?;
+        total_debt.amount = total_debt.amount.checked_sub(amount_to_repay)?;
+        total_debt.shares = total_debt.shares.checked_sub(shares_to_repay)?;
+
+        decrement(&mut self.deposits, &Coin::new(amount_to_repay.u128(), &coin.denom))
+    }
+
+    fn lend(&mut self, deps: Deps, coin: &ActionCoin) -> ContractResult<()> {
+        assert_whitelisted(deps, &coin.denom)?;
+
+        let amount = coin.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin.denom));
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        let coin = Coin::new(amount.u128(), &coin.denom);
+        decrement(&mut self.deposits, &coin)?;
+        increment(&mut self.lends, &coin)
+    }
+
+    fn reclaim(&mut self, coin: &ActionCoin) -> ContractResult<()> {
+        let lent = balance(&self.lends, &coin.denom);
+        let amount = min(lent, coin.amount.value().unwrap_or(Uint128::MAX));
+        if amount.is_zero() {
+            return Err(ContractError::NoneLent);
+        }
+
+        let coin = Coin::new(amount.u128(), &coin.denom);
+        decrement(&mut self.lends, &coin)?;
+        increment(&mut self.deposits, &coin)
+    }
+
+    fn claim_rewards(&mut self, deps: Deps) -> ContractResult<()> {
+        if self.rewards_claimed {
+            return Err(ContractError::NoAmount);
+        }
+
+        let rewards = INCENTIVES
+            .load(deps.storage)?
+            .query_unclaimed_rewards(&deps.querier, &self.account_id)?;
+        if rewards.is_empty() {
+            return Err(ContractError::NoAmount);
+        }
+
+        self.rewards_claimed = true;
+        rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))
+    }
+
+    fn swap_exact_in(
+        &mut self,
+        deps: Deps,
+        coin_in: &ActionCoin,
+        denom_out: &str,
+        min_receive: Uint128,
+        route: &Option<SwapperRoute>,
+    ) -> ContractResult<()> {
+        let amount =
+            coin_in.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin_in.denom));
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+
+        let coin_in = Coin::new(amount.u128(), &coin_in.denom);
+        let fee_paid = self.charge_fee(&coin_in, load_fee_config(deps.storage)?.swap_fee)?;
+        let coin_in = Coin::new(amount.checked_sub(fee_paid.amount)?.u128(), &coin_in.denom);
+        decrement(&mut self.deposits, &coin_in)?;
+
+        let estimated = SWAPPER.load(deps.storage)?.query_estimate_exact_in_swap(
+            &deps.querier,
+            &coin_in,
+            denom_out,
+            route.clone(),
+        )?;
+        if estimated < min_receive {
+            return Err(ContractError::MinReceiveNotMet {
+                estimated,
+                min_receive,
+            });
+        }
+
+        self.deposit(&Coin::new(estimated.u128(), denom_out))
+    }
+
+    fn provide_liquidity(
+        &mut self,
+        deps: Deps,
+        coins_in: &[ActionCoin],
+        lp_token_out: &str,
+        slippage: Decimal,
+    ) -> ContractResult<()> {
+        assert_slippage(deps.storage, slippage)?;
+        assert_whitelisted(deps, lp_token_out)?;
+
+        let fee = load_fee_config(deps.storage)?.swap_fee;
+        let mut coins = vec![];
+        for coin_in in coins_in {
+            assert_whitelisted(deps, &coin_in.denom)?;
+            let amount =
+                coin_in.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin_in.denom));
+            let fee_paid = self.charge_fee(&Coin::new(amount.u128(), &coin_in.denom), fee)?;
+            let coin = Coin::new(amount.checked_sub(fee_paid.amount)?.u128(), &coin_in.denom);
+            decrement(&mut self.deposits, &coin)?;
+            coins.push(coin);
+        }
+
+        let estimated = ZAPPER.load(deps.storage)?.estimate_provide_liquidity(
+            &deps.querier,
+            lp_token_out,
+            &coins,
+        )?;
+
+        self.deposit(&Coin::new(estimated.u128(), lp_token_out))
+    }
+
+    fn withdraw_liquidity(
+        &mut self,
+        deps: Deps,
+        lp_token: &ActionCoin,
+        slippage: Decimal,
+    ) -> ContractResult<()> {
+        assert_slippage(deps.storage, slippage)?;
+
+        let amount =
+            lp_token.amount.value().unwrap_or_else(|| balance(&self.deposits, &lp_token.denom));
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+
+        let lp_coin = Coin::new(amount.u128(), &lp_token.denom);
+        decrement(&mut self.deposits, &lp_coin)?;
+
+        let coins_out =
+            ZAPPER.load(deps.storage)?.estimate_withdraw_liquidity(&deps.querier, &lp_coin)?;
+        coins_out.iter().try_for_each(|coin| self.deposit(coin))
+    }
+
+    fn stake_astro_lp(&mut self, deps: Deps, lp_token: &ActionCoin) -> ContractResult<()> {
+        let rewards = self.astro_lp_rewards(deps, &lp_token.denom)?;
+        rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))?;
+
+        let amount =
+            lp_token.amount.value().unwrap_or_else(|| balance(&self.deposits, &lp_token.denom));
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+
+        let lp_coin = Coin::new(amount.u128(), &lp_token.denom);
+        decrement(&mut self.deposits, &lp_coin)?;
+        increment(&mut self.staked_astro_lps, &lp_coin)
+    }
+
+    fn unstake_astro_lp(&mut self, deps: Deps, lp_token: &ActionCoin) -> ContractResult<()> {
+        let rewards = self.astro_lp_rewards(deps, &lp_token.denom)?;
+        rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))?;
+
+        let staked = balance(&self.staked_astro_lps, &lp_token.denom);
+        let amount = match lp_token.amount {
+            ActionAmount::Exact(amount) if amount > staked => {
+                return Err(ContractError::InsufficientFunds {
+                    requested: amount,
+                    available: staked,
+                });
+            }
+            ActionAmount::Exact(amount) => amount,
+            ActionAmount::AccountBalance => staked,
+        };
+
+        let lp_coin = Coin::new(amount.u128(), &lp_token.denom);
+        decrement(&mut self.staked_astro_lps, &lp_coin)?;
+        increment(&mut self.deposits, &lp_coin)
+    }
+
+    fn claim_astro_lp_rewards(&mut self, deps: Deps, lp_denom: &str) -> ContractResult<()> {
+        let rewards = self.astro_lp_rewards(deps, lp_denom)?;
+        if rewards.is_empty() {
+            return Err(ContractError::NoAmount);
+        }
+        rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))
+    }
+
+    /// Rewards of a staked Astroport LP position. Rewards are claimed with every stake/unstake/claim
+    /// action, so they are only returned once per simulation.
+    fn astro_lp_rewards(&mut self, deps: Deps, lp_denom: &str) -> ContractResult<Vec<Coin>> {
+        if !self.astro_lp_rewards_claimed.insert(lp_denom.to_string()) {
+            return Ok(vec![]);
+        }
+
+        Ok(INCENTIVES.load(deps.storage)?.query_staked_astro_lp_rewards(
+            &deps.querier,
+            &self.account_id,
+            lp_denom,
+        )?)
+    }
+
+    fn delegate(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        validator: &str,
+        coin: &ActionCoin,
+    ) -> ContractResult<()> {
+        assert_staking_enabled(deps, &coin.denom)?;
+
+        let amount = coin.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin.denom));
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+        let coin = Coin::new(amount.u128(), &coin.denom);
+        decrement(&mut self.deposits, &coin)?;
+
+        amount_to_shares(deps, env, validator, amount)?;
+        self.add_delegation(deps, env, validator, coin)
+    }
+
+    fn add_delegation(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        validator: &str,
+        coin: Coin,
+    ) -> ContractResult<()> {
+        // Rewards are settled before the shares of the account change
+        self.accrue_staking_rewards(deps, env, validator)?;
+
+        match self.staking.iter_mut().find(|position| position.validator == validator) {
+            Some(position) => {
+                position.delegated.amount = position.delegated.amount.checked_add(coin.amount)?;
+            }
+            None => {
+                self.staking.push(StakingPosition {
+                    validator: validator.to_string(),
+                    delegated: coin,
+                    queued: Uint128::zero(),
+                    unbonding: vec![],
+                });
+                self.staking.sort_by(|a, b| a.validator.cmp(&b.validator));
+            }
+        }
+        Ok(())
+    }
+
+    /// Undelegations and redelegations are queued and sent right away if the validator's batch is
+    /// due, like `send_batch_if_due` does. Requests queued by earlier transactions stay queued.
+    fn queue_staking_move(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        validator: &str,
+        coin: &ActionCoin,
+        dst_validator: Option<&str>,
+    ) -> ContractResult<()> {
+        let staking_params = query_staking_params(deps, &coin.denom)?;
+
+        let index = self
+            .staking
+            .iter()
+            .position(|position| {
+                position.validator == validator && position.delegated.amount > position.queued
+            })
+            .ok_or_else(|| ContractError::NoStakingPosition {
+                validator: validator.to_string(),
+            })?;
+
+        let position = &self.staking[index];
+        let movable = position.delegated.amount.checked_sub(position.queued)?;
+        let amount = match coin.amount {
+            ActionAmount::Exact(amount) if amount > movable => {
+                return Err(ContractError::InsufficientFunds {
+                    requested: amount,
+                    available: movable,
+                });
+            }
+            ActionAmount::Exact(amount) => amount,
+            ActionAmount::AccountBalance => movable,
+        };
+        if amount.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+        check_min_staking_amount(&staking_params, amount, amount < movable)?;
+
+        let send = !self.staking_batches_sent.contains(validator)
+            && staking_batch_due(deps.storage, env, validator, &staking_params)?
+            && (dst_validator.is_none()
+                || (!self.staking_redelegations_into.contains(validator)
+                    && redelegation_allowed(deps.storage, env, validator)?));
+        if !send {
+            let position = &mut self.staking[index];
+            position.queued = position.queued.checked_add(amount)?;
+            return Ok(());
+        }
+
+        // Rewards are settled with the batch
+        self.staking_batches_sent.insert(validator.to_string());
+        self.accrue_staking_rewards(deps, env, validator)?;
+
+        let position = &mut self.staking[index];
+        position.delegated.amount = position.delegated.amount.checked_sub(amount)?;
+        let moved = Coin::new(amount.u128(), &position.delegated.denom);
+        if dst_validator.is_none() {
+            position.unbonding.push(UnbondingEntry {
+                amount,
+                completion_time: env.block.time.seconds() + staking_params.unbonding_period,
+            });
+            position.unbonding.sort_by_key(|entry| entry.completion_time);
+        }
+        if position.delegated.amount.is_zero()
+            && position.queued.is_zero()
+            && position.unbonding.is_empty()
+        {
+            self.staking.remove(index);
+        }
+
+        if let Some(dst_validator) = dst_validator {
+            self.staking_redelegations_into.insert(dst_validator.to_string());
+            self.add_delegation(deps, env, dst_validator, moved)?;
+        }
+        Ok(())
+    }
+
+    fn claim_staking_rewards(&mut self, deps: Deps, env: &Env) -> ContractResult<()> {
+        let validators = self
+            .staking
+            .iter()
+            .filter(|position| !position.delegated.amount.is_zero())
+            .map(|position| position.validator.clone())
+            .collect::<Vec<_>>();
+
+        let mut rewards = vec![];
+        for validator in validators.iter() {
+            rewards.extend(self.accrue_staking_rewards(deps, env, validator)?);
+        }
+        if rewards.is_empty() {
+            return Err(ContractError::NoAmount);
+        }
+        Ok(())
+    }
+
+    /// Staking rewards are added to the coin balance whenever the delegation of the account changes
+    /// or rewards are claimed, so they are only accrued once per simulation.
+    fn accrue_staking_rewards(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        validator: &str,
+    ) -> ContractResult<Vec<Coin>> {
+        if !self.staking_rewards_accrued.insert(validator.to_string()) {
+            return Ok(vec![]);
+        }
+
+        let rewards = query_pending_staking_rewards(deps, env, &self.account_id, validator)?;
+        rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))?;
+        Ok(rewards)
+    }
+
+    fn cl_position_index(&self, position_id: u64) -> ContractResult<usize> {
+        self.cl_positions
+            .iter()
+            .position(|position| position.position_id == position_id)
+            .ok_or_else(|| ContractError::NoClPosition {
+                account_id: self.account_id.clone(),
+                position_id,
+            })
+    }
+
+    /// Withdrawn liquidity is converted pro rata to the underlying tokens of the position
+    fn withdraw_cl_position(
+        &mut self,
+        deps: Deps,
+        position_id: u64,
+        liquidity: Option<Decimal256>,
+    ) -> ContractResult<()> {
+        let index = self.cl_position_index(position_id)?;
+        self.claim_cl_position_rewards(deps, position_id)?;
+
+        let position = self.cl_positions[index].clone();
+        let liquidity = liquidity.unwrap_or(position.liquidity);
+        if liquidity.is_zero() {
+            return Err(ContractError::NoAmount);
+        }
+        if liquidity > position.liquidity {
+            return Err(ContractError::InsufficientClLiquidity {
+                position_id,
+                requested: liquidity,
+                available: position.liquidity,
+            });
+        }
+
+        let ratio = Decimal::try_from(liquidity.checked_div(position.liquidity)?)?;
+        let amount0 = position.asset0.amount.checked_mul_floor(ratio)?;
+        let amount1 = position.asset1.amount.checked_mul_floor(ratio)?;
+        increment(&mut self.deposits, &Coin::new(amount0.u128(), &position.asset0.denom))?;
+        increment(&mut self.deposits, &Coin::new(amount1.u128(), &position.asset1.denom))?;
+
+        if liquidity == position.liquidity {
+            self.cl_positions.remove(index);
+        } else {
+            let remaining = &mut self.cl_positions[index];
+            remaining.liquidity = position.liquidity.checked_sub(liquidity)?;
+            remaining.asset0.amount = position.asset0.amount.checked_sub(amount0)?;
+            remaining.asset1.amount = position.asset1.amount.checked_sub(amount1)?;
+        }
+        Ok(())
+    }
+
+    /// Rewards of a concentrated liquidity position, claimed with every withdrawal or claim action,
+    /// so they are only added once per simulation
+    fn claim_cl_position_rewards(
+        &mut self,
+        deps: Deps,
+        position_id: u64,
+    ) -> ContractResult<Vec<Coin>> {
+        if !self.cl_rewards_claimed.insert(position_id) {
+            return Ok(vec![]);
+        }
+
+        let (_, rewards) = query_cl_position_with_rewards(&deps.querier, position_id)?;
+        let claimed =
+            rewards.spread_rewards.into_iter().chain(rewards.incentives).collect::<Vec<_>>();
+        claimed.iter().try_for_each(|coin| increment(&mut self.deposits, coin))?;
+        Ok(claimed)
+    }
+
+    fn assert_transfer_allowed(
+        &self,
+        deps: Deps,
+        recipient_account_id: &str,
+    ) -> ContractResult<()> {
+        if self.account_id == recipient_account_id {
+            return Err(ContractError::TransferNotAllowed {
+                reason: "can not transfer to the same account".to_string(),
+            });
+        }
+        assert_no_fund_manager(deps.storage, &self.account_id, recipient_account_id)
+    }
+
+    /// Removes the position from the account, like `transfer_positions` does
+    fn transfer_position(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        recipient_account_id: &str,
+        position: &TransferPosition<VaultUnchecked>,
+    ) -> ContractResult<()> {
+        match position {
+            TransferPosition::Deposit(coin) => {
+                let amount =
+                    coin.amount.value().unwrap_or_else(|| balance(&self.deposits, &coin.denom));
+                if amount.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+                decrement(&mut self.deposits, &Coin::new(amount.u128(), &coin.denom))
+            }
+            TransferPosition::Debt(coin) => {
+                let debt_shares =
+                    *self.debt_shares.get(&coin.denom).ok_or(ContractError::NoDebt)?;
+                let debt_amount = self.debt_amount(deps, &coin.denom)?;
+
+                // Total debt shares don't change, shares are only moved between the accounts
+                let shares = match coin.amount {
+                    ActionAmount::Exact(amount) if amount < debt_amount => {
+                        let total_debt = self.total_debt_mut(deps, &coin.denom)?;
+                        total_debt.shares.checked_multiply_ratio(amount, total_debt.amount)?
+                    }
+                    _ => debt_shares,
+                };
+                if shares.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+
+                if shares == debt_shares {
+                    self.debt_shares.remove(&coin.denom);
+                } else {
+                    self.debt_shares.insert(coin.denom.clone(), debt_shares.checked_sub(shares)?);
+                }
+                Ok(())
+            }
+            TransferPosition::Lend(coin) => {
+                let lent = balance(&self.lends, &coin.denom);
+                let amount = min(lent, coin.amount.value().unwrap_or(lent));
+                if amount.is_zero() {
+                    return Err(ContractError::NoneLent);
+                }
+                decrement(&mut self.lends, &Coin::new(amount.u128(), &coin.denom))
+            }
+            TransferPosition::Vault(vault) => {
+                let index = self
+                    .vaults
+                    .iter()
+                    .position(|position| position.vault.address.as_str() == vault.address)
+                    .ok_or_else(|| ContractError::TransferNotAllowed {
+                        reason: format!("account has no position in vault {}", vault.address),
+                    })?;
+
+                let vault = vault.check(deps.api)?;
+                if VAULT_POSITIONS.has(deps.storage, (recipient_account_id, vault.address.clone()))
+                {
+                    return Err(ContractError::TransferNotAllowed {
+                        reason: format!(
+                            "recipient account already has a position in vault {}",
+                            vault.address
+                        ),
+                    });
+                }
+
+                self.vaults.remove(index);
+                Ok(())
+            }
+            TransferPosition::StakedAstroLp(coin) => {
+                let rewards = self.astro_lp_rewards(deps, &coin.denom)?;
+                rewards.iter().try_for_each(|reward| increment(&mut self.deposits, reward))?;
+
+                let staked = balance(&self.staked_astro_lps, &coin.denom);
+                let amount = match coin.amount {
+                    ActionAmount::Exact(amount) if amount > staked => {
+                        return Err(ContractError::InsufficientFunds {
+                            requested: amount,
+                            available: staked,
+                        });
+                    }
+                    ActionAmount::Exact(amount) => amount,
+                    ActionAmount::AccountBalance => staked,
+                };
+                if amount.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+                decrement(&mut self.staked_astro_lps, &Coin::new(amount.u128(), &coin.denom))
+            }
+            TransferPosition::Staking(validator) => {
+                let index = self
+                    .staking
+                    .iter()
+                    .position(|position| &position.validator == validator)
+                    .ok_or_else(|| ContractError::NoStakingPosition {
+                        validator: validator.clone(),
+                    })?;
+
+                // Rewards accrued so far are settled to the account
+                if !self.staking[index].delegated.amount.is_zero() {
+                    self.accrue_staking_rewards(deps, env, validator)?;
+                }
+                self.staking.remove(index);
+                Ok(())
+            }
+            TransferPosition::ClPosition(position_id) => {
+                let index = self.cl_position_index(*position_id)?;
+                assert_under_max_cl_positions(deps.storage, recipient_account_id)?;
+                self.cl_positions.remove(index);
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves all positions like `merge_accounts` does, coin balances last
+    fn merge_accounts(
+        &mut self,
+        deps: Deps,
+        env: &Env,
+        recipient_account_id: &str,
+    ) -> ContractResult<()> {
+        self.assert_transfer_allowed(deps, recipient_account_id)?;
+        if !self.perps.is_empty() {
+            return Err(ContractError::TransferNotAllowed {
+                reason: "perp positions can not be transferred, close them first".to_string(),
+            });
+        }
+
+        let mut transfers: Vec<TransferPosition<VaultUnchecked>> = vec![];
+        transfers.extend(
+            self.staked_astro_lps
+                .keys()
+                .map(|denom| TransferPosition::StakedAstroLp(all_of(denom.clone()))),
+        );
+        transfers
+            .extend(self.lends.keys().map(|denom| TransferPosition::Lend(all_of(denom.clone()))));
+        transfers.extend(
+            self.vaults
+                .iter()
+                .map(|position| TransferPosition::Vault(VaultUnchecked::from(&position.vault))),
+        );
+        transfers.extend(
+            self.staking
+                .iter()
+                .map(|position| TransferPosition::Staking(position.validator.clone())),
+        );
+        transfers.extend(
+            self.cl_positions
+                .iter()
+                .map(|position| TransferPosition::ClPosition(position.position_id)),
+        );
+        transfers.extend(
+            self.debt_shares.keys().map(|denom| TransferPosition::Debt(all_of(denom.clone()))),
+        );
+        for transfer in transfers.iter() {
+            self.transfer_position(deps, env, recipient_account_id, transfer)?;
+        }
+
+        self.deposits.clear();
+        Ok(())
+    }
+
+    /// Like the `AssertMinDebtValues` callbacks at the end of dispatch. A failure is reported at the
+    /// last action changing the debt.
+    fn min_debt_failure(
+        &mut self,
+        deps: Deps,
+        checks: BTreeMap<(String, String), usize>,
+    ) -> ContractResult<Option<FailedAction>> {
+        for ((account_id, denom), index) in checks {
+            let amount = if account_id == self.account_id {
+                self.debt_amount(deps, &denom)?
+            } else {
+                self.recipient_debts
+                    .get(&(account_id.clone(), denom.clone()))
+                    .copied()
+                    .unwrap_or_default()
+            };
+
+            let debt = Coin::new(amount.u128(), denom);
+            if let Err(err) = assert_min_debt_value(deps, &account_id, &debt) {
+                return Ok(Some(FailedAction {
+                    index: index as u32,
+                    error: err.to_string(),
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Moves the fee share of the coin from the deposits to the fees paid
+    fn charge_fee(&mut self, coin: &Coin, fee: Decimal) -> ContractResult<Coin> {
+        let fee_paid = fee_amount(coin, fee)?;
+        if !fee_paid.amount.is_zero() {
+            decrement(&mut self.deposits, &fee_paid)?;
+            increment(&mut self.fees, &fee_paid)?;
+        }
+        Ok(fee_paid)
+    }
+
+    fn total_debt_mut(&mut self, deps: Deps, denom: &str) -> ContractResult<&mut TotalDebt> {
+        let total_debt = match self.total_debts.entry(denom.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let amount = RED_BANK.load(deps.storage)?.query_debt(&deps.querier, denom)?;
+                let shares = TOTAL_DEBT_SHARES.may_load(deps.storage, denom)?.unwrap_or_default();
+                entry.insert(TotalDebt {
+                    amount,
+                    shares,
+                })
+            }
+        };
+        Ok(total_debt)
+    }
+
+    fn deposit_caps_exceeded(&self, deps: Deps) -> ContractResult<Vec<DepositCapExceeded>> {
+        let params = PARAMS.load(deps.storage)?;
+
+        let mut exceeded = vec![];
+        for (denom, increase) in &self.deposit_increases {
+            // Deposit caps only apply to assets with params
+            if params.query_asset_params(&deps.querier, denom)?.is_none() {
+                continue;
+            }
+
+            let TotalDepositResponse {
+                amount,
+                cap,
+                ..
+            } = params.query_total_deposit(&deps.querier, denom)?;
+            let amount = amount.checked_add(*increase)?;
+            if amount > cap {
+                exceeded.push(DepositCapExceeded {
+                    denom: denom.clone(),
+                    amount,
+                    cap,
+                });
+            }
+        }
+
+        Ok(exceeded)
+    }
+
+    fn debt_amount(&mut self, deps: Deps, denom: &str) -> ContractResult<Uint128> {
+        let Some(shares) = self.debt_shares.get(denom).copied() else {
+            return Ok(Uint128::zero());
+        };
+        let total_debt = self.total_debt_mut(deps, denom)?;
+        Ok(total_debt.amount.checked_mul_ceil((shares, total_debt.shares))?)
+    }
+
+    /// Health of the projected positions
+    fn health(&self, deps: Deps) -> ContractResult<HealthValuesResponse> {
+        let positions = self.clone().into_positions(deps)?;
+        Ok(HEALTH_CONTRACT.load(deps.storage)?.query_health_values_for_positions(
+            &deps.querier,
+            &positions,
+            positions.account_kind.clone(),
+            ActionKind::Default,
+        )?)
+    }
+
+    fn into_positions(mut self, deps: Deps) -> ContractResult<Positions> {
+        let mut debts = vec![];
+        for (denom, shares) in self.debt_shares.clone() {
+            debts.push(DebtAmount {
+                amount: self.debt_amount(deps, &denom)?,
+                denom,
+                shares,
+            });
+        }
+
+        Ok(Positions {
+            account_id: self.account_id,
+            account_kind: self.account_kind,
+            deposits: to_coins(self.deposits),
+            debts,
+            lends: to_coins(self.lends),
+            vaults: self.vaults,
+            staked_astro_lps: to_coins(self.staked_astro_lps),
+            staking: self.staking,
+            cl_positions: self.cl_positions,
+            perps: self.perps,
+        })
+    }
+}
+
+fn assert_whitelisted(deps: Deps, denom: &str) -> ContractResult<()> {
+    let params = PARAMS.load(deps.storage)?;
+    match params.query_asset_params(&deps.querier, denom) {
+        Ok(Some(p)) if p.credit_manager.whitelisted => Ok(()),
+        _ => Err(ContractError::NotWhitelisted(denom.to_string())),
+    }
+}
+
+fn balance(balances: &BTreeMap<String, Uint128>, denom: &str) -> Uint128 {
+    balances.get(denom).copied().unwrap_or_default()
+}
+
+fn increment(balances: &mut BTreeMap<String, Uint128>, coin: &Coin) -> ContractResult<()> {
+    if coin.amount.is_zero() {
+        return Ok(());
+    }
+    let amount = balances.entry(coin.denom.clone()).or_default();
+    *amount = amount.checked_add(coin.amount)?;
+    Ok(())
+}
+
+fn decrement(balances: &mut BTreeMap<String, Uint128>, coin: &Coin) -> ContractResult<()> {
+    let available = balance(balances, &coin.denom);
+    if coin.amount > available {
+        return Err(ContractError::InsufficientFunds {
+            requested: coin.amount,
+            available,
+        });
+    }
+
+    let remaining = available - coin.amount;
+    if remaining.is_zero() {
+        balances.remove(&coin.denom);
+    } else {
+        balances.insert(coin.denom.clone(), remaining);
+    }
+    Ok(())
+}
+
+fn to_map(coins: Vec<Coin>) -> BTreeMap<String, Uint128> {
+    coins.into_iter().filter(|coin| !coin.amount.is_zero()).map(|c| (c.denom, c.amount)).collect()
+}
+
+fn to_coins(balances: BTreeMap<String, Uint128>) -> Vec<Coin> {
+    balances
+        .into_iter()
+        .map(|(denom, amount)| Coin {
+            denom,
+            amount,
+        })
+        .collect()
+}
@@ -0,0 +1,1040 @@
+use std::{
+    cmp::min,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Coin, CosmosMsg, Decimal, Deps, DepsMut, DistributionMsg, Env, MessageInfo, Order, Response,
+    StakingMsg, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::Bound;
+use mars_types::{
+    credit_manager::{ActionAmount, ActionCoin, CallbackMsg, StakingPosition, UnbondingEntry},
+    params::{AssetParams, StakingParams},
+    traits::Stringify,
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{
+        ACCOUNT_STAKING_REWARD_INDICES, COIN_BALANCES, DELEGATION_SHARES, LAST_STAKING_BATCHES,
+        PARAMS, PENDING_UNBONDINGS, QUEUED_STAKING_REQUESTS, REDELEGATIONS_COMPLETION,
+        REENTRANCY_GUARD, RELEASED_UNBONDINGS, REWARDS_COLLECTOR, STAKING_REWARD_INDICES,
+        TOTAL_DELEGATION_SHARES, UNBONDINGS, UNBONDING_SNAPSHOT,
+    },
+    utils::{decrement_coin_balance, increment_coin_balance},
+};
+
+/// Rover's bond denom balance at the end of the last dispatch. Comparing it with the balance at the
+/// start of the next dispatch gives the coins actually released by completed unbondings, which is
+/// less than the undelegated amount if the validator was slashed while unbonding.
+#[cw_serde]
+pub struct UnbondingSnapshot {
+    pub balance: Uint128,
+    /// Block time (in seconds) of the snapshot
+    pub time: u64,
+    /// Released coins not attributed to completed unbondings yet
+    pub unattributed: Uint128,
+}
+
+/// Unbondings completed at the same time, across all accounts and validators
+#[cw_serde]
+pub struct ReleasedUnbonding {
+    /// Share of the undelegated amount actually released to Rover
+    pub rate: Decimal,
+    /// Undelegated amount not added to the coin balance of its account yet
+    pub unsettled: Uint128,
+}
+
+/// Rover is the delegator of all credit accounts. Each account owns shares of Rover's delegation
+/// to a validator, so that slashing is shared pro rata. Staking rewards are distributed to the
+/// accounts by a reward index per validator and reward denom.
+pub fn delegate(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    validator: &str,
+    coin: ActionCoin,
+) -> ContractResult<Response> {
+    assert_staking_enabled(deps.as_ref(), &coin.denom)?;
+
+    let coin_balance =
+        COIN_BALANCES.may_load(deps.storage, (account_id, &coin.denom))?.unwrap_or_default();
+    let amount = match coin.amount {
+        ActionAmount::Exact(amt) => amt,
+        ActionAmount::AccountBalance => coin_balance,
+    };
+    if amount.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+    let coin = Coin::new(amount.u128(), coin.denom);
+    decrement_coin_balance(deps.storage, account_id, &coin)?;
+
+    // Rewards have to be settled before the shares of the account change
+    let withdraw_msg = update_reward_indices(&mut deps, &env, validator)?;
+    let rewards = accrue_account_rewards(deps.storage, account_id, validator)?;
+
+    let shares = amount_to_shares(deps.as_ref(), &env, validator, amount)?;
+    add_delegation_shares(deps.storage, account_id, validator, shares)?;
+
+    let mut res = Response::new()
+        .add_messages(withdraw_msg)
+        .add_message(StakingMsg::Delegate {
+            validator: validator.to_string(),
+            amount: coin.clone(),
+        })
+        .add_attribute("action", "delegate")
+        .add_attribute("account_id", account_id)
+        .add_attribute("validator", validator)
+        .add_attribute("coin_delegated", coin.to_string())
+        .add_attribute("delegation_shares_added", shares);
+
+    if !rewards.is_empty() {
+        res = res.add_attribute("rewards", rewards.as_slice().to_string());
+    }
+
+    Ok(res)
+}
+
+/// Undelegations are queued and sent with the validator's next batch, so that accounts don't
+/// compete for the chain's limited number of unbonding entries per delegator and validator.
+/// Queued shares stay delegated (and count as collateral) until the batch is sent.
+pub fn undelegate(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    validator: &str,
+    coin: ActionCoin,
+) -> ContractResult<Response> {
+    let staking_params = query_staking_params(deps.as_ref(), &coin.denom)?;
+
+    let (amount, shares) =
+        delegation_to_move(deps.as_ref(), &env, account_id, validator, &coin.amount)?;
+    assert_min_staking_amount(
+        deps.storage,
+        account_id,
+        validator,
+        &staking_params,
+        amount,
+        shares,
+    )?;
+    queue_staking_request(
+        deps.storage,
+        validator,
+        account_id,
+        StakingRequest {
+            shares,
+            dst_validator: None,
+        },
+    )?;
+
+    let (batch_msgs, sent) = send_batch_if_due(&mut deps, &env, validator, &staking_params)?;
+
+    let coin = Coin::new(amount.u128(), coin.denom);
+    Ok(Response::new()
+        .add_messages(batch_msgs)
+        .add_attribute("action", "undelegate")
+        .add_attribute("account_id", account_id)
+        .add_attribute("validator", validator)
+        .add_attribute("coin_undelegated", coin.to_string())
+        .add_attribute("batch_sent", sent.to_string()))
+}
+
+/// Redelegations are queued and sent with the next batch of the source validator
+pub fn redelegate(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    src_validator: &str,
+    dst_validator: &str,
+    coin: ActionCoin,
+) -> ContractResult<Response> {
+    assert_staking_enabled(deps.as_ref(), &coin.denom)?;
+    if src_validator == dst_validator {
+        return Err(ContractError::RedelegationToSameValidator(src_validator.to_string()));
+    }
+    let staking_params = query_staking_params(deps.as_ref(), &coin.denom)?;
+
+    let (amount, shares) =
+        delegation_to_move(deps.as_ref(), &env, account_id, src_validator, &coin.amount)?;
+    assert_min_staking_amount(
+        deps.storage,
+        account_id,
+        src_validator,
+        &staking_params,
+        amount,
+        shares,
+    )?;
+    queue_staking_request(
+        deps.storage,
+        src_validator,
+        account_id,
+        StakingRequest {
+            shares,
+            dst_validator: Some(dst_validator.to_string()),
+        },
+    )?;
+
+    let (batch_msgs, sent) = send_batch_if_due(&mut deps, &env, src_validator, &staking_params)?;
+
+    let coin = Coin::new(amount.u128(), coin.denom);
+    Ok(Response::new()
+        .add_messages(batch_msgs)
+        .add_attribute("action", "redelegate")
+        .add_attribute("account_id", account_id)
+        .add_attribute("src_validator", src_validator)
+        .add_attribute("dst_validator", dst_validator)
+        .add_attribute("coin_redelegated", coin.to_string())
+        .add_attribute("batch_sent", sent.to_string()))
+}
+
+/// Permissionless entry point sending the validator's queued requests once the epoch is over
+pub fn send_staking_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator: String,
+) -> ContractResult<Response> {
+    let denom = deps.querier.query_bonded_denom()?;
+    let staking_params = query_staking_params(deps.as_ref(), &denom)?;
+
+    // The rewards withdrawn with the batch change Rover's bond denom balance
+    REENTRANCY_GUARD.try_lock(deps.storage)?;
+    sync_unbonding_releases(&mut deps, &env, &info.funds)?;
+
+    let (batch_msgs, sent) = send_batch_if_due(&mut deps, &env, &validator, &staking_params)?;
+    if !sent {
+        return Err(ContractError::NoStakingBatchDue {
+            validator,
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(batch_msgs)
+        .add_message(CallbackMsg::RemoveReentrancyGuard {}.into_cosmos_msg(&env.contract.address)?)
+        .add_attribute("action", "send_staking_batch")
+        .add_attribute("validator", validator))
+}
+
+/// The chain allows 7 unbonding entries per delegator and validator (and as many redelegation
+/// entries per validator pair). A validator's queue is sent at most once per epoch of a sixth of
+/// the unbonding period, so there are never more than 7 entries in flight.
+pub const STAKING_EPOCHS_PER_UNBONDING_PERIOD: u64 = 6;
+
+/// Undelegation or redelegation of delegation shares waiting for the validator's next batch
+#[cw_serde]
+pub struct StakingRequest {
+    pub shares: Uint128,
+    /// Validator to redelegate to, None to undelegate
+    pub dst_validator: Option<String>,
+}
+
+fn queue_staking_request(
+    storage: &mut dyn Storage,
+    validator: &str,
+    account_id: &str,
+    request: StakingRequest,
+) -> ContractResult<()> {
+    QUEUED_STAKING_REQUESTS.update(storage, (validator, account_id), |requests| {
+        let mut requests = requests.unwrap_or_default();
+        requests.push(request);
+        Ok::<_, ContractError>(requests)
+    })?;
+    Ok(())
+}
+
+/// Delegation shares of the account queued to be moved away from the validator
+pub fn queued_shares(
+    storage: &dyn Storage,
+    account_id: &str,
+    validator: &str,
+) -> ContractResult<Uint128> {
+    Ok(QUEUED_STAKING_REQUESTS
+        .may_load(storage, (validator, account_id))?
+        .unwrap_or_default()
+        .iter()
+        .try_fold(Uint128::zero(), |total, request| total.checked_add(request.shares))?)
+}
+
+/// Whether the validator's last batch is at least an epoch old
+pub fn staking_batch_due(
+    storage: &dyn Storage,
+    env: &Env,
+    validator: &str,
+    staking_params: &StakingParams,
+) -> ContractResult<bool> {
+    let epoch = staking_params.unbonding_period / STAKING_EPOCHS_PER_UNBONDING_PERIOD;
+    Ok(match LAST_STAKING_BATCHES.may_load(storage, validator)? {
+        Some(last_batch) => env.block.time.seconds() >= last_batch.saturating_add(epoch),
+        None => true,
+    })
+}
+
+/// The chain doesn't allow redelegating from a validator while a redelegation into it is maturing
+pub fn redelegation_allowed(
+    storage: &dyn Storage,
+    env: &Env,
+    validator: &str,
+) -> ContractResult<bool> {
+    Ok(match REDELEGATIONS_COMPLETION.may_load(storage, validator)? {
+        Some(completion_time) => env.block.time.seconds() > completion_time,
+        None => true,
+    })
+}
+
+/// Partial moves have to be at least `min_amount`, so that the queue can't be spammed
+fn assert_min_staking_amount(
+    storage: &dyn Storage,
+    account_id: &str,
+    validator: &str,
+    staking_params: &StakingParams,
+    amount: Uint128,
+    shares: Uint128,
+) -> ContractResult<()> {
+    let account_shares =
+        DELEGATION_SHARES.may_load(storage, (account_id, validator))?.unwrap_or_default();
+    let movable = account_shares.checked_sub(queued_shares(storage, account_id, validator)?)?;
+    check_min_staking_amount(staking_params, amount, shares < movable)
+}
+
+pub fn check_min_staking_amount(
+    staking_params: &StakingParams,
+    amount: Uint128,
+    partial: bool,
+) -> ContractResult<()> {
+    if amount < staking_params.min_amount && partial {
+        return Err(ContractError::StakingAmountBelowMin {
+            amount,
+            min: staking_params.min_amount,
+        });
+    }
+    Ok(())
+}
+
+/// Sends the undelegations and redelegations queued for the validator as one batch, if its last
+/// batch is at least an epoch old. Redelegations stay queued while a redelegation into the
+/// validator is maturing, since the chain doesn't allow redelegating from it until then.
+///
+/// Requests are capped to the shares the account still owns (they might have been liquidated or
+/// transferred since) and converted to amounts at the current delegation of Rover.
+/// Returns the msgs (which have to be sent even if the batch wasn't, since rewards might have been
+/// accrued) and whether the batch was sent.
+pub fn send_batch_if_due(
+    deps: &mut DepsMut,
+    env: &Env,
+    validator: &str,
+    staking_params: &StakingParams,
+) -> ContractResult<(Vec<CosmosMsg>, bool)> {
+    let now = env.block.time.seconds();
+    if !staking_batch_due(deps.storage, env, validator, staking_params)? {
+        return Ok((vec![], false));
+    }
+
+    let redelegation_allowed = redelegation_allowed(deps.storage, env, validator)?;
+    let queued = QUEUED_STAKING_REQUESTS
+        .prefix(validator)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let sendable = queued.iter().any(|(_, requests)| {
+        requests.iter().any(|request| redelegation_allowed || request.dst_validator.is_none())
+    });
+    if !sendable {
+        return Ok((vec![], false));
+    }
+
+    let mut msgs = vec![];
+    msgs.extend(update_reward_indices(deps, env, validator)?);
+    let (delegated, total_shares) = rover_delegation(deps.as_ref(), env, validator)?;
+
+    let completion_time = now + staking_params.unbonding_period;
+    let mut undelegated = Uint128::zero();
+    let mut redelegations: BTreeMap<String, Vec<(String, Uint128)>> = BTreeMap::new();
+    for (account_id, requests) in queued {
+        accrue_account_rewards(deps.storage, &account_id, validator)?;
+        let mut account_shares =
+            DELEGATION_SHARES.may_load(deps.storage, (&account_id, validator))?.unwrap_or_default();
+
+        let mut remaining = vec![];
+        for request in requests {
+            if request.dst_validator.is_some() && !redelegation_allowed {
+                remaining.push(request);
+                continue;
+            }
+            let shares = min(request.shares, account_shares);
+            if shares.is_zero() {
+                continue;
+            }
+            // All amounts are based on Rover's delegation before the batch
+            let amount = shares.checked_multiply_ratio(delegated, total_shares)?;
+            if amount.is_zero() {
+                continue;
+            }
+            account_shares = account_shares.checked_sub(shares)?;
+            remove_delegation_shares(deps.storage, &account_id, validator, shares)?;
+
+            match request.dst_validator {
+                Some(dst_validator) => redelegations
+                    .entry(dst_validator)
+                    .or_default()
+                    .push((account_id.clone(), amount)),
+                None => {
+                    add_unbonding_entries(
+                        deps.storage,
+                        &account_id,
+                        validator,
+                        vec![UnbondingEntry {
+                            amount,
+                            completion_time,
+                        }],
+                    )?;
+                    undelegated = undelegated.checked_add(amount)?;
+                }
+            }
+        }
+
+        if remaining.is_empty() {
+            QUEUED_STAKING_REQUESTS.remove(deps.storage, (validator, &account_id));
+        } else {
+            QUEUED_STAKING_REQUESTS.save(deps.storage, (validator, &account_id), &remaining)?;
+        }
+    }
+
+    let denom = deps.querier.query_bonded_denom()?;
+    if !undelegated.is_zero() {
+        PENDING_UNBONDINGS.update(deps.storage, completion_time, |pending| {
+            pending.unwrap_or_default().checked_add(undelegated).map_err(ContractError::Overflow)
+        })?;
+        msgs.push(
+            StakingMsg::Undelegate {
+                validator: validator.to_string(),
+                amount: Coin::new(undelegated.u128(), denom.clone()),
+            }
+            .into(),
+        );
+    }
+
+    for (dst_validator, moves) in redelegations {
+        msgs.extend(update_reward_indices(deps, env, &dst_validator)?);
+
+        // Shares of the destination are minted for the whole batch at once, since Rover's
+        // delegation to it only changes once the batch is sent
+        let amount = moves
+            .iter()
+            .try_fold(Uint128::zero(), |total, (_, amount)| total.checked_add(*amount))?;
+        let dst_shares = amount_to_shares(deps.as_ref(), env, &dst_validator, amount)?;
+        for (account_id, account_amount) in moves {
+            accrue_account_rewards(deps.storage, &account_id, &dst_validator)?;
+            let shares = dst_shares.checked_multiply_ratio(account_amount, amount)?;
+            add_delegation_shares(deps.storage, &account_id, &dst_validator, shares)?;
+        }
+
+        REDELEGATIONS_COMPLETION.save(deps.storage, &dst_validator, &completion_time)?;
+        msgs.push(
+            StakingMsg::Redelegate {
+                src_validator: validator.to_string(),
+                dst_validator,
+                amount: Coin::new(amount.u128(), denom.clone()),
+            }
+            .into(),
+        );
+    }
+
+    // Requests worth nothing anymore are dropped without sending anything
+    let sent = msgs.iter().any(|msg| matches!(msg, CosmosMsg::Staking(_)));
+    if sent {
+        LAST_STAKING_BATCHES.save(deps.storage, validator, &now)?;
+    }
+    Ok((msgs, sent))
+}
+
+/// Queued requests of the account are dropped, e.g. when its delegation is liquidated
+pub fn cancel_staking_requests(storage: &mut dyn Storage, account_id: &str, validator: &str) {
+    QUEUED_STAKING_REQUESTS.remove(storage, (validator, account_id));
+}
+
+/// Queued requests move to the recipient together with the delegation shares
+pub fn transfer_staking_requests(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    recipient_account_id: &str,
+    validator: &str,
+) -> ContractResult<()> {
+    let Some(requests) = QUEUED_STAKING_REQUESTS.may_load(storage, (validator, account_id))? else {
+        return Ok(());
+    };
+    QUEUED_STAKING_REQUESTS.remove(storage, (validator, account_id));
+    for request in requests {
+        queue_staking_request(storage, validator, recipient_account_id, request)?;
+    }
+    Ok(())
+}
+
+pub fn claim_staking_rewards(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+) -> ContractResult<Response> {
+    let validators = DELEGATION_SHARES
+        .prefix(account_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs = vec![];
+    let mut rewards = vec![];
+    for validator in validators.iter() {
+        msgs.extend(update_reward_indices(&mut deps, &env, validator)?);
+        rewards.extend(accrue_account_rewards(deps.storage, account_id, validator)?);
+    }
+
+    if rewards.is_empty() {
+        return Err(ContractError::NoAmount);
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "claim_staking_rewards")
+        .add_attribute("account_id", account_id)
+        .add_attribute("rewards", rewards.as_slice().to_string()))
+}
+
+/// Attributes the bond denom received by Rover since the last snapshot to completed unbondings.
+/// Has to be called at the start of every dispatch ending with `snapshot_unbonding_balance`, with
+/// the funds sent along (which are already part of Rover's balance).
+///
+/// Unbonded coins are released at the end of the first block at or after the completion time.
+/// Unbondings completed by the time of the snapshot have been released since, so a shortfall is
+/// due to slashing and is shared pro rata. Unbondings completed later are only released once the
+/// received coins cover them in full.
+pub fn sync_unbonding_releases(
+    deps: &mut DepsMut,
+    env: &Env,
+    funds: &[Coin],
+) -> ContractResult<()> {
+    let Some(mut snapshot) = UNBONDING_SNAPSHOT.may_load(deps.storage)? else {
+        return Ok(());
+    };
+
+    let denom = deps.querier.query_bonded_denom()?;
+    let received = funds
+        .iter()
+        .filter(|coin| coin.denom == denom)
+        .try_fold(Uint128::zero(), |total, coin| total.checked_add(coin.amount))?;
+    let balance =
+        deps.querier.query_balance(&env.contract.address, &denom)?.amount.checked_sub(received)?;
+    snapshot.unattributed =
+        snapshot.unattributed.checked_add(balance.saturating_sub(snapshot.balance))?;
+    snapshot.balance = balance;
+
+    let now = env.block.time.seconds();
+    let completed = PENDING_UNBONDINGS
+        .range(deps.storage, None, Some(Bound::exclusive(now)), Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let (released, maybe_released): (Vec<_>, Vec<_>) = completed
+        .into_iter()
+        .partition(|(completion_time, _)| snapshot.time < now && *completion_time <= snapshot.time);
+
+    let expected = released
+        .iter()
+        .try_fold(Uint128::zero(), |total, (_, amount)| total.checked_add(*amount))?;
+    if !expected.is_zero() {
+        let attributed = min(expected, snapshot.unattributed);
+        let rate = Decimal::checked_from_ratio(attributed, expected)?;
+        for (completion_time, amount) in released {
+            release_unbondings(deps.storage, completion_time, rate, amount)?;
+        }
+        snapshot.unattributed = snapshot.unattributed.checked_sub(attributed)?;
+    }
+
+    for (completion_time, amount) in maybe_released {
+        if amount > snapshot.unattributed {
+            break;
+        }
+        release_unbondings(deps.storage, completion_time, Decimal::one(), amount)?;
+        snapshot.unattributed = snapshot.unattributed.checked_sub(amount)?;
+    }
+
+    UNBONDING_SNAPSHOT.save(deps.storage, &snapshot)?;
+
+    Ok(())
+}
+
+/// Records Rover's bond denom balance at the end of a dispatch, as long as there are unbondings
+/// to be released
+pub fn snapshot_unbonding_balance(deps: DepsMut, env: &Env) -> ContractResult<()> {
+    if PENDING_UNBONDINGS.is_empty(deps.storage) {
+        UNBONDING_SNAPSHOT.remove(deps.storage);
+        return Ok(());
+    }
+
+    let denom = deps.querier.query_bonded_denom()?;
+    let balance = deps.querier.query_balance(&env.contract.address, &denom)?.amount;
+    let unattributed = UNBONDING_SNAPSHOT
+        .may_load(deps.storage)?
+        .map(|snapshot| snapshot.unattributed)
+        .unwrap_or_default();
+    UNBONDING_SNAPSHOT.save(
+        deps.storage,
+        &UnbondingSnapshot {
+            balance,
+            time: env.block.time.seconds(),
+            unattributed,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn release_unbondings(
+    storage: &mut dyn Storage,
+    completion_time: u64,
+    rate: Decimal,
+    amount: Uint128,
+) -> ContractResult<()> {
+    PENDING_UNBONDINGS.remove(storage, completion_time);
+    RELEASED_UNBONDINGS.save(
+        storage,
+        completion_time,
+        &ReleasedUnbonding {
+            rate,
+            unsettled: amount,
+        },
+    )?;
+    Ok(())
+}
+
+/// Released unbonding entries of the account are added to its coin balance, reduced by the share
+/// lost to slashing
+pub fn settle_unbondings(deps: &mut DepsMut, account_id: &str) -> ContractResult<()> {
+    let unbondings = UNBONDINGS
+        .prefix(account_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut settled = Uint128::zero();
+    for (validator, entries) in unbondings {
+        let mut pending = vec![];
+        for entry in entries {
+            let Some(mut released) =
+                RELEASED_UNBONDINGS.may_load(deps.storage, entry.completion_time)?
+            else {
+                pending.push(entry);
+                continue;
+            };
+
+            settled = settled.checked_add(entry.amount.checked_mul_floor(released.rate)?)?;
+
+            released.unsettled = released.unsettled.checked_sub(entry.amount)?;
+            if released.unsettled.is_zero() {
+                RELEASED_UNBONDINGS.remove(deps.storage, entry.completion_time);
+            } else {
+                RELEASED_UNBONDINGS.save(deps.storage, entry.completion_time, &released)?;
+            }
+        }
+        save_unbonding_entries(deps.storage, account_id, &validator, pending)?;
+    }
+
+    if !settled.is_zero() {
+        let denom = deps.querier.query_bonded_denom()?;
+        increment_coin_balance(deps.storage, account_id, &Coin::new(settled.u128(), denom))?;
+    }
+
+    Ok(())
+}
+
+pub fn query_staking_positions(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+) -> ContractResult<Vec<StakingPosition>> {
+    let mut validators = DELEGATION_SHARES
+        .prefix(account_id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeSet<_>>>()?;
+    for validator in UNBONDINGS.prefix(account_id).keys(deps.storage, None, None, Order::Ascending)
+    {
+        validators.insert(validator?);
+    }
+
+    if validators.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let denom = deps.querier.query_bonded_denom()?;
+    validators
+        .into_iter()
+        .map(|validator| {
+            let delegated = query_delegated_amount(deps, env, account_id, &validator)?;
+            let queued = shares_to_amount(
+                deps,
+                env,
+                &validator,
+                queued_shares(deps.storage, account_id, &validator)?,
+            )?;
+            let unbonding = UNBONDINGS
+                .may_load(deps.storage, (account_id, &validator))?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| released_entry(deps, entry))
+                .collect::<ContractResult<Vec<_>>>()?;
+            Ok(StakingPosition {
+                validator,
+                delegated: Coin::new(delegated.u128(), denom.clone()),
+                queued,
+                unbonding,
+            })
+        })
+        .collect()
+}
+
+/// Entries released with a shortfall (slashed while unbonding) are only worth their share of it
+fn released_entry(deps: Deps, entry: UnbondingEntry) -> ContractResult<UnbondingEntry> {
+    let Some(released) = RELEASED_UNBONDINGS.may_load(deps.storage, entry.completion_time)? else {
+        return Ok(entry);
+    };
+    Ok(UnbondingEntry {
+        amount: entry.amount.checked_mul_floor(released.rate)?,
+        completion_time: entry.completion_time,
+    })
+}
+
+/// Amount of Rover's delegation to the validator owned by the account
+pub fn query_delegated_amount(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    validator: &str,
+) -> ContractResult<Uint128> {
+    let shares =
+        DELEGATION_SHARES.may_load(deps.storage, (account_id, validator))?.unwrap_or_default();
+    shares_to_amount(deps, env, validator, shares)
+}
+
+fn shares_to_amount(
+    deps: Deps,
+    env: &Env,
+    validator: &str,
+    shares: Uint128,
+) -> ContractResult<Uint128> {
+    if shares.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    let (delegated, total_shares) = rover_delegation(deps, env, validator)?;
+    Ok(shares.checked_multiply_ratio(delegated, total_shares)?)
+}
+
+/// Amount and shares of the account's delegation to be moved away from the validator.
+/// Shares already queued to be moved can't be moved again.
+pub fn delegation_to_move(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    validator: &str,
+    amount: &ActionAmount,
+) -> ContractResult<(Uint128, Uint128)> {
+    let account_shares = DELEGATION_SHARES
+        .may_load(deps.storage, (account_id, validator))?
+        .unwrap_or_default()
+        .checked_sub(queued_shares(deps.storage, account_id, validator)?)?;
+    if account_shares.is_zero() {
+        return Err(ContractError::NoStakingPosition {
+            validator: validator.to_string(),
+        });
+    }
+
+    let (delegated, total_shares) = rover_delegation(deps, env, validator)?;
+    let account_delegated = account_shares.checked_multiply_ratio(delegated, total_shares)?;
+
+    let (amount, shares) = match amount {
+        ActionAmount::Exact(amt) if *amt > account_delegated => {
+            return Err(ContractError::InsufficientFunds {
+                requested: *amt,
+                available: account_delegated,
+            });
+        }
+        // Round shares up so that the account can't move more than it owns
+        ActionAmount::Exact(amt) if *amt < account_delegated => {
+            let shares = amt.checked_mul_ceil((total_shares, delegated))?;
+            (*amt, min(shares, account_shares))
+        }
+        _ => (account_delegated, account_shares),
+    };
+
+    if amount.is_zero() {
+        return Err(ContractError::NoAmount);
+    }
+
+    Ok((amount, shares))
+}
+
+/// Shares minted for delegating `amount` to the validator
+pub fn amount_to_shares(
+    deps: Deps,
+    env: &Env,
+    validator: &str,
+    amount: Uint128,
+) -> ContractResult<Uint128> {
+    let (delegated, total_shares) = rover_delegation(deps, env, validator)?;
+
+    // If there are no shares yet, 1 unit of coin delegated = 1 share
+    let shares = if delegated.is_zero() || total_shares.is_zero() {
+        amount
+    } else {
+        total_shares.checked_multiply_ratio(amount, delegated)?
+    };
+
+    if shares.is_zero() {
+        return Err(ContractError::ZeroDelegationShares);
+    }
+
+    Ok(shares)
+}
+
+/// Amount delegated by Rover to the validator and the total shares of all accounts
+fn rover_delegation(deps: Deps, env: &Env, validator: &str) -> ContractResult<(Uint128, Uint128)> {
+    let delegated = deps
+        .querier
+        .query_delegation(&env.contract.address, validator)?
+        .map(|delegation| delegation.amount.amount)
+        .unwrap_or_default();
+    let total_shares =
+        TOTAL_DELEGATION_SHARES.may_load(deps.storage, validator)?.unwrap_or_default();
+    Ok((delegated, total_shares))
+}
+
+/// Adds the rewards accrued by Rover's delegation to the reward indices of the validator.
+/// Returns the msg withdrawing the rewards to Rover (if there are any).
+pub fn update_reward_indices(
+    deps: &mut DepsMut,
+    env: &Env,
+    validator: &str,
+) -> ContractResult<Option<CosmosMsg>> {
+    let Some(delegation) = deps.querier.query_delegation(&env.contract.address, validator)? else {
+        return Ok(None);
+    };
+
+    let total_shares =
+        TOTAL_DELEGATION_SHARES.may_load(deps.storage, validator)?.unwrap_or_default();
+    let rewards = delegation
+        .accumulated_rewards
+        .into_iter()
+        .filter(|reward| !reward.amount.is_zero())
+        .collect::<Vec<_>>();
+    if rewards.is_empty() {
+        return Ok(None);
+    }
+
+    if total_shares.is_zero() {
+        // No account owns the delegation (only rounding dust is left delegated), so the rewards
+        // go to the rewards-collector account
+        let rewards_collector_account = REWARDS_COLLECTOR.load(deps.storage)?.account_id;
+        for reward in rewards {
+            increment_coin_balance(deps.storage, &rewards_collector_account, &reward)?;
+        }
+    } else {
+        for reward in rewards {
+            let index_increase = Decimal::checked_from_ratio(reward.amount, total_shares)?;
+            STAKING_REWARD_INDICES.update(deps.storage, (validator, &reward.denom), |index| {
+                index
+                    .unwrap_or_default()
+                    .checked_add(index_increase)
+                    .map_err(ContractError::Overflow)
+            })?;
+        }
+    }
+
+    Ok(Some(
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator.to_string(),
+        }
+        .into(),
+    ))
+}
+
+/// Adds the rewards accrued by the account's shares since its last update to its coin balance.
+/// Has to be called whenever the account's shares of the validator change.
+pub fn accrue_account_rewards(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    validator: &str,
+) -> ContractResult<Vec<Coin>> {
+    let shares = DELEGATION_SHARES.may_load(storage, (account_id, validator))?.unwrap_or_default();
+    let indices = STAKING_REWARD_INDICES
+        .prefix(validator)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut rewards = vec![];
+    for (denom, index) in indices {
+        let account_index = ACCOUNT_STAKING_REWARD_INDICES
+            .may_load(storage, (account_id, validator, &denom))?
+            .unwrap_or_default();
+        let amount = shares.checked_mul_floor(index.checked_sub(account_index)?)?;
+        if !amount.is_zero() {
+            let reward = Coin::new(amount.u128(), denom.clone());
+            increment_coin_balance(storage, account_id, &reward)?;
+            rewards.push(reward);
+        }
+        ACCOUNT_STAKING_REWARD_INDICES.save(storage, (account_id, validator, &denom), &index)?;
+    }
+
+    Ok(rewards)
+}
+
+/// Rewards `accrue_account_rewards` would add to the account's coin balance, including the rewards
+/// accrued by Rover's delegation which haven't been withdrawn yet
+pub fn query_pending_staking_rewards(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    validator: &str,
+) -> ContractResult<Vec<Coin>> {
+    let shares =
+        DELEGATION_SHARES.may_load(deps.storage, (account_id, validator))?.unwrap_or_default();
+    if shares.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let mut indices = STAKING_REWARD_INDICES
+        .prefix(validator)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<_, _>>>()?;
+    if let Some(delegation) = deps.querier.query_delegation(&env.contract.address, validator)? {
+        let total_shares = TOTAL_DELEGATION_SHARES.load(deps.storage, validator)?;
+        for reward in delegation.accumulated_rewards {
+            if reward.amount.is_zero() {
+                continue;
+            }
+            let index = indices.entry(reward.denom).or_default();
+            *index =
+                index.checked_add(Decimal::checked_from_ratio(reward.amount, total_shares)?)?;
+        }
+    }
+
+    let mut rewards = vec![];
+    for (denom, index) in indices {
+        let account_index = ACCOUNT_STAKING_REWARD_INDICES
+            .may_load(deps.storage, (account_id, validator, &denom))?
+            .unwrap_or_default();
+        let amount = shares.checked_mul_floor(index.checked_sub(account_index)?)?;
+        if !amount.is_zero() {
+            rewards.push(Coin::new(amount.u128(), denom));
+        }
+    }
+
+    Ok(rewards)
+}
+
+pub fn add_delegation_shares(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    validator: &str,
+    shares: Uint128,
+) -> ContractResult<()> {
+    TOTAL_DELEGATION_SHARES.update(storage, validator, |total| {
+        total.unwrap_or_default().checked_add(shares).map_err(ContractError::Overflow)
+    })?;
+    DELEGATION_SHARES.update(storage, (account_id, validator), |s| {
+        s.unwrap_or_default().checked_add(shares).map_err(ContractError::Overflow)
+    })?;
+    Ok(())
+}
+
+pub fn remove_delegation_shares(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    validator: &str,
+    shares: Uint128,
+) -> ContractResult<()> {
+    let total = TOTAL_DELEGATION_SHARES.load(storage, validator)?.checked_sub(shares)?;
+    if total.is_zero() {
+        TOTAL_DELEGATION_SHARES.remove(storage, validator);
+    } else {
+        TOTAL_DELEGATION_SHARES.save(storage, validator, &total)?;
+    }
+
+    let account_shares =
+        DELEGATION_SHARES.load(storage, (account_id, validator))?.checked_sub(shares)?;
+    if account_shares.is_zero() {
+        DELEGATION_SHARES.remove(storage, (account_id, validator));
+    } else {
+        DELEGATION_SHARES.save(storage, (account_id, validator), &account_shares)?;
+    }
+    Ok(())
+}
+
+/// Adds the entries to the account's unbonding entries, kept sorted by completion time
+pub fn add_unbonding_entries(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    validator: &str,
+    new_entries: Vec<UnbondingEntry>,
+) -> ContractResult<()> {
+    let mut entries = UNBONDINGS.may_load(storage, (account_id, validator))?.unwrap_or_default();
+    entries.extend(new_entries);
+    entries.sort_by_key(|entry| entry.completion_time);
+    save_unbonding_entries(storage, account_id, validator, entries)
+}
+
+pub fn save_unbonding_entries(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    validator: &str,
+    entries: Vec<UnbondingEntry>,
+) -> ContractResult<()> {
+    if entries.is_empty() {
+        UNBONDINGS.remove(storage, (account_id, validator));
+    } else {
+        UNBONDINGS.save(storage, (account_id, validator), &entries)?;
+    }
+    Ok(())
+}
+
+/// Takes `amount` from the entries, earliest completion first. Entries are split if needed.
+pub fn take_unbonding_entries(
+    entries: &mut Vec<UnbondingEntry>,
+    amount: Uint128,
+) -> ContractResult<Vec<UnbondingEntry>> {
+    let mut taken = vec![];
+    let mut remaining = amount;
+    while !remaining.is_zero() {
+        let Some(entry) = entries.first_mut() else {
+            break;
+        };
+        let take = min(entry.amount, remaining);
+        taken.push(UnbondingEntry {
+            amount: take,
+            completion_time: entry.completion_time,
+        });
+        remaining = remaining.checked_sub(take)?;
+        entry.amount = entry.amount.checked_sub(take)?;
+        if entry.amount.is_zero() {
+            entries.remove(0);
+        }
+    }
+    Ok(taken)
+}
+
+/// Only the chain's bond denom with staking params set (and whitelisted) can be delegated
+pub fn assert_staking_enabled(deps: Deps, denom: &str) -> ContractResult<()> {
+    match query_bond_denom_params(deps, denom)? {
+        Some(p) if !p.credit_manager.whitelisted => {
+            Err(ContractError::NotWhitelisted(denom.to_string()))
+        }
+        Some(p) if p.credit_manager.staking.is_some() => Ok(()),
+        _ => Err(ContractError::StakingNotEnabled(denom.to_string())),
+    }
+}
+
+pub fn query_staking_params(deps: Deps, denom: &str) -> ContractResult<StakingParams> {
+    query_bond_denom_params(deps, denom)?
+        .and_then(|p| p.credit_manager.staking)
+        .ok_or_else(|| ContractError::StakingNotEnabled(denom.to_string()))
+}
+
+fn query_bond_denom_params(deps: Deps, denom: &str) -> ContractResult<Option<AssetParams>> {
+    if deps.querier.query_bonded_denom()? != denom {
+        return Err(ContractError::StakingNotEnabled(denom.to_string()));
+    }
+    Ok(PARAMS.load(deps.storage)?.query_asset_params(&deps.querier, denom)?)
+}
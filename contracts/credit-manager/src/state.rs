@@ -1,17 +1,23 @@
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 use mars_owner::Owner;
 use mars_types::{
     adapters::{
         account_nft::AccountNft, health::HealthContract, incentives::Incentives, oracle::Oracle,
-        params::Params, red_bank::RedBank, rewards_collector::RewardsCollector, swapper::Swapper,
-        vault::VaultPositionAmount, zapper::Zapper,
+        params::Params, perps::Perps, pyth::Pyth, red_bank::RedBank,
+        rewards_collector::RewardsCollector, swapper::Swapper, vault::VaultPositionAmount,
+        zapper::Zapper,
     },
+    credit_manager::{DeleverageConfig, FeeConfig, UnbondingEntry},
     health::AccountKind,
 };
 use mars_utils::guard::Guard;
 
-use crate::vault::RequestTempStorage;
+use crate::{
+    concentrated_liquidity::ClPositionRequest,
+    staking::{ReleasedUnbonding, StakingRequest, UnbondingSnapshot},
+    vault::RequestTempStorage,
+};
 
 // Contract dependencies
 pub const ACCOUNT_NFT: Item<AccountNft> = Item::new("account_nft");
@@ -22,12 +28,17 @@ pub const ZAPPER: Item<Zapper> = Item::new("zapper");
 pub const HEALTH_CONTRACT: Item<HealthContract> = Item::new("health_contract");
 pub const PARAMS: Item<Params> = Item::new("params");
 pub const INCENTIVES: Item<Incentives> = Item::new("incentives");
+pub const PERPS: Item<Perps> = Item::new("perps");
+pub const PYTH: Item<Pyth> = Item::new("pyth");
 
 // Config
 pub const OWNER: Owner = Owner::new("owner");
 pub const MAX_UNLOCKING_POSITIONS: Item<Uint128> = Item::new("max_unlocking_positions");
+pub const MAX_CL_POSITIONS: Item<Uint128> = Item::new("max_cl_positions");
 pub const REENTRANCY_GUARD: Guard = Guard::new("reentrancy_guard");
 pub const MAX_SLIPPAGE: Item<Decimal> = Item::new("max_slippage");
+pub const DELEVERAGE_CONFIG: Item<DeleverageConfig> = Item::new("deleverage_config");
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
 
 // Positions
 pub const ACCOUNT_KINDS: Map<&str, AccountKind> = Map::new("account_types"); // Map<AccountId, AccountKind>
@@ -35,11 +46,36 @@ pub const COIN_BALANCES: Map<(&str, &str), Uint128> = Map::new("coin_balance");
 pub const DEBT_SHARES: Map<(&str, &str), Uint128> = Map::new("debt_shares"); // Map<(AccountId, Denom), Shares>
 pub const TOTAL_DEBT_SHARES: Map<&str, Uint128> = Map::new("total_debt_shares"); // Map<Denom, Shares>
 
+pub const DELEVERAGE_OPT_INS: Map<&str, bool> = Map::new("deleverage_opt_ins"); // Map<AccountId, OptedIn>
+
 pub const VAULT_POSITIONS: Map<(&str, Addr), VaultPositionAmount> = Map::new("vault_positions"); // Map<(AccountId, VaultAddr), VaultPositionAmount>
 
+// Native staking (Rover is the delegator of all credit accounts)
+pub const DELEGATION_SHARES: Map<(&str, &str), Uint128> = Map::new("delegation_shares"); // Map<(AccountId, Validator), Shares>
+pub const TOTAL_DELEGATION_SHARES: Map<&str, Uint128> = Map::new("total_delegation_shares"); // Map<Validator, Shares>
+pub const UNBONDINGS: Map<(&str, &str), Vec<UnbondingEntry>> = Map::new("unbondings"); // Map<(AccountId, Validator), Entries>
+pub const STAKING_REWARD_INDICES: Map<(&str, &str), Decimal> = Map::new("staking_reward_indices"); // Map<(Validator, Denom), RewardsPerShare>
+pub const ACCOUNT_STAKING_REWARD_INDICES: Map<(&str, &str, &str), Decimal> =
+    Map::new("account_staking_reward_indices"); // Map<(AccountId, Validator, Denom), RewardsPerShare>
+pub const PENDING_UNBONDINGS: Map<u64, Uint128> = Map::new("pending_unbondings"); // Map<CompletionTime, Amount> of all accounts
+pub const RELEASED_UNBONDINGS: Map<u64, ReleasedUnbonding> = Map::new("released_unbondings"); // Map<CompletionTime, ReleasedUnbonding>
+pub const UNBONDING_SNAPSHOT: Item<UnbondingSnapshot> = Item::new("unbonding_snapshot");
+pub const QUEUED_STAKING_REQUESTS: Map<(&str, &str), Vec<StakingRequest>> =
+    Map::new("queued_staking_requests"); // Map<(Validator, AccountId), Requests> waiting for the next batch
+pub const LAST_STAKING_BATCHES: Map<&str, u64> = Map::new("last_staking_batches"); // Map<Validator, Timestamp> of the last batch sent
+pub const REDELEGATIONS_COMPLETION: Map<&str, u64> = Map::new("redelegations_completion"); // Map<Validator, Timestamp> until which redelegations into it mature
+
+// Net value of the account after the deposits of the actions, reduced by the value withdrawn since.
+// Value change assertions compare against it. Cleared when the reentrancy guard is removed.
+pub const VALUE_CHANGE_REFERENCE: Item<(String, Uint128)> = Item::new("value_change_reference"); // (AccountId, NetValue)
+
+// Osmosis concentrated liquidity positions (Rover is the owner of all positions)
+pub const CL_POSITIONS: Map<(&str, u64), Empty> = Map::new("cl_positions"); // Map<(AccountId, PositionId), Empty>
+
 // Temporary state to save variables to be used on reply handling
 pub const VAULT_REQUEST_TEMP_STORAGE: Item<RequestTempStorage> =
     Item::new("vault_request_temp_var");
+pub const CL_POSITION_TEMP_STORAGE: Item<ClPositionRequest> = Item::new("cl_position_temp_var");
 
 // (account id, addr) for rewards-collector contract
 pub const REWARDS_COLLECTOR: Item<RewardsCollector> = Item::new("rewards_collector");
@@ -6,6 +6,7 @@ use mars_types::{
 
 use crate::{
     error::{ContractError, ContractResult},
+    fees::{charge_fee, load_fee_config},
     state::{COIN_BALANCES, SWAPPER},
     utils::{decrement_coin_balance, update_balance_msg},
 };
@@ -19,7 +20,7 @@ pub fn swap_exact_in(
     min_receive: Uint128,
     route: Option<SwapperRoute>,
 ) -> ContractResult<Response> {
-    let coin_in_to_trade = Coin {
+    let mut coin_in_to_trade = Coin {
         denom: coin_in.denom.clone(),
         amount: match coin_in.amount {
             ActionAmount::Exact(a) => a,
@@ -33,6 +34,11 @@ pub fn swap_exact_in(
         return Err(ContractError::NoAmount);
     }
 
+    // Swap fee is taken from the coin in, the rest is traded
+    let fee = load_fee_config(deps.storage)?.swap_fee;
+    let fee_paid = charge_fee(deps.storage, account_id, &coin_in_to_trade, fee)?;
+    coin_in_to_trade.amount = coin_in_to_trade.amount.checked_sub(fee_paid.amount)?;
+
     decrement_coin_balance(deps.storage, account_id, &coin_in_to_trade)?;
 
     // Updates coin balances for account after the swap has taken place
@@ -52,5 +58,6 @@ pub fn swap_exact_in(
         .add_attribute("action", "swapper")
         .add_attribute("account_id", account_id)
         .add_attribute("coin_in", coin_in_to_trade.to_string())
-        .add_attribute("denom_out", denom_out))
+        .add_attribute("denom_out", denom_out)
+        .add_attribute("swap_fee", fee_paid.to_string()))
 }
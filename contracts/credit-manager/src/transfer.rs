@@ -0,0 +1,325 @@
+use std::cmp::min;
+
+use cosmwasm_std::{Addr, Coin, CosmosMsg, DepsMut, Empty, Env, Response, Storage, Uint128};
+use mars_types::{
+    adapters::vault::Vault,
+    credit_manager::{ActionAmount, ActionCoin, TransferPosition},
+    health::AccountKind,
+};
+
+use crate::{
+    concentrated_liquidity::{assert_cl_position_owner, assert_under_max_cl_positions},
+    error::{ContractError, ContractResult},
+    query::{query_coin_balances, query_positions},
+    repay::{current_debt_for_denom, debt_amount_to_shares},
+    staking::{
+        accrue_account_rewards, add_delegation_shares, add_unbonding_entries,
+        remove_delegation_shares, save_unbonding_entries, transfer_staking_requests,
+        update_reward_indices,
+    },
+    state::{
+        CL_POSITIONS, COIN_BALANCES, DEBT_SHARES, DELEGATION_SHARES, INCENTIVES, RED_BANK,
+        UNBONDINGS, VAULT_POSITIONS,
+    },
+    utils::{
+        assert_is_token_owner, decrement_coin_balance, get_account_kind, increment_coin_balance,
+    },
+};
+
+/// Positions can only be moved between two different, non fund manager, accounts of the same owner
+pub fn assert_transfer_allowed(
+    deps: &DepsMut,
+    sender: &Addr,
+    account_id: &str,
+    recipient_account_id: &str,
+) -> ContractResult<()> {
+    if account_id == recipient_account_id {
+        return Err(ContractError::TransferNotAllowed {
+            reason: "can not transfer to the same account".to_string(),
+        });
+    }
+
+    assert_is_token_owner(deps, sender, recipient_account_id)?;
+    assert_no_fund_manager(deps.storage, account_id, recipient_account_id)
+}
+
+pub fn assert_no_fund_manager(
+    storage: &dyn Storage,
+    account_id: &str,
+    recipient_account_id: &str,
+) -> ContractResult<()> {
+    for id in [account_id, recipient_account_id] {
+        if let AccountKind::FundManager {
+            ..
+        } = get_account_kind(storage, id)?
+        {
+            return Err(ContractError::TransferNotAllowed {
+                reason: "fund manager accounts can not transfer positions".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn transfer_to_account(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    recipient_account_id: &str,
+    positions: Vec<TransferPosition<Vault>>,
+) -> ContractResult<Response> {
+    let msgs = transfer_positions(&mut deps, &env, account_id, recipient_account_id, positions)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "transfer_to_account")
+        .add_attribute("account_id", account_id)
+        .add_attribute("recipient_account_id", recipient_account_id))
+}
+
+pub fn merge_accounts(
+    mut deps: DepsMut,
+    env: Env,
+    account_id: &str,
+    recipient_account_id: &str,
+) -> ContractResult<Response> {
+    let positions = query_positions(deps.as_ref(), &env, account_id)?;
+
+    // Perp positions are held by the perps contract on behalf of the account and can't be moved
+    if !positions.perps.is_empty() {
+        return Err(ContractError::TransferNotAllowed {
+            reason: "perp positions can not be transferred, close them first".to_string(),
+        });
+    }
+
+    let mut transfers = vec![];
+    transfers.extend(
+        positions
+            .staked_astro_lps
+            .into_iter()
+            .map(|coin| TransferPosition::StakedAstroLp(all_of(coin.denom))),
+    );
+    transfers
+        .extend(positions.lends.into_iter().map(|coin| TransferPosition::Lend(all_of(coin.denom))));
+    transfers.extend(
+        positions.vaults.into_iter().map(|position| TransferPosition::Vault(position.vault)),
+    );
+    transfers.extend(
+        positions.staking.into_iter().map(|position| TransferPosition::Staking(position.validator)),
+    );
+    transfers.extend(
+        positions
+            .cl_positions
+            .into_iter()
+            .map(|position| TransferPosition::ClPosition(position.position_id)),
+    );
+    transfers
+        .extend(positions.debts.into_iter().map(|debt| TransferPosition::Debt(all_of(debt.denom))));
+    let mut msgs =
+        transfer_positions(&mut deps, &env, account_id, recipient_account_id, transfers)?;
+
+    // Coin balances are moved last, they include the rewards claimed when unstaking Astroport LPs
+    // and the staking rewards accrued when moving staking positions
+    let deposits = query_coin_balances(deps.as_ref(), account_id)?
+        .into_iter()
+        .map(|coin| TransferPosition::Deposit(all_of(coin.denom)))
+        .collect();
+    msgs.extend(transfer_positions(&mut deps, &env, account_id, recipient_account_id, deposits)?);
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "merge_accounts")
+        .add_attribute("account_id", account_id)
+        .add_attribute("recipient_account_id", recipient_account_id))
+}
+
+fn transfer_positions(
+    deps: &mut DepsMut,
+    env: &Env,
+    account_id: &str,
+    recipient_account_id: &str,
+    positions: Vec<TransferPosition<Vault>>,
+) -> ContractResult<Vec<CosmosMsg>> {
+    let mut msgs = vec![];
+
+    for position in positions {
+        match position {
+            TransferPosition::Deposit(coin) => {
+                let balance = COIN_BALANCES
+                    .may_load(deps.storage, (account_id, &coin.denom))?
+                    .unwrap_or_default();
+                let amount = coin.amount.value().unwrap_or(balance);
+                if amount.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+                if amount > balance {
+                    return Err(ContractError::InsufficientFunds {
+                        requested: amount,
+                        available: balance,
+                    });
+                }
+
+                let coin = Coin::new(amount.u128(), coin.denom);
+                decrement_coin_balance(deps.storage, account_id, &coin)?;
+                increment_coin_balance(deps.storage, recipient_account_id, &coin)?;
+            }
+            TransferPosition::Debt(coin) => {
+                let (debt_amount, debt_shares) =
+                    current_debt_for_denom(deps.as_ref(), account_id, &coin.denom)?;
+
+                // Total debt shares don't change, shares are only moved between the accounts
+                let shares = match coin.amount {
+                    ActionAmount::Exact(amount) if amount < debt_amount => debt_amount_to_shares(
+                        deps.as_ref(),
+                        &Coin::new(amount.u128(), &coin.denom),
+                    )?,
+                    _ => debt_shares,
+                };
+                if shares.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+
+                if shares == debt_shares {
+                    DEBT_SHARES.remove(deps.storage, (account_id, &coin.denom));
+                } else {
+                    DEBT_SHARES.save(
+                        deps.storage,
+                        (account_id, &coin.denom),
+                        &debt_shares.checked_sub(shares)?,
+                    )?;
+                }
+                DEBT_SHARES.update(deps.storage, (recipient_account_id, &coin.denom), |s| {
+                    s.unwrap_or_else(Uint128::zero)
+                        .checked_add(shares)
+                        .map_err(ContractError::Overflow)
+                })?;
+            }
+            TransferPosition::Lend(coin) => {
+                let red_bank = RED_BANK.load(deps.storage)?;
+                let lent = red_bank.query_lent(&deps.querier, account_id, &coin.denom)?;
+                let amount = min(lent, coin.amount.value().unwrap_or(lent));
+                if amount.is_zero() {
+                    return Err(ContractError::NoneLent);
+                }
+
+                // Reclaimed coins are received by Rover before being lent again for the recipient
+                let coin = Coin::new(amount.u128(), coin.denom);
+                msgs.push(red_bank.reclaim_msg(&coin, account_id, false)?);
+                msgs.push(red_bank.lend_msg(&coin, recipient_account_id)?);
+            }
+            TransferPosition::Vault(vault) => {
+                let position = VAULT_POSITIONS
+                    .may_load(deps.storage, (account_id, vault.address.clone()))?
+                    .ok_or_else(|| ContractError::TransferNotAllowed {
+                        reason: format!("account has no position in vault {}", vault.address),
+                    })?;
+
+                if VAULT_POSITIONS.has(deps.storage, (recipient_account_id, vault.address.clone()))
+                {
+                    return Err(ContractError::TransferNotAllowed {
+                        reason: format!(
+                            "recipient account already has a position in vault {}",
+                            vault.address
+                        ),
+                    });
+                }
+
+                VAULT_POSITIONS.remove(deps.storage, (account_id, vault.address.clone()));
+                VAULT_POSITIONS.save(
+                    deps.storage,
+                    (recipient_account_id, vault.address),
+                    &position,
+                )?;
+            }
+            TransferPosition::StakedAstroLp(coin) => {
+                let incentives = INCENTIVES.load(deps.storage)?;
+
+                // Unstaking and staking claim the pending rewards of the respective account
+                let position = incentives.query_staked_astro_lp_position(
+                    &deps.querier,
+                    account_id,
+                    &coin.denom,
+                )?;
+                let recipient_rewards = incentives.query_staked_astro_lp_rewards(
+                    &deps.querier,
+                    recipient_account_id,
+                    &coin.denom,
+                )?;
+                for reward in position.rewards.iter() {
+                    increment_coin_balance(deps.storage, account_id, reward)?;
+                }
+                for reward in recipient_rewards.iter() {
+                    increment_coin_balance(deps.storage, recipient_account_id, reward)?;
+                }
+
+                let staked = position.lp_coin.amount;
+                let amount = match coin.amount {
+                    ActionAmount::Exact(amount) if amount > staked => {
+                        return Err(ContractError::InsufficientFunds {
+                            requested: amount,
+                            available: staked,
+                        });
+                    }
+                    ActionAmount::Exact(amount) => amount,
+                    ActionAmount::AccountBalance => staked,
+                };
+                if amount.is_zero() {
+                    return Err(ContractError::NoAmount);
+                }
+
+                let lp_coin = Coin::new(amount.u128(), coin.denom);
+                msgs.push(incentives.unstake_astro_lp_msg(account_id, &lp_coin)?);
+                msgs.push(incentives.stake_astro_lp_msg(recipient_account_id, lp_coin)?);
+            }
+            TransferPosition::Staking(validator) => {
+                let shares = DELEGATION_SHARES
+                    .may_load(deps.storage, (account_id, &validator))?
+                    .unwrap_or_default();
+                let entries = UNBONDINGS
+                    .may_load(deps.storage, (account_id, &validator))?
+                    .unwrap_or_default();
+                if shares.is_zero() && entries.is_empty() {
+                    return Err(ContractError::NoStakingPosition {
+                        validator,
+                    });
+                }
+
+                // Rover stays the delegator, only the shares and unbonding entries are moved.
+                // Rewards accrued so far are settled to the respective account.
+                if !shares.is_zero() {
+                    msgs.extend(update_reward_indices(deps, env, &validator)?);
+                    accrue_account_rewards(deps.storage, account_id, &validator)?;
+                    accrue_account_rewards(deps.storage, recipient_account_id, &validator)?;
+                    remove_delegation_shares(deps.storage, account_id, &validator, shares)?;
+                    add_delegation_shares(deps.storage, recipient_account_id, &validator, shares)?;
+                    transfer_staking_requests(
+                        deps.storage,
+                        account_id,
+                        recipient_account_id,
+                        &validator,
+                    )?;
+                }
+
+                save_unbonding_entries(deps.storage, account_id, &validator, vec![])?;
+                add_unbonding_entries(deps.storage, recipient_account_id, &validator, entries)?;
+            }
+            TransferPosition::ClPosition(position_id) => {
+                // Rover stays the owner of the position, claimable rewards move with it
+                assert_cl_position_owner(deps.storage, account_id, position_id)?;
+                assert_under_max_cl_positions(deps.storage, recipient_account_id)?;
+                CL_POSITIONS.remove(deps.storage, (account_id, position_id));
+                CL_POSITIONS.save(deps.storage, (recipient_account_id, position_id), &Empty {})?;
+            }
+        }
+    }
+
+    Ok(msgs)
+}
+
+pub fn all_of(denom: String) -> ActionCoin {
+    ActionCoin {
+        denom,
+        amount: ActionAmount::AccountBalance,
+    }
+}
@@ -9,11 +9,14 @@ use mars_types::{
 };
 
 use crate::{
+    deleverage::assert_deleverage_config,
     error::ContractResult,
     execute::create_credit_account,
+    fees::assert_fee_config,
     state::{
-        ACCOUNT_NFT, HEALTH_CONTRACT, INCENTIVES, MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE,
-        OWNER, RED_BANK, REWARDS_COLLECTOR, SWAPPER, ZAPPER,
+        ACCOUNT_NFT, DELEVERAGE_CONFIG, FEE_CONFIG, HEALTH_CONTRACT, INCENTIVES, MAX_CL_POSITIONS,
+        MAX_SLIPPAGE, MAX_UNLOCKING_POSITIONS, ORACLE, OWNER, PERPS, PYTH, RED_BANK,
+        REWARDS_COLLECTOR, SWAPPER, ZAPPER,
     },
     utils::assert_max_slippage,
 };
@@ -76,6 +79,13 @@ pub fn update_config(
             .add_attribute("value", num.to_string());
     }
 
+    if let Some(num) = updates.max_cl_positions {
+        MAX_CL_POSITIONS.save(deps.storage, &num)?;
+        response = response
+            .add_attribute("key", "max_cl_positions")
+            .add_attribute("value", num.to_string());
+    }
+
     if let Some(num) = updates.max_slippage {
         assert_max_slippage(num)?;
         MAX_SLIPPAGE.save(deps.storage, &num)?;
@@ -83,6 +93,22 @@ pub fn update_config(
             response.add_attribute("key", "max_slippage").add_attribute("value", num.to_string());
     }
 
+    if let Some(config) = updates.deleverage_config {
+        assert_deleverage_config(&config)?;
+        DELEVERAGE_CONFIG.save(deps.storage, &config)?;
+        response = response
+            .add_attribute("key", "deleverage_config")
+            .add_attribute("value", format!("{config:?}"));
+    }
+
+    if let Some(config) = updates.fee_config {
+        assert_fee_config(&config)?;
+        FEE_CONFIG.save(deps.storage, &config)?;
+        response = response
+            .add_attribute("key", "fee_config")
+            .add_attribute("value", format!("{config:?}"));
+    }
+
     if let Some(unchecked) = updates.health_contract {
         HEALTH_CONTRACT.save(deps.storage, &unchecked.check(deps.api)?)?;
         response = response
@@ -96,6 +122,18 @@ pub fn update_config(
             response.add_attribute("key", "incentives").add_attribute("value", unchecked.address());
     }
 
+    if let Some(unchecked) = updates.perps {
+        PERPS.save(deps.storage, &unchecked.check(deps.api)?)?;
+        response =
+            response.add_attribute("key", "perps").add_attribute("value", unchecked.address());
+    }
+
+    if let Some(unchecked) = updates.pyth {
+        PYTH.save(deps.storage, &unchecked.check(deps.api)?)?;
+        response =
+            response.add_attribute("key", "pyth").add_attribute("value", unchecked.address());
+    }
+
     if let Some(unchecked) = updates.rewards_collector {
         let rewards_collector_addr = deps.api.addr_validate(&unchecked)?;
 
@@ -8,6 +8,7 @@ use mars_types::{
 
 use crate::{
     error::{ContractError, ContractResult},
+    fees::{charge_fee, fee_amount, load_fee_config},
     state::{COIN_BALANCES, ZAPPER},
     utils::{
         assert_coin_is_whitelisted, assert_coins_are_whitelisted, assert_slippage,
@@ -28,7 +29,9 @@ pub fn provide_liquidity(
     assert_coin_is_whitelisted(&mut deps, lp_token_out)?;
     assert_coins_are_whitelisted(&mut deps, coins_in.to_denoms())?;
 
-    // Decrement coin amounts in account for those sent to pool
+    // Decrement coin amounts in account for those sent to pool. Swap fee is taken from each coin.
+    let fee = load_fee_config(deps.storage)?.swap_fee;
+    let mut fees_paid: Vec<Coin> = vec![];
     let mut updated_coins_in: Vec<Coin> = Vec::with_capacity(coins_in.len());
     for coin_in in coins_in {
         let coin_balance = COIN_BALANCES.load(deps.storage, (account_id, &coin_in.denom))?;
@@ -36,10 +39,15 @@ pub fn provide_liquidity(
             ActionAmount::Exact(amt) => amt,
             ActionAmount::AccountBalance => coin_balance,
         };
-        let updated_coin = Coin {
+        let mut updated_coin = Coin {
             denom: coin_in.denom,
             amount: new_amount,
         };
+        let fee_paid = charge_fee(deps.storage, account_id, &updated_coin, fee)?;
+        updated_coin.amount = updated_coin.amount.checked_sub(fee_paid.amount)?;
+        if !fee_paid.amount.is_zero() {
+            fees_paid.push(fee_paid);
+        }
         decrement_coin_balance(deps.storage, account_id, &updated_coin)?;
         updated_coins_in.push(updated_coin);
     }
@@ -73,7 +81,8 @@ pub fn provide_liquidity(
         .add_attribute("action", "provide_liquidity")
         .add_attribute("account_id", account_id)
         .add_attribute("coins_in", updated_coins_in.as_slice().to_string())
-        .add_attribute("lp_token_out", lp_token_out))
+        .add_attribute("lp_token_out", lp_token_out)
+        .add_attribute("swap_fee", fees_paid.as_slice().to_string()))
 }
 
 pub fn withdraw_liquidity(
@@ -140,6 +149,19 @@ pub fn estimate_provide_liquidity(
     lp_token_out: &str,
     coins_in: Vec<Coin>,
 ) -> ContractResult<Uint128> {
+    // Coins are provided net of the swap fee
+    let fee = load_fee_config(deps.storage)?.swap_fee;
+    let coins_in = coins_in
+        .into_iter()
+        .map(|coin| {
+            let fee_paid = fee_amount(&coin, fee)?;
+            Ok(Coin {
+                amount: coin.amount.checked_sub(fee_paid.amount)?,
+                denom: coin.denom,
+            })
+        })
+        .collect::<ContractResult<Vec<_>>>()?;
+
     let zapper = ZAPPER.load(deps.storage)?;
     let estimate = zapper.estimate_provide_liquidity(&deps.querier, lp_token_out, &coins_in)?;
     Ok(estimate)
@@ -1,39 +1,52 @@
 pub use mars_testing::multitest::helpers;
 
+mod test_assertions;
 mod test_borrow;
+mod test_cl_positions;
 mod test_claim_astro_lp_rewards;
 mod test_claim_rewards;
 mod test_coin_balances;
 mod test_create_credit_account;
+mod test_deleverage;
 mod test_deposit;
 mod test_deposit_cap;
 mod test_dispatch;
+mod test_dust;
 mod test_enumerate_accounts;
 mod test_enumerate_coin_balances;
 mod test_enumerate_debt_shares;
 mod test_enumerate_total_debt_shares;
 mod test_enumerate_vault_positions;
+mod test_fees;
 mod test_fund_manager_accounts;
 mod test_health;
 mod test_hls_accounts;
 mod test_instantiate;
+mod test_invariants;
 mod test_lend;
 mod test_liquidate_deposit;
 mod test_liquidate_guard;
 mod test_liquidate_lend;
 mod test_liquidate_staked_astro_lp;
+mod test_liquidate_staking;
 mod test_liquidate_vault;
 mod test_liquidation_pricing;
 mod test_migration_v2;
 mod test_no_health_check;
+mod test_pause;
+mod test_perps;
+mod test_price_updates;
 mod test_reclaim;
 mod test_reentrancy_guard;
 mod test_refund_balances;
 mod test_repay;
 mod test_repay_for_recipient;
 mod test_repay_from_wallet;
+mod test_simulate_actions;
 mod test_stake_astro_lp;
+mod test_staking;
 mod test_swap;
+mod test_transfer_to_account;
 mod test_unstake_astro_lp;
 mod test_update_admin;
 mod test_update_config;
@@ -0,0 +1,271 @@
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::credit_manager::{
+    Action::{
+        AssertCoinBalanceAtLeast, AssertDebtAtMost, AssertHealthFactorAbove, AssertValueChange,
+        Borrow, Deposit, RefundAllCoinBalances, SwapExactIn, Withdraw,
+    },
+    ActionAmount, ActionCoin,
+};
+
+use super::helpers::{assert_err, get_coin, uatom_info, uosmo_info, AccountToFund, MockEnv};
+
+#[test]
+fn coin_balance_asserted_at_its_position() {
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(200, uatom_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(100)),
+            Withdraw(uatom_info.to_action_coin(50)),
+            AssertCoinBalanceAtLeast(uatom_info.to_coin(100)),
+        ],
+        &[uatom_info.to_coin(100)],
+    );
+    assert_err(
+        res,
+        ContractError::CoinBalanceBelowMin {
+            denom: uatom_info.denom.clone(),
+            balance: Uint128::new(50),
+            min: Uint128::new(100),
+        },
+    );
+
+    // Passes before the withdraw
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(100)),
+            AssertCoinBalanceAtLeast(uatom_info.to_coin(100)),
+            Withdraw(uatom_info.to_action_coin(50)),
+        ],
+        &[uatom_info.to_coin(100)],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(&uatom_info.denom, &position.deposits).amount, Uint128::new(50));
+}
+
+#[test]
+fn health_factor_asserted_at_its_position() {
+    let (mut mock, account_id) = setup_account_with_collateral(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Borrow(uosmo_info.to_coin(2_000)), AssertHealthFactorAbove(Decimal::percent(300))],
+        &[],
+    );
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert!(matches!(
+        err,
+        ContractError::HealthFactorBelowMin {
+            min_health_factor,
+            ..
+        } if min_health_factor == Decimal::percent(300)
+    ));
+
+    // Account without debt has no max LTV health factor and passes
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AssertHealthFactorAbove(Decimal::percent(300)), Borrow(uosmo_info.to_coin(2_000))],
+        &[],
+    )
+    .unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AssertHealthFactorAbove(Decimal::percent(200))],
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn debt_asserted_at_most() {
+    let (mut mock, account_id) = setup_account_with_collateral(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    // Mock Red Bank accrues a unit of interest on borrow
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Borrow(uosmo_info.to_coin(100)), AssertDebtAtMost(uosmo_info.to_coin(100))],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::DebtAboveMax {
+            denom: uosmo_info.denom.clone(),
+            debt: Uint128::new(101),
+            max: Uint128::new(100),
+        },
+    );
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Borrow(uosmo_info.to_coin(100)), AssertDebtAtMost(uosmo_info.to_coin(101))],
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn value_change_asserted() {
+    let (mut mock, account_id) = setup_account_with_collateral(10_000);
+    let user = Addr::unchecked("user");
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    // 1_000 uatom (value 1_000) are swapped for 1_337 uosmo (value 334), a loss of 6.66%
+    let swap = SwapExactIn {
+        coin_in: ActionCoin {
+            denom: uatom_info.denom.clone(),
+            amount: ActionAmount::Exact(Uint128::new(1_000)),
+        },
+        denom_out: uosmo_info.denom.clone(),
+        min_receive: Uint128::zero(),
+        route: None,
+    };
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            swap.clone(),
+            AssertValueChange {
+                max_loss_pct: Decimal::percent(5),
+            },
+        ],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::ValueLossExceeded {
+            prev_net_value: Uint128::new(10_000),
+            new_net_value: Uint128::new(9_334),
+            max_loss_pct: Decimal::percent(5),
+        },
+    );
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            swap,
+            AssertValueChange {
+                max_loss_pct: Decimal::percent(10),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn value_change_excludes_deposits_and_withdrawals() {
+    let (mut mock, account_id) = setup_account_with_collateral(10_000);
+    let user = Addr::unchecked("user");
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Withdraw(uatom_info.to_action_coin(1_000))],
+        &[],
+    )
+    .unwrap();
+
+    // The deposit doesn't hide the loss of the swap
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            SwapExactIn {
+                coin_in: uatom_info.to_action_coin(1_000),
+                denom_out: uosmo_info.denom.clone(),
+                min_receive: Uint128::zero(),
+                route: None,
+            },
+            AssertValueChange {
+                max_loss_pct: Decimal::percent(5),
+            },
+        ],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::ValueLossExceeded {
+            prev_net_value: Uint128::new(10_000),
+            new_net_value: Uint128::new(9_334),
+            max_loss_pct: Decimal::percent(5),
+        },
+    );
+
+    // Withdrawn coins aren't a loss
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Withdraw(uatom_info.to_action_coin(5_000)),
+            AssertValueChange {
+                max_loss_pct: Decimal::percent(1),
+            },
+            RefundAllCoinBalances {},
+            AssertValueChange {
+                max_loss_pct: Decimal::percent(1),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+    assert!(mock.query_positions(&account_id).deposits.is_empty());
+}
+
+fn setup_account_with_collateral(collateral: u128) -> (MockEnv, String) {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(collateral, uatom_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(collateral))],
+        &[uatom_info.to_coin(collateral)],
+    )
+    .unwrap();
+
+    (mock, account_id)
+}
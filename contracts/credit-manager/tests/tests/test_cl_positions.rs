@@ -0,0 +1,541 @@
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{
+        Action::{
+            self, AddToClPosition, Borrow, ClaimClPositionRewards, CreateClPosition, Deposit,
+            Liquidate, TransferToAccount, Withdraw, WithdrawClPosition,
+        },
+        LiquidateRequest, TransferPosition,
+    },
+    health::AccountKind,
+    oracle::ActionKind,
+};
+
+use super::helpers::{assert_err, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv};
+
+#[test]
+fn only_owner_can_manage_cl_position() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+    let recipient_account_id = mock.create_credit_account(&user).unwrap();
+
+    let no_position = || ContractError::NoClPosition {
+        account_id: account_id.clone(),
+        position_id: 23,
+    };
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AddToClPosition {
+            position_id: 23,
+            coins_in: vec![uatom_info.to_action_coin(100), uosmo_info.to_action_coin(100)],
+            token_min_amount0: Default::default(),
+            token_min_amount1: Default::default(),
+        }],
+        &[],
+    );
+    assert_err(res, no_position());
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![WithdrawClPosition {
+            position_id: 23,
+            liquidity: Some(Decimal256::one()),
+        }],
+        &[],
+    );
+    assert_err(res, no_position());
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![ClaimClPositionRewards {
+            position_id: 23,
+        }],
+        &[],
+    );
+    assert_err(res, no_position());
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![TransferToAccount {
+            recipient_account_id,
+            positions: vec![TransferPosition::ClPosition(23)],
+        }],
+        &[],
+    );
+    assert_err(res, no_position());
+}
+
+#[test]
+fn account_without_cl_positions_reports_none() {
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert!(positions.cl_positions.is_empty());
+}
+
+const POOL_ID: u64 = 1;
+/// Price range [2, 8) uosmo per uatom
+const LOWER_TICK: i64 = 1_000_000;
+const UPPER_TICK: i64 = 7_000_000;
+
+/// Mocked CL pool trading at the oracle prices of uatom (1) and uosmo (0.25)
+fn setup_cl_pool(mock: &mut MockEnv) {
+    mock.set_cl_pool(POOL_ID, "uatom", "uosmo", Decimal::from_atomics(4u128, 0).unwrap());
+}
+
+fn create_cl_position_action(uatom: Uint128, uosmo: Uint128) -> Action {
+    CreateClPosition {
+        pool_id: POOL_ID,
+        lower_tick: LOWER_TICK,
+        upper_tick: UPPER_TICK,
+        coins_in: vec![
+            uatom_info().to_action_coin(uatom.u128()),
+            uosmo_info().to_action_coin(uosmo.u128()),
+        ],
+        token_min_amount0: Uint128::zero(),
+        token_min_amount1: Uint128::zero(),
+    }
+}
+
+fn coin_amount(denom: &str, coins: &[Coin]) -> Uint128 {
+    coins.iter().find(|c| c.denom == denom).map(|c| c.amount).unwrap_or_default()
+}
+
+#[test]
+fn create_and_withdraw_cl_position() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(5_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(5_000)),
+            create_cl_position_action(Uint128::new(1_000), Uint128::new(5_000)),
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(5_000)],
+    )
+    .unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.cl_positions.len(), 1);
+    let position = positions.cl_positions[0].clone();
+    assert_eq!(position.pool_id, POOL_ID);
+    assert_eq!(position.lower_tick, LOWER_TICK);
+    assert_eq!(position.upper_tick, UPPER_TICK);
+
+    // Tokens not used by the pool are refunded to the account, uatom limits the liquidity
+    let uatom_balance = coin_amount("uatom", &positions.deposits);
+    let uosmo_balance = coin_amount("uosmo", &positions.deposits);
+    assert_eq!(uatom_balance + position.asset0.amount, Uint128::new(1_000));
+    assert_eq!(uosmo_balance + position.asset1.amount, Uint128::new(5_000));
+    assert!(uatom_balance <= Uint128::one());
+    assert!(uosmo_balance >= Uint128::new(999));
+
+    // Partial withdrawal keeps the position
+    let half = position.liquidity * Decimal256::percent(50);
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![WithdrawClPosition {
+            position_id: position.position_id,
+            liquidity: Some(half),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.cl_positions.len(), 1);
+    let remaining = positions.cl_positions[0].clone();
+    assert_eq!(remaining.position_id, position.position_id);
+    assert_eq!(remaining.liquidity, position.liquidity - half);
+    let uatom_balance = coin_amount("uatom", &positions.deposits);
+    let uosmo_balance = coin_amount("uosmo", &positions.deposits);
+    assert!(uatom_balance > Uint128::new(490));
+
+    // Withdrawing without liquidity closes the position
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![WithdrawClPosition {
+            position_id: position.position_id,
+            liquidity: None,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert!(positions.cl_positions.is_empty());
+    assert_eq!(coin_amount("uatom", &positions.deposits), uatom_balance + remaining.asset0.amount);
+    assert_eq!(coin_amount("uosmo", &positions.deposits), uosmo_balance + remaining.asset1.amount);
+
+    let rover_uatom = mock.query_balance(&mock.rover, "uatom");
+    assert_eq!(rover_uatom.amount, coin_amount("uatom", &positions.deposits));
+}
+
+#[test]
+fn cl_positions_limited_per_account() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .max_cl_positions(1)
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(5_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(5_000)),
+            create_cl_position_action(Uint128::new(500), Uint128::new(2_500)),
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(5_000)],
+    )
+    .unwrap();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![create_cl_position_action(Uint128::new(100), Uint128::new(500))],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::ExceedsMaxClPositions {
+            new_amount: Uint128::new(2),
+            maximum: Uint128::one(),
+        },
+    );
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.cl_positions.len(), 1);
+}
+
+#[test]
+fn add_to_cl_position_replaces_position() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(4_000)),
+            create_cl_position_action(Uint128::new(500), Uint128::new(2_000)),
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+    )
+    .unwrap();
+    let position = mock.query_positions(&account_id).cl_positions[0].clone();
+
+    mock.add_cl_position_rewards(position.position_id, vec![uosmo_info.to_coin(10)], vec![]);
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AddToClPosition {
+            position_id: position.position_id,
+            coins_in: vec![uatom_info.to_action_coin(500), uosmo_info.to_action_coin(2_000)],
+            token_min_amount0: Uint128::zero(),
+            token_min_amount1: Uint128::zero(),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // Osmosis replaces the position with a new one
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(positions.cl_positions.len(), 1);
+    let new_position = positions.cl_positions[0].clone();
+    assert_ne!(new_position.position_id, position.position_id);
+    assert!(new_position.liquidity > position.liquidity);
+
+    // Rewards of the replaced position are claimed into the account
+    assert_eq!(
+        coin_amount("uatom", &positions.deposits) + new_position.asset0.amount,
+        Uint128::new(1_000)
+    );
+    assert_eq!(
+        coin_amount("uosmo", &positions.deposits) + new_position.asset1.amount,
+        Uint128::new(4_010)
+    );
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![WithdrawClPosition {
+            position_id: position.position_id,
+            liquidity: None,
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::NoClPosition {
+            account_id: account_id.clone(),
+            position_id: position.position_id,
+        },
+    );
+}
+
+#[test]
+fn claim_cl_position_rewards() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(4_000)),
+            create_cl_position_action(Uint128::new(1_000), Uint128::new(4_000)),
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+    )
+    .unwrap();
+    let positions = mock.query_positions(&account_id);
+    let position = positions.cl_positions[0].clone();
+    let uatom_balance = coin_amount("uatom", &positions.deposits);
+    let uosmo_balance = coin_amount("uosmo", &positions.deposits);
+
+    let claim = || ClaimClPositionRewards {
+        position_id: position.position_id,
+    };
+
+    let res = mock.update_credit_account(&account_id, &user, vec![claim()], &[]);
+    assert_err(res, ContractError::NoAmount);
+
+    mock.add_cl_position_rewards(
+        position.position_id,
+        vec![uosmo_info.to_coin(12)],
+        vec![uatom_info.to_coin(5)],
+    );
+    mock.update_credit_account(&account_id, &user, vec![claim()], &[]).unwrap();
+
+    let positions = mock.query_positions(&account_id);
+    assert_eq!(coin_amount("uatom", &positions.deposits), uatom_balance + Uint128::new(5));
+    assert_eq!(coin_amount("uosmo", &positions.deposits), uosmo_balance + Uint128::new(12));
+    assert_eq!(positions.cl_positions[0].liquidity, position.liquidity);
+
+    // Claimed rewards are collected from the pool
+    let rover_uosmo = mock.query_balance(&mock.rover, "uosmo");
+    assert_eq!(rover_uosmo.amount, uosmo_balance + Uint128::new(12));
+
+    let res = mock.update_credit_account(&account_id, &user, vec![claim()], &[]);
+    assert_err(res, ContractError::NoAmount);
+}
+
+#[test]
+fn cl_position_valued_at_oracle_prices() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000)), Deposit(uosmo_info.to_coin(4_000))],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+    )
+    .unwrap();
+    let health_before = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert_eq!(health_before.total_collateral_value, Uint128::new(2_000));
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![create_cl_position_action(Uint128::new(1_000), Uint128::new(4_000))],
+        &[],
+    )
+    .unwrap();
+
+    // Moving tokens into the position keeps the collateral value, up to rounding
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(health.total_collateral_value <= health_before.total_collateral_value);
+    assert!(
+        health.total_collateral_value + Uint128::new(2) >= health_before.total_collateral_value
+    );
+
+    // Moving the pool price doesn't change the value, the position is valued at oracle prices
+    mock.set_cl_pool(POOL_ID, "uatom", "uosmo", Decimal::from_atomics(7u128, 0).unwrap());
+    let positions = mock.query_positions(&account_id);
+    assert!(positions.cl_positions[0].asset0.amount < Uint128::new(999));
+    let health_pool_moved =
+        mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert_eq!(health_pool_moved.total_collateral_value, health.total_collateral_value);
+
+    // Oracle prices do
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: uatom_info.denom.clone(),
+        price: Decimal::from_atomics(2u128, 0).unwrap(),
+    });
+    let health_price_moved =
+        mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(health_price_moved.total_collateral_value > health.total_collateral_value);
+}
+
+#[test]
+fn liquidate_cl_position() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: liquidator.clone(),
+            funds: vec![uosmo_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    setup_cl_pool(&mut mock);
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(4_000)),
+            create_cl_position_action(Uint128::new(1_000), Uint128::new(4_000)),
+            Borrow(uosmo_info.to_coin(5_000)),
+            Withdraw(uosmo_info.to_action_coin(5_000)),
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(4_000)],
+    )
+    .unwrap();
+    let positions = mock.query_positions(&liquidatee_account_id);
+    let position = positions.cl_positions[0].clone();
+    let debt_before = get_debt("uosmo", &positions.debts).amount;
+
+    let health =
+        mock.query_health(&liquidatee_account_id, AccountKind::Default, ActionKind::Liquidation);
+    assert!(!health.liquidatable);
+
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Liquidation,
+        denom: uosmo_info.denom.clone(),
+        price: Decimal::from_atomics(5u128, 1).unwrap(),
+    });
+    let health =
+        mock.query_health(&liquidatee_account_id, AccountKind::Default, ActionKind::Liquidation);
+    assert!(health.liquidatable);
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![
+            Deposit(uosmo_info.to_coin(1_000)),
+            Liquidate {
+                liquidatee_account_id: liquidatee_account_id.clone(),
+                debt_coin: uosmo_info.to_coin(1_000),
+                request: LiquidateRequest::ClPosition(position.position_id),
+            },
+        ],
+        &[uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    // Part of the position was withdrawn for the repaid debt
+    let positions = mock.query_positions(&liquidatee_account_id);
+    let debt = get_debt("uosmo", &positions.debts).amount;
+    assert_eq!(debt_before - debt, Uint128::new(1_000));
+    assert_eq!(positions.cl_positions.len(), 1);
+    let liquidated_position = positions.cl_positions[0].clone();
+    assert_eq!(liquidated_position.position_id, position.position_id);
+    assert!(liquidated_position.liquidity < position.liquidity);
+
+    // Both underlying tokens go to the liquidator, protocol fee to the rewards-collector account
+    let positions = mock.query_positions(&liquidator_account_id);
+    let liquidator_uatom = coin_amount("uatom", &positions.deposits);
+    let liquidator_uosmo = coin_amount("uosmo", &positions.deposits);
+    assert!(!liquidator_uatom.is_zero());
+    assert!(!liquidator_uosmo.is_zero());
+
+    let rewards_collector_acc_id = mock.query_rewards_collector_account();
+    let positions = mock.query_positions(&rewards_collector_acc_id);
+    assert!(!coin_amount("uatom", &positions.deposits).is_zero());
+    assert!(!coin_amount("uosmo", &positions.deposits).is_zero());
+}
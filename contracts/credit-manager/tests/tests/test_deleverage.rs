@@ -0,0 +1,269 @@
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_swapper_mock::contract::MOCK_SWAP_RESULT;
+use mars_types::{
+    credit_manager::{
+        Action::{Borrow, Deposit, Withdraw},
+        ConfigUpdates, DeleverageConfig,
+    },
+    health::AccountKind,
+    oracle::ActionKind,
+    traits::Stringify,
+};
+
+use super::helpers::{
+    assert_err, get_coin, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv,
+};
+
+#[test]
+fn only_token_owner_can_opt_in() {
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let bad_guy = Addr::unchecked("bad_guy");
+    let res = mock.update_deleverage_opt_in(&bad_guy, &account_id, true);
+    assert_err(
+        res,
+        ContractError::NotTokenOwner {
+            user: bad_guy.into(),
+            account_id: account_id.clone(),
+        },
+    );
+
+    assert!(!mock.query_deleverage_opt_in(&account_id));
+
+    mock.update_deleverage_opt_in(&user, &account_id, true).unwrap();
+    assert!(mock.query_deleverage_opt_in(&account_id));
+
+    mock.update_deleverage_opt_in(&user, &account_id, false).unwrap();
+    assert!(!mock.query_deleverage_opt_in(&account_id));
+}
+
+#[test]
+fn cannot_deleverage_when_disabled() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, false);
+    mock.update_deleverage_opt_in(&Addr::unchecked("user"), &account_id, true).unwrap();
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(200),
+        "uosmo",
+        None,
+    );
+    assert_err(res, ContractError::DeleverageDisabled);
+}
+
+#[test]
+fn cannot_deleverage_account_not_opted_in() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, true);
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(200),
+        "uosmo",
+        None,
+    );
+    assert_err(
+        res,
+        ContractError::DeleverageNotOptedIn {
+            account_id,
+        },
+    );
+}
+
+#[test]
+fn cannot_deleverage_healthy_account() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, true);
+    mock.update_deleverage_opt_in(&Addr::unchecked("user"), &account_id, true).unwrap();
+
+    // Bring the account back below max LTV
+    set_price(&mut mock, "uosmo", Decimal::percent(25));
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(!health.above_max_ltv);
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(200),
+        "uosmo",
+        None,
+    );
+    assert_err(
+        res,
+        ContractError::NotDeleveragable {
+            account_id,
+            max_ltv_health_factor: health.max_ltv_health_factor.to_string(),
+            liquidation_health_factor: health.liquidation_health_factor.to_string(),
+        },
+    );
+}
+
+#[test]
+fn cannot_deleverage_liquidatable_account() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, true);
+    mock.update_deleverage_opt_in(&Addr::unchecked("user"), &account_id, true).unwrap();
+
+    set_price(&mut mock, "uatom", Decimal::percent(90));
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(health.liquidatable);
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(200),
+        "uosmo",
+        None,
+    );
+    assert_err(
+        res,
+        ContractError::NotDeleveragable {
+            account_id,
+            max_ltv_health_factor: health.max_ltv_health_factor.to_string(),
+            liquidation_health_factor: health.liquidation_health_factor.to_string(),
+        },
+    );
+}
+
+#[test]
+fn cannot_sell_more_than_max_repay_ratio() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, true);
+    mock.update_deleverage_opt_in(&Addr::unchecked("user"), &account_id, true).unwrap();
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    let max_value = health.total_debt_value.checked_mul_floor(Decimal::percent(50)).unwrap();
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(500),
+        "uosmo",
+        None,
+    );
+    assert_err(
+        res,
+        ContractError::DeleverageAmountTooLarge {
+            value: Uint128::new(500),
+            max_value,
+        },
+    );
+}
+
+#[test]
+fn deleverage_must_bring_account_below_max_ltv() {
+    let (mut mock, account_id) = setup_account_in_band(10_000, 32_000, true);
+    mock.update_deleverage_opt_in(&Addr::unchecked("user"), &account_id, true).unwrap();
+
+    // Close to the liquidation threshold, repaying the mocked swap result is not enough
+    set_price(&mut mock, "uosmo", Decimal::percent(28));
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(health.above_max_ltv);
+    assert!(!health.liquidatable);
+
+    let res = mock.deleverage(
+        &Addr::unchecked("keeper"),
+        &account_id,
+        uatom_info().to_coin(10),
+        "uosmo",
+        None,
+    );
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert!(matches!(err, ContractError::DeleverageInsufficient { .. }));
+}
+
+#[test]
+fn keeper_can_deleverage_account_in_band() {
+    let (mut mock, account_id) = setup_account_in_band(1_000, 3_200, true);
+    let user = Addr::unchecked("user");
+    mock.update_deleverage_opt_in(&user, &account_id, true).unwrap();
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(health.above_max_ltv);
+    assert!(!health.liquidatable);
+
+    let keeper = Addr::unchecked("keeper");
+    mock.deleverage(&keeper, &account_id, uatom_info().to_coin(200), "uosmo", None).unwrap();
+
+    // Keeper receives a 1% bonus of the collateral sold
+    let keeper_balance = mock.query_balance(&keeper, "uatom");
+    assert_eq!(keeper_balance.amount, Uint128::new(2));
+
+    // Remaining collateral sold and proceeds used to repay debt
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits.len(), 1);
+    assert_eq!(get_coin("uatom", &position.deposits).amount, Uint128::new(800));
+    assert_eq!(position.debts.len(), 1);
+    let debt = get_debt("uosmo", &position.debts);
+    assert_eq!(debt.amount, Uint128::new(3_201) - MOCK_SWAP_RESULT);
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert!(!health.above_max_ltv);
+}
+
+/// Creates an account with `uatom` collateral and `uosmo` debt (withdrawn to wallet) and moves the
+/// `uosmo` price so the account ends up above max LTV but not yet liquidatable.
+fn setup_account_in_band(
+    collateral: u128,
+    debt: u128,
+    deleverage_enabled: bool,
+) -> (MockEnv, String) {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(collateral, uatom_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+
+    if deleverage_enabled {
+        let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+        mock.update_config(
+            &owner,
+            ConfigUpdates {
+                deleverage_config: Some(DeleverageConfig {
+                    bonus: Decimal::percent(1),
+                    max_repay_ratio: Decimal::percent(50),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(collateral)),
+            Borrow(uosmo_info.to_coin(debt)),
+            Withdraw(uosmo_info.to_action_coin(debt)),
+        ],
+        &[uatom_info.to_coin(collateral)],
+    )
+    .unwrap();
+
+    set_price(&mut mock, "uosmo", Decimal::percent(26));
+
+    (mock, account_id)
+}
+
+fn set_price(mock: &mut MockEnv, denom: &str, price: Decimal) {
+    for pricing in [ActionKind::Default, ActionKind::Liquidation] {
+        mock.price_change(CoinPrice {
+            pricing,
+            denom: denom.to_string(),
+            price,
+        });
+    }
+}
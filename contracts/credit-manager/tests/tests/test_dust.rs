@@ -0,0 +1,173 @@
+use cosmwasm_std::{Addr, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::{
+    credit_manager::{
+        Action::{Borrow, Deposit, Repay},
+        ActionAmount, ActionCoin,
+    },
+    params::AssetParamsUpdate::AddOrUpdate,
+};
+
+use super::helpers::{
+    assert_err, get_coin, uatom_info, uosmo_info, AccountToFund, CoinInfo, MockEnv,
+};
+
+#[test]
+fn debt_cannot_be_left_below_min_debt_value() {
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    set_min_debt_value(&mut mock, &uatom_info, 100);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // Mock Red Bank adds 1 to every borrowed amount
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000)), Borrow(uatom_info.to_coin(50))],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::DebtBelowMinValue {
+            account_id: account_id.clone(),
+            denom: uatom_info.denom.clone(),
+            value: Uint128::new(51),
+            min_value: Uint128::new(100),
+        },
+    );
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000)), Borrow(uatom_info.to_coin(200))],
+        &[uatom_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    // Partial repayment leaving dust
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Repay {
+            recipient_account_id: None,
+            coin: uatom_info.to_action_coin(150),
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::DebtBelowMinValue {
+            account_id: account_id.clone(),
+            denom: uatom_info.denom.clone(),
+            value: Uint128::new(51),
+            min_value: Uint128::new(100),
+        },
+    );
+
+    // Full repayment is always allowed
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Repay {
+            recipient_account_id: None,
+            coin: ActionCoin {
+                denom: uatom_info.denom.clone(),
+                amount: ActionAmount::AccountBalance,
+            },
+        }],
+        &[],
+    )
+    .unwrap();
+    let position = mock.query_positions(&account_id);
+    assert!(position.debts.is_empty());
+}
+
+#[test]
+fn rewards_collector_sweeps_dust_debt() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let rewards_collector = Addr::unchecked("rewards_collector_contract");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uosmo_info.to_coin(4_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: rewards_collector.clone(),
+            funds: vec![uatom_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    set_min_debt_value(&mut mock, &uatom_info, 100);
+
+    let rc_account_id = mock.query_rewards_collector_account();
+    mock.update_credit_account(
+        &rc_account_id,
+        &rewards_collector,
+        vec![Deposit(uatom_info.to_coin(1_000))],
+        &[uatom_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uosmo_info.to_coin(4_000)), Borrow(uatom_info.to_coin(200))],
+        &[uosmo_info.to_coin(4_000)],
+    )
+    .unwrap();
+
+    // Only the rewards collector can sweep
+    let res = mock.sweep_dust(&user, &account_id, &uatom_info.denom, &uosmo_info.denom);
+    assert_err(
+        res,
+        ContractError::Unauthorized {
+            user: user.to_string(),
+            action: "sweep dust".to_string(),
+        },
+    );
+
+    let res =
+        mock.sweep_dust(&rewards_collector, &account_id, &uatom_info.denom, &uosmo_info.denom);
+    assert_err(
+        res,
+        ContractError::NotDust {
+            denom: uatom_info.denom.clone(),
+            value: Uint128::new(201),
+            min_value: Uint128::new(100),
+        },
+    );
+
+    // Debt of 201 uatom becomes dust
+    set_min_debt_value(&mut mock, &uatom_info, 1_000);
+    mock.sweep_dust(&rewards_collector, &account_id, &uatom_info.denom, &uosmo_info.denom).unwrap();
+
+    // Collateral worth the debt (201 / 0.25) is moved to the rewards-collector account
+    let position = mock.query_positions(&account_id);
+    assert!(position.debts.is_empty());
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(3_196));
+    assert_eq!(get_coin(&uatom_info.denom, &position.deposits).amount, Uint128::new(200));
+
+    let rc_position = mock.query_positions(&rc_account_id);
+    assert_eq!(get_coin(&uatom_info.denom, &rc_position.deposits).amount, Uint128::new(799));
+    assert_eq!(get_coin(&uosmo_info.denom, &rc_position.deposits).amount, Uint128::new(804));
+}
+
+fn set_min_debt_value(mock: &mut MockEnv, coin_info: &CoinInfo, min_debt_value: u128) {
+    let mut asset_params = mock.query_asset_params(&coin_info.denom);
+    asset_params.min_debt_value = Uint128::new(min_debt_value);
+    mock.update_asset_params(AddOrUpdate {
+        params: asset_params.into(),
+    });
+}
@@ -0,0 +1,205 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_swapper_mock::contract::MOCK_SWAP_RESULT;
+use mars_types::credit_manager::{
+    Action::{Borrow, Deposit, SwapExactIn},
+    ConfigUpdates, DenomFee, FeeConfig,
+};
+
+use super::helpers::{
+    assert_err, get_coin, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv,
+};
+
+#[test]
+fn invalid_fee_config() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            fee_config: Some(FeeConfig {
+                borrow_fees: vec![],
+                swap_fee: Decimal::one(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Swap fee must be less than 1".to_string(),
+        },
+    );
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            fee_config: Some(FeeConfig {
+                borrow_fees: vec![DenomFee {
+                    denom: "uosmo".to_string(),
+                    fee: Decimal::one(),
+                }],
+                swap_fee: Decimal::zero(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Borrow fee for uosmo must be less than 1".to_string(),
+        },
+    );
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            fee_config: Some(FeeConfig {
+                borrow_fees: vec![
+                    DenomFee {
+                        denom: "uosmo".to_string(),
+                        fee: Decimal::percent(1),
+                    },
+                    DenomFee {
+                        denom: "uosmo".to_string(),
+                        fee: Decimal::percent(2),
+                    },
+                ],
+                swap_fee: Decimal::zero(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Duplicate borrow fee for uosmo".to_string(),
+        },
+    );
+}
+
+#[test]
+fn fee_config_can_be_updated() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+    assert_eq!(mock.query_config().fee_config, None);
+
+    let fee_config = FeeConfig {
+        borrow_fees: vec![DenomFee {
+            denom: "uosmo".to_string(),
+            fee: Decimal::percent(1),
+        }],
+        swap_fee: Decimal::permille(3),
+    };
+    mock.update_config(
+        &owner,
+        ConfigUpdates {
+            fee_config: Some(fee_config.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(mock.query_config().fee_config, Some(fee_config));
+}
+
+#[test]
+fn borrow_fee_paid_to_rewards_collector() {
+    let uosmo_info = uosmo_info();
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uosmo_info.clone(), uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uosmo_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    set_fees(&mut mock, Decimal::percent(5), Decimal::zero());
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uosmo_info.to_coin(1_000)), Borrow(uosmo_info.to_coin(100))],
+        &[uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    // Debt is not reduced by the fee, only the borrowed coins
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(1_095));
+    assert_eq!(get_debt(&uosmo_info.denom, &position.debts).amount, Uint128::new(101));
+
+    let rc_position = mock.query_positions(&mock.query_rewards_collector_account());
+    assert_eq!(rc_position.deposits, vec![uosmo_info.to_coin(5)]);
+
+    // Denoms without a borrow fee
+    mock.update_credit_account(&account_id, &user, vec![Borrow(uatom_info.to_coin(100))], &[])
+        .unwrap();
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(&uatom_info.denom, &position.deposits).amount, Uint128::new(100));
+}
+
+#[test]
+fn swap_fee_paid_to_rewards_collector() {
+    let uosmo_info = uosmo_info();
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uosmo_info.clone(), uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(10_000)],
+        })
+        .build()
+        .unwrap();
+    set_fees(&mut mock, Decimal::zero(), Decimal::percent(1));
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let actions = vec![
+        Deposit(uatom_info.to_coin(10_000)),
+        SwapExactIn {
+            coin_in: uatom_info.to_action_coin(10_000),
+            denom_out: uosmo_info.denom.clone(),
+            min_receive: MOCK_SWAP_RESULT,
+            route: None,
+        },
+    ];
+
+    let simulation = mock.query_simulate_actions(&account_id, actions.clone()).unwrap();
+    assert_eq!(simulation.fees, vec![uatom_info.to_coin(100)]);
+    assert_eq!(simulation.failed_action, None);
+
+    mock.update_credit_account(&account_id, &user, actions, &[uatom_info.to_coin(10_000)]).unwrap();
+
+    // Only coins net of fee are sent to the swapper
+    let atom_balance = mock.query_balance(&mock.rover, &uatom_info.denom).amount;
+    assert_eq!(atom_balance, Uint128::new(100));
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(MOCK_SWAP_RESULT.u128())]);
+
+    let rc_position = mock.query_positions(&mock.query_rewards_collector_account());
+    assert_eq!(rc_position.deposits, vec![uatom_info.to_coin(100)]);
+}
+
+fn set_fees(mock: &mut MockEnv, borrow_fee: Decimal, swap_fee: Decimal) {
+    let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+    mock.update_config(
+        &owner,
+        ConfigUpdates {
+            fee_config: Some(FeeConfig {
+                borrow_fees: vec![DenomFee {
+                    denom: uosmo_info().denom,
+                    fee: borrow_fee,
+                }],
+                swap_fee,
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+}
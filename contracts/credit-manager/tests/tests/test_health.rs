@@ -86,6 +86,7 @@ fn terra_ragnarok() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -308,6 +309,7 @@ fn cannot_borrow_more_but_not_liquidatable() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -323,6 +325,7 @@ fn cannot_borrow_more_but_not_liquidatable() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -402,6 +405,7 @@ fn assets_and_ltv_lqdt_adjusted_value() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -417,6 +421,7 @@ fn assets_and_ltv_lqdt_adjusted_value() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -524,6 +529,7 @@ fn debt_value() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -539,6 +545,7 @@ fn debt_value() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -859,6 +866,7 @@ fn can_take_actions_if_ltv_does_not_weaken() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -874,6 +882,7 @@ fn can_take_actions_if_ltv_does_not_weaken() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
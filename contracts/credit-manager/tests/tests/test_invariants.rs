@@ -0,0 +1,66 @@
+use cosmwasm_std::{coin, Uint128};
+use mars_testing::multitest::invariants::{
+    invariants_prop_test_runner, InvariantsConfig, InvariantsEnv, Step, StepStats,
+};
+use mars_types::credit_manager::{Action, ActionAmount, ActionCoin, LiquidateRequest};
+
+#[test]
+fn random_steps_preserve_invariants() {
+    let stats = invariants_prop_test_runner(10, &InvariantsConfig::default());
+    assert!(stats.updates > 0, "no credit account update was accepted: {stats:?}");
+}
+
+#[test]
+fn random_steps_across_many_accounts_preserve_invariants() {
+    let stats = invariants_prop_test_runner(
+        5,
+        &InvariantsConfig {
+            accounts: 8,
+            max_steps: 40,
+            ..Default::default()
+        },
+    );
+    assert!(stats.updates > 0, "no credit account update was accepted: {stats:?}");
+}
+
+/// Random sequences rarely make an account liquidatable, so the invariants are checked for a
+/// liquidation explicitly
+#[test]
+fn liquidation_preserves_invariants() {
+    let mut env = InvariantsEnv::new(&InvariantsConfig::default());
+
+    // Max LTV: 10_000 * 0.25 * 0.7 = 1750, liquidation threshold: 10_000 * 0.25 * 0.78 = 1950
+    env.run_step(&Step::UpdateCreditAccount {
+        account: 0,
+        actions: vec![
+            Action::Deposit(coin(10_000, "uosmo")),
+            Action::Borrow(coin(1_700, "uatom")),
+            Action::Withdraw(ActionCoin {
+                denom: "uatom".to_string(),
+                amount: ActionAmount::Exact(Uint128::new(1_700)),
+            }),
+        ],
+    });
+    // Liquidation threshold drops to 10_000 * 0.2 * 0.78 = 1560, below the debt of 1700
+    env.run_step(&Step::PriceChange {
+        denom: "uosmo".to_string(),
+        percent: 80,
+    });
+    assert!(env.query_liquidatable()[0]);
+
+    env.run_step(&Step::Liquidate {
+        account: 0,
+        debt_coin: coin(100, "uatom"),
+        request: LiquidateRequest::Deposit("uosmo".to_string()),
+    });
+
+    assert_eq!(
+        env.stats,
+        StepStats {
+            updates: 1,
+            liquidations: 1,
+        }
+    );
+    let debts = env.mock.query_positions(&env.accounts[0].1).debts;
+    assert!(debts[0].amount < Uint128::new(1_700));
+}
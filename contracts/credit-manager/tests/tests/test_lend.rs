@@ -77,6 +77,9 @@ fn lending_zero_has_no_effect() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![]
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         }
     );
 }
@@ -160,6 +163,9 @@ fn attempting_to_lend_account_balance_with_no_funds() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![]
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         }
     );
 }
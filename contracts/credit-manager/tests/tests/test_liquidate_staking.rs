@@ -0,0 +1,222 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{
+        Action::{Borrow, Delegate, Deposit, Liquidate, Undelegate, Withdraw},
+        LiquidateRequest, Positions, StakingPositionType,
+    },
+    oracle::ActionKind,
+    params::{AssetParamsUpdate::AddOrUpdate, StakingParams},
+};
+
+use super::helpers::{
+    assert_err, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv, DEFAULT_UNBONDING_PERIOD,
+};
+
+#[test]
+fn liquidatee_must_have_staking_position() {
+    let (mut mock, liquidatee_account_id, liquidator_account_id) = setup_liquidatable_account(0);
+    let liquidator = Addr::unchecked("liquidator");
+
+    let res = mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id,
+            debt_coin: uatom_info().to_coin(20),
+            request: LiquidateRequest::Staking {
+                validator: "validator2".to_string(),
+                position_type: StakingPositionType::Delegated,
+            },
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::NoStakingPosition {
+            validator: "validator2".to_string(),
+        },
+    );
+}
+
+#[test]
+fn delegation_transferred_to_liquidator() {
+    let (mut mock, liquidatee_account_id, liquidator_account_id) = setup_liquidatable_account(0);
+    let liquidator = Addr::unchecked("liquidator");
+    let uatom_info = uatom_info();
+
+    let prev_debt =
+        get_debt(&uatom_info.denom, &mock.query_positions(&liquidatee_account_id).debts);
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id: liquidatee_account_id.clone(),
+            debt_coin: uatom_info.to_coin(20),
+            request: LiquidateRequest::Staking {
+                validator: "validator1".to_string(),
+                position_type: StakingPositionType::Delegated,
+            },
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let liquidatee_position = mock.query_positions(&liquidatee_account_id);
+    let debt = get_debt(&uatom_info.denom, &liquidatee_position.debts);
+    assert_eq!(debt.amount, prev_debt.amount - Uint128::new(20));
+
+    // Delegation is moved without unbonding
+    let liquidator_position = mock.query_positions(&liquidator_account_id);
+    let rewards_collector_position = mock.query_positions(&mock.query_rewards_collector_account());
+    let liquidatee_delegated = delegated(&liquidatee_position);
+    let liquidator_delegated = delegated(&liquidator_position);
+    assert!(liquidatee_delegated < Uint128::new(1_000));
+    assert!(!liquidator_delegated.is_zero());
+    assert_eq!(
+        liquidatee_delegated + liquidator_delegated + delegated(&rewards_collector_position),
+        Uint128::new(1_000)
+    );
+    assert!(liquidator_position.staking[0].unbonding.is_empty());
+}
+
+#[test]
+fn earliest_unbonding_entries_liquidated_first() {
+    let (mut mock, liquidatee_account_id, liquidator_account_id) = setup_liquidatable_account(600);
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let uosmo_info = uosmo_info();
+
+    let first_completion_time = mock.query_block_time() + DEFAULT_UNBONDING_PERIOD;
+    // Undelegations are sent at most once per epoch
+    mock.increment_by_time(DEFAULT_UNBONDING_PERIOD / 6);
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![Undelegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin_full_balance(),
+        }],
+        &[],
+    )
+    .unwrap();
+    let second_completion_time = mock.query_block_time() + DEFAULT_UNBONDING_PERIOD;
+
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id: liquidatee_account_id.clone(),
+            debt_coin: uatom_info().to_coin(20),
+            request: LiquidateRequest::Staking {
+                validator: "validator1".to_string(),
+                position_type: StakingPositionType::Unbonding,
+            },
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // Only the first entry is (partially) liquidated
+    let liquidatee_position = mock.query_positions(&liquidatee_account_id);
+    let liquidatee_entries = &liquidatee_position.staking[0].unbonding;
+    assert_eq!(liquidatee_entries.len(), 2);
+    assert_eq!(liquidatee_entries[0].completion_time, first_completion_time);
+    assert!(liquidatee_entries[0].amount < Uint128::new(600));
+    assert_eq!(liquidatee_entries[1].completion_time, second_completion_time);
+    assert_eq!(liquidatee_entries[1].amount, Uint128::new(400));
+
+    let liquidator_position = mock.query_positions(&liquidator_account_id);
+    let liquidator_entries = &liquidator_position.staking[0].unbonding;
+    assert_eq!(liquidator_entries.len(), 1);
+    assert_eq!(liquidator_entries[0].completion_time, first_completion_time);
+    assert!(delegated(&liquidator_position).is_zero());
+
+    let rewards_collector_position = mock.query_positions(&mock.query_rewards_collector_account());
+    let total_unbonding = [liquidatee_position, liquidator_position, rewards_collector_position]
+        .iter()
+        .flat_map(|p| p.staking.iter().flat_map(|s| s.unbonding.iter()))
+        .map(|entry| entry.amount)
+        .sum::<Uint128>();
+    assert_eq!(total_unbonding, Uint128::new(1_000));
+}
+
+fn delegated(position: &Positions) -> Uint128 {
+    position.staking.iter().map(|s| s.delegated.amount).sum()
+}
+
+/// Liquidatee has 1_000 uosmo delegated (`undelegated` of them unbonding) as the only collateral
+/// for 101 uatom of debt. The uosmo price drop makes the account liquidatable.
+fn setup_liquidatable_account(undelegated: u128) -> (MockEnv, String, String) {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1", "validator2"])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![uosmo_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: liquidator.clone(),
+            funds: vec![uatom_info.to_coin(50)],
+        })
+        .build()
+        .unwrap();
+
+    let mut asset_params = mock.query_asset_params(&uosmo_info.denom);
+    asset_params.credit_manager.staking = Some(StakingParams {
+        haircut: Decimal::percent(20),
+        unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        min_amount: Uint128::new(10),
+    });
+    mock.update_asset_params(AddOrUpdate {
+        params: asset_params.into(),
+    });
+
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    let mut actions = vec![
+        Deposit(uosmo_info.to_coin(1_000)),
+        Delegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin_full_balance(),
+        },
+        Borrow(uatom_info.to_coin(100)),
+        Withdraw(uatom_info.to_action_coin(100)),
+    ];
+    if undelegated > 0 {
+        actions.push(Undelegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(undelegated),
+        });
+    }
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        actions,
+        &[uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Deposit(uatom_info.to_coin(50))],
+        &[uatom_info.to_coin(50)],
+    )
+    .unwrap();
+
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Liquidation,
+        denom: uosmo_info.denom,
+        price: Decimal::percent(10),
+    });
+
+    (mock, liquidatee_account_id, liquidator_account_id)
+}
@@ -0,0 +1,340 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{
+        Action::{Borrow, Deposit, Liquidate, Repay, SwapExactIn, Withdraw},
+        LiquidateRequest,
+    },
+    oracle::ActionKind,
+    params::{AutoPauseConfig, PausableAction, PausableContract, PauseScope, PauseUpdate},
+};
+
+use super::helpers::{assert_err, uatom_info, uosmo_info, AccountToFund, MockEnv};
+
+#[test]
+fn paused_actions_are_rejected() {
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let guardian = Addr::unchecked("guardian");
+    let mut mock = MockEnv::new()
+        .emergency_owner(guardian.as_str())
+        .set_params(&[uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(2_000)],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000)), Borrow(uatom_info.to_coin(100))],
+        &[uatom_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    let update = PauseUpdate {
+        scope: PauseScope::Contract(PausableContract::CreditManager),
+        actions: vec![PausableAction::Deposits, PausableAction::Borrows],
+    };
+
+    // only the emergency owner can pause
+    let res = mock.emergency_pause(&user, update.clone());
+    assert!(res.is_err());
+    mock.emergency_pause(&guardian, update).unwrap();
+
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Borrows));
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Swaps));
+    assert!(!mock.query_is_paused(PausableContract::RedBank, PausableAction::Deposits));
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000))],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::ActionPaused {
+            action: "deposits".to_string(),
+        },
+    );
+
+    let res =
+        mock.update_credit_account(&account_id, &user, vec![Borrow(uatom_info.to_coin(10))], &[]);
+    assert_err(
+        res,
+        ContractError::ActionPaused {
+            action: "borrows".to_string(),
+        },
+    );
+
+    // repayments and withdrawals are never paused
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Repay {
+                recipient_account_id: None,
+                coin: uatom_info.to_action_coin(50),
+            },
+            Withdraw(uatom_info.to_action_coin(100)),
+        ],
+        &[],
+    )
+    .unwrap();
+
+    // only the owner can unpause
+    let update = PauseUpdate {
+        scope: PauseScope::Contract(PausableContract::CreditManager),
+        actions: vec![PausableAction::Borrows],
+    };
+    let res = mock.unpause(&guardian, update.clone());
+    assert!(res.is_err());
+    mock.unpause(&Addr::unchecked("owner"), update).unwrap();
+
+    mock.update_credit_account(&account_id, &user, vec![Borrow(uatom_info.to_coin(10))], &[])
+        .unwrap();
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+}
+
+#[test]
+fn liquidation_allowed_while_deposits_paused() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let guardian = Addr::unchecked("guardian");
+    let mut mock = MockEnv::new()
+        .emergency_owner(guardian.as_str())
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![uosmo_info.to_coin(300)],
+        })
+        .fund_account(AccountToFund {
+            addr: liquidator.clone(),
+            funds: vec![uatom_info.to_coin(100)],
+        })
+        .build()
+        .unwrap();
+
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![Deposit(uosmo_info.to_coin(300)), Borrow(uatom_info.to_coin(105))],
+        &[uosmo_info.to_coin(300)],
+    )
+    .unwrap();
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Liquidation,
+        denom: uatom_info.denom.clone(),
+        price: Decimal::from_atomics(20u128, 0).unwrap(),
+    });
+
+    mock.emergency_pause(
+        &guardian,
+        PauseUpdate {
+            scope: PauseScope::Contract(PausableContract::CreditManager),
+            actions: vec![PausableAction::Deposits],
+        },
+    )
+    .unwrap();
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    let liquidate = Liquidate {
+        liquidatee_account_id: liquidatee_account_id.clone(),
+        debt_coin: uatom_info.to_coin(50),
+        request: LiquidateRequest::Deposit(uatom_info.denom.clone()),
+    };
+
+    // Deposits beyond what the liquidation uses are still paused
+    let res = mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Deposit(uatom_info.to_coin(100)), liquidate.clone()],
+        &[uatom_info.to_coin(100)],
+    );
+    assert_err(
+        res,
+        ContractError::ActionPaused {
+            action: "deposits".to_string(),
+        },
+    );
+
+    let prev_debt = mock.query_positions(&liquidatee_account_id).debts[0].amount;
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Deposit(uatom_info.to_coin(50)), liquidate],
+        &[uatom_info.to_coin(50)],
+    )
+    .unwrap();
+    let debt = mock.query_positions(&liquidatee_account_id).debts[0].amount;
+    assert!(debt < prev_debt);
+}
+
+#[test]
+fn global_pause_applies_to_all_contracts() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let guardian = Addr::unchecked("guardian");
+    let mut mock = MockEnv::new()
+        .emergency_owner(guardian.as_str())
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.emergency_pause(
+        &guardian,
+        PauseUpdate {
+            scope: PauseScope::Global,
+            actions: vec![PausableAction::Swaps],
+        },
+    )
+    .unwrap();
+
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Swaps));
+    assert!(mock.query_is_paused(PausableContract::RedBank, PausableAction::Swaps));
+    assert!(mock.query_is_paused(PausableContract::ManagedVault, PausableAction::Swaps));
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            SwapExactIn {
+                coin_in: uatom_info.to_action_coin(1_000),
+                denom_out: uosmo_info.denom.clone(),
+                min_receive: Uint128::zero(),
+                route: None,
+            },
+        ],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::ActionPaused {
+            action: "swaps".to_string(),
+        },
+    );
+
+    // deposits are not affected
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000))],
+        &[uatom_info.to_coin(1_000)],
+    )
+    .unwrap();
+}
+
+#[test]
+fn price_movement_triggers_pause() {
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // not configured
+    let res = mock.check_price_movement(&keeper, &uatom_info.denom);
+    assert!(res.is_err());
+
+    let config = AutoPauseConfig {
+        max_price_change: Decimal::percent(10),
+        window: 3600,
+        actions: vec![PausableAction::Deposits, PausableAction::Borrows],
+    };
+    let res = mock.update_auto_pause_config(&user, &uatom_info.denom, Some(config.clone()));
+    assert!(res.is_err());
+    mock.update_auto_pause_config(&owner, &uatom_info.denom, Some(config)).unwrap();
+
+    // first check records the reference price
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+
+    set_uatom_price(&mut mock, uatom_info.price * Decimal::percent(95));
+    mock.increment_by_time(600);
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+
+    // after the window has passed the reference price is updated
+    set_uatom_price(&mut mock, uatom_info.price * Decimal::percent(92));
+    mock.increment_by_time(3600);
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+
+    // 92 -> 82.8 is a 10% move, not more
+    set_uatom_price(&mut mock, uatom_info.price * Decimal::permille(828));
+    mock.increment_by_time(60);
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+
+    set_uatom_price(&mut mock, uatom_info.price * Decimal::percent(82));
+    mock.increment_by_time(60);
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Deposits));
+    assert!(mock.query_is_paused(PausableContract::RedBank, PausableAction::Borrows));
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Swaps));
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000))],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::ActionPaused {
+            action: "deposits".to_string(),
+        },
+    );
+}
+
+#[test]
+fn price_drop_after_window_expired_triggers_pause() {
+    let uatom_info = uatom_info();
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+
+    let config = AutoPauseConfig {
+        max_price_change: Decimal::percent(10),
+        window: 3600,
+        actions: vec![PausableAction::Borrows],
+    };
+    mock.update_auto_pause_config(&owner, &uatom_info.denom, Some(config)).unwrap();
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+
+    // nobody checked the price during the window, the old checkpoint is still compared with
+    set_uatom_price(&mut mock, uatom_info.price * Decimal::percent(80));
+    mock.increment_by_time(7200);
+    mock.check_price_movement(&keeper, &uatom_info.denom).unwrap();
+    assert!(mock.query_is_paused(PausableContract::RedBank, PausableAction::Borrows));
+}
+
+fn set_uatom_price(mock: &mut MockEnv, price: Decimal) {
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: uatom_info().denom,
+        price,
+    });
+}
@@ -0,0 +1,414 @@
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{
+        Action::{self, Borrow, Deposit, ExecutePerpOrder, Liquidate},
+        LiquidateRequest,
+    },
+    oracle::ActionKind,
+};
+
+use super::helpers::{
+    assert_err, coin_info, get_coin, get_debt, perp_market_params, uatom_info, AccountToFund,
+    MockEnv,
+};
+
+const BASE_DENOM: &str = "uusdc";
+const PERP_DENOM: &str = "ubtc";
+
+/// Sets both the default and the liquidation price
+fn set_price(mock: &mut MockEnv, denom: &str, price: u128) {
+    for pricing in [ActionKind::Default, ActionKind::Liquidation] {
+        mock.price_change(CoinPrice {
+            pricing,
+            denom: denom.to_string(),
+            price: Decimal::from_ratio(price, 1u128),
+        });
+    }
+}
+
+fn perp_order(size: i128) -> Action {
+    ExecutePerpOrder {
+        denom: PERP_DENOM.to_string(),
+        size: Int128::new(size),
+        reduce_only: None,
+    }
+}
+
+#[test]
+fn perp_order_requires_perps_contract() {
+    let base_info = coin_info(BASE_DENOM);
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[base_info]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let res = mock.update_credit_account(&account_id, &user, vec![perp_order(10)], &[]);
+    assert_err(res, ContractError::PerpsNotConfigured);
+}
+
+#[test]
+fn perp_pnl_settled_with_coin_balance() {
+    let base_info = coin_info(BASE_DENOM);
+    let user = Addr::unchecked("user");
+    let lp = Addr::unchecked("lp");
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![base_info.to_coin(10_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    assert_eq!(mock.query_config().perps, Some(perps.address().to_string()));
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(base_info.to_coin(10_000)), perp_order(10)],
+        &[base_info.to_coin(10_000)],
+    )
+    .unwrap();
+
+    // Opening fee is paid from the coin balance
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(9_999));
+    assert_eq!(position.perps.len(), 1);
+    let perp = position.perps.first().unwrap();
+    assert_eq!(perp.size, Int128::new(10));
+    assert_eq!(perp.entry_price, Decimal::from_ratio(100u128, 1u128));
+
+    set_price(&mut mock, PERP_DENOM, 110);
+    mock.update_credit_account(&account_id, &user, vec![perp_order(-10)], &[]).unwrap();
+
+    // 100 profit minus 2 closing fee is credited to the coin balance
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(10_097));
+    assert!(position.perps.is_empty());
+    assert_eq!(mock.query_perp_vault(&perps).total_liquidity, Uint128::new(99_903));
+    assert_eq!(mock.query_balance(&mock.rover, BASE_DENOM).amount, Uint128::new(10_097));
+}
+
+#[test]
+fn perp_position_counts_towards_health() {
+    let base_info = coin_info(BASE_DENOM);
+    let user = Addr::unchecked("user");
+    let lp = Addr::unchecked("lp");
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![base_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // 995 * 0.7 deposit and 5000 * 0.8 perp collateral against 5000 + 5 closing fee perp debt
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(base_info.to_coin(1_000)), perp_order(50)],
+        &[base_info.to_coin(1_000)],
+    );
+    assert_err(
+        res,
+        ContractError::AboveMaxLTV {
+            account_id: account_id.clone(),
+            max_ltv_health_factor: "0.938261738261738261".to_string(),
+        },
+    );
+}
+
+#[test]
+fn liquidation_closes_perp_positions() {
+    let base_info = coin_info(BASE_DENOM);
+    let uatom_info = uatom_info();
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let lp = Addr::unchecked("lp");
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone(), uatom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![base_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: liquidator.clone(),
+            funds: vec![uatom_info.to_coin(10)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![Deposit(base_info.to_coin(1_000)), Borrow(uatom_info.to_coin(100)), perp_order(30)],
+        &[base_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    set_price(&mut mock, PERP_DENOM, 80);
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![
+            Deposit(uatom_info.to_coin(10)),
+            Liquidate {
+                liquidatee_account_id: liquidatee_account_id.clone(),
+                debt_coin: uatom_info.to_coin(10),
+                request: LiquidateRequest::Deposit(BASE_DENOM.to_string()),
+            },
+        ],
+        &[uatom_info.to_coin(10)],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&liquidatee_account_id);
+    assert!(position.perps.is_empty());
+    assert_eq!(mock.query_perp_position(&perps, &liquidatee_account_id, PERP_DENOM), None);
+    assert_eq!(mock.query_perp_market(&perps, PERP_DENOM).state.long_oi, Uint128::zero());
+
+    // 3 opening fee, 600 loss and 3 closing fee were paid to the vault
+    assert_eq!(mock.query_perp_vault(&perps).total_liquidity, Uint128::new(100_606));
+}
+
+#[test]
+fn perp_only_account_can_be_liquidated() {
+    let base_info = coin_info(BASE_DENOM);
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let lp = Addr::unchecked("lp");
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![base_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![Deposit(base_info.to_coin(1_000)), perp_order(30)],
+        &[base_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    let liquidate_perps = Liquidate {
+        liquidatee_account_id: liquidatee_account_id.clone(),
+        debt_coin: base_info.to_coin(0),
+        request: LiquidateRequest::Perps,
+    };
+
+    // 997 * 0.78 deposit and 3000 * 0.85 perp collateral against 3000 + 3 closing fee perp debt
+    let res = mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![liquidate_perps.clone()],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::NotLiquidatable {
+            account_id: liquidatee_account_id.clone(),
+            lqdt_health_factor: "1.107892107892107892".to_string(),
+        },
+    );
+
+    // the account has no debt, it's under water because of the perp position only
+    set_price(&mut mock, PERP_DENOM, 80);
+    mock.update_credit_account(&liquidator_account_id, &liquidator, vec![liquidate_perps], &[])
+        .unwrap();
+
+    // 600 loss and 3 closing fee were paid from the coin balance, as well as the liquidation fee
+    // of 1% of the 2400 notional value
+    let position = mock.query_positions(&liquidatee_account_id);
+    assert!(position.perps.is_empty());
+    assert!(position.debts.is_empty());
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(370));
+    assert_eq!(mock.query_perp_vault(&perps).total_liquidity, Uint128::new(100_606));
+
+    let position = mock.query_positions(&liquidator_account_id);
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(24));
+}
+
+#[test]
+fn perp_loss_of_account_with_non_base_collateral_becomes_debt() {
+    let base_info = coin_info(BASE_DENOM);
+    let atom_info = uatom_info();
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let lp = Addr::unchecked("lp");
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone(), atom_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![base_info.to_coin(3), atom_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    // the base denom deposit only covers the opening fee
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![Deposit(base_info.to_coin(3)), Deposit(atom_info.to_coin(1_000)), perp_order(30)],
+        &[base_info.to_coin(3), atom_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    set_price(&mut mock, PERP_DENOM, 80);
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id: liquidatee_account_id.clone(),
+            debt_coin: base_info.to_coin(0),
+            request: LiquidateRequest::Perps,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // 600 loss, 3 closing fee and 24 liquidation fee were borrowed against the uatom collateral
+    // and paid in full
+    let position = mock.query_positions(&liquidatee_account_id);
+    assert!(position.perps.is_empty());
+    assert_eq!(get_coin(&atom_info.denom, &position.deposits).amount, Uint128::new(1_000));
+    assert_eq!(get_debt(BASE_DENOM, &position.debts).amount, Uint128::new(627 + 1)); // simulated interest
+    assert_eq!(mock.query_perp_vault(&perps).total_liquidity, Uint128::new(100_606));
+
+    let position = mock.query_positions(&liquidator_account_id);
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(24));
+}
+
+#[test]
+fn perp_liquidation_stops_once_account_is_healthy() {
+    let base_info = coin_info(BASE_DENOM);
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+    let lp = Addr::unchecked("lp");
+    let eth_denom = "ueth";
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone()])
+        .fund_account(AccountToFund {
+            addr: liquidatee.clone(),
+            funds: vec![base_info.to_coin(1_000)],
+        })
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+    set_price(&mut mock, BASE_DENOM, 1);
+    set_price(&mut mock, PERP_DENOM, 100);
+    set_price(&mut mock, eth_denom, 100);
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.update_perp_market(&perps, perp_market_params(eth_denom)).unwrap();
+    mock.deposit_to_perp_vault(&perps, &lp, &[base_info.to_coin(100_000)]).unwrap();
+
+    let liquidatee_account_id = mock.create_credit_account(&liquidatee).unwrap();
+    mock.update_credit_account(
+        &liquidatee_account_id,
+        &liquidatee,
+        vec![
+            Deposit(base_info.to_coin(1_000)),
+            perp_order(30),
+            ExecutePerpOrder {
+                denom: eth_denom.to_string(),
+                size: Int128::new(1),
+                reduce_only: None,
+            },
+        ],
+        &[base_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    set_price(&mut mock, PERP_DENOM, 80);
+
+    let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+    mock.update_credit_account(
+        &liquidator_account_id,
+        &liquidator,
+        vec![Liquidate {
+            liquidatee_account_id: liquidatee_account_id.clone(),
+            debt_coin: base_info.to_coin(0),
+            request: LiquidateRequest::Perps,
+        }],
+        &[],
+    )
+    .unwrap();
+
+    // closing the losing position makes the account healthy, the other position stays open
+    let position = mock.query_positions(&liquidatee_account_id);
+    assert_eq!(position.perps.len(), 1);
+    let perp = position.perps.first().unwrap();
+    assert_eq!(perp.denom, eth_denom);
+    assert_eq!(perp.size, Int128::new(1));
+    assert_eq!(get_coin(BASE_DENOM, &position.deposits).amount, Uint128::new(369));
+}
@@ -0,0 +1,106 @@
+use cosmwasm_std::{coin, to_json_binary, Addr, Binary, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::credit_manager::Action::Deposit;
+use pyth_sdk_cw::{Price, PriceFeed, PriceIdentifier};
+
+use super::helpers::{assert_err, uosmo_info, AccountToFund, MockEnv};
+
+const FEE_DENOM: &str = "untrn";
+const PRICE_ID: &str = "5867f5683c757393a0670ef0f701490950fe93fdb006d181c8265a831ac0c5c6";
+
+fn price_update(price: i64) -> Binary {
+    let price = Price {
+        price,
+        conf: 1000,
+        expo: -8,
+        publish_time: 1700000000,
+    };
+    let price_feed = PriceFeed::new(PriceIdentifier::from_hex(PRICE_ID).unwrap(), price, price);
+    to_json_binary(&price_feed).unwrap()
+}
+
+#[test]
+fn price_updates_require_pyth_contract() {
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let res = mock.update_credit_account_with_price_updates(
+        &account_id,
+        &user,
+        vec![],
+        vec![price_update(100)],
+        &[],
+    );
+    assert_err(res, ContractError::PythNotConfigured);
+}
+
+#[test]
+fn price_updates_require_update_fee() {
+    let coin_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[coin_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![coin_info.to_coin(300), coin(1, FEE_DENOM)],
+        })
+        .build()
+        .unwrap();
+    mock.deploy_pyth();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // One fee unit is charged per update
+    let res = mock.update_credit_account_with_price_updates(
+        &account_id,
+        &user,
+        vec![Deposit(coin_info.to_coin(300))],
+        vec![price_update(100), price_update(101)],
+        &[coin_info.to_coin(300), coin(1, FEE_DENOM)],
+    );
+    assert_err(
+        res,
+        ContractError::InsufficientFunds {
+            requested: Uint128::new(2),
+            available: Uint128::one(),
+        },
+    );
+}
+
+#[test]
+fn price_updates_pushed_before_actions() {
+    let coin_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[coin_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![coin_info.to_coin(300), coin(1, FEE_DENOM)],
+        })
+        .build()
+        .unwrap();
+    let pyth = mock.deploy_pyth();
+    assert_eq!(mock.query_config().pyth, Some(pyth.address().to_string()));
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account_with_price_updates(
+        &account_id,
+        &user,
+        vec![Deposit(coin_info.to_coin(300))],
+        vec![price_update(12345)],
+        &[coin_info.to_coin(300), coin(1, FEE_DENOM)],
+    )
+    .unwrap();
+
+    // Fee is paid to the Pyth contract and the price is stored
+    assert_eq!(mock.query_balance(pyth.address(), FEE_DENOM).amount, Uint128::one());
+    assert_eq!(mock.query_balance(&mock.rover, FEE_DENOM).amount, Uint128::zero());
+    let feed = mock.query_pyth_price_feed(&pyth, PriceIdentifier::from_hex(PRICE_ID).unwrap());
+    assert_eq!(feed.price_feed.get_price_unchecked().price, 12345);
+
+    // Remaining funds are used by the actions
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits.len(), 1);
+    assert_eq!(position.deposits[0].denom, coin_info.denom);
+    assert_eq!(position.deposits[0].amount, Uint128::new(300));
+}
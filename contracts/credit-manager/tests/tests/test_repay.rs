@@ -87,6 +87,7 @@ fn raises_when_repaying_what_is_not_owed() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -152,6 +153,7 @@ fn raises_when_not_enough_assets_to_repay() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
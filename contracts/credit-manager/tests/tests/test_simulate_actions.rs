@@ -0,0 +1,675 @@
+use cosmwasm_std::{coins, Addr, Decimal, Int128, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_swapper_mock::contract::MOCK_SWAP_RESULT;
+use mars_types::{
+    adapters::vault::VaultUnchecked,
+    credit_manager::{
+        Action::{
+            self, AddToClPosition, AssertCoinBalanceAtLeast, AssertDebtAtMost,
+            AssertHealthFactorAbove, AssertValueChange, Borrow, ClaimAstroLpRewards,
+            ClaimClPositionRewards, ClaimRewards, ClaimStakingRewards, CreateClPosition, Delegate,
+            Deposit, EnterVault, ExecutePerpOrder, ExitVault, ExitVaultUnlocked, Lend, Liquidate,
+            MergeAccounts, ProvideLiquidity, Reclaim, Redelegate, RefundAllCoinBalances, Repay,
+            RequestVaultUnlock, StakeAstroLp, SwapExactIn, TransferToAccount, Undelegate,
+            UnstakeAstroLp, Withdraw, WithdrawClPosition, WithdrawLiquidity, WithdrawToWallet,
+        },
+        ActionAmount, ActionCoin, DepositCapExceeded, LiquidateRequest, TransferPosition,
+    },
+    health::AccountKind,
+    oracle::ActionKind,
+    params::{
+        AssetParams, AssetParamsUpdate, PausableAction, PausableContract, PauseScope, PauseUpdate,
+        StakingParams,
+    },
+};
+
+use super::helpers::{
+    get_coin, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv, DEFAULT_UNBONDING_PERIOD,
+};
+
+#[test]
+fn simulation_matches_execution() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(300, uatom_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let actions = vec![
+        Deposit(uatom_info.to_coin(300)),
+        Borrow(uosmo_info.to_coin(100)),
+        SwapExactIn {
+            coin_in: uosmo_info.to_action_coin(40),
+            denom_out: uatom_info.denom.clone(),
+            min_receive: Uint128::zero(),
+            route: None,
+        },
+    ];
+
+    // Funds are not required to simulate deposits
+    let res = mock.query_simulate_actions(&account_id, actions.clone()).unwrap();
+    assert_eq!(res.failed_action, None);
+    assert!(res.deposit_caps_exceeded.is_empty());
+
+    let positions = &res.positions;
+    assert_eq!(positions.deposits.len(), 2);
+    assert_eq!(
+        get_coin(&uatom_info.denom, &positions.deposits).amount,
+        Uint128::new(300) + MOCK_SWAP_RESULT
+    );
+    assert_eq!(get_coin(&uosmo_info.denom, &positions.deposits).amount, Uint128::new(60));
+    assert_eq!(positions.debts.len(), 1);
+    assert_eq!(get_debt(&uosmo_info.denom, &positions.debts).amount, Uint128::new(100));
+
+    // Simulation does not modify the account
+    let current = mock.query_positions(&account_id);
+    assert!(current.deposits.is_empty());
+    assert!(current.debts.is_empty());
+
+    mock.update_credit_account(&account_id, &user, actions, &[uatom_info.to_coin(300)]).unwrap();
+
+    // Mock Red Bank accrues a unit of interest on every borrow, so only the shares are compared
+    let executed = mock.query_positions(&account_id);
+    assert_eq!(res.positions.deposits, executed.deposits);
+    assert_eq!(
+        get_debt(&uosmo_info.denom, &res.positions.debts).shares,
+        get_debt(&uosmo_info.denom, &executed.debts).shares
+    );
+
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert_eq!(res.health.total_collateral_value, health.total_collateral_value);
+    assert!(res.health.total_debt_value <= health.total_debt_value);
+}
+
+#[test]
+fn failed_action_is_reported() {
+    let uatom_info = uatom_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![
+                Deposit(uatom_info.to_coin(100)),
+                Withdraw(uatom_info.to_action_coin(101)),
+                Withdraw(uatom_info.to_action_coin(10)),
+            ],
+        )
+        .unwrap();
+
+    let failed_action = res.failed_action.unwrap();
+    assert_eq!(failed_action.index, 1);
+    assert_eq!(
+        failed_action.error,
+        ContractError::InsufficientFunds {
+            requested: Uint128::new(101),
+            available: Uint128::new(100),
+        }
+        .to_string()
+    );
+
+    // Positions before the failed action
+    assert_eq!(res.positions.deposits, vec![uatom_info.to_coin(100)]);
+}
+
+#[test]
+fn vault_actions_can_not_be_simulated() {
+    let uatom_info = uatom_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![EnterVault {
+                vault: VaultUnchecked::new("vault".to_string()),
+                coin: ActionCoin {
+                    denom: uatom_info.denom.clone(),
+                    amount: ActionAmount::AccountBalance,
+                },
+            }],
+        )
+        .unwrap();
+
+    let failed_action = res.failed_action.unwrap();
+    assert_eq!(failed_action.index, 0);
+    assert_eq!(
+        failed_action.error,
+        ContractError::SimulationNotSupported("enter_vault".to_string()).to_string()
+    );
+}
+
+#[test]
+fn swap_below_min_receive_fails() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock =
+        MockEnv::new().set_params(&[uatom_info.clone(), uosmo_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let min_receive = MOCK_SWAP_RESULT + Uint128::one();
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![
+                Deposit(uatom_info.to_coin(100)),
+                SwapExactIn {
+                    coin_in: uatom_info.to_action_coin(100),
+                    denom_out: uosmo_info.denom.clone(),
+                    min_receive,
+                    route: None,
+                },
+            ],
+        )
+        .unwrap();
+
+    let failed_action = res.failed_action.unwrap();
+    assert_eq!(failed_action.index, 1);
+    assert_eq!(
+        failed_action.error,
+        ContractError::MinReceiveNotMet {
+            estimated: MOCK_SWAP_RESULT,
+            min_receive,
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn exceeded_deposit_caps_are_reported() {
+    let uatom_info = uatom_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let mut params: AssetParams = mock.query_asset_params(&uatom_info.denom);
+    params.deposit_cap = Uint128::new(100);
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: params.into(),
+    });
+
+    let res =
+        mock.query_simulate_actions(&account_id, vec![Deposit(uatom_info.to_coin(101))]).unwrap();
+
+    assert_eq!(res.failed_action, None);
+    assert_eq!(
+        res.deposit_caps_exceeded,
+        vec![DepositCapExceeded {
+            denom: uatom_info.denom,
+            amount: Uint128::new(101),
+            cap: Uint128::new(100),
+        }]
+    );
+}
+
+#[test]
+fn paused_actions_are_reported() {
+    let uatom_info = uatom_info();
+
+    let user = Addr::unchecked("user");
+    let guardian = Addr::unchecked("guardian");
+    let mut mock = MockEnv::new()
+        .emergency_owner(guardian.as_str())
+        .set_params(&[uatom_info.clone()])
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.emergency_pause(
+        &guardian,
+        PauseUpdate {
+            scope: PauseScope::Contract(PausableContract::CreditManager),
+            actions: vec![PausableAction::Borrows],
+        },
+    )
+    .unwrap();
+
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![Deposit(uatom_info.to_coin(100)), Borrow(uatom_info.to_coin(10))],
+        )
+        .unwrap();
+
+    let failed_action = res.failed_action.unwrap();
+    assert_eq!(failed_action.index, 1);
+    assert_eq!(
+        failed_action.error,
+        ContractError::ActionPaused {
+            action: PausableAction::Borrows.to_string(),
+        }
+        .to_string()
+    );
+
+    // Dispatch rejects all actions, so none are applied
+    assert!(res.positions.deposits.is_empty());
+}
+
+#[test]
+fn debt_below_min_debt_value_is_reported() {
+    let uatom_info = uatom_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    let mut params: AssetParams = mock.query_asset_params(&uatom_info.denom);
+    params.min_debt_value = Uint128::new(100);
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: params.into(),
+    });
+
+    // Only the debt at the end of the actions is checked, like in dispatch
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![
+                Deposit(uatom_info.to_coin(1_000)),
+                Borrow(uatom_info.to_coin(50)),
+                Borrow(uatom_info.to_coin(50)),
+            ],
+        )
+        .unwrap();
+    assert_eq!(res.failed_action, None);
+
+    let res = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![
+                Deposit(uatom_info.to_coin(1_000)),
+                Borrow(uatom_info.to_coin(200)),
+                Repay {
+                    recipient_account_id: None,
+                    coin: uatom_info.to_action_coin(150),
+                },
+            ],
+        )
+        .unwrap();
+
+    let failed_action = res.failed_action.unwrap();
+    assert_eq!(failed_action.index, 2);
+    assert_eq!(
+        failed_action.error,
+        ContractError::DebtBelowMinValue {
+            account_id: account_id.clone(),
+            denom: uatom_info.denom.clone(),
+            value: Uint128::new(50),
+            min_value: Uint128::new(100),
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn staking_simulation_matches_execution() {
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info(), uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1", "validator2"])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(1_000, uosmo_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    enable_staking(&mut mock, &uosmo_info.denom);
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // The undelegation sends the validator's first batch, the redelegation waits for the next one
+    let actions = vec![
+        Deposit(uosmo_info.to_coin(1_000)),
+        Delegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(400),
+        },
+        Undelegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(100),
+        },
+        Redelegate {
+            src_validator: "validator1".to_string(),
+            dst_validator: "validator2".to_string(),
+            coin: uosmo_info.to_action_coin(100),
+        },
+    ];
+
+    let res = mock.query_simulate_actions(&account_id, actions.clone()).unwrap();
+    assert_eq!(res.failed_action, None);
+
+    mock.update_credit_account(&account_id, &user, actions, &[uosmo_info.to_coin(1_000)]).unwrap();
+
+    let executed = mock.query_positions(&account_id);
+    assert_eq!(res.positions.deposits, executed.deposits);
+    assert_eq!(res.positions.staking, executed.staking);
+    assert_eq!(executed.staking[0].delegated, uosmo_info.to_coin(300));
+    assert_eq!(executed.staking[0].queued, Uint128::new(100));
+}
+
+#[test]
+fn transfer_simulation_matches_execution() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1"])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    enable_staking(&mut mock, &uosmo_info.denom);
+    let account_id = mock.create_credit_account(&user).unwrap();
+    let recipient_account_id = mock.create_credit_account(&user).unwrap();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Deposit(uosmo_info.to_coin(1_000)),
+            Borrow(uatom_info.to_coin(100)),
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(400),
+            },
+        ],
+        &[uatom_info.to_coin(1_000), uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    let actions = vec![TransferToAccount {
+        recipient_account_id: recipient_account_id.clone(),
+        positions: vec![
+            TransferPosition::Deposit(uosmo_info.to_action_coin(200)),
+            TransferPosition::Debt(uatom_info.to_action_coin(40)),
+            TransferPosition::Staking("validator1".to_string()),
+        ],
+    }];
+
+    let res = mock.query_simulate_actions(&account_id, actions.clone()).unwrap();
+    assert_eq!(res.failed_action, None);
+
+    // Everything is moved by a merge
+    let merge = mock
+        .query_simulate_actions(
+            &account_id,
+            vec![MergeAccounts {
+                recipient_account_id: recipient_account_id.clone(),
+            }],
+        )
+        .unwrap();
+    assert_eq!(merge.failed_action, None);
+    assert!(merge.positions.deposits.is_empty());
+    assert!(merge.positions.debts.is_empty());
+    assert!(merge.positions.staking.is_empty());
+
+    mock.update_credit_account(&account_id, &user, actions, &[]).unwrap();
+
+    let executed = mock.query_positions(&account_id);
+    assert_eq!(res.positions.deposits, executed.deposits);
+    assert_eq!(
+        get_debt(&uatom_info.denom, &res.positions.debts).shares,
+        get_debt(&uatom_info.denom, &executed.debts).shares
+    );
+    assert!(res.positions.staking.is_empty());
+    assert!(executed.staking.is_empty());
+}
+
+/// Actions without a simulation. Adding an action fails to compile here until it's classified,
+/// and `every_action_is_simulated` fails for an unlisted action until it gets a simulation arm.
+fn simulation_not_supported(action: &Action) -> bool {
+    match action {
+        EnterVault {
+            ..
+        }
+        | ExitVault {
+            ..
+        }
+        | RequestVaultUnlock {
+            ..
+        }
+        | ExitVaultUnlocked {
+            ..
+        }
+        | Liquidate {
+            ..
+        }
+        | CreateClPosition {
+            ..
+        }
+        | AddToClPosition {
+            ..
+        }
+        | ExecutePerpOrder {
+            ..
+        } => true,
+        Deposit(..)
+        | Withdraw(..)
+        | WithdrawToWallet {
+            ..
+        }
+        | Borrow(..)
+        | Lend(..)
+        | Reclaim(..)
+        | ClaimRewards {}
+        | Repay {
+            ..
+        }
+        | SwapExactIn {
+            ..
+        }
+        | ProvideLiquidity {
+            ..
+        }
+        | WithdrawLiquidity {
+            ..
+        }
+        | StakeAstroLp {
+            ..
+        }
+        | UnstakeAstroLp {
+            ..
+        }
+        | ClaimAstroLpRewards {
+            ..
+        }
+        | RefundAllCoinBalances {}
+        | AssertHealthFactorAbove(..)
+        | AssertCoinBalanceAtLeast(..)
+        | AssertDebtAtMost(..)
+        | AssertValueChange {
+            ..
+        }
+        | Delegate {
+            ..
+        }
+        | Undelegate {
+            ..
+        }
+        | Redelegate {
+            ..
+        }
+        | ClaimStakingRewards {}
+        | WithdrawClPosition {
+            ..
+        }
+        | ClaimClPositionRewards {
+            ..
+        }
+        | TransferToAccount {
+            ..
+        }
+        | MergeAccounts {
+            ..
+        } => false,
+    }
+}
+
+#[test]
+fn every_action_is_simulated() {
+    let uatom_info = uatom_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new().set_params(&[uatom_info.clone()]).build().unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+    let other_account_id = mock.create_credit_account(&user).unwrap();
+
+    let coin = uatom_info.to_coin(10);
+    let action_coin = uatom_info.to_action_coin(10);
+    let vault = VaultUnchecked::new("vault".to_string());
+    let actions = vec![
+        Deposit(coin.clone()),
+        Withdraw(action_coin.clone()),
+        WithdrawToWallet {
+            coin: action_coin.clone(),
+            recipient: user.to_string(),
+        },
+        Borrow(coin.clone()),
+        Lend(action_coin.clone()),
+        Reclaim(action_coin.clone()),
+        ClaimRewards {},
+        Repay {
+            recipient_account_id: None,
+            coin: action_coin.clone(),
+        },
+        EnterVault {
+            vault: vault.clone(),
+            coin: action_coin.clone(),
+        },
+        ExitVault {
+            vault: vault.clone(),
+            amount: Uint128::new(10),
+        },
+        RequestVaultUnlock {
+            vault: vault.clone(),
+            amount: Uint128::new(10),
+        },
+        ExitVaultUnlocked {
+            id: 1,
+            vault,
+        },
+        Liquidate {
+            liquidatee_account_id: other_account_id.clone(),
+            debt_coin: coin.clone(),
+            request: LiquidateRequest::Deposit(uatom_info.denom.clone()),
+        },
+        SwapExactIn {
+            coin_in: action_coin.clone(),
+            denom_out: uosmo_info().denom,
+            min_receive: Uint128::zero(),
+            route: None,
+        },
+        ProvideLiquidity {
+            coins_in: vec![action_coin.clone()],
+            lp_token_out: "lp".to_string(),
+            slippage: Decimal::percent(1),
+        },
+        WithdrawLiquidity {
+            lp_token: action_coin.clone(),
+            slippage: Decimal::percent(1),
+        },
+        StakeAstroLp {
+            lp_token: action_coin.clone(),
+        },
+        UnstakeAstroLp {
+            lp_token: action_coin.clone(),
+        },
+        ClaimAstroLpRewards {
+            lp_denom: "lp".to_string(),
+        },
+        RefundAllCoinBalances {},
+        AssertHealthFactorAbove(Decimal::one()),
+        AssertCoinBalanceAtLeast(coin.clone()),
+        AssertDebtAtMost(coin.clone()),
+        AssertValueChange {
+            max_loss_pct: Decimal::percent(1),
+        },
+        Delegate {
+            validator: "validator1".to_string(),
+            coin: action_coin.clone(),
+        },
+        Undelegate {
+            validator: "validator1".to_string(),
+            coin: action_coin.clone(),
+        },
+        Redelegate {
+            src_validator: "validator1".to_string(),
+            dst_validator: "validator2".to_string(),
+            coin: action_coin.clone(),
+        },
+        ClaimStakingRewards {},
+        CreateClPosition {
+            pool_id: 1,
+            lower_tick: -100,
+            upper_tick: 100,
+            coins_in: vec![action_coin.clone()],
+            token_min_amount0: Uint128::zero(),
+            token_min_amount1: Uint128::zero(),
+        },
+        AddToClPosition {
+            position_id: 1,
+            coins_in: vec![action_coin.clone()],
+            token_min_amount0: Uint128::zero(),
+            token_min_amount1: Uint128::zero(),
+        },
+        WithdrawClPosition {
+            position_id: 1,
+            liquidity: None,
+        },
+        ClaimClPositionRewards {
+            position_id: 1,
+        },
+        ExecutePerpOrder {
+            denom: uatom_info.denom.clone(),
+            size: Int128::new(10),
+            reduce_only: None,
+        },
+        TransferToAccount {
+            recipient_account_id: other_account_id.clone(),
+            positions: vec![TransferPosition::Deposit(action_coin)],
+        },
+        MergeAccounts {
+            recipient_account_id: other_account_id,
+        },
+    ];
+
+    // Actions may fail for other reasons, as long as they are simulated
+    for action in actions {
+        let res = mock.query_simulate_actions(&account_id, vec![action.clone()]).unwrap();
+        let not_supported = res
+            .failed_action
+            .map(|failed_action| failed_action.error.ends_with("can not be simulated"))
+            .unwrap_or_default();
+        assert_eq!(not_supported, simulation_not_supported(&action), "{action:?}");
+    }
+}
+
+fn enable_staking(mock: &mut MockEnv, denom: &str) {
+    let mut asset_params = mock.query_asset_params(denom);
+    asset_params.credit_manager.staking = Some(StakingParams {
+        haircut: Decimal::percent(20),
+        unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        min_amount: Uint128::new(10),
+    });
+    mock.update_asset_params(AssetParamsUpdate::AddOrUpdate {
+        params: asset_params.into(),
+    });
+}
@@ -0,0 +1,519 @@
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::{
+    credit_manager::{
+        Action::{
+            AssertCoinBalanceAtLeast, ClaimStakingRewards, Delegate, Deposit, Redelegate,
+            Undelegate,
+        },
+        StakingPosition, UnbondingEntry,
+    },
+    health::AccountKind,
+    oracle::ActionKind,
+    params::{AssetParamsUpdate::AddOrUpdate, StakingParams},
+};
+
+use super::helpers::{
+    assert_err, get_coin, uatom_info, uosmo_info, AccountToFund, MockEnv, DEFAULT_UNBONDING_PERIOD,
+};
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[test]
+fn only_bond_denom_with_staking_params_can_be_delegated() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1"])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![uatom_info.to_coin(1_000), uosmo_info.to_coin(1_000)],
+        })
+        .build()
+        .unwrap();
+    let account_id = mock.create_credit_account(&user).unwrap();
+
+    // Staking params not set
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uosmo_info.to_coin(1_000)),
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(400),
+            },
+        ],
+        &[uosmo_info.to_coin(1_000)],
+    );
+    assert_err(res, ContractError::StakingNotEnabled(uosmo_info.denom.clone()));
+
+    // Not the bond denom
+    enable_staking(&mut mock, &uatom_info.denom);
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(uatom_info.to_coin(1_000)),
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uatom_info.to_action_coin(400),
+            },
+        ],
+        &[uatom_info.to_coin(1_000)],
+    );
+    assert_err(res, ContractError::StakingNotEnabled(uatom_info.denom.clone()));
+}
+
+#[test]
+fn delegated_coins_count_as_collateral_with_haircut() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Delegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(400),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(600)]);
+    assert_eq!(
+        position.staking,
+        vec![StakingPosition {
+            validator: "validator1".to_string(),
+            delegated: uosmo_info.to_coin(400),
+            queued: Uint128::zero(),
+            unbonding: vec![],
+        }]
+    );
+
+    // 600 * 0.25 + 400 * 0.25 * (1 - 0.2)
+    let health = mock.query_health(&account_id, AccountKind::Default, ActionKind::Default);
+    assert_eq!(health.total_collateral_value, Uint128::new(230));
+}
+
+#[test]
+fn undelegated_coins_released_after_unbonding_period() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(400),
+            },
+            Undelegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(100),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+
+    let completion_time = mock.query_block_time() + DEFAULT_UNBONDING_PERIOD;
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(600)]);
+    assert_eq!(
+        position.staking,
+        vec![StakingPosition {
+            validator: "validator1".to_string(),
+            delegated: uosmo_info.to_coin(300),
+            queued: Uint128::zero(),
+            unbonding: vec![UnbondingEntry {
+                amount: Uint128::new(100),
+                completion_time,
+            }],
+        }]
+    );
+
+    // Can't undelegate more than delegated
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Undelegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(301),
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::InsufficientFunds {
+            requested: Uint128::new(301),
+            available: Uint128::new(300),
+        },
+    );
+
+    // Unbonding coins are not part of the coin balance yet
+    mock.increment_by_time(DEFAULT_UNBONDING_PERIOD);
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AssertCoinBalanceAtLeast(uosmo_info.to_coin(700))],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::CoinBalanceBelowMin {
+            denom: uosmo_info.denom.clone(),
+            balance: Uint128::new(600),
+            min: Uint128::new(700),
+        },
+    );
+
+    mock.process_unbonding_queue();
+    mock.increment_by_time(6);
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![AssertCoinBalanceAtLeast(uosmo_info.to_coin(700))],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(700)]);
+    assert_eq!(position.staking[0].delegated, uosmo_info.to_coin(300));
+    assert!(position.staking[0].unbonding.is_empty());
+}
+
+#[test]
+fn slashed_unbonding_released_pro_rata() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let another_user = Addr::unchecked("another_user");
+    let another_account_id = mock.create_credit_account(&another_user).unwrap();
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(1_000),
+            },
+            Undelegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(400),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+    mock.update_credit_account(
+        &another_account_id,
+        &another_user,
+        vec![
+            Deposit(uosmo_info.to_coin(1_000)),
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(1_000),
+            },
+            Undelegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin(100),
+            },
+        ],
+        &[uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    // Validator is slashed while the coins of the first account are unbonding and the
+    // undelegation of the other account is still queued
+    mock.slash_validator("validator1", Decimal::percent(10));
+    let position = mock.query_positions(&another_account_id);
+    assert_eq!(position.staking[0].queued, Uint128::new(90));
+
+    let epoch = DEFAULT_UNBONDING_PERIOD / 6;
+    mock.increment_by_time(epoch);
+    mock.send_staking_batch(&another_user, "validator1").unwrap();
+    let position = mock.query_positions(&another_account_id);
+    assert_eq!(position.staking[0].unbonding[0].amount, Uint128::new(90));
+
+    mock.increment_by_time(DEFAULT_UNBONDING_PERIOD - epoch);
+    mock.process_unbonding_queue();
+    mock.increment_by_time(6);
+
+    // The shortfall could still be coins released later, so nothing is settled yet
+    mock.update_credit_account(&account_id, &user, vec![], &[]).unwrap();
+    let position = mock.query_positions(&account_id);
+    assert!(position.deposits.is_empty());
+    assert_eq!(position.staking[0].unbonding[0].amount, Uint128::new(400));
+
+    // Only 360 of the 400 undelegated came back
+    mock.increment_by_time(6);
+    mock.update_credit_account(&account_id, &user, vec![], &[]).unwrap();
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(360)]);
+    assert!(position.staking[0].unbonding.is_empty());
+
+    // The undelegation of the other account was sent after the slash and is released in full
+    mock.increment_by_time(epoch);
+    mock.process_unbonding_queue();
+    mock.increment_by_time(6);
+    mock.update_credit_account(&another_account_id, &another_user, vec![], &[]).unwrap();
+    let position = mock.query_positions(&another_account_id);
+    assert_eq!(position.deposits, vec![uosmo_info.to_coin(90)]);
+
+    // Accounts can't be credited more than Rover received (the balance also holds the staking
+    // rewards withdrawn with the batch)
+    let rover_balance = mock.query_balance(&mock.rover, &uosmo_info.denom).amount;
+    assert!(rover_balance >= Uint128::new(450));
+}
+
+#[test]
+fn undelegations_of_many_accounts_batched_per_validator() {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+    let users = (0..10).map(|i| Addr::unchecked(format!("user{i}"))).collect::<Vec<_>>();
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info, uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1"]);
+    for user in users.iter() {
+        mock = mock.fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(100, uosmo_info.denom.clone()),
+        });
+    }
+    let mut mock = mock.build().unwrap();
+    enable_staking(&mut mock, &uosmo_info.denom);
+
+    let start = mock.query_block_time();
+    let mut account_ids = vec![];
+    for user in users.iter() {
+        let account_id = mock.create_credit_account(user).unwrap();
+        mock.update_credit_account(
+            &account_id,
+            user,
+            vec![
+                Deposit(uosmo_info.to_coin(100)),
+                Delegate {
+                    validator: "validator1".to_string(),
+                    coin: uosmo_info.to_action_coin(100),
+                },
+                Undelegate {
+                    validator: "validator1".to_string(),
+                    coin: uosmo_info.to_action_coin(50),
+                },
+            ],
+            &[uosmo_info.to_coin(100)],
+        )
+        .unwrap();
+        account_ids.push(account_id);
+    }
+
+    // Only the first undelegation is sent right away, the others wait for the next epoch
+    let position = mock.query_positions(&account_ids[0]);
+    assert_eq!(position.staking[0].delegated, uosmo_info.to_coin(50));
+    assert_eq!(position.staking[0].unbonding[0].completion_time, start + DEFAULT_UNBONDING_PERIOD);
+    for account_id in account_ids.iter().skip(1) {
+        let position = mock.query_positions(account_id);
+        assert_eq!(position.staking[0].delegated, uosmo_info.to_coin(100));
+        assert_eq!(position.staking[0].queued, Uint128::new(50));
+        assert!(position.staking[0].unbonding.is_empty());
+    }
+
+    let anyone = Addr::unchecked("anyone");
+    let res = mock.send_staking_batch(&anyone, "validator1");
+    assert_err(
+        res,
+        ContractError::NoStakingBatchDue {
+            validator: "validator1".to_string(),
+        },
+    );
+
+    let epoch = DEFAULT_UNBONDING_PERIOD / 6;
+    mock.increment_by_time(epoch);
+    mock.send_staking_batch(&anyone, "validator1").unwrap();
+
+    // All queued undelegations were sent as a single unbonding entry of the chain
+    for account_id in account_ids.iter().skip(1) {
+        let position = mock.query_positions(account_id);
+        assert_eq!(position.staking[0].delegated, uosmo_info.to_coin(50));
+        assert!(position.staking[0].queued.is_zero());
+        assert_eq!(
+            position.staking[0].unbonding,
+            vec![UnbondingEntry {
+                amount: Uint128::new(50),
+                completion_time: start + epoch + DEFAULT_UNBONDING_PERIOD,
+            }]
+        );
+    }
+    let delegation = mock.app.wrap().query_delegation(&mock.rover, "validator1").unwrap().unwrap();
+    assert_eq!(delegation.amount, uosmo_info.to_coin(500));
+}
+
+#[test]
+fn undelegation_below_min_amount_rejected() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Delegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(400),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Undelegate {
+            validator: "validator1".to_string(),
+            coin: uosmo_info.to_action_coin(9),
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::StakingAmountBelowMin {
+            amount: Uint128::new(9),
+            min: Uint128::new(10),
+        },
+    );
+}
+
+#[test]
+fn redelegate_moves_delegation() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Delegate {
+                validator: "validator1".to_string(),
+                coin: uosmo_info.to_action_coin_full_balance(),
+            },
+            Redelegate {
+                src_validator: "validator1".to_string(),
+                dst_validator: "validator2".to_string(),
+                coin: uosmo_info.to_action_coin_full_balance(),
+            },
+        ],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert!(position.deposits.is_empty());
+    assert_eq!(
+        position.staking,
+        vec![StakingPosition {
+            validator: "validator2".to_string(),
+            delegated: uosmo_info.to_coin(1_000),
+            queued: Uint128::zero(),
+            unbonding: vec![],
+        }]
+    );
+}
+
+#[test]
+fn staking_rewards_split_by_delegation_shares() {
+    let (mut mock, account_id) = setup_account_with_deposit(1_000);
+    let user = Addr::unchecked("user");
+    let uosmo_info = uosmo_info();
+    let delegate_all = Delegate {
+        validator: "validator1".to_string(),
+        coin: uosmo_info.to_action_coin_full_balance(),
+    };
+
+    mock.update_credit_account(&account_id, &user, vec![delegate_all.clone()], &[]).unwrap();
+
+    let res = mock.update_credit_account(&account_id, &user, vec![ClaimStakingRewards {}], &[]);
+    assert_err(res, ContractError::NoAmount);
+
+    // Second account delegates the same amount after half a year
+    mock.increment_by_time(YEAR / 2);
+    let another_user = Addr::unchecked("another_user");
+    let another_account_id = mock.create_credit_account(&another_user).unwrap();
+    mock.update_credit_account(
+        &another_account_id,
+        &another_user,
+        vec![Deposit(uosmo_info.to_coin(1_000)), delegate_all],
+        &[uosmo_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    // 10% APR: first half year 50 for the first account, second half year 50 for each account
+    mock.increment_by_time(YEAR / 2);
+    mock.update_credit_account(&account_id, &user, vec![ClaimStakingRewards {}], &[]).unwrap();
+    mock.update_credit_account(
+        &another_account_id,
+        &another_user,
+        vec![ClaimStakingRewards {}],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(100));
+    let position = mock.query_positions(&another_account_id);
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(50));
+}
+
+fn enable_staking(mock: &mut MockEnv, denom: &str) {
+    let mut asset_params = mock.query_asset_params(denom);
+    asset_params.credit_manager.staking = Some(StakingParams {
+        haircut: Decimal::percent(20),
+        unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        min_amount: Uint128::new(10),
+    });
+    mock.update_asset_params(AddOrUpdate {
+        params: asset_params.into(),
+    });
+}
+
+fn setup_account_with_deposit(amount: u128) -> (MockEnv, String) {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info, uosmo_info.clone()])
+        .staking(&uosmo_info.denom, &["validator1", "validator2"])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(amount, uosmo_info.denom.clone()),
+        })
+        .fund_account(AccountToFund {
+            addr: Addr::unchecked("another_user"),
+            funds: coins(amount, uosmo_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+    enable_staking(&mut mock, &uosmo_info.denom);
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uosmo_info.to_coin(amount))],
+        &[uosmo_info.to_coin(amount)],
+    )
+    .unwrap();
+
+    (mock, account_id)
+}
@@ -0,0 +1,192 @@
+use cosmwasm_std::{coins, Addr, Uint128};
+use mars_credit_manager::error::ContractError;
+use mars_types::credit_manager::{
+    Action::{Borrow, Deposit, Lend, MergeAccounts, TransferToAccount},
+    ActionAmount, ActionCoin, TransferPosition,
+};
+
+use super::helpers::{
+    assert_err, get_coin, get_debt, uatom_info, uosmo_info, AccountToFund, MockEnv,
+};
+
+#[test]
+fn recipient_must_be_owned_by_sender() {
+    let (mut mock, account_id) = setup_account();
+    let user = Addr::unchecked("user");
+
+    let another_user = Addr::unchecked("another_user");
+    let recipient_account_id = mock.create_credit_account(&another_user).unwrap();
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![MergeAccounts {
+            recipient_account_id: recipient_account_id.clone(),
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::NotTokenOwner {
+            user: user.into(),
+            account_id: recipient_account_id,
+        },
+    );
+}
+
+#[test]
+fn can_not_transfer_to_same_account() {
+    let (mut mock, account_id) = setup_account();
+    let user = Addr::unchecked("user");
+
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![MergeAccounts {
+            recipient_account_id: account_id.clone(),
+        }],
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::TransferNotAllowed {
+            reason: "can not transfer to the same account".to_string(),
+        },
+    );
+}
+
+#[test]
+fn recipient_health_is_asserted() {
+    let (mut mock, account_id) = setup_account();
+    let user = Addr::unchecked("user");
+    let recipient_account_id = mock.create_credit_account(&user).unwrap();
+
+    // Recipient receives the debt without any collateral
+    let res = mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![TransferToAccount {
+            recipient_account_id: recipient_account_id.clone(),
+            positions: vec![TransferPosition::Debt(ActionCoin {
+                denom: uosmo_info().denom,
+                amount: ActionAmount::AccountBalance,
+            })],
+        }],
+        &[],
+    );
+    let err: ContractError = res.unwrap_err().downcast().unwrap();
+    assert!(matches!(
+        err,
+        ContractError::AboveMaxLTV {
+            account_id,
+            ..
+        } if account_id == recipient_account_id
+    ));
+}
+
+#[test]
+fn transfer_deposits_and_debt() {
+    let (mut mock, account_id) = setup_account();
+    let user = Addr::unchecked("user");
+    let recipient_account_id = mock.create_credit_account(&user).unwrap();
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let prev_position = mock.query_positions(&account_id);
+    let prev_debt = get_debt(&uosmo_info.denom, &prev_position.debts);
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![TransferToAccount {
+            recipient_account_id: recipient_account_id.clone(),
+            positions: vec![
+                TransferPosition::Deposit(uatom_info.to_action_coin(600)),
+                TransferPosition::Deposit(ActionCoin {
+                    denom: uosmo_info.denom.clone(),
+                    amount: ActionAmount::AccountBalance,
+                }),
+                TransferPosition::Debt(ActionCoin {
+                    denom: uosmo_info.denom.clone(),
+                    amount: ActionAmount::AccountBalance,
+                }),
+            ],
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert_eq!(position.deposits, vec![uatom_info.to_coin(400)]);
+    assert!(position.debts.is_empty());
+
+    let position = mock.query_positions(&recipient_account_id);
+    assert_eq!(position.deposits.len(), 2);
+    assert_eq!(get_coin(&uatom_info.denom, &position.deposits).amount, Uint128::new(600));
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(100));
+    assert_eq!(position.debts, vec![prev_debt]);
+}
+
+#[test]
+fn merge_accounts() {
+    let (mut mock, account_id) = setup_account();
+    let user = Addr::unchecked("user");
+    let recipient_account_id = mock.create_credit_account(&user).unwrap();
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    mock.update_credit_account(&account_id, &user, vec![Lend(uatom_info.to_action_coin(300))], &[])
+        .unwrap();
+
+    let prev_position = mock.query_positions(&account_id);
+    let prev_lent = get_coin(&uatom_info.denom, &prev_position.lends).amount;
+
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![MergeAccounts {
+            recipient_account_id: recipient_account_id.clone(),
+        }],
+        &[],
+    )
+    .unwrap();
+
+    let position = mock.query_positions(&account_id);
+    assert!(position.deposits.is_empty());
+    assert!(position.debts.is_empty());
+    assert!(position.lends.is_empty());
+
+    let position = mock.query_positions(&recipient_account_id);
+    assert_eq!(position.deposits, prev_position.deposits);
+    assert_eq!(position.debts, prev_position.debts);
+    // Mock Red Bank accrues a unit of yield on every lend
+    assert_eq!(get_coin(&uatom_info.denom, &position.lends).amount, prev_lent + Uint128::one());
+    assert_eq!(get_coin(&uosmo_info.denom, &position.deposits).amount, Uint128::new(100));
+}
+
+/// Account with 1_000 uatom collateral and 100 uosmo debt (kept as deposit)
+fn setup_account() -> (MockEnv, String) {
+    let uatom_info = uatom_info();
+    let uosmo_info = uosmo_info();
+
+    let user = Addr::unchecked("user");
+    let mut mock = MockEnv::new()
+        .set_params(&[uatom_info.clone(), uosmo_info.clone()])
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: coins(1_000, uatom_info.denom.clone()),
+        })
+        .build()
+        .unwrap();
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![Deposit(uatom_info.to_coin(1_000)), Borrow(uosmo_info.to_coin(100))],
+        &[uatom_info.to_coin(1_000)],
+    )
+    .unwrap();
+
+    (mock, account_id)
+}
@@ -2,14 +2,14 @@ use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
 use cw_multi_test::Executor;
 use mars_credit_manager::error::ContractError;
 use mars_mock_oracle::msg::{CoinPrice, InstantiateMsg as OracleInstantiateMsg};
-use mars_testing::multitest::modules::token_factory::CustomApp;
+use mars_testing::multitest::modules::stargate::CustomApp;
 use mars_types::{
     adapters::{
         health::HealthContractUnchecked, incentives::IncentivesUnchecked, oracle::OracleUnchecked,
         red_bank::RedBankUnchecked, rewards_collector::RewardsCollector, swapper::SwapperBase,
         zapper::ZapperBase,
     },
-    credit_manager::ConfigUpdates,
+    credit_manager::{ConfigUpdates, DeleverageConfig},
     health::AccountKind,
     oracle::ActionKind,
 };
@@ -29,11 +29,16 @@ fn only_owner_can_update_config() {
             red_bank: None,
             incentives: None,
             max_unlocking_positions: None,
+            max_cl_positions: None,
             max_slippage: None,
             swapper: None,
             zapper: None,
             health_contract: None,
             rewards_collector: None,
+            deleverage_config: None,
+            fee_config: None,
+            perps: None,
+            pyth: None,
         },
     );
 
@@ -76,6 +81,47 @@ fn invalid_max_slippage() {
     );
 }
 
+#[test]
+fn invalid_deleverage_config() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = Addr::unchecked(mock.query_config().ownership.owner.unwrap());
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            deleverage_config: Some(DeleverageConfig {
+                bonus: Decimal::one(),
+                max_repay_ratio: Decimal::percent(50),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Deleverage bonus must be less than 1".to_string(),
+        },
+    );
+
+    let res = mock.update_config(
+        &owner,
+        ConfigUpdates {
+            deleverage_config: Some(DeleverageConfig {
+                bonus: Decimal::percent(1),
+                max_repay_ratio: Decimal::zero(),
+            }),
+            ..Default::default()
+        },
+    );
+    assert_err(
+        res,
+        ContractError::InvalidConfig {
+            reason: "Deleverage max repay ratio must be greater than 0 and less than or equal to 1"
+                .to_string(),
+        },
+    );
+}
+
 #[test]
 fn update_config_works_with_full_config() {
     let mut mock = MockEnv::new().build().unwrap();
@@ -87,10 +133,15 @@ fn update_config_works_with_full_config() {
     let new_incentives = IncentivesUnchecked::new("new_incentives".to_string());
     let new_zapper = ZapperBase::new("new_zapper".to_string());
     let new_unlocking_max = Uint128::new(321);
+    let new_cl_max = Uint128::new(7);
     let new_max_slippage = Decimal::percent(12);
     let new_swapper = SwapperBase::new("new_swapper".to_string());
     let new_health_contract = HealthContractUnchecked::new("new_health_contract".to_string());
     let new_rewards_collector = "rewards_collector_contract_new".to_string();
+    let new_deleverage_config = DeleverageConfig {
+        bonus: Decimal::percent(1),
+        max_repay_ratio: Decimal::percent(50),
+    };
 
     mock.update_config(
         &Addr::unchecked(original_config.ownership.owner.clone().unwrap()),
@@ -100,11 +151,16 @@ fn update_config_works_with_full_config() {
             red_bank: Some(new_red_bank.clone()),
             incentives: Some(new_incentives.clone()),
             max_unlocking_positions: Some(new_unlocking_max),
+            max_cl_positions: Some(new_cl_max),
             max_slippage: Some(new_max_slippage),
             swapper: Some(new_swapper.clone()),
             zapper: Some(new_zapper.clone()),
             health_contract: Some(new_health_contract.clone()),
             rewards_collector: Some(new_rewards_collector.clone()),
+            deleverage_config: Some(new_deleverage_config.clone()),
+            fee_config: None,
+            perps: None,
+            pyth: None,
         },
     )
     .unwrap();
@@ -131,6 +187,9 @@ fn update_config_works_with_full_config() {
     assert_eq!(new_config.max_unlocking_positions, new_unlocking_max);
     assert_ne!(new_config.max_unlocking_positions, original_config.max_unlocking_positions);
 
+    assert_eq!(new_config.max_cl_positions, new_cl_max);
+    assert_ne!(new_config.max_cl_positions, original_config.max_cl_positions);
+
     assert_eq!(new_config.max_slippage, new_max_slippage);
     assert_ne!(new_config.max_slippage, original_config.max_slippage);
 
@@ -140,6 +199,9 @@ fn update_config_works_with_full_config() {
     assert_eq!(&new_config.health_contract, new_health_contract.address());
     assert_ne!(new_config.health_contract, original_config.health_contract);
 
+    assert_eq!(new_config.deleverage_config, Some(new_deleverage_config));
+    assert_eq!(original_config.deleverage_config, None);
+
     let rc_accounts = mock.query_accounts(&new_rewards_collector, None, None);
     let rc_account = rc_accounts.first().unwrap();
     assert_eq!(rc_account.kind, AccountKind::Default);
@@ -46,6 +46,7 @@ fn utilization_if_cap_is_base_denom() {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
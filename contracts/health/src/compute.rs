@@ -12,7 +12,6 @@ use crate::querier::HealthQuerier;
 
 /// Uses `mars-rover-health-computer` which is a data agnostic package given
 /// it's compiled to .wasm and shared with the frontend.
-/// This function queries all necessary data to pass to `HealthComputer`.
 pub fn compute_health(
     deps: Deps,
     kind: AccountKind,
@@ -20,6 +19,18 @@ pub fn compute_health(
     positions: Positions,
     action: ActionKind,
 ) -> HealthResult<HealthValuesResponse> {
+    let computer = health_computer(deps, kind, q, positions, action)?;
+    Ok(computer.compute_health()?.into())
+}
+
+/// Queries all necessary data to pass to `HealthComputer`
+pub fn health_computer(
+    deps: Deps,
+    kind: AccountKind,
+    q: HealthQuerier,
+    positions: Positions,
+    action: ActionKind,
+) -> HealthResult<HealthComputer> {
     // Get the denoms that need prices + markets
     let deposit_denoms = positions.deposits.iter().map(|d| &d.denom).collect::<Vec<_>>();
     let debt_denoms = positions.debts.iter().map(|d| &d.denom).collect::<Vec<_>>();
@@ -34,6 +45,12 @@ pub fn compute_health(
         .collect::<StdResult<HashMap<_, _>>>()?;
     let vault_base_token_denoms = vault_infos.values().map(|v| &v.base_token).collect::<Vec<_>>();
     let staked_lp_denoms = positions.staked_astro_lps.iter().map(|d| &d.denom).collect::<Vec<_>>();
+    let staking_denoms = positions.staking.iter().map(|s| &s.delegated.denom).collect::<Vec<_>>();
+    let cl_denoms = positions
+        .cl_positions
+        .iter()
+        .flat_map(|p| [&p.asset0.denom, &p.asset1.denom])
+        .collect::<Vec<_>>();
 
     // Collect prices + asset
     let mut denoms_data: DenomsData = Default::default();
@@ -43,6 +60,8 @@ pub fn compute_health(
         .chain(lend_denoms)
         .chain(vault_base_token_denoms)
         .chain(staked_lp_denoms)
+        .chain(staking_denoms)
+        .chain(cl_denoms)
         .try_for_each(|denom| -> StdResult<()> {
             let params_opt = q.params.query_asset_params(&deps.querier, denom)?;
             // If the asset is not supported, we skip it (both params and price)
@@ -55,6 +74,17 @@ pub fn compute_health(
             Ok(())
         })?;
 
+    // Perp positions are valued by their market's params, so only prices are needed
+    positions.perps.iter().flat_map(|p| [&p.denom, &p.base_denom]).try_for_each(
+        |denom| -> StdResult<()> {
+            if !denoms_data.prices.contains_key(denom) {
+                let price = q.oracle.query_price(&deps.querier, denom, action.clone())?.price;
+                denoms_data.prices.insert(denom.clone(), price);
+            }
+            Ok(())
+        },
+    )?;
+
     // Collect all vault data
     let mut vaults_data: VaultsData = Default::default();
     positions.vaults.iter().try_for_each(|v| -> HealthResult<()> {
@@ -65,14 +95,13 @@ pub fn compute_health(
         Ok(())
     })?;
 
-    let computer = HealthComputer {
+    Ok(HealthComputer {
         kind,
         positions,
         denoms_data,
         vaults_data,
-    };
-
-    Ok(computer.compute_health()?.into())
+        fee_config: q.fee_config,
+    })
 }
 
 pub fn health_values(
@@ -86,6 +115,16 @@ pub fn health_values(
     compute_health(deps, kind, q, positions, action)
 }
 
+pub fn health_values_for_positions(
+    deps: Deps,
+    positions: Positions,
+    kind: AccountKind,
+    action: ActionKind,
+) -> HealthResult<HealthValuesResponse> {
+    let q = HealthQuerier::new(&deps)?;
+    compute_health(deps, kind, q, positions, action)
+}
+
 pub fn health_state(
     deps: Deps,
     account_id: &str,
@@ -97,7 +136,7 @@ pub fn health_state(
 
     // Helpful to not have to do computations & query the oracle for cases
     // like liquidations where oracle circuit breakers may hinder it.
-    if positions.debts.is_empty() {
+    if positions.debts.is_empty() && positions.perps.is_empty() {
         return Ok(HealthState::Healthy);
     }
 
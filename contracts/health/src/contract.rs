@@ -6,9 +6,10 @@ use mars_owner::OwnerInit::SetInitialOwner;
 use mars_types::health::{ConfigResponse, ExecuteMsg, HealthResult, InstantiateMsg, QueryMsg};
 
 use crate::{
-    compute::{health_state, health_values},
+    compute::{health_state, health_values, health_values_for_positions},
     migrations,
     state::{CREDIT_MANAGER, OWNER},
+    stress::{max_shock_before_liquidation, stress_test},
     update_config::update_config,
 };
 
@@ -68,6 +69,23 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> HealthResult<Binary> {
             kind,
             action,
         } => to_json_binary(&health_state(deps, &account_id, kind, action)?),
+        QueryMsg::HealthValuesForPositions {
+            positions,
+            kind,
+            action,
+        } => to_json_binary(&health_values_for_positions(deps, positions, kind, action)?),
+        QueryMsg::StressTest {
+            account_id,
+            kind,
+            action,
+            scenarios,
+        } => to_json_binary(&stress_test(deps, &account_id, kind, action, scenarios)?),
+        QueryMsg::MaxShockBeforeLiquidation {
+            account_id,
+            kind,
+            action,
+            denom,
+        } => to_json_binary(&max_shock_before_liquidation(deps, &account_id, kind, action, denom)?),
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
     };
     res.map_err(Into::into)
@@ -3,4 +3,5 @@ pub mod contract;
 pub mod migrations;
 pub mod querier;
 pub mod state;
+pub mod stress;
 pub mod update_config;
@@ -1,7 +1,7 @@
 use cosmwasm_std::{Addr, Deps, QuerierWrapper, StdError, StdResult};
 use mars_types::{
     adapters::{oracle::Oracle, params::Params, vault::Vault},
-    credit_manager::{ConfigResponse, Positions, QueryMsg as CmQueryMsg},
+    credit_manager::{ConfigResponse, FeeConfig, Positions, QueryMsg as CmQueryMsg},
     health::HealthResult,
     params::VaultConfig,
 };
@@ -13,6 +13,7 @@ pub struct HealthQuerier<'a> {
     credit_manager: Addr,
     pub params: Params,
     pub oracle: Oracle,
+    pub fee_config: FeeConfig,
 }
 
 impl<'a> HealthQuerier<'a> {
@@ -30,6 +31,7 @@ impl<'a> HealthQuerier<'a> {
             credit_manager,
             params: Params::new(Addr::unchecked(config.params)),
             oracle: Oracle::new(Addr::unchecked(config.oracle)),
+            fee_config: config.fee_config.unwrap_or_default(),
         })
     }
 
@@ -0,0 +1,133 @@
+use cosmwasm_std::{Addr, Decimal, Deps};
+use mars_rover_health_computer::HealthComputer;
+use mars_types::{
+    health::{
+        AccountKind, HealthChange, HealthResult, MaxShockBeforeLiquidationResponse, StressScenario,
+        StressTestResponse,
+    },
+    oracle::ActionKind,
+};
+
+use crate::{compute::health_computer, querier::HealthQuerier};
+
+/// Precision of the max shock search, i.e. 0.01%
+const SHOCK_PRECISION: Decimal = Decimal::raw(100_000_000_000_000);
+
+/// Highest price multiplier searched for the max price increase, i.e. 10x
+const MAX_PRICE_MULTIPLIER: Decimal = Decimal::raw(10_000_000_000_000_000_000);
+
+pub fn stress_test(
+    deps: Deps,
+    account_id: &str,
+    kind: AccountKind,
+    action: ActionKind,
+    scenarios: Vec<StressScenario>,
+) -> HealthResult<StressTestResponse> {
+    let q = HealthQuerier::new(&deps)?;
+    let positions = q.query_positions(account_id)?;
+    let computer = health_computer(deps, kind, q, positions, action)?;
+
+    let scenarios = scenarios
+        .iter()
+        .map(|scenario| {
+            let price_shocks = scenario
+                .price_shocks
+                .iter()
+                .map(|shock| price_shock(&shock.denom, shock.multiplier))
+                .collect::<Vec<_>>();
+            let mut shocked = computer.apply_changes(&price_shocks)?;
+            for shock in scenario.vault_shocks.iter().flatten() {
+                apply_vault_shock(&mut shocked, &shock.vault, shock.multiplier)?;
+            }
+            Ok(shocked.compute_health()?.into())
+        })
+        .collect::<HealthResult<Vec<_>>>()?;
+
+    Ok(StressTestResponse {
+        scenarios,
+    })
+}
+
+/// Bisects the price multiplier of the denom between the current price (healthy) and the closest
+/// price at which the account is liquidatable, in both directions. Health is assumed to move
+/// monotonically with the price in each direction.
+pub fn max_shock_before_liquidation(
+    deps: Deps,
+    account_id: &str,
+    kind: AccountKind,
+    action: ActionKind,
+    denom: String,
+) -> HealthResult<MaxShockBeforeLiquidationResponse> {
+    let q = HealthQuerier::new(&deps)?;
+    let positions = q.query_positions(account_id)?;
+    let computer = health_computer(deps, kind, q, positions, action)?;
+
+    if computer.compute_health()?.is_liquidatable() {
+        return Ok(MaxShockBeforeLiquidationResponse {
+            denom,
+            max_price_decrease: Some(Decimal::zero()),
+            max_price_increase: Some(Decimal::zero()),
+        });
+    }
+
+    let max_price_decrease = max_healthy_multiplier(&computer, &denom, Decimal::zero())?
+        .map(|multiplier| Decimal::one() - multiplier);
+    let max_price_increase = max_healthy_multiplier(&computer, &denom, MAX_PRICE_MULTIPLIER)?
+        .map(|multiplier| multiplier - Decimal::one());
+
+    Ok(MaxShockBeforeLiquidationResponse {
+        denom,
+        max_price_decrease,
+        max_price_increase,
+    })
+}
+
+/// Returns the multiplier closest to `bound` (searching from 1) at which the account is still not
+/// liquidatable, or None if it isn't liquidatable at `bound` either.
+fn max_healthy_multiplier(
+    computer: &HealthComputer,
+    denom: &str,
+    bound: Decimal,
+) -> HealthResult<Option<Decimal>> {
+    let is_liquidatable = |multiplier: Decimal| -> HealthResult<bool> {
+        let shocked = computer.apply_changes(&[price_shock(denom, multiplier)])?;
+        Ok(shocked.compute_health()?.is_liquidatable())
+    };
+
+    if !is_liquidatable(bound)? {
+        return Ok(None);
+    }
+
+    let mut healthy = Decimal::one();
+    let mut liquidatable = bound;
+    while healthy.abs_diff(liquidatable) > SHOCK_PRECISION {
+        let mid = (healthy + liquidatable) * Decimal::percent(50);
+        if is_liquidatable(mid)? {
+            liquidatable = mid;
+        } else {
+            healthy = mid;
+        }
+    }
+
+    Ok(Some(healthy))
+}
+
+fn price_shock(denom: &str, multiplier: Decimal) -> HealthChange {
+    HealthChange::PriceShock {
+        denom: denom.to_string(),
+        multiplier,
+    }
+}
+
+/// Multiplies the value of the vault coins, unlocking positions are valued in base tokens
+fn apply_vault_shock(
+    computer: &mut HealthComputer,
+    vault: &str,
+    multiplier: Decimal,
+) -> HealthResult<()> {
+    if let Some(values) = computer.vaults_data.vault_values.get_mut(&Addr::unchecked(vault)) {
+        values.vault_coin.value = values.vault_coin.value.checked_mul_floor(multiplier)?;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
 use mars_types::params::{
     AssetParamsUnchecked, CmSettings, HlsParamsUnchecked, LiquidationBonus, RedBankSettings,
 };
@@ -15,6 +15,7 @@ pub fn default_asset_params(denom: &str) -> AssetParamsUnchecked {
                 liquidation_threshold: Decimal::from_str("0.9").unwrap(),
                 correlations: vec![],
             }),
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: false,
@@ -27,8 +28,10 @@ pub fn default_asset_params(denom: &str) -> AssetParamsUnchecked {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Default::default(),
+        min_debt_value: Uint128::zero(),
     }
 }
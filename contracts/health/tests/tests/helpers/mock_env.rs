@@ -12,7 +12,7 @@ use mars_types::{
     credit_manager::Positions,
     health::{
         AccountKind, ConfigResponse, ExecuteMsg::UpdateConfig, HealthState, HealthValuesResponse,
-        QueryMsg,
+        MaxShockBeforeLiquidationResponse, QueryMsg, StressScenario, StressTestResponse,
     },
     oracle::ActionKind,
     params::{
@@ -65,6 +65,22 @@ impl MockEnv {
         )
     }
 
+    pub fn query_health_values_for_positions(
+        &self,
+        positions: &Positions,
+        kind: AccountKind,
+        action: ActionKind,
+    ) -> StdResult<HealthValuesResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.health_contract.clone(),
+            &QueryMsg::HealthValuesForPositions {
+                positions: positions.clone(),
+                kind,
+                action,
+            },
+        )
+    }
+
     pub fn query_health_state(
         &self,
         account_id: &str,
@@ -81,6 +97,42 @@ impl MockEnv {
         )
     }
 
+    pub fn query_stress_test(
+        &self,
+        account_id: &str,
+        kind: AccountKind,
+        action: ActionKind,
+        scenarios: Vec<StressScenario>,
+    ) -> StdResult<StressTestResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.health_contract.clone(),
+            &QueryMsg::StressTest {
+                account_id: account_id.to_string(),
+                kind,
+                action,
+                scenarios,
+            },
+        )
+    }
+
+    pub fn query_max_shock_before_liquidation(
+        &self,
+        account_id: &str,
+        kind: AccountKind,
+        action: ActionKind,
+        denom: &str,
+    ) -> StdResult<MaxShockBeforeLiquidationResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.health_contract.clone(),
+            &QueryMsg::MaxShockBeforeLiquidation {
+                account_id: account_id.to_string(),
+                kind,
+                action,
+                denom: denom.to_string(),
+            },
+        )
+    }
+
     pub fn query_config(&self) -> ConfigResponse {
         self.app
             .wrap()
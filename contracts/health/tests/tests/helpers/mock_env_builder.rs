@@ -132,11 +132,16 @@ impl MockEnvBuilder {
                         params,
                         account_nft: None,
                         max_unlocking_positions: Default::default(),
+                        max_cl_positions: Default::default(),
                         max_slippage: Decimal::percent(99),
                         swapper: "n/a".to_string(),
                         zapper: "n/a".to_string(),
                         health_contract: "n/a".to_string(),
                         rewards_collector: None,
+                        deleverage_config: None,
+                        fee_config: None,
+                        perps: None,
+                        pyth: None,
                     },
                 },
                 &[],
@@ -6,4 +6,5 @@ mod test_hls;
 mod test_instantiate;
 mod test_liquidation_pricing;
 mod test_migration_v2;
+mod test_stress_test;
 mod test_update_config;
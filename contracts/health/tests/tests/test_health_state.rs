@@ -26,6 +26,9 @@ fn zero_debts_results_in_healthy_state() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -63,6 +66,9 @@ fn computing_health_when_healthy() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -99,6 +105,9 @@ fn computing_health_when_unhealthy() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
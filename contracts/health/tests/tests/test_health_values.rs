@@ -6,7 +6,7 @@ use mars_types::{
         LockingVaultAmount, UnlockingPositions, Vault, VaultAmount, VaultPosition,
         VaultPositionAmount, VaultUnlockingPosition,
     },
-    credit_manager::Positions,
+    credit_manager::{DebtAmount, Positions},
     health::AccountKind,
     oracle::ActionKind,
     params::{
@@ -59,6 +59,9 @@ fn computes_correct_position_with_zero_assets() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -74,6 +77,54 @@ fn computes_correct_position_with_zero_assets() {
     assert!(!health.above_max_ltv);
 }
 
+#[test]
+fn computes_health_values_for_given_positions() {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    let umars = "umars";
+    let udai = "udai";
+    mock.set_price(umars, Decimal::one(), ActionKind::Default);
+    mock.set_price(udai, Decimal::from_atomics(313451u128, 6).unwrap(), ActionKind::Default);
+    mock.update_asset_params(AddOrUpdate {
+        params: default_asset_params(umars),
+    });
+    mock.update_asset_params(AddOrUpdate {
+        params: default_asset_params(udai),
+    });
+
+    let account_id = "123";
+    let positions = Positions {
+        account_id: account_id.to_string(),
+        account_kind: AccountKind::Default,
+        deposits: vec![Coin {
+            denom: umars.to_string(),
+            amount: Uint128::new(1200),
+        }],
+        debts: vec![DebtAmount {
+            denom: udai.to_string(),
+            shares: Uint128::new(3_000_000),
+            amount: Uint128::new(3),
+        }],
+        lends: vec![],
+        vaults: vec![],
+        staked_astro_lps: vec![],
+        staking: vec![],
+        cl_positions: vec![],
+        perps: vec![],
+    };
+
+    // Positions don't have to be stored in the Credit Manager
+    let health = mock
+        .query_health_values_for_positions(&positions, AccountKind::Default, ActionKind::Default)
+        .unwrap();
+
+    mock.set_positions_response(account_id, &positions);
+    let stored_health =
+        mock.query_health_values(account_id, AccountKind::Default, ActionKind::Default).unwrap();
+    assert_eq!(health, stored_health);
+    assert_eq!(health.total_collateral_value, Uint128::new(1200));
+}
+
 // Testable via only unlocking positions
 #[test]
 fn adds_vault_base_denoms_to_oracle_and_red_bank() {
@@ -106,6 +157,9 @@ fn adds_vault_base_denoms_to_oracle_and_red_bank() {
                 }),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -122,6 +176,7 @@ fn adds_vault_base_denoms_to_oracle_and_red_bank() {
                     liquidation_threshold: Decimal::from_str("0.9").unwrap(),
                     correlations: vec![],
                 }),
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -134,9 +189,11 @@ fn adds_vault_base_denoms_to_oracle_and_red_bank() {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     };
 
@@ -177,6 +234,7 @@ fn whitelisted_coins_work() {
         slope: Decimal::from_atomics(2u128, 0).unwrap(),
         min_lb: Decimal::percent(2u64),
         max_lb: Decimal::percent(10u64),
+        dutch_auction: None,
     };
 
     let mut asset_params = AssetParamsUnchecked {
@@ -188,6 +246,7 @@ fn whitelisted_coins_work() {
                 liquidation_threshold: Decimal::from_str("0.9").unwrap(),
                 correlations: vec![],
             }),
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: false,
@@ -198,6 +257,7 @@ fn whitelisted_coins_work() {
         liquidation_bonus,
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Default::default(),
+        min_debt_value: Uint128::zero(),
     };
 
     let update = AddOrUpdate {
@@ -222,6 +282,9 @@ fn whitelisted_coins_work() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -280,6 +343,9 @@ fn vault_whitelist_affects_max_ltv() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(vault_token_amount)),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
@@ -293,6 +359,7 @@ fn vault_whitelist_affects_max_ltv() {
                     liquidation_threshold: Decimal::from_str("0.9").unwrap(),
                     correlations: vec![],
                 }),
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -305,9 +372,11 @@ fn vault_whitelist_affects_max_ltv() {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     };
 
@@ -382,6 +451,9 @@ fn not_whitelisted_coins_work() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
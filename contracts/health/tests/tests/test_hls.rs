@@ -41,6 +41,9 @@ fn hls_account_kind_passed_along() {
             amount: VaultPositionAmount::Unlocked(VaultAmount::new(vault_token_amount)),
         }],
         staked_astro_lps: vec![],
+        staking: vec![],
+        cl_positions: vec![],
+        perps: vec![],
     };
     mock.set_positions_response(account_id, &positions);
     mock.set_price(debt_token, Decimal::one(), ActionKind::Default);
@@ -30,6 +30,7 @@ fn uses_liquidation_pricing() {
                     liquidation_threshold: Decimal::from_str("0.9").unwrap(),
                     correlations: vec![],
                 }),
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -42,9 +43,11 @@ fn uses_liquidation_pricing() {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     };
 
@@ -68,6 +71,9 @@ fn uses_liquidation_pricing() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
     );
 
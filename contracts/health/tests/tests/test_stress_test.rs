@@ -0,0 +1,217 @@
+use cosmwasm_std::{Coin, Decimal, StdError, Uint128};
+use mars_types::{
+    credit_manager::{DebtAmount, Positions},
+    health::{
+        AccountKind, MaxShockBeforeLiquidationResponse, PriceShock, StressScenario, VaultShock,
+    },
+    oracle::ActionKind,
+    params::AssetParamsUpdate::AddOrUpdate,
+};
+
+use super::helpers::MockEnv;
+use crate::tests::helpers::default_asset_params;
+
+const ACCOUNT_ID: &str = "123";
+
+/// Deposits 1200 umars (1 price) and borrows 300 udai (1 price)
+/// Health: liquidation threshold adjusted collateral: 1200 * 0.5 = 600
+///         debt value: 300
+///         liquidation health factor: 2
+fn setup_account() -> MockEnv {
+    let mut mock = MockEnv::new().build().unwrap();
+
+    mock.set_price("umars", Decimal::one(), ActionKind::Default);
+    mock.set_price("udai", Decimal::one(), ActionKind::Default);
+    mock.update_asset_params(AddOrUpdate {
+        params: default_asset_params("umars"),
+    });
+    mock.update_asset_params(AddOrUpdate {
+        params: default_asset_params("udai"),
+    });
+
+    mock.set_positions_response(
+        ACCOUNT_ID,
+        &Positions {
+            account_id: ACCOUNT_ID.to_string(),
+            account_kind: AccountKind::Default,
+            deposits: vec![Coin {
+                denom: "umars".to_string(),
+                amount: Uint128::new(1200),
+            }],
+            debts: vec![DebtAmount {
+                denom: "udai".to_string(),
+                shares: Uint128::new(300_000_000),
+                amount: Uint128::new(300),
+            }],
+            lends: vec![],
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
+        },
+    );
+
+    mock
+}
+
+fn price_shock(denom: &str, multiplier: Decimal) -> PriceShock {
+    PriceShock {
+        denom: denom.to_string(),
+        multiplier,
+    }
+}
+
+#[test]
+fn stress_test_scenarios() {
+    let mock = setup_account();
+
+    let res = mock
+        .query_stress_test(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            vec![
+                StressScenario {
+                    price_shocks: vec![],
+                    vault_shocks: None,
+                },
+                StressScenario {
+                    price_shocks: vec![price_shock("umars", Decimal::percent(60))],
+                    vault_shocks: None,
+                },
+                StressScenario {
+                    price_shocks: vec![price_shock("umars", Decimal::percent(40))],
+                    vault_shocks: None,
+                },
+                // Shocks of vaults the account has no position in are ignored
+                StressScenario {
+                    price_shocks: vec![price_shock("udai", Decimal::percent(250))],
+                    vault_shocks: Some(vec![VaultShock {
+                        vault: "vault_xyz".to_string(),
+                        multiplier: Decimal::percent(10),
+                    }]),
+                },
+            ],
+        )
+        .unwrap();
+    assert_eq!(res.scenarios.len(), 4);
+
+    let health =
+        mock.query_health_values(ACCOUNT_ID, AccountKind::Default, ActionKind::Default).unwrap();
+    assert_eq!(res.scenarios[0], health);
+    assert_eq!(health.liquidation_health_factor, Some(Decimal::from_ratio(2u128, 1u128)));
+
+    // 720 * 0.5 / 300 = 1.2
+    let shocked = &res.scenarios[1];
+    assert_eq!(shocked.total_collateral_value, Uint128::new(720));
+    assert_eq!(shocked.liquidation_health_factor, Some(Decimal::from_ratio(12u128, 10u128)));
+    assert!(!shocked.liquidatable);
+
+    // 480 * 0.5 / 300 = 0.8
+    let shocked = &res.scenarios[2];
+    assert_eq!(shocked.total_collateral_value, Uint128::new(480));
+    assert_eq!(shocked.liquidation_health_factor, Some(Decimal::from_ratio(8u128, 10u128)));
+    assert!(shocked.liquidatable);
+
+    // 600 / 750 = 0.8
+    let shocked = &res.scenarios[3];
+    assert_eq!(shocked.total_collateral_value, Uint128::new(1200));
+    assert_eq!(shocked.total_debt_value, Uint128::new(750));
+    assert_eq!(shocked.liquidation_health_factor, Some(Decimal::from_ratio(8u128, 10u128)));
+    assert!(shocked.liquidatable);
+}
+
+#[test]
+fn stress_test_with_unknown_denom() {
+    let mock = setup_account();
+
+    let err = mock
+        .query_stress_test(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            vec![StressScenario {
+                price_shocks: vec![price_shock("uatom", Decimal::percent(10))],
+                vault_shocks: None,
+            }],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: uatom was not provided a price to compute health with"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn max_shock_of_collateral_and_debt() {
+    let mock = setup_account();
+
+    // Liquidatable once 1200 * price * 0.5 < 300, i.e. below a price of 0.5
+    let res = mock
+        .query_max_shock_before_liquidation(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            "umars",
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        MaxShockBeforeLiquidationResponse {
+            denom: "umars".to_string(),
+            max_price_decrease: Some(Decimal::percent(50)),
+            max_price_increase: None,
+        }
+    );
+
+    // Liquidatable once 300 * price > 600, i.e. above a price of 2
+    let res = mock
+        .query_max_shock_before_liquidation(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            "udai",
+        )
+        .unwrap();
+    assert_eq!(res.max_price_decrease, None);
+    let max_price_increase = res.max_price_increase.unwrap();
+    assert!(max_price_increase <= Decimal::one());
+    assert!(max_price_increase > Decimal::from_ratio(9999u128, 10000u128));
+
+    let err = mock
+        .query_max_shock_before_liquidation(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            "uatom",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(
+            "Querier contract error: uatom was not provided a price to compute health with"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn max_shock_of_liquidatable_account() {
+    let mut mock = setup_account();
+    mock.set_price("udai", Decimal::from_ratio(3u128, 1u128), ActionKind::Default);
+
+    let res = mock
+        .query_max_shock_before_liquidation(
+            ACCOUNT_ID,
+            AccountKind::Default,
+            ActionKind::Default,
+            "umars",
+        )
+        .unwrap();
+    assert_eq!(res.max_price_decrease, Some(Decimal::zero()));
+    assert_eq!(res.max_price_increase, Some(Decimal::zero()));
+}
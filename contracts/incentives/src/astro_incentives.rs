@@ -11,11 +11,12 @@ use mars_types::{
     credit_manager::{ActionAmount, ActionCoin},
     error::MarsError,
     incentives::LpModification,
+    params::PausableAction,
 };
 
 use crate::{
     helpers::{
-        calculate_rewards_for_staked_astro_lp_position, claim_rewards_msg,
+        assert_not_paused, calculate_rewards_for_staked_astro_lp_position, claim_rewards_msg,
         compute_updated_astro_incentive_states, MaybeMutStorage,
     },
     query::query_unclaimed_astro_lp_rewards,
@@ -116,6 +117,8 @@ pub fn execute_stake_lp(
 
     ensure_eq!(info.sender, credit_manager_addr, ContractError::Mars(MarsError::Unauthorized {}));
 
+    assert_not_paused(deps.as_ref(), PausableAction::Deposits)?;
+
     update_user_lp_position(
         deps,
         &account_id,
@@ -347,6 +350,8 @@ pub fn execute_claim_rewards_for_staked_lp_position(
     let mars_incentives_addr = env.contract.address.to_string();
     ensure_eq!(info.sender, credit_manager_addr, ContractError::Mars(MarsError::Unauthorized {}));
 
+    assert_not_paused(deps.as_ref(), PausableAction::Claims)?;
+
     let staked_lp_amount = ASTRO_USER_LP_DEPOSITS
         .may_load(deps.storage, (account_id, lp_denom))?
         .ok_or(NoStakedLp {
@@ -31,6 +31,11 @@ pub enum ContractError {
         reason: String,
     },
 
+    #[error("Action is paused: {action}")]
+    ActionPaused {
+        action: String,
+    },
+
     #[error("Invalid Pagination Params. If start_after_incentive_denom is supplied, then start_after_collateral_denom must also be supplied")]
     InvalidPaginationParams,
 
@@ -11,15 +11,17 @@ use cosmwasm_std::{
 };
 use cw_storage_plus::Bound;
 use mars_types::{
+    adapters::params::Params,
     address_provider::{self, MarsAddressType},
     incentives::IncentiveState,
     keys::{UserId, UserIdKey},
+    params::{PausableAction, PausableContract},
     red_bank,
 };
 
 use crate::{
     state::{
-        ASTRO_INCENTIVE_STATES, ASTRO_TOTAL_LP_DEPOSITS, EMISSIONS, EPOCH_DURATION,
+        ASTRO_INCENTIVE_STATES, ASTRO_TOTAL_LP_DEPOSITS, CONFIG, EMISSIONS, EPOCH_DURATION,
         INCENTIVE_STATES, USER_ASSET_INDICES, USER_ASTRO_INCENTIVE_STATES, USER_UNCLAIMED_REWARDS,
         WHITELIST,
     },
@@ -129,6 +131,27 @@ pub fn validate_incentive_schedule(
     Ok(())
 }
 
+/// Returns an error if the action is paused for the incentives contract in the params contract
+pub fn assert_not_paused(deps: Deps, action: PausableAction) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let params_addr = address_provider::helpers::query_contract_addr(
+        deps,
+        &config.address_provider,
+        MarsAddressType::Params,
+    )?;
+    let paused = Params::new(params_addr).query_is_paused(
+        &deps.querier,
+        PausableContract::Incentives,
+        action,
+    )?;
+    if paused {
+        return Err(ContractError::ActionPaused {
+            action: action.to_string(),
+        });
+    }
+    Ok(())
+}
+
 /// Queries the total scaled collateral for a given collateral denom from the red bank contract
 pub fn query_red_bank_total_collateral(
     deps: Deps,
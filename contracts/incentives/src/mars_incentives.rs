@@ -6,6 +6,7 @@ use mars_types::{
     error::MarsError,
     incentives::IncentiveState,
     keys::{UserId, UserIdKey},
+    params::PausableAction,
 };
 use mars_utils::helpers::validate_native_denom;
 
@@ -32,6 +33,8 @@ pub fn execute_claim_rewards(
     start_after_incentive_denom: Option<String>,
     limit: Option<u32>,
 ) -> Result<Response, ContractError> {
+    helpers::assert_not_paused(deps.as_ref(), PausableAction::Claims)?;
+
     let user_addr = info.sender;
     let acc_id = account_id.clone().unwrap_or("".to_string());
     let user_id = UserId::credit_manager(user_addr.clone(), acc_id.clone());
@@ -1,8 +1,16 @@
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
-    StdResult,
+    coin, entry_point, from_json, to_json_binary, Binary, Coin, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult,
 };
-use pyth_sdk_cw::{Price, PriceFeed, PriceFeedResponse, PriceIdentifier, QueryMsg};
+use mars_types::adapters::pyth::PythExecuteMsg;
+use pyth_sdk_cw::{Price, PriceFeed, PriceFeedResponse, PriceIdentifier};
+
+use crate::{msg::QueryMsg, state::PRICE_FEEDS};
+
+/// Denom in which the update fee is paid
+pub const UPDATE_FEE_DENOM: &str = "untrn";
+/// Fee charged per price update payload
+pub const UPDATE_FEE_PER_VAA: u128 = 1;
 
 #[entry_point]
 pub fn instantiate(
@@ -15,8 +23,39 @@ pub fn instantiate(
 }
 
 #[entry_point]
-pub fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: PythExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        PythExecuteMsg::UpdatePriceFeeds {
+            data,
+        } => update_price_feeds(deps, info, data),
+    }
+}
+
+/// Instead of signed VAAs, the mock accepts JSON encoded price feeds
+fn update_price_feeds(deps: DepsMut, info: MessageInfo, data: Vec<Binary>) -> StdResult<Response> {
+    let fee = update_fee(&data);
+    let paid =
+        info.funds.iter().find(|c| c.denom == fee.denom).map(|c| c.amount).unwrap_or_default();
+    if paid < fee.amount {
+        return Err(StdError::generic_err(format!(
+            "Insufficient update fee: required {fee}, paid {paid}{}",
+            fee.denom
+        )));
+    }
+
+    for vaa in data.iter() {
+        let price_feed: PriceFeed = from_json(vaa)?;
+        PRICE_FEEDS.save(deps.storage, price_feed.id.to_hex(), &price_feed)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_price_feeds")
+        .add_attribute("num_updates", data.len().to_string()))
 }
 
 #[entry_point]
@@ -24,12 +63,25 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::PriceFeed {
             id,
-        } => to_json_binary(&mocked_price_feed(deps, id)?),
-        _ => panic!("Unsupported query!"),
+        } => to_json_binary(&query_price_feed(deps, id)?),
+        QueryMsg::GetUpdateFee {
+            vaas,
+        } => to_json_binary(&update_fee(&vaas)),
     }
 }
 
-fn mocked_price_feed(_deps: Deps, id: PriceIdentifier) -> StdResult<PriceFeedResponse> {
+fn update_fee(vaas: &[Binary]) -> Coin {
+    coin(UPDATE_FEE_PER_VAA * vaas.len() as u128, UPDATE_FEE_DENOM)
+}
+
+/// Returns the last pushed price feed, or a mocked one if no update was pushed for the id
+fn query_price_feed(deps: Deps, id: PriceIdentifier) -> StdResult<PriceFeedResponse> {
+    if let Some(price_feed) = PRICE_FEEDS.may_load(deps.storage, id.to_hex())? {
+        return Ok(PriceFeedResponse {
+            price_feed,
+        });
+    }
+
     let price_feed_response = PriceFeedResponse {
         price_feed: PriceFeed::new(
             id,
@@ -0,0 +1,14 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Binary;
+use pyth_sdk_cw::PriceIdentifier;
+
+/// Subset of the Pyth contract's queries supported by the mock
+#[cw_serde]
+pub enum QueryMsg {
+    PriceFeed {
+        id: PriceIdentifier,
+    },
+    GetUpdateFee {
+        vaas: Vec<Binary>,
+    },
+}
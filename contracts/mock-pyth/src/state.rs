@@ -0,0 +1,5 @@
+use cw_storage_plus::Map;
+use pyth_sdk_cw::PriceFeed;
+
+/// Price feeds pushed with `UpdatePriceFeeds`, keyed by the hex encoded price identifier
+pub const PRICE_FEEDS: Map<String, PriceFeed> = Map::new("price_feeds");
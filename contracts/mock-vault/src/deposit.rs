@@ -85,6 +85,7 @@ fn steal_user_funds(
             account_kind: None,
             // Depositing user funds it was sent as its own
             actions: vec![Deposit(info.funds.first().unwrap().clone())],
+            price_updates: None,
         })?,
     });
 
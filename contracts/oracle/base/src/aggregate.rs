@@ -0,0 +1,237 @@
+use std::fmt;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CustomQuery, Decimal, Deps, Env};
+use cw_storage_plus::Map;
+use mars_types::oracle::{ActionKind, Config};
+
+use crate::{
+    ContractError::{InvalidPrice, InvalidPriceSource},
+    ContractResult, PriceSourceChecked,
+};
+
+/// Maximum number of sub-sources an aggregate price source can consist of
+pub const MAX_AGGREGATE_SOURCES: usize = 10;
+
+#[cw_serde]
+pub enum AggregationMethod {
+    /// Median of the prices returned by the sub-sources
+    Median {},
+    /// Weighted mean of the prices returned by the sub-sources.
+    ///
+    /// Prices deviating from the median of all returned prices by more than `max_deviation`
+    /// (percentage) are rejected as outliers before the mean is calculated.
+    WeightedMean {
+        /// Weight of each sub-source, in the same order as the sub-sources
+        weights: Vec<Decimal>,
+
+        /// The maximum deviation (percentage) of a sub-source price from the median
+        max_deviation: Decimal,
+    },
+}
+
+impl fmt::Display for AggregationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AggregationMethod::Median {} => write!(f, "median"),
+            AggregationMethod::WeightedMean {
+                weights,
+                max_deviation,
+            } => {
+                let weights = weights.iter().map(|w| w.to_string()).collect::<Vec<_>>().join(",");
+                write!(f, "weighted_mean:[{weights}]:{max_deviation}")
+            }
+        }
+    }
+}
+
+/// Price calculated from the sub-sources of an aggregate price source
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedPrice {
+    pub price: Decimal,
+    /// Number of sub-sources the price was calculated from
+    pub sources_used: u32,
+    /// Total number of sub-sources configured
+    pub sources_total: u32,
+}
+
+/// Validates the aggregate params. Sub-sources themselves are validated by the implementing
+/// contract.
+pub fn assert_aggregate(
+    sources_len: usize,
+    min_sources: u32,
+    method: &AggregationMethod,
+) -> ContractResult<()> {
+    if sources_len == 0 {
+        return Err(InvalidPriceSource {
+            reason: "aggregate price source requires at least one sub-source".to_string(),
+        });
+    }
+
+    if sources_len > MAX_AGGREGATE_SOURCES {
+        return Err(InvalidPriceSource {
+            reason: format!(
+                "aggregate price source can have at most {MAX_AGGREGATE_SOURCES} sub-sources"
+            ),
+        });
+    }
+
+    if min_sources == 0 || min_sources as usize > sources_len {
+        return Err(InvalidPriceSource {
+            reason: "min_sources must be greater than zero and less than or equal to the number of sub-sources".to_string(),
+        });
+    }
+
+    if let AggregationMethod::WeightedMean {
+        weights,
+        max_deviation,
+    } = method
+    {
+        if weights.len() != sources_len {
+            return Err(InvalidPriceSource {
+                reason: "number of weights must match the number of sub-sources".to_string(),
+            });
+        }
+
+        if weights.iter().any(|w| w.is_zero()) {
+            return Err(InvalidPriceSource {
+                reason: "weights must be greater than zero".to_string(),
+            });
+        }
+
+        if max_deviation.is_zero() || *max_deviation >= Decimal::one() {
+            return Err(InvalidPriceSource {
+                reason: "max_deviation must be greater than zero and less than one".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Aggregates the prices of the sub-sources.
+///
+/// Sub-sources failing to return a price (e.g. stale Pyth price, missing pool) are skipped.
+/// The query fails if fewer than `min_sources` sub-sources are left to calculate the price from.
+#[allow(clippy::too_many_arguments)]
+pub fn query_aggregated_price<P, C>(
+    deps: &Deps<C>,
+    env: &Env,
+    denom: &str,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    kind: ActionKind,
+    sources: &[P],
+    min_sources: u32,
+    method: &AggregationMethod,
+) -> ContractResult<AggregatedPrice>
+where
+    P: PriceSourceChecked<C>,
+    C: CustomQuery,
+{
+    let sources_total = sources.len() as u32;
+
+    // (price, weight) of every sub-source which returned a price
+    let prices = sources
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, source)| {
+            let price =
+                source.query_price(deps, env, denom, config, price_sources, kind.clone()).ok()?;
+            let weight = match method {
+                AggregationMethod::Median {} => Decimal::one(),
+                AggregationMethod::WeightedMean {
+                    weights,
+                    ..
+                } => weights[idx],
+            };
+            Some((price, weight))
+        })
+        .collect::<Vec<_>>();
+
+    if (prices.len() as u32) < min_sources {
+        return Err(InvalidPrice {
+            reason: format!(
+                "only {} of {sources_total} sub-sources returned a price, at least {min_sources} required",
+                prices.len()
+            ),
+        });
+    }
+
+    let median = median(prices.iter().map(|(price, _)| *price).collect())?;
+
+    match method {
+        AggregationMethod::Median {} => Ok(AggregatedPrice {
+            price: median,
+            sources_used: prices.len() as u32,
+            sources_total,
+        }),
+        AggregationMethod::WeightedMean {
+            max_deviation,
+            ..
+        } => {
+            let max_diff = median.checked_mul(*max_deviation)?;
+            let prices = prices
+                .into_iter()
+                .filter(|(price, _)| price.abs_diff(median) <= max_diff)
+                .collect::<Vec<_>>();
+
+            if (prices.len() as u32) < min_sources {
+                return Err(InvalidPrice {
+                    reason: format!(
+                        "only {} of {sources_total} sub-sources are within max deviation of the median, at least {min_sources} required",
+                        prices.len()
+                    ),
+                });
+            }
+
+            let mut weighted_sum = Decimal::zero();
+            let mut total_weight = Decimal::zero();
+            for (price, weight) in &prices {
+                weighted_sum = weighted_sum.checked_add(price.checked_mul(*weight)?)?;
+                total_weight = total_weight.checked_add(*weight)?;
+            }
+
+            Ok(AggregatedPrice {
+                price: weighted_sum.checked_div(total_weight)?,
+                sources_used: prices.len() as u32,
+                sources_total,
+            })
+        }
+    }
+}
+
+/// Median of a non-empty list of prices. For an even number of prices the mean of the two middle
+/// prices is returned.
+fn median(mut prices: Vec<Decimal>) -> ContractResult<Decimal> {
+    prices.sort();
+
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        return Ok(prices[mid]);
+    }
+
+    Ok(prices[mid - 1].checked_add(prices[mid])?.checked_div(Decimal::from_ratio(2u128, 1u128))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_number_of_prices() {
+        let prices = vec![Decimal::percent(300), Decimal::percent(100), Decimal::percent(200)];
+        assert_eq!(median(prices).unwrap(), Decimal::percent(200));
+    }
+
+    #[test]
+    fn median_of_even_number_of_prices() {
+        let prices = vec![
+            Decimal::percent(400),
+            Decimal::percent(100),
+            Decimal::percent(300),
+            Decimal::percent(200),
+        ];
+        assert_eq!(median(prices).unwrap(), Decimal::percent(250));
+    }
+}
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{fmt::Display, marker::PhantomData};
 
 use cosmwasm_std::{
     to_json_binary, Addr, Binary, CustomQuery, Deps, DepsMut, Env, MessageInfo, Order, Response,
@@ -7,12 +7,17 @@ use cosmwasm_std::{
 use cw_storage_plus::{Bound, Item, Map};
 use mars_owner::{Owner, OwnerInit::SetInitialOwner, OwnerUpdate};
 use mars_types::oracle::{
-    ActionKind, Config, ConfigResponse, ExecuteMsg, InstantiateMsg, PriceResponse,
-    PriceSourceResponse, QueryMsg,
+    ActionKind, AggregatedPriceResponse, BoundedPriceResponse, Config, ConfigResponse, ExecuteMsg,
+    InstantiateMsg, PriceDetailedResponse, PriceResponse, PriceSourceResponse, QueryMsg,
 };
 use mars_utils::helpers::validate_native_denom;
 
-use crate::{error::ContractResult, ContractError, PriceSourceChecked, PriceSourceUnchecked};
+use crate::{
+    error::ContractResult,
+    exchange_rate::{RateSnapshot, RATE_SNAPSHOTS, SECONDS_PER_DAY},
+    price_details::PriceDetails,
+    ContractError, PriceSourceChecked, PriceSourceUnchecked,
+};
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
@@ -92,6 +97,7 @@ where
     pub fn execute(
         &self,
         deps: DepsMut<C>,
+        env: Env,
         info: MessageInfo,
         msg: ExecuteMsg<PU, E>,
     ) -> ContractResult<Response> {
@@ -100,10 +106,13 @@ where
             ExecuteMsg::SetPriceSource {
                 denom,
                 price_source,
-            } => self.set_price_source(deps, info.sender, denom, price_source),
+            } => self.set_price_source(deps, env, info.sender, denom, price_source),
             ExecuteMsg::RemovePriceSource {
                 denom,
             } => self.remove_price_source(deps, info.sender, denom),
+            ExecuteMsg::RefreshRateSnapshot {
+                denom,
+            } => self.refresh_rate_snapshot(deps, env, denom),
             ExecuteMsg::UpdateConfig {
                 base_denom,
             } => self.update_config(deps, info.sender, base_denom),
@@ -142,6 +151,44 @@ where
                 limit,
                 kind.unwrap_or(ActionKind::Default),
             )?),
+            QueryMsg::PriceDetailed {
+                denom,
+                kind,
+            } => to_json_binary(&self.query_price_detailed(
+                deps,
+                env,
+                denom,
+                kind.unwrap_or(ActionKind::Default),
+            )?),
+            QueryMsg::PricesDetailed {
+                start_after,
+                limit,
+                kind,
+            } => to_json_binary(&self.query_prices_detailed(
+                deps,
+                env,
+                start_after,
+                limit,
+                kind.unwrap_or(ActionKind::Default),
+            )?),
+            QueryMsg::AggregatedPrice {
+                denom,
+                kind,
+            } => to_json_binary(&self.query_aggregated_price(
+                deps,
+                env,
+                denom,
+                kind.unwrap_or(ActionKind::Default),
+            )?),
+            QueryMsg::BoundedPrice {
+                denom,
+                kind,
+            } => to_json_binary(&self.query_bounded_price(
+                deps,
+                env,
+                denom,
+                kind.unwrap_or(ActionKind::Default),
+            )?),
         };
         res.map_err(Into::into)
     }
@@ -158,6 +205,7 @@ where
     fn set_price_source(
         &self,
         deps: DepsMut<C>,
+        env: Env,
         sender_addr: Addr,
         denom: String,
         price_source: PU,
@@ -171,6 +219,18 @@ where
             price_source.validate(&deps.as_ref(), &denom, &cfg.base_denom, &self.price_sources)?;
         self.price_sources.save(deps.storage, &denom, &price_source)?;
 
+        match price_source.query_exchange_rate(&deps.as_ref(), &denom)? {
+            Some(rate) => RATE_SNAPSHOTS.save(
+                deps.storage,
+                &denom,
+                &RateSnapshot {
+                    rate,
+                    timestamp: env.block.time.seconds(),
+                },
+            )?,
+            None => RATE_SNAPSHOTS.remove(deps.storage, &denom),
+        }
+
         Ok(Response::new()
             .add_attribute("action", "set_price_source")
             .add_attribute("denom", denom)
@@ -186,12 +246,65 @@ where
         self.owner.assert_owner(deps.storage, &sender_addr)?;
 
         self.price_sources.remove(deps.storage, &denom);
+        RATE_SNAPSHOTS.remove(deps.storage, &denom);
 
         Ok(Response::new()
             .add_attribute("action", "remove_price_source")
             .add_attribute("denom", denom))
     }
 
+    fn refresh_rate_snapshot(
+        &self,
+        deps: DepsMut<C>,
+        env: Env,
+        denom: String,
+    ) -> ContractResult<Response> {
+        let snapshot = RATE_SNAPSHOTS.may_load(deps.storage, &denom)?.ok_or_else(|| {
+            ContractError::InvalidPriceSource {
+                reason: format!("price source of {denom} is not rate based"),
+            }
+        })?;
+
+        let current_time = env.block.time.seconds();
+        let next_refresh = snapshot.timestamp + SECONDS_PER_DAY;
+        if current_time < next_refresh {
+            return Err(ContractError::RateSnapshotTooRecent {
+                next_refresh,
+            });
+        }
+
+        // The price query checks the current rate against the snapshot being replaced
+        let cfg = self.config.load(deps.storage)?;
+        let price_source = self.price_sources.load(deps.storage, &denom)?;
+        price_source.query_price(
+            &deps.as_ref(),
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            ActionKind::Default,
+        )?;
+
+        let rate = price_source.query_exchange_rate(&deps.as_ref(), &denom)?.ok_or_else(|| {
+            ContractError::InvalidPriceSource {
+                reason: format!("price source of {denom} is not rate based"),
+            }
+        })?;
+        RATE_SNAPSHOTS.save(
+            deps.storage,
+            &denom,
+            &RateSnapshot {
+                rate,
+                timestamp: current_time,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "refresh_rate_snapshot")
+            .add_attribute("denom", denom)
+            .add_attribute("rate", rate.to_string()))
+    }
+
     fn update_config(
         &self,
         deps: DepsMut<C>,
@@ -286,6 +399,59 @@ where
         })
     }
 
+    fn query_aggregated_price(
+        &self,
+        deps: Deps<C>,
+        env: Env,
+        denom: String,
+        kind: ActionKind,
+    ) -> ContractResult<AggregatedPriceResponse> {
+        let cfg = self.config.load(deps.storage)?;
+
+        let price_source = self.query_price_source(deps, denom.clone())?.price_source;
+        let aggregated_price = price_source.query_aggregated_price(
+            &deps,
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            kind,
+        )?;
+
+        Ok(AggregatedPriceResponse {
+            denom,
+            price: aggregated_price.price,
+            sources_used: aggregated_price.sources_used,
+            sources_total: aggregated_price.sources_total,
+        })
+    }
+
+    fn query_bounded_price(
+        &self,
+        deps: Deps<C>,
+        env: Env,
+        denom: String,
+        kind: ActionKind,
+    ) -> ContractResult<BoundedPriceResponse> {
+        let cfg = self.config.load(deps.storage)?;
+
+        let price_source = self.query_price_source(deps, denom.clone())?.price_source;
+        let bounded_price = price_source.query_bounded_price(
+            &deps,
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            kind,
+        )?;
+
+        Ok(BoundedPriceResponse {
+            denom,
+            price: bounded_price.price,
+            raw_price: bounded_price.raw_price,
+        })
+    }
+
     fn query_prices(
         &self,
         deps: Deps<C>,
@@ -318,4 +484,81 @@ where
             })
             .collect()
     }
+
+    fn query_price_detailed(
+        &self,
+        deps: Deps<C>,
+        env: Env,
+        denom: String,
+        kind: ActionKind,
+    ) -> ContractResult<PriceDetailedResponse> {
+        let cfg = self.config.load(deps.storage)?;
+
+        let price_source = self.query_price_source(deps, denom.clone())?.price_source;
+        let details = price_source.query_price_detailed(
+            &deps,
+            &env,
+            &denom,
+            &cfg,
+            &self.price_sources,
+            kind,
+        )?;
+
+        Ok(price_detailed_response(&env, denom, &price_source, details))
+    }
+
+    fn query_prices_detailed(
+        &self,
+        deps: Deps<C>,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        kind: ActionKind,
+    ) -> ContractResult<Vec<PriceDetailedResponse>> {
+        let cfg = self.config.load(deps.storage)?;
+
+        let start = start_after.map(|denom| Bound::ExclusiveRaw(denom.into_bytes()));
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        self.price_sources
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (k, v) = item?;
+                let details = v.query_price_detailed(
+                    &deps,
+                    &env,
+                    &k,
+                    &cfg,
+                    &self.price_sources,
+                    kind.clone(),
+                )?;
+                Ok(price_detailed_response(&env, k, &v, details))
+            })
+            .collect()
+    }
+}
+
+fn price_detailed_response<P: Display>(
+    env: &Env,
+    denom: String,
+    price_source: &P,
+    details: PriceDetails,
+) -> PriceDetailedResponse {
+    PriceDetailedResponse {
+        denom,
+        price: details.price,
+        error: details.error,
+        price_source: price_source.to_string(),
+        publish_time: details.publish_time,
+        // Redemption rates can be published with a timestamp ahead of the block time
+        age: details
+            .publish_time
+            .map(|publish_time| env.block.time.seconds().saturating_sub(publish_time)),
+        max_staleness: details.max_staleness,
+        confidence: details.confidence,
+        ema_deviation: details.ema_deviation,
+        twap_window: details.twap_window,
+        transitive_denoms: details.transitive_denoms,
+    }
 }
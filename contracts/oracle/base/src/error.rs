@@ -51,6 +51,11 @@ pub enum ContractError {
         reason: String,
     },
 
+    #[error("Rate snapshot can be refreshed at {next_refresh} at the earliest")]
+    RateSnapshotTooRecent {
+        next_refresh: u64,
+    },
+
     #[error("Missing custom init params")]
     MissingCustomInitParams {},
 
@@ -0,0 +1,144 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Decimal, QuerierWrapper, QueryRequest, Storage, WasmQuery};
+use cw_storage_plus::Map;
+use serde::Deserialize;
+
+use crate::{
+    ContractError::{InvalidPrice, InvalidPriceSource},
+    ContractResult,
+};
+
+pub const SECONDS_PER_DAY: u64 = 86400;
+
+/// Exchange rate of a rate based price source (redemption rate, contract query) observed when the
+/// price source was set or the snapshot was last refreshed. It is the reference for the rate
+/// growth checks.
+#[cw_serde]
+pub struct RateSnapshot {
+    pub rate: Decimal,
+    /// Block time (in seconds) at which the rate was observed
+    pub timestamp: u64,
+}
+
+/// Rate snapshots of rate based price sources, keyed by denom
+pub const RATE_SNAPSHOTS: Map<&str, RateSnapshot> = Map::new("rate_snapshots");
+
+/// Query sent to a contract returning the exchange rate of a token to another denom
+/// (e.g. Drop dASSET, pSTAKE stkASSET or vault-like tokens)
+#[cw_serde]
+pub struct ExchangeRateQuery<T> {
+    /// Contract returning the exchange rate
+    pub contract_addr: T,
+
+    /// JSON encoded query message sent to the contract
+    pub query_msg: Binary,
+
+    /// Dot separated path of the rate in the JSON response, e.g. `state.exchange_rate`.
+    /// Array elements are selected by their index. An empty path means the response is the rate.
+    /// The rate has to be encoded as a decimal string (as `cosmwasm_std::Decimal` is).
+    pub rate_path: String,
+}
+
+/// Minimal JSON value, used to look up the rate in arbitrary query responses.
+/// Only objects, arrays and strings are inspected, other values just have to parse.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+/// How much transitive denom we get for 1 denom, as returned by the queried contract
+pub fn query_exchange_rate(
+    querier: &QuerierWrapper,
+    query: &ExchangeRateQuery<Addr>,
+) -> ContractResult<Decimal> {
+    let response: JsonValue = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: query.contract_addr.to_string(),
+        msg: query.query_msg.clone(),
+    }))?;
+
+    let mut value = &response;
+    for key in query.rate_path.split('.').filter(|key| !key.is_empty()) {
+        let next = match value {
+            JsonValue::Object(fields) => fields.get(key),
+            JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|idx| items.get(idx)),
+            _ => None,
+        };
+        value = next.ok_or_else(|| InvalidPrice {
+            reason: format!(
+                "exchange rate path {} not found in response of {}",
+                query.rate_path, query.contract_addr
+            ),
+        })?;
+    }
+
+    let JsonValue::String(rate) = value else {
+        return Err(InvalidPrice {
+            reason: format!(
+                "exchange rate at path {} of {} is not a decimal string",
+                query.rate_path, query.contract_addr
+            ),
+        });
+    };
+    Decimal::from_str(rate).map_err(|_| InvalidPrice {
+        reason: format!("invalid exchange rate {rate} returned by {}", query.contract_addr),
+    })
+}
+
+pub fn assert_rate_path(rate_path: &str) -> ContractResult<()> {
+    if !rate_path.is_empty() && rate_path.split('.').any(|key| key.is_empty()) {
+        return Err(InvalidPriceSource {
+            reason: format!("invalid exchange rate path: {rate_path}"),
+        });
+    }
+    Ok(())
+}
+
+pub fn assert_max_rate_jump_per_day(max_rate_jump_per_day: Decimal) -> ContractResult<()> {
+    if max_rate_jump_per_day.is_zero() || max_rate_jump_per_day >= Decimal::one() {
+        return Err(InvalidPriceSource {
+            reason: "max_rate_jump_per_day must be greater than 0 and less than 1".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Exchange rates of LSDs and yield bearing tokens grow slowly. A rate which grew faster than
+/// `max_rate_jump_per_day` (relative, per started day) since the last snapshot is rejected, as it
+/// indicates a faulty or manipulated rate provider. Decreasing rates (e.g. caused by slashing) are
+/// accepted.
+///
+/// The allowed growth accumulates until the snapshot is refreshed, which anyone can do once per
+/// day (see `ExecuteMsg::RefreshRateSnapshot`), keeping the bound per day.
+pub fn assert_rate_growth(
+    storage: &dyn Storage,
+    denom: &str,
+    rate: Decimal,
+    max_rate_jump_per_day: Decimal,
+    current_time: u64,
+) -> ContractResult<()> {
+    let snapshot = RATE_SNAPSHOTS.load(storage, denom)?;
+
+    let elapsed = current_time.saturating_sub(snapshot.timestamp);
+    let days = ((elapsed + SECONDS_PER_DAY - 1) / SECONDS_PER_DAY).max(1);
+    let max_growth = max_rate_jump_per_day.checked_mul(Decimal::from_ratio(days, 1u128))?;
+    let max_rate = snapshot.rate.checked_mul(Decimal::one().checked_add(max_growth)?)?;
+    if rate > max_rate {
+        return Err(InvalidPrice {
+            reason: format!(
+                "exchange rate {rate} grew too fast. rate at {}: {}, max rate: {max_rate}",
+                snapshot.timestamp, snapshot.rate
+            ),
+        });
+    }
+    Ok(())
+}
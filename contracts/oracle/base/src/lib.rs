@@ -2,9 +2,14 @@ mod contract;
 mod error;
 mod traits;
 
+pub mod aggregate;
+pub mod exchange_rate;
 pub mod lp_pricing;
+pub mod price_bounds;
+pub mod price_details;
 pub mod pyth;
 pub mod redemption_rate;
+pub mod vault_share;
 
 pub use contract::*;
 pub use error::*;
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Empty, Env, Isqrt, Uint128, Uint256};
 use cw_storage_plus::Map;
 use mars_types::oracle::{ActionKind, Config};
@@ -45,3 +47,43 @@ pub fn query_xyk_lp_price<P: PriceSourceChecked<Empty>>(
 
     Ok(Decimal::from_ratio(pool_value_u128, total_shares))
 }
+
+/// Astroport PCL (Curve V2) LP price model.
+/// The price is derived from the curve invariant and the oracle prices of the pool assets, so it
+/// can't be manipulated by swapping in the pool.
+pub fn compute_pcl_lp_price(
+    coin0_price: Decimal,
+    coin1_price: Decimal,
+    coin0_decimals: u8,
+    coin1_decimals: u8,
+    total_shares: Uint128,
+    price_scale: Decimal,
+    curve_invariant: Decimal256,
+) -> ContractResult<Decimal> {
+    // xcp represents the virtual value of the pool
+    // xcp = curve_invariant / (2 * sqrt(price_scale))
+    let xcp = curve_invariant.checked_div(
+        Decimal256::from(price_scale).sqrt().checked_mul(Decimal256::from_str("2")?)?,
+    )?;
+
+    // Virtual price represents the theoretic price of one share. This virtual price is used as input
+    // for the Curve V2 model to determine the modelled lp price.
+    // virtual_price = xcp / total_shares
+    let virtual_price = xcp.checked_div(Decimal256::from_ratio(total_shares, 1u128))?;
+
+    // The curve_invariant is calculated with amounts scaled by Astroport, e.g. 1e18 ueth is stored as 1 eth.
+    // So we need to scale the prices accordingly, so that they represent the price of 1 whole unit.
+    let coin0_price_scaled =
+        Decimal256::from(coin0_price) * Decimal256::from_str("10")?.pow(coin0_decimals as u32);
+    let coin1_price_scaled =
+        Decimal256::from(coin1_price) * Decimal256::from_str("10")?.pow(coin1_decimals as u32);
+
+    // LP price according to the model
+    // lp_price_model = 2 * virtual_price * sqrt(coin0_price * coin1_price)
+    let lp_price_model_256 = Decimal256::from_str("2")?
+        .checked_mul(virtual_price)?
+        .checked_mul(coin0_price_scaled.checked_mul(coin1_price_scaled)?.sqrt())?;
+    let lp_price_model = Decimal::try_from(lp_price_model_256)?;
+
+    Ok(lp_price_model)
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
+use mars_types::oracle::ActionKind;
+
+use crate::{
+    ContractError::{InvalidPrice, InvalidPriceSource},
+    ContractResult,
+};
+
+#[cw_serde]
+pub struct PriceBounds {
+    /// Prices below the floor are raised to the floor, e.g. for tokens with a hard redemption floor
+    pub floor: Decimal,
+
+    /// Prices above the ceiling are lowered to the ceiling
+    pub ceiling: Decimal,
+
+    /// Band wider than `[floor, ceiling]`. If the raw price leaves it, the price is rejected for
+    /// `ActionKind::Default` (e.g. borrowing against a depegged stablecoin), while liquidations
+    /// keep using the clamped price.
+    pub reject_outside: Option<PriceBand>,
+}
+
+#[cw_serde]
+pub struct PriceBand {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+impl fmt::Display for PriceBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let band = match &self.reject_outside {
+            None => "None".to_string(),
+            Some(band) => format!("Some({}:{})", band.min, band.max),
+        };
+        write!(f, "{}:{}:{band}", self.floor, self.ceiling)
+    }
+}
+
+/// Raw price of the wrapped price source together with the price clamped into the bounds
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedPrice {
+    pub raw_price: Decimal,
+    pub price: Decimal,
+}
+
+pub fn assert_price_bounds(bounds: &PriceBounds) -> ContractResult<()> {
+    if bounds.ceiling.is_zero() || bounds.floor > bounds.ceiling {
+        return Err(InvalidPriceSource {
+            reason: "ceiling must be greater than zero and greater than or equal to floor"
+                .to_string(),
+        });
+    }
+
+    if let Some(band) = &bounds.reject_outside {
+        if band.min > bounds.floor || band.max < bounds.ceiling {
+            return Err(InvalidPriceSource {
+                reason: "reject_outside band must contain [floor, ceiling]".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Clamps the raw price into `[floor, ceiling]`.
+///
+/// Returns an error for `ActionKind::Default` if the raw price is outside of the
+/// `reject_outside` band.
+pub fn apply_price_bounds(
+    denom: &str,
+    raw_price: Decimal,
+    bounds: &PriceBounds,
+    kind: &ActionKind,
+) -> ContractResult<BoundedPrice> {
+    if let (ActionKind::Default, Some(band)) = (kind, &bounds.reject_outside) {
+        if raw_price < band.min || raw_price > band.max {
+            return Err(InvalidPrice {
+                reason: format!(
+                    "price {raw_price} of {denom} is outside of the allowed band [{}, {}]",
+                    band.min, band.max
+                ),
+            });
+        }
+    }
+
+    Ok(BoundedPrice {
+        raw_price,
+        price: raw_price.clamp(bounds.floor, bounds.ceiling),
+    })
+}
@@ -0,0 +1,43 @@
+use cosmwasm_std::Decimal;
+
+use crate::ContractResult;
+
+/// Price of a price source together with the metadata needed to monitor it, e.g. how close the
+/// underlying oracle data is to becoming stale
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceDetails {
+    /// Resolved price, `None` if the price failed validation (e.g. is stale)
+    pub price: Option<Decimal>,
+    /// Reason the price failed validation
+    pub error: Option<String>,
+    /// Publish time of the underlying oracle data (Pyth price or redemption rate update time)
+    pub publish_time: Option<u64>,
+    /// Max age of the underlying oracle data before the price is rejected as stale
+    pub max_staleness: Option<u64>,
+    /// Pyth confidence interval relative to the EMA price
+    pub confidence: Option<Decimal>,
+    /// Deviation of the Pyth price from the EMA price
+    pub ema_deviation: Option<Decimal>,
+    /// TWAP window in seconds
+    pub twap_window: Option<u64>,
+    /// Denoms whose prices were consulted to price the denom
+    pub transitive_denoms: Vec<String>,
+}
+
+impl PriceDetails {
+    /// Record the outcome of the price query, so the metadata is still returned if it failed
+    pub fn with_price_result(self, price: ContractResult<Decimal>) -> Self {
+        match price {
+            Ok(price) => Self {
+                price: Some(price),
+                error: None,
+                ..self
+            },
+            Err(err) => Self {
+                price: None,
+                error: Some(err.to_string()),
+                ..self
+            },
+        }
+    }
+}
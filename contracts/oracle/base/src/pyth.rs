@@ -4,7 +4,7 @@ use mars_types::oracle::{ActionKind, Config};
 use pyth_sdk_cw::{query_price_feed, Price, PriceFeed, PriceFeedResponse, PriceIdentifier};
 
 use super::*;
-use crate::error::ContractError::InvalidPrice;
+use crate::{error::ContractError::InvalidPrice, price_details::PriceDetails};
 
 // We don't support any denom with more than 18 decimals
 const MAX_DENOM_DECIMALS: u8 = 18;
@@ -123,6 +123,47 @@ fn query_pyth_price_for_liquidation(
     Ok(current_price_dec)
 }
 
+/// Query the publish time, confidence and EMA deviation of a Pyth price feed.
+///
+/// No staleness, confidence or deviation checks are applied, so that the values can be monitored
+/// before the price gets rejected.
+pub fn query_pyth_price_details(
+    deps: &Deps,
+    contract_addr: Addr,
+    price_feed_id: PriceIdentifier,
+    max_staleness: u64,
+) -> ContractResult<PriceDetails> {
+    let price_feed = query_price_feed(&deps.querier, contract_addr, price_feed_id)?.price_feed;
+    let current_price = price_feed.get_price_unchecked();
+    let ema_price = price_feed.get_ema_price_unchecked();
+
+    // Confidence and deviation are relative to the EMA price, so they can't be calculated if it
+    // isn't positive
+    let (confidence, ema_deviation) = if current_price.price > 0 && ema_price.price > 0 {
+        let current_price_dec = scale_to_exponent(current_price.price as u128, current_price.expo)?;
+        let ema_price_dec = scale_to_exponent(ema_price.price as u128, ema_price.expo)?;
+        let confidence = scale_to_exponent(current_price.conf as u128, current_price.expo)?;
+        (
+            Some(confidence.checked_div(ema_price_dec)?),
+            Some(current_price_dec.abs_diff(ema_price_dec).checked_div(ema_price_dec)?),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(PriceDetails {
+        price: None,
+        error: None,
+        publish_time: Some(current_price.publish_time.max(0) as u64),
+        max_staleness: Some(max_staleness),
+        confidence,
+        ema_deviation,
+        twap_window: None,
+        // The Pyth price is quoted in USD and converted with the price of usd
+        transitive_denoms: vec!["usd".to_string()],
+    })
+}
+
 /// Assert Pyth configuration
 pub fn assert_pyth(
     max_confidence: Decimal,
@@ -6,7 +6,10 @@ use mars_types::oracle::{ActionKind, Config};
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::ContractResult;
+use crate::{
+    aggregate::AggregatedPrice, price_bounds::BoundedPrice, price_details::PriceDetails,
+    ContractResult,
+};
 
 pub trait PriceSourceUnchecked<P, C>:
     Serialize + DeserializeOwned + Clone + Debug + PartialEq + JsonSchema
@@ -51,4 +54,54 @@ where
         price_sources: &Map<&str, Self>,
         kind: ActionKind,
     ) -> ContractResult<Decimal>;
+
+    /// Query the price of an aggregate price source together with the number of sub-sources the
+    /// price was calculated from.
+    ///
+    /// Returns an error if the price source is not an aggregate price source.
+    fn query_aggregated_price(
+        &self,
+        deps: &Deps<C>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<AggregatedPrice>;
+
+    /// Query the price of an asset together with metadata of the price source, such as the
+    /// publish time of the underlying oracle data, the TWAP window and the transitive denoms.
+    ///
+    /// The metadata is returned even if the price fails validation (e.g. because it is stale),
+    /// with the price left empty and the reason recorded in `error`.
+    fn query_price_detailed(
+        &self,
+        deps: &Deps<C>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<PriceDetails>;
+
+    /// Query the raw price of the price source wrapped by a bounded price source together with
+    /// the price clamped into the bounds.
+    ///
+    /// Returns an error if the price source is not a bounded price source.
+    fn query_bounded_price(
+        &self,
+        deps: &Deps<C>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<BoundedPrice>;
+
+    /// Query the exchange rate of a rate based price source (e.g. redemption rate) to its
+    /// transitive denom. The rate is snapshotted when the price source is set and serves as the
+    /// reference for the rate growth checks.
+    ///
+    /// Returns `None` if the price source is not rate based.
+    fn query_exchange_rate(&self, deps: &Deps<C>, denom: &str) -> ContractResult<Option<Decimal>>;
 }
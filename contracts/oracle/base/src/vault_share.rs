@@ -0,0 +1,92 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper};
+use mars_types::adapters::vault::Vault;
+
+use crate::{
+    ContractError::{InvalidPrice, InvalidPriceSource},
+    ContractResult,
+};
+
+#[cw_serde]
+pub struct SharePriceBounds {
+    /// Minimum accepted share price (base tokens per vault token). A lower share price
+    /// indicates the vault has been drained or misreports its assets.
+    pub min_share_price: Decimal,
+
+    /// Maximum accepted share price (base tokens per vault token). A higher share price
+    /// indicates a sudden jump, e.g. caused by a donation attack, and is rejected.
+    pub max_share_price: Decimal,
+}
+
+/// How many vault base tokens are redeemable for 1 vault token.
+///
+/// Computed by converting the whole vault token supply to assets, so the price respects the
+/// vault's own conversion (including rounding) without losing precision.
+pub fn query_share_price(
+    querier: &QuerierWrapper,
+    vault_address: &Addr,
+) -> ContractResult<Decimal> {
+    let vault = Vault::new(vault_address.clone());
+    let total_vault_tokens = vault.query_total_vault_coins_issued(querier)?;
+    if total_vault_tokens.is_zero() {
+        return Err(InvalidPrice {
+            reason: format!("vault {vault_address} has no vault tokens issued"),
+        });
+    }
+    let total_assets = vault.query_convert_to_assets(querier, total_vault_tokens)?;
+    Ok(Decimal::checked_from_ratio(total_assets, total_vault_tokens)?)
+}
+
+/// Validates that the vault issues `denom` backed by `vault_base_denom` and that the bounds are sane.
+pub fn assert_vault_share(
+    querier: &QuerierWrapper,
+    vault_address: &Addr,
+    denom: &str,
+    vault_base_denom: &str,
+    bounds: &SharePriceBounds,
+) -> ContractResult<()> {
+    if bounds.min_share_price.is_zero() || bounds.min_share_price >= bounds.max_share_price {
+        return Err(InvalidPriceSource {
+            reason: "min_share_price must be greater than zero and less than max_share_price"
+                .to_string(),
+        });
+    }
+
+    let info = Vault::new(vault_address.clone()).query_info(querier)?;
+    if info.vault_token != denom {
+        return Err(InvalidPriceSource {
+            reason: format!(
+                "vault token mismatch. expected: {denom}, vault token: {}",
+                info.vault_token
+            ),
+        });
+    }
+    if info.base_token != vault_base_denom {
+        return Err(InvalidPriceSource {
+            reason: format!(
+                "vault base token mismatch. expected: {vault_base_denom}, base token: {}",
+                info.base_token
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Share price checked against the configured bounds
+pub fn query_bounded_share_price(
+    querier: &QuerierWrapper,
+    vault_address: &Addr,
+    bounds: &SharePriceBounds,
+) -> ContractResult<Decimal> {
+    let share_price = query_share_price(querier, vault_address)?;
+    if share_price < bounds.min_share_price || share_price > bounds.max_share_price {
+        return Err(InvalidPrice {
+            reason: format!(
+                "share price {share_price} of vault {vault_address} is outside of bounds [{}, {}]",
+                bounds.min_share_price, bounds.max_share_price
+            ),
+        });
+    }
+    Ok(share_price)
+}
@@ -1,6 +1,12 @@
+use astroport_v5::{
+    asset::{AssetInfo, PairInfo},
+    factory::PairType,
+    pair::QueryMsg as PairQueryMsg,
+};
+use cosmwasm_std::QuerierWrapper;
 use mars_oracle_base::{ContractError, ContractResult};
 use mars_osmosis::{
-    helpers::{CommonPoolData, Pool},
+    helpers::{query_cosmwasm_pool_contract_addr, CommonPoolData, Pool},
     BalancerPool,
 };
 
@@ -56,6 +62,113 @@ pub fn assert_osmosis_xyk_lp_pool(pool: &Pool) -> ContractResult<()> {
     Ok(())
 }
 
+/// Assert the Osmosis pool indicated by `pool_id` is StableSwap type with two assets
+pub fn assert_osmosis_ss_lp_pool(pool: &Pool) -> ContractResult<()> {
+    assert_pool_has_two_assets(pool)?;
+
+    match pool {
+        Pool::StableSwap(stable_swap_pool) => {
+            let scaling_factors = &stable_swap_pool.scaling_factors;
+            if scaling_factors.len() != 2 || scaling_factors.contains(&0) {
+                return Err(ContractError::InvalidPriceSource {
+                    reason: format!(
+                        "invalid scaling factors {:?} of pool {}",
+                        scaling_factors, stable_swap_pool.id
+                    ),
+                });
+            }
+        }
+        Pool::Balancer(balancer_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("Balancer pool not supported. Pool id {}", balancer_pool.id),
+            });
+        }
+        Pool::ConcentratedLiquidity(cl_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("ConcentratedLiquidity pool not supported. Pool id {}", cl_pool.id),
+            });
+        }
+        Pool::CosmWasm(cw_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("CosmWasm pool not supported. Pool id {}", cw_pool.id),
+            });
+        }
+    };
+
+    Ok(())
+}
+
+/// Assert the Osmosis pool indicated by `pool_id` is CosmWasm type and return its contract address
+pub fn query_cosmwasm_pool_contract(
+    querier: &QuerierWrapper,
+    pool: &Pool,
+) -> ContractResult<String> {
+    let pool_id = match pool {
+        Pool::CosmWasm(cw_pool) => cw_pool.id,
+        Pool::Balancer(balancer_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("Balancer pool not supported. Pool id {}", balancer_pool.id),
+            });
+        }
+        Pool::StableSwap(stable_swap_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("StableSwap pool not supported. Pool id {}", stable_swap_pool.id),
+            });
+        }
+        Pool::ConcentratedLiquidity(cl_pool) => {
+            return Err(ContractError::InvalidPriceSource {
+                reason: format!("ConcentratedLiquidity pool not supported. Pool id {}", cl_pool.id),
+            });
+        }
+    };
+
+    Ok(query_cosmwasm_pool_contract_addr(querier, pool_id)?)
+}
+
+/// Assert the CosmWasm pool contract is an Astroport PCL (concentrated) pair of two native coins
+pub fn assert_astroport_pcl_pair(
+    querier: &QuerierWrapper,
+    contract_addr: &str,
+) -> ContractResult<Vec<String>> {
+    let pair_info: PairInfo = querier.query_wasm_smart(contract_addr, &PairQueryMsg::Pair {})?;
+
+    if pair_info.pair_type != PairType::Custom("concentrated".to_string()) {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!(
+                "expecting pool contract {} to be a PCL pair; found {}",
+                contract_addr, pair_info.pair_type
+            ),
+        });
+    }
+
+    let denoms = pair_info
+        .asset_infos
+        .iter()
+        .map(|asset_info| match asset_info {
+            AssetInfo::NativeToken {
+                denom,
+            } => Ok(denom.clone()),
+            AssetInfo::Token {
+                contract_addr,
+            } => Err(ContractError::InvalidPriceSource {
+                reason: format!("pair contains cw20 token: {}", contract_addr),
+            }),
+        })
+        .collect::<ContractResult<Vec<_>>>()?;
+
+    if denoms.len() != 2 {
+        return Err(ContractError::InvalidPriceSource {
+            reason: format!(
+                "expecting pool contract {} to contain exactly two coins; found {}",
+                contract_addr,
+                denoms.len()
+            ),
+        });
+    }
+
+    Ok(denoms)
+}
+
 /// Assert the Osmosis pool has exactly two assets
 fn assert_pool_has_two_assets(pool: &Pool) -> ContractResult<()> {
     let pool_id = pool.get_pool_id();
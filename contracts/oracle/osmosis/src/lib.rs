@@ -1,5 +1,6 @@
 pub mod contract;
 mod helpers;
+pub mod lp_pricing;
 pub mod migrations;
 pub mod msg;
 mod price_source;
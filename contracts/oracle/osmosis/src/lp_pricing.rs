@@ -0,0 +1,153 @@
+use std::cmp::min;
+
+use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Empty, Env, Uint128, Uint256};
+use cw_storage_plus::Map;
+use mars_oracle_base::{
+    lp_pricing::compute_pcl_lp_price, ContractError, ContractResult, PriceSourceChecked,
+};
+use mars_types::oracle::{ActionKind, Config};
+
+fn query_coin_price<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    kind: ActionKind,
+    denom: &str,
+) -> ContractResult<Decimal> {
+    price_sources.load(deps.storage, denom)?.query_price(
+        deps,
+        env,
+        denom,
+        config,
+        price_sources,
+        kind,
+    )
+}
+
+/// NOTE: Price sources must exist for both assets in the pool.
+#[allow(clippy::too_many_arguments)]
+pub fn query_stable_swap_lp_price<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    kind: ActionKind,
+    coin0: Coin,
+    coin1: Coin,
+    scaling_factors: (u64, u64),
+    total_shares: Uint128,
+) -> ContractResult<Decimal> {
+    let coin0_price =
+        query_coin_price(deps, env, config, price_sources, kind.clone(), &coin0.denom)?;
+    let coin1_price = query_coin_price(deps, env, config, price_sources, kind, &coin1.denom)?;
+
+    compute_ss_lp_price(
+        coin0_price,
+        coin1_price,
+        coin0.amount,
+        coin1.amount,
+        scaling_factors,
+        total_shares,
+    )
+}
+
+pub fn compute_ss_lp_price(
+    coin0_price: Decimal,
+    coin1_price: Decimal,
+    coin0_amount: Uint128,
+    coin1_amount: Uint128,
+    scaling_factors: (u64, u64),
+    total_shares: Uint128,
+) -> ContractResult<Decimal> {
+    // Osmosis StableSwap pools with two assets use the CFMM:
+    //    x * y * (x^2 + y^2) = k
+    // where x and y are the pool amounts divided by their scaling factors.
+    // Swaps don't change k (apart from swap fees increasing it), so the LP price can't be
+    // manipulated by swapping in the pool (e.g. with a flash loan), as opposed to valuing the
+    // pool amounts.
+    //
+    // D is the sum of the scaled amounts of a balanced pool (x = y = D/2) with the same k:
+    //    D^4 = 8 * k
+    //    D^2 = sqrt(x * y) * sqrt(8 * (x^2 + y^2))
+    // The square roots are taken separately, so that big pools don't overflow Decimal256.
+    let x = Decimal256::from_ratio(coin0_amount, scaling_factors.0);
+    let y = Decimal256::from_ratio(coin1_amount, scaling_factors.1);
+    let sum_of_squares = x.checked_mul(x)?.checked_add(y.checked_mul(y)?)?;
+    let d_squared = x
+        .checked_mul(y)?
+        .sqrt()
+        .checked_mul(Decimal256::from_ratio(8u8, 1u8).checked_mul(sum_of_squares)?.sqrt())?;
+    let d = d_squared.sqrt();
+
+    // One scaled unit of an asset is worth `price * scaling_factor`. The pool is valued as if it
+    // consisted of the cheaper asset only, so a depeg of one asset doesn't inflate the LP price.
+    let coin0_unit_price = Decimal256::from(coin0_price)
+        .checked_mul(Decimal256::from_ratio(scaling_factors.0, 1u8))?;
+    let coin1_unit_price = Decimal256::from(coin1_price)
+        .checked_mul(Decimal256::from_ratio(scaling_factors.1, 1u8))?;
+
+    let pool_value = d.checked_mul(min(coin0_unit_price, coin1_unit_price))?;
+    let lp_price_256 = pool_value.checked_div(Decimal256::from_ratio(total_shares, 1u8))?;
+    let lp_price = Decimal::try_from(lp_price_256)?;
+
+    Ok(lp_price)
+}
+
+/// Transmuter pools swap their assets 1:1 (after normalization) without any price curve, so the
+/// value of the pool can't be inflated by swapping in it. The shares are priced by the oracle
+/// value of the assets held by the pool.
+///
+/// NOTE: Price sources must exist for all assets in the pool.
+pub fn query_transmuter_lp_price<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    kind: ActionKind,
+    pool_liquidity: Vec<Coin>,
+    total_shares: Uint128,
+) -> ContractResult<Decimal> {
+    if total_shares.is_zero() {
+        return Err(ContractError::InvalidPrice {
+            reason: "transmuter pool has no shares".to_string(),
+        });
+    }
+
+    let mut pool_value = Uint256::zero();
+    for coin in pool_liquidity.into_iter().filter(|coin| !coin.amount.is_zero()) {
+        let price = query_coin_price(deps, env, config, price_sources, kind.clone(), &coin.denom)?;
+        let coin_value = Uint256::from_uint128(coin.amount) * Decimal256::from(price);
+        pool_value = pool_value.checked_add(coin_value)?;
+    }
+
+    Ok(Decimal::from_ratio(Uint128::try_from(pool_value)?, total_shares))
+}
+
+/// NOTE: Price sources must exist for both assets in the pool.
+#[allow(clippy::too_many_arguments)]
+pub fn query_pcl_lp_price<P: PriceSourceChecked<Empty>>(
+    deps: &Deps,
+    env: &Env,
+    config: &Config,
+    price_sources: &Map<&str, P>,
+    kind: ActionKind,
+    denoms: (&str, &str),
+    decimals: (u8, u8),
+    total_shares: Uint128,
+    price_scale: Decimal,
+    curve_invariant: Decimal256,
+) -> ContractResult<Decimal> {
+    let coin0_price = query_coin_price(deps, env, config, price_sources, kind.clone(), denoms.0)?;
+    let coin1_price = query_coin_price(deps, env, config, price_sources, kind, denoms.1)?;
+
+    compute_pcl_lp_price(
+        coin0_price,
+        coin1_price,
+        decimals.0,
+        decimals.1,
+        total_shares,
+        price_scale,
+        curve_invariant,
+    )
+}
@@ -1,24 +1,43 @@
 use std::{cmp::min, fmt};
 
+use astroport_v5::{
+    asset::AssetInfo,
+    pair::{ConfigResponse, PoolResponse, QueryMsg as PairQueryMsg},
+    pair_concentrated::{ConcentratedPoolParams, QueryMsg as ConcentratedPairQueryMsg},
+    querier::query_token_precision,
+};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Deps, Empty, Env, QuerierWrapper, StdResult};
+use cosmwasm_std::{
+    from_json, Addr, Decimal, Decimal256, Deps, Empty, Env, QuerierWrapper, StdResult,
+};
 use cw_storage_plus::Map;
 use mars_oracle_base::{
-    lp_pricing,
+    aggregate::{assert_aggregate, query_aggregated_price, AggregatedPrice, AggregationMethod},
+    exchange_rate::{
+        assert_max_rate_jump_per_day, assert_rate_growth, assert_rate_path, query_exchange_rate,
+        ExchangeRateQuery,
+    },
+    price_bounds::{apply_price_bounds, assert_price_bounds, BoundedPrice, PriceBounds},
+    price_details::PriceDetails,
+    pyth::query_pyth_price_details,
     redemption_rate::{assert_rr_not_too_old, query_redemption_rate, RedemptionRate},
+    vault_share::{
+        assert_vault_share, query_bounded_share_price, query_share_price, SharePriceBounds,
+    },
     ContractError::{self, InvalidPrice},
     ContractResult, PriceSourceChecked, PriceSourceUnchecked,
 };
 use mars_osmosis::helpers::{
-    query_arithmetic_twap_price, query_geometric_twap_price, query_pool, query_spot_price,
-    recovered_since_downtime_of_length, Pool,
+    query_arithmetic_twap_price, query_cosmwasm_pool_contract_addr, query_geometric_twap_price,
+    query_pool, query_spot_price, query_transmuter_total_pool_liquidity,
+    query_transmuter_total_shares, recovered_since_downtime_of_length, CommonPoolData, Pool,
 };
 use mars_types::oracle::{ActionKind, Config};
 use mars_utils::helpers::validate_native_denom;
 use osmosis_std::types::osmosis::downtimedetector::v1beta1::Downtime;
 use pyth_sdk_cw::PriceIdentifier;
 
-use crate::helpers;
+use crate::{helpers, lp_pricing};
 
 #[cw_serde]
 #[derive(Eq)]
@@ -89,6 +108,33 @@ pub enum OsmosisPriceSource<T> {
     XykLiquidityToken {
         pool_id: u64,
     },
+    /// Osmosis LP token (of a StableSwap pool with two assets) price quoted in OSMO.
+    ///
+    /// The pool is valued by its invariant, as if it was balanced and consisted of the cheaper
+    /// asset only, so the price can't be manipulated by swapping in the pool.
+    ///
+    /// NOTE: Price sources should be available for both pool assets in the Mars Oracle contract.
+    StableSwapLiquidityToken {
+        pool_id: u64,
+    },
+    /// Osmosis transmuter (CosmWasm pool) share price quoted in OSMO.
+    ///
+    /// The pool is valued by the oracle prices of the assets it holds. Transmuter pools swap 1:1,
+    /// so the value of the pool can't be inflated by swapping in it.
+    ///
+    /// NOTE: Price sources should be available for all pool assets in the Mars Oracle contract.
+    TransmuterLiquidityToken {
+        pool_id: u64,
+    },
+    /// Osmosis LP token (of an Astroport PCL pair deployed as a CosmWasm pool) price quoted in OSMO.
+    ///
+    /// The price is calculated with the same model as `PclLiquidityToken` of the wasm oracle, from
+    /// the curve invariant, the price scale and the oracle prices of the pool assets.
+    ///
+    /// NOTE: Price sources should be available for both pool assets in the Mars Oracle contract.
+    PclLiquidityToken {
+        pool_id: u64,
+    },
     /// Osmosis geometric twap price quoted in OSMO for staked asset.
     ///
     /// Equation to calculate the price:
@@ -181,6 +227,108 @@ pub enum OsmosisPriceSource<T> {
         /// Params to query redemption rate
         redemption_rate: RedemptionRate<T>,
     },
+    /// Liquid Staking Derivatives (LSD) price quoted in OSMO based on the redemption rate only.
+    /// Used for LSDs without a reliable stAsset/Asset pool.
+    ///
+    /// Equation to calculate the price:
+    /// stAsset/OSMO = stAsset/Asset Redemption Rate * Asset/OSMO
+    /// where:
+    /// - stAsset/Asset Redemption Rate comes from Stride.
+    /// - Asset/OSMO price comes from the Mars Oracle contract.
+    ///
+    /// The redemption rate may grow by at most `max_rate_jump_per_day` per day since the last
+    /// rate snapshot, otherwise the price is rejected. The snapshot is taken when the price source
+    /// is set and can be refreshed once per day with `ExecuteMsg::RefreshRateSnapshot`.
+    RedemptionRate {
+        /// Transitive denom for which we query price in OSMO. It refers to 'Asset' in the equation:
+        /// stAsset/OSMO = stAsset/Asset * Asset/OSMO
+        transitive_denom: String,
+
+        /// Params to query redemption rate
+        redemption_rate: RedemptionRate<T>,
+
+        /// Max relative growth of the redemption rate per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
+    /// Price quoted in OSMO of a token with an exchange rate to another denom, queried from a
+    /// contract (e.g. Drop dASSET, pSTAKE stkASSET or vault-like tokens).
+    ///
+    /// Equation to calculate the price:
+    /// token/OSMO = token/Asset Exchange Rate * Asset/OSMO
+    /// where:
+    /// - token/Asset Exchange Rate is read from the response of `exchange_rate.query_msg`.
+    /// - Asset/OSMO price comes from the Mars Oracle contract.
+    ///
+    /// The exchange rate may grow by at most `max_rate_jump_per_day` per day since the last
+    /// rate snapshot, otherwise the price is rejected. The snapshot is taken when the price source
+    /// is set and can be refreshed once per day with `ExecuteMsg::RefreshRateSnapshot`.
+    ExchangeRate {
+        /// Transitive denom for which we query price in OSMO. It refers to 'Asset' in the equation:
+        /// token/OSMO = token/Asset * Asset/OSMO
+        transitive_denom: String,
+
+        /// Params to query the exchange rate
+        exchange_rate: ExchangeRateQuery<T>,
+
+        /// Max relative growth of the exchange rate per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
+    /// cw-vault-standard vault share (e.g. Mars managed vault token `factory/{vault}/{subdenom}`)
+    /// price quoted in OSMO.
+    ///
+    /// Equation to calculate the price:
+    /// share/OSMO = share/base_token * base_token/OSMO
+    /// where:
+    /// - share/base_token is the vault share price (`ConvertToAssets` of the whole vault token supply).
+    /// - base_token/OSMO price comes from the Mars Oracle contract.
+    ///
+    /// The share price has to be within `share_price_bounds` and may grow by at most
+    /// `max_rate_jump_per_day` per day since the last rate snapshot, otherwise the price is rejected.
+    VaultShare {
+        /// Address of the vault issuing the share token
+        vault_address: T,
+
+        /// Base token of the vault. Its price source should be available in the Mars Oracle contract.
+        base_denom: String,
+
+        /// Sanity bounds for the share price
+        share_price_bounds: SharePriceBounds,
+
+        /// Max relative growth of the share price per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
+    /// Aggregated price of multiple price sources for the same asset, quoted in OSMO.
+    ///
+    /// The price is the median of the sub-source prices, or their weighted mean after rejecting
+    /// outliers (see `AggregationMethod`). Sub-sources failing to return a price are skipped, but
+    /// at least `min_sources` of them have to return a price.
+    ///
+    /// NOTE: Sub-sources are validated for the same denom and can't be aggregates themselves.
+    Aggregate {
+        /// Price sources to aggregate
+        sources: Vec<OsmosisPriceSource<T>>,
+
+        /// Minimum number of sub-sources required to return a price
+        min_sources: u32,
+
+        /// How the sub-source prices are aggregated
+        method: AggregationMethod,
+    },
+    /// Price of the wrapped price source, quoted in OSMO, clamped into `[floor, ceiling]`.
+    ///
+    /// Used for stablecoins pegged to 1 USD or tokens with a hard redemption floor, so that short
+    /// depegs on thin pools don't trigger liquidations. If `reject_outside` is set, the price is
+    /// rejected for `ActionKind::Default` when the raw price leaves the wider band.
+    ///
+    /// NOTE: The wrapped price source is validated for the same denom and can't be bounded itself.
+    /// Bounded price sources can't be aggregated, the aggregate price source can be bounded instead.
+    Bounded {
+        /// Price source to clamp
+        source: Box<OsmosisPriceSource<T>>,
+
+        /// Floor, ceiling and optional rejection band of the price
+        bounds: PriceBounds,
+    },
 }
 
 #[cw_serde]
@@ -273,6 +421,15 @@ impl fmt::Display for OsmosisPriceSourceChecked {
             OsmosisPriceSource::XykLiquidityToken {
                 pool_id,
             } => format!("xyk_liquidity_token:{pool_id}"),
+            OsmosisPriceSource::StableSwapLiquidityToken {
+                pool_id,
+            } => format!("stable_swap_liquidity_token:{pool_id}"),
+            OsmosisPriceSource::TransmuterLiquidityToken {
+                pool_id,
+            } => format!("transmuter_liquidity_token:{pool_id}"),
+            OsmosisPriceSource::PclLiquidityToken {
+                pool_id,
+            } => format!("pcl_liquidity_token:{pool_id}"),
             OsmosisPriceSource::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -310,6 +467,55 @@ impl fmt::Display for OsmosisPriceSourceChecked {
                 } = redemption_rate;
                 format!("lsd:{transitive_denom}:{pool_id}:{window_size}:{dd_fmt}:{kind}:{contract_addr}:{max_staleness}")
             }
+            OsmosisPriceSource::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                max_rate_jump_per_day,
+            } => {
+                let RedemptionRate {
+                    contract_addr,
+                    max_staleness,
+                } = redemption_rate;
+                format!("redemption_rate:{transitive_denom}:{contract_addr}:{max_staleness}:{max_rate_jump_per_day}")
+            }
+            OsmosisPriceSource::ExchangeRate {
+                transitive_denom,
+                exchange_rate,
+                max_rate_jump_per_day,
+            } => {
+                let ExchangeRateQuery {
+                    contract_addr,
+                    query_msg,
+                    rate_path,
+                } = exchange_rate;
+                format!("exchange_rate:{transitive_denom}:{contract_addr}:{query_msg}:{rate_path}:{max_rate_jump_per_day}")
+            }
+            OsmosisPriceSource::VaultShare {
+                vault_address,
+                base_denom,
+                share_price_bounds,
+                max_rate_jump_per_day,
+            } => {
+                let SharePriceBounds {
+                    min_share_price,
+                    max_share_price,
+                } = share_price_bounds;
+                format!(
+                    "vault_share:{vault_address}:{base_denom}:{min_share_price}:{max_share_price}:{max_rate_jump_per_day}"
+                )
+            }
+            OsmosisPriceSource::Aggregate {
+                sources,
+                min_sources,
+                method,
+            } => {
+                let sources = sources.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ");
+                format!("aggregate:{method}:{min_sources}:[{sources}]")
+            }
+            OsmosisPriceSource::Bounded {
+                source,
+                bounds,
+            } => format!("bounded:{bounds}:{source}"),
         };
         write!(f, "{label}")
     }
@@ -375,6 +581,41 @@ impl PriceSourceUnchecked<OsmosisPriceSourceChecked, Empty> for OsmosisPriceSour
                     pool_id: *pool_id,
                 })
             }
+            OsmosisPriceSourceUnchecked::StableSwapLiquidityToken {
+                pool_id,
+            } => {
+                let pool = query_pool(&deps.querier, *pool_id)?;
+                helpers::assert_osmosis_ss_lp_pool(&pool)?;
+                Ok(OsmosisPriceSourceChecked::StableSwapLiquidityToken {
+                    pool_id: *pool_id,
+                })
+            }
+            OsmosisPriceSourceUnchecked::TransmuterLiquidityToken {
+                pool_id,
+            } => {
+                let pool = query_pool(&deps.querier, *pool_id)?;
+                let contract_addr = helpers::query_cosmwasm_pool_contract(&deps.querier, &pool)?;
+                let pool_liquidity =
+                    query_transmuter_total_pool_liquidity(&deps.querier, &contract_addr)?;
+                if pool_liquidity.is_empty() {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: format!("transmuter pool {} has no assets", pool_id),
+                    });
+                }
+                Ok(OsmosisPriceSourceChecked::TransmuterLiquidityToken {
+                    pool_id: *pool_id,
+                })
+            }
+            OsmosisPriceSourceUnchecked::PclLiquidityToken {
+                pool_id,
+            } => {
+                let pool = query_pool(&deps.querier, *pool_id)?;
+                let contract_addr = helpers::query_cosmwasm_pool_contract(&deps.querier, &pool)?;
+                helpers::assert_astroport_pcl_pair(&deps.querier, &contract_addr)?;
+                Ok(OsmosisPriceSourceChecked::PclLiquidityToken {
+                    pool_id: *pool_id,
+                })
+            }
             OsmosisPriceSourceUnchecked::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -437,6 +678,139 @@ impl PriceSourceUnchecked<OsmosisPriceSourceChecked, Empty> for OsmosisPriceSour
                     },
                 })
             }
+            OsmosisPriceSourceUnchecked::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                max_rate_jump_per_day,
+            } => {
+                validate_native_denom(transitive_denom)?;
+                assert_max_rate_jump_per_day(*max_rate_jump_per_day)?;
+
+                Ok(OsmosisPriceSourceChecked::RedemptionRate {
+                    transitive_denom: transitive_denom.to_string(),
+                    redemption_rate: RedemptionRate {
+                        contract_addr: deps.api.addr_validate(&redemption_rate.contract_addr)?,
+                        max_staleness: redemption_rate.max_staleness,
+                    },
+                    max_rate_jump_per_day: *max_rate_jump_per_day,
+                })
+            }
+            OsmosisPriceSourceUnchecked::ExchangeRate {
+                transitive_denom,
+                exchange_rate,
+                max_rate_jump_per_day,
+            } => {
+                validate_native_denom(transitive_denom)?;
+                assert_rate_path(&exchange_rate.rate_path)?;
+                assert_max_rate_jump_per_day(*max_rate_jump_per_day)?;
+
+                Ok(OsmosisPriceSourceChecked::ExchangeRate {
+                    transitive_denom: transitive_denom.to_string(),
+                    exchange_rate: ExchangeRateQuery {
+                        contract_addr: deps.api.addr_validate(&exchange_rate.contract_addr)?,
+                        query_msg: exchange_rate.query_msg.clone(),
+                        rate_path: exchange_rate.rate_path.clone(),
+                    },
+                    max_rate_jump_per_day: *max_rate_jump_per_day,
+                })
+            }
+            OsmosisPriceSourceUnchecked::VaultShare {
+                vault_address,
+                base_denom: vault_base_denom,
+                share_price_bounds,
+                max_rate_jump_per_day,
+            } => {
+                validate_native_denom(vault_base_denom)?;
+
+                let vault_address = deps.api.addr_validate(vault_address)?;
+                assert_vault_share(
+                    &deps.querier,
+                    &vault_address,
+                    denom,
+                    vault_base_denom,
+                    share_price_bounds,
+                )?;
+                assert_max_rate_jump_per_day(*max_rate_jump_per_day)?;
+
+                Ok(OsmosisPriceSourceChecked::VaultShare {
+                    vault_address,
+                    base_denom: vault_base_denom.to_string(),
+                    share_price_bounds: share_price_bounds.clone(),
+                    max_rate_jump_per_day: *max_rate_jump_per_day,
+                })
+            }
+            OsmosisPriceSourceUnchecked::Aggregate {
+                sources,
+                min_sources,
+                method,
+            } => {
+                assert_aggregate(sources.len(), *min_sources, method)?;
+
+                let sources = sources
+                    .iter()
+                    .map(|source| {
+                        match source {
+                            OsmosisPriceSourceUnchecked::Aggregate {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "nested aggregate price sources are not supported"
+                                        .to_string(),
+                                });
+                            }
+                            OsmosisPriceSourceUnchecked::Bounded {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "bounded price sources can't be aggregated".to_string(),
+                                });
+                            }
+                            // Rate snapshots are taken per denom, only for the top level source
+                            OsmosisPriceSourceUnchecked::RedemptionRate {
+                                ..
+                            }
+                            | OsmosisPriceSourceUnchecked::ExchangeRate {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "rate based price sources can't be aggregated"
+                                        .to_string(),
+                                });
+                            }
+                            _ => {}
+                        }
+                        source.clone().validate(deps, denom, base_denom, price_sources)
+                    })
+                    .collect::<ContractResult<Vec<_>>>()?;
+
+                Ok(OsmosisPriceSourceChecked::Aggregate {
+                    sources,
+                    min_sources: *min_sources,
+                    method: method.clone(),
+                })
+            }
+            OsmosisPriceSourceUnchecked::Bounded {
+                source,
+                bounds,
+            } => {
+                assert_price_bounds(bounds)?;
+
+                if let OsmosisPriceSourceUnchecked::Bounded {
+                    ..
+                } = source.as_ref()
+                {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: "nested bounded price sources are not supported".to_string(),
+                    });
+                }
+                let source =
+                    source.as_ref().clone().validate(deps, denom, base_denom, price_sources)?;
+
+                Ok(OsmosisPriceSourceChecked::Bounded {
+                    source: Box::new(source),
+                    bounds: bounds.clone(),
+                })
+            }
         }
     }
 }
@@ -503,6 +877,36 @@ impl PriceSourceChecked<Empty> for OsmosisPriceSourceChecked {
                 price_sources,
                 kind,
             ),
+            OsmosisPriceSourceChecked::StableSwapLiquidityToken {
+                pool_id,
+            } => Self::query_ss_liquidity_token_price(
+                deps,
+                env,
+                *pool_id,
+                config,
+                price_sources,
+                kind,
+            ),
+            OsmosisPriceSourceChecked::TransmuterLiquidityToken {
+                pool_id,
+            } => Self::query_transmuter_liquidity_token_price(
+                deps,
+                env,
+                *pool_id,
+                config,
+                price_sources,
+                kind,
+            ),
+            OsmosisPriceSourceChecked::PclLiquidityToken {
+                pool_id,
+            } => Self::query_pcl_liquidity_token_price(
+                deps,
+                env,
+                *pool_id,
+                config,
+                price_sources,
+                kind,
+            ),
             OsmosisPriceSourceChecked::StakedGeometricTwap {
                 transitive_denom,
                 pool_id,
@@ -562,11 +966,336 @@ impl PriceSourceChecked<Empty> for OsmosisPriceSourceChecked {
                     kind,
                 )
             }
+            OsmosisPriceSourceChecked::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                max_rate_jump_per_day,
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                assert_rr_not_too_old(env.block.time.seconds(), &rr, redemption_rate)?;
+
+                Self::query_rate_based_price(
+                    deps,
+                    env,
+                    denom,
+                    rr.redemption_rate,
+                    *max_rate_jump_per_day,
+                    transitive_denom,
+                    config,
+                    price_sources,
+                    kind,
+                )
+            }
+            OsmosisPriceSourceChecked::ExchangeRate {
+                transitive_denom,
+                exchange_rate,
+                max_rate_jump_per_day,
+            } => {
+                let rate = query_exchange_rate(&deps.querier, exchange_rate)?;
+
+                Self::query_rate_based_price(
+                    deps,
+                    env,
+                    denom,
+                    rate,
+                    *max_rate_jump_per_day,
+                    transitive_denom,
+                    config,
+                    price_sources,
+                    kind,
+                )
+            }
+            OsmosisPriceSourceChecked::VaultShare {
+                vault_address,
+                base_denom,
+                share_price_bounds,
+                max_rate_jump_per_day,
+            } => Self::query_vault_share_price(
+                deps,
+                env,
+                denom,
+                vault_address,
+                base_denom,
+                share_price_bounds,
+                *max_rate_jump_per_day,
+                config,
+                price_sources,
+                kind,
+            ),
+            OsmosisPriceSourceChecked::Aggregate {
+                ..
+            } => self
+                .query_aggregated_price(deps, env, denom, config, price_sources, kind)
+                .map(|aggregated_price| aggregated_price.price),
+            OsmosisPriceSourceChecked::Bounded {
+                ..
+            } => self
+                .query_bounded_price(deps, env, denom, config, price_sources, kind)
+                .map(|bounded_price| bounded_price.price),
+        }
+    }
+
+    fn query_aggregated_price(
+        &self,
+        deps: &Deps<'_, Empty>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<AggregatedPrice> {
+        match self {
+            OsmosisPriceSourceChecked::Aggregate {
+                sources,
+                min_sources,
+                method,
+            } => query_aggregated_price(
+                deps,
+                env,
+                denom,
+                config,
+                price_sources,
+                kind,
+                sources,
+                *min_sources,
+                method,
+            ),
+            _ => Err(ContractError::InvalidPriceSource {
+                reason: format!("price source for {denom} is not an aggregate price source"),
+            }),
+        }
+    }
+
+    fn query_bounded_price(
+        &self,
+        deps: &Deps<'_, Empty>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<BoundedPrice> {
+        match self {
+            OsmosisPriceSourceChecked::Bounded {
+                source,
+                bounds,
+            } => {
+                let raw_price =
+                    source.query_price(deps, env, denom, config, price_sources, kind.clone())?;
+                apply_price_bounds(denom, raw_price, bounds, &kind)
+            }
+            _ => Err(ContractError::InvalidPriceSource {
+                reason: format!("price source for {denom} is not a bounded price source"),
+            }),
+        }
+    }
+
+    fn query_price_detailed(
+        &self,
+        deps: &Deps<'_, Empty>,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<PriceDetails> {
+        let price = self.query_price(deps, env, denom, config, price_sources, kind);
+        Ok(self.price_details(deps, denom)?.with_price_result(price))
+    }
+
+    fn query_exchange_rate(
+        &self,
+        deps: &Deps<'_, Empty>,
+        denom: &str,
+    ) -> ContractResult<Option<Decimal>> {
+        match self {
+            OsmosisPriceSourceChecked::RedemptionRate {
+                redemption_rate,
+                ..
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                Ok(Some(rr.redemption_rate))
+            }
+            OsmosisPriceSourceChecked::ExchangeRate {
+                exchange_rate,
+                ..
+            } => Ok(Some(query_exchange_rate(&deps.querier, exchange_rate)?)),
+            OsmosisPriceSourceChecked::VaultShare {
+                vault_address,
+                ..
+            } => Ok(Some(query_share_price(&deps.querier, vault_address)?)),
+            // Rate snapshot of the wrapped price source is taken for the bounded denom
+            OsmosisPriceSourceChecked::Bounded {
+                source,
+                ..
+            } => source.query_exchange_rate(deps, denom),
+            _ => Ok(None),
         }
     }
 }
 
 impl OsmosisPriceSourceChecked {
+    /// Metadata of the price source, also available if the price fails validation
+    fn price_details(&self, deps: &Deps, denom: &str) -> ContractResult<PriceDetails> {
+        let details = match self {
+            OsmosisPriceSourceChecked::Pyth {
+                contract_addr,
+                price_feed_id,
+                max_staleness,
+                ..
+            } => query_pyth_price_details(
+                deps,
+                contract_addr.clone(),
+                *price_feed_id,
+                *max_staleness,
+            )?,
+            OsmosisPriceSourceChecked::ArithmeticTwap {
+                window_size,
+                ..
+            }
+            | OsmosisPriceSourceChecked::GeometricTwap {
+                window_size,
+                ..
+            } => PriceDetails {
+                twap_window: Some(*window_size),
+                ..Default::default()
+            },
+            OsmosisPriceSourceChecked::StakedGeometricTwap {
+                transitive_denom,
+                window_size,
+                ..
+            } => PriceDetails {
+                twap_window: Some(*window_size),
+                transitive_denoms: vec![transitive_denom.clone()],
+                ..Default::default()
+            },
+            OsmosisPriceSourceChecked::Lsd {
+                transitive_denom,
+                twap,
+                redemption_rate,
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                PriceDetails {
+                    publish_time: Some(rr.update_time),
+                    max_staleness: Some(redemption_rate.max_staleness),
+                    twap_window: Some(twap.window_size),
+                    transitive_denoms: vec![transitive_denom.clone()],
+                    ..Default::default()
+                }
+            }
+            OsmosisPriceSourceChecked::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                ..
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                PriceDetails {
+                    publish_time: Some(rr.update_time),
+                    max_staleness: Some(redemption_rate.max_staleness),
+                    transitive_denoms: vec![transitive_denom.clone()],
+                    ..Default::default()
+                }
+            }
+            OsmosisPriceSourceChecked::ExchangeRate {
+                transitive_denom,
+                ..
+            } => PriceDetails {
+                transitive_denoms: vec![transitive_denom.clone()],
+                ..Default::default()
+            },
+            OsmosisPriceSourceChecked::VaultShare {
+                base_denom,
+                ..
+            } => PriceDetails {
+                transitive_denoms: vec![base_denom.clone()],
+                ..Default::default()
+            },
+            // The details of the wrapped price source
+            OsmosisPriceSourceChecked::Bounded {
+                source,
+                ..
+            } => source.price_details(deps, denom)?,
+            // The pool assets are priced with their own price sources
+            OsmosisPriceSourceChecked::XykLiquidityToken {
+                pool_id,
+            }
+            | OsmosisPriceSourceChecked::StableSwapLiquidityToken {
+                pool_id,
+            } => PriceDetails {
+                transitive_denoms: query_pool(&deps.querier, *pool_id)?.get_pool_denoms(),
+                ..Default::default()
+            },
+            OsmosisPriceSourceChecked::TransmuterLiquidityToken {
+                pool_id,
+            } => {
+                let contract_addr = query_cosmwasm_pool_contract_addr(&deps.querier, *pool_id)?;
+                let pool_liquidity =
+                    query_transmuter_total_pool_liquidity(&deps.querier, &contract_addr)?;
+                PriceDetails {
+                    transitive_denoms: pool_liquidity.into_iter().map(|coin| coin.denom).collect(),
+                    ..Default::default()
+                }
+            }
+            OsmosisPriceSourceChecked::PclLiquidityToken {
+                pool_id,
+            } => {
+                let contract_addr = query_cosmwasm_pool_contract_addr(&deps.querier, *pool_id)?;
+                let pool: PoolResponse =
+                    deps.querier.query_wasm_smart(&contract_addr, &PairQueryMsg::Pool {})?;
+                PriceDetails {
+                    transitive_denoms: pool
+                        .assets
+                        .iter()
+                        .map(|asset| Ok(asset.as_coin()?.denom))
+                        .collect::<ContractResult<_>>()?,
+                    ..Default::default()
+                }
+            }
+            // The denoms consulted by any of the sub-sources
+            OsmosisPriceSourceChecked::Aggregate {
+                sources,
+                ..
+            } => {
+                let mut transitive_denoms: Vec<String> = vec![];
+                for source in sources {
+                    for transitive_denom in source.price_details(deps, denom)?.transitive_denoms {
+                        if !transitive_denoms.contains(&transitive_denom) {
+                            transitive_denoms.push(transitive_denom);
+                        }
+                    }
+                }
+                PriceDetails {
+                    transitive_denoms,
+                    ..Default::default()
+                }
+            }
+            OsmosisPriceSourceChecked::Fixed {
+                ..
+            }
+            | OsmosisPriceSourceChecked::Spot {
+                ..
+            } => PriceDetails::default(),
+        };
+
+        Ok(details)
+    }
+
     fn chain_recovered(
         deps: &Deps,
         downtime_detector: &Option<DowntimeDetector>,
@@ -620,7 +1349,7 @@ impl OsmosisPriceSourceChecked {
         let coin1 = Pool::unwrap_coin(&pool.pool_assets[1].token)?;
         let total_shares = Pool::unwrap_coin(&pool.total_shares)?.amount;
 
-        lp_pricing::query_xyk_lp_price(
+        mars_oracle_base::lp_pricing::query_xyk_lp_price(
             deps,
             env,
             config,
@@ -632,6 +1361,120 @@ impl OsmosisPriceSourceChecked {
         )
     }
 
+    fn query_ss_liquidity_token_price(
+        deps: &Deps,
+        env: &Env,
+        pool_id: u64,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        // StableSwap pool asserted during price source creation
+        let pool = match query_pool(&deps.querier, pool_id)? {
+            Pool::StableSwap(pool) => pool,
+            _ => {
+                return Err(ContractError::InvalidPrice {
+                    reason: format!("expecting pool {pool_id} to be a StableSwap pool"),
+                })
+            }
+        };
+
+        let coin0 = Pool::unwrap_coin(&pool.pool_liquidity.first().cloned())?;
+        let coin1 = Pool::unwrap_coin(&pool.pool_liquidity.get(1).cloned())?;
+        let total_shares = Pool::unwrap_coin(&pool.total_shares)?.amount;
+
+        lp_pricing::query_stable_swap_lp_price(
+            deps,
+            env,
+            config,
+            price_sources,
+            kind,
+            coin0,
+            coin1,
+            (pool.scaling_factors[0], pool.scaling_factors[1]),
+            total_shares,
+        )
+    }
+
+    fn query_transmuter_liquidity_token_price(
+        deps: &Deps,
+        env: &Env,
+        pool_id: u64,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        let contract_addr = query_cosmwasm_pool_contract_addr(&deps.querier, pool_id)?;
+        let pool_liquidity = query_transmuter_total_pool_liquidity(&deps.querier, &contract_addr)?;
+        let total_shares = query_transmuter_total_shares(&deps.querier, &contract_addr)?;
+
+        lp_pricing::query_transmuter_lp_price(
+            deps,
+            env,
+            config,
+            price_sources,
+            kind,
+            pool_liquidity,
+            total_shares,
+        )
+    }
+
+    fn query_pcl_liquidity_token_price(
+        deps: &Deps,
+        env: &Env,
+        pool_id: u64,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        // PCL pair asserted during price source creation
+        let contract_addr = query_cosmwasm_pool_contract_addr(&deps.querier, pool_id)?;
+        let pool: PoolResponse =
+            deps.querier.query_wasm_smart(&contract_addr, &PairQueryMsg::Pool {})?;
+        let coin0 = pool.assets[0].as_coin()?;
+        let coin1 = pool.assets[1].as_coin()?;
+
+        let pool_config: ConfigResponse =
+            deps.querier.query_wasm_smart(&contract_addr, &PairQueryMsg::Config {})?;
+        let pool_params = match pool_config.params {
+            Some(params) => from_json::<ConcentratedPoolParams>(params)?,
+            None => return Err(ContractError::MissingAstroportPoolParams {}),
+        };
+
+        let curve_invariant: Decimal256 = deps
+            .querier
+            .query_wasm_smart(&contract_addr, &ConcentratedPairQueryMsg::ComputeD {})?;
+
+        // Token precisions are registered in the native coin registry of the Astroport factory
+        let coin0_decimals = query_token_precision(
+            &deps.querier,
+            &AssetInfo::NativeToken {
+                denom: coin0.denom.clone(),
+            },
+            &pool_config.factory_addr,
+        )?;
+        let coin1_decimals = query_token_precision(
+            &deps.querier,
+            &AssetInfo::NativeToken {
+                denom: coin1.denom.clone(),
+            },
+            &pool_config.factory_addr,
+        )?;
+
+        lp_pricing::query_pcl_lp_price(
+            deps,
+            env,
+            config,
+            price_sources,
+            kind,
+            (&coin0.denom, &coin1.denom),
+            (coin0_decimals, coin1_decimals),
+            pool.total_share,
+            pool_params.price_scale,
+            curve_invariant,
+        )
+    }
+
     /// Staked asset price quoted in OSMO.
     ///
     /// stAsset/OSMO = stAsset/Asset * Asset/OSMO
@@ -718,4 +1561,79 @@ impl OsmosisPriceSourceChecked {
 
         min_price.checked_mul(transitive_price).map_err(Into::into)
     }
+
+    /// Price of a rate based price source quoted in OSMO.
+    ///
+    /// token/OSMO = token/Asset rate * Asset/OSMO
+    #[allow(clippy::too_many_arguments)]
+    fn query_rate_based_price(
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        rate: Decimal,
+        max_rate_jump_per_day: Decimal,
+        transitive_denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, OsmosisPriceSourceChecked>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        assert_rate_growth(
+            deps.storage,
+            denom,
+            rate,
+            max_rate_jump_per_day,
+            env.block.time.seconds(),
+        )?;
+
+        // use current price source
+        let transitive_price = price_sources.load(deps.storage, transitive_denom)?.query_price(
+            deps,
+            env,
+            transitive_denom,
+            config,
+            price_sources,
+            kind,
+        )?;
+
+        rate.checked_mul(transitive_price).map_err(Into::into)
+    }
+
+    /// Vault share price quoted in OSMO.
+    ///
+    /// share/OSMO = share/base_token * base_token/OSMO
+    #[allow(clippy::too_many_arguments)]
+    fn query_vault_share_price(
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        vault_address: &Addr,
+        base_denom: &str,
+        share_price_bounds: &SharePriceBounds,
+        max_rate_jump_per_day: Decimal,
+        config: &Config,
+        price_sources: &Map<&str, OsmosisPriceSourceChecked>,
+        kind: ActionKind,
+    ) -> ContractResult<Decimal> {
+        let share_price =
+            query_bounded_share_price(&deps.querier, vault_address, share_price_bounds)?;
+        assert_rate_growth(
+            deps.storage,
+            denom,
+            share_price,
+            max_rate_jump_per_day,
+            env.block.time.seconds(),
+        )?;
+
+        // use current price source
+        let base_price = price_sources.load(deps.storage, base_denom)?.query_price(
+            deps,
+            env,
+            base_denom,
+            config,
+            price_sources,
+            kind,
+        )?;
+
+        share_price.checked_mul(base_price).map_err(Into::into)
+    }
 }
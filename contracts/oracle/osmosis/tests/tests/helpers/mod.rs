@@ -92,13 +92,27 @@ pub fn setup_test_with_pools() -> OwnedDeps<MockStorage, MockApi, MarsMockQuerie
 
     // Set StableSwap pool
     let assets = vec![coin(42069, "uatom"), coin(69420, "uosmo")];
-    deps.querier
-        .set_query_pool_response(5555, prepare_query_stable_swap_pool_response(5555, &assets));
+    deps.querier.set_query_pool_response(
+        5555,
+        prepare_query_stable_swap_pool_response(
+            5555,
+            &assets,
+            &[100000u64, 113890u64],
+            &coin(4497913440357232330148u128, "gamm/pool/5555"),
+        ),
+    );
 
     // Set StableSwap pool with more than 3 assets
     let assets = vec![coin(42069, "uatom"), coin(69420, "uosmo"), coin(69420, "uusdc")];
-    deps.querier
-        .set_query_pool_response(6666, prepare_query_stable_swap_pool_response(6666, &assets));
+    deps.querier.set_query_pool_response(
+        6666,
+        prepare_query_stable_swap_pool_response(
+            6666,
+            &assets,
+            &[100000u64, 113890u64, 100000u64],
+            &coin(4497913440357232330148u128, "gamm/pool/6666"),
+        ),
+    );
 
     // Set ConcentratedLiquidity pool
     deps.querier
@@ -194,7 +208,12 @@ fn prepare_pool_assets(coins: &[Coin], weights: &[u64]) -> Vec<PoolAsset> {
         .collect()
 }
 
-pub fn prepare_query_stable_swap_pool_response(pool_id: u64, assets: &[Coin]) -> PoolResponse {
+pub fn prepare_query_stable_swap_pool_response(
+    pool_id: u64,
+    assets: &[Coin],
+    scaling_factors: &[u64],
+    shares: &Coin,
+) -> PoolResponse {
     let pool_liquidity: Vec<_> = assets
         .iter()
         .map(|coin| osmosis_std::types::cosmos::base::v1beta1::Coin {
@@ -209,11 +228,11 @@ pub fn prepare_query_stable_swap_pool_response(pool_id: u64, assets: &[Coin]) ->
         pool_params: None,
         future_pool_governor: "".to_string(),
         total_shares: Some(osmosis_std::types::cosmos::base::v1beta1::Coin {
-            denom: format!("gamm/pool/{pool_id}"),
-            amount: 4497913440357232330148u128.to_string(),
+            denom: shares.denom.clone(),
+            amount: shares.amount.to_string(),
         }),
         pool_liquidity,
-        scaling_factors: vec![100000u64, 113890u64],
+        scaling_factors: scaling_factors.to_vec(),
         scaling_factor_controller: "osmo1k8c2m5cn322akk5wy8lpt87dd2f4yh9afcd7af".to_string(),
     };
     PoolResponse {
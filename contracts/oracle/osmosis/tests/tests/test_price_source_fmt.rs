@@ -1,5 +1,11 @@
-use cosmwasm_std::{Addr, Decimal};
-use mars_oracle_base::redemption_rate::RedemptionRate;
+use cosmwasm_std::{Addr, Binary, Decimal};
+use mars_oracle_base::{
+    aggregate::AggregationMethod,
+    exchange_rate::ExchangeRateQuery,
+    price_bounds::{PriceBand, PriceBounds},
+    redemption_rate::RedemptionRate,
+    vault_share::SharePriceBounds,
+};
 use mars_oracle_osmosis::{DowntimeDetector, OsmosisPriceSourceChecked, Twap, TwapKind};
 use osmosis_std::types::osmosis::downtimedetector::v1beta1::Downtime;
 use pyth_sdk_cw::PriceIdentifier;
@@ -99,6 +105,30 @@ fn display_xyk_lp_price_source() {
     assert_eq!(ps.to_string(), "xyk_liquidity_token:224")
 }
 
+#[test]
+fn display_stable_swap_lp_price_source() {
+    let ps = OsmosisPriceSourceChecked::StableSwapLiquidityToken {
+        pool_id: 1361,
+    };
+    assert_eq!(ps.to_string(), "stable_swap_liquidity_token:1361")
+}
+
+#[test]
+fn display_transmuter_lp_price_source() {
+    let ps = OsmosisPriceSourceChecked::TransmuterLiquidityToken {
+        pool_id: 1212,
+    };
+    assert_eq!(ps.to_string(), "transmuter_liquidity_token:1212")
+}
+
+#[test]
+fn display_pcl_lp_price_source() {
+    let ps = OsmosisPriceSourceChecked::PclLiquidityToken {
+        pool_id: 1610,
+    };
+    assert_eq!(ps.to_string(), "pcl_liquidity_token:1610")
+}
+
 #[test]
 fn display_pyth_price_source() {
     let ps = OsmosisPriceSourceChecked::Pyth {
@@ -174,3 +204,111 @@ fn display_lsd_price_source() {
     };
     assert_eq!(ps.to_string(), "lsd:transitive:456:380:Some(Duration30m:552):geometric_twap:osmo1zw4fxj4pt0pu0jdd7cs6gecdj3pvfxhhtgkm4w2y44jp60hywzvssud6uc:1234");
 }
+
+#[test]
+fn display_redemption_rate_price_source() {
+    let ps = OsmosisPriceSourceChecked::RedemptionRate {
+        transitive_denom: "transitive".to_string(),
+        redemption_rate: RedemptionRate {
+            contract_addr: Addr::unchecked("redemption_addr"),
+            max_staleness: 1234,
+        },
+        max_rate_jump_per_day: Decimal::percent(1),
+    };
+    assert_eq!(ps.to_string(), "redemption_rate:transitive:redemption_addr:1234:0.01")
+}
+
+#[test]
+fn display_exchange_rate_price_source() {
+    let ps = OsmosisPriceSourceChecked::ExchangeRate {
+        transitive_denom: "transitive".to_string(),
+        exchange_rate: ExchangeRateQuery {
+            contract_addr: Addr::unchecked("drop_core"),
+            query_msg: Binary::from(br#"{"exchange_rate":{}}"#),
+            rate_path: "".to_string(),
+        },
+        max_rate_jump_per_day: Decimal::percent(1),
+    };
+    assert_eq!(
+        ps.to_string(),
+        "exchange_rate:transitive:drop_core:eyJleGNoYW5nZV9yYXRlIjp7fX0=::0.01"
+    )
+}
+
+#[test]
+fn display_vault_share_price_source() {
+    let ps = OsmosisPriceSourceChecked::VaultShare {
+        vault_address: Addr::unchecked("vault_contract"),
+        base_denom: "uusdc".to_string(),
+        share_price_bounds: SharePriceBounds {
+            min_share_price: Decimal::percent(90),
+            max_share_price: Decimal::percent(250),
+        },
+        max_rate_jump_per_day: Decimal::percent(2),
+    };
+    assert_eq!(ps.to_string(), "vault_share:vault_contract:uusdc:0.9:2.5:0.02")
+}
+
+#[test]
+fn display_aggregate_price_source() {
+    let ps = OsmosisPriceSourceChecked::Aggregate {
+        sources: vec![
+            OsmosisPriceSourceChecked::Fixed {
+                price: Decimal::from_ratio(1u128, 2u128),
+            },
+            OsmosisPriceSourceChecked::Spot {
+                pool_id: 123,
+            },
+        ],
+        min_sources: 2,
+        method: AggregationMethod::Median {},
+    };
+    assert_eq!(ps.to_string(), "aggregate:median:2:[fixed:0.5, spot:123]");
+
+    let ps = OsmosisPriceSourceChecked::Aggregate {
+        sources: vec![
+            OsmosisPriceSourceChecked::Fixed {
+                price: Decimal::from_ratio(1u128, 2u128),
+            },
+            OsmosisPriceSourceChecked::Spot {
+                pool_id: 123,
+            },
+        ],
+        min_sources: 1,
+        method: AggregationMethod::WeightedMean {
+            weights: vec![Decimal::one(), Decimal::percent(200)],
+            max_deviation: Decimal::percent(5),
+        },
+    };
+    assert_eq!(ps.to_string(), "aggregate:weighted_mean:[1,2]:0.05:1:[fixed:0.5, spot:123]");
+}
+
+#[test]
+fn display_bounded_price_source() {
+    let ps = OsmosisPriceSourceChecked::Bounded {
+        source: Box::new(OsmosisPriceSourceChecked::Spot {
+            pool_id: 123,
+        }),
+        bounds: PriceBounds {
+            floor: Decimal::percent(98),
+            ceiling: Decimal::percent(102),
+            reject_outside: None,
+        },
+    };
+    assert_eq!(ps.to_string(), "bounded:0.98:1.02:None:spot:123");
+
+    let ps = OsmosisPriceSourceChecked::Bounded {
+        source: Box::new(OsmosisPriceSourceChecked::Spot {
+            pool_id: 123,
+        }),
+        bounds: PriceBounds {
+            floor: Decimal::percent(98),
+            ceiling: Decimal::percent(102),
+            reject_outside: Some(PriceBand {
+                min: Decimal::percent(90),
+                max: Decimal::percent(110),
+            }),
+        },
+    };
+    assert_eq!(ps.to_string(), "bounded:0.98:1.02:Some(0.9:1.1):spot:123");
+}
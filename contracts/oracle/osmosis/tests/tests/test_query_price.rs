@@ -3,16 +3,31 @@ use std::str::FromStr;
 use cosmwasm_std::{
     coin, from_json,
     testing::{mock_env, MockApi, MockStorage},
-    Decimal, OwnedDeps, StdError,
+    Binary, Decimal, OwnedDeps, StdError, Uint128,
 };
 use helpers::prepare_query_balancer_pool_response;
 use ica_oracle::msg::RedemptionRateResponse;
-use mars_oracle_base::{pyth::scale_pyth_price, redemption_rate::RedemptionRate, ContractError};
+use mars_oracle_base::{
+    aggregate::AggregationMethod,
+    exchange_rate::{ExchangeRateQuery, RateSnapshot, RATE_SNAPSHOTS},
+    price_bounds::{PriceBand, PriceBounds},
+    pyth::scale_pyth_price,
+    redemption_rate::RedemptionRate,
+    vault_share::SharePriceBounds,
+    ContractError,
+};
 use mars_oracle_osmosis::{
-    contract::entry, DowntimeDetector, OsmosisPriceSourceUnchecked, Twap, TwapKind,
+    contract::entry, lp_pricing::compute_ss_lp_price, msg::ExecuteMsg, DowntimeDetector,
+    OsmosisPriceSourceUnchecked, Twap, TwapKind,
+};
+use mars_osmosis::helpers::{
+    GetTotalPoolLiquidityResponse, GetTotalSharesResponse, TransmuterQueryMsg,
+};
+use mars_testing::{mock_env_at_block_time, mock_info, vault_querier::MockVault, MarsMockQuerier};
+use mars_types::oracle::{
+    ActionKind, AggregatedPriceResponse, BoundedPriceResponse, PriceDetailedResponse,
+    PriceResponse, QueryMsg,
 };
-use mars_testing::{mock_env_at_block_time, MarsMockQuerier};
-use mars_types::oracle::{PriceResponse, QueryMsg};
 use osmosis_std::types::osmosis::{
     downtimedetector::v1beta1::Downtime,
     poolmanager::v1beta1::SpotPriceResponse,
@@ -1004,75 +1019,1316 @@ fn querying_xyk_lp_price() {
     //
     // Is slightly (<0.01%) off from the pre-manipulation value.
     assert_eq!(res.price, Decimal::from_ratio(1769874_u128, 10000_u128));
+
+    // the pool assets are priced with their own price sources
+    let res: PriceDetailedResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceDetailed {
+            denom: "uatom_umars_lp".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.transitive_denoms, vec!["uatom".to_string(), "umars".to_string()]);
 }
 
 #[test]
-fn querying_all_prices() {
+fn querying_vault_share_price() {
+    let mut deps = setup_vault_share(Uint128::new(1_100_000), Decimal::percent(50));
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "factory/vault_contract/vault".to_string(),
+            kind: None,
+        },
+    );
+    // share price 1.1 * uusdc price 0.5
+    assert_eq!(res.price, Decimal::from_ratio(55u128, 100u128));
+
+    // share price is recalculated on every query
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets: Uint128::new(1_500_000),
+        },
+    );
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "factory/vault_contract/vault".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_ratio(75u128, 100u128));
+}
+
+#[test]
+fn querying_vault_share_price_outside_of_bounds() {
+    let mut deps = setup_vault_share(Uint128::new(1_100_000), Decimal::percent(50));
+
+    // share price jumps above max share price
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets: Uint128::new(2_500_000),
+        },
+    );
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "factory/vault_contract/vault".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: "share price 2.5 of vault vault_contract is outside of bounds [1, 2]"
+                .to_string()
+        }
+    );
+
+    // share price drops below min share price
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets: Uint128::new(900_000),
+        },
+    );
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "factory/vault_contract/vault".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: "share price 0.9 of vault vault_contract is outside of bounds [1, 2]"
+                .to_string()
+        }
+    );
+}
+
+#[test]
+fn querying_vault_share_price_growing_too_fast() {
+    let mut deps = setup_vault_share(Uint128::new(1_100_000), Decimal::percent(1));
+    let set_time = mock_env().block.time.seconds();
+
+    let set_share_price = |deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>,
+                           total_assets: u128| {
+        deps.querier.set_vault(
+            "vault_contract",
+            MockVault {
+                base_token: "uusdc".to_string(),
+                vault_token: "factory/vault_contract/vault".to_string(),
+                total_vault_tokens: Uint128::new(1_000_000),
+                total_assets: Uint128::new(total_assets),
+            },
+        );
+    };
+    let query_price = |deps: &OwnedDeps<MockStorage, MockApi, MarsMockQuerier>, time: u64| {
+        entry::query(
+            deps.as_ref(),
+            mock_env_at_block_time(time),
+            QueryMsg::Price {
+                denom: "factory/vault_contract/vault".to_string(),
+                kind: None,
+            },
+        )
+    };
+
+    // share price jumps by ~1.8% within the same day, e.g. because of a donation to the vault
+    set_share_price(&mut deps, 1_120_000);
+    let res_err = query_price(&deps, set_time + 3600).unwrap_err();
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: format!(
+                "exchange rate 1.12 grew too fast. rate at {set_time}: 1.1, max rate: 1.111"
+            )
+        }
+    );
+
+    // the same growth is accepted over two days
+    let res: PriceResponse =
+        from_json(query_price(&deps, set_time + 86400 + 3600).unwrap()).unwrap();
+    assert_eq!(res.price, Decimal::from_ratio(56u128, 100u128));
+}
+
+fn setup_vault_share(
+    total_assets: Uint128,
+    max_rate_jump_per_day: Decimal,
+) -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
     let mut deps = helpers::setup_test_with_pools();
 
-    helpers::set_price_source(
-        deps.as_mut(),
-        "uosmo",
-        OsmosisPriceSourceUnchecked::Fixed {
-            price: Decimal::one(),
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets,
         },
     );
+
     helpers::set_price_source(
         deps.as_mut(),
-        "uatom",
-        OsmosisPriceSourceUnchecked::Spot {
-            pool_id: 1,
+        "uusdc",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(50),
         },
     );
     helpers::set_price_source(
         deps.as_mut(),
-        "umars",
-        OsmosisPriceSourceUnchecked::Spot {
-            pool_id: 89,
+        "factory/vault_contract/vault",
+        OsmosisPriceSourceUnchecked::VaultShare {
+            vault_address: "vault_contract".to_string(),
+            base_denom: "uusdc".to_string(),
+            share_price_bounds: SharePriceBounds {
+                min_share_price: Decimal::one(),
+                max_share_price: Decimal::percent(200),
+            },
+            max_rate_jump_per_day,
         },
     );
 
+    deps
+}
+
+#[test]
+fn querying_aggregated_median_price() {
+    let mut deps = helpers::setup_test_with_pools();
+    set_aggregate_price_source(&mut deps, 2, AggregationMethod::Median {});
+
     deps.querier.set_spot_price(
-        1,
-        "uatom",
+        89,
+        "umars",
         "uosmo",
         SpotPriceResponse {
-            spot_price: Decimal::from_ratio(77777u128, 12345u128).to_string(),
+            spot_price: "8".to_string(),
+        },
+    );
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("8").unwrap());
+
+    let res: AggregatedPriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::AggregatedPrice {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res,
+        AggregatedPriceResponse {
+            denom: "umars".to_string(),
+            price: Decimal::from_str("8").unwrap(),
+            sources_used: 3,
+            sources_total: 3,
+        }
+    );
+}
+
+#[test]
+fn querying_aggregated_price_skips_failing_sources() {
+    let mut deps = helpers::setup_test_with_pools();
+    set_aggregate_price_source(&mut deps, 2, AggregationMethod::Median {});
+
+    // spot price not available, median of the fixed prices
+    let res: AggregatedPriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::AggregatedPrice {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("7.5").unwrap());
+    assert_eq!(res.sources_used, 2);
+    assert_eq!(res.sources_total, 3);
+
+    // not enough sub-sources returned a price
+    set_aggregate_price_source(&mut deps, 3, AggregationMethod::Median {});
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: "only 2 of 3 sub-sources returned a price, at least 3 required".to_string()
+        }
+    );
+
+    // the detailed price reports why the price was rejected
+    let res: PriceDetailedResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceDetailed {
+            denom: "umars".to_string(),
+            kind: None,
         },
     );
+    assert_eq!(res.price, None);
+    assert_eq!(
+        res.error,
+        Some(
+            "Invalid price: only 2 of 3 sub-sources returned a price, at least 3 required"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn querying_aggregated_weighted_mean_price_rejects_outliers() {
+    let mut deps = helpers::setup_test_with_pools();
+    let method = AggregationMethod::WeightedMean {
+        weights: vec![Decimal::one(), Decimal::one(), Decimal::percent(200)],
+        max_deviation: Decimal::percent(15),
+    };
+    set_aggregate_price_source(&mut deps, 2, method.clone());
+
+    // spot price deviates too much from the median (8) and is rejected
     deps.querier.set_spot_price(
         89,
         "umars",
         "uosmo",
         SpotPriceResponse {
-            spot_price: Decimal::from_ratio(88888u128, 12345u128).to_string(),
+            spot_price: "16".to_string(),
         },
     );
 
-    // NOTE: responses are ordered alphabetically by denom
-    let res: Vec<PriceResponse> = helpers::query(
+    let res: AggregatedPriceResponse = helpers::query(
         deps.as_ref(),
-        QueryMsg::Prices {
-            start_after: None,
-            limit: None,
+        QueryMsg::AggregatedPrice {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    // (7 * 1 + 8 * 2) / 3
+    assert_eq!(res.price, Decimal::from_ratio(23u128, 3u128));
+    assert_eq!(res.sources_used, 2);
+    assert_eq!(res.sources_total, 3);
+
+    // not enough sub-sources within max deviation
+    set_aggregate_price_source(&mut deps, 3, method);
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: "only 2 of 3 sub-sources are within max deviation of the median, at least 3 required".to_string()
+        }
+    );
+}
+
+#[test]
+fn querying_aggregated_price_of_non_aggregate_price_source() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::AggregatedPrice {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPriceSource {
+            reason: "price source for umars is not an aggregate price source".to_string()
+        }
+    );
+}
+
+/// Sets an aggregate price source for umars with a spot sub-source (pool 89) and two fixed
+/// sub-sources (7 and 8 uosmo)
+fn set_aggregate_price_source(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>,
+    min_sources: u32,
+    method: AggregationMethod,
+) {
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Aggregate {
+            sources: vec![
+                OsmosisPriceSourceUnchecked::Spot {
+                    pool_id: 89,
+                },
+                OsmosisPriceSourceUnchecked::Fixed {
+                    price: Decimal::from_str("7").unwrap(),
+                },
+                OsmosisPriceSourceUnchecked::Fixed {
+                    price: Decimal::from_str("8").unwrap(),
+                },
+            ],
+            min_sources,
+            method,
+        },
+    );
+}
+
+#[test]
+fn querying_bounded_price() {
+    let mut deps = helpers::setup_test_with_pools();
+    set_bounded_price_source(&mut deps, None);
+
+    // within bounds
+    set_spot_price(&mut deps, "1.01");
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("1.01").unwrap());
+
+    // above ceiling
+    set_spot_price(&mut deps, "1.5");
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::percent(102));
+
+    // below floor
+    set_spot_price(&mut deps, "0.5");
+    let res: BoundedPriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::BoundedPrice {
+            denom: "umars".to_string(),
             kind: None,
         },
     );
     assert_eq!(
         res,
-        vec![
-            PriceResponse {
-                denom: "uatom".to_string(),
-                price: Decimal::from_ratio(77777u128, 12345u128),
-            },
-            PriceResponse {
+        BoundedPriceResponse {
+            denom: "umars".to_string(),
+            price: Decimal::percent(98),
+            raw_price: Decimal::percent(50),
+        }
+    );
+}
+
+#[test]
+fn querying_bounded_price_outside_of_reject_band() {
+    let mut deps = helpers::setup_test_with_pools();
+    set_bounded_price_source(
+        &mut deps,
+        Some(PriceBand {
+            min: Decimal::percent(90),
+            max: Decimal::percent(110),
+        }),
+    );
+
+    // between ceiling and max of the band, clamped for all action kinds
+    set_spot_price(&mut deps, "1.05");
+    for kind in [ActionKind::Default, ActionKind::Liquidation] {
+        let res: PriceResponse = helpers::query(
+            deps.as_ref(),
+            QueryMsg::Price {
                 denom: "umars".to_string(),
-                price: Decimal::from_ratio(88888u128, 12345u128),
-            },
-            PriceResponse {
-                denom: "uosmo".to_string(),
-                price: Decimal::one(),
+                kind: Some(kind),
             },
-        ]
+        );
+        assert_eq!(res.price, Decimal::percent(102));
+    }
+
+    // below min of the band, rejected for default actions
+    set_spot_price(&mut deps, "0.8");
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: "price 0.8 of umars is outside of the allowed band [0.9, 1.1]".to_string()
+        }
+    );
+
+    // liquidations keep using the clamped price
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "umars".to_string(),
+            kind: Some(ActionKind::Liquidation),
+        },
+    );
+    assert_eq!(res.price, Decimal::percent(98));
+}
+
+#[test]
+fn querying_bounded_price_of_non_bounded_price_source() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::BoundedPrice {
+            denom: "umars".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPriceSource {
+            reason: "price source for umars is not a bounded price source".to_string()
+        }
+    );
+}
+
+/// Sets a bounded price source for umars wrapping the spot price of pool 89, clamped into
+/// [0.98, 1.02] uosmo
+fn set_bounded_price_source(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>,
+    reject_outside: Option<PriceBand>,
+) {
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Bounded {
+            source: Box::new(OsmosisPriceSourceUnchecked::Spot {
+                pool_id: 89,
+            }),
+            bounds: PriceBounds {
+                floor: Decimal::percent(98),
+                ceiling: Decimal::percent(102),
+                reject_outside,
+            },
+        },
+    );
+}
+
+fn set_spot_price(deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>, price: &str) {
+    deps.querier.set_spot_price(
+        89,
+        "umars",
+        "uosmo",
+        SpotPriceResponse {
+            spot_price: price.to_string(),
+        },
+    );
+}
+
+#[test]
+fn querying_all_prices() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uosmo",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Spot {
+            pool_id: 1,
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Spot {
+            pool_id: 89,
+        },
+    );
+
+    deps.querier.set_spot_price(
+        1,
+        "uatom",
+        "uosmo",
+        SpotPriceResponse {
+            spot_price: Decimal::from_ratio(77777u128, 12345u128).to_string(),
+        },
+    );
+    deps.querier.set_spot_price(
+        89,
+        "umars",
+        "uosmo",
+        SpotPriceResponse {
+            spot_price: Decimal::from_ratio(88888u128, 12345u128).to_string(),
+        },
+    );
+
+    // NOTE: responses are ordered alphabetically by denom
+    let res: Vec<PriceResponse> = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Prices {
+            start_after: None,
+            limit: None,
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res,
+        vec![
+            PriceResponse {
+                denom: "uatom".to_string(),
+                price: Decimal::from_ratio(77777u128, 12345u128),
+            },
+            PriceResponse {
+                denom: "umars".to_string(),
+                price: Decimal::from_ratio(88888u128, 12345u128),
+            },
+            PriceResponse {
+                denom: "uosmo".to_string(),
+                price: Decimal::one(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn querying_all_prices_detailed() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uosmo",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "umars",
+        OsmosisPriceSourceUnchecked::Bounded {
+            source: Box::new(OsmosisPriceSourceUnchecked::GeometricTwap {
+                pool_id: 89,
+                window_size: 86400,
+                downtime_detector: None,
+            }),
+            bounds: PriceBounds {
+                floor: Decimal::percent(50),
+                ceiling: Decimal::from_ratio(5u128, 1u128),
+                reject_outside: None,
+            },
+        },
+    );
+    deps.querier.set_geometric_twap_price(
+        89,
+        "umars",
+        "uosmo",
+        GeometricTwapToNowResponse {
+            geometric_twap: Decimal::from_ratio(66666u128, 12345u128).to_string(),
+        },
+    );
+
+    let res: Vec<PriceDetailedResponse> = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PricesDetailed {
+            start_after: None,
+            limit: None,
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res,
+        vec![
+            PriceDetailedResponse {
+                denom: "umars".to_string(),
+                price: Some(Decimal::from_ratio(5u128, 1u128)),
+                error: None,
+                price_source: "bounded:0.5:5:None:geometric_twap:89:86400:None".to_string(),
+                publish_time: None,
+                age: None,
+                max_staleness: None,
+                confidence: None,
+                ema_deviation: None,
+                twap_window: Some(86400),
+                transitive_denoms: vec![],
+            },
+            PriceDetailedResponse {
+                denom: "uosmo".to_string(),
+                price: Some(Decimal::one()),
+                error: None,
+                price_source: "fixed:1".to_string(),
+                publish_time: None,
+                age: None,
+                max_staleness: None,
+                confidence: None,
+                ema_deviation: None,
+                twap_window: None,
+                transitive_denoms: vec![],
+            },
+        ]
+    );
+}
+
+#[test]
+fn querying_redemption_rate_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(12u128, 1u128),
+        },
+    );
+
+    // rate snapshot is taken at the block time of `mock_env()`
+    let set_time = mock_env().block.time.seconds();
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::from_str("1.1").unwrap(),
+            update_time: set_time,
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "ustatom",
+        OsmosisPriceSourceUnchecked::RedemptionRate {
+            transitive_denom: "uatom".to_string(),
+            redemption_rate: RedemptionRate {
+                contract_addr: "dummy_addr".to_string(),
+                max_staleness: 21600,
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        },
+    );
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("13.2").unwrap());
+
+    // rate grew by 2.5% after 2 days, 3% are allowed (two full days plus the started one)
+    let query_time = set_time + 2 * 86400;
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::from_str("1.12750").unwrap(),
+            update_time: query_time,
+        },
+    );
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(query_time),
+        QueryMsg::Price {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap();
+    let res: PriceResponse = from_json(res).unwrap();
+    assert_eq!(res.price, Decimal::from_str("13.53").unwrap());
+
+    // rate decrease (e.g. slashing) is accepted
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::one(),
+            update_time: query_time,
+        },
+    );
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(query_time),
+        QueryMsg::Price {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap();
+    let res: PriceResponse = from_json(res).unwrap();
+    assert_eq!(res.price, Decimal::from_ratio(12u128, 1u128));
+}
+
+#[test]
+fn querying_redemption_rate_price_detailed() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(12u128, 1u128),
+        },
+    );
+
+    let current_time = mock_env().block.time.seconds();
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::from_str("1.1").unwrap(),
+            update_time: current_time - 600,
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "ustatom",
+        OsmosisPriceSourceUnchecked::RedemptionRate {
+            transitive_denom: "uatom".to_string(),
+            redemption_rate: RedemptionRate {
+                contract_addr: "dummy_addr".to_string(),
+                max_staleness: 21600,
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        },
+    );
+
+    let res: PriceDetailedResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceDetailed {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res,
+        PriceDetailedResponse {
+            denom: "ustatom".to_string(),
+            price: Some(Decimal::from_str("13.2").unwrap()),
+            error: None,
+            price_source: "redemption_rate:uatom:dummy_addr:21600:0.01".to_string(),
+            publish_time: Some(current_time - 600),
+            age: Some(600),
+            max_staleness: Some(21600),
+            confidence: None,
+            ema_deviation: None,
+            twap_window: None,
+            transitive_denoms: vec!["uatom".to_string()],
+        }
+    );
+
+    // stale redemption rate, the price is rejected but the metadata is still returned
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::from_str("1.1").unwrap(),
+            update_time: current_time - 21601,
+        },
+    );
+    let res: PriceDetailedResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceDetailed {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res,
+        PriceDetailedResponse {
+            denom: "ustatom".to_string(),
+            price: None,
+            error: Some(format!(
+                "Invalid price: redemption rate update time is too old/stale. last updated: {}, now: {}",
+                current_time - 21601,
+                current_time
+            )),
+            price_source: "redemption_rate:uatom:dummy_addr:21600:0.01".to_string(),
+            publish_time: Some(current_time - 21601),
+            age: Some(21601),
+            max_staleness: Some(21600),
+            confidence: None,
+            ema_deviation: None,
+            twap_window: None,
+            transitive_denoms: vec!["uatom".to_string()],
+        }
+    );
+}
+
+#[test]
+fn querying_redemption_rate_price_if_rate_grew_too_fast() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(12u128, 1u128),
+        },
+    );
+
+    let set_time = mock_env().block.time.seconds();
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::one(),
+            update_time: set_time,
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "ustatom",
+        OsmosisPriceSourceUnchecked::RedemptionRate {
+            transitive_denom: "uatom".to_string(),
+            redemption_rate: RedemptionRate {
+                contract_addr: "dummy_addr".to_string(),
+                max_staleness: 21600,
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        },
+    );
+
+    // rate grew by 5% within the same day
+    let query_time = set_time + 3600;
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::from_str("1.05").unwrap(),
+            update_time: query_time,
+        },
+    );
+    let res_err = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(query_time),
+        QueryMsg::Price {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: format!(
+                "exchange rate 1.05 grew too fast. rate at {set_time}: 1, max rate: 1.01"
+            )
+        }
+    );
+
+    // redemption rate is too old
+    let res_err = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(query_time + 21600 + 1),
+        QueryMsg::Price {
+            denom: "ustatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(res_err, ContractError::InvalidPrice { .. }));
+}
+
+#[test]
+fn querying_exchange_rate_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(12u128, 1u128),
+        },
+    );
+
+    deps.querier.set_contract_response(
+        "drop_core",
+        Binary::from(br#"{"state":{"exchange_rate":"1.05","bonded":"1000"},"other":[1,-2,null]}"#),
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "udatom",
+        OsmosisPriceSourceUnchecked::ExchangeRate {
+            transitive_denom: "uatom".to_string(),
+            exchange_rate: ExchangeRateQuery {
+                contract_addr: "drop_core".to_string(),
+                query_msg: Binary::from(br#"{"state":{}}"#),
+                rate_path: "state.exchange_rate".to_string(),
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        },
+    );
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "udatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_str("12.6").unwrap());
+
+    // rate grew by 2% within the same day
+    deps.querier.set_contract_response(
+        "drop_core",
+        Binary::from(br#"{"state":{"exchange_rate":"1.071","bonded":"1000"},"other":[]}"#),
+    );
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "udatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: format!(
+                "exchange rate 1.071 grew too fast. rate at {}: 1.05, max rate: 1.0605",
+                mock_env().block.time.seconds()
+            )
+        }
+    );
+
+    // rate is not a decimal string
+    deps.querier
+        .set_contract_response("drop_core", Binary::from(br#"{"state":{"exchange_rate":1}}"#));
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "udatom".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason:
+                "exchange rate at path state.exchange_rate of drop_core is not a decimal string"
+                    .to_string()
+        }
+    );
+}
+
+#[test]
+fn refreshing_rate_snapshot_keeps_growth_bound_per_day() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::from_ratio(12u128, 1u128),
+        },
+    );
+
+    let set_rate = |deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>, rate: &str| {
+        deps.querier.set_contract_response(
+            "drop_core",
+            Binary::from(format!(r#"{{"state":{{"exchange_rate":"{rate}"}}}}"#).into_bytes()),
+        );
+    };
+    let refresh =
+        |deps: &mut OwnedDeps<MockStorage, MockApi, MarsMockQuerier>, denom: &str, time: u64| {
+            entry::execute(
+                deps.as_mut(),
+                mock_env_at_block_time(time),
+                mock_info("anyone"),
+                ExecuteMsg::RefreshRateSnapshot {
+                    denom: denom.to_string(),
+                },
+            )
+        };
+
+    set_rate(&mut deps, "1");
+    helpers::set_price_source(
+        deps.as_mut(),
+        "udatom",
+        OsmosisPriceSourceUnchecked::ExchangeRate {
+            transitive_denom: "uatom".to_string(),
+            exchange_rate: ExchangeRateQuery {
+                contract_addr: "drop_core".to_string(),
+                query_msg: Binary::from(br#"{"state":{}}"#),
+                rate_path: "state.exchange_rate".to_string(),
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        },
+    );
+    let set_time = mock_env().block.time.seconds();
+    let next_day = set_time + 86400;
+
+    // snapshot can be refreshed once per day
+    let err = refresh(&mut deps, "udatom", next_day - 1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateSnapshotTooRecent {
+            next_refresh: next_day
+        }
+    );
+
+    // only rate based price sources have a snapshot
+    let err = refresh(&mut deps, "uatom", next_day).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "price source of uatom is not rate based".to_string()
+        }
+    );
+
+    // a rate which grew too fast can't become the new reference
+    set_rate(&mut deps, "1.05");
+    let err = refresh(&mut deps, "udatom", next_day).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPrice {
+            reason: format!(
+                "exchange rate 1.05 grew too fast. rate at {set_time}: 1, max rate: 1.01"
+            )
+        }
+    );
+
+    set_rate(&mut deps, "1.01");
+    refresh(&mut deps, "udatom", next_day).unwrap();
+    assert_eq!(
+        RATE_SNAPSHOTS.load(deps.as_ref().storage, "udatom").unwrap(),
+        RateSnapshot {
+            rate: Decimal::from_str("1.01").unwrap(),
+            timestamp: next_day,
+        }
+    );
+
+    // growth is bounded relative to the refreshed snapshot, not the rate at set time
+    set_rate(&mut deps, "1.0202");
+    let res_err = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(next_day + 3600),
+        QueryMsg::Price {
+            denom: "udatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res_err,
+        ContractError::InvalidPrice {
+            reason: format!(
+                "exchange rate 1.0202 grew too fast. rate at {next_day}: 1.01, max rate: 1.0201"
+            )
+        }
+    );
+
+    set_rate(&mut deps, "1.0201");
+    let res: PriceResponse = from_json(
+        entry::query(
+            deps.as_ref(),
+            mock_env_at_block_time(next_day + 3600),
+            QueryMsg::Price {
+                denom: "udatom".to_string(),
+                kind: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.price, Decimal::from_str("12.2412").unwrap());
+}
+
+#[test]
+fn querying_stable_swap_lp_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let assets = vec![coin(1000000, "uusdc"), coin(1000000, "uusdt")];
+    deps.querier.set_query_pool_response(
+        10004,
+        helpers::prepare_query_stable_swap_pool_response(
+            10004,
+            &assets,
+            &[1u64, 1u64],
+            &coin(2000000, "gamm/pool/10004"),
+        ),
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uusdc",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uusdt",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(99),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uusdc_uusdt_lp",
+        OsmosisPriceSourceUnchecked::StableSwapLiquidityToken {
+            pool_id: 10004,
+        },
+    );
+
+    // Balanced pool: x = y = 1000000
+    // D^4 = 8 * x * y * (x^2 + y^2) => D = 2000000
+    // LP token price: D * min(1, 0.99) / 2000000 = 0.99
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uusdc_uusdt_lp".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::percent(99));
+
+    // Now assume someone sells a large amount of usdc, skewing the pool depths.
+    // The invariant doesn't change, so the LP token price stays the same (apart from rounding),
+    // while the value of the pool depths would be 1500000 * 1 + 527395 * 0.99 = 2022121.05
+    let assets = vec![coin(1500000, "uusdc"), coin(527395, "uusdt")];
+    deps.querier.set_query_pool_response(
+        10004,
+        helpers::prepare_query_stable_swap_pool_response(
+            10004,
+            &assets,
+            &[1u64, 1u64],
+            &coin(2000000, "gamm/pool/10004"),
+        ),
+    );
+
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uusdc_uusdt_lp".to_string(),
+            kind: None,
+        },
+    );
+    assert!(res.price < Decimal::percent(99));
+    assert!(res.price > Decimal::from_str("0.9899").unwrap());
+}
+
+#[test]
+fn computing_stable_swap_lp_price_with_scaling_factors() {
+    // 6 decimals usdc and 18 decimals dai scaled to the same precision
+    let lp_price = compute_ss_lp_price(
+        Decimal::one(),
+        Decimal::from_ratio(1u128, 10u128.pow(12)),
+        Uint128::new(1000000000000),
+        Uint128::new(1000000000000000000000000),
+        (1, 10u64.pow(12)),
+        Uint128::new(2000000000000),
+    )
+    .unwrap();
+    assert_eq!(lp_price, Decimal::one());
+
+    // the pool is valued by the cheaper asset
+    let lp_price = compute_ss_lp_price(
+        Decimal::percent(90),
+        Decimal::from_ratio(1u128, 10u128.pow(12)),
+        Uint128::new(1000000000000),
+        Uint128::new(1000000000000000000000000),
+        (1, 10u64.pow(12)),
+        Uint128::new(2000000000000),
+    )
+    .unwrap();
+    assert_eq!(lp_price, Decimal::percent(90));
+}
+
+#[test]
+fn querying_transmuter_lp_price() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &TransmuterQueryMsg::GetTotalPoolLiquidity {},
+        &GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: vec![coin(1000000, "uausdc"), coin(500000, "unusdc")],
+        },
+    );
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &TransmuterQueryMsg::GetTotalShares {},
+        &GetTotalSharesResponse {
+            total_shares: Uint128::new(1500000),
+        },
+    );
+
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uausdc",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::one(),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "unusdc",
+        OsmosisPriceSourceUnchecked::Fixed {
+            price: Decimal::percent(98),
+        },
+    );
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uausdc_unusdc_lp",
+        OsmosisPriceSourceUnchecked::TransmuterLiquidityToken {
+            pool_id: 8888,
+        },
+    );
+
+    // pool value: 1000000 * 1 + 500000 * 0.98 = 1490000
+    // LP token price: 1490000 / 1500000
+    let res: PriceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uausdc_unusdc_lp".to_string(),
+            kind: None,
+        },
+    );
+    assert_eq!(res.price, Decimal::from_ratio(1490000u128, 1500000u128));
+
+    // price source is missing for one of the pool assets
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &TransmuterQueryMsg::GetTotalPoolLiquidity {},
+        &GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: vec![coin(1000000, "uausdc"), coin(500000, "uother")],
+        },
+    );
+    let res_err = helpers::query_err(
+        deps.as_ref(),
+        QueryMsg::Price {
+            denom: "uausdc_unusdc_lp".to_string(),
+            kind: None,
+        },
     );
+    assert!(matches!(res_err, ContractError::Std(StdError::NotFound { .. })));
 }
@@ -2,7 +2,7 @@ use cosmwasm_std::{from_json, Decimal};
 use mars_oracle_base::ContractError;
 use mars_oracle_osmosis::{contract::entry, OsmosisPriceSourceUnchecked};
 use mars_testing::mock_env_at_block_time;
-use mars_types::oracle::{ActionKind, PriceResponse, QueryMsg};
+use mars_types::oracle::{ActionKind, PriceDetailedResponse, PriceResponse, QueryMsg};
 use pyth_sdk_cw::{Price, PriceFeed, PriceFeedResponse, PriceIdentifier};
 
 use super::helpers;
@@ -614,3 +614,102 @@ fn querying_pyth_price_successfully() {
     // Price for default and liquidation actions should be the same
     assert_eq!(liq_res.price, default_res.price);
 }
+
+#[test]
+fn querying_pyth_price_detailed() {
+    let mut deps = helpers::setup_test_for_pyth();
+
+    let price_id = PriceIdentifier::from_hex(
+        "61226d39beea19d334f17c2febce27e12646d84675924ebb02b9cdaea68727e3",
+    )
+    .unwrap();
+
+    let max_staleness = 30u64;
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        OsmosisPriceSourceUnchecked::Pyth {
+            contract_addr: "pyth_contract_addr".to_string(),
+            price_feed_id: price_id,
+            max_staleness,
+            max_confidence: Decimal::percent(10u64),
+            max_deviation: Decimal::percent(15u64),
+            denom_decimals: 6u8,
+        },
+    );
+
+    let publish_time = 1677157333u64;
+    deps.querier.set_pyth_price(
+        price_id,
+        PriceFeedResponse {
+            price_feed: PriceFeed::new(
+                price_id,
+                Price {
+                    price: 1021000,
+                    conf: 50000,
+                    expo: -4,
+                    publish_time: publish_time as i64,
+                },
+                Price {
+                    price: 1000000,
+                    conf: 40000,
+                    expo: -4,
+                    publish_time: publish_time as i64,
+                },
+            ),
+        },
+    );
+
+    // price is still valid, but close to becoming stale
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(publish_time + 25),
+        QueryMsg::PriceDetailed {
+            denom: "uatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap();
+    let res: PriceDetailedResponse = from_json(res).unwrap();
+    assert_eq!(
+        res,
+        PriceDetailedResponse {
+            denom: "uatom".to_string(),
+            price: Some(Decimal::from_ratio(1021000u128, 10000u128)),
+            error: None,
+            price_source: format!("pyth:pyth_contract_addr:{price_id}:30:0.1:0.15:6"),
+            publish_time: Some(publish_time),
+            age: Some(25),
+            max_staleness: Some(max_staleness),
+            // 5 / 100
+            confidence: Some(Decimal::percent(5)),
+            // (102.1 - 100) / 100
+            ema_deviation: Some(Decimal::permille(21)),
+            twap_window: None,
+            transitive_denoms: vec!["usd".to_string()],
+        }
+    );
+
+    // stale price is rejected, but its metadata is still returned
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(publish_time + 31),
+        QueryMsg::PriceDetailed {
+            denom: "uatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap();
+    let res: PriceDetailedResponse = from_json(res).unwrap();
+    assert_eq!(res.price, None);
+    assert_eq!(
+        res.error,
+        Some(format!(
+            "Invalid price: current price publish time is too old/stale. published: {publish_time}, now: {}",
+            publish_time + 31
+        ))
+    );
+    assert_eq!(res.publish_time, Some(publish_time));
+    assert_eq!(res.age, Some(31));
+    assert_eq!(res.max_staleness, Some(max_staleness));
+}
@@ -1,14 +1,28 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{testing::mock_env, Addr, Decimal};
-use mars_oracle_base::{redemption_rate::RedemptionRate, ContractError};
+use astroport_v5::{
+    asset::{AssetInfo, PairInfo},
+    factory::PairType,
+    pair::QueryMsg as PairQueryMsg,
+};
+use cosmwasm_std::{coin, testing::mock_env, Addr, Binary, Decimal, DepsMut, Uint128};
+use ica_oracle::msg::RedemptionRateResponse;
+use mars_oracle_base::{
+    aggregate::AggregationMethod,
+    exchange_rate::{ExchangeRateQuery, RateSnapshot, RATE_SNAPSHOTS},
+    price_bounds::{PriceBand, PriceBounds},
+    redemption_rate::RedemptionRate,
+    vault_share::SharePriceBounds,
+    ContractError,
+};
 use mars_oracle_osmosis::{
     contract::entry::execute,
     msg::{ExecuteMsg, PriceSourceResponse},
     DowntimeDetector, OsmosisPriceSourceChecked, OsmosisPriceSourceUnchecked, Twap, TwapKind,
 };
+use mars_osmosis::helpers::{GetTotalPoolLiquidityResponse, TransmuterQueryMsg};
 use mars_owner::OwnerError::NotOwner;
-use mars_testing::mock_info;
+use mars_testing::{mock_info, vault_querier::MockVault};
 use mars_types::oracle::QueryMsg;
 use mars_utils::error::ValidationError;
 use osmosis_std::types::osmosis::downtimedetector::v1beta1::Downtime;
@@ -1064,6 +1078,214 @@ fn setting_price_source_xyk_lp() {
     );
 }
 
+#[test]
+fn setting_price_source_stable_swap_lp() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_ss_lp = |denom: &str, pool_id: u64| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: denom.to_string(),
+                price_source: OsmosisPriceSourceUnchecked::StableSwapLiquidityToken {
+                    pool_id,
+                },
+            },
+        )
+    };
+
+    // attempting to use a pool that contains more than two assets; should fail
+    let err = set_price_source_ss_lp("uatom_uosmo_uusdc_lp", 6666).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "expecting pool 6666 to contain exactly two coins; found 3".to_string()
+        }
+    );
+
+    // attempting to use Balancer pool
+    let err = set_price_source_ss_lp("uosmo_umars_lp", 89).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "Balancer pool not supported. Pool id 89".to_string()
+        }
+    );
+
+    // attempting to use CosmWasm pool
+    let err = set_price_source_ss_lp("uausdc_unusdc_lp", 8888).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "CosmWasm pool not supported. Pool id 8888".to_string()
+        }
+    );
+
+    // properly set stable swap lp price source
+    let res = set_price_source_ss_lp("uatom_uosmo_lp", 5555).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "uatom_uosmo_lp".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::StableSwapLiquidityToken {
+            pool_id: 5555,
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_transmuter_lp() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let set_price_source_transmuter_lp = |deps: DepsMut, pool_id: u64| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: "uausdc_unusdc_lp".to_string(),
+                price_source: OsmosisPriceSourceUnchecked::TransmuterLiquidityToken {
+                    pool_id,
+                },
+            },
+        )
+    };
+
+    // attempting to use StableSwap pool
+    let err = set_price_source_transmuter_lp(deps.as_mut(), 5555).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "StableSwap pool not supported. Pool id 5555".to_string()
+        }
+    );
+
+    // attempting to use a transmuter pool without assets
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &TransmuterQueryMsg::GetTotalPoolLiquidity {},
+        &GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: vec![],
+        },
+    );
+    let err = set_price_source_transmuter_lp(deps.as_mut(), 8888).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "transmuter pool 8888 has no assets".to_string()
+        }
+    );
+
+    // properly set transmuter lp price source
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &TransmuterQueryMsg::GetTotalPoolLiquidity {},
+        &GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: vec![coin(1000, "uausdc"), coin(500, "unusdc")],
+        },
+    );
+    let res = set_price_source_transmuter_lp(deps.as_mut(), 8888).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "uausdc_unusdc_lp".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::TransmuterLiquidityToken {
+            pool_id: 8888,
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_pcl_lp() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let set_price_source_pcl_lp = |deps: DepsMut, pool_id: u64| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: "uausdc_unusdc_lp".to_string(),
+                price_source: OsmosisPriceSourceUnchecked::PclLiquidityToken {
+                    pool_id,
+                },
+            },
+        )
+    };
+    let pair_info = |pair_type: PairType| PairInfo {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uausdc".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "unusdc".to_string(),
+            },
+        ],
+        contract_addr: Addr::unchecked("pool_id_8888"),
+        liquidity_token: "factory/pool_id_8888/astroport/share".to_string(),
+        pair_type,
+    };
+
+    // attempting to use ConcentratedLiquidity pool
+    let err = set_price_source_pcl_lp(deps.as_mut(), 7777).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "ConcentratedLiquidity pool not supported. Pool id 7777".to_string()
+        }
+    );
+
+    // attempting to use a CosmWasm pool which is not a PCL pair
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &PairQueryMsg::Pair {},
+        &pair_info(PairType::Xyk {}),
+    );
+    let err = set_price_source_pcl_lp(deps.as_mut(), 8888).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "expecting pool contract pool_id_8888 to be a PCL pair; found xyk".to_string()
+        }
+    );
+
+    // properly set pcl lp price source
+    deps.querier.set_contract_query_response(
+        "pool_id_8888",
+        &PairQueryMsg::Pair {},
+        &pair_info(PairType::Custom("concentrated".to_string())),
+    );
+    let res = set_price_source_pcl_lp(deps.as_mut(), 8888).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "uausdc_unusdc_lp".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::PclLiquidityToken {
+            pool_id: 8888,
+        }
+    );
+}
+
 #[test]
 fn setting_price_source_pyth_with_invalid_params() {
     let mut deps = helpers::setup_test();
@@ -1208,6 +1430,818 @@ fn setting_price_source_pyth_successfully() {
     );
 }
 
+#[test]
+fn setting_price_source_vault_share_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets: Uint128::new(1_100_000),
+        },
+    );
+
+    let mut set_price_source_vault_share =
+        |denom: &str,
+         base_denom: &str,
+         min_share_price: Decimal,
+         max_share_price: Decimal,
+         max_rate_jump_per_day: Decimal| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: denom.to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::VaultShare {
+                        vault_address: "vault_contract".to_string(),
+                        base_denom: base_denom.to_string(),
+                        share_price_bounds: SharePriceBounds {
+                            min_share_price,
+                            max_share_price,
+                        },
+                        max_rate_jump_per_day,
+                    },
+                },
+            )
+        };
+
+    // invalid base denom
+    let err = set_price_source_vault_share(
+        "factory/vault_contract/vault",
+        "!*jadfaefc",
+        Decimal::one(),
+        Decimal::percent(200),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Validation(ValidationError::InvalidDenom {
+            reason: "First character is not ASCII alphabetic".to_string()
+        })
+    );
+
+    // denom is not the vault token
+    let err = set_price_source_vault_share(
+        "factory/vault_contract/other",
+        "uusdc",
+        Decimal::one(),
+        Decimal::percent(200),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "vault token mismatch. expected: factory/vault_contract/other, vault token: factory/vault_contract/vault".to_string()
+        }
+    );
+
+    // base denom is not the vault base token
+    let err = set_price_source_vault_share(
+        "factory/vault_contract/vault",
+        "uatom",
+        Decimal::one(),
+        Decimal::percent(200),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "vault base token mismatch. expected: uatom, base token: uusdc".to_string()
+        }
+    );
+
+    // invalid bounds
+    let err = set_price_source_vault_share(
+        "factory/vault_contract/vault",
+        "uusdc",
+        Decimal::zero(),
+        Decimal::percent(200),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "min_share_price must be greater than zero and less than max_share_price"
+                .to_string()
+        }
+    );
+    let err = set_price_source_vault_share(
+        "factory/vault_contract/vault",
+        "uusdc",
+        Decimal::percent(200),
+        Decimal::percent(200),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "min_share_price must be greater than zero and less than max_share_price"
+                .to_string()
+        }
+    );
+
+    // invalid max rate jump
+    for max_rate_jump_per_day in [Decimal::zero(), Decimal::one()] {
+        let err = set_price_source_vault_share(
+            "factory/vault_contract/vault",
+            "uusdc",
+            Decimal::one(),
+            Decimal::percent(200),
+            max_rate_jump_per_day,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "max_rate_jump_per_day must be greater than 0 and less than 1".to_string()
+            }
+        );
+    }
+}
+
+#[test]
+fn setting_price_source_vault_share_successfully() {
+    let mut deps = helpers::setup_test_with_pools();
+    deps.querier.set_vault(
+        "vault_contract",
+        MockVault {
+            base_token: "uusdc".to_string(),
+            vault_token: "factory/vault_contract/vault".to_string(),
+            total_vault_tokens: Uint128::new(1_000_000),
+            total_assets: Uint128::new(1_100_000),
+        },
+    );
+
+    let share_price_bounds = SharePriceBounds {
+        min_share_price: Decimal::one(),
+        max_share_price: Decimal::percent(200),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "factory/vault_contract/vault".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::VaultShare {
+                vault_address: "vault_contract".to_string(),
+                base_denom: "uusdc".to_string(),
+                share_price_bounds: share_price_bounds.clone(),
+                max_rate_jump_per_day: Decimal::percent(1),
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "factory/vault_contract/vault".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::VaultShare {
+            vault_address: Addr::unchecked("vault_contract"),
+            base_denom: "uusdc".to_string(),
+            share_price_bounds,
+            max_rate_jump_per_day: Decimal::percent(1),
+        }
+    );
+
+    // share price observed when the price source was set is the reference for the growth checks
+    let snapshot =
+        RATE_SNAPSHOTS.load(deps.as_ref().storage, "factory/vault_contract/vault").unwrap();
+    assert_eq!(
+        snapshot,
+        RateSnapshot {
+            rate: Decimal::percent(110),
+            timestamp: mock_env().block.time.seconds(),
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_redemption_rate_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_rr = |transitive_denom: &str, max_rate_jump_per_day: Decimal| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: "ustatom".to_string(),
+                price_source: OsmosisPriceSourceUnchecked::RedemptionRate {
+                    transitive_denom: transitive_denom.to_string(),
+                    redemption_rate: RedemptionRate {
+                        contract_addr: "dummy_addr".to_string(),
+                        max_staleness: 21600,
+                    },
+                    max_rate_jump_per_day,
+                },
+            },
+        )
+    };
+
+    // invalid transitive denom
+    let err = set_price_source_rr("!*jadfaefc", Decimal::percent(1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Validation(ValidationError::InvalidDenom {
+            reason: "First character is not ASCII alphabetic".to_string()
+        })
+    );
+
+    // invalid max rate jump
+    for max_rate_jump_per_day in [Decimal::zero(), Decimal::one()] {
+        let err = set_price_source_rr("uatom", max_rate_jump_per_day).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "max_rate_jump_per_day must be greater than 0 and less than 1".to_string()
+            }
+        );
+    }
+
+    // redemption rate not available
+    let err = set_price_source_rr("uatom", Decimal::percent(1)).unwrap_err();
+    assert!(matches!(err, ContractError::Std(..)));
+}
+
+#[test]
+fn setting_price_source_redemption_rate_successfully() {
+    let mut deps = helpers::setup_test_with_pools();
+    deps.querier.set_redemption_rate(
+        "ustatom",
+        RedemptionRateResponse {
+            redemption_rate: Decimal::percent(110),
+            update_time: mock_env().block.time.seconds(),
+        },
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "ustatom".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::RedemptionRate {
+                transitive_denom: "uatom".to_string(),
+                redemption_rate: RedemptionRate {
+                    contract_addr: "dummy_addr".to_string(),
+                    max_staleness: 21600,
+                },
+                max_rate_jump_per_day: Decimal::percent(1),
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "ustatom".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::RedemptionRate {
+            transitive_denom: "uatom".to_string(),
+            redemption_rate: RedemptionRate {
+                contract_addr: Addr::unchecked("dummy_addr"),
+                max_staleness: 21600,
+            },
+            max_rate_jump_per_day: Decimal::percent(1),
+        }
+    );
+
+    // rate observed when the price source was set is the reference for the growth checks
+    let snapshot = RATE_SNAPSHOTS.load(deps.as_ref().storage, "ustatom").unwrap();
+    assert_eq!(
+        snapshot,
+        RateSnapshot {
+            rate: Decimal::percent(110),
+            timestamp: mock_env().block.time.seconds(),
+        }
+    );
+
+    // snapshot is removed together with the price source
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::RemovePriceSource {
+            denom: "ustatom".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(!RATE_SNAPSHOTS.has(deps.as_ref().storage, "ustatom"));
+}
+
+#[test]
+fn setting_price_source_exchange_rate_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_exchange_rate = |rate_path: &str, max_rate_jump_per_day: Decimal| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner"),
+            ExecuteMsg::SetPriceSource {
+                denom: "udatom".to_string(),
+                price_source: OsmosisPriceSourceUnchecked::ExchangeRate {
+                    transitive_denom: "uatom".to_string(),
+                    exchange_rate: ExchangeRateQuery {
+                        contract_addr: "drop_core".to_string(),
+                        query_msg: Binary::from(br#"{"exchange_rate":{}}"#),
+                        rate_path: rate_path.to_string(),
+                    },
+                    max_rate_jump_per_day,
+                },
+            },
+        )
+    };
+
+    // invalid rate path
+    let err = set_price_source_exchange_rate("state..rate", Decimal::percent(1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "invalid exchange rate path: state..rate".to_string()
+        }
+    );
+
+    // invalid max rate jump
+    let err = set_price_source_exchange_rate("", Decimal::zero()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "max_rate_jump_per_day must be greater than 0 and less than 1".to_string()
+        }
+    );
+
+    // rate not found in the response
+    deps.querier.set_contract_response("drop_core", Binary::from(br#"{"rate":"1.05"}"#));
+    let err = set_price_source_exchange_rate("state.rate", Decimal::percent(1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPrice {
+            reason: "exchange rate path state.rate not found in response of drop_core".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_rate_based_in_aggregate() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "ustatom".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::Aggregate {
+                sources: vec![
+                    OsmosisPriceSourceUnchecked::Fixed {
+                        price: Decimal::one(),
+                    },
+                    OsmosisPriceSourceUnchecked::RedemptionRate {
+                        transitive_denom: "uatom".to_string(),
+                        redemption_rate: RedemptionRate {
+                            contract_addr: "dummy_addr".to_string(),
+                            max_staleness: 21600,
+                        },
+                        max_rate_jump_per_day: Decimal::percent(1),
+                    },
+                ],
+                min_sources: 1,
+                method: AggregationMethod::Median {},
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "rate based price sources can't be aggregated".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_aggregate_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_aggregate =
+        |sources: Vec<OsmosisPriceSourceUnchecked>, min_sources: u32, method: AggregationMethod| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: "umars".to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::Aggregate {
+                        sources,
+                        min_sources,
+                        method,
+                    },
+                },
+            )
+        };
+
+    let fixed = OsmosisPriceSourceUnchecked::Fixed {
+        price: Decimal::one(),
+    };
+    let spot = OsmosisPriceSourceUnchecked::Spot {
+        pool_id: 89,
+    };
+
+    // no sub-sources
+    let err = set_price_source_aggregate(vec![], 1, AggregationMethod::Median {}).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "aggregate price source requires at least one sub-source".to_string()
+        }
+    );
+
+    // too many sub-sources
+    let err = set_price_source_aggregate(vec![fixed.clone(); 11], 1, AggregationMethod::Median {})
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "aggregate price source can have at most 10 sub-sources".to_string()
+        }
+    );
+
+    // invalid min_sources
+    for min_sources in [0, 3] {
+        let err = set_price_source_aggregate(
+            vec![fixed.clone(), spot.clone()],
+            min_sources,
+            AggregationMethod::Median {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "min_sources must be greater than zero and less than or equal to the number of sub-sources".to_string()
+            }
+        );
+    }
+
+    // weights don't match sub-sources
+    let err = set_price_source_aggregate(
+        vec![fixed.clone(), spot.clone()],
+        1,
+        AggregationMethod::WeightedMean {
+            weights: vec![Decimal::one()],
+            max_deviation: Decimal::percent(5),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "number of weights must match the number of sub-sources".to_string()
+        }
+    );
+
+    // zero weight
+    let err = set_price_source_aggregate(
+        vec![fixed.clone(), spot.clone()],
+        1,
+        AggregationMethod::WeightedMean {
+            weights: vec![Decimal::one(), Decimal::zero()],
+            max_deviation: Decimal::percent(5),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "weights must be greater than zero".to_string()
+        }
+    );
+
+    // invalid max_deviation
+    for max_deviation in [Decimal::zero(), Decimal::one()] {
+        let err = set_price_source_aggregate(
+            vec![fixed.clone(), spot.clone()],
+            1,
+            AggregationMethod::WeightedMean {
+                weights: vec![Decimal::one(), Decimal::one()],
+                max_deviation,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "max_deviation must be greater than zero and less than one".to_string()
+            }
+        );
+    }
+
+    // nested aggregate
+    let err = set_price_source_aggregate(
+        vec![
+            fixed.clone(),
+            OsmosisPriceSourceUnchecked::Aggregate {
+                sources: vec![fixed.clone()],
+                min_sources: 1,
+                method: AggregationMethod::Median {},
+            },
+        ],
+        1,
+        AggregationMethod::Median {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "nested aggregate price sources are not supported".to_string()
+        }
+    );
+
+    // invalid sub-source, pool 1 doesn't contain umars
+    let err = set_price_source_aggregate(
+        vec![
+            fixed,
+            OsmosisPriceSourceUnchecked::Spot {
+                pool_id: 1,
+            },
+        ],
+        1,
+        AggregationMethod::Median {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "pool 1 does not contain umars".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_aggregate_successfully() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let method = AggregationMethod::WeightedMean {
+        weights: vec![Decimal::one(), Decimal::percent(200)],
+        max_deviation: Decimal::percent(5),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "umars".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::Aggregate {
+                sources: vec![
+                    OsmosisPriceSourceUnchecked::Spot {
+                        pool_id: 89,
+                    },
+                    OsmosisPriceSourceUnchecked::GeometricTwap {
+                        pool_id: 89,
+                        window_size: 86400,
+                        downtime_detector: None,
+                    },
+                ],
+                min_sources: 1,
+                method: method.clone(),
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "umars".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::Aggregate {
+            sources: vec![
+                OsmosisPriceSourceChecked::Spot {
+                    pool_id: 89,
+                },
+                OsmosisPriceSourceChecked::GeometricTwap {
+                    pool_id: 89,
+                    window_size: 86400,
+                    downtime_detector: None,
+                },
+            ],
+            min_sources: 1,
+            method,
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_bounded_with_invalid_params() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let mut set_price_source_bounded =
+        |source: OsmosisPriceSourceUnchecked, bounds: PriceBounds| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner"),
+                ExecuteMsg::SetPriceSource {
+                    denom: "umars".to_string(),
+                    price_source: OsmosisPriceSourceUnchecked::Bounded {
+                        source: Box::new(source),
+                        bounds,
+                    },
+                },
+            )
+        };
+
+    let spot = OsmosisPriceSourceUnchecked::Spot {
+        pool_id: 89,
+    };
+    let bounds = PriceBounds {
+        floor: Decimal::percent(98),
+        ceiling: Decimal::percent(102),
+        reject_outside: None,
+    };
+
+    // invalid floor and ceiling
+    for (floor, ceiling) in
+        [(Decimal::zero(), Decimal::zero()), (Decimal::one(), Decimal::percent(99))]
+    {
+        let err = set_price_source_bounded(
+            spot.clone(),
+            PriceBounds {
+                floor,
+                ceiling,
+                reject_outside: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceSource {
+                reason: "ceiling must be greater than zero and greater than or equal to floor"
+                    .to_string()
+            }
+        );
+    }
+
+    // rejection band narrower than [floor, ceiling]
+    let err = set_price_source_bounded(
+        spot.clone(),
+        PriceBounds {
+            reject_outside: Some(PriceBand {
+                min: Decimal::percent(99),
+                max: Decimal::percent(110),
+            }),
+            ..bounds.clone()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "reject_outside band must contain [floor, ceiling]".to_string()
+        }
+    );
+
+    // nested bounded
+    let err = set_price_source_bounded(
+        OsmosisPriceSourceUnchecked::Bounded {
+            source: Box::new(spot),
+            bounds: bounds.clone(),
+        },
+        bounds.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "nested bounded price sources are not supported".to_string()
+        }
+    );
+
+    // invalid wrapped source, pool 1 doesn't contain umars
+    let err = set_price_source_bounded(
+        OsmosisPriceSourceUnchecked::Spot {
+            pool_id: 1,
+        },
+        bounds,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "pool 1 does not contain umars".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_bounded_in_aggregate() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "umars".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::Aggregate {
+                sources: vec![
+                    OsmosisPriceSourceUnchecked::Fixed {
+                        price: Decimal::one(),
+                    },
+                    OsmosisPriceSourceUnchecked::Bounded {
+                        source: Box::new(OsmosisPriceSourceUnchecked::Spot {
+                            pool_id: 89,
+                        }),
+                        bounds: PriceBounds {
+                            floor: Decimal::percent(98),
+                            ceiling: Decimal::percent(102),
+                            reject_outside: None,
+                        },
+                    },
+                ],
+                min_sources: 1,
+                method: AggregationMethod::Median {},
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPriceSource {
+            reason: "bounded price sources can't be aggregated".to_string()
+        }
+    );
+}
+
+#[test]
+fn setting_price_source_bounded_successfully() {
+    let mut deps = helpers::setup_test_with_pools();
+
+    let bounds = PriceBounds {
+        floor: Decimal::percent(98),
+        ceiling: Decimal::percent(102),
+        reject_outside: Some(PriceBand {
+            min: Decimal::percent(90),
+            max: Decimal::percent(110),
+        }),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner"),
+        ExecuteMsg::SetPriceSource {
+            denom: "umars".to_string(),
+            price_source: OsmosisPriceSourceUnchecked::Bounded {
+                source: Box::new(OsmosisPriceSourceUnchecked::Spot {
+                    pool_id: 89,
+                }),
+                bounds: bounds.clone(),
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let res: PriceSourceResponse = helpers::query(
+        deps.as_ref(),
+        QueryMsg::PriceSource {
+            denom: "umars".to_string(),
+        },
+    );
+    assert_eq!(
+        res.price_source,
+        OsmosisPriceSourceChecked::Bounded {
+            source: Box::new(OsmosisPriceSourceChecked::Spot {
+                pool_id: 89,
+            }),
+            bounds,
+        }
+    );
+}
+
 #[test]
 fn querying_price_source() {
     let mut deps = helpers::setup_test_with_pools();
@@ -1,4 +1,4 @@
-use cosmwasm_std::{attr, Attribute, DepsMut, Env, Response};
+use cosmwasm_std::{attr, Addr, Attribute, DepsMut, Env, Response};
 use mars_oracle_base::{ContractError, ContractResult};
 use mars_types::oracle::AstroportTwapSnapshot;
 
@@ -29,22 +29,9 @@ impl ExecuteTwapSnapshots for WasmOracle<'_> {
         for denom in denoms {
             let price_source = self.price_sources.load(deps.storage, &denom)?;
 
-            // Asset must be configured to use TWAP price source
-            let (pair_address, window_size, tolerance) = match price_source {
-                WasmPriceSourceChecked::AstroportTwap {
-                    pair_address,
-                    window_size,
-                    tolerance,
-                } => (pair_address, window_size, tolerance),
-                WasmPriceSourceChecked::Lsd {
-                    transitive_denom: _,
-                    twap,
-                    redemption_rate: _,
-                } => (twap.pair_address, twap.window_size, twap.tolerance),
-                _ => {
-                    return Err(ContractError::PriceSourceNotTwap {});
-                }
-            };
+            // Asset must be configured to use TWAP price source (directly or as an aggregate sub-source)
+            let (pair_address, window_size, tolerance) =
+                twap_params(price_source).ok_or(ContractError::PriceSourceNotTwap {})?;
 
             // Load existing snapshots. If there's none, we initialize an empty vector
             let mut snapshots =
@@ -85,3 +72,29 @@ impl ExecuteTwapSnapshots for WasmOracle<'_> {
             .add_attributes(attrs))
     }
 }
+
+/// Astroport TWAP params (pair address, window size, tolerance) of the price source
+fn twap_params(price_source: WasmPriceSourceChecked) -> Option<(Addr, u64, u64)> {
+    match price_source {
+        WasmPriceSourceChecked::AstroportTwap {
+            pair_address,
+            window_size,
+            tolerance,
+        } => Some((pair_address, window_size, tolerance)),
+        WasmPriceSourceChecked::Lsd {
+            transitive_denom: _,
+            twap,
+            redemption_rate: _,
+        } => Some((twap.pair_address, twap.window_size, twap.tolerance)),
+        // Validation guarantees at most one TWAP sub-source
+        WasmPriceSourceChecked::Aggregate {
+            sources,
+            ..
+        } => sources.into_iter().find_map(twap_params),
+        WasmPriceSourceChecked::Bounded {
+            source,
+            ..
+        } => twap_params(*source),
+        _ => None,
+    }
+}
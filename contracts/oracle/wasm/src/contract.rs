@@ -71,7 +71,7 @@ pub mod entry {
                     denoms,
                 } => contract.execute_record_astroport_twap_snapshots(deps, env, denoms),
             },
-            _ => contract.execute(deps, info, msg),
+            _ => contract.execute(deps, env, info, msg),
         }
     }
 
@@ -5,7 +5,7 @@ use std::{
 
 use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Empty, Env, Uint128};
 use cw_storage_plus::Map;
-use mars_oracle_base::{ContractResult, PriceSourceChecked};
+use mars_oracle_base::{lp_pricing::compute_pcl_lp_price, ContractResult, PriceSourceChecked};
 use mars_types::oracle::{ActionKind, Config};
 
 use crate::{helpers::query_token_precision, state::ASTROPORT_FACTORY};
@@ -56,43 +56,6 @@ pub fn query_pcl_lp_price<P: PriceSourceChecked<Empty>>(
     )
 }
 
-pub fn compute_pcl_lp_price(
-    coin0_price: Decimal,
-    coin1_price: Decimal,
-    coin0_decimals: u8,
-    coin1_decimals: u8,
-    total_shares: Uint128,
-    price_scale: Decimal,
-    curve_invariant: Decimal256,
-) -> ContractResult<Decimal> {
-    // xcp represents the virtual value of the pool
-    // xcp = curve_invariant / (2 * sqrt(price_scale))
-    let xcp = curve_invariant.checked_div(
-        Decimal256::from(price_scale).sqrt().checked_mul(Decimal256::from_str("2")?)?,
-    )?;
-
-    // Virtual price represents the theoretic price of one share. This virtual price is used as input
-    // for the Curve V2 model to determine the modelled lp price.
-    // virtual_price = xcp / total_shares
-    let virtual_price = xcp.checked_div(Decimal256::from_ratio(total_shares, 1u128))?;
-
-    // The curve_invariant is calculated with amounts scaled by Astroport, e.g. 1e18 ueth is stored as 1 eth.
-    // So we need to scale the prices accordingly, so that they represent the price of 1 whole unit.
-    let coin0_price_scaled =
-        Decimal256::from(coin0_price) * Decimal256::from_str("10")?.pow(coin0_decimals as u32);
-    let coin1_price_scaled =
-        Decimal256::from(coin1_price) * Decimal256::from_str("10")?.pow(coin1_decimals as u32);
-
-    // LP price according to the model
-    // lp_price_model = 2 * virtual_price * sqrt(coin0_price * coin1_price)
-    let lp_price_model_256 = Decimal256::from_str("2")?
-        .checked_mul(virtual_price)?
-        .checked_mul(coin0_price_scaled.checked_mul(coin1_price_scaled)?.sqrt())?;
-    let lp_price_model = Decimal::try_from(lp_price_model_256)?;
-
-    Ok(lp_price_model)
-}
-
 #[allow(clippy::too_many_arguments)]
 pub fn query_stable_swap_lp_price<P: PriceSourceChecked<Empty>>(
     deps: &Deps,
@@ -11,11 +11,23 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{from_json, Addr, Decimal, Deps, Empty, Env, Uint128};
 use cw_storage_plus::Map;
 use mars_oracle_base::{
+    aggregate::{assert_aggregate, query_aggregated_price, AggregatedPrice, AggregationMethod},
+    exchange_rate::{
+        assert_max_rate_jump_per_day, assert_rate_growth, assert_rate_path, query_exchange_rate,
+        ExchangeRateQuery,
+    },
     lp_pricing,
+    price_bounds::{apply_price_bounds, assert_price_bounds, BoundedPrice, PriceBounds},
+    price_details::PriceDetails,
+    pyth::query_pyth_price_details,
     redemption_rate::{assert_rr_not_too_old, query_redemption_rate, RedemptionRate},
+    vault_share::{
+        assert_vault_share, query_bounded_share_price, query_share_price, SharePriceBounds,
+    },
     ContractError, ContractResult, PriceSourceChecked, PriceSourceUnchecked,
 };
 use mars_types::oracle::{ActionKind, AstroportTwapSnapshot, Config};
+use mars_utils::helpers::validate_native_denom;
 use pyth_sdk_cw::PriceIdentifier;
 
 use crate::{
@@ -122,6 +134,52 @@ pub enum WasmPriceSource<A> {
         /// Params to query redemption rate
         redemption_rate: RedemptionRate<A>,
     },
+    /// Liquid Staking Derivatives (LSD) price quoted in USD based on the redemption rate only.
+    /// Used for LSDs without a reliable stAsset/Asset pool.
+    ///
+    /// Equation to calculate the price:
+    /// stAsset/USD = stAsset/Asset Redemption Rate * Asset/USD
+    /// where:
+    /// - stAsset/Asset Redemption Rate comes from the Redemption Rate provider (e.g. Stride).
+    /// - Asset/USD price comes from the Mars Oracle contract.
+    ///
+    /// The redemption rate may grow by at most `max_rate_jump_per_day` per day since the last
+    /// rate snapshot, otherwise the price is rejected. The snapshot is taken when the price source
+    /// is set and can be refreshed once per day with `ExecuteMsg::RefreshRateSnapshot`.
+    RedemptionRate {
+        /// Transitive denom for which we query price in USD. It refers to 'Asset' in the equation:
+        /// stAsset/USD = stAsset/Asset * Asset/USD
+        transitive_denom: String,
+
+        /// Params to query redemption rate
+        redemption_rate: RedemptionRate<A>,
+
+        /// Max relative growth of the redemption rate per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
+    /// Price quoted in USD of a token with an exchange rate to another denom, queried from a
+    /// contract (e.g. Drop dASSET, pSTAKE stkASSET or vault-like tokens).
+    ///
+    /// Equation to calculate the price:
+    /// token/USD = token/Asset Exchange Rate * Asset/USD
+    /// where:
+    /// - token/Asset Exchange Rate is read from the response of `exchange_rate.query_msg`.
+    /// - Asset/USD price comes from the Mars Oracle contract.
+    ///
+    /// The exchange rate may grow by at most `max_rate_jump_per_day` per day since the last
+    /// rate snapshot, otherwise the price is rejected. The snapshot is taken when the price source
+    /// is set and can be refreshed once per day with `ExecuteMsg::RefreshRateSnapshot`.
+    ExchangeRate {
+        /// Transitive denom for which we query price in USD. It refers to 'Asset' in the equation:
+        /// token/USD = token/Asset * Asset/USD
+        transitive_denom: String,
+
+        /// Params to query the exchange rate
+        exchange_rate: ExchangeRateQuery<A>,
+
+        /// Max relative growth of the exchange rate per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
     /// Astroport LP token (of an XYK pool) price quoted in uusd
     XykLiquidityToken {
         /// Address of the Astroport pair
@@ -135,6 +193,65 @@ pub enum WasmPriceSource<A> {
         /// Address of the Astroport pair
         pair_address: A,
     },
+    /// cw-vault-standard vault share (e.g. Mars managed vault token `factory/{vault}/{subdenom}`)
+    /// price quoted in USD.
+    ///
+    /// Equation to calculate the price:
+    /// share/USD = share/base_token * base_token/USD
+    /// where:
+    /// - share/base_token is the vault share price (`ConvertToAssets` of the whole vault token supply).
+    /// - base_token/USD price comes from the Mars Oracle contract.
+    ///
+    /// The share price has to be within `share_price_bounds` and may grow by at most
+    /// `max_rate_jump_per_day` per day since the last rate snapshot, otherwise the price is rejected.
+    VaultShare {
+        /// Address of the vault issuing the share token
+        vault_address: A,
+
+        /// Base token of the vault. Its price source should be available in the Mars Oracle contract.
+        base_denom: String,
+
+        /// Sanity bounds for the share price
+        share_price_bounds: SharePriceBounds,
+
+        /// Max relative growth of the share price per day, e.g. 0.01 for 1%
+        max_rate_jump_per_day: Decimal,
+    },
+    /// Aggregated price of multiple price sources for the same asset, quoted in USD.
+    ///
+    /// The price is the median of the sub-source prices, or their weighted mean after rejecting
+    /// outliers (see `AggregationMethod`). Sub-sources failing to return a price are skipped, but
+    /// at least `min_sources` of them have to return a price.
+    ///
+    /// NOTE: Sub-sources are validated for the same denom and can't be aggregates themselves.
+    /// TWAP snapshots are recorded per denom, so at most one sub-source can use an Astroport TWAP.
+    /// Rate snapshots are recorded for the top level price source only, so rate based sources
+    /// can't be aggregated.
+    Aggregate {
+        /// Price sources to aggregate
+        sources: Vec<WasmPriceSource<A>>,
+
+        /// Minimum number of sub-sources required to return a price
+        min_sources: u32,
+
+        /// How the sub-source prices are aggregated
+        method: AggregationMethod,
+    },
+    /// Price of the wrapped price source, quoted in USD, clamped into `[floor, ceiling]`.
+    ///
+    /// Used for stablecoins pegged to 1 USD or tokens with a hard redemption floor, so that short
+    /// depegs on thin pools don't trigger liquidations. If `reject_outside` is set, the price is
+    /// rejected for `ActionKind::Default` when the raw price leaves the wider band.
+    ///
+    /// NOTE: The wrapped price source is validated for the same denom and can't be bounded itself.
+    /// Bounded price sources can't be aggregated, the aggregate price source can be bounded instead.
+    Bounded {
+        /// Price source to clamp
+        source: Box<WasmPriceSource<A>>,
+
+        /// Floor, ceiling and optional rejection band of the price
+        bounds: PriceBounds,
+    },
 }
 
 #[cw_serde]
@@ -190,9 +307,36 @@ impl fmt::Display for WasmPriceSourceChecked {
                 } = redemption_rate;
                 format!("lsd:{transitive_denom}:{pair_address}:{window_size}:{tolerance}:{contract_addr}:{max_staleness}")
             },
+            WasmPriceSource::RedemptionRate { transitive_denom, redemption_rate, max_rate_jump_per_day } => {
+                let RedemptionRate {
+                    contract_addr,
+                    max_staleness,
+                } = redemption_rate;
+                format!("redemption_rate:{transitive_denom}:{contract_addr}:{max_staleness}:{max_rate_jump_per_day}")
+            },
+            WasmPriceSource::ExchangeRate { transitive_denom, exchange_rate, max_rate_jump_per_day } => {
+                let ExchangeRateQuery {
+                    contract_addr,
+                    query_msg,
+                    rate_path,
+                } = exchange_rate;
+                format!("exchange_rate:{transitive_denom}:{contract_addr}:{query_msg}:{rate_path}:{max_rate_jump_per_day}")
+            },
             WasmPriceSource::XykLiquidityToken { pair_address } => format!("xyk_liquidity_token:{pair_address}"),
             WasmPriceSource::PclLiquidityToken { pair_address } => format!("pcl_liquidity_token:{pair_address}"),
             WasmPriceSource::SsLiquidityToken { pair_address } => format!("stable_swap_liquidity_token:{pair_address}"),
+            WasmPriceSource::VaultShare { vault_address, base_denom, share_price_bounds, max_rate_jump_per_day } => {
+                let SharePriceBounds {
+                    min_share_price,
+                    max_share_price,
+                } = share_price_bounds;
+                format!("vault_share:{vault_address}:{base_denom}:{min_share_price}:{max_share_price}:{max_rate_jump_per_day}")
+            },
+            WasmPriceSource::Aggregate { sources, min_sources, method } => {
+                let sources = sources.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ");
+                format!("aggregate:{method}:{min_sources}:[{sources}]")
+            },
+            WasmPriceSource::Bounded { source, bounds } => format!("bounded:{bounds}:{source}"),
         };
         write!(f, "{label}")
     }
@@ -334,6 +478,50 @@ impl PriceSourceUnchecked<WasmPriceSourceChecked, Empty> for WasmPriceSourceUnch
                     },
                 })
             }
+            WasmPriceSource::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                max_rate_jump_per_day,
+            } => {
+                if !price_sources.has(deps.storage, &transitive_denom) {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: format!("missing price source for {}", transitive_denom),
+                    });
+                }
+                assert_max_rate_jump_per_day(max_rate_jump_per_day)?;
+
+                Ok(WasmPriceSourceChecked::RedemptionRate {
+                    transitive_denom,
+                    redemption_rate: RedemptionRate {
+                        contract_addr: deps.api.addr_validate(&redemption_rate.contract_addr)?,
+                        max_staleness: redemption_rate.max_staleness,
+                    },
+                    max_rate_jump_per_day,
+                })
+            }
+            WasmPriceSource::ExchangeRate {
+                transitive_denom,
+                exchange_rate,
+                max_rate_jump_per_day,
+            } => {
+                if !price_sources.has(deps.storage, &transitive_denom) {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: format!("missing price source for {}", transitive_denom),
+                    });
+                }
+                assert_rate_path(&exchange_rate.rate_path)?;
+                assert_max_rate_jump_per_day(max_rate_jump_per_day)?;
+
+                Ok(WasmPriceSourceChecked::ExchangeRate {
+                    transitive_denom,
+                    exchange_rate: ExchangeRateQuery {
+                        contract_addr: deps.api.addr_validate(&exchange_rate.contract_addr)?,
+                        query_msg: exchange_rate.query_msg,
+                        rate_path: exchange_rate.rate_path,
+                    },
+                    max_rate_jump_per_day,
+                })
+            }
             WasmPriceSource::XykLiquidityToken {
                 pair_address,
             } => {
@@ -379,6 +567,118 @@ impl PriceSourceUnchecked<WasmPriceSourceChecked, Empty> for WasmPriceSourceUnch
                     pair_address,
                 })
             }
+            WasmPriceSource::VaultShare {
+                vault_address,
+                base_denom: vault_base_denom,
+                share_price_bounds,
+                max_rate_jump_per_day,
+            } => {
+                validate_native_denom(&vault_base_denom)?;
+
+                let vault_address = deps.api.addr_validate(&vault_address)?;
+                assert_vault_share(
+                    &deps.querier,
+                    &vault_address,
+                    denom,
+                    &vault_base_denom,
+                    &share_price_bounds,
+                )?;
+                assert_max_rate_jump_per_day(max_rate_jump_per_day)?;
+
+                Ok(WasmPriceSourceChecked::VaultShare {
+                    vault_address,
+                    base_denom: vault_base_denom,
+                    share_price_bounds,
+                    max_rate_jump_per_day,
+                })
+            }
+            WasmPriceSource::Aggregate {
+                sources,
+                min_sources,
+                method,
+            } => {
+                assert_aggregate(sources.len(), min_sources, &method)?;
+
+                let twap_sources = sources
+                    .iter()
+                    .filter(|source| {
+                        matches!(
+                            source,
+                            WasmPriceSource::AstroportTwap { .. } | WasmPriceSource::Lsd { .. }
+                        )
+                    })
+                    .count();
+                if twap_sources > 1 {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason:
+                            "aggregate price source can have at most one Astroport TWAP sub-source"
+                                .to_string(),
+                    });
+                }
+
+                let sources = sources
+                    .into_iter()
+                    .map(|source| {
+                        match source {
+                            WasmPriceSource::Aggregate {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "nested aggregate price sources are not supported"
+                                        .to_string(),
+                                });
+                            }
+                            WasmPriceSource::Bounded {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "bounded price sources can't be aggregated".to_string(),
+                                });
+                            }
+                            WasmPriceSource::RedemptionRate {
+                                ..
+                            }
+                            | WasmPriceSource::ExchangeRate {
+                                ..
+                            } => {
+                                return Err(ContractError::InvalidPriceSource {
+                                    reason: "rate based price sources can't be aggregated"
+                                        .to_string(),
+                                });
+                            }
+                            _ => {}
+                        }
+                        source.validate(deps, denom, base_denom, price_sources)
+                    })
+                    .collect::<ContractResult<Vec<_>>>()?;
+
+                Ok(WasmPriceSourceChecked::Aggregate {
+                    sources,
+                    min_sources,
+                    method,
+                })
+            }
+            WasmPriceSource::Bounded {
+                source,
+                bounds,
+            } => {
+                assert_price_bounds(&bounds)?;
+
+                if let WasmPriceSource::Bounded {
+                    ..
+                } = *source
+                {
+                    return Err(ContractError::InvalidPriceSource {
+                        reason: "nested bounded price sources are not supported".to_string(),
+                    });
+                }
+                let source = (*source).validate(deps, denom, base_denom, price_sources)?;
+
+                Ok(WasmPriceSourceChecked::Bounded {
+                    source: Box::new(source),
+                    bounds,
+                })
+            }
         }
     }
 }
@@ -460,6 +760,49 @@ impl PriceSourceChecked<Empty> for WasmPriceSourceChecked {
                 price_sources,
                 kind,
             ),
+            WasmPriceSource::RedemptionRate {
+                transitive_denom,
+                redemption_rate,
+                max_rate_jump_per_day,
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                assert_rr_not_too_old(env.block.time.seconds(), &rr, redemption_rate)?;
+
+                query_rate_based_price(
+                    deps,
+                    env,
+                    denom,
+                    rr.redemption_rate,
+                    *max_rate_jump_per_day,
+                    transitive_denom,
+                    config,
+                    price_sources,
+                    kind,
+                )
+            }
+            WasmPriceSource::ExchangeRate {
+                transitive_denom,
+                exchange_rate,
+                max_rate_jump_per_day,
+            } => {
+                let rate = query_exchange_rate(&deps.querier, exchange_rate)?;
+
+                query_rate_based_price(
+                    deps,
+                    env,
+                    denom,
+                    rate,
+                    *max_rate_jump_per_day,
+                    transitive_denom,
+                    config,
+                    price_sources,
+                    kind,
+                )
+            }
             WasmPriceSource::XykLiquidityToken {
                 pair_address,
             } => query_xyk_liquidity_token_price(
@@ -485,8 +828,315 @@ impl PriceSourceChecked<Empty> for WasmPriceSourceChecked {
             } => {
                 query_ss_liquidity_token_price(deps, env, config, price_sources, pair_address, kind)
             }
+            WasmPriceSource::VaultShare {
+                vault_address,
+                base_denom,
+                share_price_bounds,
+                max_rate_jump_per_day,
+            } => query_vault_share_price(
+                deps,
+                env,
+                denom,
+                vault_address,
+                base_denom,
+                share_price_bounds,
+                *max_rate_jump_per_day,
+                config,
+                price_sources,
+                kind,
+            ),
+            WasmPriceSource::Aggregate {
+                ..
+            } => self
+                .query_aggregated_price(deps, env, denom, config, price_sources, kind)
+                .map(|aggregated_price| aggregated_price.price),
+            WasmPriceSource::Bounded {
+                ..
+            } => self
+                .query_bounded_price(deps, env, denom, config, price_sources, kind)
+                .map(|bounded_price| bounded_price.price),
         }
     }
+
+    fn query_aggregated_price(
+        &self,
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<AggregatedPrice> {
+        match self {
+            WasmPriceSource::Aggregate {
+                sources,
+                min_sources,
+                method,
+            } => query_aggregated_price(
+                deps,
+                env,
+                denom,
+                config,
+                price_sources,
+                kind,
+                sources,
+                *min_sources,
+                method,
+            ),
+            _ => Err(ContractError::InvalidPriceSource {
+                reason: format!("price source for {denom} is not an aggregate price source"),
+            }),
+        }
+    }
+
+    fn query_bounded_price(
+        &self,
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<BoundedPrice> {
+        match self {
+            WasmPriceSource::Bounded {
+                source,
+                bounds,
+            } => {
+                let raw_price =
+                    source.query_price(deps, env, denom, config, price_sources, kind.clone())?;
+                apply_price_bounds(denom, raw_price, bounds, &kind)
+            }
+            _ => Err(ContractError::InvalidPriceSource {
+                reason: format!("price source for {denom} is not a bounded price source"),
+            }),
+        }
+    }
+
+    fn query_price_detailed(
+        &self,
+        deps: &Deps,
+        env: &Env,
+        denom: &str,
+        config: &Config,
+        price_sources: &Map<&str, Self>,
+        kind: ActionKind,
+    ) -> ContractResult<PriceDetails> {
+        let price = self.query_price(deps, env, denom, config, price_sources, kind);
+        Ok(query_price_details(deps, env, denom, config, self)?.with_price_result(price))
+    }
+
+    fn query_exchange_rate(&self, deps: &Deps, denom: &str) -> ContractResult<Option<Decimal>> {
+        match self {
+            WasmPriceSource::RedemptionRate {
+                redemption_rate,
+                ..
+            } => {
+                let rr = query_redemption_rate(
+                    &deps.querier,
+                    redemption_rate.contract_addr.clone(),
+                    denom.to_string(),
+                )?;
+                Ok(Some(rr.redemption_rate))
+            }
+            WasmPriceSource::ExchangeRate {
+                exchange_rate,
+                ..
+            } => Ok(Some(query_exchange_rate(&deps.querier, exchange_rate)?)),
+            WasmPriceSource::VaultShare {
+                vault_address,
+                ..
+            } => Ok(Some(query_share_price(&deps.querier, vault_address)?)),
+            // Rate snapshot of the wrapped price source is taken for the bounded denom
+            WasmPriceSource::Bounded {
+                source,
+                ..
+            } => source.query_exchange_rate(deps, denom),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Metadata of the price source, also available if the price fails validation
+fn query_price_details(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    config: &Config,
+    price_source: &WasmPriceSourceChecked,
+) -> ContractResult<PriceDetails> {
+    let details = match price_source {
+        WasmPriceSource::AstroportSpot {
+            pair_address,
+        } => PriceDetails {
+            transitive_denoms: query_astroport_transitive_denoms(
+                deps,
+                config,
+                pair_address,
+                denom,
+            )?,
+            ..Default::default()
+        },
+        WasmPriceSource::AstroportTwap {
+            pair_address,
+            window_size,
+            tolerance,
+        } => PriceDetails {
+            twap_window: query_astroport_twap_window(deps, env, denom, *window_size, *tolerance)
+                .ok(),
+            transitive_denoms: query_astroport_transitive_denoms(
+                deps,
+                config,
+                pair_address,
+                denom,
+            )?,
+            ..Default::default()
+        },
+        WasmPriceSource::Pyth {
+            contract_addr,
+            price_feed_id,
+            max_staleness,
+            ..
+        } => query_pyth_price_details(deps, contract_addr.clone(), *price_feed_id, *max_staleness)?,
+        WasmPriceSource::Lsd {
+            transitive_denom,
+            twap,
+            redemption_rate,
+        } => {
+            let rr = query_redemption_rate(
+                &deps.querier,
+                redemption_rate.contract_addr.clone(),
+                denom.to_string(),
+            )?;
+            PriceDetails {
+                publish_time: Some(rr.update_time),
+                max_staleness: Some(redemption_rate.max_staleness),
+                twap_window: query_astroport_twap_window(
+                    deps,
+                    env,
+                    denom,
+                    twap.window_size,
+                    twap.tolerance,
+                )
+                .ok(),
+                transitive_denoms: vec![transitive_denom.clone()],
+                ..Default::default()
+            }
+        }
+        WasmPriceSource::RedemptionRate {
+            transitive_denom,
+            redemption_rate,
+            ..
+        } => {
+            let rr = query_redemption_rate(
+                &deps.querier,
+                redemption_rate.contract_addr.clone(),
+                denom.to_string(),
+            )?;
+            PriceDetails {
+                publish_time: Some(rr.update_time),
+                max_staleness: Some(redemption_rate.max_staleness),
+                transitive_denoms: vec![transitive_denom.clone()],
+                ..Default::default()
+            }
+        }
+        WasmPriceSource::ExchangeRate {
+            transitive_denom,
+            ..
+        } => PriceDetails {
+            transitive_denoms: vec![transitive_denom.clone()],
+            ..Default::default()
+        },
+        WasmPriceSource::VaultShare {
+            base_denom,
+            ..
+        } => PriceDetails {
+            transitive_denoms: vec![base_denom.clone()],
+            ..Default::default()
+        },
+        // The details of the wrapped price source
+        WasmPriceSource::Bounded {
+            source,
+            ..
+        } => query_price_details(deps, env, denom, config, source)?,
+        // The pair assets are priced with their own price sources
+        WasmPriceSource::XykLiquidityToken {
+            pair_address,
+        }
+        | WasmPriceSource::PclLiquidityToken {
+            pair_address,
+        }
+        | WasmPriceSource::SsLiquidityToken {
+            pair_address,
+        } => PriceDetails {
+            transitive_denoms: get_astroport_pair_denoms(&query_astroport_pair_info(
+                &deps.querier,
+                pair_address,
+            )?)?,
+            ..Default::default()
+        },
+        // The denoms consulted by any of the sub-sources
+        WasmPriceSource::Aggregate {
+            sources,
+            ..
+        } => {
+            let mut transitive_denoms: Vec<String> = vec![];
+            for source in sources {
+                let details = query_price_details(deps, env, denom, config, source)?;
+                for transitive_denom in details.transitive_denoms {
+                    if !transitive_denoms.contains(&transitive_denom) {
+                        transitive_denoms.push(transitive_denom);
+                    }
+                }
+            }
+            PriceDetails {
+                transitive_denoms,
+                ..Default::default()
+            }
+        }
+        WasmPriceSource::Fixed {
+            ..
+        } => PriceDetails::default(),
+    };
+
+    Ok(details)
+}
+
+/// The other denom of the pair if the price is normalized to the base denom with its price
+fn query_astroport_transitive_denoms(
+    deps: &Deps,
+    config: &Config,
+    pair_address: &Addr,
+    denom: &str,
+) -> ContractResult<Vec<String>> {
+    let pair_info = query_astroport_pair_info(&deps.querier, pair_address)?;
+    let pair_denoms = get_astroport_pair_denoms(&pair_info)?;
+
+    if pair_denoms.contains(&config.base_denom) {
+        Ok(vec![])
+    } else {
+        Ok(vec![get_other_astroport_pair_denom(&pair_denoms, denom)?])
+    }
+}
+
+/// Period between the current block and the TWAP snapshot the TWAP price is calculated from,
+/// which can deviate from the configured window size by up to the tolerance
+fn query_astroport_twap_window(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    window_size: u64,
+    tolerance: u64,
+) -> ContractResult<u64> {
+    let current_time = env.block.time.seconds();
+
+    ASTROPORT_TWAP_SNAPSHOTS
+        .may_load(deps.storage, denom)?
+        .unwrap_or_default()
+        .iter()
+        .map(|snapshot| current_time - snapshot.timestamp)
+        .find(|period| period.abs_diff(window_size) <= tolerance)
+        .ok_or(ContractError::NoSnapshotWithinTolerance {})
 }
 
 /// Queries the spot price of `denom` denominated in `base_denom` from the Astroport pair at `pair_address`.
@@ -825,6 +1475,74 @@ fn query_ss_liquidity_token_price(
     )
 }
 
+/// Price of a rate based price source quoted in USD.
+///
+/// token/USD = token/Asset rate * Asset/USD
+#[allow(clippy::too_many_arguments)]
+fn query_rate_based_price(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    rate: Decimal,
+    max_rate_jump_per_day: Decimal,
+    transitive_denom: &str,
+    config: &Config,
+    price_sources: &Map<&str, WasmPriceSourceChecked>,
+    kind: ActionKind,
+) -> ContractResult<Decimal> {
+    assert_rate_growth(deps.storage, denom, rate, max_rate_jump_per_day, env.block.time.seconds())?;
+
+    // use current price source
+    let transitive_price = price_sources.load(deps.storage, transitive_denom)?.query_price(
+        deps,
+        env,
+        transitive_denom,
+        config,
+        price_sources,
+        kind,
+    )?;
+
+    rate.checked_mul(transitive_price).map_err(Into::into)
+}
+
+/// Vault share price quoted in USD.
+///
+/// share/USD = share/base_token * base_token/USD
+#[allow(clippy::too_many_arguments)]
+fn query_vault_share_price(
+    deps: &Deps,
+    env: &Env,
+    denom: &str,
+    vault_address: &Addr,
+    base_denom: &str,
+    share_price_bounds: &SharePriceBounds,
+    max_rate_jump_per_day: Decimal,
+    config: &Config,
+    price_sources: &Map<&str, WasmPriceSourceChecked>,
+    kind: ActionKind,
+) -> ContractResult<Decimal> {
+    let share_price = query_bounded_share_price(&deps.querier, vault_address, share_price_bounds)?;
+    assert_rate_growth(
+        deps.storage,
+        denom,
+        share_price,
+        max_rate_jump_per_day,
+        env.block.time.seconds(),
+    )?;
+
+    // use current price source
+    let base_price = price_sources.load(deps.storage, base_denom)?.query_price(
+        deps,
+        env,
+        base_denom,
+        config,
+        price_sources,
+        kind,
+    )?;
+
+    share_price.checked_mul(base_price).map_err(Into::into)
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
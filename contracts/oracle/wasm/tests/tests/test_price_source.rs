@@ -16,19 +16,22 @@ use cw_it::{
     traits::{CwItRunner, DEFAULT_COIN_AMOUNT},
 };
 use cw_storage_plus::Map;
-use mars_oracle_base::{redemption_rate::RedemptionRate, ContractError, PriceSourceUnchecked};
+use mars_oracle_base::{
+    lp_pricing::compute_pcl_lp_price, redemption_rate::RedemptionRate, ContractError,
+    PriceSourceUnchecked,
+};
 use mars_oracle_wasm::{
     contract::entry::{self, execute},
+    lp_pricing::compute_ss_lp_price,
     AstroportTwap, WasmPriceSource, WasmPriceSourceChecked, WasmPriceSourceUnchecked,
 };
-use mars_types::oracle::{ExecuteMsg, PriceResponse, QueryMsg};
+use mars_types::oracle::{ExecuteMsg, PriceDetailedResponse, PriceResponse, QueryMsg};
 use pyth_sdk_cw::PriceIdentifier;
 
 const ONE: Decimal = Decimal::one();
 const TWO: Decimal = Decimal::new(Uint128::new(2_000_000_000_000_000_000u128));
 const DEFAULT_LIQ: [u128; 2] = [10000000000000000000000u128, 1000000000000000000000u128];
 
-use mars_oracle_wasm::lp_pricing::{compute_pcl_lp_price, compute_ss_lp_price};
 use mars_testing::{
     mock_env_at_block_time, mock_info,
     test_runner::get_test_runner,
@@ -659,6 +662,97 @@ fn querying_pyth_price_successfully() {
     assert_eq!(res.price, Decimal::from_ratio(102000u128, 1u128));
 }
 
+#[test]
+fn querying_pyth_price_detailed() {
+    let owned_runner = get_test_runner();
+    let runner = owned_runner.as_ref();
+    let robot = WasmOracleTestRobot::new(
+        &runner,
+        get_contracts(&runner),
+        &get_test_runner().init_default_account().unwrap(),
+        None,
+    );
+
+    let mut deps = helpers::setup_test(&robot.astroport_contracts.factory.address);
+
+    // price source used to convert USD to base_denom
+    helpers::set_price_source(
+        deps.as_mut(),
+        "usd",
+        WasmPriceSourceUnchecked::Fixed {
+            price: Decimal::from_str("1000000").unwrap(),
+        },
+    );
+
+    let price_id = PriceIdentifier::from_hex(
+        "61226d39beea19d334f17c2febce27e12646d84675924ebb02b9cdaea68727e3",
+    )
+    .unwrap();
+
+    let max_staleness = 30u64;
+    helpers::set_price_source(
+        deps.as_mut(),
+        "uatom",
+        WasmPriceSourceUnchecked::Pyth {
+            contract_addr: "pyth_contract_addr".to_string(),
+            price_feed_id: price_id,
+            max_staleness,
+            max_confidence: Decimal::percent(12),
+            max_deviation: Decimal::percent(14),
+            denom_decimals: 6,
+        },
+    );
+
+    let publish_time = 1677157333u64;
+    deps.querier.set_pyth_price(
+        price_id,
+        PriceFeedResponse {
+            price_feed: PriceFeed::new(
+                price_id,
+                Price {
+                    price: 1021000,
+                    conf: 50000,
+                    expo: -4,
+                    publish_time: publish_time as i64,
+                },
+                Price {
+                    price: 1000000,
+                    conf: 40000,
+                    expo: -4,
+                    publish_time: publish_time as i64,
+                },
+            ),
+        },
+    );
+
+    let res = entry::query(
+        deps.as_ref(),
+        mock_env_at_block_time(publish_time + 10),
+        QueryMsg::PriceDetailed {
+            denom: "uatom".to_string(),
+            kind: None,
+        },
+    )
+    .unwrap();
+    let res: PriceDetailedResponse = from_json(res).unwrap();
+    assert_eq!(
+        res,
+        PriceDetailedResponse {
+            denom: "uatom".to_string(),
+            price: Some(Decimal::from_ratio(1021000u128, 10000u128)),
+            error: None,
+            price_source: format!("pyth:pyth_contract_addr:{price_id}:30:0.12:0.14:6"),
+            publish_time: Some(publish_time),
+            age: Some(10),
+            max_staleness: Some(max_staleness),
+            confidence: Some(Decimal::percent(5)),
+            ema_deviation: Some(Decimal::permille(21)),
+            twap_window: None,
+            transitive_denoms: vec!["usd".to_string()],
+        }
+    );
+}
+
 #[test]
 fn setting_price_source_pyth_if_missing_usd() {
     let owned_runner = get_test_runner();
@@ -16,11 +16,17 @@ use crate::{
         update_vault_config,
     },
     migrations,
+    pause::{
+        check_price_movement, is_paused, pause, query_all_pauses, unpause, update_auto_pause_config,
+    },
     query::{
         query_all_asset_params, query_all_total_deposits_v2, query_all_vault_configs,
         query_all_vault_configs_v2, query_config, query_total_deposit, query_vault_config,
     },
-    state::{ADDRESS_PROVIDER, ASSET_PARAMS, OWNER, TARGET_HEALTH_FACTOR},
+    state::{
+        ADDRESS_PROVIDER, ASSET_PARAMS, AUTO_PAUSE_CONFIGS, OWNER, PRICE_CHECKPOINTS,
+        TARGET_HEALTH_FACTOR,
+    },
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -55,7 +61,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> ContractResult<Response> {
@@ -80,7 +86,16 @@ pub fn execute(
                     set_zero_deposit_cap(deps, info, &v)
                 }
             },
+            EmergencyUpdate::Pause(update) => pause(deps, env, info, update),
         },
+        ExecuteMsg::Unpause(update) => unpause(deps, info, update),
+        ExecuteMsg::UpdateAutoPauseConfig {
+            denom,
+            config,
+        } => update_auto_pause_config(deps, info, denom, config),
+        ExecuteMsg::CheckPriceMovement {
+            denom,
+        } => check_price_movement(deps, env, denom),
     }
 }
 
@@ -117,6 +132,17 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_after,
             limit,
         } => to_json_binary(&query_all_total_deposits_v2(deps, start_after, limit)?),
+        QueryMsg::IsPaused {
+            contract,
+            action,
+        } => to_json_binary(&is_paused(deps.storage, contract, action)?),
+        QueryMsg::AllPauses {} => to_json_binary(&query_all_pauses(deps)?),
+        QueryMsg::AutoPauseConfig {
+            denom,
+        } => to_json_binary(&AUTO_PAUSE_CONFIGS.may_load(deps.storage, &denom)?),
+        QueryMsg::PriceCheckpoint {
+            denom,
+        } => to_json_binary(&PRICE_CHECKPOINTS.may_load(deps.storage, &denom)?),
     };
     res.map_err(Into::into)
 }
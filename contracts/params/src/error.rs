@@ -1,4 +1,4 @@
-use cosmwasm_std::{DecimalRangeExceeded, StdError};
+use cosmwasm_std::{CheckedFromRatioError, DecimalRangeExceeded, StdError};
 use cw2::VersionError;
 use mars_owner::OwnerError;
 use mars_types::error::MarsError;
@@ -15,6 +15,9 @@ pub enum ContractError {
     #[error("{0}")]
     DecimalRangeExceeded(#[from] DecimalRangeExceeded),
 
+    #[error("{0}")]
+    CheckedFromRatio(#[from] CheckedFromRatioError),
+
     #[error("{0}")]
     Owner(#[from] OwnerError),
 
@@ -26,4 +29,9 @@ pub enum ContractError {
 
     #[error("{0}")]
     Version(#[from] VersionError),
+
+    #[error("Auto pause is not configured for {denom}")]
+    AutoPauseNotConfigured {
+        denom: String,
+    },
 }
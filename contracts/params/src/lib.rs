@@ -3,5 +3,6 @@ pub mod emergency_powers;
 pub mod error;
 pub mod execute;
 pub mod migrations;
+pub mod pause;
 pub mod query;
 pub mod state;
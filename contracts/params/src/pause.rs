@@ -0,0 +1,216 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+use mars_types::{
+    adapters::oracle::Oracle,
+    address_provider::{self, MarsAddressType},
+    oracle::ActionKind,
+    params::{
+        AutoPauseConfig, PausableAction, PausableContract, PauseInfo, PauseScope, PauseUpdate,
+        PriceCheckpoint,
+    },
+};
+use mars_utils::error::ValidationError;
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{ADDRESS_PROVIDER, AUTO_PAUSE_CONFIGS, OWNER, PAUSES, PRICE_CHECKPOINTS},
+};
+
+pub fn pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    update: PauseUpdate,
+) -> ContractResult<Response> {
+    OWNER.assert_emergency_owner(deps.storage, &info.sender)?;
+
+    pause_actions(deps.storage, &env, &info.sender, update.scope, &update.actions)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "emergency_pause")
+        .add_attribute("scope", update.scope.to_string())
+        .add_attribute("actions", actions_to_string(&update.actions)))
+}
+
+pub fn unpause(deps: DepsMut, info: MessageInfo, update: PauseUpdate) -> ContractResult<Response> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    let scope = update.scope.to_string();
+    for action in update.actions.iter() {
+        PAUSES.remove(deps.storage, (&scope, &action.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("scope", scope)
+        .add_attribute("actions", actions_to_string(&update.actions)))
+}
+
+pub fn update_auto_pause_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    config: Option<AutoPauseConfig>,
+) -> ContractResult<Response> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    let response = Response::new()
+        .add_attribute("action", "update_auto_pause_config")
+        .add_attribute("denom", denom.clone());
+
+    match config {
+        Some(config) => {
+            assert_auto_pause_config(&config)?;
+            AUTO_PAUSE_CONFIGS.save(deps.storage, &denom, &config)?;
+            Ok(response
+                .add_attribute("max_price_change", config.max_price_change.to_string())
+                .add_attribute("window", config.window.to_string())
+                .add_attribute("actions", actions_to_string(&config.actions)))
+        }
+        None => {
+            AUTO_PAUSE_CONFIGS.remove(deps.storage, &denom);
+            PRICE_CHECKPOINTS.remove(deps.storage, &denom);
+            Ok(response.add_attribute("action_type", "remove"))
+        }
+    }
+}
+
+/// The price is compared with the last checkpoint, also if its window has already passed. A new
+/// checkpoint is only recorded once the window of the previous one has passed (or after a pause was
+/// triggered), so frequent calls can't move the reference price along with the market.
+pub fn check_price_movement(deps: DepsMut, env: Env, denom: String) -> ContractResult<Response> {
+    let Some(config) = AUTO_PAUSE_CONFIGS.may_load(deps.storage, &denom)? else {
+        return Err(ContractError::AutoPauseNotConfigured {
+            denom,
+        });
+    };
+
+    let address_provider = ADDRESS_PROVIDER.load(deps.storage)?;
+    let oracle_addr = address_provider::helpers::query_contract_addr(
+        deps.as_ref(),
+        &address_provider,
+        MarsAddressType::Oracle,
+    )?;
+    let price =
+        Oracle::new(oracle_addr).query_price(&deps.querier, &denom, ActionKind::Default)?.price;
+
+    let now = env.block.time.seconds();
+    let checkpoint = PriceCheckpoint {
+        price,
+        timestamp: now,
+    };
+
+    let mut response = Response::new()
+        .add_attribute("action", "check_price_movement")
+        .add_attribute("denom", denom.clone())
+        .add_attribute("price", price.to_string());
+
+    let last =
+        PRICE_CHECKPOINTS.may_load(deps.storage, &denom)?.filter(|last| !last.price.is_zero());
+
+    // An expired checkpoint is still compared with, so a move that happened while nobody checked
+    // the price is caught before the reference price is replaced.
+    let mut paused = false;
+    if let Some(last) = &last {
+        let price_change = price.abs_diff(last.price).checked_div(last.price)?;
+        response = response.add_attribute("price_change", price_change.to_string());
+
+        if price_change > config.max_price_change {
+            pause_actions(
+                deps.storage,
+                &env,
+                &env.contract.address,
+                PauseScope::Global,
+                &config.actions,
+            )?;
+            paused = true;
+            response = response
+                .add_attribute("paused", "true")
+                .add_attribute("actions", actions_to_string(&config.actions));
+        }
+    }
+
+    let expired = match last {
+        Some(last) => now - last.timestamp >= config.window,
+        None => true,
+    };
+    if paused || expired {
+        PRICE_CHECKPOINTS.save(deps.storage, &denom, &checkpoint)?;
+        response = response.add_attribute("checkpoint", "true");
+    }
+
+    Ok(response)
+}
+
+pub fn is_paused(
+    storage: &dyn Storage,
+    contract: PausableContract,
+    action: PausableAction,
+) -> StdResult<bool> {
+    let action = action.to_string();
+    Ok(PAUSES.has(storage, (&PauseScope::Global.to_string(), &action))
+        || PAUSES.has(storage, (&contract.to_string(), &action)))
+}
+
+pub fn query_all_pauses(deps: Deps) -> StdResult<Vec<PauseInfo>> {
+    PAUSES.range(deps.storage, None, None, Order::Ascending).map(|res| Ok(res?.1)).collect()
+}
+
+/// Already paused actions keep their original pause info
+fn pause_actions(
+    storage: &mut dyn Storage,
+    env: &Env,
+    paused_by: &Addr,
+    scope: PauseScope,
+    actions: &[PausableAction],
+) -> StdResult<()> {
+    let scope_key = scope.to_string();
+    for action in actions {
+        let action_key = action.to_string();
+        if PAUSES.has(storage, (&scope_key, &action_key)) {
+            continue;
+        }
+        PAUSES.save(
+            storage,
+            (&scope_key, &action_key),
+            &PauseInfo {
+                scope,
+                action: *action,
+                paused_at: env.block.time.seconds(),
+                paused_by: paused_by.to_string(),
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn assert_auto_pause_config(config: &AutoPauseConfig) -> Result<(), ValidationError> {
+    if config.max_price_change.is_zero() {
+        return Err(ValidationError::InvalidParam {
+            param_name: "max_price_change".to_string(),
+            invalid_value: config.max_price_change.to_string(),
+            predicate: "> 0".to_string(),
+        });
+    }
+
+    if config.window == 0 {
+        return Err(ValidationError::InvalidParam {
+            param_name: "window".to_string(),
+            invalid_value: config.window.to_string(),
+            predicate: "> 0".to_string(),
+        });
+    }
+
+    if config.actions.is_empty() {
+        return Err(ValidationError::InvalidParam {
+            param_name: "actions".to_string(),
+            invalid_value: "[]".to_string(),
+            predicate: "not empty".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn actions_to_string(actions: &[PausableAction]) -> String {
+    actions.iter().map(|action| action.to_string()).collect::<Vec<_>>().join(",")
+}
@@ -11,6 +11,7 @@ pub fn default_asset_params(denom: &str) -> AssetParamsUnchecked {
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -23,9 +24,11 @@ pub fn default_asset_params(denom: &str) -> AssetParamsUnchecked {
             slope: Decimal::from_str("2.0").unwrap(),
             min_lb: Decimal::percent(1),
             max_lb: Decimal::percent(8),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2),
         deposit_cap: Uint128::new(1_000_000_000),
+        min_debt_value: Uint128::zero(),
     }
 }
 
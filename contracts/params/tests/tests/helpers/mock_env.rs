@@ -6,8 +6,9 @@ use cw_multi_test::{App, AppResponse, BasicApp, Executor};
 use cw_paginate::PaginationResponse;
 use mars_owner::{OwnerResponse, OwnerUpdate};
 use mars_types::params::{
-    AssetParams, AssetParamsUpdate, ConfigResponse, EmergencyUpdate, ExecuteMsg, InstantiateMsg,
-    QueryMsg, VaultConfig, VaultConfigUpdate,
+    AssetParams, AssetParamsUpdate, AutoPauseConfig, ConfigResponse, EmergencyUpdate, ExecuteMsg,
+    InstantiateMsg, PausableAction, PausableContract, PauseInfo, PauseUpdate, QueryMsg,
+    VaultConfig, VaultConfigUpdate,
 };
 
 use super::contracts::mock_params_contract;
@@ -113,6 +114,32 @@ impl MockEnv {
         )
     }
 
+    pub fn unpause(&mut self, sender: &Addr, update: PauseUpdate) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params_contract.clone(),
+            &ExecuteMsg::Unpause(update),
+            &[],
+        )
+    }
+
+    pub fn update_auto_pause_config(
+        &mut self,
+        sender: &Addr,
+        denom: &str,
+        config: Option<AutoPauseConfig>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params_contract.clone(),
+            &ExecuteMsg::UpdateAutoPauseConfig {
+                denom: denom.to_string(),
+                config,
+            },
+            &[],
+        )
+    }
+
     //--------------------------------------------------------------------------------------------------
     // Queries
     //--------------------------------------------------------------------------------------------------
@@ -208,6 +235,38 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_is_paused(&self, contract: PausableContract, action: PausableAction) -> bool {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params_contract.clone(),
+                &QueryMsg::IsPaused {
+                    contract,
+                    action,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_all_pauses(&self) -> Vec<PauseInfo> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.params_contract.clone(), &QueryMsg::AllPauses {})
+            .unwrap()
+    }
+
+    pub fn query_auto_pause_config(&self, denom: &str) -> Option<AutoPauseConfig> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params_contract.clone(),
+                &QueryMsg::AutoPauseConfig {
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_config(&self) -> ConfigResponse {
         self.app
             .wrap()
@@ -6,6 +6,7 @@ mod test_deposit_cap;
 mod test_emergency_powers;
 mod test_migration_v2;
 mod test_owner;
+mod test_pause;
 mod test_query_all_vault_configs_v2;
 mod test_target_health_factor;
 mod test_update_asset_params;
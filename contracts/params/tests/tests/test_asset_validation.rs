@@ -4,7 +4,7 @@ use cosmwasm_std::Decimal;
 use mars_params::error::ContractError;
 use mars_types::{
     error::MarsError::Validation,
-    params::{AssetParamsUpdate, HlsAssetType, HlsParamsUnchecked},
+    params::{AssetParamsUpdate, DutchAuction, HlsAssetType, HlsParamsUnchecked},
 };
 use mars_utils::error::ValidationError::{InvalidDenom, InvalidParam};
 
@@ -364,3 +364,54 @@ fn liquidation_bonus_param_max_lb_gt_min_lb() {
         })),
     );
 }
+
+#[test]
+fn dutch_auction_duration_greater_than_zero() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let mut params = default_asset_params("denom_xyz");
+    params.liquidation_bonus.dutch_auction = Some(DutchAuction {
+        duration_blocks: 0,
+    });
+
+    let res = mock.update_asset_params(
+        &mock.query_owner(),
+        AssetParamsUpdate::AddOrUpdate {
+            params,
+        },
+    );
+    assert_err(
+        res,
+        ContractError::Mars(Validation(InvalidParam {
+            param_name: "duration_blocks".to_string(),
+            invalid_value: "0".to_string(),
+            predicate: "> 0".to_string(),
+        })),
+    );
+}
+
+#[test]
+fn dutch_auction_max_lb_ge_starting_lb() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let mut params = default_asset_params("denom_xyz");
+    params.liquidation_bonus.starting_lb = Decimal::from_str("0.09").unwrap();
+    params.liquidation_bonus.min_lb = Decimal::from_str("0.02").unwrap();
+    params.liquidation_bonus.max_lb = Decimal::from_str("0.08").unwrap();
+    params.liquidation_bonus.dutch_auction = Some(DutchAuction {
+        duration_blocks: 100,
+    });
+
+    let res = mock.update_asset_params(
+        &mock.query_owner(),
+        AssetParamsUpdate::AddOrUpdate {
+            params,
+        },
+    );
+    assert_err(
+        res,
+        ContractError::Mars(Validation(InvalidParam {
+            param_name: "max_lb".to_string(),
+            invalid_value: "0.08".to_string(),
+            predicate: ">= 0.09 (starting LB)".to_string(),
+        })),
+    );
+}
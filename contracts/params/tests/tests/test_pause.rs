@@ -0,0 +1,186 @@
+use cosmwasm_std::{Addr, Decimal};
+use mars_owner::OwnerError;
+use mars_params::error::ContractError::{self, Owner};
+use mars_types::params::{
+    AutoPauseConfig, EmergencyUpdate, PausableAction, PausableContract, PauseInfo, PauseScope,
+    PauseUpdate,
+};
+use mars_utils::error::ValidationError;
+
+use super::helpers::{assert_err, MockEnv};
+
+#[test]
+fn only_emergency_owner_can_pause() {
+    let mut mock = MockEnv::new().emergency_owner("miles_morales").build().unwrap();
+    let owner = mock.query_owner();
+
+    let res = mock.emergency_update(
+        &owner,
+        EmergencyUpdate::Pause(PauseUpdate {
+            scope: PauseScope::Global,
+            actions: PausableAction::all(),
+        }),
+    );
+    assert_err(res, Owner(OwnerError::NotEmergencyOwner {}));
+}
+
+#[test]
+fn only_owner_can_unpause() {
+    let emergency_owner = Addr::unchecked("miles_morales");
+    let mut mock = MockEnv::new().emergency_owner(emergency_owner.as_str()).build().unwrap();
+
+    let res = mock.unpause(
+        &emergency_owner,
+        PauseUpdate {
+            scope: PauseScope::Global,
+            actions: PausableAction::all(),
+        },
+    );
+    assert_err(res, Owner(OwnerError::NotOwner {}));
+}
+
+#[test]
+fn pausing_and_unpausing() {
+    let emergency_owner = Addr::unchecked("miles_morales");
+    let mut mock = MockEnv::new().emergency_owner(emergency_owner.as_str()).build().unwrap();
+    let owner = mock.query_owner();
+
+    mock.emergency_update(
+        &emergency_owner,
+        EmergencyUpdate::Pause(PauseUpdate {
+            scope: PauseScope::Global,
+            actions: vec![PausableAction::Borrows],
+        }),
+    )
+    .unwrap();
+    mock.emergency_update(
+        &emergency_owner,
+        EmergencyUpdate::Pause(PauseUpdate {
+            scope: PauseScope::Contract(PausableContract::CreditManager),
+            actions: vec![PausableAction::Swaps, PausableAction::VaultEntries],
+        }),
+    )
+    .unwrap();
+
+    // global pauses apply to every contract
+    for contract in [
+        PausableContract::RedBank,
+        PausableContract::CreditManager,
+        PausableContract::Incentives,
+        PausableContract::ManagedVault,
+    ] {
+        assert!(mock.query_is_paused(contract, PausableAction::Borrows));
+        assert!(!mock.query_is_paused(contract, PausableAction::Deposits));
+    }
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Swaps));
+    assert!(!mock.query_is_paused(PausableContract::RedBank, PausableAction::Swaps));
+
+    let pauses = mock.query_all_pauses();
+    assert_eq!(pauses.len(), 3);
+    assert!(pauses.contains(&PauseInfo {
+        scope: PauseScope::Global,
+        action: PausableAction::Borrows,
+        paused_at: mock.app.block_info().time.seconds(),
+        paused_by: emergency_owner.to_string(),
+    }));
+
+    // unpausing a contract doesn't lift the global pause
+    mock.unpause(
+        &owner,
+        PauseUpdate {
+            scope: PauseScope::Contract(PausableContract::CreditManager),
+            actions: PausableAction::all(),
+        },
+    )
+    .unwrap();
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Swaps));
+    assert!(mock.query_is_paused(PausableContract::CreditManager, PausableAction::Borrows));
+
+    mock.unpause(
+        &owner,
+        PauseUpdate {
+            scope: PauseScope::Global,
+            actions: vec![PausableAction::Borrows],
+        },
+    )
+    .unwrap();
+    assert!(!mock.query_is_paused(PausableContract::CreditManager, PausableAction::Borrows));
+    assert!(mock.query_all_pauses().is_empty());
+}
+
+#[test]
+fn auto_pause_config_validation() {
+    let mut mock = MockEnv::new().build().unwrap();
+    let owner = mock.query_owner();
+    let denom = "uatom";
+
+    let valid_config = AutoPauseConfig {
+        max_price_change: Decimal::percent(15),
+        window: 600,
+        actions: vec![PausableAction::Borrows],
+    };
+
+    let res = mock.update_auto_pause_config(
+        &Addr::unchecked("doctor_otto_983"),
+        denom,
+        Some(valid_config.clone()),
+    );
+    assert_err(res, Owner(OwnerError::NotOwner {}));
+
+    let res = mock.update_auto_pause_config(
+        &owner,
+        denom,
+        Some(AutoPauseConfig {
+            max_price_change: Decimal::zero(),
+            ..valid_config.clone()
+        }),
+    );
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "max_price_change".to_string(),
+            invalid_value: "0".to_string(),
+            predicate: "> 0".to_string(),
+        }),
+    );
+
+    let res = mock.update_auto_pause_config(
+        &owner,
+        denom,
+        Some(AutoPauseConfig {
+            window: 0,
+            ..valid_config.clone()
+        }),
+    );
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "window".to_string(),
+            invalid_value: "0".to_string(),
+            predicate: "> 0".to_string(),
+        }),
+    );
+
+    let res = mock.update_auto_pause_config(
+        &owner,
+        denom,
+        Some(AutoPauseConfig {
+            actions: vec![],
+            ..valid_config.clone()
+        }),
+    );
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "actions".to_string(),
+            invalid_value: "[]".to_string(),
+            predicate: "not empty".to_string(),
+        }),
+    );
+
+    mock.update_auto_pause_config(&owner, denom, Some(valid_config.clone())).unwrap();
+    assert_eq!(mock.query_auto_pause_config(denom), Some(valid_config));
+
+    mock.update_auto_pause_config(&owner, denom, None).unwrap();
+    assert_eq!(mock.query_auto_pause_config(denom), None);
+}
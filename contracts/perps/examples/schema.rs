@@ -0,0 +1,10 @@
+use cosmwasm_schema::write_api;
+use mars_types::perps::{ExecuteMsg, InstantiateMsg, QueryMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
+use cw2::set_contract_version;
+use mars_owner::OwnerInit::SetInitialOwner;
+use mars_types::perps::{Config, ExecuteMsg, InstantiateMsg, QueryMsg, VaultState};
+use mars_utils::helpers::validate_native_denom;
+
+use crate::{
+    error::ContractResult,
+    market::update_market,
+    position::{
+        close_position, execute_order, query_close_position_settlement, query_order_settlement,
+        query_position, query_positions,
+    },
+    query::{query_market, query_markets},
+    state::{CONFIG, OWNER, VAULT_STATE},
+    vault::{deposit, query_vault_position, unlock, withdraw},
+};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    msg: InstantiateMsg,
+) -> ContractResult<Response> {
+    set_contract_version(deps.storage, format!("crates.io:{CONTRACT_NAME}"), CONTRACT_VERSION)?;
+
+    OWNER.initialize(
+        deps.storage,
+        deps.api,
+        SetInitialOwner {
+            owner: msg.owner,
+        },
+    )?;
+
+    validate_native_denom(&msg.base_denom)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            address_provider: deps.api.addr_validate(&msg.address_provider)?,
+            credit_manager: deps.api.addr_validate(&msg.credit_manager)?,
+            base_denom: msg.base_denom,
+            cooldown_period: msg.cooldown_period,
+        },
+    )?;
+    VAULT_STATE.save(deps.storage, &VaultState::default())?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> ContractResult<Response> {
+    match msg {
+        ExecuteMsg::UpdateOwner(update) => Ok(OWNER.update(deps, info, update)?),
+        ExecuteMsg::UpdateMarket(params) => update_market(deps, env, info, params),
+        ExecuteMsg::Deposit {} => deposit(deps, info),
+        ExecuteMsg::Unlock {
+            shares,
+        } => unlock(deps, env, info, shares),
+        ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+        ExecuteMsg::ExecuteOrder {
+            account_id,
+            denom,
+            size,
+            reduce_only,
+        } => execute_order(deps, env, info, account_id, denom, size, reduce_only),
+        ExecuteMsg::ClosePosition {
+            account_id,
+            denom,
+        } => close_position(deps, env, info, account_id, denom),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
+    let res = match msg {
+        QueryMsg::Owner {} => to_json_binary(&OWNER.query(deps.storage)?),
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Market {
+            denom,
+        } => to_json_binary(&query_market(deps, &env, denom)?),
+        QueryMsg::Markets {
+            start_after,
+            limit,
+        } => to_json_binary(&query_markets(deps, &env, start_after, limit)?),
+        QueryMsg::Vault {} => to_json_binary(&VAULT_STATE.load(deps.storage)?),
+        QueryMsg::VaultPosition {
+            user,
+        } => to_json_binary(&query_vault_position(deps, user)?),
+        QueryMsg::Position {
+            account_id,
+            denom,
+        } => to_json_binary(&query_position(deps, &env, &account_id, &denom)?),
+        QueryMsg::Positions {
+            account_id,
+        } => to_json_binary(&query_positions(deps, &env, &account_id)?),
+        QueryMsg::OrderSettlement {
+            account_id,
+            denom,
+            size,
+            reduce_only,
+        } => to_json_binary(&query_order_settlement(
+            deps,
+            &env,
+            &account_id,
+            &denom,
+            size,
+            reduce_only,
+        )?),
+        QueryMsg::ClosePositionSettlement {
+            account_id,
+            denom,
+        } => to_json_binary(&query_close_position_settlement(deps, &env, &account_id, &denom)?),
+    };
+    res.map_err(Into::into)
+}
@@ -0,0 +1,101 @@
+use cosmwasm_std::{
+    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
+    ConversionOverflowError, DecimalRangeExceeded, Int128, OverflowError, StdError, Uint128,
+};
+use cw_utils::PaymentError;
+use mars_owner::OwnerError;
+use mars_utils::error::ValidationError;
+use thiserror::Error;
+
+pub type ContractResult<T> = Result<T, ContractError>;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    ConversionOverflow(#[from] ConversionOverflowError),
+
+    #[error("{0}")]
+    CheckedFromRatio(#[from] CheckedFromRatioError),
+
+    #[error("{0}")]
+    CheckedMultiplyFraction(#[from] CheckedMultiplyFractionError),
+
+    #[error("{0}")]
+    CheckedMultiplyRatio(#[from] CheckedMultiplyRatioError),
+
+    #[error("{0}")]
+    DecimalRangeExceeded(#[from] DecimalRangeExceeded),
+
+    #[error("{0}")]
+    Owner(#[from] OwnerError),
+
+    #[error("{0}")]
+    Validation(#[from] ValidationError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Sender is not the Credit Manager")]
+    SenderNotCreditManager,
+
+    #[error("Market not found: {denom}")]
+    MarketNotFound {
+        denom: String,
+    },
+
+    #[error("Account {account_id} has no position in market {denom}")]
+    PositionNotFound {
+        account_id: String,
+        denom: String,
+    },
+
+    #[error("Market {denom} is disabled, positions can only be reduced or closed")]
+    MarketDisabled {
+        denom: String,
+    },
+
+    #[error("Order size can't be zero")]
+    ZeroOrderSize,
+
+    #[error("Reduce only order can't increase or open a position in {denom}")]
+    ReduceOnlyIncreasesPosition {
+        denom: String,
+    },
+
+    #[error("Open interest cap exceeded for {denom}: {oi} > {max_oi}")]
+    OpenInterestCapExceeded {
+        denom: String,
+        oi: Uint128,
+        max_oi: Uint128,
+    },
+
+    #[error(
+        "Invalid payment for settlement of {settlement}: expected {expected}, received {received}"
+    )]
+    InvalidSettlementPayment {
+        settlement: Int128,
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Insufficient vault liquidity: {available} available, {required} required")]
+    InsufficientVaultLiquidity {
+        available: Uint128,
+        required: Uint128,
+    },
+
+    #[error("Insufficient vault shares: {available} available, {requested} requested")]
+    InsufficientShares {
+        available: Uint128,
+        requested: Uint128,
+    },
+
+    #[error("No unlocked shares to withdraw")]
+    NoUnlockedShares,
+}
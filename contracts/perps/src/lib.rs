@@ -0,0 +1,7 @@
+pub mod contract;
+pub mod error;
+pub mod market;
+pub mod position;
+pub mod query;
+pub mod state;
+pub mod vault;
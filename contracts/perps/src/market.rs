@@ -0,0 +1,103 @@
+use cosmwasm_std::{Decimal, Deps, DepsMut, Env, MessageInfo, Response};
+use mars_types::{
+    adapters::oracle::Oracle,
+    address_provider::{self, MarsAddressType},
+    oracle::ActionKind,
+    perps::{Config, MarketParams, MarketState, SECONDS_IN_DAY},
+};
+
+use crate::{
+    error::ContractResult,
+    state::{CONFIG, MARKET_PARAMS, MARKET_STATES, OWNER},
+};
+
+pub fn update_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: MarketParams,
+) -> ContractResult<Response> {
+    OWNER.assert_owner(deps.storage, &info.sender)?;
+
+    params.validate()?;
+
+    let now = env.block.time.seconds();
+    let state = match MARKET_PARAMS.may_load(deps.storage, &params.denom)? {
+        // Funding accrued so far is settled with the old params
+        Some(old_params) => {
+            let config = CONFIG.load(deps.storage)?;
+            let (price, base_price) = query_prices(deps.as_ref(), &config, &params.denom)?;
+            let mut state = MARKET_STATES.load(deps.storage, &params.denom)?;
+            accrue_funding(&old_params, &mut state, now, price, base_price)?;
+            state
+        }
+        None => MarketState {
+            last_updated: now,
+            ..Default::default()
+        },
+    };
+
+    MARKET_PARAMS.save(deps.storage, &params.denom, &params)?;
+    MARKET_STATES.save(deps.storage, &params.denom, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_market")
+        .add_attribute("denom", params.denom.clone())
+        .add_attribute("params", format!("{params:?}")))
+}
+
+/// Returns the oracle prices of the market denom and of the base denom
+pub fn query_prices(
+    deps: Deps,
+    config: &Config,
+    denom: &str,
+) -> ContractResult<(Decimal, Decimal)> {
+    let oracle_addr = address_provider::helpers::query_contract_addr(
+        deps,
+        &config.address_provider,
+        MarsAddressType::Oracle,
+    )?;
+    let oracle = Oracle::new(oracle_addr);
+    let price = oracle.query_price(&deps.querier, denom, ActionKind::Default)?.price;
+    let base_price =
+        oracle.query_price(&deps.querier, &config.base_denom, ActionKind::Default)?.price;
+    Ok((price, base_price))
+}
+
+/// Funding rate per day scales linearly with the open interest skew, up to `max_funding_rate`
+/// at `skew_scale`. The side with the larger open interest pays.
+/// Returns (funding rate, longs pay)
+pub fn funding_rate(params: &MarketParams, state: &MarketState) -> ContractResult<(Decimal, bool)> {
+    let longs_pay = state.long_oi >= state.short_oi;
+    let skew = state.long_oi.abs_diff(state.short_oi);
+    let skew_ratio = Decimal::checked_from_ratio(skew, params.skew_scale)?.min(Decimal::one());
+    Ok((params.max_funding_rate.checked_mul(skew_ratio)?, longs_pay))
+}
+
+/// Accrues funding since the last update into the funding indices, in base denom per unit of size.
+/// The current prices are used for the whole period.
+pub fn accrue_funding(
+    params: &MarketParams,
+    state: &mut MarketState,
+    now: u64,
+    price: Decimal,
+    base_price: Decimal,
+) -> ContractResult<()> {
+    let elapsed = now.saturating_sub(state.last_updated);
+    if elapsed > 0 {
+        let (rate, longs_pay) = funding_rate(params, state)?;
+        if !rate.is_zero() {
+            let per_unit = rate
+                .checked_mul(Decimal::from_ratio(elapsed, SECONDS_IN_DAY))?
+                .checked_mul(price)?
+                .checked_div(base_price)?;
+            if longs_pay {
+                state.longs_pay_index = state.longs_pay_index.checked_add(per_unit)?;
+            } else {
+                state.shorts_pay_index = state.shorts_pay_index.checked_add(per_unit)?;
+            }
+        }
+    }
+    state.last_updated = now;
+    Ok(())
+}
@@ -0,0 +1,464 @@
+use std::cmp::min;
+
+use cosmwasm_std::{
+    CosmosMsg, Decimal, Deps, DepsMut, Env, Int128, Int256, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128,
+};
+use mars_types::perps::{Config, MarketParams, MarketState, PerpPosition, Position, Settlement};
+use mars_utils::helpers::build_send_asset_msg;
+
+use crate::{
+    error::{ContractError, ContractResult},
+    market::{accrue_funding, query_prices},
+    state::{CONFIG, MARKET_PARAMS, MARKET_STATES, POSITIONS, VAULT_STATE},
+};
+
+/// Result of an order executed against the market state accrued to the current block
+#[derive(Debug, PartialEq)]
+pub struct OrderOutcome {
+    /// Position after the order, None if closed
+    pub position: Option<Position>,
+    pub state: MarketState,
+    /// Base denom amount, positive if the account receives it
+    pub settlement: Int128,
+}
+
+pub fn execute_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+    size: Int128,
+    reduce_only: Option<bool>,
+) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_credit_manager(&config, &info)?;
+
+    let outcome = simulate_order(
+        deps.as_ref(),
+        &env,
+        &config,
+        &account_id,
+        &denom,
+        size,
+        reduce_only.unwrap_or(false),
+    )?;
+    save_outcome(deps.storage, &account_id, &denom, &outcome)?;
+    let msgs = settle(deps.storage, &config, &info, outcome.settlement, false)?;
+
+    let new_size = outcome.position.map(|p| p.size).unwrap_or_default();
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "execute_order")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("order_size", size.to_string())
+        .add_attribute("new_size", new_size.to_string())
+        .add_attribute("settlement", outcome.settlement.to_string()))
+}
+
+pub fn close_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account_id: String,
+    denom: String,
+) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_credit_manager(&config, &info)?;
+
+    let outcome = simulate_close(deps.as_ref(), &env, &config, &account_id, &denom)?;
+    save_outcome(deps.storage, &account_id, &denom, &outcome)?;
+    let msgs = settle(deps.storage, &config, &info, outcome.settlement, true)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "close_position")
+        .add_attribute("account_id", account_id)
+        .add_attribute("denom", denom)
+        .add_attribute("settlement", outcome.settlement.to_string()))
+}
+
+pub fn query_position(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<Option<PerpPosition>> {
+    let Some(position) = POSITIONS.may_load(deps.storage, (account_id, denom))? else {
+        return Ok(None);
+    };
+    let config = CONFIG.load(deps.storage)?;
+    Ok(Some(to_perp_position(deps, env, &config, denom, position)?))
+}
+
+pub fn query_positions(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+) -> ContractResult<Vec<PerpPosition>> {
+    let config = CONFIG.load(deps.storage)?;
+    POSITIONS
+        .prefix(account_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| {
+            let (denom, position) = res?;
+            to_perp_position(deps, env, &config, &denom, position)
+        })
+        .collect()
+}
+
+pub fn query_order_settlement(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    denom: &str,
+    size: Int128,
+    reduce_only: Option<bool>,
+) -> ContractResult<Settlement> {
+    let config = CONFIG.load(deps.storage)?;
+    let outcome =
+        simulate_order(deps, env, &config, account_id, denom, size, reduce_only.unwrap_or(false))?;
+    Ok(Settlement {
+        base_denom: config.base_denom,
+        amount: outcome.settlement,
+    })
+}
+
+pub fn query_close_position_settlement(
+    deps: Deps,
+    env: &Env,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<Settlement> {
+    let config = CONFIG.load(deps.storage)?;
+    let outcome = simulate_close(deps, env, &config, account_id, denom)?;
+    Ok(Settlement {
+        base_denom: config.base_denom,
+        amount: outcome.settlement,
+    })
+}
+
+fn simulate_order(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    account_id: &str,
+    denom: &str,
+    size: Int128,
+    reduce_only: bool,
+) -> ContractResult<OrderOutcome> {
+    let params = MARKET_PARAMS.may_load(deps.storage, denom)?.ok_or_else(|| {
+        ContractError::MarketNotFound {
+            denom: denom.to_string(),
+        }
+    })?;
+    let mut state = MARKET_STATES.load(deps.storage, denom)?;
+    let (price, base_price) = query_prices(deps, config, denom)?;
+    accrue_funding(&params, &mut state, env.block.time.seconds(), price, base_price)?;
+
+    let position = POSITIONS.may_load(deps.storage, (account_id, denom))?;
+    compute_order(&params, state, position, size, reduce_only, price, base_price)
+}
+
+fn simulate_close(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    account_id: &str,
+    denom: &str,
+) -> ContractResult<OrderOutcome> {
+    let position = POSITIONS.may_load(deps.storage, (account_id, denom))?.ok_or_else(|| {
+        ContractError::PositionNotFound {
+            account_id: account_id.to_string(),
+            denom: denom.to_string(),
+        }
+    })?;
+    let close_size = Int128::zero().checked_sub(position.size)?;
+    simulate_order(deps, env, config, account_id, denom, close_size, true)
+}
+
+/// Executes the order against the given market state (which has to be accrued to the current
+/// block) without saving anything.
+///
+/// Funding of the existing position is settled in full. The reduced part of the position realizes
+/// its PnL. Opening fees are charged on the increased, closing fees on the reduced notional value.
+/// All amounts are converted to base denom at oracle prices.
+pub fn compute_order(
+    params: &MarketParams,
+    mut state: MarketState,
+    position: Option<Position>,
+    mut size: Int128,
+    reduce_only: bool,
+    price: Decimal,
+    base_price: Decimal,
+) -> ContractResult<OrderOutcome> {
+    if size.is_zero() {
+        return Err(ContractError::ZeroOrderSize);
+    }
+
+    let old_size = position.as_ref().map(|p| p.size).unwrap_or_default();
+    let increases = old_size.is_zero() || (old_size > Int128::zero()) == (size > Int128::zero());
+
+    if reduce_only {
+        if increases {
+            return Err(ContractError::ReduceOnlyIncreasesPosition {
+                denom: params.denom.clone(),
+            });
+        }
+        if size.unsigned_abs() > old_size.unsigned_abs() {
+            size = Int128::zero().checked_sub(old_size)?;
+        }
+    }
+
+    let new_size = old_size.checked_add(size)?;
+    let old_abs = old_size.unsigned_abs();
+    let order_abs = size.unsigned_abs();
+    let new_abs = new_size.unsigned_abs();
+
+    let (closed, opened) = if increases {
+        (Uint128::zero(), order_abs)
+    } else {
+        let closed = min(order_abs, old_abs);
+        (closed, order_abs - closed)
+    };
+
+    if !opened.is_zero() && !params.enabled {
+        return Err(ContractError::MarketDisabled {
+            denom: params.denom.clone(),
+        });
+    }
+
+    let mut received = Uint128::zero();
+    let mut paid = Uint128::zero();
+
+    if let Some(position) = position.as_ref() {
+        let (funding_received, funding_paid) = position_funding(position, &state)?;
+        received = received.checked_add(funding_received)?;
+        paid = paid.checked_add(funding_paid)?;
+
+        if !closed.is_zero() {
+            let is_long = position.size > Int128::zero();
+            let price_diff = price.abs_diff(position.entry_price);
+            let profitable = (is_long && price > position.entry_price)
+                || (!is_long && price < position.entry_price);
+            if profitable {
+                let pnl = closed.checked_mul_floor(price_diff)?.checked_div_floor(base_price)?;
+                received = received.checked_add(pnl)?;
+            } else {
+                let pnl = closed.checked_mul_ceil(price_diff)?.checked_div_ceil(base_price)?;
+                paid = paid.checked_add(pnl)?;
+            }
+        }
+    }
+
+    let opening_fee = opened
+        .checked_mul_ceil(price)?
+        .checked_mul_ceil(params.opening_fee_rate)?
+        .checked_div_ceil(base_price)?;
+    let closing_fee = closed
+        .checked_mul_ceil(price)?
+        .checked_mul_ceil(params.closing_fee_rate)?
+        .checked_div_ceil(base_price)?;
+    paid = paid.checked_add(opening_fee)?.checked_add(closing_fee)?;
+
+    // Move open interest and entry value from the old to the new position
+    if old_size > Int128::zero() {
+        state.long_oi = state.long_oi.checked_sub(old_abs)?;
+    } else {
+        state.short_oi = state.short_oi.checked_sub(old_abs)?;
+    }
+    if let Some(position) = position.as_ref() {
+        let value = entry_value(position)?;
+        if old_size > Int128::zero() {
+            state.long_entry_value = state.long_entry_value.checked_sub(value)?;
+        } else {
+            state.short_entry_value = state.short_entry_value.checked_sub(value)?;
+        }
+    }
+    if new_size > Int128::zero() {
+        state.long_oi = state.long_oi.checked_add(new_abs)?;
+    } else {
+        state.short_oi = state.short_oi.checked_add(new_abs)?;
+    }
+
+    if !opened.is_zero() {
+        let (oi, max_oi) = if new_size > Int128::zero() {
+            (state.long_oi, params.max_long_oi)
+        } else {
+            (state.short_oi, params.max_short_oi)
+        };
+        if oi > max_oi {
+            return Err(ContractError::OpenInterestCapExceeded {
+                denom: params.denom.clone(),
+                oi,
+                max_oi,
+            });
+        }
+    }
+
+    let new_position = if new_size.is_zero() {
+        None
+    } else {
+        let entry_price = match position.as_ref() {
+            // Increased: average of the old entry and the current price, weighted by size
+            Some(position) if increases => Decimal::checked_from_ratio(old_abs, new_abs)?
+                .checked_mul(position.entry_price)?
+                .checked_add(
+                    Decimal::checked_from_ratio(order_abs, new_abs)?.checked_mul(price)?,
+                )?,
+            // Reduced
+            Some(position) if opened.is_zero() => position.entry_price,
+            // Opened or flipped
+            _ => price,
+        };
+        Some(Position {
+            size: new_size,
+            entry_price,
+            longs_pay_index: state.longs_pay_index,
+            shorts_pay_index: state.shorts_pay_index,
+        })
+    };
+    if let Some(position) = new_position.as_ref() {
+        let value = entry_value(position)?;
+        if new_size > Int128::zero() {
+            state.long_entry_value = state.long_entry_value.checked_add(value)?;
+        } else {
+            state.short_entry_value = state.short_entry_value.checked_add(value)?;
+        }
+    }
+
+    Ok(OrderOutcome {
+        position: new_position,
+        state,
+        settlement: signed_amount(received, paid)?,
+    })
+}
+
+/// Size times entry price of the position, as tracked in the market state
+fn entry_value(position: &Position) -> ContractResult<Uint128> {
+    Ok(position.size.unsigned_abs().checked_mul_floor(position.entry_price)?)
+}
+
+/// Funding since the position was last modified, in base denom.
+/// Returns (received, paid)
+pub fn position_funding(
+    position: &Position,
+    state: &MarketState,
+) -> ContractResult<(Uint128, Uint128)> {
+    let size = position.size.unsigned_abs();
+    let longs_paid_per_unit = state.longs_pay_index.checked_sub(position.longs_pay_index)?;
+    let shorts_paid_per_unit = state.shorts_pay_index.checked_sub(position.shorts_pay_index)?;
+
+    let (received_per_unit, paid_per_unit) = if position.size > Int128::zero() {
+        (shorts_paid_per_unit, longs_paid_per_unit)
+    } else {
+        (longs_paid_per_unit, shorts_paid_per_unit)
+    };
+
+    Ok((size.checked_mul_floor(received_per_unit)?, size.checked_mul_ceil(paid_per_unit)?))
+}
+
+fn to_perp_position(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    denom: &str,
+    position: Position,
+) -> ContractResult<PerpPosition> {
+    let params = MARKET_PARAMS.load(deps.storage, denom)?;
+    let mut state = MARKET_STATES.load(deps.storage, denom)?;
+    let (price, base_price) = query_prices(deps, config, denom)?;
+    accrue_funding(&params, &mut state, env.block.time.seconds(), price, base_price)?;
+
+    let (received, paid) = position_funding(&position, &state)?;
+
+    Ok(PerpPosition {
+        denom: denom.to_string(),
+        base_denom: config.base_denom.clone(),
+        size: position.size,
+        entry_price: position.entry_price,
+        accrued_funding: signed_amount(received, paid)?,
+        closing_fee_rate: params.closing_fee_rate,
+        liquidation_fee_rate: params.liquidation_fee_rate,
+        max_loan_to_value: params.max_loan_to_value,
+        liquidation_threshold: params.liquidation_threshold,
+    })
+}
+
+fn save_outcome(
+    storage: &mut dyn Storage,
+    account_id: &str,
+    denom: &str,
+    outcome: &OrderOutcome,
+) -> StdResult<()> {
+    MARKET_STATES.save(storage, denom, &outcome.state)?;
+    match outcome.position.as_ref() {
+        Some(position) => POSITIONS.save(storage, (account_id, denom), position),
+        None => {
+            POSITIONS.remove(storage, (account_id, denom));
+            Ok(())
+        }
+    }
+}
+
+/// Settles with the counterparty vault. A negative settlement has to be paid exactly, unless
+/// `allow_partial` is set (liquidations), in which case the vault absorbs the shortfall.
+fn settle(
+    storage: &mut dyn Storage,
+    config: &Config,
+    info: &MessageInfo,
+    settlement: Int128,
+    allow_partial: bool,
+) -> ContractResult<Vec<CosmosMsg>> {
+    let received = cw_utils::may_pay(info, &config.base_denom)?;
+    let mut vault = VAULT_STATE.load(storage)?;
+    let mut msgs = vec![];
+
+    if settlement < Int128::zero() {
+        let owed = settlement.unsigned_abs();
+        if received > owed || (!allow_partial && received != owed) {
+            return Err(ContractError::InvalidSettlementPayment {
+                settlement,
+                expected: owed,
+                received,
+            });
+        }
+        vault.total_liquidity = vault.total_liquidity.checked_add(received)?;
+    } else {
+        if !received.is_zero() {
+            return Err(ContractError::InvalidSettlementPayment {
+                settlement,
+                expected: Uint128::zero(),
+                received,
+            });
+        }
+        let amount = settlement.unsigned_abs();
+        if !amount.is_zero() {
+            if vault.total_liquidity < amount {
+                return Err(ContractError::InsufficientVaultLiquidity {
+                    available: vault.total_liquidity,
+                    required: amount,
+                });
+            }
+            vault.total_liquidity -= amount;
+            msgs.push(build_send_asset_msg(&config.credit_manager, &config.base_denom, amount));
+        }
+    }
+
+    VAULT_STATE.save(storage, &vault)?;
+    Ok(msgs)
+}
+
+fn assert_credit_manager(config: &Config, info: &MessageInfo) -> ContractResult<()> {
+    if info.sender != config.credit_manager {
+        return Err(ContractError::SenderNotCreditManager);
+    }
+    Ok(())
+}
+
+fn signed_amount(received: Uint128, paid: Uint128) -> StdResult<Int128> {
+    let amount = Int256::from(received) - Int256::from(paid);
+    Ok(amount.try_into()?)
+}
@@ -0,0 +1,53 @@
+use cosmwasm_std::{Deps, Env, Order};
+use cw_storage_plus::Bound;
+use mars_types::perps::{MarketParams, MarketResponse};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    market::{accrue_funding, funding_rate, query_prices},
+    state::{CONFIG, MARKET_PARAMS, MARKET_STATES},
+};
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+pub fn query_market(deps: Deps, env: &Env, denom: String) -> ContractResult<MarketResponse> {
+    let params =
+        MARKET_PARAMS.may_load(deps.storage, &denom)?.ok_or(ContractError::MarketNotFound {
+            denom,
+        })?;
+    to_market_response(deps, env, params)
+}
+
+pub fn query_markets(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> ContractResult<Vec<MarketResponse>> {
+    let start = start_after.as_ref().map(|denom| Bound::exclusive(denom.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    MARKET_PARAMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| to_market_response(deps, env, res?.1))
+        .collect()
+}
+
+fn to_market_response(
+    deps: Deps,
+    env: &Env,
+    params: MarketParams,
+) -> ContractResult<MarketResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = MARKET_STATES.load(deps.storage, &params.denom)?;
+    let (price, base_price) = query_prices(deps, &config, &params.denom)?;
+    accrue_funding(&params, &mut state, env.block.time.seconds(), price, base_price)?;
+    let (funding_rate, longs_pay) = funding_rate(&params, &state)?;
+    Ok(MarketResponse {
+        params,
+        state,
+        funding_rate,
+        longs_pay,
+    })
+}
@@ -0,0 +1,16 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use mars_owner::Owner;
+use mars_types::perps::{Config, MarketParams, MarketState, Position, UnlockState, VaultState};
+
+pub const OWNER: Owner = Owner::new("owner");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const MARKET_PARAMS: Map<&str, MarketParams> = Map::new("market_params"); // Map<Denom, MarketParams>
+pub const MARKET_STATES: Map<&str, MarketState> = Map::new("market_states"); // Map<Denom, MarketState>
+pub const POSITIONS: Map<(&str, &str), Position> = Map::new("positions"); // Map<(AccountId, Denom), Position>
+
+// Counterparty vault
+pub const VAULT_STATE: Item<VaultState> = Item::new("vault_state");
+pub const VAULT_SHARES: Map<&Addr, Uint128> = Map::new("vault_shares"); // Map<User, Shares> (not unlocking)
+pub const UNLOCKS: Map<&Addr, Vec<UnlockState>> = Map::new("unlocks");
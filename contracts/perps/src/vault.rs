@@ -0,0 +1,181 @@
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Uint128};
+use mars_types::perps::{Config, UnlockState, VaultPositionResponse, VaultState};
+use mars_utils::helpers::build_send_asset_msg;
+
+use crate::{
+    error::{ContractError, ContractResult},
+    market::query_prices,
+    state::{CONFIG, MARKET_STATES, UNLOCKS, VAULT_SHARES, VAULT_STATE},
+};
+
+pub const DEFAULT_SHARES_PER_AMOUNT: Uint128 = Uint128::new(1_000_000);
+
+/// Unrealized PnL of all open positions against the vault, in base denom.
+/// Positions are netted per market side. Unsettled funding is not taken into account.
+#[derive(Debug, Default, PartialEq)]
+pub struct UnrealizedPnl {
+    /// Profits of the traders, owed by the vault
+    pub profits: Uint128,
+    /// Losses of the traders, owed to the vault
+    pub losses: Uint128,
+}
+
+impl UnrealizedPnl {
+    /// Liquidity of the vault once all open positions are settled at the current prices
+    pub fn vault_value(&self, vault: &VaultState) -> ContractResult<Uint128> {
+        Ok(vault.total_liquidity.checked_add(self.losses)?.saturating_sub(self.profits))
+    }
+
+    /// Liquidity which isn't owed to open positions
+    pub fn available_liquidity(&self, vault: &VaultState) -> Uint128 {
+        vault.total_liquidity.saturating_sub(self.profits)
+    }
+}
+
+pub fn unrealized_pnl(deps: Deps, config: &Config) -> ContractResult<UnrealizedPnl> {
+    let mut pnl = UnrealizedPnl::default();
+    for res in MARKET_STATES.range(deps.storage, None, None, Order::Ascending) {
+        let (denom, state) = res?;
+        let (price, base_price) = query_prices(deps, config, &denom)?;
+
+        let long_value = state.long_oi.checked_mul_floor(price)?;
+        let short_value = state.short_oi.checked_mul_ceil(price)?;
+        for (value, entry_value) in
+            [(long_value, state.long_entry_value), (state.short_entry_value, short_value)]
+        {
+            if value > entry_value {
+                let profit = (value - entry_value).checked_div_ceil(base_price)?;
+                pnl.profits = pnl.profits.checked_add(profit)?;
+            } else {
+                let loss = (entry_value - value).checked_div_floor(base_price)?;
+                pnl.losses = pnl.losses.checked_add(loss)?;
+            }
+        }
+    }
+    Ok(pnl)
+}
+
+/// Vault shares are priced by the liquidity of the vault including the unrealized PnL of open
+/// positions, so that LPs can't enter or exit ahead of it being settled.
+pub fn deposit(deps: DepsMut, info: MessageInfo) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &config.base_denom)?;
+
+    let mut vault = VAULT_STATE.load(deps.storage)?;
+    let shares = if vault.total_shares.is_zero() {
+        amount.checked_mul(DEFAULT_SHARES_PER_AMOUNT)?
+    } else {
+        let vault_value = unrealized_pnl(deps.as_ref(), &config)?.vault_value(&vault)?;
+        amount.checked_multiply_ratio(vault.total_shares, vault_value)?
+    };
+
+    vault.total_liquidity = vault.total_liquidity.checked_add(amount)?;
+    vault.total_shares = vault.total_shares.checked_add(shares)?;
+    VAULT_STATE.save(deps.storage, &vault)?;
+
+    VAULT_SHARES.update(deps.storage, &info.sender, |current| {
+        current.unwrap_or_default().checked_add(shares)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("depositor", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("shares", shares))
+}
+
+pub fn unlock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    shares: Uint128,
+) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let available = VAULT_SHARES.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if shares.is_zero() || shares > available {
+        return Err(ContractError::InsufficientShares {
+            available,
+            requested: shares,
+        });
+    }
+
+    let remaining = available - shares;
+    if remaining.is_zero() {
+        VAULT_SHARES.remove(deps.storage, &info.sender);
+    } else {
+        VAULT_SHARES.save(deps.storage, &info.sender, &remaining)?;
+    }
+
+    let now = env.block.time.seconds();
+    let cooldown_end = now + config.cooldown_period;
+    let mut unlocks = UNLOCKS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    unlocks.push(UnlockState {
+        created_at: now,
+        cooldown_end,
+        shares,
+    });
+    UNLOCKS.save(deps.storage, &info.sender, &unlocks)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unlock")
+        .add_attribute("user", info.sender)
+        .add_attribute("shares", shares)
+        .add_attribute("cooldown_end", cooldown_end.to_string()))
+}
+
+pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let now = env.block.time.seconds();
+    let (unlocked, pending): (Vec<_>, Vec<_>) = UNLOCKS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|unlock| unlock.cooldown_end <= now);
+
+    if unlocked.is_empty() {
+        return Err(ContractError::NoUnlockedShares);
+    }
+
+    if pending.is_empty() {
+        UNLOCKS.remove(deps.storage, &info.sender);
+    } else {
+        UNLOCKS.save(deps.storage, &info.sender, &pending)?;
+    }
+
+    let shares = unlocked.iter().map(|unlock| unlock.shares).sum::<Uint128>();
+    let mut vault = VAULT_STATE.load(deps.storage)?;
+    let pnl = unrealized_pnl(deps.as_ref(), &config)?;
+    let amount = shares.checked_multiply_ratio(pnl.vault_value(&vault)?, vault.total_shares)?;
+    let available = pnl.available_liquidity(&vault);
+    if amount > available {
+        return Err(ContractError::InsufficientVaultLiquidity {
+            available,
+            required: amount,
+        });
+    }
+    vault.total_liquidity = vault.total_liquidity.checked_sub(amount)?;
+    vault.total_shares = vault.total_shares.checked_sub(shares)?;
+    VAULT_STATE.save(deps.storage, &vault)?;
+
+    let mut response = Response::new();
+    if !amount.is_zero() {
+        response =
+            response.add_message(build_send_asset_msg(&info.sender, &config.base_denom, amount));
+    }
+
+    Ok(response
+        .add_attribute("action", "withdraw")
+        .add_attribute("user", info.sender)
+        .add_attribute("shares", shares)
+        .add_attribute("amount", amount))
+}
+
+pub fn query_vault_position(deps: Deps, user: String) -> StdResult<VaultPositionResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    Ok(VaultPositionResponse {
+        shares: VAULT_SHARES.may_load(deps.storage, &user)?.unwrap_or_default(),
+        unlocks: UNLOCKS.may_load(deps.storage, &user)?.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,56 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{Addr, Decimal};
+use cw_multi_test::AppResponse;
+use mars_mock_oracle::msg::CoinPrice;
+use mars_perps::error::ContractError;
+pub use mars_testing::multitest::helpers::*;
+use mars_types::{adapters::perps::Perps, oracle::ActionKind};
+
+pub const BASE_DENOM: &str = "uusdc";
+pub const PERP_DENOM: &str = "ubtc";
+pub const VAULT_LIQUIDITY: u128 = 100_000;
+
+pub fn assert_err(res: AnyResult<AppResponse>, err: ContractError) {
+    match res {
+        Ok(_) => panic!("Result was not an error"),
+        Err(generic_err) => {
+            let contract_err: ContractError = generic_err.downcast().unwrap();
+            assert_eq!(contract_err, err);
+        }
+    }
+}
+
+/// Env with a perps contract settled in `BASE_DENOM` (priced at 1) and a `PERP_DENOM` market
+/// (priced at 100). The vault is funded by `lp` and `user` holds base denom to trade with.
+pub fn setup_perps(lp: &Addr, user: &Addr) -> (MockEnv, Perps) {
+    let base_info = coin_info(BASE_DENOM);
+    let mut mock = MockEnv::new()
+        .set_params(&[base_info.clone()])
+        .fund_account(AccountToFund {
+            addr: lp.clone(),
+            funds: vec![base_info.to_coin(VAULT_LIQUIDITY)],
+        })
+        .fund_account(AccountToFund {
+            addr: user.clone(),
+            funds: vec![base_info.to_coin(100_000)],
+        })
+        .build()
+        .unwrap();
+
+    set_price(&mut mock, BASE_DENOM, Decimal::one());
+    set_price(&mut mock, PERP_DENOM, Decimal::from_ratio(100u128, 1u128));
+
+    let perps = mock.deploy_perps(BASE_DENOM);
+    mock.update_perp_market(&perps, perp_market_params(PERP_DENOM)).unwrap();
+    mock.deposit_to_perp_vault(&perps, lp, &[base_info.to_coin(VAULT_LIQUIDITY)]).unwrap();
+
+    (mock, perps)
+}
+
+pub fn set_price(mock: &mut MockEnv, denom: &str, price: Decimal) {
+    mock.price_change(CoinPrice {
+        pricing: ActionKind::Default,
+        denom: denom.to_string(),
+        price,
+    });
+}
@@ -0,0 +1,5 @@
+mod helpers;
+
+mod test_markets;
+mod test_orders;
+mod test_vault;
@@ -0,0 +1,113 @@
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use cw_multi_test::Executor;
+use mars_owner::OwnerError;
+use mars_perps::error::ContractError;
+use mars_types::{
+    credit_manager::Action::{Deposit, ExecutePerpOrder},
+    perps::ExecuteMsg,
+};
+use mars_utils::error::ValidationError;
+
+use super::helpers::{
+    assert_err, coin_info, perp_market_params, setup_perps, BASE_DENOM, PERP_DENOM,
+};
+
+#[test]
+fn only_owner_can_update_market() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let res = mock.app.execute_contract(
+        user,
+        perps.address().clone(),
+        &ExecuteMsg::UpdateMarket(perp_market_params("ueth")),
+        &[],
+    );
+    assert_err(res, ContractError::Owner(OwnerError::NotOwner {}));
+}
+
+#[test]
+fn invalid_market_params_rejected() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let mut params = perp_market_params("ueth");
+    params.liquidation_threshold = params.max_loan_to_value;
+    let res = mock.update_perp_market(&perps, params);
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "liquidation_threshold".to_string(),
+            invalid_value: "0.8".to_string(),
+            predicate: "> 0.8 (max LTV)".to_string(),
+        }),
+    );
+
+    let mut params = perp_market_params("ueth");
+    params.skew_scale = Uint128::zero();
+    let res = mock.update_perp_market(&perps, params);
+    assert_err(
+        res,
+        ContractError::Validation(ValidationError::InvalidParam {
+            param_name: "skew_scale".to_string(),
+            invalid_value: "0".to_string(),
+            predicate: "> 0".to_string(),
+        }),
+    );
+}
+
+#[test]
+fn new_market_starts_without_open_interest() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mock, perps) = setup_perps(&lp, &user);
+
+    let market = mock.query_perp_market(&perps, PERP_DENOM);
+    assert_eq!(market.params, perp_market_params(PERP_DENOM));
+    assert_eq!(market.state.long_oi, Uint128::zero());
+    assert_eq!(market.state.short_oi, Uint128::zero());
+    assert_eq!(market.state.last_updated, mock.app.block_info().time.seconds());
+    assert_eq!(market.funding_rate, Decimal::zero());
+}
+
+#[test]
+fn funding_paid_by_side_with_larger_open_interest() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+    let base_info = coin_info(BASE_DENOM);
+
+    let account_id = mock.create_credit_account(&user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        &user,
+        vec![
+            Deposit(base_info.to_coin(20_000)),
+            ExecutePerpOrder {
+                denom: PERP_DENOM.to_string(),
+                size: Int128::new(500),
+                reduce_only: None,
+            },
+        ],
+        &[base_info.to_coin(20_000)],
+    )
+    .unwrap();
+
+    // Skew is half of the skew scale: half of the max funding rate
+    let market = mock.query_perp_market(&perps, PERP_DENOM);
+    assert_eq!(market.state.long_oi, Uint128::new(500));
+    assert_eq!(market.funding_rate, Decimal::permille(5));
+    assert!(market.longs_pay);
+
+    mock.increment_by_time(24 * 60 * 60);
+
+    // 0.5% of the price of 100 per unit per day
+    let market = mock.query_perp_market(&perps, PERP_DENOM);
+    assert_eq!(market.state.longs_pay_index, Decimal::percent(50));
+    assert_eq!(market.state.shorts_pay_index, Decimal::zero());
+
+    let position = mock.query_perp_position(&perps, &account_id, PERP_DENOM).unwrap();
+    assert_eq!(position.accrued_funding, Int128::new(-250));
+}
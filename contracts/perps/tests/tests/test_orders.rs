@@ -0,0 +1,393 @@
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use cw_multi_test::Executor;
+use mars_perps::{
+    error::ContractError,
+    position::{compute_order, OrderOutcome},
+};
+use mars_types::perps::{ExecuteMsg, MarketState, Position};
+
+use super::helpers::{assert_err, perp_market_params, setup_perps, PERP_DENOM};
+
+fn price(value: u128) -> Decimal {
+    Decimal::from_ratio(value, 1u128)
+}
+
+fn position(size: i128, entry_price: u128) -> Position {
+    Position {
+        size: Int128::new(size),
+        entry_price: price(entry_price),
+        longs_pay_index: Decimal::zero(),
+        shorts_pay_index: Decimal::zero(),
+    }
+}
+
+/// Market state of positions which were all opened at a price of 100
+fn state(long_oi: u128, short_oi: u128) -> MarketState {
+    MarketState {
+        long_oi: Uint128::new(long_oi),
+        short_oi: Uint128::new(short_oi),
+        long_entry_value: Uint128::new(long_oi * 100),
+        short_entry_value: Uint128::new(short_oi * 100),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn only_credit_manager_can_execute_orders() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let res = mock.app.execute_contract(
+        user.clone(),
+        perps.address().clone(),
+        &ExecuteMsg::ExecuteOrder {
+            account_id: "1".to_string(),
+            denom: PERP_DENOM.to_string(),
+            size: Int128::new(10),
+            reduce_only: None,
+        },
+        &[],
+    );
+    assert_err(res, ContractError::SenderNotCreditManager);
+
+    let res = mock.app.execute_contract(
+        user,
+        perps.address().clone(),
+        &ExecuteMsg::ClosePosition {
+            account_id: "1".to_string(),
+            denom: PERP_DENOM.to_string(),
+        },
+        &[],
+    );
+    assert_err(res, ContractError::SenderNotCreditManager);
+}
+
+#[test]
+fn zero_size_order_rejected() {
+    let params = perp_market_params(PERP_DENOM);
+    let err = compute_order(
+        &params,
+        state(0, 0),
+        None,
+        Int128::zero(),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ZeroOrderSize);
+}
+
+#[test]
+fn opening_position_pays_opening_fee() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(0, 0),
+        None,
+        Int128::new(10),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    // 0.1% of 1000 notional value
+    assert_eq!(
+        outcome,
+        OrderOutcome {
+            position: Some(position(10, 100)),
+            state: state(10, 0),
+            settlement: Int128::new(-1),
+        }
+    );
+}
+
+#[test]
+fn increasing_position_averages_entry_price() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(30),
+        false,
+        price(120),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    assert_eq!(outcome.position, Some(position(40, 115)));
+    assert_eq!(
+        outcome.state,
+        MarketState {
+            long_oi: Uint128::new(40),
+            long_entry_value: Uint128::new(4_600),
+            ..Default::default()
+        }
+    );
+    // 0.1% of 3600 notional value
+    assert_eq!(outcome.settlement, Int128::new(-4));
+}
+
+#[test]
+fn closing_position_realizes_profit() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(-10),
+        false,
+        price(110),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    // 100 profit minus 2 closing fee (0.1% of 1100 rounded up)
+    assert_eq!(
+        outcome,
+        OrderOutcome {
+            position: None,
+            state: state(0, 0),
+            settlement: Int128::new(98),
+        }
+    );
+}
+
+#[test]
+fn reducing_short_realizes_loss() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(0, 10),
+        Some(position(-10, 100)),
+        Int128::new(4),
+        false,
+        price(120),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    // 80 loss plus 1 closing fee (0.1% of 480 rounded up), entry price is kept
+    assert_eq!(
+        outcome,
+        OrderOutcome {
+            position: Some(position(-6, 100)),
+            state: state(0, 6),
+            settlement: Int128::new(-81),
+        }
+    );
+}
+
+#[test]
+fn flipping_position_closes_and_opens() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(-15),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    // closing fee on 1000 and opening fee on 500 notional value
+    assert_eq!(
+        outcome,
+        OrderOutcome {
+            position: Some(position(-5, 100)),
+            state: state(0, 5),
+            settlement: Int128::new(-2),
+        }
+    );
+}
+
+#[test]
+fn pnl_converted_to_base_denom() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(-10),
+        false,
+        price(110),
+        price(2),
+    )
+    .unwrap();
+
+    // 100 profit and 2 closing fee in value, converted to base denom priced at 2
+    assert_eq!(outcome.settlement, Int128::new(49));
+}
+
+#[test]
+fn reduce_only_order_capped_to_position_size() {
+    let params = perp_market_params(PERP_DENOM);
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(-15),
+        true,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    assert_eq!(outcome.position, None);
+    assert_eq!(outcome.state, state(0, 0));
+    assert_eq!(outcome.settlement, Int128::new(-1));
+}
+
+#[test]
+fn reduce_only_order_can_not_increase_position() {
+    let params = perp_market_params(PERP_DENOM);
+
+    let err = compute_order(
+        &params,
+        state(0, 0),
+        None,
+        Int128::new(10),
+        true,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ReduceOnlyIncreasesPosition {
+            denom: PERP_DENOM.to_string()
+        }
+    );
+
+    let err = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(5),
+        true,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ReduceOnlyIncreasesPosition {
+            denom: PERP_DENOM.to_string()
+        }
+    );
+}
+
+#[test]
+fn open_interest_cap_enforced() {
+    let mut params = perp_market_params(PERP_DENOM);
+    params.max_short_oi = Uint128::new(100);
+
+    let err = compute_order(
+        &params,
+        state(0, 95),
+        None,
+        Int128::new(-10),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::OpenInterestCapExceeded {
+            denom: PERP_DENOM.to_string(),
+            oi: Uint128::new(105),
+            max_oi: Uint128::new(100),
+        }
+    );
+
+    // Reducing is allowed even above the cap
+    let outcome = compute_order(
+        &params,
+        state(0, 120),
+        Some(position(-20, 100)),
+        Int128::new(5),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+    assert_eq!(outcome.state, state(0, 115));
+}
+
+#[test]
+fn disabled_market_only_allows_reducing() {
+    let mut params = perp_market_params(PERP_DENOM);
+    params.enabled = false;
+
+    let err = compute_order(
+        &params,
+        state(0, 0),
+        None,
+        Int128::new(10),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MarketDisabled {
+            denom: PERP_DENOM.to_string()
+        }
+    );
+
+    let outcome = compute_order(
+        &params,
+        state(10, 0),
+        Some(position(10, 100)),
+        Int128::new(-5),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+    assert_eq!(outcome.position, Some(position(5, 100)));
+}
+
+#[test]
+fn funding_settled_on_order() {
+    let params = perp_market_params(PERP_DENOM);
+    let market_state = MarketState {
+        long_oi: Uint128::new(10),
+        short_oi: Uint128::zero(),
+        long_entry_value: Uint128::new(1_000),
+        short_entry_value: Uint128::zero(),
+        longs_pay_index: Decimal::percent(50),
+        shorts_pay_index: Decimal::zero(),
+        last_updated: 0,
+    };
+
+    let outcome = compute_order(
+        &params,
+        market_state,
+        Some(position(10, 100)),
+        Int128::new(-5),
+        false,
+        price(100),
+        Decimal::one(),
+    )
+    .unwrap();
+
+    // 5 funding paid by the long plus 1 closing fee (0.5 rounded up)
+    assert_eq!(outcome.settlement, Int128::new(-6));
+    assert_eq!(
+        outcome.position,
+        Some(Position {
+            size: Int128::new(5),
+            entry_price: price(100),
+            longs_pay_index: Decimal::percent(50),
+            shorts_pay_index: Decimal::zero(),
+        })
+    );
+}
@@ -0,0 +1,212 @@
+use cosmwasm_std::{coin, Addr, Decimal, Int128, Uint128};
+use cw_multi_test::Executor;
+use cw_utils::PaymentError;
+use mars_perps::{error::ContractError, vault::DEFAULT_SHARES_PER_AMOUNT};
+use mars_types::{
+    adapters::perps::Perps,
+    credit_manager::Action::{Deposit, ExecutePerpOrder},
+    perps::{ExecuteMsg, QueryMsg, VaultPositionResponse, VaultState},
+};
+
+use super::helpers::{
+    assert_err, coin_info, set_price, setup_perps, MockEnv, BASE_DENOM,
+    DEFAULT_PERPS_COOLDOWN_PERIOD, PERP_DENOM, VAULT_LIQUIDITY,
+};
+
+/// Opens a position in a new credit account of the user, backed by 20_000 base denom
+fn open_position(mock: &mut MockEnv, user: &Addr, size: i128) {
+    let base_info = coin_info(BASE_DENOM);
+    let account_id = mock.create_credit_account(user).unwrap();
+    mock.update_credit_account(
+        &account_id,
+        user,
+        vec![
+            Deposit(base_info.to_coin(20_000)),
+            ExecutePerpOrder {
+                denom: PERP_DENOM.to_string(),
+                size: Int128::new(size),
+                reduce_only: None,
+            },
+        ],
+        &[base_info.to_coin(20_000)],
+    )
+    .unwrap();
+}
+
+fn query_shares(mock: &MockEnv, perps: &Perps, user: &Addr) -> Uint128 {
+    let position: VaultPositionResponse = mock
+        .app
+        .wrap()
+        .query_wasm_smart(
+            perps.address(),
+            &QueryMsg::VaultPosition {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+    position.shares
+}
+
+#[test]
+fn deposit_mints_shares() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let lp_shares = Uint128::new(VAULT_LIQUIDITY) * DEFAULT_SHARES_PER_AMOUNT;
+    assert_eq!(
+        mock.query_perp_vault(&perps),
+        VaultState {
+            total_liquidity: Uint128::new(VAULT_LIQUIDITY),
+            total_shares: lp_shares,
+        }
+    );
+
+    // Shares are minted pro rata to the existing liquidity
+    mock.deposit_to_perp_vault(&perps, &user, &[coin(50_000, BASE_DENOM)]).unwrap();
+    let position: VaultPositionResponse = mock
+        .app
+        .wrap()
+        .query_wasm_smart(
+            perps.address(),
+            &QueryMsg::VaultPosition {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(position.shares, lp_shares / Uint128::new(2));
+    assert!(position.unlocks.is_empty());
+}
+
+#[test]
+fn deposit_requires_base_denom() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let res = mock.deposit_to_perp_vault(&perps, &user, &[]);
+    assert_err(res, ContractError::Payment(PaymentError::NoFunds {}));
+}
+
+#[test]
+fn can_not_unlock_more_than_owned_shares() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let available = Uint128::new(VAULT_LIQUIDITY) * DEFAULT_SHARES_PER_AMOUNT;
+    let res = mock.app.execute_contract(
+        lp,
+        perps.address().clone(),
+        &ExecuteMsg::Unlock {
+            shares: available + Uint128::one(),
+        },
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::InsufficientShares {
+            available,
+            requested: available + Uint128::one(),
+        },
+    );
+}
+
+#[test]
+fn withdraw_after_cooldown() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    let shares = Uint128::new(VAULT_LIQUIDITY) * DEFAULT_SHARES_PER_AMOUNT;
+    mock.app
+        .execute_contract(
+            lp.clone(),
+            perps.address().clone(),
+            &ExecuteMsg::Unlock {
+                shares,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res = mock.app.execute_contract(
+        lp.clone(),
+        perps.address().clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    );
+    assert_err(res, ContractError::NoUnlockedShares);
+
+    mock.increment_by_time(DEFAULT_PERPS_COOLDOWN_PERIOD);
+
+    mock.app
+        .execute_contract(lp.clone(), perps.address().clone(), &ExecuteMsg::Withdraw {}, &[])
+        .unwrap();
+
+    assert_eq!(mock.query_balance(&lp, BASE_DENOM).amount, Uint128::new(VAULT_LIQUIDITY));
+    assert_eq!(mock.query_perp_vault(&perps), VaultState::default());
+}
+
+#[test]
+fn deposit_priced_with_unrealized_pnl() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+    let lp_shares = Uint128::new(VAULT_LIQUIDITY) * DEFAULT_SHARES_PER_AMOUNT;
+
+    // 50 opening fee paid to the vault
+    open_position(&mut mock, &user, 500);
+
+    // 50_000 unrealized profit owed by the vault, which is worth 50_050 afterwards
+    set_price(&mut mock, PERP_DENOM, Decimal::from_ratio(200u128, 1u128));
+    mock.deposit_to_perp_vault(&perps, &user, &[coin(50_050, BASE_DENOM)]).unwrap();
+    assert_eq!(query_shares(&mock, &perps, &user), lp_shares);
+}
+
+#[test]
+fn withdraw_capped_by_profits_owed() {
+    let lp = Addr::unchecked("lp");
+    let user = Addr::unchecked("user");
+    let (mut mock, perps) = setup_perps(&lp, &user);
+
+    // 100 opening fees paid to the vault
+    open_position(&mut mock, &user, 500);
+    open_position(&mut mock, &user, -500);
+
+    let shares = Uint128::new(VAULT_LIQUIDITY) * DEFAULT_SHARES_PER_AMOUNT;
+    mock.app
+        .execute_contract(
+            lp.clone(),
+            perps.address().clone(),
+            &ExecuteMsg::Unlock {
+                shares,
+            },
+            &[],
+        )
+        .unwrap();
+    mock.increment_by_time(DEFAULT_PERPS_COOLDOWN_PERIOD);
+
+    // The loss of the short is owed to the vault, but only realized liquidity not owed to the
+    // long can be withdrawn
+    set_price(&mut mock, PERP_DENOM, Decimal::from_ratio(200u128, 1u128));
+    let res = mock.app.execute_contract(
+        lp.clone(),
+        perps.address().clone(),
+        &ExecuteMsg::Withdraw {},
+        &[],
+    );
+    assert_err(
+        res,
+        ContractError::InsufficientVaultLiquidity {
+            available: Uint128::new(50_100),
+            required: Uint128::new(100_100),
+        },
+    );
+
+    set_price(&mut mock, PERP_DENOM, Decimal::from_ratio(100u128, 1u128));
+    mock.app
+        .execute_contract(lp.clone(), perps.address().clone(), &ExecuteMsg::Withdraw {}, &[])
+        .unwrap();
+    assert_eq!(mock.query_balance(&lp, BASE_DENOM).amount, Uint128::new(100_100));
+}
@@ -2,19 +2,23 @@ use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
 use mars_interest_rate::{
     get_scaled_debt_amount, get_underlying_debt_amount, get_underlying_liquidity_amount,
 };
-use mars_types::{address_provider, address_provider::MarsAddressType};
+use mars_types::{address_provider, address_provider::MarsAddressType, params::PausableAction};
 use mars_utils::helpers::build_send_asset_msg;
 
 use crate::{
+    delegation::decrease_borrow_allowance,
     error::ContractError,
     health::assert_below_max_ltv_after_borrow,
-    helpers::query_asset_params,
+    helpers::{assert_min_debt_value, assert_not_paused, query_asset_params},
     interest_rates::{apply_accumulated_interests, update_interest_rates},
-    state::{CONFIG, MARKETS},
+    state::{CONFIG, DEBTS, MARKETS},
     user::User,
 };
 
-/// Add debt for the borrower and send the borrowed funds
+/// Add debt for the borrower and send the borrowed funds.
+///
+/// If `on_behalf_of` is provided, the debt is added to that user (the delegator) instead of the
+/// sender, consuming the allowance previously approved for the sender.
 pub fn borrow(
     deps: DepsMut,
     env: Env,
@@ -22,8 +26,10 @@ pub fn borrow(
     denom: String,
     borrow_amount: Uint128,
     recipient: Option<String>,
+    on_behalf_of: Option<String>,
 ) -> Result<Response, ContractError> {
-    let borrower = User(&info.sender);
+    let delegator_addr = on_behalf_of.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let borrower = User(delegator_addr.as_ref().unwrap_or(&info.sender));
 
     let config = CONFIG.load(deps.storage)?;
 
@@ -44,6 +50,8 @@ pub fn borrow(
     let params_addr = &addresses[&MarsAddressType::Params];
     let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
 
+    assert_not_paused(&deps.querier, params_addr, PausableAction::Borrows)?;
+
     let asset_params = query_asset_params(&deps.querier, params_addr, &denom)?;
 
     if !asset_params.red_bank.borrow_enabled {
@@ -74,9 +82,24 @@ pub fn borrow(
         });
     }
 
+    // Borrowing on behalf of another user is only possible within the approved allowance
+    let mut remaining_allowance = None;
+    if let Some(delegator_addr) = &delegator_addr {
+        if delegator_addr == credit_manager_addr {
+            return Err(ContractError::CannotDelegateCreditManager {});
+        }
+        remaining_allowance = Some(decrease_borrow_allowance(
+            deps.storage,
+            delegator_addr,
+            &info.sender,
+            &denom,
+            borrow_amount,
+        )?);
+    }
+
     // Check if user can borrow specified amount
     let mut uncollateralized_debt = false;
-    if info.sender != credit_manager_addr {
+    if borrower.address() != credit_manager_addr {
         if !assert_below_max_ltv_after_borrow(
             &deps.as_ref(),
             &env,
@@ -89,6 +112,22 @@ pub fn borrow(
         )? {
             return Err(ContractError::BorrowAmountExceedsGivenCollateral {});
         }
+
+        // Credit manager debt is the sum of all credit accounts, they are checked there
+        let debt_amount_before = match DEBTS.may_load(deps.storage, (borrower.address(), &denom))? {
+            Some(debt) => get_underlying_debt_amount(
+                debt.amount_scaled,
+                &borrow_market,
+                env.block.time.seconds(),
+            )?,
+            None => Uint128::zero(),
+        };
+        assert_min_debt_value(
+            &deps.querier,
+            oracle_addr,
+            &asset_params,
+            debt_amount_before.checked_add(borrow_amount)?,
+        )?;
     } else {
         uncollateralized_debt = true;
     }
@@ -114,19 +153,27 @@ pub fn borrow(
     response = update_interest_rates(&env, &mut borrow_market, response)?;
     MARKETS.save(deps.storage, &denom, &borrow_market)?;
 
-    // Send borrow amount to borrower or another recipient
+    // Send borrow amount to the sender or another recipient
     let recipient_addr = if let Some(recipient) = recipient {
         deps.api.addr_validate(&recipient)?
     } else {
-        borrower.address().clone()
+        info.sender.clone()
     };
 
-    Ok(response
+    response = response
         .add_message(build_send_asset_msg(&recipient_addr, &denom, borrow_amount))
         .add_attribute("action", "borrow")
-        .add_attribute("sender", borrower)
+        .add_attribute("sender", &info.sender)
         .add_attribute("recipient", recipient_addr)
         .add_attribute("denom", denom)
         .add_attribute("amount", borrow_amount)
-        .add_attribute("amount_scaled", borrow_amount_scaled))
+        .add_attribute("amount_scaled", borrow_amount_scaled);
+
+    if let Some(remaining_allowance) = remaining_allowance {
+        response = response
+            .add_attribute("on_behalf_of", borrower)
+            .add_attribute("remaining_allowance", remaining_allowance);
+    }
+
+    Ok(response)
 }
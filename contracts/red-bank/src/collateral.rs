@@ -8,6 +8,7 @@ use mars_types::{
 use crate::{
     error::ContractError,
     health::get_health_and_positions,
+    liquidate::clear_liquidation_auction_if_healthy,
     state::{COLLATERALS, CONFIG},
     user::User,
 };
@@ -66,6 +67,8 @@ pub fn update_asset_collateral_status(
         }
     }
 
+    clear_liquidation_auction_if_healthy(deps, &env, user.address())?;
+
     Ok(Response::new()
         .add_attribute("action", "update_asset_collateral_status")
         .add_attribute("user", user)
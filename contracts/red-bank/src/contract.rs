@@ -1,11 +1,14 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
 };
-use mars_types::red_bank::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use mars_types::{
+    error::MarsError,
+    red_bank::{CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg},
+};
 
 use crate::{
-    asset, borrow, collateral, config, deposit, error::ContractError, instantiate, liquidate,
-    migrations, query, repay, state::MIGRATION_GUARD, withdraw,
+    asset, borrow, collateral, config, delegation, deposit, error::ContractError, instantiate,
+    liquidate, migrations, price_updates, query, repay, state::MIGRATION_GUARD, withdraw,
 };
 
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -26,8 +29,13 @@ pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    msg: ExecuteMsg,
+    mut msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if let Some(price_updates) = price_updates::take_price_updates(&mut msg) {
+        MIGRATION_GUARD.assert_unlocked(deps.storage)?;
+        return price_updates::update_prices_and_dispatch(deps, env, info, price_updates, msg);
+    }
+
     match msg {
         ExecuteMsg::UpdateOwner(update) => config::update_owner(deps, info, update),
         ExecuteMsg::UpdateConfig {
@@ -63,6 +71,7 @@ pub fn execute(
             recipient,
             account_id,
             liquidation_related,
+            ..
         } => {
             MIGRATION_GUARD.assert_unlocked(deps.storage)?;
             cw_utils::nonpayable(&info)?;
@@ -81,10 +90,21 @@ pub fn execute(
             denom,
             amount,
             recipient,
+            on_behalf_of,
+            ..
+        } => {
+            MIGRATION_GUARD.assert_unlocked(deps.storage)?;
+            cw_utils::nonpayable(&info)?;
+            borrow::borrow(deps, env, info, denom, amount, recipient, on_behalf_of)
+        }
+        ExecuteMsg::ApproveDelegation {
+            delegatee,
+            denom,
+            amount,
         } => {
             MIGRATION_GUARD.assert_unlocked(deps.storage)?;
             cw_utils::nonpayable(&info)?;
-            borrow::borrow(deps, env, info, denom, amount, recipient)
+            delegation::approve_delegation(deps, info, delegatee, denom, amount)
         }
         ExecuteMsg::Repay {
             on_behalf_of,
@@ -97,6 +117,7 @@ pub fn execute(
             user,
             collateral_denom,
             recipient,
+            ..
         } => {
             MIGRATION_GUARD.assert_unlocked(deps.storage)?;
             let user_addr = deps.api.addr_validate(&user)?;
@@ -112,6 +133,14 @@ pub fn execute(
                 recipient,
             )
         }
+        ExecuteMsg::RefreshLiquidationAuction {
+            user,
+        } => {
+            MIGRATION_GUARD.assert_unlocked(deps.storage)?;
+            cw_utils::nonpayable(&info)?;
+            let user_addr = deps.api.addr_validate(&user)?;
+            liquidate::refresh_liquidation_auction(deps, env, user_addr)
+        }
         ExecuteMsg::UpdateAssetCollateralStatus {
             denom,
             enable,
@@ -120,6 +149,19 @@ pub fn execute(
             cw_utils::nonpayable(&info)?;
             collateral::update_asset_collateral_status(deps, env, info, denom, enable)
         }
+        ExecuteMsg::Callback(CallbackMsg::Dispatch {
+            sender,
+            msg,
+        }) => {
+            if info.sender != env.contract.address {
+                return Err(ContractError::Mars(MarsError::Unauthorized {}));
+            }
+            let info = MessageInfo {
+                sender: deps.api.addr_validate(&sender)?,
+                funds: info.funds,
+            };
+            execute(deps, env, info, *msg)
+        }
     }
 }
 
@@ -218,6 +260,39 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             let user_addr = deps.api.addr_validate(&user)?;
             to_json_binary(&query::query_user_position(deps, env, user_addr, account_id, true)?)
         }
+        QueryMsg::BorrowAllowance {
+            delegator,
+            delegatee,
+            denom,
+        } => {
+            let delegator_addr = deps.api.addr_validate(&delegator)?;
+            let delegatee_addr = deps.api.addr_validate(&delegatee)?;
+            to_json_binary(&query::query_borrow_allowance(
+                deps,
+                delegator_addr,
+                delegatee_addr,
+                denom,
+            )?)
+        }
+        QueryMsg::BorrowAllowances {
+            delegator,
+            start_after,
+            limit,
+        } => {
+            let delegator_addr = deps.api.addr_validate(&delegator)?;
+            to_json_binary(&query::query_borrow_allowances(
+                deps,
+                delegator_addr,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::LiquidationAuction {
+            user,
+        } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            to_json_binary(&query::query_liquidation_auction(deps, env, user_addr)?)
+        }
         QueryMsg::ScaledLiquidityAmount {
             denom,
             amount,
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Addr, DepsMut, MessageInfo, Response, Storage, Uint128};
+use mars_types::address_provider::{self, MarsAddressType};
+
+use crate::{
+    error::ContractError,
+    state::{BORROW_ALLOWANCES, CONFIG, MARKETS},
+};
+
+/// Set the amount of an asset the delegatee is allowed to borrow against the sender's collateral.
+/// Approving zero amount removes the delegation.
+pub fn approve_delegation(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegatee: String,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let delegatee_addr = deps.api.addr_validate(&delegatee)?;
+
+    if delegatee_addr == info.sender {
+        return Err(ContractError::CannotDelegateToSelf {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let credit_manager_addr = address_provider::helpers::query_contract_addr(
+        deps.as_ref(),
+        &config.address_provider,
+        MarsAddressType::CreditManager,
+    )?;
+
+    // Credit manager debt is not backed by Red Bank collateral, so it can't be used on either side
+    // of a delegation
+    if info.sender == credit_manager_addr || delegatee_addr == credit_manager_addr {
+        return Err(ContractError::CannotDelegateCreditManager {});
+    }
+
+    if !MARKETS.has(deps.storage, &denom) {
+        return Err(ContractError::AssetNotInitialized {});
+    }
+
+    let key = (&info.sender, &delegatee_addr, denom.as_str());
+    if amount.is_zero() {
+        BORROW_ALLOWANCES.remove(deps.storage, key);
+    } else {
+        BORROW_ALLOWANCES.save(deps.storage, key, &amount)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_delegation")
+        .add_attribute("delegator", info.sender)
+        .add_attribute("delegatee", delegatee_addr)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount))
+}
+
+/// Consume part of the allowance given by the delegator to the delegatee.
+/// Returns the remaining allowance.
+pub fn decrease_borrow_allowance(
+    store: &mut dyn Storage,
+    delegator_addr: &Addr,
+    delegatee_addr: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let key = (delegator_addr, delegatee_addr, denom);
+    let allowance = BORROW_ALLOWANCES.may_load(store, key)?.unwrap_or_default();
+
+    if amount > allowance {
+        return Err(ContractError::BorrowAllowanceExceeded {
+            delegator: delegator_addr.to_string(),
+            denom: denom.to_string(),
+            amount,
+            allowance,
+        });
+    }
+
+    let remaining = allowance - amount;
+    if remaining.is_zero() {
+        BORROW_ALLOWANCES.remove(store, key);
+    } else {
+        BORROW_ALLOWANCES.save(store, key, &remaining)?;
+    }
+
+    Ok(remaining)
+}
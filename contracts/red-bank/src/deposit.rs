@@ -3,12 +3,14 @@ use mars_interest_rate::get_scaled_liquidity_amount;
 use mars_types::{
     address_provider::{self, MarsAddressType},
     error::MarsError,
+    params::PausableAction,
 };
 
 use crate::{
     error::ContractError,
-    helpers::{query_asset_params, query_total_deposit},
+    helpers::{assert_not_paused, query_asset_params, query_total_deposit},
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    liquidate::clear_liquidation_auction_if_healthy,
     state::{CONFIG, MARKETS},
     user::User,
 };
@@ -63,6 +65,8 @@ pub fn deposit(
         None => User(&info.sender),
     };
 
+    assert_not_paused(&deps.querier, params_addr, PausableAction::Deposits)?;
+
     let mut market = MARKETS.load(deps.storage, &denom)?;
 
     let asset_params = query_asset_params(&deps.querier, params_addr, &denom)?;
@@ -104,7 +108,7 @@ pub fn deposit(
         deposit_amount_scaled,
         incentives_addr,
         response,
-        account_id,
+        account_id.clone(),
     )?;
 
     market.increase_collateral(deposit_amount_scaled)?;
@@ -113,6 +117,11 @@ pub fn deposit(
 
     MARKETS.save(deps.storage, &denom, &market)?;
 
+    // Liquidation auctions only exist for Red Bank users, not credit accounts
+    if account_id.is_none() {
+        clear_liquidation_auction_if_healthy(deps, &env, user.address())?;
+    }
+
     Ok(response
         .add_attribute("action", "deposit")
         .add_attribute("sender", &info.sender)
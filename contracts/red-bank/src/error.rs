@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyFractionError, DivideByZeroError, OverflowError, StdError,
+    CheckedFromRatioError, CheckedMultiplyFractionError, DivideByZeroError, OverflowError,
+    StdError, Uint128,
 };
 use cw_utils::PaymentError;
 use mars_health::error::HealthError;
@@ -135,6 +136,11 @@ pub enum ContractError {
         denom: String,
     },
 
+    #[error("Action is paused: {action}")]
+    ActionPaused {
+        action: String,
+    },
+
     #[error("Cannot liquidate. Debt asset {denom:?}")]
     LiquidationNotAllowedWhenDebtMarketInactive {
         denom: String,
@@ -154,4 +160,31 @@ pub enum ContractError {
 
     #[error("Cannot liquidate credit manager (use credit-manager contract liquidate function)")]
     CannotLiquidateCreditManager {},
+
+    #[error("Cannot delegate borrowing power to self")]
+    CannotDelegateToSelf {},
+
+    #[error("Cannot delegate borrowing power to or from credit manager")]
+    CannotDelegateCreditManager {},
+
+    #[error("Borrow amount {amount} exceeds allowance {allowance} approved by {delegator:?} for {denom:?}")]
+    BorrowAllowanceExceeded {
+        delegator: String,
+        denom: String,
+        amount: Uint128,
+        allowance: Uint128,
+    },
+
+    #[error("Debt of {denom:?} worth {value} would be below the minimum debt value {min_value}, repay it in full instead")]
+    DebtBelowMinValue {
+        denom: String,
+        value: Uint128,
+        min_value: Uint128,
+    },
+
+    #[error("Insufficient funds to pay the price update fee. Required: {required}, available: {available}")]
+    InsufficientPriceUpdateFee {
+        required: Uint128,
+        available: Uint128,
+    },
 }
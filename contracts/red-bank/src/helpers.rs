@@ -1,5 +1,10 @@
-use cosmwasm_std::{Coin, Decimal, QuerierWrapper, StdResult};
-use mars_types::params::{AssetParams, QueryMsg};
+use cosmwasm_std::{Addr, Coin, Decimal, QuerierWrapper, StdResult, Uint128};
+use mars_types::{
+    oracle,
+    params::{AssetParams, PausableAction, PausableContract, QueryMsg},
+};
+
+use crate::error::ContractError;
 
 pub fn query_asset_params(
     querier: &QuerierWrapper,
@@ -14,6 +19,26 @@ pub fn query_asset_params(
     )
 }
 
+pub fn assert_not_paused(
+    querier: &QuerierWrapper,
+    params: impl Into<String>,
+    action: PausableAction,
+) -> Result<(), ContractError> {
+    let paused: bool = querier.query_wasm_smart(
+        params.into(),
+        &QueryMsg::IsPaused {
+            contract: PausableContract::RedBank,
+            action,
+        },
+    )?;
+    if paused {
+        return Err(ContractError::ActionPaused {
+            action: action.to_string(),
+        });
+    }
+    Ok(())
+}
+
 pub fn query_target_health_factor(
     querier: &QuerierWrapper,
     params: impl Into<String>,
@@ -33,3 +58,27 @@ pub fn query_total_deposit(
         },
     )
 }
+
+/// Debt left after a borrow or repay must be either zero or worth at least the asset's
+/// `min_debt_value`. Small debts are uneconomic to liquidate.
+pub fn assert_min_debt_value(
+    querier: &QuerierWrapper,
+    oracle_addr: &Addr,
+    asset_params: &AssetParams,
+    debt_amount: Uint128,
+) -> Result<(), ContractError> {
+    if asset_params.min_debt_value.is_zero() || debt_amount.is_zero() {
+        return Ok(());
+    }
+
+    let price = oracle::helpers::query_price(querier, oracle_addr, &asset_params.denom)?;
+    let value = debt_amount.checked_mul_floor(price)?;
+    if value < asset_params.min_debt_value {
+        return Err(ContractError::DebtBelowMinValue {
+            denom: asset_params.denom.clone(),
+            value,
+            min_value: asset_params.min_debt_value,
+        });
+    }
+    Ok(())
+}
@@ -4,6 +4,7 @@ pub mod collateral;
 pub mod config;
 #[cfg(not(feature = "library"))]
 pub mod contract;
+pub mod delegation;
 pub mod deposit;
 pub mod error;
 pub mod health;
@@ -11,6 +12,7 @@ pub mod instantiate;
 pub mod interest_rates;
 pub mod liquidate;
 pub mod migrations;
+pub mod price_updates;
 pub mod query;
 pub mod repay;
 pub mod state;
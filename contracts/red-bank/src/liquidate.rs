@@ -1,21 +1,29 @@
-use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Uint128};
+use std::collections::HashMap;
+
+use cosmwasm_std::{Addr, Decimal, Deps, DepsMut, Env, MessageInfo, Response, Uint128};
+use mars_health::health::Health;
 use mars_interest_rate::{
     get_scaled_debt_amount, get_scaled_liquidity_amount, get_underlying_debt_amount,
     get_underlying_liquidity_amount,
 };
-use mars_liquidation::liquidation::calculate_liquidation_amounts;
+use mars_liquidation::liquidation::{
+    calculate_dutch_auction_liquidation_bonus, calculate_liquidation_amounts_with_bonus,
+    calculate_liquidation_bonus,
+};
 use mars_types::{
     address_provider::{self, MarsAddressType},
     keys::{UserId, UserIdKey},
+    params::AssetParams,
+    red_bank::{LiquidationAuction, Position},
 };
 use mars_utils::helpers::{build_send_asset_msg, option_string_to_addr};
 
 use crate::{
     error::ContractError,
-    health::get_health_and_positions,
+    health::{compute_position_health, get_health_and_positions},
     helpers::{query_asset_params, query_target_health_factor},
     interest_rates::{apply_accumulated_interests, update_interest_rates},
-    state::{COLLATERALS, CONFIG, DEBTS, MARKETS},
+    state::{COLLATERALS, CONFIG, DEBTS, LIQUIDATION_AUCTIONS, MARKETS},
     user::User,
 };
 
@@ -91,7 +99,7 @@ pub fn liquidate(
     let collateral_market = MARKETS.load(deps.storage, &collateral_denom)?;
 
     // 2. Compute health factor
-    let (health, assets_positions) = get_health_and_positions(
+    let (health, mut assets_positions) = get_health_and_positions(
         &deps.as_ref(),
         &env,
         &liquidatee_addr,
@@ -129,6 +137,22 @@ pub fn liquidate(
     let collateral_params = query_asset_params(&deps.querier, params_addr, &collateral_denom)?;
     let target_health_factor = query_target_health_factor(&deps.querier, params_addr)?;
 
+    // Collaterals in Dutch auction mode start the user's auction if it isn't running yet, or
+    // restart it if it's stale
+    let mut auction = LIQUIDATION_AUCTIONS.may_load(deps.storage, &liquidatee_addr)?;
+    if let Some(dutch_auction) = &collateral_params.liquidation_bonus.dutch_auction {
+        let restart = match &auction {
+            Some(auction) => auction.is_stale(env.block.height, dutch_auction.duration_blocks),
+            None => true,
+        };
+        if restart {
+            auction = Some(LiquidationAuction::new(env.block.height));
+        }
+    }
+
+    let liquidation_bonus =
+        current_liquidation_bonus(env.block.height, auction.as_ref(), &health, &collateral_params)?;
+
     let user_collateral_amount = get_underlying_liquidity_amount(
         user_collateral.amount_scaled,
         &collateral_market,
@@ -138,7 +162,7 @@ pub fn liquidate(
         debt_amount_to_repay,
         collateral_amount_to_liquidate,
         collateral_amount_received_by_liquidator,
-    ) = calculate_liquidation_amounts(
+    ) = calculate_liquidation_amounts_with_bonus(
         user_collateral_amount,
         collateral_price,
         &collateral_params,
@@ -147,6 +171,7 @@ pub fn liquidate(
         debt_price,
         target_health_factor,
         &health,
+        liquidation_bonus,
     )?;
     let protocol_fee = collateral_amount_to_liquidate - collateral_amount_received_by_liquidator;
 
@@ -223,7 +248,27 @@ pub fn liquidate(
     response = update_interest_rates(&env, &mut debt_market_after, response)?;
     MARKETS.save(deps.storage, &debt_denom, &debt_market_after)?;
 
-    // 7. Build response
+    // 7. Keep the auction running until the user is healthy again
+    if let Some(mut auction) = auction {
+        auction.last_seen_block = env.block.height;
+
+        if let Some(position) = assets_positions.get_mut(&collateral_denom) {
+            position.collateral_amount =
+                position.collateral_amount.checked_sub(collateral_amount_to_liquidate)?;
+        }
+        if let Some(position) = assets_positions.get_mut(&debt_denom) {
+            position.debt_amount = position.debt_amount.checked_sub(debt_amount_to_repay)?;
+        }
+        let health_after = compute_position_health(&assets_positions)?;
+
+        if health_after.is_liquidatable() {
+            LIQUIDATION_AUCTIONS.save(deps.storage, &liquidatee_addr, &auction)?;
+        } else {
+            LIQUIDATION_AUCTIONS.remove(deps.storage, &liquidatee_addr);
+        }
+    }
+
+    // 8. Build response
     // refund sent amount in excess of actual debt amount to liquidate
     if !refund_amount.is_zero() {
         response =
@@ -242,3 +287,164 @@ pub fn liquidate(
         .add_attribute("debt_amount", debt_amount_to_repay)
         .add_attribute("debt_amount_scaled", debt_amount_scaled_delta))
 }
+
+/// Start the Dutch liquidation auction of a liquidatable user, or clear it if the user is healthy
+pub fn refresh_liquidation_auction(
+    deps: DepsMut,
+    env: Env,
+    user_addr: Addr,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params, MarsAddressType::CreditManager],
+    )?;
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+    let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+
+    if user_addr == credit_manager_addr {
+        return Err(ContractError::CannotLiquidateCreditManager {});
+    }
+
+    let (health, positions) = get_health_and_positions(
+        &deps.as_ref(),
+        &env,
+        &user_addr,
+        "",
+        oracle_addr,
+        params_addr,
+        true,
+    )?;
+
+    let auction = LIQUIDATION_AUCTIONS.may_load(deps.storage, &user_addr)?;
+    let status = match (health.is_liquidatable(), auction) {
+        (true, None) => {
+            LIQUIDATION_AUCTIONS.save(
+                deps.storage,
+                &user_addr,
+                &LiquidationAuction::new(env.block.height),
+            )?;
+            "started"
+        }
+        (true, Some(mut auction)) => {
+            // Stale for all Dutch auction collaterals of the user
+            let stale = match max_auction_duration(deps.as_ref(), params_addr, &positions)? {
+                Some(duration_blocks) => auction.is_stale(env.block.height, duration_blocks),
+                None => false,
+            };
+            let status = if stale {
+                auction = LiquidationAuction::new(env.block.height);
+                "restarted"
+            } else {
+                auction.last_seen_block = env.block.height;
+                "unchanged"
+            };
+            LIQUIDATION_AUCTIONS.save(deps.storage, &user_addr, &auction)?;
+            status
+        }
+        (false, Some(_)) => {
+            LIQUIDATION_AUCTIONS.remove(deps.storage, &user_addr);
+            "cleared"
+        }
+        _ => "unchanged",
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "refresh_liquidation_auction")
+        .add_attribute("user", user_addr)
+        .add_attribute("status", status))
+}
+
+/// Longest auction duration among the Dutch auction collaterals of the user
+fn max_auction_duration(
+    deps: Deps,
+    params_addr: &Addr,
+    positions: &HashMap<String, Position>,
+) -> Result<Option<u64>, ContractError> {
+    let mut max_duration = None;
+    for position in positions.values().filter(|p| !p.collateral_amount.is_zero()) {
+        let params = query_asset_params(&deps.querier, params_addr, &position.denom)?;
+        if let Some(dutch_auction) = params.liquidation_bonus.dutch_auction {
+            max_duration = max_duration.max(Some(dutch_auction.duration_blocks));
+        }
+    }
+    Ok(max_duration)
+}
+
+/// Clear the user's Dutch liquidation auction if they are healthy again after a repay, deposit or
+/// collateral update, so that the next time they become liquidatable the auction starts over
+/// from `starting_lb` instead of the bonus of the previous auction
+pub fn clear_liquidation_auction_if_healthy(
+    deps: DepsMut,
+    env: &Env,
+    user_addr: &Addr,
+) -> Result<(), ContractError> {
+    if !LIQUIDATION_AUCTIONS.has(deps.storage, user_addr) {
+        return Ok(());
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps.as_ref(),
+        &config.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params],
+    )?;
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+
+    let (health, _) = get_health_and_positions(
+        &deps.as_ref(),
+        env,
+        user_addr,
+        "",
+        oracle_addr,
+        params_addr,
+        true,
+    )?;
+
+    if !health.is_liquidatable() {
+        LIQUIDATION_AUCTIONS.remove(deps.storage, user_addr);
+    }
+
+    Ok(())
+}
+
+/// Liquidation bonus offered for a collateral of a liquidatable user at the given block.
+///
+/// If the collateral is in Dutch auction mode, the bonus depends on the number of blocks elapsed
+/// since the start of the user's auction (an auction which is not started yet would start at the
+/// given block). Otherwise the bonus is derived from the user's Health Factor.
+pub fn current_liquidation_bonus(
+    block_height: u64,
+    auction: Option<&LiquidationAuction>,
+    health: &Health,
+    collateral_params: &AssetParams,
+) -> Result<Decimal, ContractError> {
+    if let Some(dutch_auction) = &collateral_params.liquidation_bonus.dutch_auction {
+        // A stale auction would start over at this block
+        let elapsed_blocks = auction
+            .filter(|auction| !auction.is_stale(block_height, dutch_auction.duration_blocks))
+            .map(|auction| block_height.saturating_sub(auction.start_block))
+            .unwrap_or(0);
+        return Ok(calculate_dutch_auction_liquidation_bonus(
+            elapsed_blocks,
+            health.total_collateral_value,
+            health.total_debt_value,
+            collateral_params,
+        )?);
+    }
+
+    let liquidation_health_factor =
+        health.liquidation_health_factor.ok_or(ContractError::CannotLiquidateHealthyPosition {})?;
+
+    Ok(calculate_liquidation_bonus(
+        liquidation_health_factor,
+        health.total_collateral_value,
+        health.total_debt_value,
+        collateral_params,
+    )?)
+}
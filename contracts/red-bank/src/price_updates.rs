@@ -0,0 +1,94 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg,
+};
+use mars_types::{
+    adapters::pyth::Pyth,
+    address_provider::{self, MarsAddressType},
+    red_bank::{CallbackMsg, ExecuteMsg},
+};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::CONFIG,
+};
+
+/// Takes the Pyth price updates out of the message, if it has any
+pub fn take_price_updates(msg: &mut ExecuteMsg) -> Option<Vec<Binary>> {
+    match msg {
+        ExecuteMsg::Withdraw {
+            price_updates,
+            ..
+        }
+        | ExecuteMsg::Borrow {
+            price_updates,
+            ..
+        }
+        | ExecuteMsg::Liquidate {
+            price_updates,
+            ..
+        } => price_updates.take(),
+        _ => None,
+    }
+}
+
+/// Pushes the Pyth price updates before executing the message.
+///
+/// Health checks run while the message is executed, so it's deferred to a callback which is
+/// executed after the Pyth contract has stored the new prices. The update fee is paid from the
+/// sent funds, the remaining funds are passed on to the callback.
+pub fn update_prices_and_dispatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_updates: Vec<Binary>,
+    msg: ExecuteMsg,
+) -> ContractResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let pyth_addr = address_provider::helpers::query_contract_addr(
+        deps.as_ref(),
+        &config.address_provider,
+        MarsAddressType::Pyth,
+    )?;
+    let pyth = Pyth::new(pyth_addr);
+
+    let fee = pyth.query_update_fee(&deps.querier, &price_updates)?;
+    let funds = deduct_fee(info.funds, &fee)?;
+
+    let update_msg = pyth.update_price_feeds_msg(price_updates.clone(), fee.clone())?;
+    let dispatch_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_json_binary(&ExecuteMsg::Callback(CallbackMsg::Dispatch {
+            sender: info.sender.to_string(),
+            msg: Box::new(msg),
+        }))?,
+        funds,
+    });
+
+    Ok(Response::new()
+        .add_message(update_msg)
+        .add_message(dispatch_msg)
+        .add_attribute("action", "update_prices")
+        .add_attribute("price_updates", price_updates.len().to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+fn deduct_fee(mut funds: Vec<Coin>, fee: &Coin) -> ContractResult<Vec<Coin>> {
+    if fee.amount.is_zero() {
+        return Ok(funds);
+    }
+
+    let available =
+        funds.iter().find(|c| c.denom == fee.denom).map(|c| c.amount).unwrap_or_default();
+    if available < fee.amount {
+        return Err(ContractError::InsufficientPriceUpdateFee {
+            required: fee.amount,
+            available,
+        });
+    }
+
+    for coin in funds.iter_mut().filter(|c| c.denom == fee.denom) {
+        coin.amount -= fee.amount;
+    }
+    funds.retain(|c| c.amount != Uint128::zero());
+    Ok(funds)
+}
@@ -9,16 +9,18 @@ use mars_types::{
     address_provider::{self, MarsAddressType},
     keys::{UserId, UserIdKey},
     red_bank::{
-        Collateral, ConfigResponse, Debt, Market, MarketV2Response,
-        PaginatedUserCollateralResponse, UserCollateralResponse, UserDebtResponse,
-        UserHealthStatus, UserPositionResponse,
+        BorrowAllowanceResponse, Collateral, CollateralLiquidationBonus, ConfigResponse, Debt,
+        LiquidationAuctionResponse, Market, MarketV2Response, PaginatedUserCollateralResponse,
+        UserCollateralResponse, UserDebtResponse, UserHealthStatus, UserPositionResponse,
     },
 };
 
 use crate::{
     error::{ContractError, ContractResult},
     health,
-    state::{COLLATERALS, CONFIG, DEBTS, MARKETS, OWNER},
+    helpers::query_asset_params,
+    liquidate,
+    state::{BORROW_ALLOWANCES, COLLATERALS, CONFIG, DEBTS, LIQUIDATION_AUCTIONS, MARKETS, OWNER},
 };
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -250,6 +252,109 @@ pub fn query_user_collaterals_v2(
     )
 }
 
+pub fn query_borrow_allowance(
+    deps: Deps,
+    delegator_addr: Addr,
+    delegatee_addr: Addr,
+    denom: String,
+) -> StdResult<BorrowAllowanceResponse> {
+    let amount = BORROW_ALLOWANCES
+        .may_load(deps.storage, (&delegator_addr, &delegatee_addr, &denom))?
+        .unwrap_or_default();
+
+    Ok(BorrowAllowanceResponse {
+        delegator: delegator_addr.to_string(),
+        delegatee: delegatee_addr.to_string(),
+        denom,
+        amount,
+    })
+}
+
+pub fn query_borrow_allowances(
+    deps: Deps,
+    delegator_addr: Addr,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<BorrowAllowanceResponse>> {
+    let start_after = start_after
+        .map(|(delegatee, denom)| -> StdResult<_> {
+            Ok((deps.api.addr_validate(&delegatee)?, denom))
+        })
+        .transpose()?;
+    let start = start_after
+        .as_ref()
+        .map(|(delegatee_addr, denom)| Bound::exclusive((delegatee_addr, denom.as_str())));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    BORROW_ALLOWANCES
+        .sub_prefix(&delegator_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let ((delegatee_addr, denom), amount) = item?;
+            Ok(BorrowAllowanceResponse {
+                delegator: delegator_addr.to_string(),
+                delegatee: delegatee_addr.to_string(),
+                denom,
+                amount,
+            })
+        })
+        .collect()
+}
+
+pub fn query_liquidation_auction(
+    deps: Deps,
+    env: Env,
+    user_addr: Addr,
+) -> Result<LiquidationAuctionResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let addresses = address_provider::helpers::query_contract_addrs(
+        deps,
+        &config.address_provider,
+        vec![MarsAddressType::Oracle, MarsAddressType::Params],
+    )?;
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
+
+    let auction = LIQUIDATION_AUCTIONS.may_load(deps.storage, &user_addr)?;
+
+    let (health, positions) = health::get_health_and_positions(
+        &deps,
+        &env,
+        &user_addr,
+        "",
+        oracle_addr,
+        params_addr,
+        true,
+    )?;
+
+    let mut liquidation_bonuses = vec![];
+    if health.is_liquidatable() {
+        for position in positions.values().filter(|p| !p.collateral_amount.is_zero()) {
+            let params = query_asset_params(&deps.querier, params_addr, &position.denom)?;
+            let liquidation_bonus = liquidate::current_liquidation_bonus(
+                env.block.height,
+                auction.as_ref(),
+                &health,
+                &params,
+            )?;
+            liquidation_bonuses.push(CollateralLiquidationBonus {
+                denom: position.denom.clone(),
+                liquidation_bonus,
+                dutch_auction: params.liquidation_bonus.dutch_auction.is_some(),
+            });
+        }
+        liquidation_bonuses.sort_by(|a, b| a.denom.cmp(&b.denom));
+    }
+
+    Ok(LiquidationAuctionResponse {
+        user: user_addr.to_string(),
+        start_block: auction.map(|auction| auction.start_block),
+        liquidation_bonuses,
+    })
+}
+
 pub fn query_scaled_liquidity_amount(
     deps: Deps,
     env: Env,
@@ -5,7 +5,9 @@ use mars_utils::helpers::build_send_asset_msg;
 
 use crate::{
     error::ContractError,
+    helpers::{assert_min_debt_value, query_asset_params},
     interest_rates::{apply_accumulated_interests, update_interest_rates},
+    liquidate::clear_liquidation_auction_if_healthy,
     state::{CONFIG, DEBTS, MARKETS},
     user::User,
 };
@@ -27,11 +29,15 @@ pub fn repay(
             MarsAddressType::Incentives,
             MarsAddressType::RewardsCollector,
             MarsAddressType::CreditManager,
+            MarsAddressType::Oracle,
+            MarsAddressType::Params,
         ],
     )?;
     let rewards_collector_addr = &addresses[&MarsAddressType::RewardsCollector];
     let incentives_addr = &addresses[&MarsAddressType::Incentives];
     let credit_manager_addr = &addresses[&MarsAddressType::CreditManager];
+    let oracle_addr = &addresses[&MarsAddressType::Oracle];
+    let params_addr = &addresses[&MarsAddressType::Params];
 
     let user_addr: Addr;
     let user = match on_behalf_of.as_ref() {
@@ -79,6 +85,12 @@ pub fn repay(
         debt_amount_after = debt_amount_before - repay_amount;
     }
 
+    // Partial repayments can't leave dust debt behind (credit manager debts are checked there)
+    if !debt_amount_after.is_zero() && user.address() != credit_manager_addr {
+        let asset_params = query_asset_params(&deps.querier, params_addr, &denom)?;
+        assert_min_debt_value(&deps.querier, oracle_addr, &asset_params, debt_amount_after)?;
+    }
+
     let debt_amount_scaled_after =
         get_scaled_debt_amount(debt_amount_after, &market, env.block.time.seconds())?;
 
@@ -91,6 +103,10 @@ pub fn repay(
     response = update_interest_rates(&env, &mut market, response)?;
     MARKETS.save(deps.storage, &denom, &market)?;
 
+    if user.address() != credit_manager_addr {
+        clear_liquidation_auction_if_healthy(deps, &env, user.address())?;
+    }
+
     Ok(response
         .add_attribute("action", "repay")
         .add_attribute("sender", &info.sender)
@@ -1,9 +1,9 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 use mars_owner::Owner;
 use mars_types::{
     keys::UserIdKey,
-    red_bank::{Collateral, Config, Debt, Market},
+    red_bank::{Collateral, Config, Debt, LiquidationAuction, Market},
 };
 use mars_utils::guard::Guard;
 
@@ -13,5 +13,12 @@ pub const MARKETS: Map<&str, Market> = Map::new("markets");
 pub const COLLATERALS: Map<(&UserIdKey, &str), Collateral> = Map::new("colls");
 pub const DEBTS: Map<(&Addr, &str), Debt> = Map::new("debts");
 
+/// Amount a delegatee is allowed to borrow on behalf of a delegator, keyed by
+/// (delegator, delegatee, denom)
+pub const BORROW_ALLOWANCES: Map<(&Addr, &Addr, &str), Uint128> = Map::new("borrow_allowances");
+
+/// Dutch liquidation auctions of unhealthy users
+pub const LIQUIDATION_AUCTIONS: Map<&Addr, LiquidationAuction> = Map::new("liquidation_auctions");
+
 /// Used to mark the contract as locked during migrations
 pub const MIGRATION_GUARD: Guard = Guard::new("guard");
@@ -129,6 +129,7 @@ pub fn th_default_asset_params() -> AssetParams {
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -141,9 +142,11 @@ pub fn th_default_asset_params() -> AssetParams {
             slope: Decimal::one(),
             min_lb: Decimal::percent(0u64),
             max_lb: Decimal::percent(5u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
     }
 }
 
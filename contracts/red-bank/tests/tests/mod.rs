@@ -1,15 +1,18 @@
 mod helpers;
 
 mod test_admin;
+mod test_approve_delegation;
 mod test_borrow;
 mod test_credit_accounts;
 mod test_deposit;
 mod test_health;
 mod test_inflated_collateral;
 mod test_liquidate;
+mod test_liquidation_auction;
 mod test_migration_v2;
 mod test_misc;
 mod test_payment;
+mod test_price_updates;
 mod test_query;
 mod test_update_owner;
 mod test_withdraw;
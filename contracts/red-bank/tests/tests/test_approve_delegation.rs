@@ -0,0 +1,166 @@
+use cosmwasm_std::{attr, testing::mock_info, Addr, Uint128};
+use mars_red_bank::{contract::execute, error::ContractError, state::BORROW_ALLOWANCES};
+use mars_testing::{mock_env, MockEnvParams};
+use mars_types::{
+    address_provider::MarsAddressType,
+    red_bank::{BorrowAllowanceResponse, ExecuteMsg, Market, QueryMsg},
+};
+
+use super::helpers::{th_init_market, th_query, th_setup};
+
+#[test]
+fn approve_and_revoke_delegation() {
+    let mut deps = th_setup(&[]);
+    th_init_market(deps.as_mut(), "uosmo", &Market::default());
+    th_init_market(deps.as_mut(), "uusd", &Market::default());
+
+    let delegator_addr = Addr::unchecked("delegator");
+    let delegatee_addr = Addr::unchecked("delegatee");
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: delegatee_addr.to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(1000),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegator", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "approve_delegation"),
+            attr("delegator", "delegator"),
+            attr("delegatee", "delegatee"),
+            attr("denom", "uosmo"),
+            attr("amount", "1000"),
+        ]
+    );
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: delegatee_addr.to_string(),
+        denom: "uusd".to_string(),
+        amount: Uint128::new(2000),
+    };
+    execute(deps.as_mut(), mock_env(MockEnvParams::default()), mock_info("delegator", &[]), msg)
+        .unwrap();
+
+    let allowance: BorrowAllowanceResponse = th_query(
+        deps.as_ref(),
+        QueryMsg::BorrowAllowance {
+            delegator: delegator_addr.to_string(),
+            delegatee: delegatee_addr.to_string(),
+            denom: "uosmo".to_string(),
+        },
+    );
+    assert_eq!(allowance.amount, Uint128::new(1000));
+
+    let allowances: Vec<BorrowAllowanceResponse> = th_query(
+        deps.as_ref(),
+        QueryMsg::BorrowAllowances {
+            delegator: delegator_addr.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(
+        allowances,
+        vec![
+            BorrowAllowanceResponse {
+                delegator: delegator_addr.to_string(),
+                delegatee: delegatee_addr.to_string(),
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(1000),
+            },
+            BorrowAllowanceResponse {
+                delegator: delegator_addr.to_string(),
+                delegatee: delegatee_addr.to_string(),
+                denom: "uusd".to_string(),
+                amount: Uint128::new(2000),
+            },
+        ]
+    );
+
+    let allowances: Vec<BorrowAllowanceResponse> = th_query(
+        deps.as_ref(),
+        QueryMsg::BorrowAllowances {
+            delegator: delegator_addr.to_string(),
+            start_after: Some((delegatee_addr.to_string(), "uosmo".to_string())),
+            limit: None,
+        },
+    );
+    assert_eq!(allowances.len(), 1);
+    assert_eq!(allowances[0].denom, "uusd".to_string());
+
+    // Approving zero amount revokes the delegation
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: delegatee_addr.to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::zero(),
+    };
+    execute(deps.as_mut(), mock_env(MockEnvParams::default()), mock_info("delegator", &[]), msg)
+        .unwrap();
+    assert!(!BORROW_ALLOWANCES.has(&deps.storage, (&delegator_addr, &delegatee_addr, "uosmo")));
+}
+
+#[test]
+fn cannot_delegate_to_self() {
+    let mut deps = th_setup(&[]);
+    th_init_market(deps.as_mut(), "uosmo", &Market::default());
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: "delegator".to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(1000),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegator", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotDelegateToSelf {});
+}
+
+#[test]
+fn cannot_delegate_to_credit_manager() {
+    let mut deps = th_setup(&[]);
+    th_init_market(deps.as_mut(), "uosmo", &Market::default());
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: MarsAddressType::CreditManager.to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(1000),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegator", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CannotDelegateCreditManager {});
+}
+
+#[test]
+fn cannot_delegate_for_uninitialized_asset() {
+    let mut deps = th_setup(&[]);
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: "delegatee".to_string(),
+        denom: "uosmo".to_string(),
+        amount: Uint128::new(1000),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegator", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetNotInitialized {});
+}
@@ -9,7 +9,7 @@ use mars_interest_rate::{
 use mars_red_bank::{
     contract::execute,
     error::ContractError,
-    state::{DEBTS, MARKETS},
+    state::{BORROW_ALLOWANCES, DEBTS, MARKETS},
 };
 use mars_testing::{mock_env, mock_env_at_block_time, MockEnvParams};
 use mars_types::{
@@ -106,6 +106,8 @@ fn borrow_and_repay() {
         denom: "uosmo".to_string(),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
 
     let env = mock_env_at_block_time(block_time);
@@ -173,6 +175,8 @@ fn borrow_and_repay() {
         denom: "uosmo".to_string(),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
 
     let env = mock_env_at_block_time(block_time);
@@ -223,6 +227,8 @@ fn borrow_and_repay() {
         denom: String::from("uusd"),
         amount: borrow_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -285,6 +291,8 @@ fn borrow_and_repay() {
         denom: String::from("uusd"),
         amount: Uint128::from(83968_u128),
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(error_res, ContractError::BorrowAmountExceedsGivenCollateral {});
@@ -538,6 +546,8 @@ fn repay_without_refund_on_behalf_of() {
         denom: String::from("borrowedcoinnative"),
         amount: Uint128::from(borrow_amount),
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -633,6 +643,8 @@ fn repay_with_refund_on_behalf_of() {
         denom: String::from("borrowedcoinnative"),
         amount: Uint128::from(borrow_amount),
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -750,6 +762,8 @@ fn borrow_uusd() {
         denom: "uusd".to_string(),
         amount: max_to_borrow + Uint128::from(1u128),
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let env = mock_env_at_block_time(new_block_time);
     let info = mock_info("borrower", &[]);
@@ -761,6 +775,8 @@ fn borrow_uusd() {
         denom: "uusd".to_string(),
         amount: valid_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let env = mock_env_at_block_time(block_time);
     let info = mock_info("borrower", &[]);
@@ -830,6 +846,8 @@ fn borrow_full_liquidity_and_then_repay() {
             denom: "uusd".to_string(),
             amount: initial_liquidity.into(),
             recipient: None,
+            on_behalf_of: None,
+            price_updates: None,
         };
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -972,6 +990,8 @@ fn borrow_collateral_check() {
         denom: "uosmo".to_string(),
         amount: exceeding_borrow_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let env = mock_env(MockEnvParams::default());
     let info = mock_info("borrower", &[]);
@@ -983,6 +1003,8 @@ fn borrow_collateral_check() {
         denom: "uosmo".to_string(),
         amount: permissible_borrow_amount,
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     execute(deps.as_mut(), env, info, borrow_msg).unwrap();
 }
@@ -1000,6 +1022,7 @@ fn cannot_borrow_if_market_not_enabled() {
                 whitelisted: false,
 
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -1016,6 +1039,8 @@ fn cannot_borrow_if_market_not_enabled() {
         denom: "somecoin".to_string(),
         amount: Uint128::new(1000),
         recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(
@@ -1060,6 +1085,8 @@ fn borrow_and_send_funds_to_another_user() {
         denom: "uusd".to_string(),
         amount: borrow_amount,
         recipient: Some(another_user_addr.to_string()),
+        on_behalf_of: None,
+        price_updates: None,
     };
     let env = mock_env(MockEnvParams::default());
     let info = mock_info("borrower", &[]);
@@ -1106,3 +1133,316 @@ fn borrow_and_send_funds_to_another_user() {
         ]
     );
 }
+
+#[test]
+fn borrow_on_behalf_of_delegator() {
+    let initial_liquidity = 10000000;
+    let mut deps = th_setup(&[coin(initial_liquidity, "uusd")]);
+
+    let delegator_addr = Addr::unchecked("delegator");
+    let delegatee_addr = Addr::unchecked("delegatee");
+
+    let mock_market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000_000_000u128),
+        debt_total_scaled: Uint128::zero(),
+        ..Default::default()
+    };
+    let market = th_init_market(deps.as_mut(), "uusd", &mock_market);
+
+    deps.querier.set_redbank_params(
+        "uusd",
+        AssetParams {
+            max_loan_to_value: Decimal::from_ratio(5u128, 10u128),
+            ..th_default_asset_params()
+        },
+    );
+
+    // Only the delegator has collateral deposited
+    let deposit_amount_scaled = Uint128::new(100_000) * SCALING_FACTOR;
+    set_collateral(deps.as_mut(), &delegator_addr, &market.denom, deposit_amount_scaled, true);
+
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: delegatee_addr.to_string(),
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1500),
+    };
+    execute(deps.as_mut(), mock_env(MockEnvParams::default()), mock_info("delegator", &[]), msg)
+        .unwrap();
+
+    // Delegatee has no collateral and cannot borrow for itself
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000),
+        recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
+    };
+    let error_res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegatee", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(error_res, ContractError::BorrowAmountExceedsGivenCollateral {});
+
+    // Borrow on behalf of the delegator, funds are sent to the delegatee
+    let borrow_amount = Uint128::from(1000u128);
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: borrow_amount,
+        recipient: None,
+        on_behalf_of: Some(delegator_addr.to_string()),
+        price_updates: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegatee", &[]),
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: delegatee_addr.to_string(),
+            amount: coins(borrow_amount.u128(), "uusd")
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "borrow"),
+            attr("sender", delegatee_addr.as_str()),
+            attr("recipient", delegatee_addr.as_str()),
+            attr("denom", "uusd"),
+            attr("amount", borrow_amount.to_string()),
+            attr("amount_scaled", borrow_amount * SCALING_FACTOR),
+            attr("on_behalf_of", delegator_addr.as_str()),
+            attr("remaining_allowance", "500"),
+        ]
+    );
+
+    // Debt is recorded against the delegator only
+    assert!(has_debt_position(deps.as_ref(), &delegator_addr, "uusd"));
+    assert!(!has_debt_position(deps.as_ref(), &delegatee_addr, "uusd"));
+
+    let allowance =
+        BORROW_ALLOWANCES.load(&deps.storage, (&delegator_addr, &delegatee_addr, "uusd")).unwrap();
+    assert_eq!(allowance, Uint128::new(500));
+
+    // Borrowing above the remaining allowance fails
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(501),
+        recipient: None,
+        on_behalf_of: Some(delegator_addr.to_string()),
+        price_updates: None,
+    };
+    let error_res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegatee", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error_res,
+        ContractError::BorrowAllowanceExceeded {
+            delegator: delegator_addr.to_string(),
+            denom: "uusd".to_string(),
+            amount: Uint128::new(501),
+            allowance: Uint128::new(500),
+        }
+    );
+
+    // Using the full remaining allowance removes it from storage
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(500),
+        recipient: None,
+        on_behalf_of: Some(delegator_addr.to_string()),
+        price_updates: None,
+    };
+    execute(deps.as_mut(), mock_env(MockEnvParams::default()), mock_info("delegatee", &[]), msg)
+        .unwrap();
+    assert!(!BORROW_ALLOWANCES.has(&deps.storage, (&delegator_addr, &delegatee_addr, "uusd")));
+}
+
+#[test]
+fn borrow_on_behalf_of_delegator_checks_delegator_health() {
+    let initial_liquidity = 10000000;
+    let mut deps = th_setup(&[coin(initial_liquidity, "uusd")]);
+
+    let delegator_addr = Addr::unchecked("delegator");
+
+    let mock_market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000_000_000u128),
+        debt_total_scaled: Uint128::zero(),
+        ..Default::default()
+    };
+    let market = th_init_market(deps.as_mut(), "uusd", &mock_market);
+
+    deps.querier.set_redbank_params(
+        "uusd",
+        AssetParams {
+            max_loan_to_value: Decimal::from_ratio(5u128, 10u128),
+            ..th_default_asset_params()
+        },
+    );
+
+    // Delegator can borrow up to 500 uusd
+    let deposit_amount_scaled = Uint128::new(1_000) * SCALING_FACTOR;
+    set_collateral(deps.as_mut(), &delegator_addr, &market.denom, deposit_amount_scaled, true);
+
+    // Allowance is larger than what the delegator's collateral can support
+    let msg = ExecuteMsg::ApproveDelegation {
+        delegatee: "delegatee".to_string(),
+        denom: "uusd".to_string(),
+        amount: Uint128::new(10_000),
+    };
+    execute(deps.as_mut(), mock_env(MockEnvParams::default()), mock_info("delegator", &[]), msg)
+        .unwrap();
+
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(600),
+        recipient: None,
+        on_behalf_of: Some(delegator_addr.to_string()),
+        price_updates: None,
+    };
+    let error_res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegatee", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(error_res, ContractError::BorrowAmountExceedsGivenCollateral {});
+}
+
+#[test]
+fn cannot_borrow_on_behalf_of_without_delegation() {
+    let mut deps = th_setup(&[coin(10000000, "uusd")]);
+
+    let mock_market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000_000_000u128),
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uusd", &mock_market);
+    deps.querier.set_redbank_params("uusd", th_default_asset_params());
+
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1000),
+        recipient: None,
+        on_behalf_of: Some("delegator".to_string()),
+        price_updates: None,
+    };
+    let error_res = execute(
+        deps.as_mut(),
+        mock_env(MockEnvParams::default()),
+        mock_info("delegatee", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error_res,
+        ContractError::BorrowAllowanceExceeded {
+            delegator: "delegator".to_string(),
+            denom: "uusd".to_string(),
+            amount: Uint128::new(1000),
+            allowance: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn debt_cannot_be_left_below_min_debt_value() {
+    let mut deps = th_setup(&[coin(1_000_000, "uusd")]);
+    let borrower_addr = Addr::unchecked("borrower");
+    let env = mock_env_at_block_time(1);
+
+    let mock_market = Market {
+        liquidity_index: Decimal::one(),
+        borrow_index: Decimal::one(),
+        collateral_total_scaled: Uint128::new(1_000_000_000_000u128),
+        indexes_last_updated: 1,
+        ..Default::default()
+    };
+    th_init_market(deps.as_mut(), "uusd", &mock_market);
+    deps.querier.set_oracle_price("uusd", Decimal::one());
+    deps.querier.set_redbank_params(
+        "uusd",
+        AssetParams {
+            min_debt_value: Uint128::new(100),
+            ..th_default_asset_params()
+        },
+    );
+    set_collateral(
+        deps.as_mut(),
+        &borrower_addr,
+        "uusd",
+        Uint128::new(1_000_000) * SCALING_FACTOR,
+        true,
+    );
+
+    // Borrowing less than the min debt value
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(50),
+        recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
+    };
+    let error_res =
+        execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), msg).unwrap_err();
+    assert_eq!(
+        error_res,
+        ContractError::DebtBelowMinValue {
+            denom: "uusd".to_string(),
+            value: Uint128::new(50),
+            min_value: Uint128::new(100),
+        }
+    );
+
+    let msg = ExecuteMsg::Borrow {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(150),
+        recipient: None,
+        on_behalf_of: None,
+        price_updates: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("borrower", &[]), msg).unwrap();
+
+    // Partial repay leaving dust debt
+    let msg = ExecuteMsg::Repay {
+        on_behalf_of: None,
+    };
+    let error_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("borrower", &coins(100, "uusd")),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error_res,
+        ContractError::DebtBelowMinValue {
+            denom: "uusd".to_string(),
+            value: Uint128::new(50),
+            min_value: Uint128::new(100),
+        }
+    );
+
+    // Full repay is always allowed
+    execute(deps.as_mut(), env, mock_info("borrower", &coins(150, "uusd")), msg).unwrap();
+    assert!(!has_debt_position(deps.as_ref(), &borrower_addr, "uusd"));
+}
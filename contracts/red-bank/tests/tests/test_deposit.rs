@@ -18,7 +18,10 @@ use mars_types::{
     error::MarsError,
     incentives,
     keys::{UserId, UserIdKey},
-    params::{AssetParams, CmSettings, LiquidationBonus, RedBankSettings},
+    params::{
+        AssetParams, CmSettings, LiquidationBonus, PausableAction, PausableContract,
+        RedBankSettings,
+    },
     red_bank::{Collateral, ExecuteMsg, Market},
 };
 use test_case::test_case;
@@ -67,10 +70,12 @@ fn setup_test() -> TestSuite {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -78,6 +83,7 @@ fn setup_test() -> TestSuite {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Uint128::new(12_000_000),
+            min_debt_value: Uint128::zero(),
         },
     );
 
@@ -185,6 +191,7 @@ fn depositing_to_disabled_market() {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -212,6 +219,35 @@ fn depositing_to_disabled_market() {
     );
 }
 
+#[test]
+fn depositing_when_paused() {
+    let TestSuite {
+        mut deps,
+        denom,
+        depositor_addr,
+        ..
+    } = setup_test();
+
+    deps.querier.set_paused(PausableContract::RedBank, PausableAction::Deposits);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(depositor_addr.as_str(), &coins(123, denom)),
+        ExecuteMsg::Deposit {
+            account_id: None,
+            on_behalf_of: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ActionPaused {
+            action: "deposits".to_string(),
+        }
+    );
+}
+
 // note: the initial deposit amount set in the TestSuite is 11_000_000 uosmo
 #[test_case(
     1_000_001,
@@ -241,6 +277,7 @@ fn depositing_above_cap(amount_to_deposit: u128, deposit_cap: u128, exp_ok: bool
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -147,6 +147,7 @@ fn atom_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParams) {
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -159,9 +160,11 @@ fn atom_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParams) {
             slope: Decimal::from_str("1").unwrap(),
             min_lb: Decimal::percent(5),
             max_lb: Decimal::percent(20),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(25),
         deposit_cap: Uint128::from(700000000000u128),
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
@@ -181,6 +184,7 @@ fn osmo_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParams) {
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -193,9 +197,11 @@ fn osmo_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParams) {
             slope: Decimal::from_str("1").unwrap(),
             min_lb: Decimal::percent(5),
             max_lb: Decimal::percent(20),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(25),
         deposit_cap: Uint128::from(10000000000000u128),
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
@@ -76,6 +76,7 @@ fn liquidate_if_no_coins_sent() {
         user: "user".to_string(),
         collateral_denom: "collateral".to_string(),
         recipient: None,
+        price_updates: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(error_res, PaymentError::NoFunds {}.into());
@@ -90,6 +91,7 @@ fn liquidate_if_many_coins_sent() {
         user: "user".to_string(),
         collateral_denom: "collateral".to_string(),
         recipient: None,
+        price_updates: None,
     };
     let error_res = execute(deps.as_mut(), env, info, msg).unwrap_err();
     assert_eq!(error_res, PaymentError::MultipleDenoms {}.into());
@@ -710,6 +712,7 @@ fn mdr_negative() {
             slope: Decimal::from_str("2.0").unwrap(),
             min_lb: Decimal::percent(10),
             max_lb: Decimal::percent(10),
+            dutch_auction: None,
         },
     );
     red_bank.init_asset(&mut mock_env, &asset_params.denom, market_params);
@@ -953,6 +956,8 @@ fn response_verification() {
             denom: "uusdc".to_string(),
             amount: Uint128::from(3000u128),
             recipient: None,
+            on_behalf_of: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -964,6 +969,8 @@ fn response_verification() {
             denom: "untrn".to_string(),
             amount: Uint128::from(1200u128),
             recipient: None,
+            on_behalf_of: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -1011,6 +1018,7 @@ fn response_verification() {
             user: liquidatee.to_string(),
             collateral_denom: "uosmo".to_string(),
             recipient: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -1287,6 +1295,7 @@ fn default_asset_params_with(
             slope: Decimal::from_str("2.0").unwrap(),
             min_lb: Decimal::percent(2),
             max_lb: Decimal::percent(10),
+            dutch_auction: None,
         },
     )
 }
@@ -1311,6 +1320,7 @@ fn _default_asset_params_with(
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -1321,6 +1331,7 @@ fn _default_asset_params_with(
         liquidation_bonus,
         protocol_liquidation_fee: Decimal::percent(2),
         deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
@@ -0,0 +1,345 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cw_multi_test::AppResponse;
+use mars_testing::integration::mock_env::{MockEnv, MockEnvBuilder};
+use mars_types::{
+    params::{AssetParams, CmSettings, DutchAuction, LiquidationBonus, RedBankSettings},
+    red_bank::{CollateralLiquidationBonus, InitOrUpdateAssetParams, InterestRateModel},
+};
+
+const AUCTION_DURATION_BLOCKS: u64 = 100;
+
+#[test]
+fn auction_not_started_for_healthy_user() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    let res =
+        red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    assert_eq!(wasm_attribute(&res, "status"), "unchanged");
+
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+    assert!(auction.liquidation_bonuses.is_empty());
+}
+
+#[test]
+fn liquidation_bonus_increases_over_auction() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    // change price to be able to liquidate
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+
+    let start_block = mock_env.app.block_info().height;
+    let res =
+        red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    assert_eq!(wasm_attribute(&res, "status"), "started");
+
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+    assert_eq!(
+        auction.liquidation_bonuses,
+        vec![CollateralLiquidationBonus {
+            denom: "uosmo".to_string(),
+            liquidation_bonus: Decimal::percent(1),
+            dutch_auction: true,
+        }]
+    );
+
+    // refreshing a running auction doesn't restart it
+    mock_env.increment_by_blocks(10);
+    let res =
+        red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    assert_eq!(wasm_attribute(&res, "status"), "unchanged");
+
+    // halfway through the auction the bonus is halfway between starting_lb and max_lb
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS / 2 - 10);
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+    assert_eq!(
+        auction.liquidation_bonuses[0].liquidation_bonus,
+        Decimal::from_str("0.055").unwrap()
+    );
+
+    // liquidation uses the auction bonus:
+    // collateral = 1000 * 1 * (1 + 0.055) / 1.9 = 555
+    let res = red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(1000, "uusdc")])
+        .unwrap();
+    assert_eq!(wasm_attribute(&res, "debt_amount"), "1000");
+    assert_eq!(wasm_attribute(&res, "collateral_amount"), "555");
+
+    // user is still liquidatable so the auction keeps running
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+
+    // the bonus is capped at max_lb after the auction duration
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS);
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.liquidation_bonuses[0].liquidation_bonus, Decimal::percent(10));
+}
+
+#[test]
+fn auction_cleared_when_user_healthy_after_liquidation() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+
+    // first liquidation starts the auction at starting_lb
+    let start_block = mock_env.app.block_info().height;
+    let res = red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(1000, "uusdc")])
+        .unwrap();
+    // collateral = 1000 * 1 * (1 + 0.01) / 1.9 = 531
+    assert_eq!(wasm_attribute(&res, "collateral_amount"), "531");
+
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+
+    // liquidate up to the target health factor
+    mock_env.increment_by_blocks(20);
+    red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(10000, "uusdc")])
+        .unwrap();
+
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+    assert!(auction.liquidation_bonuses.is_empty());
+}
+
+#[test]
+fn auction_cleared_when_user_healthy_again() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+
+    // price recovers
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("2.2").unwrap());
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS);
+
+    let res =
+        red_bank.refresh_liquidation_auction(&mut mock_env, &liquidatee, &liquidatee).unwrap();
+    assert_eq!(wasm_attribute(&res, "status"), "cleared");
+
+    // next time the user becomes liquidatable the auction starts over
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+    assert_eq!(auction.liquidation_bonuses[0].liquidation_bonus, Decimal::percent(1));
+}
+
+#[test]
+fn stale_auction_restarted_after_recovery_by_price_move() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+
+    // price recovers without anyone touching the user, so the auction isn't cleared
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("2.2").unwrap());
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS + 1);
+
+    // on the next drop the bonus starts over instead of continuing at max_lb
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.liquidation_bonuses[0].liquidation_bonus, Decimal::percent(1));
+
+    let start_block = mock_env.app.block_info().height;
+    let res =
+        red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    assert_eq!(wasm_attribute(&res, "status"), "restarted");
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+
+    // collateral = 1000 * 1 * (1 + 0.01) / 1.9 = 531
+    let res = red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(1000, "uusdc")])
+        .unwrap();
+    assert_eq!(wasm_attribute(&res, "collateral_amount"), "531");
+}
+
+#[test]
+fn stale_auction_restarted_by_liquidation() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("2.2").unwrap());
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS + 1);
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+
+    let start_block = mock_env.app.block_info().height;
+    let res = red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(1000, "uusdc")])
+        .unwrap();
+    assert_eq!(wasm_attribute(&res, "collateral_amount"), "531");
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+}
+
+#[test]
+fn liquidation_bonus_restarts_after_repay() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+    mock_env.increment_by_blocks(AUCTION_DURATION_BLOCKS / 2);
+
+    // user repays half of the debt and is healthy again
+    red_bank.repay(&mut mock_env, &liquidatee, coin(5000, "uusdc")).unwrap();
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+    assert!(auction.liquidation_bonuses.is_empty());
+
+    // next unhealthy episode starts at starting_lb rather than halfway through the old auction
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.2").unwrap());
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+    assert_eq!(auction.liquidation_bonuses[0].liquidation_bonus, Decimal::percent(1));
+}
+
+#[test]
+fn auction_cleared_when_user_deposits_back_to_health() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let oracle = mock_env.oracle.clone();
+
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+
+    oracle.set_price_source_fixed(&mut mock_env, "uosmo", Decimal::from_str("1.9").unwrap());
+    red_bank.refresh_liquidation_auction(&mut mock_env, &liquidator, &liquidatee).unwrap();
+
+    // a deposit too small to make the user healthy keeps the auction running
+    let start_block = mock_env.app.block_info().height;
+    mock_env.increment_by_blocks(10);
+    red_bank.deposit(&mut mock_env, &liquidatee, coin(10, "uosmo")).unwrap();
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, Some(start_block));
+
+    // collateral = 12010 * 1.9 * 0.78 = 17798 > debt = 15000
+    red_bank.deposit(&mut mock_env, &liquidatee, coin(2000, "uosmo")).unwrap();
+    let auction = red_bank.query_liquidation_auction(&mut mock_env, &liquidatee);
+    assert_eq!(auction.start_block, None);
+}
+
+fn setup_env(mock_env: &mut MockEnv) -> (Addr, Addr) {
+    let funded_amt = 1_000_000_000_000u128;
+    let provider = Addr::unchecked("provider"); // provides collateral to be borrowed by others
+    let liquidatee = Addr::unchecked("liquidatee");
+    let liquidator = Addr::unchecked("liquidator");
+
+    // setup red-bank
+    let red_bank = mock_env.red_bank.clone();
+    let params = mock_env.params.clone();
+    let (market_params, asset_params) = asset_params_with(
+        "uosmo",
+        Decimal::percent(70),
+        Decimal::percent(78),
+        Some(DutchAuction {
+            duration_blocks: AUCTION_DURATION_BLOCKS,
+        }),
+    );
+    red_bank.init_asset(mock_env, &asset_params.denom, market_params);
+    params.init_params(mock_env, asset_params);
+    let (market_params, asset_params) =
+        asset_params_with("uusdc", Decimal::percent(90), Decimal::percent(95), None);
+    red_bank.init_asset(mock_env, &asset_params.denom, market_params);
+    params.init_params(mock_env, asset_params);
+
+    // setup oracle
+    let oracle = mock_env.oracle.clone();
+    oracle.set_price_source_fixed(mock_env, "uosmo", Decimal::from_str("2.2").unwrap());
+    oracle.set_price_source_fixed(mock_env, "uusdc", Decimal::one());
+
+    // fund accounts
+    mock_env.fund_accounts(&[&provider, &liquidatee, &liquidator], funded_amt, &["uosmo", "uusdc"]);
+
+    // provider deposits collaterals
+    red_bank.deposit(mock_env, &provider, coin(1000000, "uusdc")).unwrap();
+
+    // liquidatee deposits and borrows
+    red_bank.deposit(mock_env, &liquidatee, coin(10000, "uosmo")).unwrap();
+    red_bank.borrow(mock_env, &liquidatee, "uusdc", 15000).unwrap();
+
+    (liquidatee, liquidator)
+}
+
+fn asset_params_with(
+    denom: &str,
+    max_loan_to_value: Decimal,
+    liquidation_threshold: Decimal,
+    dutch_auction: Option<DutchAuction>,
+) -> (InitOrUpdateAssetParams, AssetParams) {
+    let market_params = InitOrUpdateAssetParams {
+        reserve_factor: Some(Decimal::percent(20)),
+        interest_rate_model: Some(InterestRateModel {
+            optimal_utilization_rate: Decimal::percent(10),
+            base: Decimal::percent(30),
+            slope_1: Decimal::percent(25),
+            slope_2: Decimal::percent(30),
+        }),
+    };
+    let asset_params = AssetParams {
+        denom: denom.to_string(),
+        credit_manager: CmSettings {
+            whitelisted: false,
+            hls: None,
+            staking: None,
+        },
+        red_bank: RedBankSettings {
+            deposit_enabled: true,
+            borrow_enabled: true,
+        },
+        max_loan_to_value,
+        liquidation_threshold,
+        liquidation_bonus: LiquidationBonus {
+            starting_lb: Decimal::percent(1),
+            slope: Decimal::from_str("2.0").unwrap(),
+            min_lb: Decimal::percent(2),
+            max_lb: Decimal::percent(10),
+            dutch_auction,
+        },
+        protocol_liquidation_fee: Decimal::percent(2),
+        deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
+    };
+    (market_params, asset_params)
+}
+
+fn wasm_attribute(res: &AppResponse, key: &str) -> String {
+    res.events
+        .iter()
+        .filter(|event| event.ty == "wasm")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.clone())
+        .unwrap()
+}
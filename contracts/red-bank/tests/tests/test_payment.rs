@@ -31,6 +31,7 @@ fn rejecting_unexpected_payments() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
@@ -44,6 +45,8 @@ fn rejecting_unexpected_payments() {
             denom: "".into(),
             amount: Uint128::zero(),
             recipient: None,
+            on_behalf_of: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
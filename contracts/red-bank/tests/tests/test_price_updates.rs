@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cw_multi_test::Executor;
+use mars_red_bank::error::ContractError;
+use mars_testing::integration::mock_env::{pyth_price_update, MockEnv, MockEnvBuilder};
+use mars_types::{
+    error::MarsError,
+    params::{AssetParams, CmSettings, LiquidationBonus, RedBankSettings},
+    red_bank::{CallbackMsg, ExecuteMsg, InitOrUpdateAssetParams, InterestRateModel},
+};
+
+use super::helpers::assert_err;
+
+const FEE_DENOM: &str = "untrn";
+const PRICE_ID: &str = "61226d39beea19d334f17c2febce27e12646d84675924ebb02b9cdaea68727e3";
+
+#[test]
+fn price_updates_require_update_fee() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let (user, _) = setup_env(&mut mock_env);
+
+    // One fee unit is charged per update
+    let publish_time = mock_env.app.block_info().time.seconds() as i64;
+    let res = red_bank.borrow_with_price_updates(
+        &mut mock_env,
+        &user,
+        "uusdc",
+        1000,
+        vec![
+            pyth_price_update(PRICE_ID, 100_000_000, publish_time),
+            pyth_price_update(PRICE_ID, 100_000_000, publish_time),
+        ],
+        &[coin(1, FEE_DENOM)],
+    );
+    assert_err(
+        res,
+        ContractError::InsufficientPriceUpdateFee {
+            required: Uint128::new(2),
+            available: Uint128::one(),
+        },
+    );
+}
+
+#[test]
+fn price_updates_pushed_before_borrow() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let (user, _) = setup_env(&mut mock_env);
+    set_usdc_price_source_pyth(&mut mock_env);
+
+    // the confidence interval of the mocked Pyth price is too wide to borrow
+    red_bank.borrow(&mut mock_env, &user, "uusdc", 10000).unwrap_err();
+
+    let usdc_balance = mock_env.query_balance(&user, "uusdc").unwrap().amount;
+    let publish_time = mock_env.app.block_info().time.seconds() as i64;
+    red_bank
+        .borrow_with_price_updates(
+            &mut mock_env,
+            &user,
+            "uusdc",
+            10000,
+            vec![pyth_price_update(PRICE_ID, 100_000_000, publish_time)],
+            &[coin(1, FEE_DENOM)],
+        )
+        .unwrap();
+
+    // Fee is paid to the Pyth contract and the price is stored
+    assert_eq!(mock_env.query_balance(&mock_env.pyth, FEE_DENOM).unwrap().amount, Uint128::one());
+    assert_eq!(mock_env.query_pyth_price(PRICE_ID), 100_000_000);
+
+    // The debt is recorded for the sender
+    let debt = red_bank.query_user_debt(&mut mock_env, &user, "uusdc");
+    assert_eq!(debt.amount, Uint128::new(10000));
+    let new_usdc_balance = mock_env.query_balance(&user, "uusdc").unwrap().amount;
+    assert_eq!(new_usdc_balance, usdc_balance + Uint128::new(10000));
+}
+
+#[test]
+fn price_updates_pushed_before_liquidation() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let (liquidatee, liquidator) = setup_env(&mut mock_env);
+    red_bank.borrow(&mut mock_env, &liquidatee, "uusdc", 10000).unwrap();
+
+    // with the mocked Pyth price of 6.8 USD the liquidatee is liquidatable
+    set_usdc_price_source_pyth(&mut mock_env);
+
+    // the price update brings the debt back to 1 USD per USDC before the health check
+    let publish_time = mock_env.app.block_info().time.seconds() as i64;
+    let res = red_bank.liquidate_with_price_updates(
+        &mut mock_env,
+        &liquidator,
+        &liquidatee,
+        "uosmo",
+        vec![pyth_price_update(PRICE_ID, 100_000_000, publish_time)],
+        &[coin(1, FEE_DENOM), coin(100, "uusdc")],
+    );
+    assert_err(res, ContractError::CannotLiquidateHealthyPosition {});
+
+    red_bank
+        .liquidate(&mut mock_env, &liquidator, &liquidatee, "uosmo", &[coin(100, "uusdc")])
+        .unwrap();
+    let collateral = red_bank.query_user_collateral(&mut mock_env, &liquidator, "uosmo");
+    assert!(!collateral.amount.is_zero());
+}
+
+#[test]
+fn only_red_bank_can_dispatch_callbacks() {
+    let mut mock_env = MockEnvBuilder::new(None, Addr::unchecked("owner")).build();
+    let red_bank = mock_env.red_bank.clone();
+    let (user, liquidator) = setup_env(&mut mock_env);
+
+    let res = mock_env.app.execute_contract(
+        liquidator,
+        red_bank.contract_addr.clone(),
+        &ExecuteMsg::Callback(CallbackMsg::Dispatch {
+            sender: user.to_string(),
+            msg: Box::new(ExecuteMsg::Borrow {
+                denom: "uusdc".to_string(),
+                amount: Uint128::new(1000),
+                recipient: None,
+                on_behalf_of: None,
+                price_updates: None,
+            }),
+        }),
+        &[],
+    );
+    assert_err(res, ContractError::Mars(MarsError::Unauthorized {}));
+}
+
+fn setup_env(mock_env: &mut MockEnv) -> (Addr, Addr) {
+    let funded_amt = 1_000_000_000_000u128;
+    let provider = Addr::unchecked("provider"); // provides collateral to be borrowed by others
+    let user = Addr::unchecked("user");
+    let liquidator = Addr::unchecked("liquidator");
+
+    // setup red-bank
+    let red_bank = mock_env.red_bank.clone();
+    let params = mock_env.params.clone();
+    let (market_params, asset_params) =
+        asset_params_with("uosmo", Decimal::percent(70), Decimal::percent(78));
+    red_bank.init_asset(mock_env, &asset_params.denom, market_params);
+    params.init_params(mock_env, asset_params);
+    let (market_params, asset_params) =
+        asset_params_with("uusdc", Decimal::percent(90), Decimal::percent(95));
+    red_bank.init_asset(mock_env, &asset_params.denom, market_params);
+    params.init_params(mock_env, asset_params);
+
+    // setup oracle
+    let oracle = mock_env.oracle.clone();
+    oracle.set_price_source_fixed(mock_env, "uosmo", Decimal::from_str("2.2").unwrap());
+    oracle.set_price_source_fixed(mock_env, "uusdc", Decimal::one());
+
+    // fund accounts
+    mock_env.fund_accounts(
+        &[&provider, &user, &liquidator],
+        funded_amt,
+        &["uosmo", "uusdc", FEE_DENOM],
+    );
+
+    // provider deposits collaterals, user deposits 10000 * 2.2 * 0.7 = 15400 borrowing power
+    red_bank.deposit(mock_env, &provider, coin(1000000, "uusdc")).unwrap();
+    red_bank.deposit(mock_env, &user, coin(10000, "uosmo")).unwrap();
+
+    (user, liquidator)
+}
+
+fn set_usdc_price_source_pyth(mock_env: &mut MockEnv) {
+    let oracle = mock_env.oracle.clone();
+    let pyth = mock_env.pyth.clone();
+    oracle.set_price_source_fixed(mock_env, "usd", Decimal::from_str("1000000").unwrap());
+    oracle.set_price_source_pyth(
+        mock_env,
+        "uusdc",
+        pyth.to_string(),
+        Decimal::percent(10u64),
+        Decimal::percent(15u64),
+    );
+}
+
+fn asset_params_with(
+    denom: &str,
+    max_loan_to_value: Decimal,
+    liquidation_threshold: Decimal,
+) -> (InitOrUpdateAssetParams, AssetParams) {
+    let market_params = InitOrUpdateAssetParams {
+        reserve_factor: Some(Decimal::percent(20)),
+        interest_rate_model: Some(InterestRateModel {
+            optimal_utilization_rate: Decimal::percent(10),
+            base: Decimal::percent(30),
+            slope_1: Decimal::percent(25),
+            slope_2: Decimal::percent(30),
+        }),
+    };
+    let asset_params = AssetParams {
+        denom: denom.to_string(),
+        credit_manager: CmSettings {
+            whitelisted: false,
+            hls: None,
+            staking: None,
+        },
+        red_bank: RedBankSettings {
+            deposit_enabled: true,
+            borrow_enabled: true,
+        },
+        max_loan_to_value,
+        liquidation_threshold,
+        liquidation_bonus: LiquidationBonus {
+            starting_lb: Decimal::percent(1),
+            slope: Decimal::from_str("2.0").unwrap(),
+            min_lb: Decimal::percent(2),
+            max_lb: Decimal::percent(10),
+            dutch_auction: None,
+        },
+        protocol_liquidation_fee: Decimal::percent(2),
+        deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
+    };
+    (market_params, asset_params)
+}
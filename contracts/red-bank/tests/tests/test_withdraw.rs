@@ -94,6 +94,7 @@ fn withdrawing_more_than_balance() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
@@ -139,6 +140,7 @@ fn withdrawing_partially() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -280,6 +282,7 @@ fn withdrawing_completely() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -391,6 +394,7 @@ fn withdrawing_to_another_user() {
             recipient: Some(recipient_addr.to_string()),
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -690,6 +694,7 @@ fn withdrawing_if_health_factor_not_met() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
@@ -728,6 +733,7 @@ fn withdrawing_if_health_factor_met() {
             recipient: None,
             account_id: None,
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap();
@@ -962,6 +968,7 @@ fn withdrawing_with_account_id_by_non_credit_manager_user() {
             recipient: None,
             account_id: Some("".to_string()),
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
@@ -978,6 +985,7 @@ fn withdrawing_with_account_id_by_non_credit_manager_user() {
             recipient: None,
             account_id: Some("1234".to_string()),
             liquidation_related: None,
+            price_updates: None,
         },
     )
     .unwrap_err();
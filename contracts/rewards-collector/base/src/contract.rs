@@ -91,6 +91,11 @@ where
                 account_id,
                 actions,
             } => self.withdraw_from_credit_manager(deps, account_id, actions),
+            ExecuteMsg::SweepDust {
+                account_id,
+                debt_denom,
+                collateral_denom,
+            } => self.sweep_dust(deps, account_id, debt_denom, collateral_denom),
             ExecuteMsg::DistributeRewards {
                 denom,
                 amount,
@@ -203,6 +208,7 @@ where
                 recipient: None,
                 account_id: None,
                 liquidation_related: None,
+                price_updates: None,
             })?,
             funds: vec![],
         });
@@ -241,6 +247,7 @@ where
                 account_id: Some(account_id.clone()),
                 account_kind: None,
                 actions,
+                price_updates: None,
             })?,
             funds: vec![],
         });
@@ -251,6 +258,39 @@ where
             .add_attribute("account_id", account_id))
     }
 
+    pub fn sweep_dust(
+        &self,
+        deps: DepsMut,
+        account_id: String,
+        debt_denom: String,
+        collateral_denom: String,
+    ) -> ContractResult<Response<M>> {
+        let cfg = self.config.load(deps.storage)?;
+
+        let cm_addr = address_provider::helpers::query_contract_addr(
+            deps.as_ref(),
+            &cfg.address_provider,
+            MarsAddressType::CreditManager,
+        )?;
+
+        let sweep_dust_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: cm_addr.to_string(),
+            msg: to_json_binary(&credit_manager::ExecuteMsg::SweepDust {
+                account_id: account_id.clone(),
+                debt_denom: debt_denom.clone(),
+                collateral_denom: collateral_denom.clone(),
+            })?,
+            funds: vec![],
+        });
+
+        Ok(Response::new()
+            .add_message(sweep_dust_msg)
+            .add_attribute("action", "sweep_dust")
+            .add_attribute("account_id", account_id)
+            .add_attribute("debt_denom", debt_denom)
+            .add_attribute("collateral_denom", collateral_denom))
+    }
+
     pub fn claim_incentive_rewards(
         &self,
         deps: DepsMut,
@@ -38,6 +38,7 @@ fn withdrawing_from_red_bank() {
                 recipient: None,
                 account_id: None,
                 liquidation_related: None
+                price_updates: None,
             })
             .unwrap(),
             funds: vec![]
@@ -123,7 +124,41 @@ fn withdrawing_from_cm_successfully() {
             msg: to_json_binary(&credit_manager::ExecuteMsg::UpdateCreditAccount {
                 account_id: Some(account_id),
                 account_kind: None,
-                actions
+                actions,
+                price_updates: None
+            })
+            .unwrap(),
+            funds: vec![]
+        }))
+    )
+}
+
+#[test]
+fn sweeping_dust_in_cm() {
+    let mut deps = helpers::setup_test();
+
+    // anyone can sweep dust
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("jake"),
+        ExecuteMsg::SweepDust {
+            account_id: "random_id".to_string(),
+            debt_denom: "uatom".to_string(),
+            collateral_denom: "uosmo".to_string(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "credit_manager".to_string(),
+            msg: to_json_binary(&credit_manager::ExecuteMsg::SweepDust {
+                account_id: "random_id".to_string(),
+                debt_denom: "uatom".to_string(),
+                collateral_denom: "uosmo".to_string(),
             })
             .unwrap(),
             funds: vec![]
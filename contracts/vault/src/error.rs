@@ -81,6 +81,11 @@ pub enum ContractError {
 
     #[error("Contract owner not set")]
     NoOwner {},
+
+    #[error("Action is paused: {action}")]
+    ActionPaused {
+        action: String,
+    },
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -5,14 +5,18 @@ use cosmwasm_std::{
     MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use mars_types::{
-    adapters::{account_nft::AccountNftBase, health::HealthContractBase, oracle::OracleBase},
+    adapters::{
+        account_nft::AccountNftBase, health::HealthContractBase, oracle::OracleBase,
+        params::ParamsBase,
+    },
     credit_manager::{self, Action, ActionAmount, ActionCoin, ConfigResponse, Positions, QueryMsg},
     health::AccountKind,
     oracle::ActionKind,
+    params::{PausableAction, PausableContract},
 };
 
 use crate::{
-    error::ContractError,
+    error::{ContractError, ContractResult},
     msg::UnlockState,
     performance_fee::PerformanceFeeConfig,
     state::{
@@ -59,6 +63,8 @@ pub fn deposit(
         return Err(ContractError::VaultAccountNotFound {});
     };
 
+    assert_not_paused(deps.as_ref(), &cm_addr, PausableAction::Deposits)?;
+
     // unwrap recipient or use caller's address
     let vault_token_recipient =
         recipient.map_or(Ok(info.sender.clone()), |r| deps.api.addr_validate(&r))?;
@@ -100,6 +106,7 @@ pub fn deposit(
             account_id: Some(vault_acc_id.clone()),
             account_kind: None,
             actions: vec![Action::Deposit(coin_deposited.clone())],
+            price_updates: None,
         })?,
         funds: vec![coin_deposited],
     });
@@ -116,6 +123,18 @@ pub fn deposit(
         .add_event(event))
 }
 
+/// The params contract is resolved through the Credit Manager config
+fn assert_not_paused(deps: Deps, cm_addr: &str, action: PausableAction) -> ContractResult<()> {
+    let config: ConfigResponse = deps.querier.query_wasm_smart(cm_addr, &QueryMsg::Config {})?;
+    let params = ParamsBase::new(deps.api.addr_validate(&config.params)?);
+    if params.query_is_paused(&deps.querier, PausableContract::ManagedVault, action)? {
+        return Err(ContractError::ActionPaused {
+            action: action.to_string(),
+        });
+    }
+    Ok(())
+}
+
 pub fn unlock(
     deps: DepsMut,
     env: Env,
@@ -309,6 +328,7 @@ fn prepare_credit_manager_msg(
             account_id: Some(vault_acc_id.clone()),
             account_kind: None,
             actions,
+            price_updates: None,
         })?,
         funds: vec![],
     });
@@ -0,0 +1,67 @@
+//! Replays historical price paths against account snapshots and reports bad debt, liquidation
+//! volume, liquidator profit and protocol fees per asset.
+//!
+//! Usage:
+//!     cargo run -p mars-integration-tests --bin simulate -- <price_paths.csv> <accounts.csv>
+//!         [--params <asset_params.csv>] [--target-health-factor <decimal>]
+//!         [--max-liquidations-per-block <n>]
+//!
+//! See `tests/files/simulation` for the CSV formats.
+
+use std::{env, fs, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use cosmwasm_std::Decimal;
+use mars_integration_tests::simulation::{
+    parse_account_snapshots, parse_asset_params, parse_price_paths, run_simulation,
+    SimulationConfig,
+};
+
+fn main() -> AnyResult<()> {
+    let mut args = env::args().skip(1);
+    let mut files = vec![];
+    let mut params_file = None;
+    let mut target_health_factor = None;
+    let mut max_liquidations_per_block = 10;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("missing value for {arg}"));
+        match arg.as_str() {
+            "--params" => params_file = Some(value()?),
+            "--target-health-factor" => {
+                target_health_factor = Some(
+                    Decimal::from_str(&value()?)
+                        .map_err(|e| anyhow!("invalid target health factor: {e}"))?,
+                )
+            }
+            "--max-liquidations-per-block" => {
+                max_liquidations_per_block =
+                    value()?.parse().context("invalid max liquidations per block")?
+            }
+            _ => files.push(arg),
+        }
+    }
+    let [price_paths_file, accounts_file] = files.as_slice() else {
+        bail!("expected <price_paths.csv> <accounts.csv>");
+    };
+
+    let config = SimulationConfig {
+        price_paths: parse_price_paths(&read(price_paths_file)?)?,
+        accounts: parse_account_snapshots(&read(accounts_file)?)?,
+        asset_params: match params_file {
+            Some(file) => parse_asset_params(&read(&file)?)?,
+            None => vec![],
+        },
+        target_health_factor,
+        max_liquidations_per_block,
+    };
+
+    let report = run_simulation(&config)?;
+    println!("{report}");
+
+    Ok(())
+}
+
+fn read(file: &str) -> AnyResult<String> {
+    fs::read_to_string(file).with_context(|| format!("reading {file}"))
+}
@@ -1 +1,2 @@
-
+/// Replays price paths against account snapshots in multitest to evaluate parameter changes
+pub mod simulation;
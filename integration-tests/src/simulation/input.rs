@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use mars_testing::multitest::helpers::{coin_info, CoinInfo};
+use mars_types::params::LiquidationBonus;
+
+/// Price of a denom from the given block on
+#[derive(Clone, Debug, PartialEq)]
+pub struct PricePoint {
+    pub block: u64,
+    pub denom: String,
+    pub price: Decimal,
+}
+
+/// Positions an account starts the simulation with. Debts are borrowed and withdrawn to the
+/// owner's wallet, so they don't count as collateral.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountSnapshot {
+    pub account: String,
+    pub deposits: Vec<Coin>,
+    pub debts: Vec<Coin>,
+}
+
+/// Parses a CSV with the columns `block,denom,price`
+pub fn parse_price_paths(csv: &str) -> AnyResult<Vec<PricePoint>> {
+    rows(csv, 3)?
+        .into_iter()
+        .map(|(line, cols)| {
+            Ok(PricePoint {
+                block: cols[0].parse().with_context(|| format!("line {line}: invalid block"))?,
+                denom: cols[1].to_string(),
+                price: parse_decimal(line, cols[2])?,
+            })
+        })
+        .collect()
+}
+
+/// Parses a CSV with the columns `account,denom,deposit,debt`, one row per account and denom
+pub fn parse_account_snapshots(csv: &str) -> AnyResult<Vec<AccountSnapshot>> {
+    let mut snapshots: BTreeMap<String, AccountSnapshot> = BTreeMap::new();
+    for (line, cols) in rows(csv, 4)? {
+        let snapshot = snapshots.entry(cols[0].to_string()).or_insert_with(|| AccountSnapshot {
+            account: cols[0].to_string(),
+            ..Default::default()
+        });
+        let denom = cols[1].to_string();
+        let deposit = parse_amount(line, cols[2])?;
+        let debt = parse_amount(line, cols[3])?;
+        if !deposit.is_zero() {
+            snapshot.deposits.push(Coin {
+                denom: denom.clone(),
+                amount: deposit,
+            });
+        }
+        if !debt.is_zero() {
+            snapshot.debts.push(Coin {
+                denom,
+                amount: debt,
+            });
+        }
+    }
+    Ok(snapshots.into_values().collect())
+}
+
+/// Parses a CSV with the columns
+/// `denom,max_ltv,liquidation_threshold,starting_lb,slope,min_lb,max_lb,protocol_liquidation_fee`.
+/// Prices are set from the price paths.
+pub fn parse_asset_params(csv: &str) -> AnyResult<Vec<CoinInfo>> {
+    rows(csv, 8)?
+        .into_iter()
+        .map(|(line, cols)| {
+            Ok(CoinInfo {
+                max_ltv: parse_decimal(line, cols[1])?,
+                liquidation_threshold: parse_decimal(line, cols[2])?,
+                liquidation_bonus: LiquidationBonus {
+                    starting_lb: parse_decimal(line, cols[3])?,
+                    slope: parse_decimal(line, cols[4])?,
+                    min_lb: parse_decimal(line, cols[5])?,
+                    max_lb: parse_decimal(line, cols[6])?,
+                    dutch_auction: None,
+                },
+                protocol_liquidation_fee: parse_decimal(line, cols[7])?,
+                ..coin_info(cols[0])
+            })
+        })
+        .collect()
+}
+
+/// Returns the line number and columns of every row, skipping the header, empty lines and
+/// `#` comments
+fn rows(csv: &str, columns: usize) -> AnyResult<Vec<(usize, Vec<&str>)>> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    // header
+    if lines.next().is_none() {
+        bail!("missing CSV header");
+    }
+
+    lines
+        .map(|(line, row)| {
+            let cols = row.split(',').map(str::trim).collect::<Vec<_>>();
+            if cols.len() != columns {
+                bail!("line {line}: expected {columns} columns, found {}", cols.len());
+            }
+            Ok((line, cols))
+        })
+        .collect()
+}
+
+fn parse_decimal(line: usize, value: &str) -> AnyResult<Decimal> {
+    Decimal::from_str(value).map_err(|e| anyhow!("line {line}: invalid decimal {value}: {e}"))
+}
+
+fn parse_amount(line: usize, value: &str) -> AnyResult<Uint128> {
+    Uint128::from_str(value).map_err(|e| anyhow!("line {line}: invalid amount {value}: {e}"))
+}
@@ -0,0 +1,5 @@
+pub use self::{input::*, replay::*, report::*};
+
+mod input;
+mod replay;
+mod report;
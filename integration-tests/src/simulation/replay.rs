@@ -0,0 +1,444 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, Context, Result as AnyResult};
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use cw_multi_test::{BankSudo, SudoMsg};
+use mars_mock_oracle::msg::CoinPrice;
+use mars_testing::multitest::helpers::{coin_info, AccountToFund, CoinInfo, MockEnv};
+use mars_types::{
+    credit_manager::{Action, ActionAmount, ActionCoin, LiquidateRequest, Positions},
+    health::AccountKind,
+    oracle::ActionKind,
+};
+
+use super::{AccountSnapshot, PricePoint, SimulationReport};
+
+/// Amount of every denom the liquidator is funded with
+const LIQUIDATOR_BALANCE: u128 = 1_000_000_000_000_000;
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub price_paths: Vec<PricePoint>,
+    pub accounts: Vec<AccountSnapshot>,
+    /// Params of the denoms, defaulting to `coin_info` for denoms not listed
+    pub asset_params: Vec<CoinInfo>,
+    pub target_health_factor: Option<Decimal>,
+    /// Liquidations attempted per account and block, the liquidator stops earlier once the
+    /// account is no longer liquidatable
+    pub max_liquidations_per_block: usize,
+}
+
+/// Instantiates the protocol with the account snapshots at the prices of the first block, then
+/// replays the price paths block by block. Accounts above max LTV at these prices are opened at
+/// raised collateral prices, the prices of the first block are applied once they are open. After every block a liquidator liquidates the largest
+/// debt of each liquidatable account against its largest collateral.
+pub fn run_simulation(config: &SimulationConfig) -> AnyResult<SimulationReport> {
+    let mut simulation = Simulation::new(config)?;
+    let mut report = SimulationReport::default();
+
+    let mut blocks: BTreeMap<u64, Vec<&PricePoint>> = BTreeMap::new();
+    for point in &config.price_paths {
+        blocks.entry(point.block).or_default().push(point);
+    }
+
+    let mut last_block = None;
+    for (block, points) in blocks {
+        if let Some(last_block) = last_block {
+            simulation.mock.increment_by_blocks(block - last_block);
+        }
+        last_block = Some(block);
+
+        for point in points {
+            simulation.set_price(&point.denom, point.price);
+        }
+        for account_id in simulation.accounts.clone() {
+            simulation.liquidate(&account_id, config.max_liquidations_per_block, &mut report)?;
+        }
+        report.blocks += 1;
+    }
+
+    simulation.record_bad_debt(&mut report)?;
+
+    Ok(report)
+}
+
+struct Simulation {
+    mock: MockEnv,
+    accounts: Vec<String>,
+    liquidator: Addr,
+    liquidator_account_id: String,
+    rewards_collector_account_id: String,
+    prices: HashMap<String, Decimal>,
+}
+
+impl Simulation {
+    fn new(config: &SimulationConfig) -> AnyResult<Self> {
+        let prices = initial_prices(&config.price_paths);
+        let denoms = prices.keys().cloned().collect::<BTreeSet<_>>();
+
+        let coins = denoms
+            .iter()
+            .map(|denom| CoinInfo {
+                price: prices[denom],
+                ..config
+                    .asset_params
+                    .iter()
+                    .find(|c| &c.denom == denom)
+                    .cloned()
+                    .unwrap_or_else(|| coin_info(denom))
+            })
+            .collect::<Vec<_>>();
+
+        let liquidator = Addr::unchecked("liquidator");
+        let mut builder = MockEnv::new().set_params(&coins).fund_account(AccountToFund {
+            addr: liquidator.clone(),
+            funds: coins.iter().map(|c| c.to_coin(LIQUIDATOR_BALANCE)).collect(),
+        });
+        if let Some(thf) = config.target_health_factor {
+            builder = builder.target_health_factor(thf);
+        }
+        for snapshot in &config.accounts {
+            if let Some(denom) = snapshot
+                .deposits
+                .iter()
+                .chain(&snapshot.debts)
+                .map(|c| &c.denom)
+                .find(|denom| !denoms.contains(*denom))
+            {
+                return Err(anyhow!("account {}: {denom} has no price path", snapshot.account));
+            }
+            if !snapshot.deposits.is_empty() {
+                builder = builder.fund_account(AccountToFund {
+                    addr: owner(&snapshot.account),
+                    funds: snapshot.deposits.clone(),
+                });
+            }
+        }
+        let mut mock = builder.build()?;
+
+        // The Red Bank needs to be able to lend all the debts of the snapshots
+        let red_bank = mock.query_config().red_bank;
+        let mut debts: BTreeMap<String, Uint128> = BTreeMap::new();
+        for debt in config.accounts.iter().flat_map(|s| &s.debts) {
+            *debts.entry(debt.denom.clone()).or_default() += debt.amount;
+        }
+        if !debts.is_empty() {
+            mock.app.sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: red_bank,
+                amount: debts
+                    .into_iter()
+                    .map(|(denom, amount)| Coin::new(amount.u128(), denom))
+                    .collect(),
+            }))?;
+        }
+
+        let liquidator_account_id = mock.create_credit_account(&liquidator)?;
+        let rewards_collector_account_id = mock.query_rewards_collector_account();
+
+        let mut simulation = Self {
+            mock,
+            accounts: vec![],
+            liquidator,
+            liquidator_account_id,
+            rewards_collector_account_id,
+            prices: HashMap::new(),
+        };
+        for (denom, price) in &prices {
+            simulation.set_price(denom, *price);
+        }
+        for snapshot in &config.accounts {
+            let account_id = simulation
+                .open_account(snapshot, &coins)
+                .with_context(|| format!("account {}: opening positions", snapshot.account))?;
+            simulation.accounts.push(account_id);
+        }
+
+        Ok(simulation)
+    }
+
+    /// Opens the positions of the snapshot. The credit manager rejects borrowing above max LTV,
+    /// so the prices of the collateral (deposits in denoms the account doesn't owe) are raised
+    /// while opening an account which is above max LTV at the current prices, and restored after.
+    fn open_account(
+        &mut self,
+        snapshot: &AccountSnapshot,
+        coins: &[CoinInfo],
+    ) -> AnyResult<String> {
+        let owner = owner(&snapshot.account);
+        let account_id = self.mock.create_credit_account(&owner)?;
+
+        let is_debt = |denom: &str| snapshot.debts.iter().any(|d| d.denom == denom);
+        let mut debt_value = Uint128::zero();
+        for debt in &snapshot.debts {
+            debt_value += self.value(&debt.denom, debt.amount)?;
+        }
+        let mut collateral_value = Uint128::zero();
+        let mut debt_denoms_collateral_value = Uint128::zero();
+        for deposit in &snapshot.deposits {
+            let max_ltv = coins
+                .iter()
+                .find(|c| c.denom == deposit.denom)
+                .map(|c| c.max_ltv)
+                .unwrap_or_default();
+            let value = self.value(&deposit.denom, deposit.amount)?.checked_mul_floor(max_ltv)?;
+            if is_debt(&deposit.denom) {
+                debt_denoms_collateral_value += value;
+            } else {
+                collateral_value += value;
+            }
+        }
+
+        let mut raised_prices = vec![];
+        if debt_value >= collateral_value + debt_denoms_collateral_value {
+            if collateral_value.is_zero() {
+                return Err(anyhow!(
+                    "debt can't be opened without collateral in denoms the account doesn't owe"
+                ));
+            }
+            // Twice the factor bringing the account to max LTV, leaving room for rounding
+            let factor = Decimal::from_ratio(
+                (debt_value - debt_denoms_collateral_value) * Uint128::new(2),
+                collateral_value,
+            ) + Decimal::one();
+            for deposit in snapshot.deposits.iter().filter(|d| !is_debt(&d.denom)) {
+                let price = self.prices[&deposit.denom];
+                raised_prices.push((deposit.denom.clone(), price));
+                self.set_price(&deposit.denom, price.checked_mul(factor)?);
+            }
+        }
+
+        let actions = snapshot
+            .deposits
+            .iter()
+            .cloned()
+            .map(Action::Deposit)
+            .chain(snapshot.debts.iter().flat_map(|debt| {
+                [
+                    Action::Borrow(debt.clone()),
+                    Action::Withdraw(ActionCoin {
+                        denom: debt.denom.clone(),
+                        amount: ActionAmount::Exact(debt.amount),
+                    }),
+                ]
+            }))
+            .collect();
+        self.mock.update_credit_account(&account_id, &owner, actions, &snapshot.deposits)?;
+
+        for (denom, price) in raised_prices {
+            self.set_price(&denom, price);
+        }
+
+        Ok(account_id)
+    }
+
+    fn set_price(&mut self, denom: &str, price: Decimal) {
+        for pricing in [ActionKind::Default, ActionKind::Liquidation] {
+            self.mock.price_change(CoinPrice {
+                pricing,
+                denom: denom.to_string(),
+                price,
+            });
+        }
+        self.prices.insert(denom.to_string(), price);
+    }
+
+    fn value(&self, denom: &str, amount: Uint128) -> AnyResult<Uint128> {
+        let price = self.prices.get(denom).ok_or_else(|| anyhow!("{denom} has no price"))?;
+        Ok(amount.checked_mul_floor(*price)?)
+    }
+
+    fn liquidate(
+        &mut self,
+        account_id: &str,
+        max_liquidations: usize,
+        report: &mut SimulationReport,
+    ) -> AnyResult<()> {
+        for _ in 0..max_liquidations {
+            let health =
+                self.mock.query_health(account_id, AccountKind::Default, ActionKind::Liquidation);
+            if !health.liquidatable {
+                return Ok(());
+            }
+
+            let positions = self.mock.query_positions(account_id);
+            let Some((debt_coin, collateral)) = self.pick_liquidation(&positions)? else {
+                // Nothing left to liquidate, the remaining debt is bad debt
+                return Ok(());
+            };
+            let collateral_denom = collateral.denom.clone();
+            let request = if collateral.lent {
+                LiquidateRequest::Lend(collateral.denom)
+            } else {
+                LiquidateRequest::Deposit(collateral.denom)
+            };
+
+            let wallet_before = self.liquidator_balances(&debt_coin.denom, &collateral_denom);
+            let fees_before = self.rewards_collector_balance(&collateral_denom);
+
+            let res = self.mock.update_credit_account(
+                &self.liquidator_account_id,
+                &self.liquidator,
+                vec![
+                    Action::Deposit(debt_coin.clone()),
+                    Action::Liquidate {
+                        liquidatee_account_id: account_id.to_string(),
+                        debt_coin: debt_coin.clone(),
+                        request,
+                    },
+                    Action::RefundAllCoinBalances {},
+                ],
+                &[debt_coin.clone()],
+            );
+            if res.is_err() {
+                report.failed_liquidations += 1;
+                return Ok(());
+            }
+            report.liquidations += 1;
+
+            let positions_after = self.mock.query_positions(account_id);
+            let repaid = debt_amount(&positions, &debt_coin.denom)
+                - debt_amount(&positions_after, &debt_coin.denom);
+            let seized = collateral_amount(&positions, &collateral_denom)
+                - collateral_amount(&positions_after, &collateral_denom);
+            let fees = self.rewards_collector_balance(&collateral_denom) - fees_before;
+
+            let wallet_after = self.liquidator_balances(&debt_coin.denom, &collateral_denom);
+            let mut profit = 0i128;
+            for (denom, before) in wallet_before {
+                let after = wallet_after[&denom];
+                profit += if after >= before {
+                    self.value(&denom, after - before)?.u128() as i128
+                } else {
+                    -(self.value(&denom, before - after)?.u128() as i128)
+                };
+            }
+
+            let repaid_value = self.value(&debt_coin.denom, repaid)?;
+            let debt_report = report.asset_mut(&debt_coin.denom);
+            debt_report.liquidated_debt_amount += repaid;
+            debt_report.liquidated_debt_value += repaid_value;
+
+            let seized_value = self.value(&collateral_denom, seized)?;
+            let fees_value = self.value(&collateral_denom, fees)?;
+            let collateral_report = report.asset_mut(&collateral_denom);
+            collateral_report.seized_collateral_amount += seized;
+            collateral_report.seized_collateral_value += seized_value;
+            collateral_report.protocol_fee_amount += fees;
+            collateral_report.protocol_fee_value += fees_value;
+            collateral_report.liquidator_profit += profit;
+        }
+        Ok(())
+    }
+
+    /// Largest debt by value against the largest coin collateral by value
+    fn pick_liquidation(&self, positions: &Positions) -> AnyResult<Option<(Coin, Collateral)>> {
+        let mut debt: Option<(Uint128, Coin)> = None;
+        for d in &positions.debts {
+            let value = self.value(&d.denom, d.amount)?;
+            if !matches!(&debt, Some((max, _)) if *max >= value) {
+                debt = Some((value, Coin::new(d.amount.u128(), d.denom.clone())));
+            }
+        }
+
+        let mut collateral: Option<(Uint128, Collateral)> = None;
+        let deposits = positions.deposits.iter().map(|c| (c, false));
+        let lends = positions.lends.iter().map(|c| (c, true));
+        for (c, lent) in deposits.chain(lends) {
+            let value = self.value(&c.denom, c.amount)?;
+            if !matches!(&collateral, Some((max, _)) if *max >= value) {
+                collateral = Some((
+                    value,
+                    Collateral {
+                        denom: c.denom.clone(),
+                        lent,
+                    },
+                ));
+            }
+        }
+
+        Ok(debt.zip(collateral).map(|((_, debt), (_, collateral))| (debt, collateral)))
+    }
+
+    fn liquidator_balances(
+        &self,
+        debt_denom: &str,
+        collateral_denom: &str,
+    ) -> HashMap<String, Uint128> {
+        [debt_denom, collateral_denom]
+            .into_iter()
+            .map(|denom| {
+                (denom.to_string(), self.mock.query_balance(&self.liquidator, denom).amount)
+            })
+            .collect()
+    }
+
+    fn rewards_collector_balance(&self, denom: &str) -> Uint128 {
+        let positions = self.mock.query_positions(&self.rewards_collector_account_id);
+        collateral_amount(&positions, denom)
+    }
+
+    /// Debt not covered by the collateral left in the accounts at the final prices
+    fn record_bad_debt(&self, report: &mut SimulationReport) -> AnyResult<()> {
+        for account_id in &self.accounts {
+            let health =
+                self.mock.query_health(account_id, AccountKind::Default, ActionKind::Liquidation);
+            if health.total_collateral_value >= health.total_debt_value {
+                continue;
+            }
+            let shortfall = health.total_debt_value - health.total_collateral_value;
+
+            let positions = self.mock.query_positions(account_id);
+            let debt_values = positions
+                .debts
+                .iter()
+                .map(|d| Ok((d.denom.clone(), self.value(&d.denom, d.amount)?)))
+                .collect::<AnyResult<Vec<_>>>()?;
+            let total_debt_value = debt_values.iter().map(|(_, v)| *v).sum::<Uint128>();
+            if total_debt_value.is_zero() {
+                continue;
+            }
+            for (denom, value) in debt_values {
+                report.asset_mut(&denom).bad_debt_value +=
+                    shortfall.multiply_ratio(value, total_debt_value);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Collateral {
+    denom: String,
+    /// Lent to the Red Bank rather than deposited
+    lent: bool,
+}
+
+fn owner(account: &str) -> Addr {
+    Addr::unchecked(format!("owner_{account}"))
+}
+
+/// Price of every denom at its earliest block
+fn initial_prices(price_paths: &[PricePoint]) -> BTreeMap<String, Decimal> {
+    let mut prices: BTreeMap<String, (u64, Decimal)> = BTreeMap::new();
+    for point in price_paths {
+        let entry = prices.entry(point.denom.clone()).or_insert((point.block, point.price));
+        if point.block < entry.0 {
+            *entry = (point.block, point.price);
+        }
+    }
+    prices.into_iter().map(|(denom, (_, price))| (denom, price)).collect()
+}
+
+fn debt_amount(positions: &Positions, denom: &str) -> Uint128 {
+    positions.debts.iter().find(|d| d.denom == denom).map(|d| d.amount).unwrap_or_default()
+}
+
+/// Deposited and lent amount of the denom
+fn collateral_amount(positions: &Positions, denom: &str) -> Uint128 {
+    positions
+        .deposits
+        .iter()
+        .chain(&positions.lends)
+        .filter(|c| c.denom == denom)
+        .map(|c| c.amount)
+        .sum()
+}
@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, fmt};
+
+use cosmwasm_std::Uint128;
+
+/// Outcome of a replay. Values are denominated in the oracle's base currency, at the prices of
+/// the block they happened in (bad debt at the final prices).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub blocks: u64,
+    pub liquidations: u64,
+    /// Liquidations of liquidatable accounts rejected by the credit manager
+    pub failed_liquidations: u64,
+    pub assets: BTreeMap<String, AssetReport>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetReport {
+    /// Debt of this denom repaid by the liquidator
+    pub liquidated_debt_amount: Uint128,
+    pub liquidated_debt_value: Uint128,
+    /// Collateral of this denom taken from liquidated accounts
+    pub seized_collateral_amount: Uint128,
+    pub seized_collateral_value: Uint128,
+    /// Value the liquidator received minus the value it repaid, for liquidations of this
+    /// collateral denom
+    pub liquidator_profit: i128,
+    /// Protocol liquidation fees collected in this denom
+    pub protocol_fee_amount: Uint128,
+    pub protocol_fee_value: Uint128,
+    /// Debt of this denom not covered by the collateral left in accounts at the end, split
+    /// between the debt denoms of an account by value
+    pub bad_debt_value: Uint128,
+}
+
+impl SimulationReport {
+    pub fn asset_mut(&mut self, denom: &str) -> &mut AssetReport {
+        self.assets.entry(denom.to_string()).or_default()
+    }
+
+    pub fn total_bad_debt_value(&self) -> Uint128 {
+        self.assets.values().map(|a| a.bad_debt_value).sum()
+    }
+
+    pub fn total_liquidator_profit(&self) -> i128 {
+        self.assets.values().map(|a| a.liquidator_profit).sum()
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "blocks: {}, liquidations: {}, failed liquidations: {}",
+            self.blocks, self.liquidations, self.failed_liquidations
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:>20} {:>20} {:>20} {:>20} {:>20}",
+            "denom",
+            "liquidated debt",
+            "seized collateral",
+            "liquidator profit",
+            "protocol fees",
+            "bad debt"
+        )?;
+        for (denom, asset) in &self.assets {
+            writeln!(
+                f,
+                "{:<16} {:>20} {:>20} {:>20} {:>20} {:>20}",
+                denom,
+                asset.liquidated_debt_value,
+                asset.seized_collateral_value,
+                asset.liquidator_profit,
+                asset.protocol_fee_value,
+                asset.bad_debt_value
+            )?;
+        }
+        write!(
+            f,
+            "total liquidator profit: {}, total bad debt: {}",
+            self.total_liquidator_profit(),
+            self.total_bad_debt_value()
+        )
+    }
+}
@@ -30,6 +30,7 @@ pub fn default_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParam
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -42,9 +43,11 @@ pub fn default_asset_params(denom: &str) -> (InitOrUpdateAssetParams, AssetParam
             slope: Decimal::one(),
             min_lb: Decimal::percent(0u64),
             max_lb: Decimal::percent(5u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
@@ -69,6 +72,7 @@ pub fn default_asset_params_with(
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -79,6 +83,7 @@ pub fn default_asset_params_with(
         liquidation_bonus,
         protocol_liquidation_fee: Decimal::percent(2u64),
         deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
@@ -1155,6 +1155,7 @@ fn redbank_should_fail_if_no_price() {
             denom: "umars".to_string(),
             amount: Uint128::new(10_000),
             recipient: None,
+            on_behalf_of: None,
         },
         &[],
         depositor,
@@ -1217,6 +1218,7 @@ fn redbank_quering_oracle_successfully() {
             denom: "uatom".to_string(),
             amount: Uint128::new(10_000),
             recipient: None,
+            on_behalf_of: None,
         },
         &[],
         depositor,
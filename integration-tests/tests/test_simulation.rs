@@ -0,0 +1,95 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use mars_integration_tests::simulation::{
+    parse_account_snapshots, parse_asset_params, parse_price_paths, run_simulation,
+    AccountSnapshot, SimulationConfig,
+};
+
+const PRICE_PATHS: &str = include_str!("files/simulation/price_paths.csv");
+const ACCOUNTS: &str = include_str!("files/simulation/accounts.csv");
+const ASSET_PARAMS: &str = include_str!("files/simulation/asset_params.csv");
+
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        price_paths: parse_price_paths(PRICE_PATHS).unwrap(),
+        accounts: parse_account_snapshots(ACCOUNTS).unwrap(),
+        asset_params: parse_asset_params(ASSET_PARAMS).unwrap(),
+        target_health_factor: None,
+        max_liquidations_per_block: 10,
+    }
+}
+
+#[test]
+fn parses_simulation_files() {
+    let config = config();
+    assert_eq!(config.price_paths.len(), 6);
+    assert_eq!(config.accounts.len(), 2);
+
+    let bob = &config.accounts[1];
+    assert_eq!(bob.account, "bob");
+    assert_eq!(bob.deposits, vec![coin(10000, "uosmo")]);
+    assert_eq!(bob.debts, vec![coin(6900, "uatom")]);
+
+    let uosmo = &config.asset_params[0];
+    assert_eq!(uosmo.denom, "uosmo");
+    assert_eq!(uosmo.liquidation_threshold, Decimal::percent(78));
+    assert_eq!(uosmo.liquidation_bonus.max_lb, Decimal::percent(10));
+}
+
+#[test]
+fn rejects_malformed_rows() {
+    let err = parse_price_paths("block,denom,price\n1,uosmo").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: expected 3 columns, found 2");
+
+    let err = parse_account_snapshots("account,denom,deposit,debt\nalice,uosmo,abc,0").unwrap_err();
+    assert!(err.to_string().starts_with("line 2: invalid amount abc"));
+}
+
+/// uosmo collateral crashes against uatom debt: both accounts get liquidated on the way down and
+/// the final drop leaves debt the remaining collateral can't cover
+#[test]
+fn replays_price_crash() {
+    let report = run_simulation(&config()).unwrap();
+
+    assert_eq!(report.blocks, 5);
+    assert!(report.liquidations >= 2);
+
+    let uatom = &report.assets["uatom"];
+    assert!(uatom.liquidated_debt_amount > Uint128::zero());
+    assert!(uatom.bad_debt_value > Uint128::zero());
+    assert_eq!(uatom.seized_collateral_amount, Uint128::zero());
+
+    let uosmo = &report.assets["uosmo"];
+    assert!(uosmo.seized_collateral_amount > Uint128::zero());
+    assert!(uosmo.protocol_fee_amount > Uint128::zero());
+    assert!(uosmo.liquidator_profit > 0);
+    assert_eq!(uosmo.bad_debt_value, Uint128::zero());
+    assert_eq!(uosmo.liquidated_debt_amount, Uint128::zero());
+}
+
+/// Snapshots can contain accounts which are already liquidatable at the prices of the first block
+#[test]
+fn opens_accounts_above_max_ltv() {
+    let mut config = config();
+    config.accounts.push(AccountSnapshot {
+        account: "carol".to_string(),
+        deposits: vec![coin(10000, "uosmo")],
+        debts: vec![coin(9000, "uatom")],
+    });
+
+    let report = run_simulation(&config).unwrap();
+    assert!(report.liquidations >= 3);
+    assert!(report.assets["uatom"].liquidated_debt_amount > Uint128::zero());
+}
+
+#[test]
+fn rejects_debt_without_collateral() {
+    let mut config = config();
+    config.accounts.push(AccountSnapshot {
+        account: "dave".to_string(),
+        deposits: vec![],
+        debts: vec![coin(1000, "uatom")],
+    });
+
+    let err = run_simulation(&config).unwrap_err();
+    assert_eq!(err.to_string(), "account dave: opening positions");
+}
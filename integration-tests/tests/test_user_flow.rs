@@ -302,6 +302,7 @@ fn internally_tracked_balances_used_for_borrow() {
         slope: Decimal::one(),
         min_lb: Decimal::percent(0u64),
         max_lb: Decimal::percent(5u64),
+        dutch_auction: None,
     };
 
     let mut mock_env = MockEnvBuilder::new(None, owner).build();
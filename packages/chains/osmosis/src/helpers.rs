@@ -10,7 +10,10 @@ use osmosis_std::{
     types::{
         cosmos::base::v1beta1::Coin,
         osmosis::{
-            concentratedliquidity::v1beta1::Pool as ConcentratedLiquidityPool,
+            concentratedliquidity::v1beta1::{
+                ConcentratedliquidityQuerier, FullPositionBreakdown,
+                Pool as ConcentratedLiquidityPool,
+            },
             cosmwasmpool::v1beta1::{
                 CalcOutAmtGivenIn, CalcOutAmtGivenInRequest, CalcOutAmtGivenInResponse,
                 CosmWasmPool as OsmoCosmWasmPool, CosmwasmpoolQuerier, InstantiateMsg,
@@ -210,6 +213,16 @@ pub fn query_pool(querier: &QuerierWrapper, pool_id: u64) -> StdResult<Pool> {
     res.pool.ok_or_else(|| StdError::not_found("pool"))?.try_into() // convert `Any` to `Pool`
 }
 
+/// Query a concentrated liquidity position with its underlying assets and claimable rewards
+pub fn query_cl_position(
+    querier: &QuerierWrapper,
+    position_id: u64,
+) -> StdResult<FullPositionBreakdown> {
+    ConcentratedliquidityQuerier::new(querier).position_by_id(position_id)?.position.ok_or_else(
+        || StdError::not_found(format!("concentrated liquidity position {position_id}")),
+    )
+}
+
 /// Query the spot price of a coin, denominated in OSMO
 pub fn query_spot_price(
     querier: &QuerierWrapper,
@@ -324,6 +337,43 @@ pub fn query_cosmwasm_pool_out_amount(
     Uint128::from_str(&amount_str)
 }
 
+/// Queries of the transmuter CosmWasm pool contract used to price its shares
+#[cw_serde]
+pub enum TransmuterQueryMsg {
+    GetTotalPoolLiquidity {},
+    GetTotalShares {},
+}
+
+#[cw_serde]
+pub struct GetTotalPoolLiquidityResponse {
+    pub total_pool_liquidity: Vec<cosmwasm_std::Coin>,
+}
+
+#[cw_serde]
+pub struct GetTotalSharesResponse {
+    pub total_shares: Uint128,
+}
+
+/// Query the assets held by a transmuter CosmWasm pool contract
+pub fn query_transmuter_total_pool_liquidity(
+    querier: &QuerierWrapper,
+    contract_addr: &str,
+) -> StdResult<Vec<cosmwasm_std::Coin>> {
+    let res: GetTotalPoolLiquidityResponse =
+        querier.query_wasm_smart(contract_addr, &TransmuterQueryMsg::GetTotalPoolLiquidity {})?;
+    Ok(res.total_pool_liquidity)
+}
+
+/// Query the total supply of shares of a transmuter CosmWasm pool contract
+pub fn query_transmuter_total_shares(
+    querier: &QuerierWrapper,
+    contract_addr: &str,
+) -> StdResult<Uint128> {
+    let res: GetTotalSharesResponse =
+        querier.query_wasm_smart(contract_addr, &TransmuterQueryMsg::GetTotalShares {})?;
+    Ok(res.total_shares)
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::to_json_vec;
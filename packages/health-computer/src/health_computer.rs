@@ -1,18 +1,21 @@
 use std::cmp::min;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Decimal, Fraction, Uint128};
+use cosmwasm_std::{Coin, Decimal, Fraction, Int128, Uint128};
+use mars_liquidation::liquidation::calculate_liquidation_amounts;
 use mars_types::{
-    credit_manager::Positions,
+    credit_manager::{DebtAmount, FeeConfig, Positions},
     health::{
-        AccountKind, BorrowTarget, Health,
+        AccountKind, BorrowTarget, Health, HealthChange,
         HealthError::{
-            MissingAmount, MissingHLSParams, MissingParams, MissingPrice, MissingVaultConfig,
-            MissingVaultValues,
+            AmountExceedsAvailable, DenomNotPresent, MissingAmount, MissingHLSParams,
+            MissingParams, MissingPrice, MissingVaultConfig, MissingVaultValues,
         },
-        HealthResult, LiquidationPriceKind, SwapKind,
+        HealthResult, HealthValuesResponse, LiquidationAmounts, LiquidationPreview,
+        LiquidationPriceKind, SwapKind,
     },
     params::{AssetParams, CmSettings, HlsAssetType, VaultConfig},
+    perps::PerpPosition,
 };
 #[cfg(feature = "javascript")]
 use tsify::Tsify;
@@ -29,6 +32,9 @@ pub struct HealthComputer {
     pub positions: Positions,
     pub denoms_data: DenomsData,
     pub vaults_data: VaultsData,
+    /// Protocol fees of the credit manager. The swap fee is taken from the coin swapped.
+    #[serde(default)]
+    pub fee_config: FeeConfig,
 }
 
 impl HealthComputer {
@@ -85,7 +91,9 @@ impl HealthComputer {
 
         // If no debt or coin is blacklisted (meaning does not contribute to max ltv hf),
         // the total amount deposited can be withdrawn
-        if self.positions.debts.is_empty() || !params.credit_manager.whitelisted {
+        if (self.positions.debts.is_empty() && self.positions.perps.is_empty())
+            || !params.credit_manager.whitelisted
+        {
             return Ok(withdraw_coin.amount);
         }
 
@@ -149,7 +157,10 @@ impl HealthComputer {
         let from_coin = self.get_coin_from_deposits_and_lends(from_denom)?;
 
         // If no debt the total amount deposited can be swapped (only for default swaps)
-        if kind == &SwapKind::Default && self.positions.debts.is_empty() {
+        if kind == &SwapKind::Default
+            && self.positions.debts.is_empty()
+            && self.positions.perps.is_empty()
+        {
             return Ok(from_coin.amount);
         }
 
@@ -177,8 +188,8 @@ impl HealthComputer {
         // Swapping that asset for an asset with the same price, but 0.8 max ltv results in a collateral_value of 0.8.
         // Therefore, when the asset that is swapped to has a higher or equal max ltv than the asset swapped from,
         // the collateral value will increase and we can allow the full balance to be swapped.
-        // The ltv_out is adjusted for slippage, as the swap_out_value can drop by the slippage.
-        let to_ltv_slippage_corrected = to_ltv.checked_mul(Decimal::one() - slippage)?;
+        // The ltv_out is adjusted for slippage and the swap fee, as the swap_out_value can drop by both.
+        let to_ltv_slippage_corrected = self.swap_out_ltv(to_ltv, slippage)?;
         let swappable_amount = if to_ltv_slippage_corrected >= from_ltv {
             from_coin.amount
         } else {
@@ -397,8 +408,7 @@ impl HealthComputer {
                 //      1 = (total_max_ltv_adjusted_value + (slippage * max_borrow_denom_amount * borrow_denom_price * denom_out_ltv)) / (debt_value + (max_borrow_denom_amount * borrow_denom_price))
                 // Re-arranging this to isolate borrow denom amount renders:
                 //      max_borrow_denom_amount = (total_max_ltv_adjusted_value - debt_value) / (borrow_denom_price * (1 - slippage * denom_out_ltv))
-                let out_ltv_slippage_corrected =
-                    denom_out_ltv.checked_mul(Decimal::one() - slippage)?;
+                let out_ltv_slippage_corrected = self.swap_out_ltv(denom_out_ltv, *slippage)?;
                 total_max_ltv_adjusted_value
                     .checked_sub(debt_value)?
                     .checked_sub(Uint128::one())?
@@ -479,6 +489,228 @@ impl HealthComputer {
         }
     }
 
+    /// Health of the account after applying the changes in order. Evaluating a multi-step
+    /// transaction this way is exact, as opposed to combining single-action estimates.
+    pub fn compute_health_with_changes(&self, changes: &[HealthChange]) -> HealthResult<Health> {
+        self.apply_changes(changes)?.compute_health()
+    }
+
+    /// Copy of the health computer with the changes applied in order.
+    /// Note: Swaps are valued at the given prices (including price shocks applied before them).
+    pub fn apply_changes(&self, changes: &[HealthChange]) -> HealthResult<HealthComputer> {
+        let mut computer = self.clone();
+        for change in changes {
+            computer.apply_change(change)?;
+        }
+        Ok(computer)
+    }
+
+    /// What a liquidator would repay and take for each pair of debt and collateral (deposits and
+    /// lends combined), if they repaid as much debt as allowed.
+    /// Pairs that can't be liquidated (e.g. because the amounts round down to zero) are skipped.
+    pub fn liquidation_preview(
+        &self,
+        target_health_factor: Decimal,
+    ) -> HealthResult<LiquidationPreview> {
+        let health = self.compute_health()?;
+        if !health.is_liquidatable() {
+            return Ok(LiquidationPreview {
+                liquidatable: false,
+                liquidations: vec![],
+            });
+        }
+        let health: mars_health::health::Health = HealthValuesResponse::from(health).into();
+
+        let mut collateral_denoms = vec![];
+        for coin in self.positions.deposits.iter().chain(self.positions.lends.iter()) {
+            if !collateral_denoms.contains(&coin.denom) {
+                collateral_denoms.push(coin.denom.clone());
+            }
+        }
+
+        let mut liquidations = vec![];
+        for debt in &self.positions.debts {
+            let debt_price =
+                self.denoms_data.prices.get(&debt.denom).ok_or(MissingPrice(debt.denom.clone()))?;
+
+            for collateral_denom in &collateral_denoms {
+                // Collateral without params can't be priced by the liquidation
+                let Some(collateral_params) = self.denoms_data.params.get(collateral_denom) else {
+                    continue;
+                };
+                let collateral_price = self
+                    .denoms_data
+                    .prices
+                    .get(collateral_denom)
+                    .ok_or(MissingPrice(collateral_denom.clone()))?;
+                let collateral_amount =
+                    self.get_coin_from_deposits_and_lends(collateral_denom)?.amount;
+
+                let Ok((
+                    debt_amount_to_repay,
+                    collateral_amount_to_liquidate,
+                    collateral_amount_received_by_liquidator,
+                )) = calculate_liquidation_amounts(
+                    collateral_amount,
+                    *collateral_price,
+                    collateral_params,
+                    debt.amount,
+                    debt.amount,
+                    *debt_price,
+                    target_health_factor,
+                    &health,
+                )
+                else {
+                    continue;
+                };
+
+                liquidations.push(LiquidationAmounts {
+                    debt_denom: debt.denom.clone(),
+                    collateral_denom: collateral_denom.clone(),
+                    debt_amount_to_repay,
+                    collateral_amount_to_liquidate,
+                    collateral_amount_received_by_liquidator,
+                });
+            }
+        }
+
+        Ok(LiquidationPreview {
+            liquidatable: true,
+            liquidations,
+        })
+    }
+
+    fn apply_change(&mut self, change: &HealthChange) -> HealthResult<()> {
+        match change {
+            HealthChange::Deposit {
+                coin,
+            } => add_coin(&mut self.positions.deposits, coin)?,
+            HealthChange::Withdraw {
+                coin,
+            } => self.remove_from_deposits_and_lends(coin)?,
+            HealthChange::Borrow {
+                coin,
+            } => {
+                match self.positions.debts.iter_mut().find(|d| d.denom == coin.denom) {
+                    // Debt shares are not simulated, they don't affect health
+                    Some(debt) => debt.amount = debt.amount.checked_add(coin.amount)?,
+                    None => self.positions.debts.push(DebtAmount {
+                        denom: coin.denom.clone(),
+                        shares: Uint128::zero(),
+                        amount: coin.amount,
+                    }),
+                }
+                add_coin(&mut self.positions.deposits, coin)?;
+            }
+            HealthChange::Repay {
+                coin,
+            } => {
+                let debt = self
+                    .positions
+                    .debts
+                    .iter_mut()
+                    .find(|d| d.denom == coin.denom)
+                    .ok_or(DenomNotPresent(coin.denom.clone()))?;
+                debt.amount = checked_sub_available(&coin.denom, debt.amount, coin.amount)?;
+                self.positions.debts.retain(|d| !d.amount.is_zero());
+                remove_coin(&mut self.positions.deposits, coin)?;
+            }
+            HealthChange::Swap {
+                coin_in,
+                denom_out,
+                slippage,
+            } => {
+                let price_in = self
+                    .denoms_data
+                    .prices
+                    .get(&coin_in.denom)
+                    .ok_or(MissingPrice(coin_in.denom.clone()))?;
+                let price_out = self
+                    .denoms_data
+                    .prices
+                    .get(denom_out)
+                    .ok_or(MissingPrice(denom_out.clone()))?;
+                // Swap fee is taken from the coin in, the rest is traded
+                let fee = coin_in.amount.checked_mul_floor(self.fee_config.swap_fee)?;
+                let amount_out = coin_in
+                    .amount
+                    .checked_sub(fee)?
+                    .checked_mul_floor(price_in.checked_div(*price_out)?)?
+                    .checked_mul_floor(Decimal::one() - slippage)?;
+
+                self.remove_from_deposits_and_lends(coin_in)?;
+                add_coin(
+                    &mut self.positions.deposits,
+                    &Coin {
+                        denom: denom_out.clone(),
+                        amount: amount_out,
+                    },
+                )?;
+            }
+            HealthChange::PriceShock {
+                denom,
+                multiplier,
+            } => {
+                let mut shocked = false;
+                if let Some(price) = self.denoms_data.prices.get_mut(denom) {
+                    *price = price.checked_mul(*multiplier)?;
+                    shocked = true;
+                }
+                // Vault values are priced in their base token
+                for values in self.vaults_data.vault_values.values_mut() {
+                    if values.base_coin.denom == *denom {
+                        values.vault_coin.value =
+                            values.vault_coin.value.checked_mul_floor(*multiplier)?;
+                        values.base_coin.value =
+                            values.base_coin.value.checked_mul_floor(*multiplier)?;
+                        shocked = true;
+                    }
+                }
+                if !shocked {
+                    return Err(MissingPrice(denom.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Max LTV of the coin received by a swap, scaled by the share of the swapped value which is
+    /// received in the worst case (after slippage and the swap fee)
+    fn swap_out_ltv(&self, ltv: Decimal, slippage: Decimal) -> HealthResult<Decimal> {
+        Ok(ltv
+            .checked_mul(Decimal::one() - slippage)?
+            .checked_mul(Decimal::one() - self.fee_config.swap_fee)?)
+    }
+
+    /// Takes the coin from deposits first and the remainder from lends
+    fn remove_from_deposits_and_lends(&mut self, coin: &Coin) -> HealthResult<()> {
+        let available = self.get_coin_from_deposits_and_lends(&coin.denom)?.amount;
+        checked_sub_available(&coin.denom, available, coin.amount)?;
+
+        let deposited = self
+            .positions
+            .deposits
+            .iter()
+            .find(|c| c.denom == coin.denom)
+            .map(|c| c.amount)
+            .unwrap_or_default();
+        let from_deposits = min(deposited, coin.amount);
+        remove_coin(
+            &mut self.positions.deposits,
+            &Coin {
+                denom: coin.denom.clone(),
+                amount: from_deposits,
+            },
+        )?;
+        remove_coin(
+            &mut self.positions.lends,
+            &Coin {
+                denom: coin.denom.clone(),
+                amount: coin.amount - from_deposits,
+            },
+        )
+    }
+
     fn total_debt_value(&self) -> HealthResult<Uint128> {
         let mut total = Uint128::zero();
         for debt in &self.positions.debts {
@@ -487,6 +719,10 @@ impl HealthComputer {
             let debt_value = debt.amount.checked_mul_ceil(*coin_price)?;
             total = total.checked_add(debt_value)?;
         }
+        for perp in &self.positions.perps {
+            let (_, debt_value) = self.perp_values(perp)?;
+            total = total.checked_add(debt_value)?;
+        }
         Ok(total)
     }
 
@@ -495,26 +731,138 @@ impl HealthComputer {
         let lends = self.coins_value(&self.positions.lends)?;
         let vaults = self.vaults_value()?;
         let staked_lp = self.coins_value(&self.positions.staked_astro_lps)?;
+        let staking = self.staking_value()?;
+        let cl_positions = self.cl_positions_value()?;
+        let perps = self.perps_value()?;
 
         Ok(CollateralValue {
             total_collateral_value: deposits
                 .total_collateral_value
                 .checked_add(vaults.total_collateral_value)?
                 .checked_add(lends.total_collateral_value)?
-                .checked_add(staked_lp.total_collateral_value)?,
+                .checked_add(staked_lp.total_collateral_value)?
+                .checked_add(staking.total_collateral_value)?
+                .checked_add(cl_positions.total_collateral_value)?
+                .checked_add(perps.total_collateral_value)?,
             max_ltv_adjusted_collateral: deposits
                 .max_ltv_adjusted_collateral
                 .checked_add(vaults.max_ltv_adjusted_collateral)?
                 .checked_add(lends.max_ltv_adjusted_collateral)?
-                .checked_add(staked_lp.max_ltv_adjusted_collateral)?,
+                .checked_add(staked_lp.max_ltv_adjusted_collateral)?
+                .checked_add(staking.max_ltv_adjusted_collateral)?
+                .checked_add(cl_positions.max_ltv_adjusted_collateral)?
+                .checked_add(perps.max_ltv_adjusted_collateral)?,
             liquidation_threshold_adjusted_collateral: deposits
                 .liquidation_threshold_adjusted_collateral
                 .checked_add(vaults.liquidation_threshold_adjusted_collateral)?
                 .checked_add(lends.liquidation_threshold_adjusted_collateral)?
-                .checked_add(staked_lp.liquidation_threshold_adjusted_collateral)?,
+                .checked_add(staked_lp.liquidation_threshold_adjusted_collateral)?
+                .checked_add(staking.liquidation_threshold_adjusted_collateral)?
+                .checked_add(cl_positions.liquidation_threshold_adjusted_collateral)?
+                .checked_add(perps.liquidation_threshold_adjusted_collateral)?,
         })
     }
 
+    /// Delegated and unbonding coins are valued at the oracle price reduced by the staking haircut.
+    /// Without staking params (haircut) the position does not count as collateral.
+    fn staking_value(&self) -> HealthResult<CollateralValue> {
+        let mut staked_coins = vec![];
+        for position in self.positions.staking.iter() {
+            let total = position.total()?;
+            let Some(staking) = self
+                .denoms_data
+                .params
+                .get(&total.denom)
+                .and_then(|params| params.credit_manager.staking.as_ref())
+            else {
+                continue;
+            };
+            staked_coins.push(Coin {
+                amount: total.amount.checked_mul_floor(Decimal::one() - staking.haircut)?,
+                denom: total.denom,
+            });
+        }
+        self.coins_value(&staked_coins)
+    }
+
+    /// Concentrated liquidity positions are valued by their underlying tokens at the ratio of
+    /// oracle prices (pool price can be manipulated). Positions with an unpriced token are skipped.
+    fn cl_positions_value(&self) -> HealthResult<CollateralValue> {
+        let mut underlying_coins = vec![];
+        for position in self.positions.cl_positions.iter() {
+            let (Some(price0), Some(price1)) = (
+                self.denoms_data.prices.get(&position.asset0.denom),
+                self.denoms_data.prices.get(&position.asset1.denom),
+            ) else {
+                continue;
+            };
+            let (amount0, amount1) = position.underlying_at_prices(*price0, *price1)?;
+            underlying_coins.push(amount0);
+            underlying_coins.push(amount1);
+        }
+        self.coins_value(&underlying_coins)
+    }
+
+    fn perps_value(&self) -> HealthResult<CollateralValue> {
+        let mut total_collateral_value = Uint128::zero();
+        let mut max_ltv_adjusted_collateral = Uint128::zero();
+        let mut liquidation_threshold_adjusted_collateral = Uint128::zero();
+
+        for perp in &self.positions.perps {
+            let (collateral_value, _) = self.perp_values(perp)?;
+            total_collateral_value = total_collateral_value.checked_add(collateral_value)?;
+            max_ltv_adjusted_collateral = max_ltv_adjusted_collateral
+                .checked_add(collateral_value.checked_mul_floor(perp.max_loan_to_value)?)?;
+            liquidation_threshold_adjusted_collateral =
+                liquidation_threshold_adjusted_collateral
+                    .checked_add(collateral_value.checked_mul_floor(perp.liquidation_threshold)?)?;
+        }
+
+        Ok(CollateralValue {
+            total_collateral_value,
+            max_ltv_adjusted_collateral,
+            liquidation_threshold_adjusted_collateral,
+        })
+    }
+
+    /// A perp position is valued like the equivalent spot position: a long counts its size at the
+    /// current price as collateral and at the entry price as debt, a short the other way around.
+    /// The difference is the unrealized PnL. The collateral side is weighted by the market's max LTV
+    /// and liquidation threshold, which acts as the margin requirement of the position.
+    /// Accrued funding is added to the collateral (if received) or debt (if paid) side, the closing
+    /// fee to the debt side.
+    /// Returns (collateral value, debt value)
+    fn perp_values(&self, perp: &PerpPosition) -> HealthResult<(Uint128, Uint128)> {
+        let price =
+            self.denoms_data.prices.get(&perp.denom).ok_or(MissingPrice(perp.denom.clone()))?;
+        let base_price = self
+            .denoms_data
+            .prices
+            .get(&perp.base_denom)
+            .ok_or(MissingPrice(perp.base_denom.clone()))?;
+
+        let size = perp.size.unsigned_abs();
+        let (mut collateral_value, mut debt_value) = if perp.is_long() {
+            (size.checked_mul_floor(*price)?, size.checked_mul_ceil(perp.entry_price)?)
+        } else {
+            (size.checked_mul_floor(perp.entry_price)?, size.checked_mul_ceil(*price)?)
+        };
+
+        let funding = perp.accrued_funding.unsigned_abs();
+        if perp.accrued_funding > Int128::zero() {
+            collateral_value =
+                collateral_value.checked_add(funding.checked_mul_floor(*base_price)?)?;
+        } else {
+            debt_value = debt_value.checked_add(funding.checked_mul_ceil(*base_price)?)?;
+        }
+
+        let closing_fee_value =
+            size.checked_mul_ceil(*price)?.checked_mul_ceil(perp.closing_fee_rate)?;
+        debt_value = debt_value.checked_add(closing_fee_value)?;
+
+        Ok((collateral_value, debt_value))
+    }
+
     fn coins_value(&self, coins: &[Coin]) -> HealthResult<CollateralValue> {
         let mut total_collateral_value = Uint128::zero();
         let mut max_ltv_adjusted_collateral = Uint128::zero();
@@ -741,3 +1089,37 @@ impl HealthComputer {
         })
     }
 }
+
+fn add_coin(coins: &mut Vec<Coin>, coin: &Coin) -> HealthResult<()> {
+    match coins.iter_mut().find(|c| c.denom == coin.denom) {
+        Some(c) => c.amount = c.amount.checked_add(coin.amount)?,
+        None => coins.push(coin.clone()),
+    }
+    Ok(())
+}
+
+fn remove_coin(coins: &mut Vec<Coin>, coin: &Coin) -> HealthResult<()> {
+    if coin.amount.is_zero() {
+        return Ok(());
+    }
+    let c = coins.iter_mut().find(|c| c.denom == coin.denom).ok_or(AmountExceedsAvailable {
+        denom: coin.denom.clone(),
+        requested: coin.amount,
+        available: Uint128::zero(),
+    })?;
+    c.amount = checked_sub_available(&coin.denom, c.amount, coin.amount)?;
+    coins.retain(|c| !c.amount.is_zero());
+    Ok(())
+}
+
+fn checked_sub_available(
+    denom: &str,
+    available: Uint128,
+    requested: Uint128,
+) -> HealthResult<Uint128> {
+    available.checked_sub(requested).map_err(|_| AmountExceedsAvailable {
+        denom: denom.to_string(),
+        requested,
+        available,
+    })
+}
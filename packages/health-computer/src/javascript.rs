@@ -1,5 +1,9 @@
+use std::str::FromStr;
+
+use cosmwasm_std::Decimal;
 use mars_types::health::{
-    BorrowTarget, HealthValuesResponse, LiquidationPriceKind, Slippage, SwapKind,
+    BorrowTarget, HealthChanges, HealthValuesResponse, LiquidationPreview, LiquidationPriceKind,
+    Slippage, SwapKind,
 };
 use wasm_bindgen::prelude::*;
 
@@ -50,3 +54,21 @@ pub fn liquidation_price_js(
 ) -> String {
     c.liquidation_price(&denom, &kind).unwrap().to_string()
 }
+
+#[wasm_bindgen]
+pub fn compute_health_with_changes_js(
+    c: HealthComputer,
+    changes: HealthChanges,
+) -> HealthValuesResponse {
+    c.compute_health_with_changes(&changes.0).unwrap().into()
+}
+
+#[wasm_bindgen]
+pub fn liquidation_preview_js(
+    c: HealthComputer,
+    changes: HealthChanges,
+    target_health_factor: String,
+) -> LiquidationPreview {
+    let target_health_factor = Decimal::from_str(&target_health_factor).unwrap();
+    c.apply_changes(&changes.0).unwrap().liquidation_preview(target_health_factor).unwrap()
+}
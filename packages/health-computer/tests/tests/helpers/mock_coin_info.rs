@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Uint128};
 use mars_types::params::{
     AssetParams, CmSettings, HlsAssetType, HlsParams, LiquidationBonus, RedBankSettings,
 };
@@ -27,10 +27,12 @@ pub fn umars_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -38,6 +40,7 @@ pub fn umars_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -56,10 +59,12 @@ pub fn udai_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -67,6 +72,7 @@ pub fn udai_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -85,10 +91,12 @@ pub fn uluna_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -96,6 +104,7 @@ pub fn uluna_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -114,6 +123,7 @@ pub fn ustars_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
@@ -130,6 +140,7 @@ pub fn ustars_info() -> CoinInfo {
                         },
                     ],
                 }),
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -137,6 +148,7 @@ pub fn ustars_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -155,10 +167,12 @@ pub fn ujuno_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -166,6 +180,7 @@ pub fn ujuno_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -184,6 +199,7 @@ pub fn uatom_info() -> CoinInfo {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             credit_manager: CmSettings {
                 whitelisted: true,
@@ -194,6 +210,7 @@ pub fn uatom_info() -> CoinInfo {
                         denom: "stAtom".to_string(),
                     }],
                 }),
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -201,6 +218,7 @@ pub fn uatom_info() -> CoinInfo {
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     }
 }
@@ -63,6 +63,7 @@ fn random_coin_info() -> impl Strategy<Value = AssetParams> {
                         liquidation_threshold: hls_liq_threshold,
                         correlations: vec![],
                     }),
+                    staking: None,
                 },
                 red_bank: RedBankSettings {
                     deposit_enabled: true,
@@ -75,9 +76,11 @@ fn random_coin_info() -> impl Strategy<Value = AssetParams> {
                     slope: Default::default(),
                     min_lb: Default::default(),
                     max_lb: Default::default(),
+                    dutch_auction: None,
                 },
                 protocol_liquidation_fee: Default::default(),
                 deposit_cap: Default::default(),
+                min_debt_value: Uint128::zero(),
             }
         },
     )
@@ -308,9 +311,13 @@ pub fn random_health_computer() -> impl Strategy<Value = HealthComputer> {
                         lends,
                         vaults,
                         staked_astro_lps,
+                        staking: vec![],
+                        cl_positions: vec![],
+                        perps: vec![],
                     },
                     denoms_data: denoms_data.clone(),
                     vaults_data: vaults_data.clone(),
+                    fee_config: Default::default(),
                 }
             })
     })
@@ -1,5 +1,6 @@
 mod helpers;
 
+mod test_health_changes;
 mod test_health_scenarios;
 mod test_hls;
 mod test_input_validation;
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+use mars_rover_health_computer::{DenomsData, HealthComputer, VaultsData};
+use mars_types::{
+    credit_manager::{DebtAmount, FeeConfig, Positions},
+    health::{AccountKind, HealthChange, HealthError, LiquidationAmounts, LiquidationPreview},
+};
+
+use super::helpers::{uluna_info, umars_info};
+
+/// Step 1: User deposits 1000 mars (1 price)
+/// Step 2: User borrows 50 luna (10 price) and swaps them to 500 mars
+/// Health: assets_value: 1500
+///         debt value 500
+///         max ltv health factor: 1200 / 500 = 2.4
+///         liquidation health factor: 1260 / 500 = 2.52
+/// Step 3: Price of mars drops by 70%
+/// Health: assets_value: 450
+///         debt value 500
+///         liquidation health factor: 378 / 500 = 0.756
+#[test]
+fn multi_step_changes() {
+    let h = mars_account(vec![coin(1000, "umars")], vec![]);
+
+    let changes = vec![
+        HealthChange::Borrow {
+            coin: coin(50, "uluna"),
+        },
+        HealthChange::Swap {
+            coin_in: coin(50, "uluna"),
+            denom_out: "umars".to_string(),
+            slippage: Decimal::zero(),
+        },
+    ];
+
+    let after = h.apply_changes(&changes).unwrap();
+    assert_eq!(after.positions.deposits, vec![coin(1500, "umars")]);
+    assert_eq!(
+        after.positions.debts,
+        vec![DebtAmount {
+            denom: "uluna".to_string(),
+            shares: Uint128::zero(),
+            amount: Uint128::new(50),
+        }]
+    );
+
+    let health = h.compute_health_with_changes(&changes).unwrap();
+    assert_eq!(health.total_collateral_value, Uint128::new(1500));
+    assert_eq!(health.total_debt_value, Uint128::new(500));
+    assert_eq!(health.max_ltv_health_factor, Some(Decimal::from_ratio(12u128, 5u128)));
+    assert_eq!(health.liquidation_health_factor, Some(Decimal::from_ratio(252u128, 100u128)));
+    assert!(!health.is_liquidatable());
+
+    // the original computer is not modified
+    assert_eq!(h.compute_health().unwrap().total_collateral_value, Uint128::new(1000));
+
+    let mut changes = changes;
+    changes.push(HealthChange::PriceShock {
+        denom: "umars".to_string(),
+        multiplier: Decimal::percent(30),
+    });
+    let health = h.compute_health_with_changes(&changes).unwrap();
+    assert_eq!(health.total_collateral_value, Uint128::new(450));
+    assert_eq!(health.liquidation_health_factor, Some(Decimal::from_ratio(756u128, 1000u128)));
+    assert!(health.is_liquidatable());
+}
+
+#[test]
+fn swap_fee_is_taken_from_coin_in() {
+    let mut h = mars_account(vec![coin(1000, "umars"), coin(100, "uluna")], vec![]);
+    h.fee_config = FeeConfig {
+        borrow_fees: vec![],
+        swap_fee: Decimal::percent(10),
+    };
+
+    let after = h
+        .apply_changes(&[HealthChange::Swap {
+            coin_in: coin(50, "uluna"),
+            denom_out: "umars".to_string(),
+            slippage: Decimal::zero(),
+        }])
+        .unwrap();
+
+    // 5 luna fee, 45 luna (10 price) swapped to 450 mars
+    assert_eq!(after.positions.deposits, vec![coin(1450, "umars"), coin(50, "uluna")]);
+}
+
+#[test]
+fn withdraw_takes_from_deposits_then_lends() {
+    let h = mars_account(vec![coin(100, "umars")], vec![coin(50, "umars")]);
+
+    let after = h
+        .apply_changes(&[HealthChange::Withdraw {
+            coin: coin(120, "umars"),
+        }])
+        .unwrap();
+    assert!(after.positions.deposits.is_empty());
+    assert_eq!(after.positions.lends, vec![coin(30, "umars")]);
+
+    let err = h
+        .apply_changes(&[HealthChange::Withdraw {
+            coin: coin(151, "umars"),
+        }])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        HealthError::AmountExceedsAvailable {
+            denom: "umars".to_string(),
+            requested: Uint128::new(151),
+            available: Uint128::new(150),
+        }
+    );
+}
+
+#[test]
+fn repay_reduces_debt_and_deposits() {
+    let h = mars_account(vec![coin(1000, "umars")], vec![]);
+
+    let after = h
+        .apply_changes(&[
+            HealthChange::Borrow {
+                coin: coin(50, "uluna"),
+            },
+            HealthChange::Repay {
+                coin: coin(50, "uluna"),
+            },
+        ])
+        .unwrap();
+    assert_eq!(after.positions.deposits, vec![coin(1000, "umars")]);
+    assert!(after.positions.debts.is_empty());
+
+    let err = h
+        .apply_changes(&[HealthChange::Repay {
+            coin: coin(50, "uluna"),
+        }])
+        .unwrap_err();
+    assert_eq!(err, HealthError::DenomNotPresent("uluna".to_string()));
+
+    let err = h
+        .apply_changes(&[HealthChange::PriceShock {
+            denom: "uatom".to_string(),
+            multiplier: Decimal::percent(50),
+        }])
+        .unwrap_err();
+    assert_eq!(err, HealthError::MissingPrice("uatom".to_string()));
+}
+
+#[test]
+fn liquidation_preview() {
+    let h = mars_account(vec![coin(1000, "umars")], vec![]);
+    let target_health_factor = Decimal::from_ratio(12u128, 10u128);
+
+    let h = h
+        .apply_changes(&[
+            HealthChange::Borrow {
+                coin: coin(50, "uluna"),
+            },
+            HealthChange::Swap {
+                coin_in: coin(50, "uluna"),
+                denom_out: "umars".to_string(),
+                slippage: Decimal::zero(),
+            },
+        ])
+        .unwrap();
+    assert_eq!(
+        h.liquidation_preview(target_health_factor).unwrap(),
+        LiquidationPreview {
+            liquidatable: false,
+            liquidations: vec![],
+        }
+    );
+
+    // Liquidation bonus is capped at min_lb (2%), as the account is undercollateralized (CR < 1).
+    // The debt repaid is limited by the collateral available:
+    //      floor(floor(450 / 1.02) / 10) = 44 uluna (440 value)
+    // collateral to liquidate: floor(floor(440 * 1.02) / 0.3) = 1493 umars
+    // protocol fee: floor(ceil(floor(440 * 0.02) * 0.02) / 0.3) = 3 umars
+    let h = h
+        .apply_changes(&[HealthChange::PriceShock {
+            denom: "umars".to_string(),
+            multiplier: Decimal::percent(30),
+        }])
+        .unwrap();
+    assert_eq!(
+        h.liquidation_preview(target_health_factor).unwrap(),
+        LiquidationPreview {
+            liquidatable: true,
+            liquidations: vec![LiquidationAmounts {
+                debt_denom: "uluna".to_string(),
+                collateral_denom: "umars".to_string(),
+                debt_amount_to_repay: Uint128::new(44),
+                collateral_amount_to_liquidate: Uint128::new(1493),
+                collateral_amount_received_by_liquidator: Uint128::new(1490),
+            }],
+        }
+    );
+}
+
+fn mars_account(deposits: Vec<Coin>, lends: Vec<Coin>) -> HealthComputer {
+    let umars = umars_info();
+    let uluna = uluna_info();
+
+    HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+            deposits,
+            debts: vec![],
+            lends,
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
+        },
+        denoms_data: DenomsData {
+            prices: HashMap::from([
+                (umars.denom.clone(), umars.price),
+                (uluna.denom.clone(), uluna.price),
+            ]),
+            params: HashMap::from([(umars.denom, umars.params), (uluna.denom, uluna.params)]),
+        },
+        vaults_data: VaultsData::default(),
+        fee_config: Default::default(),
+    }
+}
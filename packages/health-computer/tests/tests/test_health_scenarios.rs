@@ -1,15 +1,16 @@
 use std::{collections::HashMap, ops::Add, str::FromStr};
 
-use cosmwasm_std::{coin, Addr, Coin, Decimal, Uint128};
+use cosmwasm_std::{coin, Addr, Coin, Decimal, Decimal256, Int128, Uint128};
 use mars_rover_health_computer::{DenomsData, HealthComputer, VaultsData};
 use mars_types::{
     adapters::vault::{
         CoinValue, LockingVaultAmount, UnlockingPositions, Vault, VaultAmount, VaultPosition,
         VaultPositionAmount, VaultPositionValue, VaultUnlockingPosition,
     },
-    credit_manager::{DebtAmount, Positions},
+    credit_manager::{ClPosition, DebtAmount, Positions},
     health::AccountKind,
     params::VaultConfig,
+    perps::PerpPosition,
 };
 
 use super::helpers::{udai_info, ujuno_info, uluna_info, umars_info, ustars_info};
@@ -48,9 +49,13 @@ fn only_assets_with_no_debts() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -116,9 +121,13 @@ fn terra_ragnarok() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data: vaults_data.clone(),
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -178,9 +187,13 @@ fn terra_ragnarok() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -248,9 +261,13 @@ fn ltv_and_lqdt_adjusted_values() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -371,9 +388,13 @@ fn debt_value() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -476,9 +497,13 @@ fn above_max_ltv_below_liq_threshold() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -541,9 +566,13 @@ fn liquidatable() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -608,9 +637,13 @@ fn rover_whitelist_influences_max_ltv() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -702,9 +735,13 @@ fn unlocked_vault() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -799,9 +836,13 @@ fn locked_vault() {
                 }),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -905,9 +946,13 @@ fn locked_vault_with_unlocking_positions() {
                 }),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -999,9 +1044,13 @@ fn vault_is_not_whitelisted() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -1111,9 +1160,13 @@ fn vault_base_token_is_not_whitelisted() {
                 }),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -1172,9 +1225,13 @@ fn lent_coins_used_as_collateral() {
             lends: vec![coin(10, udai.denom), coin(2, uluna.denom)],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -1235,9 +1292,13 @@ fn allowed_lent_coins_influence_max_ltv() {
             lends: vec![coin(10, udai.denom), coin(2, uluna.denom)],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -1256,3 +1317,147 @@ fn allowed_lent_coins_influence_max_ltv() {
     assert!(health.is_above_max_ltv());
     assert!(!health.is_liquidatable());
 }
+
+/// Concentrated liquidity position luna/mars in range [0.1, 100) with liquidity 1_000_000.
+/// Oracle prices give pool price 10 (luna 10, mars 1), so the underlying is:
+///     luna: L * (1 / sqrt(10) - 1 / sqrt(100)) = 216_227
+///     mars: L * (sqrt(10) - sqrt(0.1)) = 2_846_049
+/// The second position contains an unpriced token and is not counted.
+#[test]
+fn cl_positions_valued_at_oracle_prices() {
+    let umars = umars_info();
+    let uluna = uluna_info();
+
+    let denoms_data = DenomsData {
+        prices: HashMap::from([
+            (umars.denom.clone(), umars.price),
+            (uluna.denom.clone(), uluna.price),
+        ]),
+        params: HashMap::from([
+            (umars.denom.clone(), umars.params.clone()),
+            (uluna.denom.clone(), uluna.params.clone()),
+        ]),
+    };
+
+    let vaults_data = VaultsData {
+        vault_values: Default::default(),
+        vault_configs: Default::default(),
+    };
+
+    let h = HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+            deposits: vec![],
+            debts: vec![DebtAmount {
+                denom: umars.denom.clone(),
+                shares: Default::default(),
+                amount: Uint128::new(3_000_000),
+            }],
+            lends: vec![],
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![
+                ClPosition {
+                    position_id: 1,
+                    pool_id: 1,
+                    lower_tick: -9_000_000,
+                    upper_tick: 18_000_000,
+                    liquidity: Decimal256::from_ratio(1_000_000u128, 1u128),
+                    // Pool price is ignored, only oracle prices are used
+                    asset0: coin(1, &uluna.denom),
+                    asset1: coin(1, &umars.denom),
+                },
+                ClPosition {
+                    position_id: 2,
+                    pool_id: 2,
+                    lower_tick: -9_000_000,
+                    upper_tick: 18_000_000,
+                    liquidity: Decimal256::from_ratio(1_000_000u128, 1u128),
+                    asset0: coin(100, "uunknown"),
+                    asset1: coin(100, &umars.denom),
+                },
+            ],
+            perps: vec![],
+        },
+        denoms_data,
+        vaults_data,
+        fee_config: Default::default(),
+    };
+
+    let health = h.compute_health().unwrap();
+    assert_eq!(health.total_collateral_value, Uint128::new(2_162_270 + 2_846_049));
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::new(1_513_589 + 2_276_839));
+    assert_eq!(
+        health.liquidation_threshold_adjusted_collateral,
+        Uint128::new(1_686_570 + 2_390_681)
+    );
+    assert_eq!(health.total_debt_value, Uint128::new(3_000_000));
+    assert!(!health.is_above_max_ltv());
+    assert!(!health.is_liquidatable());
+}
+
+/// Perp positions on luna (price 10), settled in mars (price 1). Market max LTV 0.8, liquidation
+/// threshold 0.85, closing fee 0.1%.
+///     long 100 @ 8 with 20 funding received:  collateral 1000 + 20, debt 800 + 1 closing fee
+///     short 50 @ 12 with 10 funding paid:     collateral 600, debt 500 + 1 closing fee + 10
+/// Perp denoms only need a price, not asset params.
+#[test]
+fn perp_positions_valued_with_unrealized_pnl() {
+    let umars = umars_info();
+    let uluna = uluna_info();
+
+    let denoms_data = DenomsData {
+        prices: HashMap::from([
+            (umars.denom.clone(), umars.price),
+            (uluna.denom.clone(), uluna.price),
+        ]),
+        params: HashMap::from([(umars.denom.clone(), umars.params.clone())]),
+    };
+
+    let vaults_data = VaultsData {
+        vault_values: Default::default(),
+        vault_configs: Default::default(),
+    };
+
+    let perp = |size: i128, entry_price: u128, accrued_funding: i128| PerpPosition {
+        denom: uluna.denom.clone(),
+        base_denom: umars.denom.clone(),
+        size: Int128::new(size),
+        entry_price: Decimal::from_ratio(entry_price, 1u128),
+        accrued_funding: Int128::new(accrued_funding),
+        closing_fee_rate: Decimal::permille(1),
+        liquidation_fee_rate: Decimal::percent(1),
+        max_loan_to_value: Decimal::percent(80),
+        liquidation_threshold: Decimal::percent(85),
+    };
+
+    let h = HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+            deposits: vec![coin(1_000, &umars.denom)],
+            debts: vec![],
+            lends: vec![],
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![perp(100, 8, 20), perp(-50, 12, -10)],
+        },
+        denoms_data,
+        vaults_data,
+        fee_config: Default::default(),
+    };
+
+    let health = h.compute_health().unwrap();
+    assert_eq!(health.total_collateral_value, Uint128::new(1_000 + 1_020 + 600));
+    assert_eq!(health.max_ltv_adjusted_collateral, Uint128::new(800 + 816 + 480));
+    assert_eq!(health.liquidation_threshold_adjusted_collateral, Uint128::new(840 + 867 + 510));
+    assert_eq!(health.total_debt_value, Uint128::new(801 + 511));
+    assert!(!health.is_above_max_ltv());
+    assert!(!health.is_liquidatable());
+}
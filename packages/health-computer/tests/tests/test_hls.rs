@@ -42,9 +42,13 @@ fn hls_deposit() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -130,9 +134,13 @@ fn hls_vault() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -181,9 +189,13 @@ fn hls_on_blacklisted_asset() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -264,9 +276,13 @@ fn hls_on_blacklisted_vault() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
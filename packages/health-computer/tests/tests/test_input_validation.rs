@@ -53,9 +53,13 @@ fn missing_price_data() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.compute_health().unwrap_err();
@@ -102,9 +106,13 @@ fn missing_params() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     // If asset params is missing for a denom (in params contract), both price and params will be missing in denoms_data.
@@ -182,9 +190,13 @@ fn missing_market_data_for_vault_base_token() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::one())),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.compute_health().unwrap_err();
@@ -229,9 +241,13 @@ fn missing_vault_value() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::one())),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.compute_health().unwrap_err();
@@ -280,9 +296,13 @@ fn missing_vault_config() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::one())),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.compute_health().unwrap_err();
@@ -318,9 +338,13 @@ fn missing_hls_params() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.compute_health().unwrap_err();
@@ -34,9 +34,13 @@ fn max_borrow_deposit_offset_good() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_borrow_amount =
@@ -69,9 +73,13 @@ fn max_borrow_deposit_offset_margin_of_error() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_borrow_amount =
@@ -52,9 +52,13 @@ fn missing_borrow_denom_price_data() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError =
@@ -101,9 +105,13 @@ fn missing_borrow_denom_params() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError =
@@ -153,9 +161,13 @@ fn cannot_borrow_when_unhealthy() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_withdraw_amount =
@@ -225,9 +237,13 @@ fn hls_influences_max_borrow() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_before = h.max_borrow_amount_estimate(&ustars.denom, &BorrowTarget::Deposit).unwrap();
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use cosmwasm_std::{coin, Decimal, Uint128};
 use mars_rover_health_computer::{DenomsData, HealthComputer, VaultsData};
 use mars_types::{
-    credit_manager::Positions,
+    credit_manager::{FeeConfig, Positions},
     health::{AccountKind, SwapKind},
 };
 
@@ -41,9 +41,13 @@ fn max_swap_default() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_borrow_amount = h
@@ -84,9 +88,13 @@ fn max_swap_margin() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_borrow_amount = h
@@ -94,3 +102,57 @@ fn max_swap_margin() {
         .unwrap();
     assert_eq!(Uint128::new(31351), max_borrow_amount);
 }
+
+#[test]
+fn max_swap_with_swap_fee() {
+    let udai = udai_info();
+    let umars = umars_info();
+
+    let mut h = HealthComputer {
+        kind: AccountKind::Default,
+        positions: Positions {
+            account_id: "123".to_string(),
+            account_kind: AccountKind::Default,
+
+            deposits: vec![coin(5000, &udai.denom), coin(500, &umars.denom)],
+            debts: vec![],
+            lends: vec![],
+            vaults: vec![],
+            staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
+        },
+        denoms_data: DenomsData {
+            prices: HashMap::from([
+                (udai.denom.clone(), udai.price),
+                (umars.denom.clone(), umars.price),
+            ]),
+            params: HashMap::from([
+                (udai.denom.clone(), udai.params.clone()),
+                (umars.denom.clone(), umars.params.clone()),
+            ]),
+        },
+        vaults_data: VaultsData::default(),
+        fee_config: Default::default(),
+    };
+
+    let fee = Decimal::percent(5);
+    let without_fee = h
+        .max_swap_amount_estimate(&udai.denom, &umars.denom, &SwapKind::Margin, Decimal::zero())
+        .unwrap();
+    let fee_as_slippage =
+        h.max_swap_amount_estimate(&udai.denom, &umars.denom, &SwapKind::Margin, fee).unwrap();
+
+    h.fee_config = FeeConfig {
+        borrow_fees: vec![],
+        swap_fee: fee,
+    };
+    let with_fee = h
+        .max_swap_amount_estimate(&udai.denom, &umars.denom, &SwapKind::Margin, Decimal::zero())
+        .unwrap();
+
+    // The swap fee reduces the coins received like slippage does
+    assert!(with_fee < without_fee);
+    assert_eq!(with_fee, fee_as_slippage);
+}
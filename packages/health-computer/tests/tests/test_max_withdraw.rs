@@ -53,9 +53,13 @@ fn missing_price_data() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.max_withdraw_amount_estimate(&udai.denom).unwrap_err();
@@ -102,9 +106,13 @@ fn missing_params() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let err: HealthError = h.max_withdraw_amount_estimate(&umars.denom).unwrap_err();
@@ -134,9 +142,13 @@ fn deposit_not_present() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_withdraw_amount = h.max_withdraw_amount_estimate("xyz").unwrap();
@@ -190,9 +202,13 @@ fn blacklisted_assets_should_be_able_be_fully_withdrawn() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -246,9 +262,13 @@ fn zero_when_unhealthy() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let health = h.compute_health().unwrap();
@@ -283,9 +303,13 @@ fn no_debts() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_withdraw_amount = h.max_withdraw_amount_estimate(&ustars.denom).unwrap();
@@ -328,9 +352,13 @@ fn should_allow_max_withdraw() {
             lends: vec![],
             vaults: vec![],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     // Max when debt value is smaller than collateral value - withdraw denom value
@@ -399,9 +427,13 @@ fn hls_with_max_withdraw() {
                 amount: VaultPositionAmount::Unlocked(VaultAmount::new(Uint128::new(5264))),
             }],
             staked_astro_lps: vec![],
+            staking: vec![],
+            cl_positions: vec![],
+            perps: vec![],
         },
         denoms_data,
         vaults_data,
+        fee_config: Default::default(),
     };
 
     let max_before = h.max_withdraw_amount_estimate(&ustars.denom).unwrap();
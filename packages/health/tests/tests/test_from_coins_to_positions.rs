@@ -134,6 +134,7 @@ fn mock_setup() -> MarsMockQuerier {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -146,9 +147,11 @@ fn mock_setup() -> MarsMockQuerier {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::zero(),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     );
     let atom_market = Market {
@@ -163,6 +166,7 @@ fn mock_setup() -> MarsMockQuerier {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -175,9 +179,11 @@ fn mock_setup() -> MarsMockQuerier {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::zero(),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     );
 
@@ -28,6 +28,7 @@ fn health_success_from_coins() {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -40,9 +41,11 @@ fn health_success_from_coins() {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::zero(),
             deposit_cap: Uint128::MAX,
+            min_debt_value: Uint128::zero(),
         },
     );
     let atom_market = Market {
@@ -57,6 +60,7 @@ fn health_success_from_coins() {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -69,9 +73,11 @@ fn health_success_from_coins() {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::zero(),
             deposit_cap: Uint128::MAX,
+            min_debt_value: Uint128::zero(),
         },
     );
 
@@ -125,6 +131,7 @@ fn health_error_from_coins() {
             credit_manager: CmSettings {
                 whitelisted: false,
                 hls: None,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: false,
@@ -137,9 +144,11 @@ fn health_error_from_coins() {
                 slope: Decimal::one(),
                 min_lb: Decimal::percent(0u64),
                 max_lb: Decimal::percent(5u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::zero(),
             deposit_cap: Default::default(),
+            min_debt_value: Uint128::zero(),
         },
     );
 
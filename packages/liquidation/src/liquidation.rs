@@ -45,8 +45,6 @@ pub fn calculate_liquidation_amounts(
     // if health.liquidatable == true, save to unwrap
     let liquidation_health_factor = health.liquidation_health_factor.unwrap();
 
-    let user_collateral_value = collateral_amount.checked_mul_floor(collateral_price)?;
-
     let liquidation_bonus = calculate_liquidation_bonus(
         liquidation_health_factor,
         health.total_collateral_value,
@@ -54,6 +52,36 @@ pub fn calculate_liquidation_amounts(
         collateral_params,
     )?;
 
+    calculate_liquidation_amounts_with_bonus(
+        collateral_amount,
+        collateral_price,
+        collateral_params,
+        debt_amount,
+        debt_requested_to_repay,
+        debt_price,
+        target_health_factor,
+        health,
+        liquidation_bonus,
+    )
+}
+
+/// Same as [`calculate_liquidation_amounts`] but uses the provided liquidation bonus instead of
+/// deriving it from the Health Factor, e.g. when the bonus is determined by a Dutch auction
+/// (see [`calculate_dutch_auction_liquidation_bonus`]).
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_liquidation_amounts_with_bonus(
+    collateral_amount: Uint128,
+    collateral_price: Decimal,
+    collateral_params: &AssetParams,
+    debt_amount: Uint128,
+    debt_requested_to_repay: Uint128,
+    debt_price: Decimal,
+    target_health_factor: Decimal,
+    health: &Health,
+    liquidation_bonus: Decimal,
+) -> Result<(Uint128, Uint128, Uint128), LiquidationError> {
+    let user_collateral_value = collateral_amount.checked_mul_floor(collateral_price)?;
+
     // All debt is liquidatable: When MDR < 0, it means even repaying the whole debt is not going to be enough
     // to bring the account back to the THF, so the liquidator should be able to repay all the available debt.
     // Given the numerator in the MDR formula is always > 0, MDR < 0 happens when the denominator is < 0
@@ -135,11 +163,73 @@ pub fn calculate_liquidation_amounts(
 ///     )
 /// )
 /// `CR` is the Collateralization Ratio of the position calculated as `CR = Total Assets / Total Debt`.
-fn calculate_liquidation_bonus(
+pub fn calculate_liquidation_bonus(
     liquidation_health_factor: Decimal,
     total_collateral_value: Uint128,
     total_debt_value: Uint128,
     collateral_params: &AssetParams,
+) -> Result<Decimal, LiquidationError> {
+    let max_lb_adjusted =
+        calculate_max_lb_adjusted(total_collateral_value, total_debt_value, collateral_params)?;
+
+    let calculated_bonus = collateral_params.liquidation_bonus.starting_lb.checked_add(
+        collateral_params
+            .liquidation_bonus
+            .slope
+            .checked_mul(Decimal::one() - liquidation_health_factor)?,
+    )?;
+
+    let liquidation_bonus = min(calculated_bonus, max_lb_adjusted);
+
+    Ok(liquidation_bonus)
+}
+
+/// The LB offered by a Dutch auction increases linearly with the number of blocks elapsed since
+/// the auction started:
+/// Liquidation Bonus = min(
+///     starting_lb + (max_lb - starting_lb) * min(elapsed_blocks / duration_blocks, 1),
+///     max(
+///         min(CR - 1, max_lb),
+///         min_lb
+///     )
+/// )
+/// If no Dutch auction is configured for the collateral, the auction is treated as finished.
+pub fn calculate_dutch_auction_liquidation_bonus(
+    elapsed_blocks: u64,
+    total_collateral_value: Uint128,
+    total_debt_value: Uint128,
+    collateral_params: &AssetParams,
+) -> Result<Decimal, LiquidationError> {
+    let lb_params = &collateral_params.liquidation_bonus;
+
+    let max_lb_adjusted =
+        calculate_max_lb_adjusted(total_collateral_value, total_debt_value, collateral_params)?;
+
+    let auction_progress = match &lb_params.dutch_auction {
+        Some(dutch_auction) if elapsed_blocks < dutch_auction.duration_blocks => {
+            Decimal::checked_from_ratio(elapsed_blocks, dutch_auction.duration_blocks)?
+        }
+        _ => Decimal::one(),
+    };
+
+    let lb_range = lb_params.max_lb.checked_sub(lb_params.starting_lb)?;
+    let calculated_bonus =
+        lb_params.starting_lb.checked_add(lb_range.checked_mul(auction_progress)?)?;
+
+    let liquidation_bonus = min(calculated_bonus, max_lb_adjusted);
+
+    Ok(liquidation_bonus)
+}
+
+/// Upper bound of the LB based on the Collateralization Ratio of the position:
+/// max(
+///     min(CR - 1, max_lb),
+///     min_lb
+/// )
+fn calculate_max_lb_adjusted(
+    total_collateral_value: Uint128,
+    total_debt_value: Uint128,
+    collateral_params: &AssetParams,
 ) -> Result<Decimal, LiquidationError> {
     let collateralization_ratio =
         Decimal::checked_from_ratio(total_collateral_value, total_debt_value)?;
@@ -151,19 +241,8 @@ fn calculate_liquidation_bonus(
         Decimal::zero()
     };
 
-    let max_lb_adjusted = max(
+    Ok(max(
         min(collateralization_ratio_adjusted, collateral_params.liquidation_bonus.max_lb),
         collateral_params.liquidation_bonus.min_lb,
-    );
-
-    let calculated_bonus = collateral_params.liquidation_bonus.starting_lb.checked_add(
-        collateral_params
-            .liquidation_bonus
-            .slope
-            .checked_mul(Decimal::one() - liquidation_health_factor)?,
-    )?;
-
-    let liquidation_bonus = min(calculated_bonus, max_lb_adjusted);
-
-    Ok(liquidation_bonus)
+    ))
 }
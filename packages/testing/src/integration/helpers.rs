@@ -33,6 +33,7 @@ pub fn default_asset_params_with(
         credit_manager: CmSettings {
             whitelisted: false,
             hls: None,
+            staking: None,
         },
         red_bank: RedBankSettings {
             deposit_enabled: true,
@@ -45,9 +46,11 @@ pub fn default_asset_params_with(
             slope: Decimal::from_str("2.0").unwrap(),
             min_lb: Decimal::percent(2),
             max_lb: Decimal::percent(10),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2),
         deposit_cap: Uint128::MAX,
+        min_debt_value: Uint128::zero(),
     };
     (market_params, asset_params)
 }
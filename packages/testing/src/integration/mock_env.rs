@@ -4,9 +4,10 @@ use std::{collections::HashMap, default::Default, mem::take, str::FromStr};
 
 use anyhow::Result as AnyResult;
 use astroport_v5::incentives::InputSchedule;
-use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, StdResult, Uint128};
+use cosmwasm_std::{coin, to_json_binary, Addr, Binary, Coin, Decimal, Empty, StdResult, Uint128};
 use cw_multi_test::{App, AppResponse, BankSudo, BasicApp, Executor, SudoMsg};
 use cw_paginate::PaginationResponse;
+use mars_mock_pyth::msg::QueryMsg as PythQueryMsg;
 use mars_oracle_osmosis::OsmosisPriceSourceUnchecked;
 use mars_types::{
     address_provider::{self, MarsAddressType},
@@ -19,12 +20,12 @@ use mars_types::{
     },
     params::{AssetParams, AssetParamsUpdate, TotalDepositResponse},
     red_bank::{
-        self, CreateOrUpdateConfig, InitOrUpdateAssetParams, Market, MarketV2Response,
-        UserCollateralResponse, UserDebtResponse, UserPositionResponse,
+        self, CreateOrUpdateConfig, InitOrUpdateAssetParams, LiquidationAuctionResponse, Market,
+        MarketV2Response, UserCollateralResponse, UserDebtResponse, UserPositionResponse,
     },
     rewards_collector,
 };
-use pyth_sdk_cw::PriceIdentifier;
+use pyth_sdk_cw::{Price, PriceFeed, PriceFeedResponse, PriceIdentifier};
 
 use super::mock_contracts::mock_astroport_incentives;
 use crate::integration::mock_contracts::{
@@ -123,6 +124,32 @@ impl MockEnv {
         let res: Vec<Coin> = self.app.wrap().query_all_balances(addr).unwrap();
         res.into_iter().map(|r| (r.denom, r.amount)).collect()
     }
+
+    pub fn query_pyth_price(&self, price_feed_id: &str) -> i64 {
+        let res: PriceFeedResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.pyth,
+                &PythQueryMsg::PriceFeed {
+                    id: PriceIdentifier::from_hex(price_feed_id).unwrap(),
+                },
+            )
+            .unwrap();
+        res.price_feed.get_price_unchecked().price
+    }
+}
+
+/// Price update payload accepted by the mock Pyth contract (a JSON encoded price feed)
+pub fn pyth_price_update(price_feed_id: &str, price: i64, publish_time: i64) -> Binary {
+    let price = Price {
+        price,
+        conf: 1000,
+        expo: -8,
+        publish_time,
+    };
+    let id = PriceIdentifier::from_hex(price_feed_id).unwrap();
+    to_json_binary(&PriceFeed::new(id, price, price)).unwrap()
 }
 
 impl AstroIncentives {
@@ -479,11 +506,36 @@ impl RedBank {
                 denom: denom.to_string(),
                 amount: amount.into(),
                 recipient: None,
+                on_behalf_of: None,
+                price_updates: None,
             },
             &[],
         )
     }
 
+    pub fn borrow_with_price_updates(
+        &self,
+        env: &mut MockEnv,
+        sender: &Addr,
+        denom: &str,
+        amount: u128,
+        price_updates: Vec<Binary>,
+        send_funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        env.app.execute_contract(
+            sender.clone(),
+            self.contract_addr.clone(),
+            &red_bank::ExecuteMsg::Borrow {
+                denom: denom.to_string(),
+                amount: amount.into(),
+                recipient: None,
+                on_behalf_of: None,
+                price_updates: Some(price_updates),
+            },
+            send_funds,
+        )
+    }
+
     pub fn repay(&self, env: &mut MockEnv, sender: &Addr, coin: Coin) -> AnyResult<AppResponse> {
         env.app.execute_contract(
             sender.clone(),
@@ -523,6 +575,7 @@ impl RedBank {
                 recipient: None,
                 account_id,
                 liquidation_related,
+                price_updates: None,
             },
             &[],
         )
@@ -562,11 +615,66 @@ impl RedBank {
                 user: user.to_string(),
                 collateral_denom: collateral_denom.to_string(),
                 recipient,
+                price_updates: None,
             },
             send_funds,
         )
     }
 
+    pub fn liquidate_with_price_updates(
+        &self,
+        env: &mut MockEnv,
+        liquidator: &Addr,
+        user: &Addr,
+        collateral_denom: &str,
+        price_updates: Vec<Binary>,
+        send_funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        env.app.execute_contract(
+            liquidator.clone(),
+            self.contract_addr.clone(),
+            &red_bank::ExecuteMsg::Liquidate {
+                user: user.to_string(),
+                collateral_denom: collateral_denom.to_string(),
+                recipient: None,
+                price_updates: Some(price_updates),
+            },
+            send_funds,
+        )
+    }
+
+    pub fn refresh_liquidation_auction(
+        &self,
+        env: &mut MockEnv,
+        sender: &Addr,
+        user: &Addr,
+    ) -> AnyResult<AppResponse> {
+        env.app.execute_contract(
+            sender.clone(),
+            self.contract_addr.clone(),
+            &red_bank::ExecuteMsg::RefreshLiquidationAuction {
+                user: user.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_liquidation_auction(
+        &self,
+        env: &mut MockEnv,
+        user: &Addr,
+    ) -> LiquidationAuctionResponse {
+        env.app
+            .wrap()
+            .query_wasm_smart(
+                self.contract_addr.clone(),
+                &red_bank::QueryMsg::LiquidationAuction {
+                    user: user.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_market(&self, env: &mut MockEnv, denom: &str) -> Market {
         env.app
             .wrap()
@@ -950,6 +1058,7 @@ impl MockEnvBuilder {
             MarsAddressType::AstroportIncentives,
             &astroport_incentives_addr,
         );
+        self.update_address_provider(&address_provider_addr, MarsAddressType::Pyth, &pyth_addr);
 
         MockEnv {
             app: take(&mut self.app),
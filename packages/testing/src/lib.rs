@@ -20,6 +20,7 @@ mod pyth_querier;
 mod red_bank_querier;
 mod redemption_rate_querier;
 pub mod test_runner;
+pub mod vault_querier;
 #[cfg(feature = "astroport")]
 pub mod wasm_oracle;
 
@@ -1,13 +1,19 @@
+use std::collections::HashMap;
+
 use astroport_v5::asset::Asset;
 use cosmwasm_std::{
     from_json,
     testing::{MockQuerier, MOCK_CONTRACT_ADDR},
-    Addr, Coin, Decimal, Empty, Querier, QuerierResult, QueryRequest, StdResult, SystemError,
-    SystemResult, Uint128, WasmQuery,
+    to_json_binary, Addr, Binary, Coin, ContractResult, Decimal, Empty, Querier, QuerierResult,
+    QueryRequest, StdResult, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use ica_oracle::msg::RedemptionRateResponse;
 use mars_oracle_osmosis::DowntimeDetector;
-use mars_types::{address_provider, incentives, oracle, params::AssetParams, red_bank};
+use mars_types::{
+    address_provider, incentives, oracle,
+    params::{AssetParams, PausableAction, PausableContract},
+    red_bank,
+};
 use osmosis_std::types::osmosis::{
     cosmwasmpool::v1beta1::CalcOutAmtGivenInRequest,
     downtimedetector::v1beta1::RecoveredSinceDowntimeOfLengthResponse,
@@ -15,6 +21,7 @@ use osmosis_std::types::osmosis::{
     twap::v1beta1::{ArithmeticTwapToNowResponse, GeometricTwapToNowResponse},
 };
 use pyth_sdk_cw::{PriceFeedResponse, PriceIdentifier};
+use serde::Serialize;
 
 use crate::{
     astroport_incentives_querier::AstroportIncentivesQuerier,
@@ -27,6 +34,7 @@ use crate::{
     pyth_querier::PythQuerier,
     red_bank_querier::RedBankQuerier,
     redemption_rate_querier::RedemptionRateQuerier,
+    vault_querier::{MockVault, VaultQuerier},
 };
 
 pub struct MarsMockQuerier {
@@ -40,6 +48,11 @@ pub struct MarsMockQuerier {
     redemption_rate_querier: RedemptionRateQuerier,
     params_querier: ParamsQuerier,
     cosmwasm_pool_queries: CosmWasmPoolQuerier,
+    vault_querier: VaultQuerier,
+    /// Raw responses returned by a contract regardless of the query, e.g. exchange rate providers
+    contract_responses: HashMap<Addr, Binary>,
+    /// Raw responses returned by a contract for a specific query
+    contract_query_responses: HashMap<(Addr, Binary), Binary>,
 }
 
 impl Querier for MarsMockQuerier {
@@ -71,6 +84,9 @@ impl MarsMockQuerier {
             redemption_rate_querier: Default::default(),
             params_querier: ParamsQuerier::default(),
             cosmwasm_pool_queries: CosmWasmPoolQuerier::default(),
+            vault_querier: VaultQuerier::default(),
+            contract_responses: HashMap::new(),
+            contract_query_responses: HashMap::new(),
         }
     }
 
@@ -192,6 +208,26 @@ impl MarsMockQuerier {
         self.redemption_rate_querier.redemption_rates.insert(denom.to_string(), redemption_rate);
     }
 
+    pub fn set_vault(&mut self, vault_address: &str, vault: MockVault) {
+        self.vault_querier.vaults.insert(Addr::unchecked(vault_address), vault);
+    }
+
+    pub fn set_contract_response(&mut self, contract_addr: &str, response: Binary) {
+        self.contract_responses.insert(Addr::unchecked(contract_addr), response);
+    }
+
+    pub fn set_contract_query_response(
+        &mut self,
+        contract_addr: &str,
+        query: &impl Serialize,
+        response: &impl Serialize,
+    ) {
+        self.contract_query_responses.insert(
+            (Addr::unchecked(contract_addr), to_json_binary(query).unwrap()),
+            to_json_binary(response).unwrap(),
+        );
+    }
+
     pub fn set_redbank_market(&mut self, market: red_bank::Market) {
         self.redbank_querier.markets.insert(market.denom.clone(), market);
     }
@@ -234,6 +270,10 @@ impl MarsMockQuerier {
         self.params_querier.total_deposits.insert(denom.into(), amount.into());
     }
 
+    pub fn set_paused(&mut self, contract: PausableContract, action: PausableAction) {
+        self.params_querier.paused.insert((contract, action));
+    }
+
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
             QueryRequest::Wasm(WasmQuery::Smart {
@@ -241,6 +281,16 @@ impl MarsMockQuerier {
                 msg,
             }) => {
                 let contract_addr = Addr::unchecked(contract_addr);
+                // Raw contract responses
+                if let Some(response) =
+                    self.contract_query_responses.get(&(contract_addr.clone(), msg.clone()))
+                {
+                    return SystemResult::Ok(ContractResult::Ok(response.clone()));
+                }
+                if let Some(response) = self.contract_responses.get(&contract_addr) {
+                    return SystemResult::Ok(ContractResult::Ok(response.clone()));
+                }
+
                 // Address Provider Queries
                 let parse_address_provider_query: StdResult<address_provider::QueryMsg> =
                     from_json(msg);
@@ -297,6 +347,11 @@ impl MarsMockQuerier {
                     return self.params_querier.handle_query(params_query);
                 }
 
+                // Vault Queries
+                if let Ok(vault_query) = from_json::<mars_types::adapters::vault::QueryMsg>(msg) {
+                    return self.vault_querier.handle_query(&contract_addr, vault_query);
+                }
+
                 // CosmWasm pool Queries
                 if let Ok(cw_pool_query) = from_json::<CalcOutAmtGivenInRequest>(msg) {
                     println!("query: {:?}", cw_pool_query);
@@ -16,6 +16,7 @@ pub fn build_mock_coin_infos(count: usize) -> Vec<CoinInfo> {
                 slope: Decimal::from_atomics(2u128, 0).unwrap(),
                 min_lb: Decimal::percent(2u64),
                 max_lb: Decimal::percent(10u64),
+                dutch_auction: None,
             },
             protocol_liquidation_fee: Decimal::percent(2u64),
             whitelisted: true,
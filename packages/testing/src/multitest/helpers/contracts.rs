@@ -118,3 +118,21 @@ pub fn mock_managed_vault_contract() -> Box<dyn Contract<Empty>> {
     );
     Box::new(contract)
 }
+
+pub fn mock_perps_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mars_perps::contract::execute,
+        mars_perps::contract::instantiate,
+        mars_perps::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn mock_pyth_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mars_mock_pyth::contract::execute,
+        mars_mock_pyth::contract::instantiate,
+        mars_mock_pyth::contract::query,
+    );
+    Box::new(contract)
+}
@@ -1,8 +1,11 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{coin, Decimal};
+use cosmwasm_std::{coin, Decimal, Uint128};
 use cw_utils::Duration;
-use mars_types::params::{HlsAssetType, HlsParamsUnchecked, LiquidationBonus};
+use mars_types::{
+    params::{HlsAssetType, HlsParamsUnchecked, LiquidationBonus},
+    perps::MarketParams,
+};
 
 use super::{CoinInfo, VaultTestInfo};
 
@@ -20,6 +23,7 @@ pub fn coin_info(denom: &str) -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -38,6 +42,7 @@ pub fn uosmo_info() -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -56,6 +61,7 @@ pub fn uatom_info() -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -88,6 +94,7 @@ pub fn ujake_info() -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: true,
@@ -110,6 +117,7 @@ pub fn blacklisted_coin_info() -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(2u64),
         whitelisted: false,
@@ -128,6 +136,7 @@ pub fn lp_token_info() -> CoinInfo {
             slope: Decimal::from_atomics(2u128, 0).unwrap(),
             min_lb: Decimal::percent(2u64),
             max_lb: Decimal::percent(10u64),
+            dutch_auction: None,
         },
         protocol_liquidation_fee: Decimal::percent(40u64),
         whitelisted: true,
@@ -139,6 +148,22 @@ pub fn lp_token_info() -> CoinInfo {
     }
 }
 
+pub fn perp_market_params(denom: &str) -> MarketParams {
+    MarketParams {
+        denom: denom.to_string(),
+        enabled: true,
+        max_long_oi: Uint128::new(1_000_000),
+        max_short_oi: Uint128::new(1_000_000),
+        skew_scale: Uint128::new(1_000),
+        max_funding_rate: Decimal::percent(1),
+        opening_fee_rate: Decimal::permille(1),
+        closing_fee_rate: Decimal::permille(1),
+        liquidation_fee_rate: Decimal::percent(1),
+        max_loan_to_value: Decimal::percent(80),
+        liquidation_threshold: Decimal::percent(85),
+    }
+}
+
 pub fn locked_vault_info() -> VaultTestInfo {
     generate_mock_vault(Some(Duration::Time(1_209_600))) // 14 days)
 }
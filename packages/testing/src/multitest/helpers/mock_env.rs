@@ -2,11 +2,14 @@ use std::{default::Default, str::FromStr};
 
 use anyhow::Result as AnyResult;
 use cosmwasm_std::{
-    coin, coins, testing::MockApi, Addr, Coin, Decimal, Empty, StdResult, Timestamp, Uint128,
+    coin, coins, testing::MockApi, Addr, Binary, Coin, Decimal, Empty, StdResult, Timestamp,
+    Uint128, Validator,
 };
 use cw721::TokensResponse;
 use cw721_base::{Action::TransferOwnership, Ownership};
-use cw_multi_test::{no_init, AppResponse, BankSudo, BasicAppBuilder, Executor, SudoMsg};
+use cw_multi_test::{
+    no_init, AppResponse, BankSudo, BasicAppBuilder, Executor, StakingInfo, StakingSudo, SudoMsg,
+};
 use cw_paginate::PaginationResponse;
 use cw_vault_standard::{
     extensions::lockup::{LockupQueryMsg, UnlockingPosition},
@@ -15,6 +18,7 @@ use cw_vault_standard::{
 use mars_mock_oracle::msg::{
     CoinPrice, ExecuteMsg as OracleExecuteMsg, InstantiateMsg as OracleInstantiateMsg,
 };
+use mars_mock_pyth::msg::QueryMsg as PythQueryMsg;
 use mars_mock_vault::{
     contract::DEFAULT_VAULT_TOKEN_PREFUND, msg::InstantiateMsg as VaultInstantiateMsg,
 };
@@ -30,6 +34,8 @@ use mars_types::{
         incentives::{Incentives, IncentivesUnchecked},
         oracle::{Oracle, OracleBase, OracleUnchecked},
         params::Params,
+        perps::{Perps, PerpsUnchecked},
+        pyth::{Pyth, PythUnchecked},
         red_bank::RedBankUnchecked,
         swapper::{Swapper, SwapperBase},
         vault::{Vault, VaultPosition, VaultPositionValue as VPositionValue, VaultUnchecked},
@@ -40,7 +46,8 @@ use mars_types::{
         Account, Action, CallbackMsg, CoinBalanceResponseItem, ConfigResponse, ConfigUpdates,
         DebtShares, ExecuteMsg, InstantiateMsg, Positions,
         QueryMsg::{self, EstimateProvideLiquidity, VaultPositionValue},
-        SharesResponseItem, VaultBinding, VaultPositionResponseItem, VaultUtilizationResponse,
+        SharesResponseItem, SimulateActionsResponse, VaultBinding, VaultPositionResponseItem,
+        VaultUtilizationResponse,
     },
     health::{
         AccountKind, ExecuteMsg::UpdateConfig, HealthValuesResponse,
@@ -55,9 +62,14 @@ use mars_types::{
     params::{
         AssetParams,
         AssetParamsUpdate::{self, AddOrUpdate},
-        ExecuteMsg::{UpdateAssetParams, UpdateVaultConfig},
-        InstantiateMsg as ParamsInstantiateMsg, QueryMsg as ParamsQueryMsg, VaultConfig,
-        VaultConfigUnchecked, VaultConfigUpdate,
+        AutoPauseConfig, EmergencyUpdate,
+        ExecuteMsg::{self as ParamsExecuteMsg, UpdateAssetParams, UpdateVaultConfig},
+        InstantiateMsg as ParamsInstantiateMsg, PausableAction, PausableContract, PauseUpdate,
+        QueryMsg as ParamsQueryMsg, VaultConfig, VaultConfigUnchecked, VaultConfigUpdate,
+    },
+    perps::{
+        ExecuteMsg as PerpsExecuteMsg, InstantiateMsg as PerpsInstantiateMsg, MarketParams,
+        MarketResponse, PerpPosition, QueryMsg as PerpsQueryMsg, VaultState,
     },
     red_bank::{
         self, InitOrUpdateAssetParams, InterestRateModel,
@@ -73,21 +85,29 @@ use mars_vault::{
     msg::InstantiateMsg as ManagedVaultInstantiateMsg, performance_fee::PerformanceFeeConfig,
 };
 use mars_zapper_mock::msg::{InstantiateMsg as ZapperInstantiateMsg, LpConfig};
+use pyth_sdk_cw::{PriceFeedResponse, PriceIdentifier};
 
 use super::{
     lp_token_info, mock_account_nft_contract, mock_address_provider_contract,
     mock_astro_incentives_contract, mock_health_contract, mock_incentives_contract,
-    mock_managed_vault_contract, mock_oracle_contract, mock_params_contract,
-    mock_red_bank_contract, mock_rover_contract, mock_swapper_contract, mock_v2_zapper_contract,
-    mock_vault_contract, AccountToFund, CoinInfo, VaultTestInfo, ASTRO_LP_DENOM,
+    mock_managed_vault_contract, mock_oracle_contract, mock_params_contract, mock_perps_contract,
+    mock_pyth_contract, mock_red_bank_contract, mock_rover_contract, mock_swapper_contract,
+    mock_v2_zapper_contract, mock_vault_contract, AccountToFund, CoinInfo, VaultTestInfo,
+    ASTRO_LP_DENOM,
+};
+use crate::multitest::modules::{
+    concentrated_liquidity::{ConcentratedLiquidity, MockClPool},
+    stargate::{CustomApp, StargateModules},
 };
-use crate::multitest::modules::token_factory::{CustomApp, TokenFactory};
 
 pub const DEFAULT_RED_BANK_COIN_BALANCE: Uint128 = Uint128::new(1_000_000);
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 14 * 24 * 60 * 60;
+pub const DEFAULT_PERPS_COOLDOWN_PERIOD: u64 = 24 * 60 * 60;
 
 pub struct MockEnv {
     pub app: CustomApp,
     pub rover: Addr,
+    pub address_provider: Addr,
     pub mars_oracle: Addr,
     pub health_contract: HealthContract,
     pub incentives: Incentives,
@@ -110,16 +130,17 @@ pub struct MockEnvBuilder {
     pub accounts_to_fund: Vec<AccountToFund>,
     pub target_health_factor: Option<Decimal>,
     pub max_unlocking_positions: Option<Uint128>,
+    pub max_cl_positions: Option<Uint128>,
     pub max_slippage: Option<Decimal>,
     pub health_contract: Option<HealthContract>,
     pub evil_vault: Option<String>,
+    pub staking: Option<(String, Vec<String>)>,
 }
 
 #[allow(clippy::new_ret_no_self)]
 impl MockEnv {
     pub fn new() -> MockEnvBuilder {
-        let tf_default = TokenFactory::default();
-        let app = BasicAppBuilder::new().with_stargate(tf_default).build(no_init);
+        let app = BasicAppBuilder::new().with_stargate(StargateModules::default()).build(no_init);
 
         MockEnvBuilder {
             app,
@@ -137,9 +158,11 @@ impl MockEnv {
             accounts_to_fund: vec![],
             target_health_factor: None,
             max_unlocking_positions: None,
+            max_cl_positions: None,
             max_slippage: None,
             health_contract: None,
             evil_vault: None,
+            staking: None,
         }
     }
 
@@ -165,6 +188,59 @@ impl MockEnv {
         })
     }
 
+    /// Releases completed unbondings to the delegators, done by the chain at the end of a block
+    pub fn process_unbonding_queue(&mut self) {
+        #[allow(deprecated)]
+        self.app.sudo(SudoMsg::Staking(StakingSudo::ProcessQueue {})).unwrap();
+    }
+
+    pub fn slash_validator(&mut self, validator: &str, percentage: Decimal) {
+        self.app
+            .sudo(SudoMsg::Staking(StakingSudo::Slash {
+                validator: validator.to_string(),
+                percentage,
+            }))
+            .unwrap();
+    }
+
+    /// Creates or updates a pool of the mocked concentrated liquidity module
+    pub fn set_cl_pool(&mut self, pool_id: u64, token0: &str, token1: &str, price: Decimal) {
+        let pool = MockClPool {
+            token0: token0.to_string(),
+            token1: token1.to_string(),
+            price,
+        };
+        self.app
+            .init_modules(|_, _, storage| ConcentratedLiquidity::set_pool(storage, pool_id, &pool))
+            .unwrap();
+    }
+
+    /// Adds claimable rewards to a concentrated liquidity position, minted to the pool paying them
+    pub fn add_cl_position_rewards(
+        &mut self,
+        position_id: u64,
+        spread_rewards: Vec<Coin>,
+        incentives: Vec<Coin>,
+    ) {
+        let pool_addr = self
+            .app
+            .init_modules(|_, _, storage| {
+                ConcentratedLiquidity::add_rewards(
+                    storage,
+                    position_id,
+                    &spread_rewards,
+                    &incentives,
+                )
+            })
+            .unwrap();
+        self.app
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: pool_addr.to_string(),
+                amount: spread_rewards.into_iter().chain(incentives).collect(),
+            }))
+            .unwrap();
+    }
+
     pub fn query_block_time(&self) -> u64 {
         self.app.block_info().time.seconds()
     }
@@ -204,6 +280,28 @@ impl MockEnv {
                 account_id,
                 account_kind,
                 actions,
+                price_updates: None,
+            },
+            send_funds,
+        )
+    }
+
+    pub fn update_credit_account_with_price_updates(
+        &mut self,
+        account_id: &str,
+        sender: &Addr,
+        actions: Vec<Action>,
+        price_updates: Vec<Binary>,
+        send_funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::UpdateCreditAccount {
+                account_id: Some(account_id.to_string()),
+                account_kind: None,
+                actions,
+                price_updates: Some(price_updates),
             },
             send_funds,
         )
@@ -238,6 +336,124 @@ impl MockEnv {
         )
     }
 
+    pub fn update_deleverage_opt_in(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        enabled: bool,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::UpdateDeleverageOptIn {
+                account_id: account_id.to_string(),
+                enabled,
+            },
+            &[],
+        )
+    }
+
+    pub fn deleverage(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        coin_in: Coin,
+        debt_denom: &str,
+        route: Option<SwapperRoute>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::Deleverage {
+                account_id: account_id.to_string(),
+                coin_in,
+                debt_denom: debt_denom.to_string(),
+                route,
+            },
+            &[],
+        )
+    }
+
+    pub fn sweep_dust(
+        &mut self,
+        sender: &Addr,
+        account_id: &str,
+        debt_denom: &str,
+        collateral_denom: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::SweepDust {
+                account_id: account_id.to_string(),
+                debt_denom: debt_denom.to_string(),
+                collateral_denom: collateral_denom.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn send_staking_batch(&mut self, sender: &Addr, validator: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.rover.clone(),
+            &ExecuteMsg::SendStakingBatch {
+                validator: validator.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn emergency_pause(
+        &mut self,
+        sender: &Addr,
+        update: PauseUpdate,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params.address().clone(),
+            &ParamsExecuteMsg::EmergencyUpdate(EmergencyUpdate::Pause(update)),
+            &[],
+        )
+    }
+
+    pub fn unpause(&mut self, sender: &Addr, update: PauseUpdate) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params.address().clone(),
+            &ParamsExecuteMsg::Unpause(update),
+            &[],
+        )
+    }
+
+    pub fn update_auto_pause_config(
+        &mut self,
+        sender: &Addr,
+        denom: &str,
+        config: Option<AutoPauseConfig>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params.address().clone(),
+            &ParamsExecuteMsg::UpdateAutoPauseConfig {
+                denom: denom.to_string(),
+                config,
+            },
+            &[],
+        )
+    }
+
+    pub fn check_price_movement(&mut self, sender: &Addr, denom: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.params.address().clone(),
+            &ParamsExecuteMsg::CheckPriceMovement {
+                denom: denom.to_string(),
+            },
+            &[],
+        )
+    }
+
     pub fn update_config(
         &mut self,
         sender: &Addr,
@@ -306,6 +522,88 @@ impl MockEnv {
         Ok(AccountNftUnchecked::new(nft_contract.to_string()))
     }
 
+    /// Deploys a perps contract settled in `base_denom` and sets it in the Credit Manager config
+    pub fn deploy_perps(&mut self, base_denom: &str) -> Perps {
+        let owner = Addr::unchecked(self.query_config().ownership.owner.unwrap());
+        let code_id = self.app.store_code(mock_perps_contract());
+        let addr = self
+            .app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &PerpsInstantiateMsg {
+                    owner: owner.to_string(),
+                    address_provider: self.address_provider.to_string(),
+                    credit_manager: self.rover.to_string(),
+                    base_denom: base_denom.to_string(),
+                    cooldown_period: DEFAULT_PERPS_COOLDOWN_PERIOD,
+                },
+                &[],
+                "mock-perps",
+                None,
+            )
+            .unwrap();
+
+        self.update_config(
+            &owner,
+            ConfigUpdates {
+                perps: Some(PerpsUnchecked::new(addr.to_string())),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        Perps::new(addr)
+    }
+
+    pub fn deploy_pyth(&mut self) -> Pyth {
+        let owner = Addr::unchecked(self.query_config().ownership.owner.unwrap());
+        let code_id = self.app.store_code(mock_pyth_contract());
+        let addr = self
+            .app
+            .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "mock-pyth", None)
+            .unwrap();
+
+        self.update_config(
+            &owner,
+            ConfigUpdates {
+                pyth: Some(PythUnchecked::new(addr.to_string())),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        Pyth::new(addr)
+    }
+
+    pub fn update_perp_market(
+        &mut self,
+        perps: &Perps,
+        params: MarketParams,
+    ) -> AnyResult<AppResponse> {
+        let owner = Addr::unchecked(self.query_config().ownership.owner.unwrap());
+        self.app.execute_contract(
+            owner,
+            perps.address().clone(),
+            &PerpsExecuteMsg::UpdateMarket(params),
+            &[],
+        )
+    }
+
+    pub fn deposit_to_perp_vault(
+        &mut self,
+        perps: &Perps,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            perps.address().clone(),
+            &PerpsExecuteMsg::Deposit {},
+            funds,
+        )
+    }
+
     pub fn create_credit_account(&mut self, sender: &Addr) -> AnyResult<String> {
         self._create_credit_account(sender, AccountKind::Default)
     }
@@ -638,6 +936,19 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_is_paused(&self, contract: PausableContract, action: PausableAction) -> bool {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.params.address(),
+                &ParamsQueryMsg::IsPaused {
+                    contract,
+                    action,
+                },
+            )
+            .unwrap()
+    }
+
     pub fn query_all_vault_params(&self) -> Vec<VaultConfig> {
         self.app
             .wrap()
@@ -906,6 +1217,78 @@ impl MockEnv {
             .unwrap()
     }
 
+    pub fn query_deleverage_opt_in(&self, account_id: &str) -> bool {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.rover.clone(),
+                &QueryMsg::DeleverageOptIn {
+                    account_id: account_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_perp_position(
+        &self,
+        perps: &Perps,
+        account_id: &str,
+        denom: &str,
+    ) -> Option<PerpPosition> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                perps.address(),
+                &PerpsQueryMsg::Position {
+                    account_id: account_id.to_string(),
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_perp_market(&self, perps: &Perps, denom: &str) -> MarketResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                perps.address(),
+                &PerpsQueryMsg::Market {
+                    denom: denom.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_perp_vault(&self, perps: &Perps) -> VaultState {
+        self.app.wrap().query_wasm_smart(perps.address(), &PerpsQueryMsg::Vault {}).unwrap()
+    }
+
+    pub fn query_pyth_price_feed(&self, pyth: &Pyth, id: PriceIdentifier) -> PriceFeedResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                pyth.address(),
+                &PythQueryMsg::PriceFeed {
+                    id,
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_simulate_actions(
+        &self,
+        account_id: &str,
+        actions: Vec<Action>,
+    ) -> StdResult<SimulateActionsResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.rover.clone(),
+            &QueryMsg::SimulateActions {
+                account_id: account_id.to_string(),
+                actions,
+            },
+        )
+    }
+
     pub fn query_vault_bindings(
         &self,
         start_after: Option<String>,
@@ -925,6 +1308,7 @@ impl MockEnvBuilder {
     pub fn build(mut self) -> AnyResult<MockEnv> {
         let rover = self.get_rover()?;
         self.set_emergency_owner(&rover);
+        let address_provider = self.get_address_provider();
 
         let mars_oracle = self.get_oracle();
         let incentives =
@@ -952,9 +1336,12 @@ impl MockEnvBuilder {
 
         self.deploy_vaults();
 
+        self.setup_staking();
+
         Ok(MockEnv {
             app: self.app,
             rover,
+            address_provider,
             mars_oracle: mars_oracle.address().clone(),
             health_contract,
             incentives,
@@ -966,6 +1353,42 @@ impl MockEnvBuilder {
     // Execute Msgs
     //--------------------------------------------------------------------------------------------------
 
+    fn setup_staking(&mut self) {
+        let Some((bonded_denom, validators)) = self.staking.clone() else {
+            return;
+        };
+        let block = self.app.block_info();
+        self.app.init_modules(|router, api, storage| {
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom,
+                        unbonding_time: DEFAULT_UNBONDING_PERIOD,
+                        apr: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+            for address in validators {
+                router
+                    .staking
+                    .add_validator(
+                        api,
+                        storage,
+                        &block,
+                        Validator {
+                            address,
+                            commission: Decimal::zero(),
+                            max_commission: Decimal::one(),
+                            max_change_rate: Decimal::one(),
+                        },
+                    )
+                    .unwrap();
+            }
+        });
+    }
+
     fn fund_users(&mut self) {
         for account in &self.accounts_to_fund {
             self.app
@@ -1084,6 +1507,7 @@ impl MockEnvBuilder {
         let incentives = self.get_incentives();
         let swapper = self.deploy_swapper().into();
         let max_unlocking_positions = self.get_max_unlocking_positions();
+        let max_cl_positions = self.get_max_cl_positions();
         let max_slippage = self.get_max_slippage();
 
         let oracle = self.get_oracle().into();
@@ -1103,6 +1527,7 @@ impl MockEnvBuilder {
                     red_bank,
                     oracle,
                     max_unlocking_positions,
+                    max_cl_positions,
                     max_slippage,
                     swapper,
                     zapper,
@@ -1155,6 +1580,7 @@ impl MockEnvBuilder {
     fn get_oracle(&mut self) -> Oracle {
         if self.oracle.is_none() {
             let addr = self.deploy_oracle();
+            self.set_address(MarsAddressType::Oracle, addr.address().clone());
             self.oracle = Some(addr);
         }
         self.oracle.clone().unwrap()
@@ -1237,6 +1663,19 @@ impl MockEnvBuilder {
             )
             .unwrap();
 
+        if let Some(eo) = self.emergency_owner.clone() {
+            self.app
+                .execute_contract(
+                    owner,
+                    addr.clone(),
+                    &ParamsExecuteMsg::UpdateOwner(OwnerUpdate::SetEmergencyOwner {
+                        emergency_owner: eo.to_string(),
+                    }),
+                    &[],
+                )
+                .unwrap();
+        }
+
         Params::new(addr)
     }
 
@@ -1506,6 +1945,10 @@ impl MockEnvBuilder {
         self.max_unlocking_positions.unwrap_or_else(|| Uint128::new(100))
     }
 
+    fn get_max_cl_positions(&self) -> Uint128 {
+        self.max_cl_positions.unwrap_or_else(|| Uint128::new(10))
+    }
+
     fn get_max_slippage(&self) -> Decimal {
         self.max_slippage.unwrap_or_else(|| Decimal::percent(99))
     }
@@ -1524,6 +1967,12 @@ impl MockEnvBuilder {
         self
     }
 
+    /// Emergency owner of both Credit Manager and params contract
+    pub fn emergency_owner(mut self, emergency_owner: &str) -> Self {
+        self.emergency_owner = Some(Addr::unchecked(emergency_owner));
+        self
+    }
+
     pub fn vault_configs(mut self, vault_configs: &[VaultTestInfo]) -> Self {
         self.vault_configs = Some(vault_configs.to_vec());
         self
@@ -1579,6 +2028,11 @@ impl MockEnvBuilder {
         self
     }
 
+    pub fn max_cl_positions(mut self, max: u128) -> Self {
+        self.max_cl_positions = Some(Uint128::new(max));
+        self
+    }
+
     pub fn max_slippage(mut self, max: Decimal) -> Self {
         self.max_slippage = Some(max);
         self
@@ -1588,6 +2042,12 @@ impl MockEnvBuilder {
         self.evil_vault = Some(credit_account.to_string());
         self
     }
+
+    pub fn staking(mut self, bonded_denom: &str, validators: &[&str]) -> Self {
+        self.staking =
+            Some((bonded_denom.to_string(), validators.iter().map(|v| v.to_string()).collect()));
+        self
+    }
 }
 
 //--------------------------------------------------------------------------------------------------
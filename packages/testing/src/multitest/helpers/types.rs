@@ -74,6 +74,7 @@ impl From<CoinInfo> for AssetParamsUnchecked {
             credit_manager: CmSettings {
                 whitelisted: c.whitelisted,
                 hls: c.hls,
+                staking: None,
             },
             red_bank: RedBankSettings {
                 deposit_enabled: true,
@@ -84,6 +85,7 @@ impl From<CoinInfo> for AssetParamsUnchecked {
             liquidation_bonus: c.liquidation_bonus,
             protocol_liquidation_fee: c.protocol_liquidation_fee,
             deposit_cap: Uint128::MAX,
+            min_debt_value: Uint128::zero(),
         }
     }
 }
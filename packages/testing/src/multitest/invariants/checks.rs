@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::Uint128;
+use mars_types::credit_manager::{CoinBalanceResponseItem, DebtShares, SharesResponseItem};
+
+use crate::multitest::helpers::MockEnv;
+
+/// Asserts all the protocol invariants that must hold after any step
+pub fn assert_invariants(mock: &MockEnv) {
+    assert_total_debt_shares_match(mock);
+    assert_coin_balances_covered(mock);
+}
+
+/// TOTAL_DEBT_SHARES of every denom must equal the sum of the accounts' DEBT_SHARES
+pub fn assert_total_debt_shares_match(mock: &MockEnv) {
+    let mut summed: HashMap<String, Uint128> = HashMap::new();
+    for item in query_all_debt_shares(mock) {
+        *summed.entry(item.denom).or_default() += item.shares;
+    }
+
+    for total in query_all_total_debt_shares(mock) {
+        let sum = summed.remove(&total.denom).unwrap_or_default();
+        assert_eq!(
+            total.shares, sum,
+            "total debt shares of {} don't match the sum of the accounts' debt shares",
+            total.denom
+        );
+    }
+
+    // Denoms with account debt shares but no total
+    for (denom, sum) in summed {
+        assert!(sum.is_zero(), "{denom} has account debt shares but no total debt shares");
+    }
+}
+
+/// The credit manager's bank balance of every denom must cover the sum of the accounts'
+/// COIN_BALANCES
+pub fn assert_coin_balances_covered(mock: &MockEnv) {
+    let mut summed: HashMap<String, Uint128> = HashMap::new();
+    for item in query_all_coin_balances(mock) {
+        *summed.entry(item.denom).or_default() += item.amount;
+    }
+
+    for (denom, sum) in summed {
+        let balance = mock.query_balance(&mock.rover, &denom).amount;
+        assert!(
+            balance >= sum,
+            "credit manager holds {balance}{denom} but accounts' coin balances add up to {sum}{denom}"
+        );
+    }
+}
+
+fn query_all_coin_balances(mock: &MockEnv) -> Vec<CoinBalanceResponseItem> {
+    let mut all = vec![];
+    loop {
+        let start_after = all
+            .last()
+            .map(|item: &CoinBalanceResponseItem| (item.account_id.clone(), item.denom.clone()));
+        let page = mock.query_all_coin_balances(start_after, None);
+        if page.is_empty() {
+            return all;
+        }
+        all.extend(page);
+    }
+}
+
+fn query_all_debt_shares(mock: &MockEnv) -> Vec<SharesResponseItem> {
+    let mut all = vec![];
+    loop {
+        let start_after = all
+            .last()
+            .map(|item: &SharesResponseItem| (item.account_id.clone(), item.denom.clone()));
+        let page = mock.query_all_debt_shares(start_after, None);
+        if page.is_empty() {
+            return all;
+        }
+        all.extend(page);
+    }
+}
+
+fn query_all_total_debt_shares(mock: &MockEnv) -> Vec<DebtShares> {
+    let mut all = vec![];
+    loop {
+        let start_after = all.last().map(|item: &DebtShares| item.denom.clone());
+        let page = mock.query_all_total_debt_shares(start_after, None);
+        if page.is_empty() {
+            return all;
+        }
+        all.extend(page);
+    }
+}
@@ -0,0 +1,5 @@
+pub use self::{checks::*, runner::*, strategies::*};
+
+mod checks;
+mod runner;
+mod strategies;
@@ -0,0 +1,270 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+};
+
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use mars_mock_oracle::msg::CoinPrice;
+use mars_types::{
+    credit_manager::{Action, DebtAmount},
+    health::AccountKind,
+    oracle::ActionKind,
+};
+use proptest::test_runner::{Config, TestRunner};
+
+use super::{assert_invariants, random_steps, Step, StepsConfig};
+use crate::multitest::helpers::{
+    uatom_info, ujake_info, uosmo_info, AccountToFund, CoinInfo, MockEnv,
+};
+
+/// Amount of every coin the account owners and the liquidator are funded with
+const WALLET_BALANCE: u128 = 1_000_000_000;
+
+#[derive(Clone, Debug)]
+pub struct InvariantsConfig {
+    pub coins: Vec<CoinInfo>,
+    pub accounts: usize,
+    pub max_steps: usize,
+    pub max_actions: usize,
+    pub max_amount: u128,
+}
+
+impl Default for InvariantsConfig {
+    fn default() -> Self {
+        Self {
+            coins: vec![uosmo_info(), uatom_info(), ujake_info()],
+            accounts: 3,
+            max_steps: 20,
+            max_actions: 3,
+            max_amount: 10_000,
+        }
+    }
+}
+
+impl InvariantsConfig {
+    pub fn steps_config(&self) -> StepsConfig {
+        StepsConfig {
+            denoms: self.coins.iter().map(|c| c.denom.clone()).collect(),
+            accounts: self.accounts,
+            max_steps: self.max_steps,
+            max_actions: self.max_actions,
+            max_amount: self.max_amount,
+            min_price_change: 50,
+            max_price_change: 150,
+        }
+    }
+}
+
+/// Steps accepted by the contracts. Rejected steps leave the state unchanged, so these show
+/// whether the sequences reached the states the invariants are meant to cover.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    pub updates: u32,
+    pub liquidations: u32,
+}
+
+impl StepStats {
+    fn add(self, other: StepStats) -> Self {
+        Self {
+            updates: self.updates + other.updates,
+            liquidations: self.liquidations + other.liquidations,
+        }
+    }
+}
+
+/// Runs random sequences of steps across many accounts and asserts the protocol invariants
+/// after each step. Returns the steps accepted across all cases.
+pub fn invariants_prop_test_runner(cases: u32, config: &InvariantsConfig) -> StepStats {
+    let stats = Cell::new(StepStats::default());
+    let mut runner = TestRunner::new(Config::with_cases(cases));
+    runner
+        .run(&random_steps(&config.steps_config()), |steps| {
+            let mut env = InvariantsEnv::new(config);
+            for step in &steps {
+                env.run_step(step);
+            }
+            stats.set(stats.get().add(env.stats));
+            Ok(())
+        })
+        .unwrap();
+    stats.get()
+}
+
+/// Credit manager environment with funded account owners and a liquidator
+pub struct InvariantsEnv {
+    pub mock: MockEnv,
+    /// Owner and credit account id of every account
+    pub accounts: Vec<(Addr, String)>,
+    pub liquidator: Addr,
+    pub liquidator_account_id: String,
+    /// Steps accepted by the contracts so far
+    pub stats: StepStats,
+    prices: HashMap<String, Decimal>,
+}
+
+impl InvariantsEnv {
+    pub fn new(config: &InvariantsConfig) -> Self {
+        let wallet_funds =
+            config.coins.iter().map(|c| c.to_coin(WALLET_BALANCE)).collect::<Vec<_>>();
+        let owners =
+            (0..config.accounts).map(|i| Addr::unchecked(format!("user_{i}"))).collect::<Vec<_>>();
+        let liquidator = Addr::unchecked("liquidator");
+
+        let mut builder = MockEnv::new().set_params(&config.coins);
+        for addr in owners.iter().chain([&liquidator]) {
+            builder = builder.fund_account(AccountToFund {
+                addr: addr.clone(),
+                funds: wallet_funds.clone(),
+            });
+        }
+        let mut mock = builder.build().unwrap();
+
+        // The mock oracle has no liquidation pricing by default
+        for coin in &config.coins {
+            mock.price_change(CoinPrice {
+                pricing: ActionKind::Liquidation,
+                denom: coin.denom.clone(),
+                price: coin.price,
+            });
+        }
+
+        let accounts = owners
+            .into_iter()
+            .map(|owner| {
+                let account_id = mock.create_credit_account(&owner).unwrap();
+                (owner, account_id)
+            })
+            .collect();
+        let liquidator_account_id = mock.create_credit_account(&liquidator).unwrap();
+
+        Self {
+            mock,
+            accounts,
+            liquidator,
+            liquidator_account_id,
+            stats: StepStats::default(),
+            prices: config.coins.iter().map(|c| (c.denom.clone(), c.price)).collect(),
+        }
+    }
+
+    /// Runs the step and asserts the invariants afterwards. Steps rejected by the contracts are
+    /// expected (e.g. borrowing above max LTV) and leave the state unchanged.
+    pub fn run_step(&mut self, step: &Step) {
+        let liquidatable_before = self.query_liquidatable();
+
+        match step {
+            Step::UpdateCreditAccount {
+                account,
+                actions,
+            } => {
+                let (owner, account_id) = self.accounts[*account].clone();
+                let funds = deposited_funds(actions);
+                let res =
+                    self.mock.update_credit_account(&account_id, &owner, actions.clone(), &funds);
+                if res.is_ok() {
+                    self.stats.updates += 1;
+                }
+            }
+            Step::PriceChange {
+                denom,
+                percent,
+            } => {
+                let price = self.prices.get_mut(denom).unwrap();
+                *price *= Decimal::percent(*percent);
+                for pricing in [ActionKind::Default, ActionKind::Liquidation] {
+                    self.mock.price_change(CoinPrice {
+                        pricing,
+                        denom: denom.clone(),
+                        price: *price,
+                    });
+                }
+            }
+            Step::Liquidate {
+                account,
+                debt_coin,
+                request,
+            } => {
+                let account_id = self.accounts[*account].1.clone();
+                let debts_before = self.mock.query_positions(&account_id).debts;
+
+                let res = self.mock.update_credit_account(
+                    &self.liquidator_account_id,
+                    &self.liquidator,
+                    vec![
+                        Action::Deposit(debt_coin.clone()),
+                        Action::Liquidate {
+                            liquidatee_account_id: account_id.clone(),
+                            debt_coin: debt_coin.clone(),
+                            request: request.clone(),
+                        },
+                    ],
+                    &[debt_coin.clone()],
+                );
+                if res.is_ok() {
+                    self.stats.liquidations += 1;
+                }
+
+                let debts_after = self.mock.query_positions(&account_id).debts;
+                assert_debts_not_increased(&account_id, &debts_before, &debts_after);
+            }
+        }
+
+        assert_invariants(&self.mock);
+
+        // Only price changes can make a healthy account liquidatable
+        if !matches!(step, Step::PriceChange { .. }) {
+            let liquidatable_after = self.query_liquidatable();
+            for (i, (before, after)) in
+                liquidatable_before.iter().zip(liquidatable_after).enumerate()
+            {
+                assert!(
+                    *before || !after,
+                    "account {} became liquidatable without a price change",
+                    self.accounts[i].1
+                );
+            }
+        }
+    }
+
+    /// Whether each account is liquidatable, in the order of `accounts`
+    pub fn query_liquidatable(&self) -> Vec<bool> {
+        self.accounts
+            .iter()
+            .map(|(_, account_id)| {
+                self.mock
+                    .query_health(account_id, AccountKind::Default, ActionKind::Liquidation)
+                    .liquidatable
+            })
+            .collect()
+    }
+}
+
+/// Coins to send along with the actions, i.e. the sum of their deposits
+fn deposited_funds(actions: &[Action]) -> Vec<Coin> {
+    let mut funds: BTreeMap<String, Uint128> = BTreeMap::new();
+    for action in actions {
+        if let Action::Deposit(coin) = action {
+            *funds.entry(coin.denom.clone()).or_default() += coin.amount;
+        }
+    }
+    funds
+        .into_iter()
+        .map(|(denom, amount)| Coin {
+            denom,
+            amount,
+        })
+        .collect()
+}
+
+fn assert_debts_not_increased(account_id: &str, before: &[DebtAmount], after: &[DebtAmount]) {
+    for debt in after {
+        let amount_before =
+            before.iter().find(|d| d.denom == debt.denom).map(|d| d.amount).unwrap_or_default();
+        assert!(
+            debt.amount <= amount_before,
+            "liquidation increased {} debt of account {account_id} from {amount_before} to {}",
+            debt.denom,
+            debt.amount
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use cosmwasm_std::{Coin, Uint128};
+use mars_types::{
+    adapters::vault::VaultUnchecked,
+    credit_manager::{Action, ActionAmount, ActionCoin, LiquidateRequest},
+};
+use proptest::{collection::vec, prelude::*, sample::select};
+
+/// A single step of a randomly generated sequence run against the credit manager
+#[derive(Clone, Debug)]
+pub enum Step {
+    /// The owner of the account (index into the harness accounts) updates it with the actions.
+    /// Coins deposited are sent from the owner's wallet.
+    UpdateCreditAccount {
+        account: usize,
+        actions: Vec<Action>,
+    },
+    /// The price of the denom (both default and liquidation pricing) is multiplied by the
+    /// percentage, e.g. 80 for a 20% drop
+    PriceChange {
+        denom: String,
+        percent: u64,
+    },
+    /// The liquidator deposits the debt coin and liquidates the account with it
+    Liquidate {
+        account: usize,
+        debt_coin: Coin,
+        request: LiquidateRequest<VaultUnchecked>,
+    },
+}
+
+/// Settings of the generated step sequences
+#[derive(Clone, Debug)]
+pub struct StepsConfig {
+    pub denoms: Vec<String>,
+    pub accounts: usize,
+    pub max_steps: usize,
+    pub max_actions: usize,
+    pub max_amount: u128,
+    /// Bounds (in percent) of a single price change
+    pub min_price_change: u64,
+    pub max_price_change: u64,
+}
+
+pub fn random_steps(config: &StepsConfig) -> impl Strategy<Value = Vec<Step>> {
+    vec(random_step(config), 1..=config.max_steps)
+}
+
+pub fn random_step(config: &StepsConfig) -> impl Strategy<Value = Step> {
+    let denoms = config.denoms.clone();
+
+    prop_oneof![
+        6 => (0..config.accounts, vec(random_action(config), 1..=config.max_actions)).prop_map(
+            |(account, actions)| Step::UpdateCreditAccount {
+                account,
+                actions,
+            }
+        ),
+        2 => (select(denoms.clone()), config.min_price_change..=config.max_price_change).prop_map(
+            |(denom, percent)| Step::PriceChange {
+                denom,
+                percent,
+            }
+        ),
+        2 => (
+            0..config.accounts,
+            random_coin(&denoms, config.max_amount),
+            select(denoms),
+            any::<bool>()
+        )
+            .prop_map(|(account, debt_coin, collateral_denom, from_lends)| {
+                let request = if from_lends {
+                    LiquidateRequest::Lend(collateral_denom)
+                } else {
+                    LiquidateRequest::Deposit(collateral_denom)
+                };
+                Step::Liquidate {
+                    account,
+                    debt_coin,
+                    request,
+                }
+            }),
+    ]
+}
+
+pub fn random_action(config: &StepsConfig) -> impl Strategy<Value = Action> {
+    let denoms = &config.denoms;
+    let max_amount = config.max_amount;
+
+    prop_oneof![
+        random_coin(denoms, max_amount).prop_map(Action::Deposit),
+        random_action_coin(denoms, max_amount).prop_map(Action::Withdraw),
+        random_coin(denoms, max_amount).prop_map(Action::Borrow),
+        random_action_coin(denoms, max_amount).prop_map(|coin| Action::Repay {
+            recipient_account_id: None,
+            coin,
+        }),
+        random_action_coin(denoms, max_amount).prop_map(Action::Lend),
+        random_action_coin(denoms, max_amount).prop_map(Action::Reclaim),
+    ]
+}
+
+fn random_coin(denoms: &[String], max_amount: u128) -> impl Strategy<Value = Coin> {
+    (select(denoms.to_vec()), 1..=max_amount).prop_map(|(denom, amount)| Coin {
+        denom,
+        amount: Uint128::new(amount),
+    })
+}
+
+fn random_action_coin(denoms: &[String], max_amount: u128) -> impl Strategy<Value = ActionCoin> {
+    (select(denoms.to_vec()), prop::option::weighted(0.8, 1..=max_amount)).prop_map(
+        |(denom, amount)| ActionCoin {
+            denom,
+            amount: match amount {
+                Some(amount) => ActionAmount::Exact(Uint128::new(amount)),
+                None => ActionAmount::AccountBalance,
+            },
+        },
+    )
+}
@@ -0,0 +1,620 @@
+use std::{fmt::Debug, str::FromStr};
+
+use anyhow::{bail, Result as AnyResult};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, Api, BankMsg, Binary, BlockInfo, Coin,
+    CustomQuery, Decimal, Decimal256, Event, Querier, StdError, StdResult, Storage, Uint128,
+};
+use cw_multi_test::{AppResponse, CosmosRouter, Stargate};
+use mars_types::credit_manager::{tick_to_price, ClPosition};
+use osmosis_std::types::{
+    cosmos::base::v1beta1::Coin as OsmoCoin,
+    osmosis::{
+        concentratedliquidity::v1beta1::{
+            FullPositionBreakdown, MsgAddToPosition, MsgAddToPositionResponse,
+            MsgCollectIncentives, MsgCollectIncentivesResponse, MsgCollectSpreadRewards,
+            MsgCollectSpreadRewardsResponse, MsgCreatePosition, MsgCreatePositionResponse,
+            MsgWithdrawPosition, MsgWithdrawPositionResponse, Pool as ConcentratedLiquidityPool,
+            Position, PositionByIdRequest, PositionByIdResponse,
+        },
+        poolmanager::v1beta1::{PoolRequest, PoolResponse},
+    },
+};
+use prost::Message;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+const POOLS_NAMESPACE: &str = "cl_module_pools";
+const POSITIONS_NAMESPACE: &str = "cl_module_positions";
+const NEXT_POSITION_ID_KEY: &[u8] = b"cl_module_next_position_id";
+
+/// Concentrated liquidity pool trading at a fixed price
+#[cw_serde]
+pub struct MockClPool {
+    pub token0: String,
+    pub token1: String,
+    /// Price of token0 denominated in token1
+    pub price: Decimal,
+}
+
+#[cw_serde]
+pub struct MockClPosition {
+    pub owner: String,
+    pub pool_id: u64,
+    pub lower_tick: i64,
+    pub upper_tick: i64,
+    pub liquidity: Decimal256,
+    pub spread_rewards: Vec<Coin>,
+    pub incentives: Vec<Coin>,
+}
+
+/// Mocked Osmosis concentrated liquidity module.
+///
+/// Pools never trade, the underlying tokens of a position follow the CL math at the pool price
+/// (the same math the health computer uses with oracle prices). Tokens are held by the pool address
+/// and rewards are paid from its balance, so they have to be minted to it when added.
+#[derive(Clone, Default)]
+pub struct ConcentratedLiquidity;
+
+impl Stargate for ConcentratedLiquidity {
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        msg: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match type_url.as_str() {
+            MsgCreatePosition::TYPE_URL => {
+                self.create_position(api, storage, router, block, sender, msg)
+            }
+            MsgAddToPosition::TYPE_URL => {
+                self.add_to_position(api, storage, router, block, sender, msg)
+            }
+            MsgWithdrawPosition::TYPE_URL => {
+                self.withdraw_position(api, storage, router, block, sender, msg)
+            }
+            MsgCollectSpreadRewards::TYPE_URL => {
+                let msg: MsgCollectSpreadRewards = msg.try_into()?;
+                let collected = self.collect_rewards(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    &sender,
+                    &msg.position_ids,
+                    |position| &mut position.spread_rewards,
+                )?;
+                let mut res = AppResponse::default();
+                res.data = Some(
+                    MsgCollectSpreadRewardsResponse {
+                        collected_spread_rewards: collected,
+                    }
+                    .into(),
+                );
+                Ok(res)
+            }
+            MsgCollectIncentives::TYPE_URL => {
+                let msg: MsgCollectIncentives = msg.try_into()?;
+                let collected = self.collect_rewards(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    &sender,
+                    &msg.position_ids,
+                    |position| &mut position.incentives,
+                )?;
+                let mut res = AppResponse::default();
+                res.data = Some(
+                    MsgCollectIncentivesResponse {
+                        collected_incentives: collected,
+                        ..Default::default()
+                    }
+                    .into(),
+                );
+                Ok(res)
+            }
+            _ => bail!("Unknown message type {}", type_url),
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        match path.as_str() {
+            "/osmosis.poolmanager.v1beta1.Query/Pool" => {
+                let request = PoolRequest::decode(data.as_slice())?;
+                let pool = load_pool(storage, request.pool_id)?;
+                let cl_pool = ConcentratedLiquidityPool {
+                    address: pool_address(request.pool_id).to_string(),
+                    incentives_address: format!("clincentives{}", request.pool_id),
+                    spread_rewards_address: format!("clspreadrewards{}", request.pool_id),
+                    id: request.pool_id,
+                    token0: pool.token0,
+                    token1: pool.token1,
+                    current_sqrt_price: Decimal256::from(pool.price).sqrt().to_string(),
+                    tick_spacing: 100,
+                    exponent_at_price_one: -6,
+                    spread_factor: Decimal::zero().to_string(),
+                    ..Default::default()
+                };
+                Ok(to_json_binary(&PoolResponse {
+                    pool: Some(cl_pool.to_any()),
+                })?)
+            }
+            "/osmosis.concentratedliquidity.v1beta1.Query/PositionById" => {
+                let request = PositionByIdRequest::decode(data.as_slice())?;
+                let position = load_position(storage, request.position_id)?;
+                let pool = load_pool(storage, position.pool_id)?;
+                let (asset0, asset1) = position.underlying(request.position_id, &pool)?;
+                Ok(to_json_binary(&PositionByIdResponse {
+                    position: Some(FullPositionBreakdown {
+                        position: Some(Position {
+                            position_id: request.position_id,
+                            address: position.owner,
+                            pool_id: position.pool_id,
+                            lower_tick: position.lower_tick,
+                            upper_tick: position.upper_tick,
+                            liquidity: position.liquidity.to_string(),
+                            ..Default::default()
+                        }),
+                        asset0: Some(to_osmo_coin(&asset0)),
+                        asset1: Some(to_osmo_coin(&asset1)),
+                        claimable_spread_rewards: position
+                            .spread_rewards
+                            .iter()
+                            .map(to_osmo_coin)
+                            .collect(),
+                        claimable_incentives: position
+                            .incentives
+                            .iter()
+                            .map(to_osmo_coin)
+                            .collect(),
+                        ..Default::default()
+                    }),
+                })?)
+            }
+            _ => bail!("Unknown query path {}", path),
+        }
+    }
+}
+
+impl ConcentratedLiquidity {
+    pub fn set_pool(storage: &mut dyn Storage, pool_id: u64, pool: &MockClPool) -> StdResult<()> {
+        storage.set(&namespaced_key(POOLS_NAMESPACE, pool_id), &to_json_vec(pool)?);
+        Ok(())
+    }
+
+    /// Adds claimable rewards to the position and returns the pool address paying them out
+    pub fn add_rewards(
+        storage: &mut dyn Storage,
+        position_id: u64,
+        spread_rewards: &[Coin],
+        incentives: &[Coin],
+    ) -> StdResult<Addr> {
+        let mut position = load_position(storage, position_id)?;
+        add_coins(&mut position.spread_rewards, spread_rewards);
+        add_coins(&mut position.incentives, incentives);
+        save_position(storage, position_id, &position)?;
+        Ok(pool_address(position.pool_id))
+    }
+
+    pub fn query_position(storage: &dyn Storage, position_id: u64) -> StdResult<MockClPosition> {
+        load_position(storage, position_id)
+    }
+
+    fn create_position<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let msg: MsgCreatePosition = msg.try_into()?;
+        if sender != msg.sender {
+            bail!("Invalid sender. Sender in msg must be same as sender of transaction.");
+        }
+        if msg.lower_tick >= msg.upper_tick {
+            bail!("Invalid tick range [{}, {})", msg.lower_tick, msg.upper_tick);
+        }
+
+        let pool = load_pool(storage, msg.pool_id)?;
+        let mut amount0 = Uint128::zero();
+        let mut amount1 = Uint128::zero();
+        for token in msg.tokens_provided.iter() {
+            let amount = Uint128::from_str(&token.amount)?;
+            if token.denom == pool.token0 {
+                amount0 += amount;
+            } else if token.denom == pool.token1 {
+                amount1 += amount;
+            } else {
+                bail!("Denom {} is not a token of pool {}", token.denom, msg.pool_id);
+            }
+        }
+
+        let liquidity =
+            pool.liquidity_for_amounts(msg.lower_tick, msg.upper_tick, amount0, amount1)?;
+        if liquidity.is_zero() {
+            bail!("Zero liquidity created");
+        }
+        let position = MockClPosition {
+            owner: sender.to_string(),
+            pool_id: msg.pool_id,
+            lower_tick: msg.lower_tick,
+            upper_tick: msg.upper_tick,
+            liquidity,
+            spread_rewards: vec![],
+            incentives: vec![],
+        };
+
+        let position_id = next_position_id(storage)?;
+        let (used0, used1) = position.underlying(position_id, &pool)?;
+        assert_min_amounts(&used0, &used1, &msg.token_min_amount0, &msg.token_min_amount1)?;
+
+        transfer(api, storage, router, block, sender, pool_address(msg.pool_id), [&used0, &used1])?;
+        save_position(storage, position_id, &position)?;
+
+        let mut res = AppResponse::default();
+        res.events.push(
+            Event::new("create_position")
+                .add_attribute("position_id", position_id.to_string())
+                .add_attribute("liquidity", liquidity.to_string()),
+        );
+        res.data = Some(
+            MsgCreatePositionResponse {
+                position_id,
+                amount0: used0.amount.to_string(),
+                amount1: used1.amount.to_string(),
+                liquidity_created: liquidity.to_string(),
+                ..Default::default()
+            }
+            .into(),
+        );
+        Ok(res)
+    }
+
+    /// Like Osmosis, the position is withdrawn (paying out its rewards) and replaced by a new
+    /// position with the withdrawn and added tokens. Amounts in the response are the tokens added.
+    fn add_to_position<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let msg: MsgAddToPosition = msg.try_into()?;
+        if sender != msg.sender {
+            bail!("Invalid sender. Sender in msg must be same as sender of transaction.");
+        }
+
+        let position = load_owned_position(storage, msg.position_id, &sender)?;
+        let pool = load_pool(storage, position.pool_id)?;
+        let (withdrawn0, withdrawn1) = position.underlying(msg.position_id, &pool)?;
+
+        let rewards = position.spread_rewards.iter().chain(position.incentives.iter());
+        transfer(
+            api,
+            storage,
+            router,
+            block,
+            pool_address(position.pool_id),
+            sender.clone(),
+            rewards,
+        )?;
+
+        let liquidity = pool.liquidity_for_amounts(
+            position.lower_tick,
+            position.upper_tick,
+            withdrawn0.amount + Uint128::from_str(&msg.amount0)?,
+            withdrawn1.amount + Uint128::from_str(&msg.amount1)?,
+        )?;
+        let new_position = MockClPosition {
+            liquidity,
+            spread_rewards: vec![],
+            incentives: vec![],
+            ..position
+        };
+
+        let position_id = next_position_id(storage)?;
+        let (used0, used1) = new_position.underlying(position_id, &pool)?;
+        let added0 = Coin::new(used0.amount.saturating_sub(withdrawn0.amount).u128(), used0.denom);
+        let added1 = Coin::new(used1.amount.saturating_sub(withdrawn1.amount).u128(), used1.denom);
+        assert_min_amounts(&added0, &added1, &msg.token_min_amount0, &msg.token_min_amount1)?;
+
+        transfer(
+            api,
+            storage,
+            router,
+            block,
+            sender,
+            pool_address(new_position.pool_id),
+            [&added0, &added1],
+        )?;
+        storage.remove(&namespaced_key(POSITIONS_NAMESPACE, msg.position_id));
+        save_position(storage, position_id, &new_position)?;
+
+        let mut res = AppResponse::default();
+        res.data = Some(
+            MsgAddToPositionResponse {
+                position_id,
+                amount0: added0.amount.to_string(),
+                amount1: added1.amount.to_string(),
+            }
+            .into(),
+        );
+        Ok(res)
+    }
+
+    /// Withdrawing all liquidity closes the position and pays out its rewards
+    fn withdraw_position<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let msg: MsgWithdrawPosition = msg.try_into()?;
+        if sender != msg.sender {
+            bail!("Invalid sender. Sender in msg must be same as sender of transaction.");
+        }
+
+        let mut position = load_owned_position(storage, msg.position_id, &sender)?;
+        let pool = load_pool(storage, position.pool_id)?;
+        let liquidity = Decimal256::from_str(&msg.liquidity_amount)?;
+        if liquidity.is_zero() || liquidity > position.liquidity {
+            bail!(
+                "Invalid liquidity {} to withdraw from position {} with liquidity {}",
+                liquidity,
+                msg.position_id,
+                position.liquidity
+            );
+        }
+
+        let withdrawn = MockClPosition {
+            liquidity,
+            ..position.clone()
+        };
+        let (amount0, amount1) = withdrawn.underlying(msg.position_id, &pool)?;
+        let pool_addr = pool_address(position.pool_id);
+
+        if liquidity == position.liquidity {
+            let rewards = position.spread_rewards.iter().chain(position.incentives.iter());
+            transfer(api, storage, router, block, pool_addr.clone(), sender.clone(), rewards)?;
+            storage.remove(&namespaced_key(POSITIONS_NAMESPACE, msg.position_id));
+        } else {
+            position.liquidity -= liquidity;
+            save_position(storage, msg.position_id, &position)?;
+        }
+        transfer(api, storage, router, block, pool_addr, sender, [&amount0, &amount1])?;
+
+        let mut res = AppResponse::default();
+        res.data = Some(
+            MsgWithdrawPositionResponse {
+                amount0: amount0.amount.to_string(),
+                amount1: amount1.amount.to_string(),
+            }
+            .into(),
+        );
+        Ok(res)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_rewards<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: &Addr,
+        position_ids: &[u64],
+        rewards: impl Fn(&mut MockClPosition) -> &mut Vec<Coin>,
+    ) -> AnyResult<Vec<OsmoCoin>>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let mut collected = vec![];
+        for position_id in position_ids {
+            let mut position = load_owned_position(storage, *position_id, sender)?;
+            let coins = std::mem::take(rewards(&mut position));
+            save_position(storage, *position_id, &position)?;
+
+            let pool_addr = pool_address(position.pool_id);
+            transfer(api, storage, router, block, pool_addr, sender.clone(), coins.iter())?;
+            collected.extend(coins.iter().map(to_osmo_coin));
+        }
+        Ok(collected)
+    }
+}
+
+impl MockClPool {
+    /// Largest liquidity the amounts can provide in the tick range at the pool price
+    fn liquidity_for_amounts(
+        &self,
+        lower_tick: i64,
+        upper_tick: i64,
+        amount0: Uint128,
+        amount1: Uint128,
+    ) -> AnyResult<Decimal256> {
+        let sqrt_lower = tick_to_price(lower_tick)?.sqrt();
+        let sqrt_upper = tick_to_price(upper_tick)?.sqrt();
+        let sqrt_price = Decimal256::from(self.price).sqrt().max(sqrt_lower).min(sqrt_upper);
+
+        let mut liquidity: Option<Decimal256> = None;
+        if sqrt_price < sqrt_upper {
+            // L = amount0 * sqrt_price * sqrt_upper / (sqrt_upper - sqrt_price)
+            let liquidity0 = Decimal256::from_ratio(amount0, 1u128)
+                .checked_mul(sqrt_price)?
+                .checked_mul(sqrt_upper)?
+                .checked_div(sqrt_upper - sqrt_price)?;
+            liquidity = Some(liquidity0);
+        }
+        if sqrt_price > sqrt_lower {
+            // L = amount1 / (sqrt_price - sqrt_lower)
+            let liquidity1 =
+                Decimal256::from_ratio(amount1, 1u128).checked_div(sqrt_price - sqrt_lower)?;
+            liquidity = Some(liquidity.map_or(liquidity1, |l| l.min(liquidity1)));
+        }
+        Ok(liquidity.unwrap_or_default())
+    }
+}
+
+impl MockClPosition {
+    /// Underlying tokens at the pool price
+    fn underlying(&self, position_id: u64, pool: &MockClPool) -> StdResult<(Coin, Coin)> {
+        ClPosition {
+            position_id,
+            pool_id: self.pool_id,
+            lower_tick: self.lower_tick,
+            upper_tick: self.upper_tick,
+            liquidity: self.liquidity,
+            asset0: Coin::new(0, &pool.token0),
+            asset1: Coin::new(0, &pool.token1),
+        }
+        .underlying_at_prices(pool.price, Decimal::one())
+    }
+}
+
+pub fn pool_address(pool_id: u64) -> Addr {
+    Addr::unchecked(format!("clpool{pool_id}"))
+}
+
+fn namespaced_key(namespace: &str, id: u64) -> Vec<u8> {
+    let mut key = namespace.as_bytes().to_vec();
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn load_pool(storage: &dyn Storage, pool_id: u64) -> StdResult<MockClPool> {
+    storage
+        .get(&namespaced_key(POOLS_NAMESPACE, pool_id))
+        .map(from_json)
+        .unwrap_or_else(|| Err(StdError::not_found(format!("pool {pool_id}"))))
+}
+
+fn load_position(storage: &dyn Storage, position_id: u64) -> StdResult<MockClPosition> {
+    storage
+        .get(&namespaced_key(POSITIONS_NAMESPACE, position_id))
+        .map(from_json)
+        .unwrap_or_else(|| Err(StdError::not_found(format!("position {position_id}"))))
+}
+
+fn load_owned_position(
+    storage: &dyn Storage,
+    position_id: u64,
+    owner: &Addr,
+) -> AnyResult<MockClPosition> {
+    let position = load_position(storage, position_id)?;
+    if position.owner != owner.as_str() {
+        bail!("Position {} is not owned by {}", position_id, owner);
+    }
+    Ok(position)
+}
+
+fn save_position(
+    storage: &mut dyn Storage,
+    position_id: u64,
+    position: &MockClPosition,
+) -> StdResult<()> {
+    storage.set(&namespaced_key(POSITIONS_NAMESPACE, position_id), &to_json_vec(position)?);
+    Ok(())
+}
+
+fn next_position_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = match storage.get(NEXT_POSITION_ID_KEY) {
+        Some(data) => from_json(data)?,
+        None => 1u64,
+    };
+    storage.set(NEXT_POSITION_ID_KEY, &to_json_vec(&(id + 1))?);
+    Ok(id)
+}
+
+fn assert_min_amounts(
+    amount0: &Coin,
+    amount1: &Coin,
+    min_amount0: &str,
+    min_amount1: &str,
+) -> AnyResult<()> {
+    for (coin, min_amount) in [(amount0, min_amount0), (amount1, min_amount1)] {
+        let min_amount = Uint128::from_str(min_amount)?;
+        if coin.amount < min_amount {
+            bail!("Insufficient {}: got {}, min {}", coin.denom, coin.amount, min_amount);
+        }
+    }
+    Ok(())
+}
+
+fn transfer<'a, ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    from: Addr,
+    to: Addr,
+    coins: impl IntoIterator<Item = &'a Coin>,
+) -> AnyResult<()>
+where
+    ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+{
+    let amount =
+        coins.into_iter().filter(|coin| !coin.amount.is_zero()).cloned().collect::<Vec<_>>();
+    if amount.is_empty() {
+        return Ok(());
+    }
+    let send_msg = BankMsg::Send {
+        to_address: to.to_string(),
+        amount,
+    };
+    router.execute(api, storage, block, from, send_msg.into())?;
+    Ok(())
+}
+
+fn add_coins(coins: &mut Vec<Coin>, to_add: &[Coin]) {
+    for coin in to_add {
+        match coins.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount += coin.amount,
+            None => coins.push(coin.clone()),
+        }
+    }
+}
+
+fn to_osmo_coin(coin: &Coin) -> OsmoCoin {
+    OsmoCoin {
+        denom: coin.denom.clone(),
+        amount: coin.amount.to_string(),
+    }
+}
@@ -1 +1,3 @@
+pub mod concentrated_liquidity;
+pub mod stargate;
 pub mod token_factory;
@@ -0,0 +1,71 @@
+use std::fmt::Debug;
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{
+    testing::MockApi, Addr, Api, Binary, BlockInfo, CustomQuery, Empty, GovMsg, IbcMsg, IbcQuery,
+    MemoryStorage, Querier, Storage,
+};
+use cw_multi_test::{
+    App, AppResponse, BankKeeper, CosmosRouter, DistributionKeeper, FailingModule, StakeKeeper,
+    Stargate, WasmKeeper,
+};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use super::{concentrated_liquidity::ConcentratedLiquidity, token_factory::TokenFactory};
+
+pub type CustomApp = App<
+    BankKeeper,
+    MockApi,
+    MemoryStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    FailingModule<IbcMsg, IbcQuery, Empty>,
+    FailingModule<GovMsg, Empty, Empty>,
+    StargateModules,
+>;
+
+/// Routes Stargate messages and queries to the mocked chain modules
+#[derive(Clone, Default)]
+pub struct StargateModules {
+    pub token_factory: TokenFactory,
+    pub concentrated_liquidity: ConcentratedLiquidity,
+}
+
+impl Stargate for StargateModules {
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        msg: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        if type_url.starts_with("/osmosis.concentratedliquidity.") {
+            self.concentrated_liquidity.execute(api, storage, router, block, sender, type_url, msg)
+        } else {
+            self.token_factory.execute(api, storage, router, block, sender, type_url, msg)
+        }
+    }
+
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        // Token factory has no queries
+        self.concentrated_liquidity.query(api, storage, querier, block, path, data)
+    }
+}
@@ -5,14 +5,10 @@ use std::{fmt::Debug, str::FromStr};
 
 use anyhow::{bail, Result as AnyResult};
 use cosmwasm_std::{
-    from_json, testing::MockApi, Addr, Api, BankMsg, BankQuery, Binary, BlockInfo, Coin,
-    CustomQuery, Empty, Event, GovMsg, IbcMsg, IbcQuery, MemoryStorage, Querier, QueryRequest,
-    Storage, SupplyResponse, Uint128,
-};
-use cw_multi_test::{
-    App, AppResponse, BankKeeper, BankSudo, CosmosRouter, DistributionKeeper, FailingModule,
-    StakeKeeper, Stargate, WasmKeeper,
+    from_json, Addr, Api, BankMsg, BankQuery, Binary, BlockInfo, Coin, CustomQuery, Event, Querier,
+    QueryRequest, Storage, SupplyResponse, Uint128,
 };
+use cw_multi_test::{AppResponse, BankSudo, CosmosRouter, Stargate};
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
     MsgBurn, MsgBurnResponse, MsgCreateDenom, MsgCreateDenomResponse, MsgMint, MsgMintResponse,
 };
@@ -20,19 +16,6 @@ use regex::Regex;
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 
-pub type CustomApp = App<
-    BankKeeper,
-    MockApi,
-    MemoryStorage,
-    FailingModule<Empty, Empty, Empty>,
-    WasmKeeper<Empty, Empty>,
-    StakeKeeper,
-    DistributionKeeper,
-    FailingModule<IbcMsg, IbcQuery, Empty>,
-    FailingModule<GovMsg, Empty, Empty>,
-    TokenFactory,
->;
-
 impl Stargate for TokenFactory {
     fn execute<ExecC, QueryC>(
         &self,
@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use cosmwasm_std::{to_json_binary, Binary, Coin, ContractResult, Decimal, QuerierResult, Uint128};
-use mars_types::params::{AssetParams, QueryMsg};
+use mars_types::params::{AssetParams, PausableAction, PausableContract, QueryMsg};
 
 #[derive(Default)]
 pub struct ParamsQuerier {
     pub target_health_factor: Decimal,
     pub params: HashMap<String, AssetParams>,
     pub total_deposits: HashMap<String, Uint128>,
+    pub paused: HashSet<(PausableContract, PausableAction)>,
 }
 
 impl ParamsQuerier {
@@ -30,6 +31,10 @@ impl ParamsQuerier {
                 .into(),
                 None => Err(format!("[mock]: could not find total deposit for {denom}")).into(),
             },
+            QueryMsg::IsPaused {
+                contract,
+                action,
+            } => to_json_binary(&self.paused.contains(&(contract, action))).into(),
             _ => Err("[mock]: Unsupported params query".to_string()).into(),
         };
         Ok(ret).into()
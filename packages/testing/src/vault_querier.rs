@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{to_json_binary, Addr, Binary, ContractResult, QuerierResult, Uint128};
+use cw_vault_standard::VaultInfoResponse;
+use mars_types::adapters::vault::QueryMsg;
+
+#[derive(Clone)]
+pub struct MockVault {
+    pub base_token: String,
+    pub vault_token: String,
+    pub total_vault_tokens: Uint128,
+    pub total_assets: Uint128,
+}
+
+#[derive(Default)]
+pub struct VaultQuerier {
+    pub vaults: HashMap<Addr, MockVault>,
+}
+
+impl VaultQuerier {
+    pub fn handle_query(&self, contract_addr: &Addr, query: QueryMsg) -> QuerierResult {
+        let Some(vault) = self.vaults.get(contract_addr) else {
+            let res: ContractResult<Binary> =
+                Err(format!("[mock]: could not find vault {contract_addr}")).into();
+            return Ok(res).into();
+        };
+
+        let res: ContractResult<Binary> = match query {
+            QueryMsg::Info {} => to_json_binary(&VaultInfoResponse {
+                base_token: vault.base_token.clone(),
+                vault_token: vault.vault_token.clone(),
+            })
+            .into(),
+            QueryMsg::TotalVaultTokenSupply {} => to_json_binary(&vault.total_vault_tokens).into(),
+            QueryMsg::ConvertToAssets {
+                amount,
+            } => {
+                to_json_binary(&amount.multiply_ratio(vault.total_assets, vault.total_vault_tokens))
+                    .into()
+            }
+            _ => Err("[mock]: Unsupported vault query").into(),
+        };
+
+        Ok(res).into()
+    }
+}
@@ -25,6 +25,37 @@ pub enum ExecuteMsg {
     Burn {
         token_id: String,
     },
+    /// Set the address which can recover the token after `recovery_delay` seconds if the owner
+    /// loses access to it. The delay has to be between a day and a year. Only the token owner can
+    /// execute. Replaces the previous backup owner and cancels a pending recovery.
+    SetBackupOwner {
+        token_id: String,
+        backup_owner: String,
+        recovery_delay: u64,
+    },
+    /// Remove the backup owner of the token and cancel a pending recovery. Only the token owner can execute.
+    RemoveBackupOwner {
+        token_id: String,
+    },
+    /// Start the recovery of a token. Only the backup owner can execute.
+    InitiateRecovery {
+        token_id: String,
+    },
+    /// Cancel a pending recovery. Only the token owner can execute.
+    CancelRecovery {
+        token_id: String,
+    },
+    /// Transfer the token to the backup owner once the recovery delay has passed.
+    /// Only the backup owner can execute.
+    CompleteRecovery {
+        token_id: String,
+    },
+    /// Set a short label shown in the token metadata, or remove it if `None`.
+    /// Only the token owner can execute. Cleared when the token is transferred.
+    SetLabel {
+        token_id: String,
+        label: Option<String>,
+    },
 
     //--------------------------------------------------------------------------------------------------
     // Base cw721 messages
@@ -0,0 +1,19 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Int128};
+
+use crate::health::AccountKind;
+
+/// Token metadata returned by `NftInfo` and `AllNftInfo`. Apart from the label, it is derived from
+/// the Credit Manager and Health contracts at query time, and is `None` if they are not set in
+/// the config or the query fails.
+#[cw_serde]
+#[derive(Default)]
+pub struct AccountMetadata {
+    /// Label set by the token owner
+    pub label: Option<String>,
+    pub account_kind: Option<AccountKind>,
+    /// Total collateral value minus total debt value, denominated in the oracle's base currency
+    pub net_value: Option<Int128>,
+    pub max_ltv_health_factor: Option<Decimal>,
+    pub liquidation_health_factor: Option<Decimal>,
+}
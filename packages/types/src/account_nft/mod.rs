@@ -1,9 +1,13 @@
 mod execute;
 mod instantiate;
+mod metadata;
 mod nft_config;
 mod query;
+mod recovery;
 
 pub use execute::*;
 pub use instantiate::*;
+pub use metadata::*;
 pub use nft_config::*;
 pub use query::*;
+pub use recovery::*;
@@ -16,6 +16,12 @@ pub enum QueryMsg {
     #[returns(String)]
     NextId {},
 
+    /// Backup owner and pending recovery of the given token
+    #[returns(super::RecoveryResponse)]
+    Recovery {
+        token_id: String,
+    },
+
     //--------------------------------------------------------------------------------------------------
     // Base cw721 messages
     //--------------------------------------------------------------------------------------------------
@@ -62,13 +68,13 @@ pub enum QueryMsg {
     /// With MetaData Extension.
     /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
     /// but directly from the contract
-    #[returns(cw721::NftInfoResponse<cosmwasm_std::Empty>)]
+    #[returns(cw721::NftInfoResponse<super::AccountMetadata>)]
     NftInfo {
         token_id: String,
     },
     /// With MetaData Extension.
     /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization for clients
-    #[returns(cw721::AllNftInfoResponse<cosmwasm_std::Empty>)]
+    #[returns(cw721::AllNftInfoResponse<super::AccountMetadata>)]
     AllNftInfo {
         token_id: String,
         /// unset or false will filter out expired approvals, you must set to true to see them
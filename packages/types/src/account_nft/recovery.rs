@@ -0,0 +1,25 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+
+/// Address allowed to recover a token if the owner loses access to it
+#[cw_serde]
+pub struct BackupOwner {
+    pub address: Addr,
+    /// Seconds between initiating and completing a recovery, during which the owner can cancel it
+    pub recovery_delay: u64,
+}
+
+#[cw_serde]
+pub struct PendingRecovery {
+    /// Block time (in seconds) at which the recovery was initiated
+    pub initiated_at: u64,
+    /// Block time (in seconds) from which the recovery can be completed
+    pub completable_at: u64,
+}
+
+#[cw_serde]
+pub struct RecoveryResponse {
+    pub token_id: String,
+    pub backup_owner: Option<BackupOwner>,
+    pub pending_recovery: Option<PendingRecovery>,
+}
@@ -2,6 +2,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Api, QuerierWrapper, StdResult};
 
 use crate::{
+    credit_manager::Positions,
     health::{AccountKind, HealthState, HealthValuesResponse, QueryMsg},
     oracle::ActionKind,
 };
@@ -68,4 +69,21 @@ impl HealthContract {
             },
         )
     }
+
+    pub fn query_health_values_for_positions(
+        &self,
+        querier: &QuerierWrapper,
+        positions: &Positions,
+        kind: AccountKind,
+        action: ActionKind,
+    ) -> StdResult<HealthValuesResponse> {
+        querier.query_wasm_smart(
+            self.address().to_string(),
+            &QueryMsg::HealthValuesForPositions {
+                positions: positions.clone(),
+                kind,
+                action,
+            },
+        )
+    }
 }
@@ -2,7 +2,9 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Api, Decimal, QuerierWrapper, StdResult};
 use cw_paginate::PaginationResponse;
 
-use crate::params::{AssetParams, QueryMsg, TotalDepositResponse, VaultConfig};
+use crate::params::{
+    AssetParams, PausableAction, PausableContract, QueryMsg, TotalDepositResponse, VaultConfig,
+};
 
 #[cw_serde]
 pub struct ParamsBase<T>(T);
@@ -90,4 +92,19 @@ impl Params {
     pub fn query_target_health_factor(&self, querier: &QuerierWrapper) -> StdResult<Decimal> {
         querier.query_wasm_smart(self.address().to_string(), &QueryMsg::TargetHealthFactor {})
     }
+
+    pub fn query_is_paused(
+        &self,
+        querier: &QuerierWrapper,
+        contract: PausableContract,
+        action: PausableAction,
+    ) -> StdResult<bool> {
+        querier.query_wasm_smart(
+            self.address().to_string(),
+            &QueryMsg::IsPaused {
+                contract,
+                action,
+            },
+        )
+    }
 }
@@ -0,0 +1,120 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, Coin, CosmosMsg, Int128, QuerierWrapper, StdResult, WasmMsg,
+};
+
+use crate::perps::{ExecuteMsg, PerpPosition, QueryMsg, Settlement};
+
+#[cw_serde]
+pub struct PerpsBase<T>(T);
+
+impl<T> PerpsBase<T> {
+    pub fn new(address: T) -> PerpsBase<T> {
+        PerpsBase(address)
+    }
+
+    pub fn address(&self) -> &T {
+        &self.0
+    }
+}
+
+pub type PerpsUnchecked = PerpsBase<String>;
+pub type Perps = PerpsBase<Addr>;
+
+impl From<Perps> for PerpsUnchecked {
+    fn from(perps: Perps) -> Self {
+        Self(perps.address().to_string())
+    }
+}
+
+impl PerpsUnchecked {
+    pub fn check(&self, api: &dyn Api) -> StdResult<Perps> {
+        Ok(PerpsBase::new(api.addr_validate(self.address())?))
+    }
+}
+
+impl Perps {
+    /// `funds` has to cover a negative settlement of the order
+    pub fn execute_order_msg(
+        &self,
+        account_id: &str,
+        denom: &str,
+        size: Int128,
+        reduce_only: Option<bool>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().to_string(),
+            msg: to_json_binary(&ExecuteMsg::ExecuteOrder {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+                size,
+                reduce_only,
+            })?,
+            funds,
+        }))
+    }
+
+    pub fn close_position_msg(
+        &self,
+        account_id: &str,
+        denom: &str,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().to_string(),
+            msg: to_json_binary(&ExecuteMsg::ClosePosition {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+            })?,
+            funds,
+        }))
+    }
+
+    pub fn query_positions(
+        &self,
+        querier: &QuerierWrapper,
+        account_id: &str,
+    ) -> StdResult<Vec<PerpPosition>> {
+        querier.query_wasm_smart(
+            self.address(),
+            &QueryMsg::Positions {
+                account_id: account_id.to_string(),
+            },
+        )
+    }
+
+    pub fn query_order_settlement(
+        &self,
+        querier: &QuerierWrapper,
+        account_id: &str,
+        denom: &str,
+        size: Int128,
+        reduce_only: Option<bool>,
+    ) -> StdResult<Settlement> {
+        querier.query_wasm_smart(
+            self.address(),
+            &QueryMsg::OrderSettlement {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+                size,
+                reduce_only,
+            },
+        )
+    }
+
+    pub fn query_close_position_settlement(
+        &self,
+        querier: &QuerierWrapper,
+        account_id: &str,
+        denom: &str,
+    ) -> StdResult<Settlement> {
+        querier.query_wasm_smart(
+            self.address(),
+            &QueryMsg::ClosePositionSettlement {
+                account_id: account_id.to_string(),
+                denom: denom.to_string(),
+            },
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, Binary, Coin, CosmosMsg, QuerierWrapper, StdResult, WasmMsg,
+};
+
+/// Subset of the Pyth contract's execute messages used by Mars contracts
+#[cw_serde]
+pub enum PythExecuteMsg {
+    /// Verifies the signed price update payloads (VAAs) and stores the prices.
+    /// The fee returned by `GetUpdateFee` has to be sent along.
+    UpdatePriceFeeds {
+        data: Vec<Binary>,
+    },
+}
+
+/// Subset of the Pyth contract's queries used by Mars contracts
+#[cw_serde]
+pub enum PythQueryMsg {
+    /// Fee required to update the price feeds with the given payloads
+    GetUpdateFee {
+        vaas: Vec<Binary>,
+    },
+}
+
+#[cw_serde]
+pub struct PythBase<T>(T);
+
+impl<T> PythBase<T> {
+    pub fn new(address: T) -> PythBase<T> {
+        PythBase(address)
+    }
+
+    pub fn address(&self) -> &T {
+        &self.0
+    }
+}
+
+pub type PythUnchecked = PythBase<String>;
+pub type Pyth = PythBase<Addr>;
+
+impl From<Pyth> for PythUnchecked {
+    fn from(pyth: Pyth) -> Self {
+        Self(pyth.address().to_string())
+    }
+}
+
+impl PythUnchecked {
+    pub fn check(&self, api: &dyn Api) -> StdResult<Pyth> {
+        Ok(PythBase::new(api.addr_validate(self.address())?))
+    }
+}
+
+impl Pyth {
+    pub fn query_update_fee(&self, querier: &QuerierWrapper, vaas: &[Binary]) -> StdResult<Coin> {
+        querier.query_wasm_smart(
+            self.address(),
+            &PythQueryMsg::GetUpdateFee {
+                vaas: vaas.to_vec(),
+            },
+        )
+    }
+
+    /// `fee` has to match the fee returned by `query_update_fee`
+    pub fn update_price_feeds_msg(&self, vaas: Vec<Binary>, fee: Coin) -> StdResult<CosmosMsg> {
+        let funds = if fee.amount.is_zero() {
+            vec![]
+        } else {
+            vec![fee]
+        };
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.address().to_string(),
+            msg: to_json_binary(&PythExecuteMsg::UpdatePriceFeeds {
+                data: vaas,
+            })?,
+            funds,
+        }))
+    }
+}
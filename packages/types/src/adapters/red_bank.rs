@@ -54,6 +54,8 @@ impl RedBank {
                 denom: coin.denom.to_string(),
                 amount: coin.amount,
                 recipient: None,
+                on_behalf_of: None,
+                price_updates: None,
             })?,
             funds: vec![],
         }))
@@ -97,6 +99,7 @@ impl RedBank {
                 recipient: None,
                 account_id: Some(account_id.to_string()),
                 liquidation_related: Some(liquidation_related),
+                price_updates: None,
             })?,
             funds: vec![],
         }))
@@ -225,6 +225,19 @@ impl Vault {
         }))
     }
 
+    pub fn query_convert_to_assets(
+        &self,
+        querier: &QuerierWrapper,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.address.to_string(),
+            msg: to_json_binary(&QueryMsg::ConvertToAssets {
+                amount,
+            })?,
+        }))
+    }
+
     pub fn query_total_vault_coins_issued(&self, querier: &QuerierWrapper) -> StdResult<Uint128> {
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: self.address.to_string(),
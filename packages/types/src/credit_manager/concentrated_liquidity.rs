@@ -0,0 +1,172 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal, Decimal256, StdError, StdResult, Uint128, Uint256};
+
+/// Number of ticks it takes for the price to grow tenfold
+const TICKS_PER_DECADE: i64 = 9_000_000;
+
+/// Price increment per tick (as a power of ten) in the decade starting at price one
+const EXPONENT_AT_PRICE_ONE: i64 = -6;
+
+/// Osmosis concentrated liquidity position held by Rover on behalf of a credit account
+#[cw_serde]
+pub struct ClPosition {
+    pub position_id: u64,
+    pub pool_id: u64,
+    pub lower_tick: i64,
+    pub upper_tick: i64,
+    pub liquidity: Decimal256,
+    /// Underlying token0 at the current pool price
+    pub asset0: Coin,
+    /// Underlying token1 at the current pool price
+    pub asset1: Coin,
+}
+
+impl ClPosition {
+    /// Underlying (token0, token1) of the position if the pool traded at the ratio of the given
+    /// prices (both quoted in the same denom). Used to value the position at oracle prices
+    /// instead of the pool price which can be moved within a transaction.
+    pub fn underlying_at_prices(
+        &self,
+        price0: Decimal,
+        price1: Decimal,
+    ) -> StdResult<(Coin, Coin)> {
+        if self.lower_tick >= self.upper_tick {
+            return Err(StdError::generic_err(format!(
+                "invalid tick range [{}, {}) for position {}",
+                self.lower_tick, self.upper_tick, self.position_id
+            )));
+        }
+
+        let sqrt_lower = tick_to_price(self.lower_tick)?.sqrt();
+        let sqrt_upper = tick_to_price(self.upper_tick)?.sqrt();
+        let price = Decimal256::from(price0)
+            .checked_div(Decimal256::from(price1))
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        let sqrt_price = price.sqrt().max(sqrt_lower).min(sqrt_upper);
+
+        // amount0 = L * (sqrt_upper - sqrt_price) / (sqrt_price * sqrt_upper)
+        let amount0 = self
+            .liquidity
+            .checked_mul(sqrt_upper - sqrt_price)?
+            .checked_div(sqrt_upper)
+            .and_then(|a| a.checked_div(sqrt_price))
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        // amount1 = L * (sqrt_price - sqrt_lower)
+        let amount1 = self.liquidity.checked_mul(sqrt_price - sqrt_lower)?;
+
+        Ok((
+            Coin {
+                denom: self.asset0.denom.clone(),
+                amount: Uint128::try_from(amount0.to_uint_floor())?,
+            },
+            Coin {
+                denom: self.asset1.denom.clone(),
+                amount: Uint128::try_from(amount1.to_uint_floor())?,
+            },
+        ))
+    }
+}
+
+/// Osmosis tick to price (token1 per token0) conversion. Ticks are spaced geometrically:
+/// in the decade [10^k, 10^(k+1)) every tick increments the price by 10^(k + EXPONENT_AT_PRICE_ONE).
+pub fn tick_to_price(tick: i64) -> StdResult<Decimal256> {
+    let decade = tick.div_euclid(TICKS_PER_DECADE);
+    let ticks_in_decade = tick.rem_euclid(TICKS_PER_DECADE);
+    let increment = pow10(decade + EXPONENT_AT_PRICE_ONE)?
+        .checked_mul(Decimal256::from_ratio(ticks_in_decade as u128, 1u128))?;
+    Ok(pow10(decade)?.checked_add(increment)?)
+}
+
+fn pow10(exp: i64) -> StdResult<Decimal256> {
+    let res = if exp >= 0 {
+        let atomics = Uint256::from(10u128).checked_pow(exp as u32)?;
+        Decimal256::from_atomics(atomics, 0)
+    } else {
+        Decimal256::from_atomics(1u128, exp.unsigned_abs() as u32)
+    };
+    let dec = res.map_err(|e| StdError::generic_err(e.to_string()))?;
+    if dec.is_zero() {
+        return Err(StdError::generic_err(format!("10^{exp} is out of the supported price range")));
+    }
+    Ok(dec)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn position(lower_tick: i64, upper_tick: i64, liquidity: u128) -> ClPosition {
+        ClPosition {
+            position_id: 1,
+            pool_id: 1,
+            lower_tick,
+            upper_tick,
+            liquidity: Decimal256::from_ratio(liquidity, 1u128),
+            asset0: Coin::new(0, "uatom"),
+            asset1: Coin::new(0, "uosmo"),
+        }
+    }
+
+    #[test]
+    fn tick_to_price_conversion() {
+        assert_eq!(tick_to_price(0).unwrap(), Decimal256::one());
+        assert_eq!(tick_to_price(1).unwrap(), Decimal256::from_str("1.000001").unwrap());
+        assert_eq!(tick_to_price(-1).unwrap(), Decimal256::from_str("0.9999999").unwrap());
+        assert_eq!(tick_to_price(9_000_000).unwrap(), Decimal256::from_ratio(10u128, 1u128));
+        assert_eq!(tick_to_price(-9_000_000).unwrap(), Decimal256::permille(100));
+        assert_eq!(
+            tick_to_price(9_000_000 + 4_500_000).unwrap(),
+            Decimal256::from_ratio(55u128, 1u128)
+        );
+        assert_eq!(
+            tick_to_price(-108_000_000).unwrap(),
+            Decimal256::from_str("0.000000000001").unwrap()
+        );
+        assert_eq!(
+            tick_to_price(342_000_000).unwrap(),
+            Decimal256::from_ratio(10u128.pow(38), 1u128)
+        );
+    }
+
+    #[test]
+    fn underlying_in_range() {
+        // Symmetric range around price one: [0.1, 10)
+        let position = position(-9_000_000, 9_000_000, 1_000_000);
+        let (amount0, amount1) =
+            position.underlying_at_prices(Decimal::percent(50), Decimal::percent(50)).unwrap();
+
+        // L * (1 - 1 / sqrt(10)) = L * (1 - sqrt(0.1))
+        assert_eq!(amount0, Coin::new(683_772, "uatom"));
+        assert_eq!(amount1, Coin::new(683_772, "uosmo"));
+    }
+
+    #[test]
+    fn underlying_below_range_is_token0_only() {
+        let position = position(-9_000_000, 9_000_000, 1_000_000);
+        let (amount0, amount1) =
+            position.underlying_at_prices(Decimal::percent(1), Decimal::one()).unwrap();
+
+        // L * (sqrt(10) - sqrt(0.1)) / (sqrt(0.1) * sqrt(10)) = L * (sqrt(10) - sqrt(0.1))
+        assert_eq!(amount0, Coin::new(2_846_049, "uatom"));
+        assert_eq!(amount1, Coin::new(0, "uosmo"));
+    }
+
+    #[test]
+    fn underlying_above_range_is_token1_only() {
+        let position = position(-9_000_000, 9_000_000, 1_000_000);
+        let (amount0, amount1) = position
+            .underlying_at_prices(Decimal::from_ratio(100u128, 1u128), Decimal::one())
+            .unwrap();
+
+        assert_eq!(amount0, Coin::new(0, "uatom"));
+        assert_eq!(amount1, Coin::new(2_846_049, "uosmo"));
+    }
+
+    #[test]
+    fn invalid_tick_range() {
+        let position = position(100, 100, 1_000_000);
+        position.underlying_at_prices(Decimal::one(), Decimal::one()).unwrap_err();
+    }
+}
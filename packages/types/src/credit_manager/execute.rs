@@ -1,7 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Int128, StdResult, Uint128,
+    WasmMsg,
+};
 use mars_owner::OwnerUpdate;
 
 use super::ConfigUpdates;
@@ -24,12 +27,46 @@ pub enum ExecuteMsg {
         account_id: Option<String>,
         account_kind: Option<AccountKind>,
         actions: Vec<Action>,
+        /// Signed Pyth price update payloads (VAAs). If provided, the prices are pushed to the Pyth
+        /// contract before the actions are executed, so health checks use the updated prices.
+        /// The Pyth update fee is paid from the sent funds, the rest is available to the actions.
+        price_updates: Option<Vec<Binary>>,
     },
     /// Repay debt on behalf of an account, funded from wallet. Must send exactly one coin in message funds.
     /// Allows repaying debts of assets that have been de-listed from credit manager.
     RepayFromWallet {
         account_id: String,
     },
+    /// Opt in (or out) of soft-liquidation for a credit account. Only callable by the account owner.
+    /// Opted-in accounts can be deleveraged by anyone while they are above max LTV but not yet liquidatable.
+    UpdateDeleverageOptIn {
+        account_id: String,
+        enabled: bool,
+    },
+    /// Permissionless entry point for keepers. Sells `coin_in` from the account's deposits for `debt_denom`
+    /// via the swapper (oracle-checked slippage) and repays the account's debt with the proceeds.
+    /// The keeper receives `deleverage_config.bonus` percent of `coin_in` as a reward.
+    /// The account must have opted in, be within the soft-liquidation band (max LTV health factor < 1 and
+    /// liquidation health factor >= 1) and end up back below max LTV.
+    Deleverage {
+        account_id: String,
+        coin_in: Coin,
+        debt_denom: String,
+        route: Option<SwapperRoute>,
+    },
+    /// Closes out a debt worth less than the asset's `min_debt_value`. Only callable by the
+    /// rewards-collector contract. The rewards-collector account repays the debt in full and takes
+    /// `collateral_denom` of the same value (at most the account's balance) from the account.
+    SweepDust {
+        account_id: String,
+        debt_denom: String,
+        collateral_denom: String,
+    },
+    /// Sends the undelegations and redelegations queued for the validator as one batch, if the
+    /// validator's last batch is at least an epoch old. Callable by anyone.
+    SendStakingBatch {
+        validator: String,
+    },
 
     //--------------------------------------------------------------------------------------------------
     // Privileged messages
@@ -114,6 +151,50 @@ pub enum LiquidateRequest<T> {
     /// Pay back debt of a liquidatable credit manager account for a via liquidating an Astro LP position.
     /// LP shares are transfered from the liquidatable to the liquidator.
     StakedAstroLp(String),
+    /// Pay back debt of a liquidatable credit manager account via liquidating a native staking
+    /// position with the validator. The delegation (or unbonding entries, earliest completion
+    /// first) is transferred from the liquidatable to the liquidator account without unbonding.
+    /// The `StakingPositionType` will determine which bucket to liquidate from.
+    Staking {
+        validator: String,
+        position_type: StakingPositionType,
+    },
+    /// Pay back debt of a liquidatable credit manager account via liquidating an Osmosis
+    /// concentrated liquidity position (by id). The position is valued at oracle prices in terms
+    /// of its token0. The share of liquidity matching the request is withdrawn from the pool and
+    /// both underlying tokens are transferred to the liquidator.
+    ClPosition(u64),
+    /// Close perp positions of a liquidatable credit manager account, one market at a time, until
+    /// the account is no longer liquidatable. The account doesn't need to have debt, `debt_coin`
+    /// is not used. Losses are paid from the account's base denom balance, a shortfall is borrowed
+    /// against the account's other collateral (or absorbed by the perps vault if there's none).
+    /// The liquidator receives a fee on the notional value of every closed position.
+    Perps,
+}
+
+#[cw_serde]
+pub enum StakingPositionType {
+    Delegated,
+    Unbonding,
+}
+
+/// Position of a credit account which can be transferred to another credit account of the same owner
+#[cw_serde]
+pub enum TransferPosition<T> {
+    /// Coin balance of the account
+    Deposit(ActionCoin),
+    /// Debt shares owed to Red Bank. `AccountBalance` transfers the full debt of the denom.
+    Debt(ActionCoin),
+    /// Coin lent to Red Bank. `AccountBalance` transfers the full lent amount of the denom.
+    Lend(ActionCoin),
+    /// The full position (unlocked, locked and unlocking) in a vault
+    Vault(T),
+    /// Astroport LP staked via Mars incentives. `AccountBalance` transfers the full staked amount.
+    StakedAstroLp(ActionCoin),
+    /// The full native staking position (delegated and unbonding) with the validator
+    Staking(String),
+    /// Osmosis concentrated liquidity position (by id)
+    ClPosition(u64),
 }
 
 /// The list of actions that users can perform on their positions
@@ -211,6 +292,95 @@ pub enum Action {
     },
     /// Refunds all coin balances back to user wallet
     RefundAllCoinBalances {},
+    /// Reverts the transaction if, at this point in the action list, the account's max LTV
+    /// health factor is below the given value. Accounts without debt always pass.
+    AssertHealthFactorAbove(Decimal),
+    /// Reverts the transaction if, at this point in the action list, the account's coin balance
+    /// of the denom is below the given amount
+    AssertCoinBalanceAtLeast(Coin),
+    /// Reverts the transaction if, at this point in the action list, the account's debt
+    /// of the denom is above the given amount
+    AssertDebtAtMost(Coin),
+    /// Reverts the transaction if, at this point in the action list, the account's net value
+    /// (collateral value minus debt value) dropped by more than `max_loss_pct` compared to the
+    /// net value before the actions. Deposits and withdrawals (including refunds) don't count
+    /// towards the change.
+    AssertValueChange {
+        max_loss_pct: Decimal,
+    },
+    /// Natively delegate coin (the chain's bond denom) to the validator.
+    /// Delegated coins count as collateral at the haircut set in the params contract.
+    Delegate {
+        validator: String,
+        coin: ActionCoin,
+    },
+    /// Undelegate coin from the validator. The coin is added to the account's coin balance once
+    /// the unbonding period is over, until then it counts as collateral at the same haircut.
+    /// If `coin.amount: AccountBalance`, the whole delegation is undelegated.
+    /// Undelegations of all accounts are queued and sent to the chain in one batch per validator
+    /// and epoch (a sixth of the unbonding period), the unbonding period starts once it's sent.
+    Undelegate {
+        validator: String,
+        coin: ActionCoin,
+    },
+    /// Move delegated coin from one validator to another without unbonding.
+    /// If `coin.amount: AccountBalance`, the whole delegation is redelegated.
+    /// Sent with the batch of the source validator, like undelegations.
+    Redelegate {
+        src_validator: String,
+        dst_validator: String,
+        coin: ActionCoin,
+    },
+    /// Claim accrued staking rewards from all validators the account delegates to
+    ClaimStakingRewards {},
+    /// Create an Osmosis concentrated liquidity position in the tick range [lower_tick, upper_tick).
+    /// `coins_in` must be the pool's tokens. Amounts not used by the pool are kept in the account's
+    /// coin balance. The position counts as collateral valued at oracle prices.
+    CreateClPosition {
+        pool_id: u64,
+        lower_tick: i64,
+        upper_tick: i64,
+        coins_in: Vec<ActionCoin>,
+        token_min_amount0: Uint128,
+        token_min_amount1: Uint128,
+    },
+    /// Add liquidity to an existing concentrated liquidity position of the account.
+    /// NOTE: Osmosis replaces the position with a new one (new position id).
+    AddToClPosition {
+        position_id: u64,
+        coins_in: Vec<ActionCoin>,
+        token_min_amount0: Uint128,
+        token_min_amount1: Uint128,
+    },
+    /// Withdraw liquidity from a concentrated liquidity position into the account's coin balance.
+    /// If `liquidity: None`, the full position is withdrawn (and closed).
+    /// Claimable spread rewards and incentives are claimed first.
+    WithdrawClPosition {
+        position_id: u64,
+        liquidity: Option<Decimal256>,
+    },
+    /// Claim spread rewards and incentives of a concentrated liquidity position
+    ClaimClPositionRewards {
+        position_id: u64,
+    },
+    /// Open, increase, reduce, close or flip a perpetual futures position.
+    /// `size` is positive to buy (long) and negative to sell (short).
+    /// Realized PnL, funding and fees are settled with the account's base denom coin balance.
+    ExecutePerpOrder {
+        denom: String,
+        size: Int128,
+        reduce_only: Option<bool>,
+    },
+    /// Moves positions to another credit account owned by the same wallet.
+    /// Health (and HLS rules for HLS accounts) of both accounts is asserted at the end.
+    TransferToAccount {
+        recipient_account_id: String,
+        positions: Vec<TransferPosition<VaultUnchecked>>,
+    },
+    /// Moves all positions to another credit account owned by the same wallet
+    MergeAccounts {
+        recipient_account_id: String,
+    },
 }
 
 /// Internal actions made by the contract with pre-validated inputs
@@ -267,6 +437,16 @@ pub enum CallbackMsg {
         account_id: String,
         prev_health_state: HealthState,
     },
+    /// Assert that a deleveraged account is back below max LTV (max LTV health factor >= 1)
+    AssertDeleveraged {
+        account_id: String,
+    },
+    /// Assert that the account's debts of the given denoms are either zero or worth at least
+    /// the asset's `min_debt_value`
+    AssertMinDebtValues {
+        account_id: String,
+        denoms: BTreeSet<String>,
+    },
     /// Assert that the total deposit amounts of the given denoms across Red
     /// Bank and Rover do not exceed their respective deposit caps.
     AssertDepositCaps {
@@ -380,9 +560,117 @@ pub enum CallbackMsg {
     AssertHlsRules {
         account_id: String,
     },
+    /// Assert the max LTV health factor is greater than or equal to `min_health_factor`
+    AssertHealthFactorAbove {
+        account_id: String,
+        min_health_factor: Decimal,
+    },
+    /// Assert the coin balance of the account is greater than or equal to `coin.amount`
+    AssertCoinBalanceAtLeast {
+        account_id: String,
+        coin: Coin,
+    },
+    /// Assert the debt of the account is less than or equal to `coin.amount`
+    AssertDebtAtMost {
+        account_id: String,
+        coin: Coin,
+    },
+    /// Assert the net value of the account did not drop by more than `max_loss_pct`
+    /// compared to the net value after the deposits, reduced by the value withdrawn since
+    AssertValueChange {
+        account_id: String,
+        max_loss_pct: Decimal,
+    },
+    /// Natively delegate coin from the account's coin balance to the validator
+    Delegate {
+        account_id: String,
+        validator: String,
+        coin: ActionCoin,
+    },
+    /// Queue the undelegation of coin from the validator
+    Undelegate {
+        account_id: String,
+        validator: String,
+        coin: ActionCoin,
+    },
+    /// Queue moving delegated coin from one validator to another
+    Redelegate {
+        account_id: String,
+        src_validator: String,
+        dst_validator: String,
+        coin: ActionCoin,
+    },
+    /// Claim accrued staking rewards from all validators the account delegates to
+    ClaimStakingRewards {
+        account_id: String,
+    },
+    /// Create an Osmosis concentrated liquidity position with coins from the account's balance
+    CreateClPosition {
+        account_id: String,
+        pool_id: u64,
+        lower_tick: i64,
+        upper_tick: i64,
+        coins_in: Vec<ActionCoin>,
+        token_min_amount0: Uint128,
+        token_min_amount1: Uint128,
+    },
+    /// Add liquidity to a concentrated liquidity position of the account
+    AddToClPosition {
+        account_id: String,
+        position_id: u64,
+        coins_in: Vec<ActionCoin>,
+        token_min_amount0: Uint128,
+        token_min_amount1: Uint128,
+    },
+    /// Withdraw liquidity from a concentrated liquidity position of the account
+    WithdrawClPosition {
+        account_id: String,
+        position_id: u64,
+        liquidity: Option<Decimal256>,
+    },
+    /// Claim spread rewards and incentives of a concentrated liquidity position of the account
+    ClaimClPositionRewards {
+        account_id: String,
+        position_id: u64,
+    },
+    /// Execute a perp order for the account and settle it with the account's coin balance
+    ExecutePerpOrder {
+        account_id: String,
+        denom: String,
+        size: Int128,
+        reduce_only: Option<bool>,
+    },
+    /// Close the perp position of a liquidated account in the market, if the account is still
+    /// liquidatable. Losses and the liquidation fee (paid to the liquidator) are paid from the
+    /// account's base denom coin balance, a shortfall is borrowed against its other collateral.
+    ClosePerpPosition {
+        liquidator_account_id: String,
+        account_id: String,
+        denom: String,
+    },
+    /// Moves positions from one credit account to another
+    TransferToAccount {
+        account_id: String,
+        recipient_account_id: String,
+        positions: Vec<TransferPosition<Vault>>,
+    },
+    /// Moves all positions from one credit account to another
+    MergeAccounts {
+        account_id: String,
+        recipient_account_id: String,
+    },
     /// At the end of the execution of dispatched actions, this callback removes the guard
     /// and allows subsequent dispatches.
     RemoveReentrancyGuard {},
+    /// Dispatch actions of the original sender after the Pyth price feeds have been updated.
+    /// `funds` are the funds sent by the sender, excluding the Pyth update fee.
+    DispatchActions {
+        sender: Addr,
+        account_id: Option<String>,
+        account_kind: Option<AccountKind>,
+        actions: Vec<Action>,
+        funds: Vec<Coin>,
+    },
 }
 
 impl CallbackMsg {
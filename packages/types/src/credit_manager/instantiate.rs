@@ -4,7 +4,8 @@ use cosmwasm_std::{Decimal, Uint128};
 use crate::adapters::{
     account_nft::AccountNftUnchecked, health::HealthContractUnchecked,
     incentives::IncentivesUnchecked, oracle::OracleUnchecked, params::ParamsUnchecked,
-    red_bank::RedBankUnchecked, swapper::SwapperUnchecked, zapper::ZapperUnchecked,
+    perps::PerpsUnchecked, pyth::PythUnchecked, red_bank::RedBankUnchecked,
+    swapper::SwapperUnchecked, zapper::ZapperUnchecked,
 };
 
 #[cw_serde]
@@ -19,6 +20,9 @@ pub struct InstantiateMsg {
     /// Note: As health checking requires looping through each, this number must not be too large.
     ///       If so, having too many could prevent the account from being liquidated due to gas constraints.
     pub max_unlocking_positions: Uint128,
+    /// The maximum number of concentrated liquidity positions an account can have.
+    /// Each position is queried for health checks, so this number must not be too large either.
+    pub max_cl_positions: Uint128,
     /// The maximum slippage allowed for swaps, provide liquidity and withdraw liquidity
     pub max_slippage: Decimal,
     /// Helper contract for making swaps
@@ -42,10 +46,52 @@ pub struct ConfigUpdates {
     pub red_bank: Option<RedBankUnchecked>,
     pub incentives: Option<IncentivesUnchecked>,
     pub max_unlocking_positions: Option<Uint128>,
+    pub max_cl_positions: Option<Uint128>,
     pub max_slippage: Option<Decimal>,
     pub swapper: Option<SwapperUnchecked>,
     pub zapper: Option<ZapperUnchecked>,
     pub health_contract: Option<HealthContractUnchecked>,
     /// The Mars Protocol rewards-collector contract. We collect protocol fee for its account.
     pub rewards_collector: Option<String>,
+    pub deleverage_config: Option<DeleverageConfig>,
+    /// Replaces the protocol fees charged on borrows and swaps
+    pub fee_config: Option<FeeConfig>,
+    /// Perps contract in which the accounts can hold perpetual futures positions
+    pub perps: Option<PerpsUnchecked>,
+    /// Pyth contract to which price updates sent along with credit account updates are pushed
+    pub pyth: Option<PythUnchecked>,
+}
+
+/// Soft-liquidation settings. Deleveraging is disabled until the owner sets this config.
+#[cw_serde]
+pub struct DeleverageConfig {
+    /// Percentage of the collateral sold that is paid to the keeper
+    pub bonus: Decimal,
+    /// Maximum value of collateral that can be sold in a single deleverage,
+    /// as a percentage of the account's total debt value
+    pub max_repay_ratio: Decimal,
+}
+
+/// Protocol fees charged by Rover. Fees are paid to the rewards-collector account.
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeConfig {
+    /// Origination fee taken from borrowed coins, per denom.
+    /// Denoms without an entry are borrowed without a fee.
+    pub borrow_fees: Vec<DenomFee>,
+    /// Fee taken from coins going into `SwapExactIn` and `ProvideLiquidity`
+    pub swap_fee: Decimal,
+}
+
+impl FeeConfig {
+    pub fn borrow_fee(&self, denom: &str) -> Decimal {
+        self.borrow_fees.iter().find(|f| f.denom == denom).map(|f| f.fee).unwrap_or_default()
+    }
+}
+
+#[cw_serde]
+pub struct DenomFee {
+    pub denom: String,
+    /// Percentage of the amount taken as fee
+    pub fee: Decimal,
 }
@@ -1,9 +1,11 @@
+mod concentrated_liquidity;
 mod execute;
 mod instantiate;
 mod migrate;
 mod query;
 mod reply;
 
+pub use concentrated_liquidity::*;
 pub use execute::*;
 pub use instantiate::*;
 pub use migrate::*;
@@ -1,13 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{Coin, Decimal, StdResult, Uint128};
 use mars_owner::OwnerResponse;
 
+use super::{Action, ClPosition, DeleverageConfig, FeeConfig};
 use crate::{
     adapters::{
         rewards_collector::RewardsCollector,
         vault::{Vault, VaultPosition, VaultUnchecked},
     },
-    health::AccountKind,
+    health::{AccountKind, HealthValuesResponse},
+    perps::PerpPosition,
     traits::Coins,
 };
 
@@ -96,6 +98,22 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Whether the account has opted in to soft-liquidation
+    #[returns(bool)]
+    DeleverageOptIn {
+        account_id: String,
+    },
+    /// Dry-run of `UpdateCreditAccount` actions. The actions are applied to a copy of the
+    /// account's positions using swapper/zapper estimates and Red Bank debt share math.
+    ///
+    /// NOTE: Funds are not required for `Deposit` actions. Actions which can't be simulated
+    /// (vault, liquidation, perp and creating or adding to concentrated liquidity positions) are
+    /// reported as the failed action, as are paused actions and debts left below the min debt value.
+    #[returns(SimulateActionsResponse)]
+    SimulateActions {
+        account_id: String,
+        actions: Vec<Action>,
+    },
 }
 
 #[cw_serde]
@@ -159,6 +177,71 @@ pub struct Positions {
     pub lends: Vec<Coin>,
     pub vaults: Vec<VaultPosition>,
     pub staked_astro_lps: Vec<Coin>,
+    pub staking: Vec<StakingPosition>,
+    pub cl_positions: Vec<ClPosition>,
+    pub perps: Vec<PerpPosition>,
+}
+
+/// Coins natively staked with a validator on behalf of a credit account
+#[cw_serde]
+pub struct StakingPosition {
+    pub validator: String,
+    /// Amount currently delegated to the validator
+    pub delegated: Coin,
+    /// Part of `delegated` queued to be undelegated or redelegated with the validator's next batch
+    pub queued: Uint128,
+    /// Amounts undelegated from the validator which are still unbonding (denom of `delegated`)
+    pub unbonding: Vec<UnbondingEntry>,
+}
+
+impl StakingPosition {
+    /// Delegated and unbonding amounts combined
+    pub fn total(&self) -> StdResult<Coin> {
+        let mut amount = self.delegated.amount;
+        for entry in self.unbonding.iter() {
+            amount = amount.checked_add(entry.amount)?;
+        }
+        Ok(Coin {
+            denom: self.delegated.denom.clone(),
+            amount,
+        })
+    }
+}
+
+#[cw_serde]
+pub struct UnbondingEntry {
+    pub amount: Uint128,
+    /// Timestamp (in seconds) at which the coins are released to the account's coin balance
+    pub completion_time: u64,
+}
+
+#[cw_serde]
+pub struct SimulateActionsResponse {
+    /// Projected positions. If an action fails, the positions before the failed action.
+    pub positions: Positions,
+    /// Health of the projected positions
+    pub health: HealthValuesResponse,
+    /// Deposit caps which would be exceeded by the actions
+    pub deposit_caps_exceeded: Vec<DepositCapExceeded>,
+    /// Protocol fees (borrow and swap) which would be paid to the rewards-collector account
+    pub fees: Vec<Coin>,
+    /// The first action which would fail, if any
+    pub failed_action: Option<FailedAction>,
+}
+
+#[cw_serde]
+pub struct DepositCapExceeded {
+    pub denom: String,
+    /// Projected total deposit amount across Red Bank and Rover
+    pub amount: Uint128,
+    pub cap: Uint128,
+}
+
+#[cw_serde]
+pub struct FailedAction {
+    /// Index of the action in the simulated list
+    pub index: u32,
+    pub error: String,
 }
 
 #[cw_serde]
@@ -182,11 +265,16 @@ pub struct ConfigResponse {
     pub oracle: String,
     pub params: String,
     pub max_unlocking_positions: Uint128,
+    pub max_cl_positions: Uint128,
     pub max_slippage: Decimal,
     pub swapper: String,
     pub zapper: String,
     pub health_contract: String,
     pub rewards_collector: Option<RewardsCollector>,
+    pub deleverage_config: Option<DeleverageConfig>,
+    pub fee_config: Option<FeeConfig>,
+    pub perps: Option<String>,
+    pub pyth: Option<String>,
 }
 
 #[cw_serde]
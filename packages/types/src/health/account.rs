@@ -1,7 +1,7 @@
 use std::fmt;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
 #[cfg(feature = "javascript")]
 use tsify::Tsify;
 
@@ -65,3 +65,71 @@ pub enum LiquidationPriceKind {
     Asset,
     Debt,
 }
+
+/// Hypothetical change to an account, applied by the health computer to evaluate multi-step
+/// transactions
+#[cw_serde]
+#[cfg_attr(feature = "javascript", derive(Tsify))]
+#[cfg_attr(feature = "javascript", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum HealthChange {
+    /// Coin deposited into the account
+    Deposit {
+        coin: Coin,
+    },
+    /// Coin withdrawn from the account's deposits, and from its lends if deposits are not enough
+    Withdraw {
+        coin: Coin,
+    },
+    /// Coin borrowed into the account's deposits
+    Borrow {
+        coin: Coin,
+    },
+    /// Debt repaid from the account's deposits
+    Repay {
+        coin: Coin,
+    },
+    /// Swap of `coin_in` (taken from deposits, then lends) into `denom_out` at the given prices,
+    /// with the output reduced by the slippage
+    Swap {
+        coin_in: Coin,
+        denom_out: String,
+        slippage: Decimal,
+    },
+    /// Price of `denom` multiplied by `multiplier`, e.g. 0.8 for a 20% drop. The value of vaults
+    /// with `denom` as base token is multiplied as well.
+    PriceShock {
+        denom: String,
+        multiplier: Decimal,
+    },
+}
+
+/// Changes applied in order
+#[cw_serde]
+#[cfg_attr(feature = "javascript", derive(Tsify))]
+#[cfg_attr(feature = "javascript", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct HealthChanges(pub Vec<HealthChange>);
+
+#[cw_serde]
+#[cfg_attr(feature = "javascript", derive(Tsify))]
+#[cfg_attr(feature = "javascript", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LiquidationPreview {
+    /// Whether the account can be liquidated, i.e. its liquidation health factor is below 1
+    pub liquidatable: bool,
+    /// What a liquidator would repay and take for each pair of debt and collateral. Empty if the
+    /// account is not liquidatable.
+    pub liquidations: Vec<LiquidationAmounts>,
+}
+
+#[cw_serde]
+#[cfg_attr(feature = "javascript", derive(Tsify))]
+#[cfg_attr(feature = "javascript", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LiquidationAmounts {
+    pub debt_denom: String,
+    pub collateral_denom: String,
+    /// Debt amount repaid by the liquidator
+    pub debt_amount_to_repay: Uint128,
+    /// Collateral amount taken from the account
+    pub collateral_amount_to_liquidate: Uint128,
+    /// Collateral amount received by the liquidator, i.e. without the protocol liquidation fee
+    pub collateral_amount_received_by_liquidator: Uint128,
+}
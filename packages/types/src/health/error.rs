@@ -1,4 +1,6 @@
-use cosmwasm_std::{CheckedFromRatioError, CheckedMultiplyFractionError, OverflowError, StdError};
+use cosmwasm_std::{
+    CheckedFromRatioError, CheckedMultiplyFractionError, OverflowError, StdError, Uint128,
+};
 use cw2::VersionError;
 use mars_owner::OwnerError;
 use thiserror::Error;
@@ -22,6 +24,13 @@ pub enum HealthError {
     #[error("{0} amount was not provided or is 0")]
     MissingAmount(String),
 
+    #[error("{denom} amount {requested} exceeds the available amount {available}")]
+    AmountExceedsAvailable {
+        denom: String,
+        requested: Uint128,
+        available: Uint128,
+    },
+
     #[error(
         "Account is an HLS account, but {0} was not provided HLS params to compute health with"
     )]
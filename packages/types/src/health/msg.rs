@@ -1,8 +1,9 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Decimal;
 use mars_owner::{OwnerResponse, OwnerUpdate};
 
 use super::AccountKind;
-use crate::oracle::ActionKind;
+use crate::{credit_manager::Positions, oracle::ActionKind};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -41,10 +42,76 @@ pub enum QueryMsg {
         kind: AccountKind,
         action: ActionKind,
     },
+    /// Returns all values that comprise health for the given positions (e.g. projected positions
+    /// of a simulated transaction) instead of the positions stored in the Credit Manager
+    #[returns(super::HealthValuesResponse)]
+    HealthValuesForPositions {
+        positions: Positions,
+        kind: AccountKind,
+        action: ActionKind,
+    },
+    /// Returns the health values of the account under each scenario of price shocks, in the same
+    /// order as the scenarios
+    #[returns(StressTestResponse)]
+    StressTest {
+        account_id: String,
+        kind: AccountKind,
+        action: ActionKind,
+        scenarios: Vec<StressScenario>,
+    },
+    /// Returns the largest relative moves of the denom's price the account can absorb before it
+    /// becomes liquidatable. Fails if the account has no position priced in the denom.
+    #[returns(MaxShockBeforeLiquidationResponse)]
+    MaxShockBeforeLiquidation {
+        account_id: String,
+        kind: AccountKind,
+        action: ActionKind,
+        denom: String,
+    },
     #[returns(ConfigResponse)]
     Config {},
 }
 
+/// Set of shocks applied together to the account's prices. Price shocks of denoms without a price
+/// in the account's health computation are rejected, as they are most likely a typo. Shocks of
+/// vaults the account has no position in are ignored.
+#[cw_serde]
+pub struct StressScenario {
+    pub price_shocks: Vec<PriceShock>,
+    pub vault_shocks: Option<Vec<VaultShock>>,
+}
+
+/// Multiplies the price of `denom` (and the value of vaults with it as base token), e.g. 0.7 for
+/// a 30% drop
+#[cw_serde]
+pub struct PriceShock {
+    pub denom: String,
+    pub multiplier: Decimal,
+}
+
+/// Multiplies the share price of the vault, i.e. the value of its locked and unlocked vault coins
+#[cw_serde]
+pub struct VaultShock {
+    pub vault: String,
+    pub multiplier: Decimal,
+}
+
+#[cw_serde]
+pub struct StressTestResponse {
+    pub scenarios: Vec<super::HealthValuesResponse>,
+}
+
+#[cw_serde]
+pub struct MaxShockBeforeLiquidationResponse {
+    pub denom: String,
+    /// Largest relative drop of the price (e.g. 0.3 for 30%) the account can absorb without being
+    /// liquidatable. None if it stays healthy even at a price of zero.
+    pub max_price_decrease: Option<Decimal>,
+    /// Largest relative rise of the price (e.g. 0.3 for 30%) the account can absorb without being
+    /// liquidatable. None if it stays healthy up to the maximum price searched.
+    pub max_price_increase: Option<Decimal>,
+}
+
 #[cw_serde]
 pub struct ConfigResponse {
     pub credit_manager: Option<String>,
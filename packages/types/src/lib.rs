@@ -8,6 +8,7 @@ pub mod incentives;
 pub mod keys;
 pub mod oracle;
 pub mod params;
+pub mod perps;
 pub mod red_bank;
 pub mod rewards_collector;
 pub mod swapper;
@@ -31,6 +31,12 @@ pub enum ExecuteMsg<T, C = Empty> {
     RemovePriceSource {
         denom: String,
     },
+    /// Roll the rate snapshot of a rate based price source (redemption rate, exchange rate) forward
+    /// to the current rate. Callable by anyone, at most once per day and only if the current price
+    /// is valid.
+    RefreshRateSnapshot {
+        denom: String,
+    },
     /// Manages admin role state
     UpdateOwner(OwnerUpdate),
     /// Update contract config (only callable by owner)
@@ -88,6 +94,42 @@ pub enum QueryMsg {
         limit: Option<u32>,
         kind: Option<ActionKind>,
     },
+    /// Query a coin's price together with metadata of its price source, such as the publish time
+    /// and age of the underlying oracle data, Pyth confidence and EMA deviation, the TWAP window
+    /// and the transitive denoms consulted.
+    ///
+    /// Intended for monitoring, e.g. to alert before a price becomes stale. The metadata is also
+    /// returned if the price fails validation, with the price left empty.
+    #[returns(PriceDetailedResponse)]
+    PriceDetailed {
+        denom: String,
+        kind: Option<ActionKind>,
+    },
+    /// Enumerate all coins' prices together with metadata of their price sources.
+    #[returns(Vec<PriceDetailedResponse>)]
+    PricesDetailed {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        kind: Option<ActionKind>,
+    },
+    /// Query the price of a coin whose price source aggregates multiple sub-sources, along with
+    /// how many of the sub-sources were used to calculate it.
+    ///
+    /// NOTE: Fails if the coin's price source is not an aggregate price source.
+    #[returns(AggregatedPriceResponse)]
+    AggregatedPrice {
+        denom: String,
+        kind: Option<ActionKind>,
+    },
+    /// Query the price of a coin whose price source is bounded, along with the raw price of the
+    /// wrapped price source before clamping.
+    ///
+    /// NOTE: Fails if the coin's price source is not a bounded price source.
+    #[returns(BoundedPriceResponse)]
+    BoundedPrice {
+        denom: String,
+        kind: Option<ActionKind>,
+    },
 }
 
 #[cw_serde]
@@ -112,6 +154,50 @@ pub struct PriceResponse {
     pub price: Decimal,
 }
 
+#[cw_serde]
+pub struct PriceDetailedResponse {
+    pub denom: String,
+    /// Price of the coin, `None` if the price failed validation (e.g. is stale)
+    pub price: Option<Decimal>,
+    /// Reason the price failed validation
+    pub error: Option<String>,
+    /// Price source the price was resolved with, e.g. `pyth:...`
+    pub price_source: String,
+    /// Publish time of the underlying oracle data (Pyth price or redemption rate update time)
+    pub publish_time: Option<u64>,
+    /// Seconds elapsed since `publish_time` at the current block
+    pub age: Option<u64>,
+    /// Max age of the underlying oracle data before the price is rejected as stale
+    pub max_staleness: Option<u64>,
+    /// Pyth confidence interval relative to the EMA price
+    pub confidence: Option<Decimal>,
+    /// Deviation of the Pyth price from the EMA price
+    pub ema_deviation: Option<Decimal>,
+    /// TWAP window in seconds
+    pub twap_window: Option<u64>,
+    /// Denoms whose prices were consulted to price the coin
+    pub transitive_denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AggregatedPriceResponse {
+    pub denom: String,
+    pub price: Decimal,
+    /// Number of sub-sources the price was calculated from
+    pub sources_used: u32,
+    /// Total number of sub-sources configured
+    pub sources_total: u32,
+}
+
+#[cw_serde]
+pub struct BoundedPriceResponse {
+    pub denom: String,
+    /// Price clamped into the bounds
+    pub price: Decimal,
+    /// Price returned by the wrapped price source
+    pub raw_price: Decimal,
+}
+
 #[cw_serde]
 pub enum MigrateMsg {
     V1_1_0ToV2_0_0(V2Updates),
@@ -86,3 +86,28 @@ pub(super) fn assert_max_lb_gt_min_lb(
     }
     Ok(())
 }
+
+pub(super) fn assert_auction_duration_gt_zero(duration_blocks: u64) -> Result<(), ValidationError> {
+    if duration_blocks == 0 {
+        return Err(ValidationError::InvalidParam {
+            param_name: "duration_blocks".to_string(),
+            invalid_value: duration_blocks.to_string(),
+            predicate: "> 0".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub(super) fn assert_max_lb_ge_starting_lb(
+    starting_lb: Decimal,
+    max_lb: Decimal,
+) -> Result<(), ValidationError> {
+    if starting_lb > max_lb {
+        return Err(ValidationError::InvalidParam {
+            param_name: "max_lb".to_string(),
+            invalid_value: max_lb.to_string(),
+            predicate: format!(">= {} (starting LB)", starting_lb),
+        });
+    }
+    Ok(())
+}
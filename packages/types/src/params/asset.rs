@@ -7,9 +7,9 @@ use mars_utils::{
 
 use super::{
     assertions::{
-        assert_hls_lqt_gt_max_ltv, assert_lb_slope_within_range, assert_lqt_gt_max_ltv,
-        assert_max_lb_gt_min_lb, assert_max_lb_within_range, assert_min_lb_within_range,
-        assert_starting_lb_within_range,
+        assert_auction_duration_gt_zero, assert_hls_lqt_gt_max_ltv, assert_lb_slope_within_range,
+        assert_lqt_gt_max_ltv, assert_max_lb_ge_starting_lb, assert_max_lb_gt_min_lb,
+        assert_max_lb_within_range, assert_min_lb_within_range, assert_starting_lb_within_range,
     },
     hls::HlsParamsBase,
 };
@@ -19,6 +19,20 @@ use crate::error::MarsError;
 pub struct CmSettings<T> {
     pub whitelisted: bool,
     pub hls: Option<HlsParamsBase<T>>,
+    /// If set, the coin can be natively delegated from credit accounts and the delegated
+    /// (and unbonding) amount counts as collateral
+    pub staking: Option<StakingParams>,
+}
+
+#[cw_serde]
+pub struct StakingParams {
+    /// Share of the delegated/unbonding value that is NOT counted as collateral
+    pub haircut: Decimal,
+    /// Unbonding period of the chain in seconds
+    pub unbonding_period: u64,
+    /// Minimum amount which can be undelegated or redelegated at once, unless it's the account's
+    /// whole delegation to the validator
+    pub min_amount: Uint128,
 }
 
 #[cw_serde]
@@ -48,6 +62,9 @@ pub struct LiquidationBonus {
     /// Maximum LB that can be granted to a liquidator; in other words, the maxLB establishes a ceiling to the LB.
     /// This is a precautionary parameter to mitigate liquidated users being over-punished.
     pub max_lb: Decimal,
+    /// If set, the LB is not derived from the HF. Instead, it is determined by a Dutch auction which
+    /// starts at `starting_lb` and increases linearly to `max_lb` over the auction duration.
+    pub dutch_auction: Option<DutchAuction>,
 }
 
 impl LiquidationBonus {
@@ -57,10 +74,30 @@ impl LiquidationBonus {
         assert_min_lb_within_range(self.min_lb)?;
         assert_max_lb_within_range(self.max_lb)?;
         assert_max_lb_gt_min_lb(self.min_lb, self.max_lb)?;
+        if let Some(dutch_auction) = &self.dutch_auction {
+            assert_auction_duration_gt_zero(dutch_auction.duration_blocks)?;
+            assert_max_lb_ge_starting_lb(self.starting_lb, self.max_lb)?;
+        }
         Ok(())
     }
 }
 
+/// Dutch auction liquidation mode.
+///
+/// Once an auction is started for an unhealthy position, the LB offered for the collateral is:
+/// Liquidation Bonus = min(
+///     starting_lb + (max_lb - starting_lb) * min(elapsed_blocks / duration_blocks, 1),
+///     max(
+///         min(CR - 1, max_lb),
+///         min_lb
+///     )
+/// )
+#[cw_serde]
+pub struct DutchAuction {
+    /// Number of blocks it takes for the LB to increase from `starting_lb` to `max_lb`
+    pub duration_blocks: u64,
+}
+
 #[cw_serde]
 pub struct AssetParamsBase<T> {
     pub denom: String,
@@ -71,6 +108,10 @@ pub struct AssetParamsBase<T> {
     pub liquidation_bonus: LiquidationBonus,
     pub protocol_liquidation_fee: Decimal,
     pub deposit_cap: Uint128,
+    /// Minimum value (in the oracle's base denom) of a debt position. Debts can't be left below it
+    /// unless repaid in full, smaller ones can be swept by the rewards collector. Zero disables it.
+    #[serde(default)]
+    pub min_debt_value: Uint128,
 }
 
 pub type AssetParams = AssetParamsBase<Addr>;
@@ -83,6 +124,7 @@ impl From<AssetParams> for AssetParamsUnchecked {
             credit_manager: CmSettings {
                 whitelisted: p.credit_manager.whitelisted,
                 hls: p.credit_manager.hls.map(Into::into),
+                staking: p.credit_manager.staking,
             },
             red_bank: p.red_bank,
             max_loan_to_value: p.max_loan_to_value,
@@ -90,6 +132,7 @@ impl From<AssetParams> for AssetParamsUnchecked {
             liquidation_bonus: p.liquidation_bonus,
             protocol_liquidation_fee: p.protocol_liquidation_fee,
             deposit_cap: p.deposit_cap,
+            min_debt_value: p.min_debt_value,
         }
     }
 }
@@ -111,6 +154,10 @@ impl AssetParamsUnchecked {
             assert_hls_lqt_gt_max_ltv(hls.max_loan_to_value, hls.liquidation_threshold)?;
         }
 
+        if let Some(staking) = self.credit_manager.staking.as_ref() {
+            decimal_param_lt_one(staking.haircut, "staking_haircut")?;
+        }
+
         let hls = self.credit_manager.hls.as_ref().map(|hls| hls.check(api)).transpose()?;
 
         Ok(AssetParams {
@@ -118,6 +165,7 @@ impl AssetParamsUnchecked {
             credit_manager: CmSettings {
                 whitelisted: self.credit_manager.whitelisted,
                 hls,
+                staking: self.credit_manager.staking.clone(),
             },
             red_bank: self.red_bank.clone(),
             max_loan_to_value: self.max_loan_to_value,
@@ -125,6 +173,7 @@ impl AssetParamsUnchecked {
             liquidation_bonus: self.liquidation_bonus.clone(),
             protocol_liquidation_fee: self.protocol_liquidation_fee,
             deposit_cap: self.deposit_cap,
+            min_debt_value: self.min_debt_value,
         })
     }
 }
@@ -2,9 +2,11 @@ mod assertions;
 mod asset;
 mod hls;
 mod msg;
+mod pause;
 mod vault;
 
 pub use asset::*;
 pub use hls::*;
 pub use msg::*;
+pub use pause::*;
 pub use vault::*;
@@ -2,7 +2,11 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Decimal, Uint128};
 use mars_owner::OwnerUpdate;
 
-use super::{asset::AssetParamsUnchecked, vault::VaultConfigUnchecked};
+use super::{
+    asset::AssetParamsUnchecked,
+    pause::{AutoPauseConfig, PausableAction, PausableContract, PauseUpdate},
+    vault::VaultConfigUnchecked,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -24,6 +28,18 @@ pub enum ExecuteMsg {
     UpdateAssetParams(AssetParamsUpdate),
     UpdateVaultConfig(VaultConfigUpdate),
     EmergencyUpdate(EmergencyUpdate),
+    /// Lift pauses set by the emergency owner or by the price movement check. Only callable by the owner.
+    Unpause(PauseUpdate),
+    /// Set (or remove if `None`) the price movement limits of an asset triggering an automatic pause
+    UpdateAutoPauseConfig {
+        denom: String,
+        config: Option<AutoPauseConfig>,
+    },
+    /// Compare the current oracle price of an asset with the last checkpoint and pause the configured
+    /// actions if it moved more than allowed within the window. Callable by any address.
+    CheckPriceMovement {
+        denom: String,
+    },
 }
 
 #[cw_serde]
@@ -81,6 +97,26 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+
+    /// Check if the action is paused for the contract, either globally or for the contract only
+    #[returns(bool)]
+    IsPaused {
+        contract: PausableContract,
+        action: PausableAction,
+    },
+
+    #[returns(Vec<super::pause::PauseInfo>)]
+    AllPauses {},
+
+    #[returns(Option<AutoPauseConfig>)]
+    AutoPauseConfig {
+        denom: String,
+    },
+
+    #[returns(Option<super::pause::PriceCheckpoint>)]
+    PriceCheckpoint {
+        denom: String,
+    },
 }
 
 #[cw_serde]
@@ -126,4 +162,6 @@ pub enum RedBankEmergencyUpdate {
 pub enum EmergencyUpdate {
     CreditManager(CmEmergencyUpdate),
     RedBank(RedBankEmergencyUpdate),
+    /// Pause several actions at once, globally or for a single contract
+    Pause(PauseUpdate),
 }
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
+
+/// Contracts checking the pause registry before executing a pausable action
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord, Hash)]
+pub enum PausableContract {
+    RedBank,
+    CreditManager,
+    Incentives,
+    ManagedVault,
+}
+
+impl fmt::Display for PausableContract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            PausableContract::RedBank => "red_bank",
+            PausableContract::CreditManager => "credit_manager",
+            PausableContract::Incentives => "incentives",
+            PausableContract::ManagedVault => "managed_vault",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Actions which can be paused. Liquidations and repayments are deliberately not part of the list,
+/// positions can always be closed or made healthier.
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord, Hash)]
+pub enum PausableAction {
+    /// Red Bank deposits, Credit Manager deposits, Astroport LP staking, managed vault deposits
+    Deposits,
+    /// Red Bank and Credit Manager borrows
+    Borrows,
+    /// Entering vaults from Credit Manager
+    VaultEntries,
+    /// Swaps and liquidity provision from Credit Manager
+    Swaps,
+    /// Incentive rewards claims
+    Claims,
+}
+
+impl PausableAction {
+    pub fn all() -> Vec<PausableAction> {
+        vec![
+            PausableAction::Deposits,
+            PausableAction::Borrows,
+            PausableAction::VaultEntries,
+            PausableAction::Swaps,
+            PausableAction::Claims,
+        ]
+    }
+}
+
+impl fmt::Display for PausableAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            PausableAction::Deposits => "deposits",
+            PausableAction::Borrows => "borrows",
+            PausableAction::VaultEntries => "vault_entries",
+            PausableAction::Swaps => "swaps",
+            PausableAction::Claims => "claims",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Where a pause applies. Global pauses apply to every contract.
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord, Hash)]
+pub enum PauseScope {
+    Global,
+    Contract(PausableContract),
+}
+
+impl fmt::Display for PauseScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PauseScope::Global => write!(f, "global"),
+            PauseScope::Contract(contract) => write!(f, "{contract}"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PauseUpdate {
+    pub scope: PauseScope,
+    pub actions: Vec<PausableAction>,
+}
+
+#[cw_serde]
+pub struct PauseInfo {
+    pub scope: PauseScope,
+    pub action: PausableAction,
+    /// Block time (in seconds) at which the action was paused
+    pub paused_at: u64,
+    /// Address which paused the action. Params contract address if triggered by an oracle price movement.
+    pub paused_by: String,
+}
+
+/// Pause the given actions globally if the oracle price of an asset moves more than
+/// `max_price_change` within `window` seconds.
+#[cw_serde]
+pub struct AutoPauseConfig {
+    /// Max relative price change within the window, e.g. 0.2 for 20%
+    pub max_price_change: Decimal,
+    /// Length of the observation window in seconds
+    pub window: u64,
+    pub actions: Vec<PausableAction>,
+}
+
+/// Reference price the price movement is measured against
+#[cw_serde]
+pub struct PriceCheckpoint {
+    pub price: Decimal,
+    /// Block time (in seconds) at which the price was recorded
+    pub timestamp: u64,
+}
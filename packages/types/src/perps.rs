@@ -0,0 +1,288 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Int128, Uint128};
+use mars_owner::OwnerUpdate;
+use mars_utils::{
+    error::ValidationError,
+    helpers::{decimal_param_le_one, decimal_param_lt_one, validate_native_denom},
+};
+
+/// Seconds in a day. Funding rates are expressed per day.
+pub const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The contract's owner, who can update market params
+    pub owner: String,
+    /// Address provider returns addresses for all protocol contracts (oracle is used for pricing)
+    pub address_provider: String,
+    /// Credit Manager is the only contract allowed to open, modify and close positions
+    pub credit_manager: String,
+    /// Denom in which PnL, funding and fees are settled, and in which the counterparty vault is denominated
+    pub base_denom: String,
+    /// Seconds an LP has to wait between unlocking vault shares and withdrawing liquidity
+    pub cooldown_period: u64,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub address_provider: Addr,
+    pub credit_manager: Addr,
+    pub base_denom: String,
+    pub cooldown_period: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Manages owner role state
+    UpdateOwner(OwnerUpdate),
+
+    /// Initialize or update the params of a market (owner only)
+    UpdateMarket(MarketParams),
+
+    /// Deposit base denom into the counterparty vault in exchange for vault shares.
+    /// Shares are priced by the vault liquidity including the unrealized PnL of open positions.
+    Deposit {},
+
+    /// Start the cooldown period for the given amount of vault shares
+    Unlock {
+        shares: Uint128,
+    },
+
+    /// Withdraw liquidity of all unlocked shares which passed the cooldown period. Liquidity owed
+    /// to open positions in profit can't be withdrawn.
+    Withdraw {},
+
+    /// Open, increase, reduce, close or flip the position of a credit account (Credit Manager only).
+    /// A negative settlement (losses, fees, funding paid) has to be sent along in base denom.
+    /// A positive settlement is sent to the Credit Manager.
+    ExecuteOrder {
+        account_id: String,
+        denom: String,
+        /// Positive to buy (long), negative to sell (short)
+        size: Int128,
+        /// If set, the order can only reduce the existing position. The order size is capped to
+        /// the position size, so a reduce only order can never flip the position.
+        reduce_only: Option<bool>,
+    },
+
+    /// Close the position of a credit account in the market (Credit Manager only, used during
+    /// liquidation). If the account can't fully cover its losses, the shortfall is absorbed by the vault.
+    ClosePosition {
+        account_id: String,
+        denom: String,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(mars_owner::OwnerResponse)]
+    Owner {},
+
+    #[returns(Config)]
+    Config {},
+
+    #[returns(MarketResponse)]
+    Market {
+        denom: String,
+    },
+
+    #[returns(Vec<MarketResponse>)]
+    Markets {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(VaultState)]
+    Vault {},
+
+    #[returns(VaultPositionResponse)]
+    VaultPosition {
+        user: String,
+    },
+
+    #[returns(Option<PerpPosition>)]
+    Position {
+        account_id: String,
+        denom: String,
+    },
+
+    /// All open positions of a credit account, used for health computation
+    #[returns(Vec<PerpPosition>)]
+    Positions {
+        account_id: String,
+    },
+
+    /// Base denom amount settled if the order is executed at the current block and prices
+    #[returns(Settlement)]
+    OrderSettlement {
+        account_id: String,
+        denom: String,
+        size: Int128,
+        reduce_only: Option<bool>,
+    },
+
+    /// Base denom amount settled if the position is closed at the current block and prices
+    #[returns(Settlement)]
+    ClosePositionSettlement {
+        account_id: String,
+        denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct MarketParams {
+    pub denom: String,
+    /// If false, positions can only be reduced or closed
+    pub enabled: bool,
+    /// Max open interest of long positions (in denom units)
+    pub max_long_oi: Uint128,
+    /// Max open interest of short positions (in denom units)
+    pub max_short_oi: Uint128,
+    /// Open interest skew (long OI - short OI, in denom units) at which the funding rate
+    /// reaches `max_funding_rate`
+    pub skew_scale: Uint128,
+    /// Max funding rate per day paid by the side with the larger open interest
+    pub max_funding_rate: Decimal,
+    /// Fee charged on the notional value of increased positions
+    pub opening_fee_rate: Decimal,
+    /// Fee charged on the notional value of reduced positions
+    pub closing_fee_rate: Decimal,
+    /// Fee paid by a liquidated account to the liquidator on the notional value of each position
+    /// closed during the liquidation
+    pub liquidation_fee_rate: Decimal,
+    /// Percentage of the position notional value counted towards the max LTV health factor
+    pub max_loan_to_value: Decimal,
+    /// Percentage of the position notional value counted towards the liquidation health factor
+    pub liquidation_threshold: Decimal,
+}
+
+impl MarketParams {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_native_denom(&self.denom)?;
+
+        if self.skew_scale.is_zero() {
+            return Err(ValidationError::InvalidParam {
+                param_name: "skew_scale".to_string(),
+                invalid_value: self.skew_scale.to_string(),
+                predicate: "> 0".to_string(),
+            });
+        }
+
+        decimal_param_lt_one(self.opening_fee_rate, "opening_fee_rate")?;
+        decimal_param_lt_one(self.closing_fee_rate, "closing_fee_rate")?;
+        decimal_param_lt_one(self.liquidation_fee_rate, "liquidation_fee_rate")?;
+        decimal_param_lt_one(self.max_loan_to_value, "max_loan_to_value")?;
+        decimal_param_le_one(self.liquidation_threshold, "liquidation_threshold")?;
+
+        if self.liquidation_threshold <= self.max_loan_to_value {
+            return Err(ValidationError::InvalidParam {
+                param_name: "liquidation_threshold".to_string(),
+                invalid_value: self.liquidation_threshold.to_string(),
+                predicate: format!("> {} (max LTV)", self.max_loan_to_value),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Funding is tracked with two indices which only increase: the base denom amount paid per unit
+/// of size by longs (to shorts) and by shorts (to longs).
+/// A position's funding is the difference between the indices at the time of the query and at
+/// the time the position was last modified.
+#[cw_serde]
+#[derive(Default)]
+pub struct MarketState {
+    pub long_oi: Uint128,
+    pub short_oi: Uint128,
+    /// Sum of size times entry price of all long positions, used to compute their unrealized PnL
+    /// against the vault
+    pub long_entry_value: Uint128,
+    /// Sum of size times entry price of all short positions
+    pub short_entry_value: Uint128,
+    pub longs_pay_index: Decimal,
+    pub shorts_pay_index: Decimal,
+    /// Block time (in seconds) the funding indices were last updated
+    pub last_updated: u64,
+}
+
+#[cw_serde]
+pub struct MarketResponse {
+    pub params: MarketParams,
+    /// State with funding indices accrued up to the current block
+    pub state: MarketState,
+    /// Current funding rate per day
+    pub funding_rate: Decimal,
+    /// True if longs pay the funding rate to shorts
+    pub longs_pay: bool,
+}
+
+/// Position stored in the perps contract
+#[cw_serde]
+pub struct Position {
+    /// Positive for long, negative for short positions
+    pub size: Int128,
+    /// Oracle price at which the position was opened, averaged when the position is increased
+    pub entry_price: Decimal,
+    /// Funding indices of the market at the time the position was last modified
+    pub longs_pay_index: Decimal,
+    pub shorts_pay_index: Decimal,
+}
+
+/// Position of a credit account as used by the health computer
+#[cw_serde]
+pub struct PerpPosition {
+    pub denom: String,
+    /// Denom in which PnL, funding and fees are settled
+    pub base_denom: String,
+    /// Positive for long, negative for short positions
+    pub size: Int128,
+    /// Oracle price at which the position was opened, averaged when the position is increased
+    pub entry_price: Decimal,
+    /// Unsettled funding in base denom. Positive if the account receives funding.
+    pub accrued_funding: Int128,
+    /// Fee which would be charged on the notional value if the position is closed
+    pub closing_fee_rate: Decimal,
+    /// Fee paid to the liquidator on the notional value if the position is closed in a liquidation
+    pub liquidation_fee_rate: Decimal,
+    /// Market params applied when computing health
+    pub max_loan_to_value: Decimal,
+    pub liquidation_threshold: Decimal,
+}
+
+impl PerpPosition {
+    pub fn is_long(&self) -> bool {
+        self.size > Int128::zero()
+    }
+}
+
+/// Amount of base denom settled between a credit account and the perps contract
+#[cw_serde]
+pub struct Settlement {
+    pub base_denom: String,
+    /// Positive if the account receives base denom, negative if it has to pay
+    pub amount: Int128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct VaultState {
+    /// Base denom owned by the vault (deposits plus realized trader losses and fees, minus realized trader profits)
+    pub total_liquidity: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct UnlockState {
+    pub created_at: u64,
+    pub cooldown_end: u64,
+    pub shares: Uint128,
+}
+
+#[cw_serde]
+pub struct VaultPositionResponse {
+    /// Shares which are not unlocking
+    pub shares: Uint128,
+    pub unlocks: Vec<UnlockState>,
+}
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use mars_owner::OwnerUpdate;
 
 use crate::red_bank::InterestRateModel;
@@ -61,6 +61,9 @@ pub enum ExecuteMsg {
         // Withdraw action related to liquidation process initiated in credit manager.
         // This flag is used to identify different way for pricing assets during liquidation.
         liquidation_related: Option<bool>,
+        /// Pyth price update payloads pushed to the Pyth contract before the health checks.
+        /// The update fee has to be sent along.
+        price_updates: Option<Vec<Binary>>,
     },
 
     /// Borrow native coins. If borrow allowed, amount is added to caller's debt
@@ -72,6 +75,24 @@ pub enum ExecuteMsg {
         amount: Uint128,
         /// The address where the borrowed amount is sent
         recipient: Option<String>,
+        /// Borrow against the collateral of another user who has approved a delegation to the
+        /// caller. The debt is recorded against that user and reduces the caller's allowance.
+        on_behalf_of: Option<String>,
+        /// Pyth price update payloads pushed to the Pyth contract before the health checks.
+        /// The update fee has to be sent along.
+        price_updates: Option<Vec<Binary>>,
+    },
+
+    /// Approve (or update) the amount of a given asset the delegatee is allowed to borrow on
+    /// behalf of the caller. The debt is recorded against the caller's position.
+    /// Setting the amount to zero revokes the delegation.
+    ApproveDelegation {
+        /// The address allowed to borrow on behalf of the caller
+        delegatee: String,
+        /// Asset the delegation applies to
+        denom: String,
+        /// Maximum amount the delegatee can borrow
+        amount: Uint128,
     },
 
     /// Repay native coins loan. Coins used to repay must be sent in the
@@ -93,6 +114,20 @@ pub enum ExecuteMsg {
         collateral_denom: String,
         /// The address for receiving underlying collateral
         recipient: Option<String>,
+        /// Pyth price update payloads pushed to the Pyth contract before the health checks.
+        /// The update fee has to be sent along.
+        price_updates: Option<Vec<Binary>>,
+    },
+
+    /// Start or clear the Dutch liquidation auction of a user.
+    ///
+    /// If the user is liquidatable and no auction is running, an auction is started at the current
+    /// block. A running auction is restarted if the user wasn't seen liquidatable for longer than
+    /// the auction duration, otherwise it's kept running. If the user is healthy and an auction
+    /// is running, the auction is cleared.
+    RefreshLiquidationAuction {
+        /// The address of the borrower
+        user: String,
     },
 
     /// Update (enable / disable) asset as collateral for the caller
@@ -102,6 +137,19 @@ pub enum ExecuteMsg {
         /// Option to enable (true) / disable (false) asset as collateral
         enable: bool,
     },
+
+    /// Internal callbacks (only the contract itself can call)
+    Callback(CallbackMsg),
+}
+
+#[cw_serde]
+pub enum CallbackMsg {
+    /// Executes the message on behalf of the original sender, once the price updates sent with it
+    /// have been pushed. The funds left after paying the update fee are sent along.
+    Dispatch {
+        sender: String,
+        msg: Box<ExecuteMsg>,
+    },
 }
 
 #[cw_serde]
@@ -217,6 +265,29 @@ pub enum QueryMsg {
         account_id: Option<String>,
     },
 
+    /// Get the amount of an asset a delegatee is allowed to borrow on behalf of a delegator
+    #[returns(crate::red_bank::BorrowAllowanceResponse)]
+    BorrowAllowance {
+        delegator: String,
+        delegatee: String,
+        denom: String,
+    },
+
+    /// Enumerate all borrow allowances approved by a delegator
+    #[returns(Vec<crate::red_bank::BorrowAllowanceResponse>)]
+    BorrowAllowances {
+        delegator: String,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    /// Get the Dutch liquidation auction of a user together with the liquidation bonus currently
+    /// offered for each of the user's collaterals
+    #[returns(crate::red_bank::LiquidationAuctionResponse)]
+    LiquidationAuction {
+        user: String,
+    },
+
     /// Get liquidity scaled amount for a given underlying asset amount.
     /// (i.e: how much scaled collateral is added if the given amount is deposited)
     #[returns(Uint128)]
@@ -36,6 +36,32 @@ pub struct Debt {
     pub uncollateralized: bool,
 }
 
+/// Dutch liquidation auction of an unhealthy user
+#[cw_serde]
+pub struct LiquidationAuction {
+    /// Block height at which the auction was started
+    pub start_block: u64,
+    /// Last block height at which the user was seen liquidatable, by the start of the auction,
+    /// a liquidation or a refresh
+    pub last_seen_block: u64,
+}
+
+impl LiquidationAuction {
+    pub fn new(block_height: u64) -> Self {
+        Self {
+            start_block: block_height,
+            last_seen_block: block_height,
+        }
+    }
+
+    /// A price move making the user healthy again doesn't clear the auction. If the user wasn't
+    /// seen liquidatable for longer than the auction duration, the auction belongs to an earlier
+    /// unhealthy episode and starts over instead of continuing.
+    pub fn is_stale(&self, block_height: u64, duration_blocks: u64) -> bool {
+        block_height > self.last_seen_block.saturating_add(duration_blocks)
+    }
+}
+
 #[cw_serde]
 pub enum UserHealthStatus {
     NotBorrowing,
@@ -91,6 +117,39 @@ pub struct UserCollateralResponse {
     pub enabled: bool,
 }
 
+#[cw_serde]
+pub struct BorrowAllowanceResponse {
+    /// Address whose collateral backs the borrowed funds
+    pub delegator: String,
+    /// Address allowed to borrow on behalf of the delegator
+    pub delegatee: String,
+    /// Asset denom
+    pub denom: String,
+    /// Remaining amount the delegatee is allowed to borrow
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LiquidationAuctionResponse {
+    /// The address of the borrower
+    pub user: String,
+    /// Block height at which the auction was started, `None` if no auction is running
+    pub start_block: Option<u64>,
+    /// Liquidation bonus currently offered for each enabled collateral.
+    /// Empty if the user is not liquidatable.
+    pub liquidation_bonuses: Vec<CollateralLiquidationBonus>,
+}
+
+#[cw_serde]
+pub struct CollateralLiquidationBonus {
+    /// Collateral denom
+    pub denom: String,
+    /// Liquidation bonus a liquidator would receive for this collateral at the current block
+    pub liquidation_bonus: Decimal,
+    /// Whether the bonus is determined by a Dutch auction
+    pub dutch_auction: bool,
+}
+
 pub type PaginatedUserCollateralResponse = PaginationResponse<UserCollateralResponse>;
 
 #[cw_serde]
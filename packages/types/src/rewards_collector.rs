@@ -138,6 +138,15 @@ pub enum ExecuteMsg {
         actions: Vec<Action>,
     },
 
+    /// Close out a dust debt of a credit account, see credit manager's `SweepDust`.
+    /// The debt is repaid from the rewards-collector credit account.
+    /// Callable by any address.
+    SweepDust {
+        account_id: String,
+        debt_denom: String,
+        collateral_denom: String,
+    },
+
     /// Distribute the accrued protocol income between the safety fund and the fee modules on mars hub,
     /// according to the split set in config.
     /// Callable by any address.
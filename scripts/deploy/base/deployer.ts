@@ -193,6 +193,7 @@ export class Deployer {
     const msg: RoverInstantiateMsg = {
       params: this.storage.addresses.params!,
       max_unlocking_positions: this.config.maxUnlockingPositions,
+      max_cl_positions: this.config.maxClPositions,
       max_slippage: this.config.maxSlippage,
       oracle: this.storage.addresses.oracle!,
       owner: this.deployerAddr,
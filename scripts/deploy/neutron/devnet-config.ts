@@ -459,6 +459,7 @@ export const neutronDevnetConfig: DeploymentConfig = {
   creditLineCoins: [],
   maxValueForBurn: '10000',
   maxUnlockingPositions: '1',
+  maxClPositions: '10',
   maxSlippage: '0.2',
   zapperContractName: 'mars_zapper_astroport',
   runTests: false,
@@ -399,6 +399,7 @@ export const neutronMainnetConfig: DeploymentConfig = {
   creditLineCoins: [],
   maxValueForBurn: '10000',
   maxUnlockingPositions: '1',
+  maxClPositions: '10',
   maxSlippage: '0.2',
   zapperContractName: 'mars_zapper_osmosis',
   runTests: false,
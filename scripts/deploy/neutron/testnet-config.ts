@@ -478,6 +478,7 @@ export const neutronTestnetConfig: DeploymentConfig = {
   creditLineCoins: [],
   maxValueForBurn: '10000',
   maxUnlockingPositions: '1',
+  maxClPositions: '10',
   maxSlippage: '0.2',
   zapperContractName: 'mars_zapper_astroport',
   runTests: false,
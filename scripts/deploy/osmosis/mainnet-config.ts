@@ -1040,6 +1040,7 @@ export const osmosisMainnetConfig: DeploymentConfig = {
   ],
   maxValueForBurn: '10000',
   maxUnlockingPositions: '1',
+  maxClPositions: '10',
   maxSlippage: '0.2',
   zapperContractName: 'mars_zapper_osmosis',
   runTests: false,
@@ -283,6 +283,7 @@ export const osmosisTestnetConfig: DeploymentConfig = {
   ],
   maxValueForBurn: '10000',
   maxUnlockingPositions: '1',
+  maxClPositions: '10',
   maxSlippage: '0.2',
   zapperContractName: 'mars_zapper_osmosis',
   runTests: true,
@@ -82,6 +82,7 @@ export interface DeploymentConfig {
   creditLineCoins: { denom: string; creditLine: String }[]
   maxValueForBurn: string
   maxUnlockingPositions: string
+  maxClPositions: string
   maxSlippage: string
   runTests: boolean
   testActions?: TestActions